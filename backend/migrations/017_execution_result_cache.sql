@@ -0,0 +1,19 @@
+-- 执行结果缓存表
+-- cache_key（v2）= sha256(user_id + 凭证指纹（以 user_id 加盐）+ 执行目标名 + 规范化 Prompt + 规范化输入 + 执行目标配置（不含密钥）)
+-- 按用户隔离：不同用户 / 不同密钥互不命中；同一用户同一内容的重跑/恢复/新任务均可复用
+
+CREATE TABLE IF NOT EXISTS execution_result_cache (
+    cache_key TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL, -- 任务所属用户（缓存分区）
+    execution_target TEXT NOT NULL,
+    result_json TEXT NOT NULL,
+    hit_count INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL, -- Unix 毫秒时间戳
+    expires_at INTEGER NOT NULL  -- Unix 毫秒时间戳
+);
+
+CREATE INDEX IF NOT EXISTS idx_execution_result_cache_user_id
+    ON execution_result_cache(user_id);
+
+CREATE INDEX IF NOT EXISTS idx_execution_result_cache_expires_at
+    ON execution_result_cache(expires_at);
@@ -0,0 +1,14 @@
+-- 用户自定义大模型 Provider
+-- 内置 Provider 由代码注册表提供，不入库；此表仅保存用户自定义项（如自建 vLLM / Ollama）
+
+CREATE TABLE IF NOT EXISTS llm_providers (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    provider_id TEXT NOT NULL, -- 与 api_credentials.provider 对应
+    spec_json TEXT NOT NULL, -- LlmProviderSpec JSON
+    created_at INTEGER NOT NULL, -- Unix 毫秒时间戳
+    updated_at INTEGER NOT NULL, -- Unix 毫秒时间戳
+    UNIQUE(user_id, provider_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_llm_providers_user_id ON llm_providers(user_id);
@@ -0,0 +1,36 @@
+-- 命名凭证：每个用户每种凭证类型可保存多条凭证（按 name 区分），其中一条为默认凭证
+-- 测试集 / 任务可按凭证 ID 引用；未引用时使用默认凭证
+-- SQLite 不支持删除 UNIQUE 约束，需重建表
+
+CREATE TABLE IF NOT EXISTS api_credentials_new (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL DEFAULT 'default_user',
+    credential_type TEXT NOT NULL, -- 'dify' | 'generic_llm'
+    name TEXT NOT NULL DEFAULT 'default', -- 凭证名称（同用户同类型内唯一）
+    is_default INTEGER NOT NULL DEFAULT 1, -- 未显式指定时视为默认凭证（兼容旧写入方式）
+    provider TEXT, -- 仅 generic_llm 使用：LLM Provider ID
+    base_url TEXT NOT NULL,
+    encrypted_api_key BLOB NOT NULL, -- AES-GCM 加密后的 API Key
+    nonce BLOB NOT NULL, -- 12 字节随机数 (AES-GCM 标准)
+    salt BLOB NOT NULL, -- Argon2 派生密钥用的盐值
+    created_at INTEGER NOT NULL, -- Unix 毫秒时间戳
+    updated_at INTEGER NOT NULL, -- Unix 毫秒时间戳
+    UNIQUE(user_id, credential_type, name)
+);
+
+INSERT INTO api_credentials_new (
+    id, user_id, credential_type, name, is_default, provider, base_url,
+    encrypted_api_key, nonce, salt, created_at, updated_at
+)
+SELECT id, user_id, credential_type, 'default', 1, provider, base_url,
+       encrypted_api_key, nonce, salt, created_at, updated_at
+FROM api_credentials;
+
+DROP TABLE api_credentials;
+ALTER TABLE api_credentials_new RENAME TO api_credentials;
+
+CREATE INDEX IF NOT EXISTS idx_api_credentials_user_id ON api_credentials(user_id);
+CREATE INDEX IF NOT EXISTS idx_api_credentials_type ON api_credentials(user_id, credential_type);
+-- 每个用户每种凭证类型至多一条默认凭证
+CREATE UNIQUE INDEX IF NOT EXISTS idx_api_credentials_default
+    ON api_credentials(user_id, credential_type) WHERE is_default = 1;
@@ -0,0 +1,2 @@
+-- 优雅停机：记录因服务停机而在安全点暂停的任务（启动时按策略自动恢复或列入未完成任务）
+ALTER TABLE optimization_tasks ADD COLUMN interrupted_by_shutdown_at INTEGER; -- Unix 毫秒时间戳，NULL 表示未被停机打断
@@ -0,0 +1,16 @@
+-- 信封加密：每个用户一把随机数据密钥（DEK），凭证由 DEK 直接加密
+-- DEK 由登录密码派生密钥包装，并可选地由服务端密钥加密密钥（KEK）包装，
+-- 后者用于无登录会话的后台任务；server_kek_id 记录包装时使用的 KEK 指纹，便于轮换
+
+CREATE TABLE IF NOT EXISTS user_data_keys (
+    user_id TEXT PRIMARY KEY,
+    password_wrapped_key BLOB NOT NULL, -- 登录密码派生密钥包装后的 DEK
+    password_nonce BLOB NOT NULL, -- 12 字节随机数 (AES-GCM 标准)
+    password_salt BLOB NOT NULL, -- Argon2 派生密钥用的盐值
+    server_wrapped_key BLOB, -- 服务端 KEK 包装后的 DEK（未配置 KEK 时为空）
+    server_nonce BLOB,
+    server_kek_id TEXT, -- 包装时使用的 KEK 指纹
+    created_at INTEGER NOT NULL, -- Unix 毫秒时间戳
+    updated_at INTEGER NOT NULL, -- Unix 毫秒时间戳
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,18 @@
+-- 持久化会话：服务重启后会话仍然有效，支持列出 / 吊销
+-- 仅保存 token 的 SHA-256 摘要，明文 token 只存在于客户端；
+-- 解锁上下文（登录密码 / 数据密钥）不落库，重启后由服务端 KEK 兜底解锁
+
+CREATE TABLE IF NOT EXISTS user_sessions (
+    id TEXT PRIMARY KEY, -- 会话 ID（用于列出 / 吊销，不可用于鉴权）
+    token_hash TEXT NOT NULL UNIQUE, -- SHA-256(session_token) 十六进制
+    user_id TEXT NOT NULL,
+    user_agent TEXT, -- 创建会话时的 User-Agent
+    ip_address TEXT, -- 创建会话时的客户端 IP
+    created_at INTEGER NOT NULL, -- Unix 毫秒时间戳
+    last_seen_at INTEGER NOT NULL, -- 最近一次鉴权通过的时间
+    expires_at INTEGER NOT NULL, -- 滑动过期：每次使用后顺延
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_sessions_user_id ON user_sessions(user_id);
+CREATE INDEX IF NOT EXISTS idx_user_sessions_expires_at ON user_sessions(expires_at);
@@ -0,0 +1,22 @@
+-- 个人访问令牌（PAT）：供脚本 / CI 无需登录会话调用 API
+-- 仅保存 token 的 SHA-256 摘要；run 令牌可附带由令牌本身派生密钥包装的数据密钥，
+-- 使其无需登录密码即可解密凭证（令牌明文不落库，故服务端单独无法解包）
+
+CREATE TABLE IF NOT EXISTS personal_access_tokens (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    token_prefix TEXT NOT NULL, -- 展示用前缀（如 pf_pat_1a2b3c4d），便于识别
+    token_hash TEXT NOT NULL UNIQUE, -- SHA-256(token) 十六进制
+    scope TEXT NOT NULL, -- 'read' | 'run'
+    workspace_id TEXT, -- 限定工作区（为空表示不限）
+    wrapped_data_key BLOB, -- 令牌派生密钥包装后的 DEK（仅 run 令牌）
+    data_key_nonce BLOB,
+    created_at INTEGER NOT NULL, -- Unix 毫秒时间戳
+    expires_at INTEGER, -- 为空表示永不过期
+    last_used_at INTEGER,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_personal_access_tokens_user_id ON personal_access_tokens(user_id);
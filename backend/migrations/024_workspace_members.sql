@@ -0,0 +1,30 @@
+-- 工作区成员：支持多人共享同一工作区的测试集、任务与结果
+-- 角色：owner（创建者，可管理成员与删除工作区）/ editor（可修改）/ viewer（只读）
+-- workspaces.user_id 仍表示创建者；新建工作区时由触发器自动写入 owner 成员
+-- 凭证（api_credentials）不随工作区共享，任务始终使用发起者本人的凭证
+
+CREATE TABLE IF NOT EXISTS workspace_members (
+    workspace_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('owner', 'editor', 'viewer')),
+    invited_by TEXT, -- 邀请人（owner 成员为空）
+    created_at INTEGER NOT NULL, -- Unix 毫秒时间戳
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (workspace_id, user_id),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_workspace_members_user_id ON workspace_members(user_id);
+
+-- 已有工作区：创建者成为 owner
+INSERT OR IGNORE INTO workspace_members (workspace_id, user_id, role, invited_by, created_at, updated_at)
+SELECT id, user_id, 'owner', NULL, created_at, created_at
+FROM workspaces;
+
+CREATE TRIGGER IF NOT EXISTS trg_workspaces_owner_member
+AFTER INSERT ON workspaces
+BEGIN
+    INSERT OR IGNORE INTO workspace_members (workspace_id, user_id, role, invited_by, created_at, updated_at)
+    VALUES (NEW.id, NEW.user_id, 'owner', NULL, NEW.created_at, NEW.created_at);
+END;
@@ -0,0 +1,26 @@
+-- TOTP 两步验证（RFC 6238）
+-- 密钥由用户数据密钥（DEK）加密后落库，登录时用登录密码解锁的 DEK 解密；
+-- enabled_at 为空表示已生成密钥但尚未用验证码确认（待启用）
+-- 恢复码仅保存 SHA-256 摘要，used_at 非空即已使用（一次性）
+
+CREATE TABLE IF NOT EXISTS user_totp (
+    user_id TEXT PRIMARY KEY,
+    encrypted_secret BLOB NOT NULL, -- DEK 加密后的 TOTP 密钥
+    secret_nonce BLOB NOT NULL, -- 12 字节随机数 (AES-GCM 标准)
+    enabled_at INTEGER, -- 启用时间（Unix 毫秒时间戳），为空表示待启用
+    last_used_step INTEGER, -- 最近一次通过校验的时间步，防止验证码重放
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS user_totp_recovery_codes (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    code_hash TEXT NOT NULL, -- SHA-256(规范化恢复码) 十六进制
+    used_at INTEGER, -- 使用时间，为空表示未使用
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_totp_recovery_codes_user_id ON user_totp_recovery_codes(user_id);
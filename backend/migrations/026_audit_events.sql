@@ -0,0 +1,28 @@
+-- 安全审计事件：登录、锁定、凭证变更、会话吊销、任务访问拒绝等
+-- 仅追加：不提供更新，过期记录按保留期统一清理；details 写入前已脱敏，不含任何密钥 / 口令
+-- actor_user_id 不设外键，用户删除后审计记录仍保留
+
+CREATE TABLE IF NOT EXISTS audit_events (
+    id TEXT PRIMARY KEY,
+    event_type TEXT NOT NULL, -- 如 login_succeeded / credential_deleted / task_access_denied
+    outcome TEXT NOT NULL, -- 'success' | 'failure' | 'denied'
+    actor_user_id TEXT, -- 未知用户名登录失败时为空
+    actor_username TEXT,
+    ip_address TEXT,
+    user_agent TEXT,
+    correlation_id TEXT,
+    target_type TEXT, -- 如 credential / session / task
+    target_id TEXT,
+    details TEXT, -- JSON（已脱敏）
+    created_at INTEGER NOT NULL -- Unix 毫秒时间戳
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_created_at ON audit_events(created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_actor_user_id ON audit_events(actor_user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_event_type ON audit_events(event_type, created_at);
+
+CREATE TRIGGER IF NOT EXISTS audit_events_no_update
+BEFORE UPDATE ON audit_events
+BEGIN
+    SELECT RAISE(ABORT, 'audit_events is append-only');
+END;
@@ -0,0 +1,4 @@
+-- 迭代历史记录自适应并发指标
+-- effective_concurrency：本轮结束时的有效并发（仅并行执行的轮次有值）
+
+ALTER TABLE iterations ADD COLUMN effective_concurrency INTEGER;
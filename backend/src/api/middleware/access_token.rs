@@ -0,0 +1,264 @@
+//! 个人访问令牌访问策略
+//!
+//! 令牌只用于业务 API（工作区 / 测试集 / 任务 / 结果）：
+//! - 账户、凭证与令牌管理（`/api/v1/auth/*`）仅限登录会话，令牌只能调用 `GET /auth/me`
+//! - `read` 令牌只允许 GET / HEAD
+//! - 限定工作区的令牌只能访问该工作区及其任务、Checkpoint
+
+use axum::http::{Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use sqlx::SqlitePool;
+use tracing::warn;
+
+use crate::api::middleware::session::AccessTokenGrant;
+use crate::api::response::ApiResponse;
+use crate::infra::db::repositories::{
+    AccessTokenScope, CheckpointRepo, OptimizationTaskRepo, OptimizationTaskRepoError,
+};
+use crate::shared::error_codes;
+
+const API_PREFIX: &str = "/api/v1/";
+
+/// 令牌访问被拒绝
+#[derive(Debug)]
+pub(crate) enum AccessTokenDenied {
+    Forbidden(&'static str),
+    Internal,
+}
+
+impl IntoResponse for AccessTokenDenied {
+    fn into_response(self) -> Response {
+        match self {
+            AccessTokenDenied::Forbidden(message) => {
+                ApiResponse::<()>::err(StatusCode::FORBIDDEN, error_codes::FORBIDDEN, message)
+                    .into_response()
+            }
+            AccessTokenDenied::Internal => {
+                crate::api::response::internal_error::<()>("令牌权限校验失败").into_response()
+            }
+        }
+    }
+}
+
+/// 请求所属的工作区
+enum WorkspaceTarget {
+    /// 路由属于该工作区
+    Workspace(String),
+    /// 资源不存在（交由 handler 返回 404）
+    Missing,
+    /// 路由不属于任何单一工作区（列表、创建、元优化等）
+    Unbound,
+}
+
+/// 校验个人访问令牌能否访问指定路由
+pub(crate) async fn authorize_access_token(
+    db: &SqlitePool,
+    user_id: &str,
+    grant: &AccessTokenGrant,
+    method: &Method,
+    path: &str,
+) -> Result<(), AccessTokenDenied> {
+    let segments: Vec<&str> = path
+        .strip_prefix(API_PREFIX)
+        .unwrap_or(path)
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if segments.first() == Some(&"auth") {
+        return if segments == ["auth", "me"] && method == Method::GET {
+            Ok(())
+        } else {
+            Err(AccessTokenDenied::Forbidden(
+                "个人访问令牌不能用于账户与凭证管理",
+            ))
+        };
+    }
+
+    if grant.scope == AccessTokenScope::Read && !is_read_method(method) {
+        return Err(AccessTokenDenied::Forbidden("只读令牌不允许修改操作"));
+    }
+
+    let Some(allowed_workspace) = grant.workspace_id.as_deref() else {
+        return Ok(());
+    };
+    match resolve_workspace(db, user_id, &segments).await? {
+        WorkspaceTarget::Workspace(workspace_id) if workspace_id == allowed_workspace => Ok(()),
+        WorkspaceTarget::Missing => Ok(()),
+        _ => Err(AccessTokenDenied::Forbidden("令牌仅限访问指定工作区")),
+    }
+}
+
+/// 令牌是否允许对任务执行指定操作（WS 命令使用）
+pub(crate) fn token_allows_task(
+    grant: &AccessTokenGrant,
+    task_workspace_id: &str,
+    write: bool,
+) -> bool {
+    if write && grant.scope == AccessTokenScope::Read {
+        return false;
+    }
+    grant
+        .workspace_id
+        .as_deref()
+        .is_none_or(|workspace_id| workspace_id == task_workspace_id)
+}
+
+fn is_read_method(method: &Method) -> bool {
+    method == Method::GET || method == Method::HEAD
+}
+
+async fn resolve_workspace(
+    db: &SqlitePool,
+    user_id: &str,
+    segments: &[&str],
+) -> Result<WorkspaceTarget, AccessTokenDenied> {
+    match segments {
+        ["workspaces", workspace_id, ..] => {
+            Ok(WorkspaceTarget::Workspace((*workspace_id).to_string()))
+        }
+        ["tasks", task_id, ..] | ["recovery", "tasks", task_id, ..] => {
+            task_workspace(db, user_id, task_id).await
+        }
+        ["checkpoints", checkpoint_id, ..] => {
+            match CheckpointRepo::get_checkpoint_for_user(db, user_id, checkpoint_id).await {
+                Ok(Some(checkpoint)) => task_workspace(db, user_id, &checkpoint.task_id).await,
+                Ok(None) => Ok(WorkspaceTarget::Missing),
+                Err(e) => {
+                    warn!(error = %e, "令牌权限校验：查询 Checkpoint 失败");
+                    Err(AccessTokenDenied::Internal)
+                }
+            }
+        }
+        _ => Ok(WorkspaceTarget::Unbound),
+    }
+}
+
+async fn task_workspace(
+    db: &SqlitePool,
+    user_id: &str,
+    task_id: &str,
+) -> Result<WorkspaceTarget, AccessTokenDenied> {
+    match OptimizationTaskRepo::find_by_id_for_user(db, user_id, task_id).await {
+        Ok(task) => Ok(WorkspaceTarget::Workspace(task.workspace_id)),
+        Err(OptimizationTaskRepoError::NotFound) => Ok(WorkspaceTarget::Missing),
+        Err(e) => {
+            warn!(error = %e, "令牌权限校验：查询任务失败");
+            Err(AccessTokenDenied::Internal)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grant(scope: AccessTokenScope, workspace_id: Option<&str>) -> AccessTokenGrant {
+        AccessTokenGrant {
+            token_id: "pat-1".to_string(),
+            scope,
+            workspace_id: workspace_id.map(str::to_string),
+        }
+    }
+
+    async fn setup_db() -> SqlitePool {
+        let pool = crate::infra::db::pool::create_pool("sqlite::memory:")
+            .await
+            .expect("创建测试数据库失败");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("运行 migrations 失败");
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_account_routes_require_login_session() {
+        let db = setup_db().await;
+        let run = grant(AccessTokenScope::Run, None);
+
+        assert!(
+            authorize_access_token(&db, "u1", &run, &Method::GET, "/api/v1/auth/me")
+                .await
+                .is_ok()
+        );
+        for (method, path) in [
+            (Method::GET, "/api/v1/auth/config"),
+            (Method::POST, "/api/v1/auth/password"),
+            (Method::GET, "/api/v1/auth/tokens"),
+            (Method::DELETE, "/api/v1/auth/sessions"),
+        ] {
+            assert!(matches!(
+                authorize_access_token(&db, "u1", &run, &method, path).await,
+                Err(AccessTokenDenied::Forbidden(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_scope_only_allows_read_methods() {
+        let db = setup_db().await;
+        let read = grant(AccessTokenScope::Read, None);
+
+        assert!(
+            authorize_access_token(&db, "u1", &read, &Method::GET, "/api/v1/workspaces")
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            authorize_access_token(&db, "u1", &read, &Method::POST, "/api/v1/workspaces").await,
+            Err(AccessTokenDenied::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_workspace_limited_token_only_reaches_its_workspace() {
+        let db = setup_db().await;
+        let limited = grant(AccessTokenScope::Run, Some("ws-1"));
+
+        assert!(
+            authorize_access_token(
+                &db,
+                "u1",
+                &limited,
+                &Method::POST,
+                "/api/v1/workspaces/ws-1/optimization-tasks"
+            )
+            .await
+            .is_ok()
+        );
+        for path in [
+            "/api/v1/workspaces/ws-2/test-sets",
+            "/api/v1/workspaces",
+            "/api/v1/meta-optimization/prompts",
+        ] {
+            assert!(matches!(
+                authorize_access_token(&db, "u1", &limited, &Method::GET, path).await,
+                Err(AccessTokenDenied::Forbidden(_))
+            ));
+        }
+        // 不存在的任务交由 handler 返回 404
+        assert!(
+            authorize_access_token(
+                &db,
+                "u1",
+                &limited,
+                &Method::GET,
+                "/api/v1/tasks/missing/result"
+            )
+            .await
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_token_allows_task() {
+        let read = grant(AccessTokenScope::Read, Some("ws-1"));
+        assert!(token_allows_task(&read, "ws-1", false));
+        assert!(!token_allows_task(&read, "ws-1", true));
+        assert!(!token_allows_task(&read, "ws-2", false));
+
+        let run = grant(AccessTokenScope::Run, None);
+        assert!(token_allows_task(&run, "ws-2", true));
+    }
+}
@@ -0,0 +1,106 @@
+//! 安全审计上下文
+//! 从请求中提取客户端 IP / User-Agent / correlationId，并以尽力而为的方式写入审计事件
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, header};
+use sqlx::SqlitePool;
+use tracing::warn;
+
+use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
+use crate::infra::db::repositories::{AuditEventRepo, NewAuditEvent};
+use crate::shared::time::now_millis;
+
+/// User-Agent 最大保存长度（超出截断）
+const USER_AGENT_MAX_CHARS: usize = 512;
+
+/// 审计请求上下文
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub correlation_id: Option<String>,
+}
+
+impl AuditContext {
+    /// 从请求头与连接信息构建
+    pub fn from_request(headers: &HeaderMap, addr: Option<SocketAddr>) -> Self {
+        Self {
+            ip_address: addr.map(|addr| addr.ip().to_string()),
+            user_agent: headers
+                .get(header::USER_AGENT)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.chars().take(USER_AGENT_MAX_CHARS).collect()),
+            correlation_id: headers
+                .get(CORRELATION_ID_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string()),
+        }
+    }
+
+    /// 使用指定 correlationId（如 WS 命令自带的 correlationId）
+    pub fn with_correlation_id(&self, correlation_id: &str) -> Self {
+        Self {
+            correlation_id: Some(correlation_id.to_string()),
+            ..self.clone()
+        }
+    }
+
+    /// 写入审计事件
+    ///
+    /// 审计写入失败只记录告警，不影响业务请求结果
+    pub async fn record(&self, db: &SqlitePool, mut event: NewAuditEvent) {
+        event.ip_address = self.ip_address.clone();
+        event.user_agent = self.user_agent.clone();
+        event.correlation_id = self.correlation_id.clone();
+
+        if let Err(e) = AuditEventRepo::append(db, &event, now_millis()).await {
+            warn!(
+                error = %e,
+                event_type = event.event_type.as_str(),
+                correlation_id = %self.correlation_id.as_deref().unwrap_or("unknown"),
+                "写入审计事件失败"
+            );
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for AuditContext
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let addr = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| *addr);
+        Ok(Self::from_request(&parts.headers, addr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_request_extracts_metadata() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::USER_AGENT, "x".repeat(600).parse().unwrap());
+        headers.insert(CORRELATION_ID_HEADER, "cid-1".parse().unwrap());
+
+        let ctx = AuditContext::from_request(&headers, Some(SocketAddr::from(([10, 0, 0, 7], 1))));
+
+        assert_eq!(ctx.ip_address.as_deref(), Some("10.0.0.7"));
+        assert_eq!(ctx.user_agent.as_ref().map(|ua| ua.len()), Some(512));
+        assert_eq!(ctx.correlation_id.as_deref(), Some("cid-1"));
+        assert_eq!(
+            ctx.with_correlation_id("ws-1").correlation_id.as_deref(),
+            Some("ws-1")
+        );
+    }
+}
@@ -1,13 +1,14 @@
 //! 鉴权中间件
-//! 从 Authorization 头解析 Bearer token 并验证会话
+//! 从 Authorization 头解析 Bearer token 并验证会话（或个人访问令牌）
 
 use axum::{
-    extract::{Request, State},
+    extract::{OriginalUri, Request, State},
     middleware::Next,
     response::{IntoResponse, Response},
 };
 
-use crate::api::middleware::session::{SessionStore, UnlockContext};
+use crate::api::middleware::access_token::authorize_access_token;
+use crate::api::middleware::session::{AccessTokenGrant, SessionStore, UnlockContext};
 
 /// 当前用户信息
 /// 通过 request extensions 传递给后续 handler
@@ -17,6 +18,8 @@ pub struct CurrentUser {
     pub user_id: String,
     /// 解锁上下文（用于 API Key 加解密）
     pub unlock_context: Option<UnlockContext>,
+    /// 个人访问令牌授权范围（登录会话为 None）
+    pub access_token: Option<AccessTokenGrant>,
 }
 
 /// 从 Authorization 头提取 Bearer token
@@ -34,6 +37,7 @@ fn extract_bearer_token(auth_header: Option<&str>) -> Option<&str> {
 /// # 失败处理
 /// - 缺少或无效的 Authorization 头返回 401
 /// - 会话不存在或已过期返回 401
+/// - 个人访问令牌超出授权范围返回 403
 /// - 错误响应统一使用 `ApiResponse` 格式（AC #3）
 pub async fn auth_middleware(
     State(session_store): State<SessionStore>,
@@ -62,10 +66,31 @@ pub async fn auth_middleware(
         }
     };
 
+    // 个人访问令牌：校验权限范围（路由需使用嵌套前的完整路径）
+    if let Some(grant) = session.access_token.as_ref() {
+        let path = request
+            .extensions()
+            .get::<OriginalUri>()
+            .map(|uri| uri.path().to_string())
+            .unwrap_or_else(|| request.uri().path().to_string());
+        if let Err(denied) = authorize_access_token(
+            session_store.db(),
+            &session.user_id,
+            grant,
+            request.method(),
+            &path,
+        )
+        .await
+        {
+            return denied.into_response();
+        }
+    }
+
     // 注入 CurrentUser 到 request extensions
     let current_user = CurrentUser {
         user_id: session.user_id,
         unlock_context: session.unlock_context,
+        access_token: session.access_token,
     };
     request.extensions_mut().insert(current_user);
 
@@ -42,7 +42,22 @@ impl LoginAttemptStore {
         }
     }
 
-    pub async fn record_failure(&self, key: String) {
+    /// 冷却中时返回剩余秒数（向上取整，至少 1 秒），用于 `Retry-After`；未冷却时返回 None
+    pub async fn cooldown_remaining_secs(&self, key: &str) -> Option<u64> {
+        let now = now_millis();
+        let attempts = self.attempts.read().await;
+
+        match attempts.get(key) {
+            Some(record) if record.expires_at_ms > now && record.cooldown_until_ms > now => {
+                let remaining_ms = (record.cooldown_until_ms - now) as u64;
+                Some(remaining_ms.div_ceil(1000).max(1))
+            }
+            _ => None,
+        }
+    }
+
+    /// 记录一次失败；返回本次失败是否触发冷却（用于审计锁定事件）
+    pub async fn record_failure(&self, key: String) -> bool {
         let now = now_millis();
         let mut attempts = self.attempts.write().await;
 
@@ -59,11 +74,13 @@ impl LoginAttemptStore {
 
         record.failure_count += 1;
 
-        if record.failure_count >= self.max_failures {
+        let locked = record.failure_count >= self.max_failures;
+        if locked {
             record.cooldown_until_ms = now + self.cooldown_ms;
         }
 
         record.expires_at_ms = std::cmp::max(record.cooldown_until_ms, now + self.cooldown_ms);
+        locked
     }
 
     pub async fn reset(&self, key: &str) {
@@ -1,12 +1,17 @@
 //! 中间件模块
 
+pub mod access_token;
+pub mod audit;
 pub mod auth;
 pub mod connectivity;
 pub mod correlation_id;
 pub mod login_attempt;
+pub mod rate_limit;
 pub mod session;
 
+pub use audit::AuditContext;
 pub use auth::{CurrentUser, auth_middleware};
 pub use connectivity::connectivity_middleware;
 pub use login_attempt::LoginAttemptStore;
-pub use session::{SessionStore, UnlockContext};
+pub use rate_limit::{PromptExecutionRateLimit, RateLimiter, UpstreamQueryRateLimit};
+pub use session::{AccessTokenGrant, SessionMetadata, SessionStore, UnlockContext};
@@ -0,0 +1,359 @@
+//! 按用户 + 路由类别的令牌桶限流
+//!
+//! 用于会真实调用上游（执行目标 / 模型 / Dify）的高开销接口：
+//! - 每个（用户，路由类别）一个令牌桶：容量为突发上限，按每分钟配额匀速补充
+//! - 超限返回 429 + `Retry-After`（统一 `ApiResponse` 错误格式）
+//! - 按类别累计放行 / 拒绝次数，供监控接口读取
+//!
+//! 限流器随 `AppState` 按配置构建（与 `LoginAttemptStore` 一致），由 handler 上的
+//! [`PromptExecutionRateLimit`] / [`UpstreamQueryRateLimit`] 提取器执行；
+//! 未登录请求（无 [`CurrentUser`]）直接放行，由鉴权中间件处理。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::FromRequestParts;
+use axum::http::header::RETRY_AFTER;
+use axum::http::request::Parts;
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use tokio::time::Instant;
+use tracing::warn;
+
+use crate::api::middleware::CurrentUser;
+use crate::api::response::ApiResponse;
+use crate::api::state::AppState;
+use crate::shared::error_codes;
+
+/// 路由类别（同一类别内的接口共享令牌桶）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitClass {
+    /// 对真实执行目标 / 老师模型发起调用（Prompt 预览、对比）
+    PromptExecution,
+    /// 查询上游元数据（Dify 变量刷新、模型列表）
+    UpstreamQuery,
+}
+
+impl RateLimitClass {
+    pub const ALL: [Self; 2] = [Self::PromptExecution, Self::UpstreamQuery];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PromptExecution => "prompt_execution",
+            Self::UpstreamQuery => "upstream_query",
+        }
+    }
+}
+
+/// 单个类别的限流规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitRule {
+    /// 每分钟补充的令牌数（0 = 不限制）
+    pub per_minute: u32,
+    /// 桶容量（允许的突发请求数，最小 1）
+    pub burst: u32,
+}
+
+impl RateLimitRule {
+    fn is_unlimited(&self) -> bool {
+        self.per_minute == 0
+    }
+
+    fn capacity(&self) -> f64 {
+        f64::from(self.burst.max(1))
+    }
+
+    fn tokens_per_sec(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+/// 各类别限流规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    pub prompt_execution: RateLimitRule,
+    pub upstream_query: RateLimitRule,
+}
+
+/// Prompt 执行类默认每分钟配额
+pub const PROMPT_EXECUTION_PER_MINUTE_DEFAULT: u32 = 10;
+/// 上游查询类默认每分钟配额
+pub const UPSTREAM_QUERY_PER_MINUTE_DEFAULT: u32 = 30;
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            prompt_execution: RateLimitRule {
+                per_minute: PROMPT_EXECUTION_PER_MINUTE_DEFAULT,
+                burst: PROMPT_EXECUTION_PER_MINUTE_DEFAULT,
+            },
+            upstream_query: RateLimitRule {
+                per_minute: UPSTREAM_QUERY_PER_MINUTE_DEFAULT,
+                burst: UPSTREAM_QUERY_PER_MINUTE_DEFAULT,
+            },
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn rule(&self, class: RateLimitClass) -> RateLimitRule {
+        match class {
+            RateLimitClass::PromptExecution => self.prompt_execution,
+            RateLimitClass::UpstreamQuery => self.upstream_query,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct ClassCounters {
+    allowed: AtomicU64,
+    rejected: AtomicU64,
+}
+
+/// 单个类别的限流统计
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitClassStats {
+    pub class: RateLimitClass,
+    pub rule: RateLimitRule,
+    /// 累计放行次数（进程启动以来）
+    pub allowed_total: u64,
+    /// 累计拒绝次数（进程启动以来）
+    pub rejected_total: u64,
+    /// 当前活跃令牌桶数（近似等于近期活跃用户数）
+    pub active_buckets: usize,
+}
+
+/// 令牌桶限流器
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Arc<Mutex<HashMap<(String, RateLimitClass), Bucket>>>,
+    counters: Arc<HashMap<RateLimitClass, ClassCounters>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+            counters: Arc::new(
+                RateLimitClass::ALL
+                    .into_iter()
+                    .map(|class| (class, ClassCounters::default()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// 尝试消耗一个令牌；超限时返回建议的重试等待时间
+    pub fn try_acquire(&self, user_id: &str, class: RateLimitClass) -> Result<(), Duration> {
+        let rule = self.config.rule(class);
+        let counters = &self.counters[&class];
+        if rule.is_unlimited() {
+            counters.allowed.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets
+            .entry((user_id.to_string(), class))
+            .or_insert(Bucket {
+                tokens: rule.capacity(),
+                updated_at: now,
+            });
+        refill(bucket, rule, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            counters.allowed.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        } else {
+            counters.rejected.fetch_add(1, Ordering::Relaxed);
+            let wait_secs = (1.0 - bucket.tokens) / rule.tokens_per_sec();
+            Err(Duration::from_secs_f64(wait_secs))
+        }
+    }
+
+    /// 清理已补满的令牌桶（补满后与新建桶等价）；返回清理数量
+    pub fn cleanup_idle(&self) -> usize {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let before = buckets.len();
+        buckets.retain(|(_, class), bucket| {
+            let rule = self.config.rule(*class);
+            refill(bucket, rule, now);
+            bucket.tokens < rule.capacity()
+        });
+        before - buckets.len()
+    }
+
+    /// 各类别限流统计
+    pub fn stats(&self) -> Vec<RateLimitClassStats> {
+        let buckets = self.buckets.lock().unwrap();
+        RateLimitClass::ALL
+            .into_iter()
+            .map(|class| {
+                let counters = &self.counters[&class];
+                RateLimitClassStats {
+                    class,
+                    rule: self.config.rule(class),
+                    allowed_total: counters.allowed.load(Ordering::Relaxed),
+                    rejected_total: counters.rejected.load(Ordering::Relaxed),
+                    active_buckets: buckets.keys().filter(|(_, c)| *c == class).count(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn refill(bucket: &mut Bucket, rule: RateLimitRule, now: Instant) {
+    let elapsed = now
+        .saturating_duration_since(bucket.updated_at)
+        .as_secs_f64();
+    bucket.tokens = (bucket.tokens + elapsed * rule.tokens_per_sec()).min(rule.capacity());
+    bucket.updated_at = now;
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(RateLimitConfig::default())
+    }
+}
+
+/// Prompt 执行类接口限流（预览、对比）
+///
+/// 作为 handler 参数使用，需放在请求体提取器之前，以便在解析请求体前拒绝超限请求。
+pub struct PromptExecutionRateLimit;
+
+/// 上游查询类接口限流（Dify 变量刷新、模型列表）
+pub struct UpstreamQueryRateLimit;
+
+impl FromRequestParts<AppState> for PromptExecutionRateLimit {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        enforce_rate_limit(&state.rate_limiter, RateLimitClass::PromptExecution, parts)
+            .map_err(rate_limited_response)?;
+        Ok(Self)
+    }
+}
+
+impl FromRequestParts<AppState> for UpstreamQueryRateLimit {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        enforce_rate_limit(&state.rate_limiter, RateLimitClass::UpstreamQuery, parts)
+            .map_err(rate_limited_response)?;
+        Ok(Self)
+    }
+}
+
+/// 超限时返回建议的 `Retry-After` 秒数
+fn enforce_rate_limit(
+    limiter: &RateLimiter,
+    class: RateLimitClass,
+    parts: &Parts,
+) -> Result<(), u64> {
+    let Some(current_user) = parts.extensions.get::<CurrentUser>() else {
+        return Ok(());
+    };
+
+    limiter
+        .try_acquire(&current_user.user_id, class)
+        .map_err(|wait| {
+            let retry_after_secs = wait.as_secs_f64().ceil().max(1.0) as u64;
+            warn!(
+                user_id = %current_user.user_id,
+                class = class.as_str(),
+                retry_after_secs = retry_after_secs,
+                "请求频率超限"
+            );
+            retry_after_secs
+        })
+}
+
+fn rate_limited_response(retry_after_secs: u64) -> Response {
+    let mut response = ApiResponse::<()>::err(
+        StatusCode::TOO_MANY_REQUESTS,
+        error_codes::RATE_LIMITED,
+        format!("请求过于频繁，请 {retry_after_secs} 秒后重试"),
+    )
+    .into_response();
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, HeaderValue::from(retry_after_secs));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(per_minute: u32, burst: u32) -> RateLimiter {
+        let rule = RateLimitRule { per_minute, burst };
+        RateLimiter::new(RateLimitConfig {
+            prompt_execution: rule,
+            upstream_query: RateLimitConfig::default().upstream_query,
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_bucket_allows_burst_then_refills() {
+        let limiter = limiter(60, 2);
+        let class = RateLimitClass::PromptExecution;
+
+        assert!(limiter.try_acquire("u1", class).is_ok());
+        assert!(limiter.try_acquire("u1", class).is_ok());
+        let wait = limiter.try_acquire("u1", class).unwrap_err();
+        assert!(wait <= Duration::from_secs(1) && wait > Duration::ZERO);
+
+        // 其他用户 / 其他类别互不影响
+        assert!(limiter.try_acquire("u2", class).is_ok());
+        assert!(
+            limiter
+                .try_acquire("u1", RateLimitClass::UpstreamQuery)
+                .is_ok()
+        );
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(limiter.try_acquire("u1", class).is_ok());
+        assert!(limiter.try_acquire("u1", class).is_err());
+
+        let stats = limiter.stats();
+        let execution = stats.iter().find(|s| s.class == class).unwrap();
+        assert_eq!(execution.allowed_total, 4);
+        assert_eq!(execution.rejected_total, 2);
+        assert_eq!(execution.active_buckets, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cleanup_idle_removes_full_buckets_and_unlimited_rule() {
+        let bucketed = limiter(60, 5);
+        let class = RateLimitClass::PromptExecution;
+        assert!(bucketed.try_acquire("u1", class).is_ok());
+        assert_eq!(bucketed.cleanup_idle(), 0);
+
+        tokio::time::advance(Duration::from_secs(2)).await;
+        assert_eq!(bucketed.cleanup_idle(), 1);
+
+        let unlimited = limiter(0, 1);
+        for _ in 0..100 {
+            assert!(unlimited.try_acquire("u1", class).is_ok());
+        }
+    }
+}
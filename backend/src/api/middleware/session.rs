@@ -1,16 +1,25 @@
 //! 会话管理模块
-//! 提供内存会话存储和会话管理功能
+//! 提供持久化会话存储（SQLite）、个人访问令牌校验和会话管理功能
 
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tracing::warn;
 use zeroize::Zeroizing;
 
+use crate::infra::db::repositories::{
+    AccessTokenRepo, AccessTokenScope, SessionRecord, SessionRepo, SessionRepoError,
+};
+use crate::infra::external::api_key_manager::{CredentialKeys, DataKey};
 use crate::shared::time::now_millis;
 
 /// 会话数据
 #[derive(Debug, Clone)]
 pub struct SessionData {
+    /// 会话 ID（用于列出 / 吊销）
+    pub session_id: String,
     /// 用户 ID
     pub user_id: String,
     /// 过期时间戳（Unix 毫秒）
@@ -18,25 +27,44 @@ pub struct SessionData {
     /// 解锁上下文（用于 API Key 加解密的派生材料）
     /// 注意：登出/过期时必须清除此字段
     pub unlock_context: Option<UnlockContext>,
+    /// 使用个人访问令牌鉴权时的授权范围（登录会话为 None）
+    pub access_token: Option<AccessTokenGrant>,
+}
+
+/// 个人访问令牌前缀（用于区分登录会话 token）
+pub const ACCESS_TOKEN_PREFIX: &str = "pf_pat_";
+
+/// 个人访问令牌授权范围
+#[derive(Debug, Clone)]
+pub struct AccessTokenGrant {
+    /// 令牌 ID
+    pub token_id: String,
+    /// 权限范围
+    pub scope: AccessTokenScope,
+    /// 限定工作区（None 表示不限）
+    pub workspace_id: Option<String>,
 }
 
 /// 解锁上下文
-/// 用于存放可再派生材料（用户密码的内存副本）
+/// 用于存放可再派生材料（用户密码的内存副本）与登录时解锁的数据密钥
 ///
 /// Code Review Fix (Story 1.6):
 /// - 使用 zeroize 进行内存清零
 /// - Drop 时自动清除密码内存
 #[derive(Clone)]
 pub struct UnlockContext {
-    /// 用户密码（仅存在内存中，登出时清除）
+    /// 用户密码（仅存在内存中，登出时清除；个人访问令牌无密码）
     /// 使用 zeroize 确保 Drop 时内存清零
-    password: Arc<Zeroizing<Vec<u8>>>,
+    password: Option<Arc<Zeroizing<Vec<u8>>>>,
+    /// 用户数据密钥（信封加密，登录时解锁）
+    data_key: Option<DataKey>,
 }
 
 impl std::fmt::Debug for UnlockContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("UnlockContext")
             .field("password", &"<redacted>")
+            .field("data_key", &self.data_key.is_some())
             .finish()
     }
 }
@@ -45,27 +73,87 @@ impl UnlockContext {
     /// 创建解锁上下文
     pub fn new(password: String) -> Self {
         Self {
-            password: Arc::new(Zeroizing::new(password.into_bytes())),
+            password: Some(Arc::new(Zeroizing::new(password.into_bytes()))),
+            data_key: None,
         }
     }
 
-    /// 获取密码引用（用于派生加密密钥）
+    /// 创建仅含数据密钥的解锁上下文（个人访问令牌）
+    pub fn from_data_key(data_key: DataKey) -> Self {
+        Self {
+            password: None,
+            data_key: Some(data_key),
+        }
+    }
+
+    /// 创建附带数据密钥的解锁上下文
+    pub fn with_data_key(password: String, data_key: DataKey) -> Self {
+        Self {
+            data_key: Some(data_key),
+            ..Self::new(password)
+        }
+    }
+
+    /// 获取密码引用（用于派生加密密钥；无密码时为空）
     pub fn password_bytes(&self) -> &[u8] {
-        self.password.as_ref().as_slice()
+        self.password.as_ref().map_or(&[], |p| p.as_slice())
     }
 
     pub fn password_str(&self) -> Option<&str> {
-        std::str::from_utf8(self.password_bytes()).ok()
+        let password = self.password.as_ref()?;
+        std::str::from_utf8(password.as_slice()).ok()
+    }
+
+    /// 已解锁的数据密钥
+    pub fn data_key(&self) -> Option<&DataKey> {
+        self.data_key.as_ref()
     }
+
+    /// 凭证解锁材料（数据密钥 + 登录密码）
+    pub fn credential_keys(&self) -> CredentialKeys {
+        match (&self.password, &self.data_key) {
+            (Some(password), Some(data_key)) => {
+                CredentialKeys::from_password(password.as_slice()).with_data_key(data_key.clone())
+            }
+            (Some(password), None) => CredentialKeys::from_password(password.as_slice()),
+            (None, Some(data_key)) => CredentialKeys::from_data_key(data_key.clone()),
+            (None, None) => CredentialKeys::default(),
+        }
+    }
+}
+
+/// 会话元数据（设备信息）
+#[derive(Debug, Clone, Default)]
+pub struct SessionMetadata {
+    /// 客户端 User-Agent
+    pub user_agent: Option<String>,
+    /// 客户端 IP
+    pub ip_address: Option<String>,
+}
+
+/// User-Agent 最大保存长度（字符）
+const USER_AGENT_MAX_CHARS: usize = 256;
+
+/// 最近使用时间的最小刷新间隔（毫秒），避免每个请求都写库
+const TOUCH_INTERVAL_MS: i64 = 60 * 1000;
+
+/// 计算会话 token / 个人访问令牌摘要（SHA-256 十六进制），数据库仅保存摘要
+pub fn hash_session_token(session_token: &str) -> String {
+    format!("{:x}", Sha256::digest(session_token.as_bytes()))
 }
 
 /// 会话存储
-/// 使用 Arc<RwLock<HashMap>> 实现并发安全的内存存储
+///
+/// 会话持久化在 SQLite（`user_sessions`，仅保存 token 摘要），服务重启后仍然有效；
+/// 解锁上下文只保存在内存中（session_id -> UnlockContext），重启后丢失。
+/// 以 [`ACCESS_TOKEN_PREFIX`] 开头的 token 按个人访问令牌校验。
 #[derive(Clone)]
 pub struct SessionStore {
-    /// 会话映射: session_token -> SessionData
-    sessions: Arc<RwLock<HashMap<String, SessionData>>>,
-    /// 会话过期时间（毫秒）
+    /// 会话数据库
+    db: SqlitePool,
+    /// 解锁上下文: session_id -> UnlockContext
+    unlock_contexts: Arc<RwLock<HashMap<String, UnlockContext>>>,
+    /// 会话过期时间（毫秒，滑动过期）
     session_ttl_ms: i64,
 }
 
@@ -73,14 +161,21 @@ impl SessionStore {
     /// 创建新的会话存储
     ///
     /// # 参数
-    /// - `session_ttl_hours`: 会话过期时间（小时），默认 24 小时
-    pub fn new(session_ttl_hours: u64) -> Self {
+    /// - `db`: 会话数据库
+    /// - `session_ttl_hours`: 会话空闲过期时间（小时），默认 24 小时
+    pub fn new(db: SqlitePool, session_ttl_hours: u64) -> Self {
         Self {
-            sessions: Arc::new(RwLock::new(HashMap::new())),
+            db,
+            unlock_contexts: Arc::new(RwLock::new(HashMap::new())),
             session_ttl_ms: (session_ttl_hours * 60 * 60 * 1000) as i64,
         }
     }
 
+    /// 会话数据库
+    pub(crate) fn db(&self) -> &SqlitePool {
+        &self.db
+    }
+
     /// 创建新会话
     ///
     /// # 参数
@@ -93,88 +188,280 @@ impl SessionStore {
         &self,
         user_id: String,
         unlock_context: Option<UnlockContext>,
-    ) -> String {
-        let session_token = uuid::Uuid::new_v4().to_string();
-        let expires_at = now_millis() + self.session_ttl_ms;
+    ) -> Result<String, SessionRepoError> {
+        self.create_session_with_metadata(user_id, unlock_context, SessionMetadata::default())
+            .await
+    }
 
-        let session_data = SessionData {
+    /// 创建新会话（附带设备信息）
+    pub async fn create_session_with_metadata(
+        &self,
+        user_id: String,
+        unlock_context: Option<UnlockContext>,
+        metadata: SessionMetadata,
+    ) -> Result<String, SessionRepoError> {
+        let session_token = uuid::Uuid::new_v4().to_string();
+        let now = now_millis();
+        let record = SessionRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            token_hash: hash_session_token(&session_token),
             user_id,
-            expires_at,
-            unlock_context,
+            user_agent: metadata
+                .user_agent
+                .map(|ua| ua.chars().take(USER_AGENT_MAX_CHARS).collect()),
+            ip_address: metadata.ip_address,
+            created_at: now,
+            last_seen_at: now,
+            expires_at: now + self.session_ttl_ms,
         };
+        SessionRepo::insert(&self.db, &record).await?;
 
-        let mut sessions = self.sessions.write().await;
-        sessions.insert(session_token.clone(), session_data);
+        if let Some(unlock_context) = unlock_context {
+            let mut contexts = self.unlock_contexts.write().await;
+            contexts.insert(record.id, unlock_context);
+        }
 
-        session_token
+        Ok(session_token)
     }
 
     /// 验证会话并获取会话数据
     ///
+    /// 有效会话会顺延过期时间（滑动过期）。
+    ///
     /// # 参数
     /// - `session_token`: 会话 token
     ///
     /// # 返回
     /// - 有效会话返回 `Some(SessionData)`
-    /// - 无效或过期会话返回 `None`
+    /// - 无效、过期或查询失败返回 `None`
     pub async fn validate_session(&self, session_token: &str) -> Option<SessionData> {
-        let sessions = self.sessions.read().await;
+        if session_token.starts_with(ACCESS_TOKEN_PREFIX) {
+            return self.validate_access_token(session_token).await;
+        }
 
-        if let Some(session) = sessions.get(session_token) {
-            let now = now_millis();
-            if session.expires_at > now {
-                return Some(session.clone());
+        let now = now_millis();
+        let record = match SessionRepo::find_active_by_token_hash(
+            &self.db,
+            &hash_session_token(session_token),
+            now,
+        )
+        .await
+        {
+            Ok(record) => record?,
+            Err(e) => {
+                warn!(error = %e, "查询会话失败");
+                return None;
+            }
+        };
+
+        let mut expires_at = record.expires_at;
+        if now - record.last_seen_at >= TOUCH_INTERVAL_MS {
+            expires_at = now + self.session_ttl_ms;
+            if let Err(e) = SessionRepo::touch(&self.db, &record.id, now, expires_at).await {
+                warn!(error = %e, session_id = %record.id, "刷新会话过期时间失败");
+                expires_at = record.expires_at;
             }
         }
 
-        None
+        let unlock_context = self.unlock_contexts.read().await.get(&record.id).cloned();
+        Some(SessionData {
+            session_id: record.id,
+            user_id: record.user_id,
+            expires_at,
+            unlock_context,
+            access_token: None,
+        })
+    }
+
+    /// 校验个人访问令牌
+    ///
+    /// run 令牌如附带包装的数据密钥，解包后作为解锁上下文（仅含数据密钥）。
+    async fn validate_access_token(&self, token: &str) -> Option<SessionData> {
+        let now = now_millis();
+        let record = match AccessTokenRepo::find_active_by_token_hash(
+            &self.db,
+            &hash_session_token(token),
+            now,
+        )
+        .await
+        {
+            Ok(record) => record?,
+            Err(e) => {
+                warn!(error = %e, "查询个人访问令牌失败");
+                return None;
+            }
+        };
+        let scope = AccessTokenScope::parse(&record.scope)?;
+
+        if record
+            .last_used_at
+            .is_none_or(|last_used_at| now - last_used_at >= TOUCH_INTERVAL_MS)
+            && let Err(e) = AccessTokenRepo::touch(&self.db, &record.id, now).await
+        {
+            warn!(error = %e, token_id = %record.id, "更新令牌使用时间失败");
+        }
+
+        let unlock_context = match (scope, record.wrapped_data_key()) {
+            (AccessTokenScope::Run, Some(wrapped)) => {
+                match DataKey::unwrap_for_token(token, &wrapped) {
+                    Ok(data_key) => Some(UnlockContext::from_data_key(data_key)),
+                    Err(e) => {
+                        warn!(error = %e, token_id = %record.id, "解包令牌数据密钥失败");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        Some(SessionData {
+            session_id: record.id.clone(),
+            user_id: record.user_id,
+            expires_at: record.expires_at.unwrap_or(i64::MAX),
+            unlock_context,
+            access_token: Some(AccessTokenGrant {
+                token_id: record.id,
+                scope,
+                workspace_id: record.workspace_id,
+            }),
+        })
     }
 
     /// 移除会话（登出）
     ///
     /// # 注意
     /// 移除会话时会清除 unlock_context
-    pub async fn remove_session(&self, session_token: &str) -> bool {
-        let mut sessions = self.sessions.write().await;
-        sessions.remove(session_token).is_some()
+    pub async fn remove_session(&self, session_token: &str) -> Result<bool, SessionRepoError> {
+        let removed =
+            SessionRepo::delete_by_token_hash(&self.db, &hash_session_token(session_token)).await?;
+        match removed {
+            Some(session_id) => {
+                self.forget_unlock_contexts(std::slice::from_ref(&session_id))
+                    .await;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
     }
 
-    /// 清理过期会话
+    /// 移除用户除指定会话外的全部会话（修改密码、吊销其他设备）
     ///
-    /// 定期调用此方法以防止内存泄漏
-    pub async fn cleanup_expired_sessions(&self) -> usize {
-        let now = now_millis();
-        let mut sessions = self.sessions.write().await;
+    /// # 返回
+    /// 被移除的会话数量
+    pub async fn remove_user_sessions_except(
+        &self,
+        user_id: &str,
+        keep_token: &str,
+    ) -> Result<usize, SessionRepoError> {
+        let keep_hash = hash_session_token(keep_token);
+        let removed =
+            SessionRepo::delete_by_user_except(&self.db, user_id, Some(&keep_hash)).await?;
+        self.forget_unlock_contexts(&removed).await;
+        Ok(removed.len())
+    }
 
-        let before_count = sessions.len();
-        sessions.retain(|_, session| session.expires_at > now);
-        let after_count = sessions.len();
+    /// 移除用户的全部会话
+    pub async fn remove_all_user_sessions(&self, user_id: &str) -> Result<usize, SessionRepoError> {
+        let removed = SessionRepo::delete_by_user_except(&self.db, user_id, None).await?;
+        self.forget_unlock_contexts(&removed).await;
+        Ok(removed.len())
+    }
 
-        before_count - after_count
+    /// 吊销用户的指定会话
+    pub async fn revoke_user_session(
+        &self,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<bool, SessionRepoError> {
+        let removed = SessionRepo::delete_by_id(&self.db, user_id, session_id).await?;
+        if removed {
+            self.forget_unlock_contexts(&[session_id.to_string()]).await;
+        }
+        Ok(removed)
+    }
+
+    /// 列出用户未过期的会话
+    pub async fn list_user_sessions(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<SessionRecord>, SessionRepoError> {
+        SessionRepo::list_active_by_user(&self.db, user_id, now_millis()).await
+    }
+
+    /// 替换会话的解锁上下文（修改密码后使用新密码派生）
+    pub async fn replace_unlock_context(
+        &self,
+        session_token: &str,
+        unlock_context: UnlockContext,
+    ) -> bool {
+        let Some(session) = self.validate_session(session_token).await else {
+            return false;
+        };
+        let mut contexts = self.unlock_contexts.write().await;
+        contexts.insert(session.session_id, unlock_context);
+        true
+    }
+
+    /// 清理过期会话
+    ///
+    /// 定期调用此方法以删除过期会话并释放其解锁上下文
+    pub async fn cleanup_expired_sessions(&self) -> Result<usize, SessionRepoError> {
+        let removed = SessionRepo::delete_expired(&self.db, now_millis()).await?;
+        self.forget_unlock_contexts(&removed).await;
+        Ok(removed.len())
     }
 
     /// 获取当前活跃会话数量
-    pub async fn active_session_count(&self) -> usize {
-        let sessions = self.sessions.read().await;
-        sessions.len()
+    pub async fn active_session_count(&self) -> Result<usize, SessionRepoError> {
+        let count = SessionRepo::count_active(&self.db, now_millis()).await?;
+        Ok(count as usize)
     }
-}
 
-impl Default for SessionStore {
-    fn default() -> Self {
-        Self::new(24)
+    async fn forget_unlock_contexts(&self, session_ids: &[String]) {
+        if session_ids.is_empty() {
+            return;
+        }
+        let mut contexts = self.unlock_contexts.write().await;
+        for session_id in session_ids {
+            contexts.remove(session_id);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::infra::db::pool::create_pool;
+
+    async fn setup_store() -> SessionStore {
+        let pool = create_pool("sqlite::memory:")
+            .await
+            .expect("创建测试数据库失败");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("运行 migrations 失败");
+        // user_sessions.user_id 关联 users(id)
+        for user_id in ["user123", "user456"] {
+            sqlx::query(
+                "INSERT INTO users (id, username, password_hash, created_at, updated_at) VALUES (?1, ?1, 'hash', 0, 0)",
+            )
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .expect("插入测试用户失败");
+        }
+        SessionStore::new(pool, 1) // 1 小时过期
+    }
 
     #[tokio::test]
     async fn test_create_and_validate_session() {
-        let store = SessionStore::new(1); // 1 小时过期
+        let store = setup_store().await;
 
-        let token = store.create_session("user123".to_string(), None).await;
+        let token = store
+            .create_session("user123".to_string(), None)
+            .await
+            .unwrap();
 
         let session = store.validate_session(&token).await;
         assert!(session.is_some());
@@ -183,7 +470,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_invalid_token_returns_none() {
-        let store = SessionStore::default();
+        let store = setup_store().await;
 
         let session = store.validate_session("invalid_token").await;
         assert!(session.is_none());
@@ -191,13 +478,16 @@ mod tests {
 
     #[tokio::test]
     async fn test_remove_session() {
-        let store = SessionStore::default();
+        let store = setup_store().await;
 
-        let token = store.create_session("user123".to_string(), None).await;
+        let token = store
+            .create_session("user123".to_string(), None)
+            .await
+            .unwrap();
 
         assert!(store.validate_session(&token).await.is_some());
 
-        let removed = store.remove_session(&token).await;
+        let removed = store.remove_session(&token).await.unwrap();
         assert!(removed);
 
         assert!(store.validate_session(&token).await.is_none());
@@ -205,12 +495,13 @@ mod tests {
 
     #[tokio::test]
     async fn test_session_with_unlock_context() {
-        let store = SessionStore::default();
+        let store = setup_store().await;
 
         let unlock_ctx = UnlockContext::new("user_password".to_string());
         let token = store
             .create_session("user123".to_string(), Some(unlock_ctx))
-            .await;
+            .await
+            .unwrap();
 
         let session = store.validate_session(&token).await.unwrap();
         assert!(session.unlock_context.is_some());
@@ -219,4 +510,210 @@ mod tests {
             Some("user_password")
         );
     }
+
+    #[tokio::test]
+    async fn test_session_survives_restart_without_unlock_context() {
+        let store = setup_store().await;
+        let token = store
+            .create_session_with_metadata(
+                "user123".to_string(),
+                Some(UnlockContext::new("user_password".to_string())),
+                SessionMetadata {
+                    user_agent: Some("x".repeat(USER_AGENT_MAX_CHARS + 10)),
+                    ip_address: Some("127.0.0.1".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+
+        // 同一数据库上的新实例（模拟重启）：会话有效，解锁上下文不落库
+        let restarted = SessionStore::new(store.db.clone(), 1);
+        let session = restarted.validate_session(&token).await.unwrap();
+        assert_eq!(session.user_id, "user123");
+        assert!(session.unlock_context.is_none());
+
+        let sessions = restarted.list_user_sessions("user123").await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, session.session_id);
+        assert_eq!(sessions[0].token_hash, hash_session_token(&token));
+        assert_ne!(sessions[0].token_hash, token);
+        assert_eq!(
+            sessions[0]
+                .user_agent
+                .as_deref()
+                .map(|ua| ua.chars().count()),
+            Some(USER_AGENT_MAX_CHARS)
+        );
+        assert_eq!(sessions[0].ip_address.as_deref(), Some("127.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_session_slides_expiry() {
+        let store = setup_store().await;
+        let token = store
+            .create_session("user123".to_string(), None)
+            .await
+            .unwrap();
+        let session_id = store.validate_session(&token).await.unwrap().session_id;
+
+        // 模拟已空闲一段时间
+        let stale = now_millis() - 2 * TOUCH_INTERVAL_MS;
+        SessionRepo::touch(&store.db, &session_id, stale, now_millis() + 10_000)
+            .await
+            .unwrap();
+
+        let session = store.validate_session(&token).await.unwrap();
+        assert!(session.expires_at > now_millis() + store.session_ttl_ms - 60_000);
+        let record = &store.list_user_sessions("user123").await.unwrap()[0];
+        assert!(record.last_seen_at > stale);
+        assert_eq!(record.expires_at, session.expires_at);
+
+        // 已过期会话不可用，并由清理任务删除
+        SessionRepo::touch(&store.db, &session_id, stale, stale)
+            .await
+            .unwrap();
+        assert!(store.validate_session(&token).await.is_none());
+        assert_eq!(store.cleanup_expired_sessions().await.unwrap(), 1);
+        assert_eq!(store.active_session_count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_remove_user_sessions_except_keeps_current_and_other_users() {
+        let store = setup_store().await;
+
+        let current = store
+            .create_session("user123".to_string(), None)
+            .await
+            .unwrap();
+        let other_device = store
+            .create_session("user123".to_string(), None)
+            .await
+            .unwrap();
+        let other_user = store
+            .create_session("user456".to_string(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store
+                .remove_user_sessions_except("user123", &current)
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(store.validate_session(&current).await.is_some());
+        assert!(store.validate_session(&other_device).await.is_none());
+        assert!(store.validate_session(&other_user).await.is_some());
+
+        assert_eq!(store.remove_all_user_sessions("user123").await.unwrap(), 1);
+        assert!(store.validate_session(&current).await.is_none());
+        assert!(store.validate_session(&other_user).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_user_session_requires_owner() {
+        let store = setup_store().await;
+        let token = store
+            .create_session(
+                "user123".to_string(),
+                Some(UnlockContext::new("pw".to_string())),
+            )
+            .await
+            .unwrap();
+        let session_id = store.validate_session(&token).await.unwrap().session_id;
+
+        assert!(
+            !store
+                .revoke_user_session("user456", &session_id)
+                .await
+                .unwrap()
+        );
+        assert!(store.validate_session(&token).await.is_some());
+
+        assert!(
+            store
+                .revoke_user_session("user123", &session_id)
+                .await
+                .unwrap()
+        );
+        assert!(store.validate_session(&token).await.is_none());
+        assert!(store.unlock_contexts.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_validate_access_token_respects_expiry_and_records_use() {
+        use crate::infra::db::repositories::{CreateAccessTokenInput, UserRepo, WorkspaceRepo};
+
+        let store = setup_store().await;
+        let user = UserRepo::create_user(&store.db, "pat_user", "hash")
+            .await
+            .unwrap();
+        let workspace = WorkspaceRepo::create(&store.db, &user.id, "ws", None)
+            .await
+            .unwrap();
+        let create = |token: &str, expires_at: Option<i64>| CreateAccessTokenInput {
+            user_id: user.id.clone(),
+            name: "ci".to_string(),
+            token_prefix: token[..ACCESS_TOKEN_PREFIX.len() + 4].to_string(),
+            token_hash: hash_session_token(token),
+            scope: AccessTokenScope::Read,
+            workspace_id: Some(workspace.id.clone()),
+            wrapped_data_key: None,
+            expires_at,
+        };
+
+        let now = now_millis();
+        let active = format!("{ACCESS_TOKEN_PREFIX}active");
+        let record = AccessTokenRepo::create(&store.db, create(&active, None), now)
+            .await
+            .unwrap();
+        let expired = format!("{ACCESS_TOKEN_PREFIX}expired");
+        AccessTokenRepo::create(&store.db, create(&expired, Some(now - 1)), now)
+            .await
+            .unwrap();
+
+        let session = store.validate_session(&active).await.unwrap();
+        assert_eq!(session.user_id, user.id);
+        assert!(session.unlock_context.is_none());
+        let grant = session.access_token.unwrap();
+        assert_eq!(grant.token_id, record.id);
+        assert_eq!(grant.scope, AccessTokenScope::Read);
+        assert_eq!(grant.workspace_id.as_deref(), Some(workspace.id.as_str()));
+        let listed = AccessTokenRepo::list_by_user(&store.db, &user.id)
+            .await
+            .unwrap();
+        assert!(
+            listed
+                .iter()
+                .any(|t| t.id == record.id && t.last_used_at.is_some())
+        );
+
+        assert!(store.validate_session(&expired).await.is_none());
+        assert!(
+            store
+                .validate_session(&format!("{ACCESS_TOKEN_PREFIX}unknown"))
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replace_unlock_context() {
+        let store = setup_store().await;
+        let token = store
+            .create_session(
+                "user123".to_string(),
+                Some(UnlockContext::new("old".to_string())),
+            )
+            .await
+            .unwrap();
+
+        assert!(
+            store
+                .replace_unlock_context(&token, UnlockContext::new("new".to_string()))
+                .await
+        );
+        let session = store.validate_session(&token).await.unwrap();
+        assert_eq!(session.unlock_context.unwrap().password_str(), Some("new"));
+    }
 }
@@ -0,0 +1,349 @@
+//! 个人访问令牌路由
+//! 提供令牌的创建、列出与吊销 API（仅限登录会话调用）
+//!
+//! 凭证解密策略：令牌不持有登录密码。`run` 令牌创建时，若当前会话已解锁数据密钥
+//! （或可由服务端 KEK 解锁），数据密钥会用令牌本身派生的密钥包装后随令牌保存，
+//! 持令牌的请求据此解密凭证；否则令牌请求只能依赖服务端 KEK。`read` 令牌从不携带数据密钥。
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::{
+    Json, Router,
+    routing::{delete, get},
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use crate::api::middleware::CurrentUser;
+use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
+use crate::api::middleware::session::{ACCESS_TOKEN_PREFIX, hash_session_token};
+use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
+use crate::api::state::AppState;
+use crate::infra::db::repositories::{
+    ACCESS_TOKEN_NAME_MAX_CHARS, AccessTokenRecord, AccessTokenRepo, AccessTokenScope,
+    CreateAccessTokenInput, WorkspaceRepo, WorkspaceRepoError,
+};
+use crate::infra::external::api_key_manager::DataKey;
+use crate::shared::error_codes;
+use crate::shared::time::now_millis;
+
+/// 令牌随机部分字节数（十六进制编码后 64 字符）
+const ACCESS_TOKEN_RANDOM_BYTES: usize = 32;
+/// 列表展示的令牌前缀长度（含固定前缀）
+const ACCESS_TOKEN_DISPLAY_PREFIX_CHARS: usize = ACCESS_TOKEN_PREFIX.len() + 8;
+/// 最长有效期（天）
+const ACCESS_TOKEN_MAX_EXPIRES_DAYS: u32 = 365;
+
+/// 创建个人访问令牌请求
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct CreateAccessTokenRequest {
+    pub name: String,
+    /// 权限范围：`read`（只读）或 `run`（可启动任务、导出结果）
+    pub scope: String,
+    /// 限定工作区（为空表示不限）
+    pub workspace_id: Option<String>,
+    /// 有效天数（1-365，为空表示永不过期）
+    pub expires_in_days: Option<u32>,
+}
+
+/// 个人访问令牌信息（不含令牌明文）
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct AccessTokenInfo {
+    pub id: String,
+    pub name: String,
+    /// 令牌前缀（用于识别）
+    pub token_prefix: String,
+    pub scope: String,
+    pub workspace_id: Option<String>,
+    /// 是否可在无登录会话时解密凭证
+    pub credential_access: bool,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    #[ts(type = "number | null")]
+    pub expires_at: Option<i64>,
+    #[ts(type = "number | null")]
+    pub last_used_at: Option<i64>,
+}
+
+/// 创建个人访问令牌响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct CreateAccessTokenResponse {
+    /// 令牌明文（仅此一次返回）
+    pub token: String,
+    pub access_token: AccessTokenInfo,
+}
+
+/// 个人访问令牌列表响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct AccessTokenListResponse {
+    pub tokens: Vec<AccessTokenInfo>,
+}
+
+/// 吊销个人访问令牌响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct RevokeAccessTokenResponse {
+    pub message: String,
+}
+
+fn extract_correlation_id(headers: &HeaderMap) -> String {
+    headers
+        .get(CORRELATION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+fn generate_access_token() -> String {
+    let mut bytes = [0u8; ACCESS_TOKEN_RANDOM_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let random: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("{ACCESS_TOKEN_PREFIX}{random}")
+}
+
+fn to_access_token_info(record: AccessTokenRecord) -> AccessTokenInfo {
+    AccessTokenInfo {
+        credential_access: record.wrapped_data_key.is_some(),
+        id: record.id,
+        name: record.name,
+        token_prefix: record.token_prefix,
+        scope: record.scope,
+        workspace_id: record.workspace_id,
+        created_at: record.created_at,
+        expires_at: record.expires_at,
+        last_used_at: record.last_used_at,
+    }
+}
+
+/// 获取可随 run 令牌保存的数据密钥：优先使用会话中已解锁的，其次服务端 KEK
+async fn data_key_for_token(state: &AppState, current_user: &CurrentUser) -> Option<DataKey> {
+    if let Some(data_key) = current_user
+        .unlock_context
+        .as_ref()
+        .and_then(|ctx| ctx.data_key())
+    {
+        return Some(data_key.clone());
+    }
+    state
+        .api_key_manager
+        .unlock_data_key_unattended(&state.db, &current_user.user_id)
+        .await
+        .ok()
+}
+
+/// 创建个人访问令牌
+///
+/// POST /api/v1/auth/tokens
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/tokens",
+    request_body = CreateAccessTokenRequest,
+    responses(
+        (status = 200, description = "创建成功（令牌明文仅返回一次）", body = ApiSuccess<CreateAccessTokenResponse>),
+        (status = 400, description = "参数错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 403, description = "令牌不能创建令牌", body = ApiError),
+        (status = 404, description = "工作区不存在", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn create_access_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    current_user: CurrentUser,
+    Json(req): Json<CreateAccessTokenRequest>,
+) -> ApiResponse<CreateAccessTokenResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    let user_id = &current_user.user_id;
+
+    let name = req.name.trim();
+    if name.is_empty() || name.chars().count() > ACCESS_TOKEN_NAME_MAX_CHARS {
+        return ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            format!("令牌名称不能为空且不能超过 {ACCESS_TOKEN_NAME_MAX_CHARS} 个字符"),
+        );
+    }
+    let Some(scope) = AccessTokenScope::parse(req.scope.trim()) else {
+        return ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            "scope 仅支持 read 或 run",
+        );
+    };
+    let expires_at = match req.expires_in_days {
+        None => None,
+        Some(days) if (1..=ACCESS_TOKEN_MAX_EXPIRES_DAYS).contains(&days) => {
+            Some(now_millis() + i64::from(days) * 24 * 60 * 60 * 1000)
+        }
+        Some(_) => {
+            return ApiResponse::err(
+                StatusCode::BAD_REQUEST,
+                error_codes::VALIDATION_ERROR,
+                format!("有效天数必须在 1-{ACCESS_TOKEN_MAX_EXPIRES_DAYS} 之间"),
+            );
+        }
+    };
+
+    let workspace_id = req
+        .workspace_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty());
+    if let Some(workspace_id) = workspace_id {
+        match WorkspaceRepo::find_by_id(&state.db, workspace_id, user_id).await {
+            Ok(_) => {}
+            Err(WorkspaceRepoError::NotFound) => {
+                return ApiResponse::err(
+                    StatusCode::NOT_FOUND,
+                    error_codes::WORKSPACE_NOT_FOUND,
+                    "工作区不存在",
+                );
+            }
+            Err(e) => {
+                warn!(error = %e, "查询工作区失败");
+                return ApiResponse::err(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    error_codes::DATABASE_ERROR,
+                    "创建令牌失败",
+                );
+            }
+        }
+    }
+
+    let token = generate_access_token();
+    let wrapped_data_key = match scope {
+        AccessTokenScope::Run => match data_key_for_token(&state, &current_user).await {
+            Some(data_key) => match data_key.wrap_for_token(&token) {
+                Ok(wrapped) => Some(wrapped),
+                Err(e) => {
+                    warn!(error = %e, user_id = %user_id, "包装令牌数据密钥失败");
+                    None
+                }
+            },
+            None => None,
+        },
+        AccessTokenScope::Read => None,
+    };
+
+    let input = CreateAccessTokenInput {
+        user_id: user_id.clone(),
+        name: name.to_string(),
+        token_prefix: token[..ACCESS_TOKEN_DISPLAY_PREFIX_CHARS].to_string(),
+        token_hash: hash_session_token(&token),
+        scope,
+        workspace_id: workspace_id.map(str::to_string),
+        wrapped_data_key,
+        expires_at,
+    };
+    match AccessTokenRepo::create(&state.db, input, now_millis()).await {
+        Ok(record) => {
+            info!(
+                correlation_id = %correlation_id,
+                user_id = %user_id,
+                token_id = %record.id,
+                scope = scope.as_str(),
+                credential_access = record.wrapped_data_key.is_some(),
+                "创建个人访问令牌"
+            );
+            ApiResponse::ok(CreateAccessTokenResponse {
+                token,
+                access_token: to_access_token_info(record),
+            })
+        }
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "创建个人访问令牌失败");
+            ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "创建令牌失败",
+            )
+        }
+    }
+}
+
+/// 列出当前用户的个人访问令牌
+///
+/// GET /api/v1/auth/tokens
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/tokens",
+    responses(
+        (status = 200, description = "查询成功", body = ApiSuccess<AccessTokenListResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn list_access_tokens(
+    State(state): State<AppState>,
+    current_user: CurrentUser,
+) -> ApiResponse<AccessTokenListResponse> {
+    match AccessTokenRepo::list_by_user(&state.db, &current_user.user_id).await {
+        Ok(records) => ApiResponse::ok(AccessTokenListResponse {
+            tokens: records.into_iter().map(to_access_token_info).collect(),
+        }),
+        Err(e) => {
+            warn!(error = %e, user_id = %current_user.user_id, "查询个人访问令牌失败");
+            ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "查询令牌失败",
+            )
+        }
+    }
+}
+
+/// 吊销个人访问令牌
+///
+/// DELETE /api/v1/auth/tokens/{token_id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/tokens/{token_id}",
+    params(("token_id" = String, Path, description = "令牌 ID")),
+    responses(
+        (status = 200, description = "吊销成功", body = ApiSuccess<RevokeAccessTokenResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 404, description = "令牌不存在", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn revoke_access_token(
+    State(state): State<AppState>,
+    current_user: CurrentUser,
+    Path(token_id): Path<String>,
+) -> ApiResponse<RevokeAccessTokenResponse> {
+    match AccessTokenRepo::delete_by_id(&state.db, &current_user.user_id, &token_id).await {
+        Ok(true) => {
+            info!(user_id = %current_user.user_id, token_id = %token_id, "个人访问令牌已吊销");
+            ApiResponse::ok(RevokeAccessTokenResponse {
+                message: "令牌已吊销".to_string(),
+            })
+        }
+        Ok(false) => ApiResponse::err(StatusCode::NOT_FOUND, error_codes::NOT_FOUND, "令牌不存在"),
+        Err(e) => {
+            warn!(error = %e, user_id = %current_user.user_id, "吊销个人访问令牌失败");
+            ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "吊销令牌失败",
+            )
+        }
+    }
+}
+
+/// 创建个人访问令牌路由（需鉴权）
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_access_tokens).post(create_access_token))
+        .route("/{token_id}", delete(revoke_access_token))
+}
@@ -0,0 +1,349 @@
+//! 安全审计事件路由
+//! 提供审计事件的筛选查询 API（仅限登录会话调用）
+//!
+//! 普通用户只能查询以自己为操作者的事件；实例管理员（首个注册用户）可查询全部事件，
+//! 包括未知用户名的登录失败记录。审计事件只读，不提供修改 / 删除接口。
+
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::{Router, routing::get};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use ts_rs::TS;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::api::middleware::CurrentUser;
+use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
+use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
+use crate::api::state::AppState;
+use crate::infra::db::repositories::{
+    AuditEventFilter, AuditEventRecord, AuditEventRepo, AuditEventType, AuditOutcome, UserRepo,
+};
+use crate::shared::error_codes;
+
+/// 默认每页条数
+const AUDIT_EVENTS_DEFAULT_LIMIT: usize = 50;
+/// 每页最大条数
+const AUDIT_EVENTS_MAX_LIMIT: usize = 100;
+/// 最大偏移量
+const AUDIT_EVENTS_MAX_OFFSET: usize = 10_000;
+
+/// 审计事件查询参数
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuditEventQuery {
+    /// 事件类型（逗号分隔，snake_case，如 `login_failed,login_locked_out`）
+    pub event_types: Option<String>,
+    /// 结果（success / failure / denied）
+    pub outcome: Option<String>,
+    /// 操作者用户 ID（仅实例管理员可查询他人）
+    pub actor_user_id: Option<String>,
+    /// 客户端 IP
+    pub ip_address: Option<String>,
+    /// correlationId
+    pub correlation_id: Option<String>,
+    /// 时间范围起点（Unix ms，包含）
+    pub time_start: Option<i64>,
+    /// 时间范围终点（Unix ms，包含）
+    pub time_end: Option<i64>,
+    /// 最大返回条数（默认 50，最大 100）
+    pub limit: Option<usize>,
+    /// 偏移量（默认 0，最大 10000）
+    pub offset: Option<usize>,
+}
+
+/// 审计事件
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct AuditEventInfo {
+    pub id: String,
+    pub event_type: String,
+    /// success / failure / denied
+    pub outcome: String,
+    pub actor_user_id: Option<String>,
+    pub actor_username: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub correlation_id: Option<String>,
+    /// 目标类型（credential / session / task）
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    /// 附加信息（已脱敏）
+    pub details: Option<serde_json::Value>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+/// 审计事件列表响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct AuditEventListResponse {
+    pub events: Vec<AuditEventInfo>,
+    pub total: u32,
+    pub has_more: bool,
+}
+
+fn extract_correlation_id(headers: &HeaderMap) -> String {
+    headers
+        .get(CORRELATION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+fn non_empty(raw: &Option<String>) -> Option<String> {
+    raw.as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn parse_event_types(raw: &Option<String>) -> Result<Option<Vec<AuditEventType>>, String> {
+    let Some(raw) = raw.as_ref() else {
+        return Ok(None);
+    };
+    let mut out = Vec::new();
+    for item in raw.split(',') {
+        let value = item.trim();
+        if value.is_empty() {
+            continue;
+        }
+        let parsed = AuditEventType::parse(value)
+            .ok_or_else(|| format!("不支持的 event_types 值: {value}"))?;
+        out.push(parsed);
+    }
+    Ok(if out.is_empty() { None } else { Some(out) })
+}
+
+fn build_filter(query: &AuditEventQuery) -> Result<AuditEventFilter, String> {
+    if let (Some(start), Some(end)) = (query.time_start, query.time_end)
+        && start > end
+    {
+        return Err("time_start 不能大于 time_end".to_string());
+    }
+    let outcome = match non_empty(&query.outcome) {
+        Some(value) => Some(
+            AuditOutcome::parse(&value).ok_or_else(|| format!("不支持的 outcome 值: {value}"))?,
+        ),
+        None => None,
+    };
+
+    Ok(AuditEventFilter {
+        event_types: parse_event_types(&query.event_types)?,
+        outcome,
+        actor_user_id: non_empty(&query.actor_user_id),
+        ip_address: non_empty(&query.ip_address),
+        correlation_id: non_empty(&query.correlation_id),
+        time_start: query.time_start,
+        time_end: query.time_end,
+    })
+}
+
+fn normalize_limit_offset(query: &AuditEventQuery) -> Result<(usize, usize), String> {
+    let limit = query.limit.unwrap_or(AUDIT_EVENTS_DEFAULT_LIMIT);
+    if limit == 0 || limit > AUDIT_EVENTS_MAX_LIMIT {
+        return Err(format!("limit 必须在 1-{AUDIT_EVENTS_MAX_LIMIT} 范围内"));
+    }
+    let offset = query.offset.unwrap_or(0);
+    if offset > AUDIT_EVENTS_MAX_OFFSET {
+        return Err(format!("offset 不能超过 {AUDIT_EVENTS_MAX_OFFSET}"));
+    }
+    Ok((limit, offset))
+}
+
+fn to_info(record: AuditEventRecord) -> AuditEventInfo {
+    AuditEventInfo {
+        details: record
+            .details
+            .as_deref()
+            .and_then(|raw| serde_json::from_str(raw).ok()),
+        id: record.id,
+        event_type: record.event_type,
+        outcome: record.outcome,
+        actor_user_id: record.actor_user_id,
+        actor_username: record.actor_username,
+        ip_address: record.ip_address,
+        user_agent: record.user_agent,
+        correlation_id: record.correlation_id,
+        target_type: record.target_type,
+        target_id: record.target_id,
+        created_at: record.created_at,
+    }
+}
+
+fn validation_error<T: Serialize>(msg: String) -> ApiResponse<T> {
+    ApiResponse::err(StatusCode::BAD_REQUEST, error_codes::VALIDATION_ERROR, msg)
+}
+
+fn database_error<T: Serialize>() -> ApiResponse<T> {
+    ApiResponse::err(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        error_codes::DATABASE_ERROR,
+        "查询审计事件失败",
+    )
+}
+
+/// 查询安全审计事件
+///
+/// GET /api/v1/auth/audit-events
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/audit-events",
+    params(AuditEventQuery),
+    responses(
+        (status = 200, description = "查询成功", body = ApiSuccess<AuditEventListResponse>),
+        (status = 400, description = "参数错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 403, description = "无权查询其他用户的审计事件", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn list_audit_events(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    current_user: CurrentUser,
+    Query(query): Query<AuditEventQuery>,
+) -> ApiResponse<AuditEventListResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    let user_id = &current_user.user_id;
+
+    let mut filter = match build_filter(&query) {
+        Ok(filter) => filter,
+        Err(msg) => return validation_error(msg),
+    };
+    let (limit, offset) = match normalize_limit_offset(&query) {
+        Ok(value) => value,
+        Err(msg) => return validation_error(msg),
+    };
+
+    let is_admin = match UserRepo::get_first_user(&state.db).await {
+        Ok(first_user) => first_user.is_some_and(|u| &u.id == user_id),
+        Err(e) => {
+            warn!(correlation_id = %correlation_id, error = %e, "查询实例管理员失败");
+            return database_error();
+        }
+    };
+    if !is_admin {
+        if filter
+            .actor_user_id
+            .as_ref()
+            .is_some_and(|actor| actor != user_id)
+        {
+            return ApiResponse::err(
+                StatusCode::FORBIDDEN,
+                error_codes::FORBIDDEN,
+                "仅实例管理员可查询其他用户的审计事件",
+            );
+        }
+        filter.actor_user_id = Some(user_id.clone());
+    }
+
+    let total = match AuditEventRepo::count(&state.db, &filter).await {
+        Ok(total) => total,
+        Err(e) => {
+            warn!(correlation_id = %correlation_id, error = %e, "统计审计事件失败");
+            return database_error();
+        }
+    };
+    let events = match AuditEventRepo::list(&state.db, &filter, limit, offset).await {
+        Ok(records) => records.into_iter().map(to_info).collect::<Vec<_>>(),
+        Err(e) => {
+            warn!(correlation_id = %correlation_id, error = %e, "查询审计事件失败");
+            return database_error();
+        }
+    };
+
+    info!(
+        correlation_id = %correlation_id,
+        user_id = %user_id,
+        is_admin = is_admin,
+        total = total,
+        "查询审计事件"
+    );
+
+    let has_more = offset + events.len() < total as usize;
+    ApiResponse::ok(AuditEventListResponse {
+        events,
+        total,
+        has_more,
+    })
+}
+
+/// 创建审计事件路由（需鉴权）
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(list_audit_events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query() -> AuditEventQuery {
+        AuditEventQuery {
+            event_types: None,
+            outcome: None,
+            actor_user_id: None,
+            ip_address: None,
+            correlation_id: None,
+            time_start: None,
+            time_end: None,
+            limit: None,
+            offset: None,
+        }
+    }
+
+    #[test]
+    fn test_build_filter_parses_and_validates() {
+        let filter = build_filter(&AuditEventQuery {
+            event_types: Some("login_failed, login_locked_out,".to_string()),
+            outcome: Some("failure".to_string()),
+            ip_address: Some(" ".to_string()),
+            ..query()
+        })
+        .unwrap();
+        assert_eq!(
+            filter.event_types,
+            Some(vec![
+                AuditEventType::LoginFailed,
+                AuditEventType::LoginLockedOut
+            ])
+        );
+        assert_eq!(filter.outcome, Some(AuditOutcome::Failure));
+        assert!(filter.ip_address.is_none());
+
+        assert!(
+            build_filter(&AuditEventQuery {
+                event_types: Some("password_dumped".to_string()),
+                ..query()
+            })
+            .is_err()
+        );
+        assert!(
+            build_filter(&AuditEventQuery {
+                time_start: Some(2),
+                time_end: Some(1),
+                ..query()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_normalize_limit_offset_bounds() {
+        assert_eq!(normalize_limit_offset(&query()).unwrap(), (50, 0));
+        assert!(
+            normalize_limit_offset(&AuditEventQuery {
+                limit: Some(0),
+                ..query()
+            })
+            .is_err()
+        );
+        assert!(
+            normalize_limit_offset(&AuditEventQuery {
+                offset: Some(10_001),
+                ..query()
+            })
+            .is_err()
+        );
+    }
+}
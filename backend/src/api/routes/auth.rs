@@ -1,33 +1,37 @@
 //! 认证相关路由
 //! 包含 API 连接测试端点和凭证配置管理
 
-use axum::extract::State;
+use axum::extract::{Path, Query, State};
 use axum::http::{HeaderMap, StatusCode};
 use axum::{
     Json, Router, middleware,
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use serde::{Deserialize, Serialize};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 use ts_rs::TS;
-use utoipa::ToSchema;
-use zeroize::Zeroizing;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
-use crate::api::middleware::{CurrentUser, connectivity_middleware};
+use crate::api::middleware::{
+    AuditContext, CurrentUser, UpstreamQueryRateLimit, connectivity_middleware,
+};
 use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
 use crate::api::state::AppState;
+use crate::domain::models::{LlmProviderRegistry, LlmProviderSpec};
 use crate::infra::db::repositories::{
-    CredentialRepo, CredentialType, TeacherSettingsRepo, UpsertCredentialInput,
+    AuditEventType, CREDENTIAL_NAME_MAX_CHARS, CreateCredentialInput, CredentialRecord,
+    CredentialRepo, CredentialRepoError, CredentialType, LlmProviderRepo, LlmProviderRepoError,
+    NewAuditEvent, TeacherSettingsRepo, UpdateCredentialInput, UpsertCredentialInput,
     UpsertTeacherSettingsInput,
 };
-use crate::infra::external::api_key_manager::EncryptedApiKey;
+use crate::infra::external::api_key_manager::{CredentialKeys, EncryptedApiKey};
 use crate::infra::external::dify_client::{self, ConnectionError, TestConnectionResult};
 use crate::infra::external::llm_client::{self, LlmConnectionError};
 use crate::shared::error_codes;
 use crate::shared::log_sanitizer::sanitize_api_key;
 use crate::shared::url_validator::{
-    BaseUrlValidationOptions, validate_api_key, validate_base_url_with_options,
+    BaseUrlValidationOptions, resolve_and_validate_base_url, validate_api_key,
 };
 
 /// Dify 连接测试请求
@@ -44,7 +48,12 @@ pub struct TestDifyConnectionRequest {
 pub struct TestGenericLlmConnectionRequest {
     pub base_url: String,
     pub api_key: String,
-    pub provider: String, // "siliconflow" | "modelscope"
+    /// Provider 标识：内置 Provider ID，或用户自定义 Provider ID（需同时提供 provider_spec）
+    pub provider: String,
+    /// 用户自定义 Provider 描述（仅当 provider 不是内置 ID 时使用）
+    #[serde(default)]
+    #[ts(optional)]
+    pub provider_spec: Option<LlmProviderSpec>,
 }
 
 /// 连接测试请求类型（用于前端类型别名）
@@ -132,7 +141,7 @@ pub(crate) async fn test_dify_connection(
         allow_private_network: state.config.allow_private_network_base_url,
     };
 
-    if let Err(e) = validate_base_url_with_options(&base_url, base_url_opts) {
+    if let Err(e) = resolve_and_validate_base_url(&base_url, base_url_opts).await {
         warn!(error = %e, "URL 验证失败");
         return ApiResponse::err(
             StatusCode::BAD_REQUEST,
@@ -211,7 +220,7 @@ pub(crate) async fn test_generic_llm_connection(
         allow_private_network: state.config.allow_private_network_base_url,
     };
 
-    if let Err(e) = validate_base_url_with_options(&base_url, base_url_opts) {
+    if let Err(e) = resolve_and_validate_base_url(&base_url, base_url_opts).await {
         warn!(error = %e, "URL 验证失败");
         return ApiResponse::err(
             StatusCode::BAD_REQUEST,
@@ -230,11 +239,23 @@ pub(crate) async fn test_generic_llm_connection(
         );
     }
 
-    match llm_client::test_connection(
+    let spec = match resolve_test_connection_provider(&req) {
+        Ok(spec) => spec,
+        Err(e) => {
+            warn!(error = %e, "Provider 验证失败");
+            return ApiResponse::err(
+                StatusCode::BAD_REQUEST,
+                error_codes::AUTH_VALIDATION_ERROR,
+                e,
+            );
+        }
+    };
+
+    match llm_client::test_connection_with_provider(
         &state.http_client,
         &base_url,
         &req.api_key,
-        &req.provider,
+        &spec,
         &correlation_id,
     )
     .await
@@ -250,6 +271,26 @@ pub(crate) async fn test_generic_llm_connection(
     }
 }
 
+/// 解析连接测试使用的 Provider（内置优先；自定义 Provider 由请求携带描述并校验）
+fn resolve_test_connection_provider(
+    req: &TestGenericLlmConnectionRequest,
+) -> Result<LlmProviderSpec, String> {
+    let provider = req.provider.trim();
+    if let Some(spec) = LlmProviderRegistry::builtin().get(provider) {
+        return Ok(spec.clone());
+    }
+    match &req.provider_spec {
+        Some(spec) if spec.id == provider => {
+            spec.validate()?;
+            let mut spec = spec.clone();
+            spec.builtin = false;
+            Ok(spec)
+        }
+        Some(_) => Err("provider_spec.id 与 provider 不一致".to_string()),
+        None => Err(format!("不支持的 Provider: {provider}")),
+    }
+}
+
 // ============================================================================
 // 连接错误映射（统一 trait 处理，消除代码重复）
 // ============================================================================
@@ -578,6 +619,7 @@ fn validate_teacher_settings(settings: &TeacherSettingsInput) -> Result<(), Stri
 pub(crate) async fn save_config(
     State(state): State<AppState>,
     headers: HeaderMap,
+    audit: AuditContext,
     current_user: CurrentUser,
     Json(req): Json<SaveConfigRequest>,
 ) -> ApiResponse<SaveConfigResponse> {
@@ -585,15 +627,9 @@ pub(crate) async fn save_config(
     let user_id = &current_user.user_id;
     info!(correlation_id = %correlation_id, user_id = %user_id, "保存配置");
 
-    let user_password = match current_user.unlock_context.as_ref() {
-        Some(ctx) => ctx.password_bytes(),
-        None => {
-            return ApiResponse::err(
-                StatusCode::UNAUTHORIZED,
-                error_codes::UNAUTHORIZED,
-                "会话已过期，请重新登录",
-            );
-        }
+    let credential_keys = match resolve_credential_keys(&state, &current_user).await {
+        Ok(keys) => keys,
+        Err(resp) => return resp,
     };
 
     // 强制校验：必须同时包含 Dify 和 Generic LLM 凭证 (Story 1.5 Task 3.3)
@@ -630,7 +666,7 @@ pub(crate) async fn save_config(
     if let Some(dify) = &req.dify {
         let base_url = normalize_base_url_for_docker(&dify.base_url, state.config.is_docker);
         // 验证 URL
-        if let Err(e) = validate_base_url_with_options(&base_url, base_url_opts) {
+        if let Err(e) = resolve_and_validate_base_url(&base_url, base_url_opts).await {
             warn!(error = %e, "Dify URL 验证失败");
             return ApiResponse::err(
                 StatusCode::BAD_REQUEST,
@@ -649,7 +685,10 @@ pub(crate) async fn save_config(
         }
 
         // 加密 API Key
-        let encrypted = match state.api_key_manager.encrypt(user_password, &dify.api_key) {
+        let encrypted = match state
+            .api_key_manager
+            .encrypt_credential(&credential_keys, &dify.api_key)
+        {
             Ok(e) => e,
             Err(e) => {
                 warn!(error = %e, "API Key 加密失败");
@@ -662,7 +701,7 @@ pub(crate) async fn save_config(
         };
 
         // 保存到数据库
-        if let Err(e) = CredentialRepo::upsert(
+        let record = match CredentialRepo::upsert(
             &state.db,
             UpsertCredentialInput {
                 user_id: user_id.clone(),
@@ -676,32 +715,47 @@ pub(crate) async fn save_config(
         )
         .await
         {
-            warn!(error = %e, "保存 Dify 凭证失败");
-            return ApiResponse::err(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                error_codes::DATABASE_ERROR,
-                "保存 Dify 凭证失败",
-            );
-        }
+            Ok(record) => record,
+            Err(e) => {
+                warn!(error = %e, "保存 Dify 凭证失败");
+                return ApiResponse::err(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    error_codes::DATABASE_ERROR,
+                    "保存 Dify 凭证失败",
+                );
+            }
+        };
         info!("Dify 凭证保存成功");
+        audit_credential_upsert(&state, &audit, &record).await;
     }
 
     // 保存通用大模型凭证
     if let Some(generic_llm) = &req.generic_llm {
         let base_url = normalize_base_url_for_docker(&generic_llm.base_url, state.config.is_docker);
-        // 验证 provider
-        if generic_llm.provider != "siliconflow" && generic_llm.provider != "modelscope" {
-            return ApiResponse::err(
-                StatusCode::BAD_REQUEST,
-                error_codes::VALIDATION_ERROR,
-                format!(
-                    "无效的 provider: {}，支持 siliconflow 或 modelscope",
-                    generic_llm.provider
-                ),
-            );
+        // 验证 provider：内置 Provider 或当前用户的自定义 Provider
+        match LlmProviderRepo::resolve(&state.db, user_id, Some(&generic_llm.provider)).await {
+            Ok(_) if !generic_llm.provider.trim().is_empty() => {}
+            Ok(_) | Err(LlmProviderRepoError::NotFound(_)) => {
+                return ApiResponse::err(
+                    StatusCode::BAD_REQUEST,
+                    error_codes::VALIDATION_ERROR,
+                    format!(
+                        "无效的 provider: {}，请选择内置 Provider 或先创建自定义 Provider",
+                        generic_llm.provider
+                    ),
+                );
+            }
+            Err(e) => {
+                warn!(error = %e, "查询 Provider 失败");
+                return ApiResponse::err(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    error_codes::DATABASE_ERROR,
+                    "查询 Provider 失败",
+                );
+            }
         }
         // 验证 URL
-        if let Err(e) = validate_base_url_with_options(&base_url, base_url_opts) {
+        if let Err(e) = resolve_and_validate_base_url(&base_url, base_url_opts).await {
             warn!(error = %e, "通用大模型 URL 验证失败");
             return ApiResponse::err(
                 StatusCode::BAD_REQUEST,
@@ -722,7 +776,7 @@ pub(crate) async fn save_config(
         // 加密 API Key
         let encrypted = match state
             .api_key_manager
-            .encrypt(user_password, &generic_llm.api_key)
+            .encrypt_credential(&credential_keys, &generic_llm.api_key)
         {
             Ok(e) => e,
             Err(e) => {
@@ -736,7 +790,7 @@ pub(crate) async fn save_config(
         };
 
         // 保存到数据库
-        if let Err(e) = CredentialRepo::upsert(
+        let record = match CredentialRepo::upsert(
             &state.db,
             UpsertCredentialInput {
                 user_id: user_id.clone(),
@@ -750,14 +804,18 @@ pub(crate) async fn save_config(
         )
         .await
         {
-            warn!(error = %e, "保存通用大模型凭证失败");
-            return ApiResponse::err(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                error_codes::DATABASE_ERROR,
-                "保存通用大模型凭证失败",
-            );
-        }
+            Ok(record) => record,
+            Err(e) => {
+                warn!(error = %e, "保存通用大模型凭证失败");
+                return ApiResponse::err(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    error_codes::DATABASE_ERROR,
+                    "保存通用大模型凭证失败",
+                );
+            }
+        };
         info!("通用大模型凭证保存成功");
+        audit_credential_upsert(&state, &audit, &record).await;
     }
 
     // 保存老师模型参数
@@ -811,15 +869,9 @@ pub(crate) async fn get_config(
     let user_id = &current_user.user_id;
     info!(correlation_id = %correlation_id, user_id = %user_id, "获取配置");
 
-    let user_password = match current_user.unlock_context.as_ref() {
-        Some(ctx) => ctx.password_bytes(),
-        None => {
-            return ApiResponse::err(
-                StatusCode::UNAUTHORIZED,
-                error_codes::UNAUTHORIZED,
-                "会话已过期，请重新登录",
-            );
-        }
+    let credential_keys = match resolve_credential_keys(&state, &current_user).await {
+        Ok(keys) => keys,
+        Err(resp) => return resp,
     };
 
     // 查询 Dify 凭证
@@ -852,7 +904,7 @@ pub(crate) async fn get_config(
         Some(cred) => {
             let masked = decrypt_and_mask(
                 &state,
-                user_password,
+                &credential_keys,
                 &cred.encrypted_api_key,
                 &cred.nonce,
                 &cred.salt,
@@ -868,7 +920,7 @@ pub(crate) async fn get_config(
             Some(cred) => {
                 let masked = decrypt_and_mask(
                     &state,
-                    user_password,
+                    &credential_keys,
                     &cred.encrypted_api_key,
                     &cred.nonce,
                     &cred.salt,
@@ -907,6 +959,7 @@ pub(crate) async fn get_config(
         (status = 200, description = "查询成功", body = ApiSuccess<GenericLlmModelsResponse>),
         (status = 401, description = "未授权", body = ApiError),
         (status = 403, description = "访问被拒绝", body = ApiError),
+        (status = 429, description = "请求过于频繁（响应头 Retry-After 给出重试秒数）", body = ApiError),
         (status = 502, description = "上游错误", body = ApiError),
         (status = 500, description = "服务器错误", body = ApiError)
     ),
@@ -916,20 +969,15 @@ pub(crate) async fn list_generic_llm_models(
     State(state): State<AppState>,
     headers: HeaderMap,
     current_user: CurrentUser,
+    _rate_limit: UpstreamQueryRateLimit,
 ) -> ApiResponse<GenericLlmModelsResponse> {
     let correlation_id = extract_correlation_id(&headers);
     let user_id = &current_user.user_id;
     info!(correlation_id = %correlation_id, user_id = %user_id, "获取通用大模型可用模型列表");
 
-    let user_password = match current_user.unlock_context.as_ref() {
-        Some(ctx) => ctx.password_bytes(),
-        None => {
-            return ApiResponse::err(
-                StatusCode::UNAUTHORIZED,
-                error_codes::UNAUTHORIZED,
-                "会话已过期，请重新登录",
-            );
-        }
+    let credential_keys = match resolve_credential_keys(&state, &current_user).await {
+        Ok(keys) => keys,
+        Err(resp) => return resp,
     };
 
     let cred =
@@ -960,6 +1008,24 @@ pub(crate) async fn list_generic_llm_models(
             );
         }
     };
+    let spec = match LlmProviderRepo::resolve(&state.db, user_id, Some(provider)).await {
+        Ok(spec) => spec,
+        Err(LlmProviderRepoError::NotFound(_)) => {
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::AUTH_INTERNAL_ERROR,
+                "通用大模型凭证引用的 Provider 已不存在，请重新保存",
+            );
+        }
+        Err(e) => {
+            warn!(error = %e, "查询 Provider 失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "查询 Provider 失败",
+            );
+        }
+    };
 
     let base_url = normalize_base_url_for_docker(&cred.base_url, state.config.is_docker);
     let base_url_opts = BaseUrlValidationOptions {
@@ -967,7 +1033,7 @@ pub(crate) async fn list_generic_llm_models(
         allow_localhost: state.config.allow_localhost_base_url,
         allow_private_network: state.config.allow_private_network_base_url,
     };
-    if let Err(e) = validate_base_url_with_options(&base_url, base_url_opts) {
+    if let Err(e) = resolve_and_validate_base_url(&base_url, base_url_opts).await {
         warn!(error = %e, "通用大模型 Base URL 验证失败");
         return ApiResponse::err(
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -984,9 +1050,9 @@ pub(crate) async fn list_generic_llm_models(
 
     let api_key_bytes = match state
         .api_key_manager
-        .decrypt_bytes(user_password, &encrypted)
+        .decrypt_credential(&credential_keys, &encrypted)
     {
-        Ok(v) => Zeroizing::new(v),
+        Ok(v) => v,
         Err(e) => {
             warn!(error = %e, "解密通用大模型 API Key 失败");
             return ApiResponse::err(
@@ -1008,11 +1074,11 @@ pub(crate) async fn list_generic_llm_models(
         }
     };
 
-    match llm_client::list_models(
+    match llm_client::list_models_with_provider(
         &state.http_client,
         &base_url,
         api_key,
-        provider,
+        &spec,
         &correlation_id,
     )
     .await
@@ -1025,6 +1091,743 @@ pub(crate) async fn list_generic_llm_models(
     }
 }
 
+// ============================================================================
+// 大模型 Provider 注册表 API
+// ============================================================================
+
+/// Provider 列表响应（内置 + 当前用户自定义）
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct LlmProvidersResponse {
+    pub providers: Vec<LlmProviderSpec>,
+}
+
+/// 删除 Provider 响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct DeleteLlmProviderResponse {
+    pub message: String,
+}
+
+fn map_llm_provider_repo_error<T: Serialize>(error: LlmProviderRepoError) -> ApiResponse<T> {
+    warn!(error = %error, "Provider 仓储操作失败");
+    match error {
+        LlmProviderRepoError::NotFound(_) => ApiResponse::err(
+            StatusCode::NOT_FOUND,
+            error_codes::RESOURCE_NOT_FOUND,
+            "Provider 不存在",
+        ),
+        _ => ApiResponse::err(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            error_codes::DATABASE_ERROR,
+            "Provider 数据访问失败",
+        ),
+    }
+}
+
+/// 获取大模型 Provider 列表
+///
+/// GET /api/v1/auth/llm-providers
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/llm-providers",
+    responses(
+        (status = 200, description = "查询成功", body = ApiSuccess<LlmProvidersResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "auth"
+)]
+pub(crate) async fn list_llm_providers(
+    State(state): State<AppState>,
+    current_user: CurrentUser,
+) -> ApiResponse<LlmProvidersResponse> {
+    match LlmProviderRepo::registry_for_user(&state.db, &current_user.user_id).await {
+        Ok(registry) => ApiResponse::ok(LlmProvidersResponse {
+            providers: registry.list().to_vec(),
+        }),
+        Err(e) => map_llm_provider_repo_error(e),
+    }
+}
+
+/// 创建或更新用户自定义 Provider
+///
+/// PUT /api/v1/auth/llm-providers/{provider_id}
+///
+/// 路径中的 provider_id 为准（覆盖请求体中的 id）；不允许覆盖内置 Provider。
+#[utoipa::path(
+    put,
+    path = "/api/v1/auth/llm-providers/{provider_id}",
+    params(("provider_id" = String, Path, description = "自定义 Provider ID")),
+    request_body = LlmProviderSpec,
+    responses(
+        (status = 200, description = "保存成功", body = ApiSuccess<LlmProviderSpec>),
+        (status = 400, description = "参数错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "auth"
+)]
+pub(crate) async fn upsert_llm_provider(
+    State(state): State<AppState>,
+    current_user: CurrentUser,
+    Path(provider_id): Path<String>,
+    Json(mut spec): Json<LlmProviderSpec>,
+) -> ApiResponse<LlmProviderSpec> {
+    spec.id = provider_id.trim().to_string();
+    spec.display_name = spec.display_name.trim().to_string();
+    if LlmProviderRegistry::is_builtin_id(&spec.id) {
+        return ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            format!("不能覆盖内置 Provider: {}", spec.id),
+        );
+    }
+    if let Err(e) = spec.validate() {
+        return ApiResponse::err(StatusCode::BAD_REQUEST, error_codes::VALIDATION_ERROR, e);
+    }
+
+    match LlmProviderRepo::upsert(&state.db, &current_user.user_id, &spec).await {
+        Ok(saved) => {
+            info!(user_id = %current_user.user_id, provider_id = %saved.id, "自定义 Provider 已保存");
+            ApiResponse::ok(saved)
+        }
+        Err(e) => map_llm_provider_repo_error(e),
+    }
+}
+
+/// 删除用户自定义 Provider
+///
+/// DELETE /api/v1/auth/llm-providers/{provider_id}
+///
+/// 仍有通用大模型凭证引用该 Provider 时拒绝删除。
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/llm-providers/{provider_id}",
+    params(("provider_id" = String, Path, description = "自定义 Provider ID")),
+    responses(
+        (status = 200, description = "删除成功", body = ApiSuccess<DeleteLlmProviderResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 404, description = "Provider 不存在", body = ApiError),
+        (status = 409, description = "Provider 仍被凭证引用", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "auth"
+)]
+pub(crate) async fn delete_llm_provider(
+    State(state): State<AppState>,
+    current_user: CurrentUser,
+    Path(provider_id): Path<String>,
+) -> ApiResponse<DeleteLlmProviderResponse> {
+    let user_id = &current_user.user_id;
+    match CredentialRepo::find_all_by_user(&state.db, user_id).await {
+        Ok(creds)
+            if creds.iter().any(|c| {
+                c.credential_type == CredentialType::GenericLlm.as_str()
+                    && c.provider.as_deref() == Some(provider_id.as_str())
+            }) =>
+        {
+            return ApiResponse::err(
+                StatusCode::CONFLICT,
+                error_codes::VALIDATION_ERROR,
+                "该 Provider 仍被通用大模型凭证引用，请先切换凭证的 Provider",
+            );
+        }
+        Ok(_) => {}
+        Err(e) => {
+            warn!(error = %e, "查询通用大模型凭证失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "查询通用大模型凭证失败",
+            );
+        }
+    }
+
+    match LlmProviderRepo::delete(&state.db, user_id, &provider_id).await {
+        Ok(true) => ApiResponse::ok(DeleteLlmProviderResponse {
+            message: "Provider 已删除".to_string(),
+        }),
+        Ok(false) => map_llm_provider_repo_error(LlmProviderRepoError::NotFound(provider_id)),
+        Err(e) => map_llm_provider_repo_error(e),
+    }
+}
+
+// ============================================================================
+// 命名凭证管理 API
+// ============================================================================
+
+/// 凭证列表查询参数
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListCredentialsQuery {
+    /// 凭证类型过滤（dify / generic_llm；可选）
+    pub credential_type: Option<String>,
+}
+
+/// 命名凭证概要（API Key 仅返回脱敏值）
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct CredentialProfileResponse {
+    pub id: String,
+    pub credential_type: String,
+    pub name: String,
+    pub provider: Option<String>,
+    pub base_url: String,
+    pub is_default: bool,
+    pub masked_api_key: Option<String>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
+
+/// 凭证列表响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct CredentialListResponse {
+    pub credentials: Vec<CredentialProfileResponse>,
+}
+
+/// 新建命名凭证请求
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct CreateCredentialRequest {
+    /// 凭证类型：dify / generic_llm
+    pub credential_type: String,
+    pub name: String,
+    /// Provider（仅 generic_llm 需要）
+    #[serde(default)]
+    #[ts(optional)]
+    pub provider: Option<String>,
+    pub base_url: String,
+    pub api_key: String,
+    /// 是否设为默认凭证（该类型尚无凭证时总是设为默认）
+    #[serde(default)]
+    #[ts(optional)]
+    pub is_default: Option<bool>,
+}
+
+/// 更新命名凭证请求（api_key 缺省时保留原值）
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct UpdateCredentialRequest {
+    pub name: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub provider: Option<String>,
+    pub base_url: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub api_key: Option<String>,
+}
+
+/// 删除凭证响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct DeleteCredentialResponse {
+    pub message: String,
+}
+
+fn map_credential_repo_error<T: Serialize>(error: CredentialRepoError) -> ApiResponse<T> {
+    match error {
+        CredentialRepoError::ProfileNotFound(_) | CredentialRepoError::NotFound { .. } => {
+            ApiResponse::err(
+                StatusCode::NOT_FOUND,
+                error_codes::RESOURCE_NOT_FOUND,
+                "凭证不存在",
+            )
+        }
+        CredentialRepoError::NameConflict(name) => ApiResponse::err(
+            StatusCode::CONFLICT,
+            error_codes::VALIDATION_ERROR,
+            format!("同类型凭证名称已存在: {name}"),
+        ),
+        CredentialRepoError::TypeMismatch { .. } => ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            "凭证类型不匹配",
+        ),
+        CredentialRepoError::NotAccessible(_) => ApiResponse::err(
+            StatusCode::FORBIDDEN,
+            error_codes::CREDENTIAL_NOT_ACCESSIBLE,
+            "凭证属于工作区其他成员，请绑定自己的凭证",
+        ),
+        CredentialRepoError::DatabaseError(e) => {
+            warn!(error = %e, "凭证仓储操作失败");
+            ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "凭证数据访问失败",
+            )
+        }
+    }
+}
+
+/// 解析当前请求的凭证解锁材料
+///
+/// 会话持有解锁上下文时直接使用（数据密钥 + 登录密码）；否则尝试以服务端 KEK 解锁数据密钥，
+/// 使无登录密码的会话与后台任务仍可访问凭证。
+pub(crate) async fn resolve_credential_keys<T: Serialize>(
+    state: &AppState,
+    current_user: &CurrentUser,
+) -> Result<CredentialKeys, ApiResponse<T>> {
+    if let Some(ctx) = current_user.unlock_context.as_ref() {
+        return Ok(ctx.credential_keys());
+    }
+    match state
+        .api_key_manager
+        .unlock_data_key_unattended(&state.db, &current_user.user_id)
+        .await
+    {
+        Ok(data_key) => Ok(CredentialKeys::from_data_key(data_key)),
+        Err(e) => {
+            debug!(error = %e, user_id = %current_user.user_id, "无法使用服务端密钥解锁数据密钥");
+            Err(ApiResponse::err(
+                StatusCode::UNAUTHORIZED,
+                error_codes::UNAUTHORIZED,
+                "会话已过期，请重新登录",
+            ))
+        }
+    }
+}
+
+fn to_credential_profile(
+    state: &AppState,
+    credential_keys: &CredentialKeys,
+    record: CredentialRecord,
+) -> CredentialProfileResponse {
+    let masked_api_key = decrypt_and_mask(
+        state,
+        credential_keys,
+        &record.encrypted_api_key,
+        &record.nonce,
+        &record.salt,
+    );
+    CredentialProfileResponse {
+        id: record.id,
+        credential_type: record.credential_type,
+        name: record.name,
+        provider: record.provider,
+        base_url: record.base_url,
+        is_default: record.is_default,
+        masked_api_key,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }
+}
+
+/// 校验并规范化命名凭证的公共字段，返回 (name, provider, base_url)
+async fn validate_credential_fields<T: Serialize>(
+    state: &AppState,
+    user_id: &str,
+    credential_type: &CredentialType,
+    name: &str,
+    provider: Option<&str>,
+    base_url: &str,
+) -> Result<(String, Option<String>, String), ApiResponse<T>> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > CREDENTIAL_NAME_MAX_CHARS {
+        return Err(ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            format!("凭证名称长度需为 1-{CREDENTIAL_NAME_MAX_CHARS} 个字符"),
+        ));
+    }
+
+    let provider = match credential_type {
+        CredentialType::Dify => None,
+        CredentialType::GenericLlm => {
+            let provider = provider.map(str::trim).unwrap_or_default();
+            match LlmProviderRepo::resolve(&state.db, user_id, Some(provider)).await {
+                Ok(_) if !provider.is_empty() => Some(provider.to_string()),
+                Ok(_) | Err(LlmProviderRepoError::NotFound(_)) => {
+                    return Err(ApiResponse::err(
+                        StatusCode::BAD_REQUEST,
+                        error_codes::VALIDATION_ERROR,
+                        format!(
+                            "无效的 provider: {provider}，请选择内置 Provider 或先创建自定义 Provider"
+                        ),
+                    ));
+                }
+                Err(e) => return Err(map_llm_provider_repo_error(e)),
+            }
+        }
+    };
+
+    let base_url = normalize_base_url_for_docker(base_url, state.config.is_docker);
+    if let Err(e) =
+        resolve_and_validate_base_url(&base_url, state.config.base_url_validation_options()).await
+    {
+        return Err(ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            format!("Base URL 无效: {e}"),
+        ));
+    }
+
+    Ok((name.to_string(), provider, base_url))
+}
+
+fn encrypt_credential_api_key<T: Serialize>(
+    state: &AppState,
+    credential_keys: &CredentialKeys,
+    api_key: &str,
+) -> Result<EncryptedApiKey, ApiResponse<T>> {
+    if let Err(e) = validate_api_key(api_key) {
+        return Err(ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            format!("API Key 无效: {e}"),
+        ));
+    }
+    state
+        .api_key_manager
+        .encrypt_credential(credential_keys, api_key)
+        .map_err(|e| {
+            warn!(error = %e, "API Key 加密失败");
+            ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::ENCRYPTION_ERROR,
+                "API Key 加密失败",
+            )
+        })
+}
+
+/// 列出当前用户的命名凭证
+///
+/// GET /api/v1/auth/credentials
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/credentials",
+    params(ListCredentialsQuery),
+    responses(
+        (status = 200, description = "查询成功", body = ApiSuccess<CredentialListResponse>),
+        (status = 400, description = "参数错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "auth"
+)]
+pub(crate) async fn list_credentials(
+    State(state): State<AppState>,
+    Query(query): Query<ListCredentialsQuery>,
+    current_user: CurrentUser,
+) -> ApiResponse<CredentialListResponse> {
+    let credential_keys = match resolve_credential_keys(&state, &current_user).await {
+        Ok(keys) => keys,
+        Err(resp) => return resp,
+    };
+    let type_filter = match query.credential_type.as_deref() {
+        Some(raw) => match CredentialType::parse(raw.trim()) {
+            Some(t) => Some(t),
+            None => {
+                return ApiResponse::err(
+                    StatusCode::BAD_REQUEST,
+                    error_codes::VALIDATION_ERROR,
+                    format!("未知的凭证类型: {raw}"),
+                );
+            }
+        },
+        None => None,
+    };
+
+    match CredentialRepo::find_all_by_user(&state.db, &current_user.user_id).await {
+        Ok(records) => ApiResponse::ok(CredentialListResponse {
+            credentials: records
+                .into_iter()
+                .filter(|r| {
+                    type_filter
+                        .as_ref()
+                        .is_none_or(|t| r.credential_type == t.as_str())
+                })
+                .map(|r| to_credential_profile(&state, &credential_keys, r))
+                .collect(),
+        }),
+        Err(e) => map_credential_repo_error(e),
+    }
+}
+
+/// 新建命名凭证
+///
+/// POST /api/v1/auth/credentials
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/credentials",
+    request_body = CreateCredentialRequest,
+    responses(
+        (status = 200, description = "创建成功", body = ApiSuccess<CredentialProfileResponse>),
+        (status = 400, description = "参数错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 409, description = "名称冲突", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "auth"
+)]
+pub(crate) async fn create_credential(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    current_user: CurrentUser,
+    Json(req): Json<CreateCredentialRequest>,
+) -> ApiResponse<CredentialProfileResponse> {
+    let user_id = &current_user.user_id;
+    let credential_keys = match resolve_credential_keys(&state, &current_user).await {
+        Ok(keys) => keys,
+        Err(resp) => return resp,
+    };
+    let Some(credential_type) = CredentialType::parse(req.credential_type.trim()) else {
+        return ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            format!("未知的凭证类型: {}", req.credential_type),
+        );
+    };
+    let (name, provider, base_url) = match validate_credential_fields(
+        &state,
+        user_id,
+        &credential_type,
+        &req.name,
+        req.provider.as_deref(),
+        &req.base_url,
+    )
+    .await
+    {
+        Ok(v) => v,
+        Err(resp) => return resp,
+    };
+    let encrypted = match encrypt_credential_api_key(&state, &credential_keys, &req.api_key) {
+        Ok(e) => e,
+        Err(resp) => return resp,
+    };
+
+    match CredentialRepo::create(
+        &state.db,
+        CreateCredentialInput {
+            user_id: user_id.clone(),
+            credential_type,
+            name,
+            provider,
+            base_url,
+            encrypted_api_key: encrypted.ciphertext,
+            nonce: encrypted.nonce,
+            salt: encrypted.salt,
+            make_default: req.is_default.unwrap_or(false),
+        },
+    )
+    .await
+    {
+        Ok(record) => {
+            info!(user_id = %user_id, credential_id = %record.id, "命名凭证已创建");
+            audit_credential_change(&state, &audit, AuditEventType::CredentialCreated, &record)
+                .await;
+            ApiResponse::ok(to_credential_profile(&state, &credential_keys, record))
+        }
+        Err(e) => map_credential_repo_error(e),
+    }
+}
+
+/// 更新命名凭证
+///
+/// PUT /api/v1/auth/credentials/{credential_id}
+#[utoipa::path(
+    put,
+    path = "/api/v1/auth/credentials/{credential_id}",
+    params(("credential_id" = String, Path, description = "凭证 ID")),
+    request_body = UpdateCredentialRequest,
+    responses(
+        (status = 200, description = "更新成功", body = ApiSuccess<CredentialProfileResponse>),
+        (status = 400, description = "参数错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 404, description = "凭证不存在", body = ApiError),
+        (status = 409, description = "名称冲突", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "auth"
+)]
+pub(crate) async fn update_credential(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    current_user: CurrentUser,
+    Path(credential_id): Path<String>,
+    Json(req): Json<UpdateCredentialRequest>,
+) -> ApiResponse<CredentialProfileResponse> {
+    let user_id = &current_user.user_id;
+    let credential_keys = match resolve_credential_keys(&state, &current_user).await {
+        Ok(keys) => keys,
+        Err(resp) => return resp,
+    };
+    let existing = match CredentialRepo::find_by_id(&state.db, user_id, &credential_id).await {
+        Ok(r) => r,
+        Err(e) => return map_credential_repo_error(e),
+    };
+    let Some(credential_type) = CredentialType::parse(&existing.credential_type) else {
+        return ApiResponse::err(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            error_codes::INTERNAL_ERROR,
+            "凭证类型数据异常",
+        );
+    };
+    let (name, provider, base_url) = match validate_credential_fields(
+        &state,
+        user_id,
+        &credential_type,
+        &req.name,
+        req.provider.as_deref(),
+        &req.base_url,
+    )
+    .await
+    {
+        Ok(v) => v,
+        Err(resp) => return resp,
+    };
+    let api_key_rotated = req.api_key.is_some();
+    let encrypted_api_key = match req.api_key.as_deref() {
+        Some(api_key) => match encrypt_credential_api_key(&state, &credential_keys, api_key) {
+            Ok(e) => Some((e.ciphertext, e.nonce, e.salt)),
+            Err(resp) => return resp,
+        },
+        None => None,
+    };
+
+    match CredentialRepo::update(
+        &state.db,
+        user_id,
+        &credential_id,
+        UpdateCredentialInput {
+            name,
+            provider,
+            base_url,
+            encrypted_api_key,
+        },
+    )
+    .await
+    {
+        Ok(record) => {
+            audit
+                .record(
+                    &state.db,
+                    credential_audit_event(AuditEventType::CredentialUpdated, &record).details(
+                        serde_json::json!({
+                            "credential_type": record.credential_type,
+                            "name": record.name,
+                            "provider": record.provider,
+                            "key_rotated": api_key_rotated,
+                        }),
+                    ),
+                )
+                .await;
+            ApiResponse::ok(to_credential_profile(&state, &credential_keys, record))
+        }
+        Err(e) => map_credential_repo_error(e),
+    }
+}
+
+/// 删除命名凭证（删除默认凭证时自动提升同类型最近更新的凭证为默认）
+///
+/// DELETE /api/v1/auth/credentials/{credential_id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/credentials/{credential_id}",
+    params(("credential_id" = String, Path, description = "凭证 ID")),
+    responses(
+        (status = 200, description = "删除成功", body = ApiSuccess<DeleteCredentialResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 404, description = "凭证不存在", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "auth"
+)]
+pub(crate) async fn delete_credential(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    current_user: CurrentUser,
+    Path(credential_id): Path<String>,
+) -> ApiResponse<DeleteCredentialResponse> {
+    match CredentialRepo::delete_by_id(&state.db, &current_user.user_id, &credential_id).await {
+        Ok(()) => {
+            info!(user_id = %current_user.user_id, credential_id = %credential_id, "命名凭证已删除");
+            audit
+                .record(
+                    &state.db,
+                    NewAuditEvent::new(AuditEventType::CredentialDeleted)
+                        .actor(&current_user.user_id)
+                        .target("credential", &credential_id),
+                )
+                .await;
+            ApiResponse::ok(DeleteCredentialResponse {
+                message: "凭证已删除".to_string(),
+            })
+        }
+        Err(e) => map_credential_repo_error(e),
+    }
+}
+
+/// 将命名凭证设为其类型的默认凭证
+///
+/// POST /api/v1/auth/credentials/{credential_id}/default
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/credentials/{credential_id}/default",
+    params(("credential_id" = String, Path, description = "凭证 ID")),
+    responses(
+        (status = 200, description = "设置成功", body = ApiSuccess<CredentialProfileResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 404, description = "凭证不存在", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "auth"
+)]
+pub(crate) async fn set_default_credential(
+    State(state): State<AppState>,
+    current_user: CurrentUser,
+    Path(credential_id): Path<String>,
+) -> ApiResponse<CredentialProfileResponse> {
+    let credential_keys = match resolve_credential_keys(&state, &current_user).await {
+        Ok(keys) => keys,
+        Err(resp) => return resp,
+    };
+    match CredentialRepo::set_default(&state.db, &current_user.user_id, &credential_id).await {
+        Ok(record) => ApiResponse::ok(to_credential_profile(&state, &credential_keys, record)),
+        Err(e) => map_credential_repo_error(e),
+    }
+}
+
+/// 凭证审计事件（仅含元数据，不含 API Key 明文或密文）
+fn credential_audit_event(event_type: AuditEventType, record: &CredentialRecord) -> NewAuditEvent {
+    NewAuditEvent::new(event_type)
+        .actor(&record.user_id)
+        .target("credential", &record.id)
+        .details(serde_json::json!({
+            "credential_type": record.credential_type,
+            "name": record.name,
+            "provider": record.provider,
+        }))
+}
+
+async fn audit_credential_change(
+    state: &AppState,
+    audit: &AuditContext,
+    event_type: AuditEventType,
+    record: &CredentialRecord,
+) {
+    audit
+        .record(&state.db, credential_audit_event(event_type, record))
+        .await;
+}
+
+/// 保存配置时写入的默认凭证：新建与原地更新分别记录
+async fn audit_credential_upsert(
+    state: &AppState,
+    audit: &AuditContext,
+    record: &CredentialRecord,
+) {
+    let event_type = if record.created_at == record.updated_at {
+        AuditEventType::CredentialCreated
+    } else {
+        AuditEventType::CredentialUpdated
+    };
+    audit_credential_change(state, audit, event_type, record).await;
+}
+
 /// 解密 API Key 并脱敏
 ///
 /// # 安全说明
@@ -1034,7 +1837,7 @@ pub(crate) async fn list_generic_llm_models(
 /// - 仅返回脱敏后的字符串（masked），避免在业务层传播完整明文
 fn decrypt_and_mask(
     state: &AppState,
-    user_password: &[u8],
+    credential_keys: &CredentialKeys,
     ciphertext: &[u8],
     nonce: &[u8],
     salt: &[u8],
@@ -1047,7 +1850,7 @@ fn decrypt_and_mask(
 
     match state
         .api_key_manager
-        .decrypt_bytes(user_password, &encrypted)
+        .decrypt_credential(credential_keys, &encrypted)
     {
         Ok(api_key_bytes) => std::str::from_utf8(api_key_bytes.as_slice())
             .ok()
@@ -1078,6 +1881,23 @@ pub fn protected_router() -> Router<AppState> {
             "/generic-llm/models",
             get(list_generic_llm_models).route_layer(middleware::from_fn(connectivity_middleware)),
         )
+        .route("/llm-providers", get(list_llm_providers))
+        .route(
+            "/llm-providers/{provider_id}",
+            put(upsert_llm_provider).delete(delete_llm_provider),
+        )
+        .route(
+            "/credentials",
+            get(list_credentials).post(create_credential),
+        )
+        .route(
+            "/credentials/{credential_id}",
+            put(update_credential).delete(delete_credential),
+        )
+        .route(
+            "/credentials/{credential_id}/default",
+            post(set_default_credential),
+        )
 }
 
 /// 创建认证路由（向后兼容，包含所有路由）
@@ -328,7 +328,7 @@ pub fn router() -> Router<AppState> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api::middleware::{LoginAttemptStore, SessionStore};
+    use crate::api::middleware::{LoginAttemptStore, RateLimiter, SessionStore};
     use crate::domain::models::{
         ExecutionTargetType, OptimizationTaskMode, TaskReference, TestCase,
     };
@@ -351,28 +351,19 @@ mod tests {
 
         let config = Arc::new(AppConfig {
             database_url: "sqlite::memory:".to_string(),
-            server_host: "127.0.0.1".to_string(),
-            server_port: 3000,
-            log_level: "info".to_string(),
-            is_dev: true,
-            cors_origins: Vec::new(),
-            is_docker: false,
-            allow_http_base_url: true,
-            allow_localhost_base_url: true,
-            allow_private_network_base_url: true,
-            checkpoint_cache_limit: 10,
-            checkpoint_memory_alert_threshold: 10,
+            ..AppConfig::default()
         });
 
         AppState {
-            db: pool,
+            db: pool.clone(),
             http_client: Client::new(),
             config,
             api_key_manager: Arc::new(crate::infra::external::api_key_manager::ApiKeyManager::new(
                 None,
             )),
-            session_store: SessionStore::new(24),
+            session_store: SessionStore::new(pool, 24),
             login_attempt_store: LoginAttemptStore::default(),
+            rate_limiter: RateLimiter::default(),
         }
     }
 
@@ -425,7 +416,7 @@ mod tests {
             goal: "goal",
             execution_target_type: ExecutionTargetType::Example,
             task_mode: OptimizationTaskMode::Fixed,
-            test_set_ids: &[test_set.id.clone()],
+            test_set_ids: std::slice::from_ref(&test_set.id),
             teacher_prompt_version_id: None,
         };
         let created = OptimizationTaskRepo::create_scoped(pool, input)
@@ -506,6 +497,7 @@ mod tests {
             CurrentUser {
                 user_id: "u1".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
         )
         .await;
@@ -533,6 +525,7 @@ mod tests {
             CurrentUser {
                 user_id: "u1".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
         )
         .await;
@@ -558,6 +551,7 @@ mod tests {
             CurrentUser {
                 user_id: "u2".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
         )
         .await;
@@ -591,6 +585,7 @@ mod tests {
             CurrentUser {
                 user_id: "u1".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
         )
         .await;
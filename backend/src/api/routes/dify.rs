@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use utoipa::ToSchema;
 
-use crate::infra::external::dify_client::DifyInputVariable;
+use crate::domain::types::{DifyAppType, DifyResponseMode};
+use crate::infra::external::dify_client::{DifyInputVariable, DifyOutputVariable};
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, TS)]
 #[ts(export_to = "api/")]
@@ -13,6 +14,20 @@ pub struct DifyConfig {
     pub target_prompt_variable: String,
     pub bindings: HashMap<String, DifyBinding>,
     pub parameters_snapshot: Option<Vec<DifyInputVariable>>,
+    /// 输出选择器：输出变量名或 JSON Pointer（`/` 开头）；None 表示按常见字段猜测
+    #[serde(default)]
+    pub output_selector: Option<String>,
+    /// 工作流声明的输出变量快照（刷新变量时更新）
+    #[serde(default)]
+    pub outputs_snapshot: Option<Vec<DifyOutputVariable>>,
+    /// 应用类型（工作流 / 对话 / 文本生成）；旧配置缺省为工作流
+    #[serde(default)]
+    pub app_type: DifyAppType,
+    #[serde(default)]
+    pub response_mode: DifyResponseMode,
+    /// 使用的 Dify 凭证 ID；None 表示使用默认 Dify 凭证
+    #[serde(default)]
+    pub credential_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, TS)]
@@ -21,6 +36,14 @@ pub struct DifyConfig {
 pub struct SaveDifyConfigRequest {
     pub target_prompt_variable: String,
     pub bindings: HashMap<String, DifyBinding>,
+    #[serde(default)]
+    pub output_selector: Option<String>,
+    #[serde(default)]
+    pub app_type: DifyAppType,
+    #[serde(default)]
+    pub response_mode: DifyResponseMode,
+    #[serde(default)]
+    pub credential_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, TS)]
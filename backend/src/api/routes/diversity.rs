@@ -8,6 +8,7 @@ use tracing::{info, warn};
 use crate::api::middleware::CurrentUser;
 use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
 use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
+use crate::api::routes::workspace_members::ensure_task_writable;
 use crate::api::state::AppState;
 use crate::domain::models::{DiversityAnalysisResult, DiversityBaseline, OptimizationTaskMode};
 use crate::infra::db::repositories::{
@@ -128,7 +129,7 @@ where
         (status = 200, description = "查询成功", body = ApiSuccess<DiversityAnalysisResult>),
         (status = 400, description = "非创意任务", body = ApiError),
         (status = 401, description = "未授权", body = ApiError),
-        (status = 403, description = "无权访问", body = ApiError),
+        (status = 403, description = "无权访问或只读成员无权修改", body = ApiError),
         (status = 404, description = "分析结果不存在", body = ApiError),
         (status = 500, description = "服务器错误", body = ApiError)
     ),
@@ -238,7 +239,7 @@ pub async fn get_diversity_analysis(
         (status = 200, description = "记录成功", body = ApiSuccess<DiversityBaseline>),
         (status = 400, description = "非创意任务或缺少分析", body = ApiError),
         (status = 401, description = "未授权", body = ApiError),
-        (status = 403, description = "无权访问", body = ApiError),
+        (status = 403, description = "无权访问或只读成员无权修改", body = ApiError),
         (status = 404, description = "分析结果不存在", body = ApiError),
         (status = 500, description = "服务器错误", body = ApiError)
     ),
@@ -263,6 +264,12 @@ pub async fn record_diversity_baseline(
         "N/A",
     );
 
+    // 只读成员不能修改任务
+    if let Err(resp) = ensure_task_writable::<DiversityBaseline>(&state.db, &task_id, user_id).await
+    {
+        return resp;
+    }
+
     if let Err(resp) = ensure_creative_task(&state, user_id, &task_id, &correlation_id).await {
         return resp;
     }
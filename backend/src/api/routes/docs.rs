@@ -78,15 +78,43 @@ use utoipa_swagger_ui::SwaggerUi;
         crate::api::routes::auth::save_config,
         crate::api::routes::auth::get_config,
         crate::api::routes::auth::list_generic_llm_models,
+        crate::api::routes::auth::list_llm_providers,
+        crate::api::routes::auth::upsert_llm_provider,
+        crate::api::routes::auth::delete_llm_provider,
+        crate::api::routes::auth::list_credentials,
+        crate::api::routes::auth::create_credential,
+        crate::api::routes::auth::update_credential,
+        crate::api::routes::auth::delete_credential,
+        crate::api::routes::auth::set_default_credential,
         crate::api::routes::user_auth::get_system_status,
         crate::api::routes::user_auth::register,
         crate::api::routes::user_auth::login,
         crate::api::routes::user_auth::logout,
         crate::api::routes::user_auth::get_me,
+        crate::api::routes::user_auth::change_password,
+        crate::api::routes::user_auth::list_sessions,
+        crate::api::routes::user_auth::revoke_session,
+        crate::api::routes::user_auth::revoke_all_sessions,
+        crate::api::routes::access_tokens::create_access_token,
+        crate::api::routes::access_tokens::list_access_tokens,
+        crate::api::routes::access_tokens::revoke_access_token,
+        crate::api::routes::totp::get_totp_status,
+        crate::api::routes::totp::setup_totp,
+        crate::api::routes::totp::enable_totp,
+        crate::api::routes::totp::disable_totp,
+        crate::api::routes::totp::regenerate_recovery_codes,
+        crate::api::routes::totp::admin_reset_totp,
+        crate::api::routes::audit_events::list_audit_events,
+        crate::api::routes::config_diagnostics::get_config_diagnostics,
+        crate::api::routes::rate_limits::get_rate_limit_stats,
         crate::api::routes::workspaces::create_workspace,
         crate::api::routes::workspaces::list_workspaces,
         crate::api::routes::workspaces::get_workspace,
         crate::api::routes::workspaces::delete_workspace,
+        crate::api::routes::workspace_members::list_members,
+        crate::api::routes::workspace_members::add_member,
+        crate::api::routes::workspace_members::update_member,
+        crate::api::routes::workspace_members::remove_member,
         crate::api::routes::test_sets::list_test_sets,
         crate::api::routes::test_sets::create_test_set,
         crate::api::routes::test_sets::get_test_set,
@@ -146,6 +174,16 @@ use utoipa_swagger_ui::SwaggerUi;
             crate::api::routes::auth::TeacherSettingsResponse,
             crate::api::routes::auth::SaveConfigResponse,
             crate::api::routes::auth::GenericLlmModelsResponse,
+            crate::api::routes::auth::LlmProvidersResponse,
+            crate::api::routes::auth::DeleteLlmProviderResponse,
+            crate::api::routes::auth::CredentialProfileResponse,
+            crate::api::routes::auth::CredentialListResponse,
+            crate::api::routes::auth::CreateCredentialRequest,
+            crate::api::routes::auth::UpdateCredentialRequest,
+            crate::api::routes::auth::DeleteCredentialResponse,
+            crate::domain::models::LlmProviderSpec,
+            crate::domain::models::LlmAuthStyle,
+            crate::domain::models::LlmApiFormat,
             // User Auth
             crate::api::routes::user_auth::RegisterRequest,
             crate::api::routes::user_auth::LoginRequest,
@@ -153,10 +191,41 @@ use utoipa_swagger_ui::SwaggerUi;
             crate::api::routes::user_auth::UserInfo,
             crate::api::routes::user_auth::SystemStatusResponse,
             crate::api::routes::user_auth::LogoutResponse,
+            crate::api::routes::user_auth::ChangePasswordRequest,
+            crate::api::routes::user_auth::ChangePasswordResponse,
+            crate::api::routes::user_auth::SessionInfo,
+            crate::api::routes::user_auth::SessionListResponse,
+            crate::api::routes::user_auth::RevokeSessionsResponse,
+            crate::api::routes::access_tokens::CreateAccessTokenRequest,
+            crate::api::routes::access_tokens::AccessTokenInfo,
+            crate::api::routes::access_tokens::CreateAccessTokenResponse,
+            crate::api::routes::access_tokens::AccessTokenListResponse,
+            crate::api::routes::access_tokens::RevokeAccessTokenResponse,
+            crate::api::routes::totp::TotpStatusResponse,
+            crate::api::routes::totp::TotpSetupRequest,
+            crate::api::routes::totp::TotpSetupResponse,
+            crate::api::routes::totp::TotpEnableRequest,
+            crate::api::routes::totp::TotpRecoveryCodesResponse,
+            crate::api::routes::totp::TotpReauthRequest,
+            crate::api::routes::totp::AdminResetTotpRequest,
+            crate::api::routes::totp::DisableTotpResponse,
+            crate::api::routes::audit_events::AuditEventInfo,
+            crate::api::routes::audit_events::AuditEventListResponse,
+            crate::api::routes::config_diagnostics::ConfigDiagnosticsResponse,
+            crate::api::routes::config_diagnostics::BaseUrlPolicyInfo,
+            crate::api::routes::config_diagnostics::UpstreamGuardInfo,
+            crate::api::routes::config_diagnostics::OutboundHttpInfo,
+            crate::api::routes::rate_limits::RateLimitStatsResponse,
+            crate::api::routes::rate_limits::RateLimitClassInfo,
             // Workspaces
             crate::api::routes::workspaces::CreateWorkspaceRequest,
             crate::api::routes::workspaces::WorkspaceResponse,
             crate::api::routes::workspaces::DeleteWorkspaceResponse,
+            crate::api::routes::workspace_members::WorkspaceMemberInfo,
+            crate::api::routes::workspace_members::WorkspaceMemberListResponse,
+            crate::api::routes::workspace_members::AddWorkspaceMemberRequest,
+            crate::api::routes::workspace_members::UpdateWorkspaceMemberRequest,
+            crate::api::routes::workspace_members::RemoveWorkspaceMemberResponse,
             // Test Sets
             crate::api::routes::test_sets::CreateTestSetRequest,
             crate::api::routes::test_sets::UpdateTestSetRequest,
@@ -17,6 +17,7 @@ use tracing::{info, warn};
 use crate::api::middleware::CurrentUser;
 use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
 use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
+use crate::api::routes::workspace_members::ensure_task_writable;
 use crate::api::state::AppState;
 use crate::core::iteration_engine::events::record_event_async;
 use crate::core::iteration_engine::pause_state::global_pause_registry;
@@ -120,6 +121,12 @@ pub(crate) async fn add_rounds(
         return ApiResponse::err(StatusCode::BAD_REQUEST, error_codes::VALIDATION_ERROR, msg);
     }
 
+    // 只读成员不能修改任务
+    if let Err(resp) = ensure_task_writable::<AddRoundsResponse>(&state.db, &task_id, user_id).await
+    {
+        return resp;
+    }
+
     // 查询任务（权限校验）
     let task = match OptimizationTaskRepo::find_by_id_for_user(&state.db, user_id, &task_id).await {
         Ok(task) => task,
@@ -467,6 +474,13 @@ pub(crate) async fn terminate_task(
         "终止任务请求"
     );
 
+    // 只读成员不能修改任务
+    if let Err(resp) =
+        ensure_task_writable::<TerminateTaskResponse>(&state.db, &task_id, user_id).await
+    {
+        return resp;
+    }
+
     // 查询任务（权限校验）
     let task = match OptimizationTaskRepo::find_by_id_for_user(&state.db, user_id, &task_id).await {
         Ok(task) => task,
@@ -677,7 +691,7 @@ pub fn router() -> Router<AppState> {
 mod tests {
     use super::*;
     use crate::api::middleware::CurrentUser;
-    use crate::api::middleware::{LoginAttemptStore, SessionStore};
+    use crate::api::middleware::{LoginAttemptStore, RateLimiter, SessionStore};
     use crate::core::iteration_engine::pause_state::global_pause_registry;
     use crate::domain::models::optimization_task_config::{
         OptimizationTaskConfig, serialize_config_with_existing_extra,
@@ -706,28 +720,17 @@ mod tests {
 
         let config = Arc::new(AppConfig {
             database_url: "sqlite::memory:".to_string(),
-            server_host: "127.0.0.1".to_string(),
-            server_port: 3000,
-            log_level: "info".to_string(),
-            is_dev: true,
-            cors_origins: Vec::new(),
-            is_docker: false,
-            allow_http_base_url: true,
-            allow_localhost_base_url: true,
-            allow_private_network_base_url: true,
-
-            checkpoint_cache_limit: 10,
-
-            checkpoint_memory_alert_threshold: 10,
+            ..AppConfig::default()
         });
 
         AppState {
-            db: pool,
+            db: pool.clone(),
             http_client: Client::new(),
             config,
             api_key_manager: Arc::new(ApiKeyManager::new(None)),
-            session_store: SessionStore::new(24),
+            session_store: SessionStore::new(pool, 24),
             login_attempt_store: LoginAttemptStore::default(),
+            rate_limiter: RateLimiter::default(),
         }
     }
 
@@ -862,6 +865,7 @@ mod tests {
             CurrentUser {
                 user_id: "u1".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
             Json(AddRoundsRequest {
                 additional_rounds: 3,
@@ -900,6 +904,7 @@ mod tests {
             CurrentUser {
                 user_id: "u2".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
             Json(AddRoundsRequest {
                 additional_rounds: 1,
@@ -926,6 +931,7 @@ mod tests {
             CurrentUser {
                 user_id: "u1".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
             Json(AddRoundsRequest {
                 additional_rounds: 1,
@@ -954,6 +960,7 @@ mod tests {
             CurrentUser {
                 user_id: "u1".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
             Json(TerminateTaskRequest {
                 selected_iteration_id: Some(iteration_id.clone()),
@@ -997,6 +1004,7 @@ mod tests {
             CurrentUser {
                 user_id: "u2".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
             Json(TerminateTaskRequest {
                 selected_iteration_id: None,
@@ -1023,6 +1031,7 @@ mod tests {
             CurrentUser {
                 user_id: "u1".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
             Json(TerminateTaskRequest {
                 selected_iteration_id: None,
@@ -1096,6 +1105,7 @@ mod tests {
             CurrentUser {
                 user_id: "u1".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
         )
         .await;
@@ -1123,6 +1133,7 @@ mod tests {
             CurrentUser {
                 user_id: "u1".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
         )
         .await;
@@ -1151,6 +1162,7 @@ mod tests {
             CurrentUser {
                 user_id: "u2".to_string(),
                 unlock_context: None,
+                access_token: None,
             },
         )
         .await;
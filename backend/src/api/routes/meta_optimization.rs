@@ -7,16 +7,14 @@ use axum::{
     routing::{get, post, put},
 };
 use serde::Deserialize;
-use std::collections::{HashMap, VecDeque};
-use std::sync::{Mutex, OnceLock};
-use std::time::{Duration, Instant};
 use tracing::{info, warn};
 use utoipa::IntoParams;
 use uuid::Uuid;
 
-use crate::api::middleware::CurrentUser;
 use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
+use crate::api::middleware::{CurrentUser, PromptExecutionRateLimit};
 use crate::api::response::{ApiResponse, ApiSuccess};
+use crate::api::routes::auth::resolve_credential_keys;
 use crate::api::state::AppState;
 use crate::core::meta_optimization_service::{
     MetaOptimizationServiceError, compare_prompts, create_prompt_version,
@@ -34,59 +32,6 @@ use crate::shared::time::now_millis;
 
 const DEFAULT_LIMIT: i64 = 50;
 const MAX_LIMIT: i64 = 100;
-const DEFAULT_COMPARE_RATE_LIMIT_MAX: usize = 5;
-const DEFAULT_COMPARE_RATE_LIMIT_WINDOW_SECS: u64 = 60;
-
-fn compare_rate_limit_max() -> usize {
-    std::env::var("PROMPT_FASTER_COMPARE_RATE_LIMIT_MAX")
-        .ok()
-        .and_then(|value| value.parse::<usize>().ok())
-        .filter(|value| *value > 0)
-        .unwrap_or(DEFAULT_COMPARE_RATE_LIMIT_MAX)
-}
-
-fn compare_rate_limit_window_secs() -> u64 {
-    std::env::var("PROMPT_FASTER_COMPARE_RATE_LIMIT_WINDOW_SECS")
-        .ok()
-        .and_then(|value| value.parse::<u64>().ok())
-        .filter(|value| *value > 0)
-        .unwrap_or(DEFAULT_COMPARE_RATE_LIMIT_WINDOW_SECS)
-}
-
-fn compare_rate_limiter() -> &'static Mutex<HashMap<String, VecDeque<Instant>>> {
-    static RATE_LIMITER: OnceLock<Mutex<HashMap<String, VecDeque<Instant>>>> = OnceLock::new();
-    RATE_LIMITER.get_or_init(|| Mutex::new(HashMap::new()))
-}
-
-fn prune_rate_limit_queue(queue: &mut VecDeque<Instant>, now: Instant, window: Duration) {
-    while let Some(front) = queue.front() {
-        if now.duration_since(*front) > window {
-            queue.pop_front();
-        } else {
-            break;
-        }
-    }
-}
-
-fn allow_compare_request(user_id: &str) -> bool {
-    let mut guard = compare_rate_limiter().lock().unwrap();
-    let now = Instant::now();
-    let window = Duration::from_secs(compare_rate_limit_window_secs());
-
-    guard.retain(|_, queue| {
-        prune_rate_limit_queue(queue, now, window);
-        !queue.is_empty()
-    });
-
-    let queue = guard.entry(user_id.to_string()).or_default();
-    if queue.len() >= compare_rate_limit_max() {
-        return false;
-    }
-
-    queue.push_back(now);
-    true
-}
-
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct PromptListQuery {
     /// 返回条数（默认 50，最大 100）
@@ -475,6 +420,7 @@ pub(crate) async fn list_historical_tasks(
         (status = 200, description = "预览成功", body = ApiSuccess<PromptPreviewResponse>),
         (status = 400, description = "参数错误"),
         (status = 401, description = "未授权"),
+        (status = 429, description = "请求过于频繁（响应头 Retry-After 给出重试秒数）"),
         (status = 500, description = "服务器错误")
     ),
     tag = "meta_optimization"
@@ -483,19 +429,14 @@ pub(crate) async fn preview_prompt_handler(
     State(state): State<AppState>,
     headers: HeaderMap,
     current_user: CurrentUser,
+    _rate_limit: PromptExecutionRateLimit,
     Json(request): Json<PromptPreviewRequest>,
 ) -> ApiResponse<PromptPreviewResponse> {
     let correlation_id = extract_correlation_id(&headers);
     let user_id = &current_user.user_id;
-    let user_password = match current_user.unlock_context.as_ref() {
-        Some(ctx) => ctx.password_bytes(),
-        None => {
-            return ApiResponse::err(
-                StatusCode::UNAUTHORIZED,
-                error_codes::UNAUTHORIZED,
-                "会话已过期，请重新登录",
-            );
-        }
+    let credential_keys = match resolve_credential_keys(&state, &current_user).await {
+        Ok(keys) => keys,
+        Err(resp) => return resp,
     };
 
     log_action(
@@ -511,7 +452,7 @@ pub(crate) async fn preview_prompt_handler(
         &state.db,
         state.api_key_manager.as_ref(),
         user_id,
-        user_password,
+        &credential_keys,
         request,
         Some(correlation_id.clone()),
     )
@@ -533,6 +474,7 @@ pub(crate) async fn preview_prompt_handler(
         (status = 401, description = "未授权"),
         (status = 403, description = "无权访问"),
         (status = 404, description = "版本不存在"),
+        (status = 429, description = "请求过于频繁（响应头 Retry-After 给出重试秒数）"),
         (status = 500, description = "服务器错误")
     ),
     tag = "meta_optimization"
@@ -541,29 +483,16 @@ pub(crate) async fn compare_prompt_handler(
     State(state): State<AppState>,
     headers: HeaderMap,
     current_user: CurrentUser,
+    _rate_limit: PromptExecutionRateLimit,
     Json(request): Json<PromptCompareRequest>,
 ) -> ApiResponse<PromptCompareResponse> {
     let correlation_id = extract_correlation_id(&headers);
     let user_id = &current_user.user_id;
-    let user_password = match current_user.unlock_context.as_ref() {
-        Some(ctx) => ctx.password_bytes(),
-        None => {
-            return ApiResponse::err(
-                StatusCode::UNAUTHORIZED,
-                error_codes::UNAUTHORIZED,
-                "会话已过期，请重新登录",
-            );
-        }
+    let credential_keys = match resolve_credential_keys(&state, &current_user).await {
+        Ok(keys) => keys,
+        Err(resp) => return resp,
     };
 
-    if !allow_compare_request(user_id) {
-        return ApiResponse::err(
-            StatusCode::TOO_MANY_REQUESTS,
-            error_codes::RATE_LIMITED,
-            "对比请求过于频繁，请稍后再试",
-        );
-    }
-
     log_compare_action(
         &correlation_id,
         user_id,
@@ -576,7 +505,7 @@ pub(crate) async fn compare_prompt_handler(
         &state.db,
         state.api_key_manager.as_ref(),
         user_id,
-        user_password,
+        &credential_keys,
         request,
         Some(correlation_id.clone()),
     )
@@ -645,6 +574,11 @@ async fn map_service_error<T: serde::Serialize>(
         MetaOptimizationServiceError::InvalidRequest(msg) => {
             ApiResponse::err(StatusCode::BAD_REQUEST, error_codes::VALIDATION_ERROR, msg)
         }
+        MetaOptimizationServiceError::CredentialNotAccessible(msg) => ApiResponse::err(
+            StatusCode::FORBIDDEN,
+            error_codes::CREDENTIAL_NOT_ACCESSIBLE,
+            msg,
+        ),
         MetaOptimizationServiceError::ExecutionFailed(msg) => {
             warn!(correlation_id = %correlation_id, error = %msg, "元优化预览执行失败");
             ApiResponse::err(
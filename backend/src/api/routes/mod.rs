@@ -1,5 +1,8 @@
+pub mod access_tokens;
+pub mod audit_events;
 pub mod auth;
 pub mod checkpoints;
+pub mod config_diagnostics;
 pub mod diagnostic;
 pub mod dify;
 pub mod diversity;
@@ -12,9 +15,12 @@ pub mod iterations;
 pub mod meta;
 pub mod meta_optimization;
 pub mod optimization_tasks;
+pub mod rate_limits;
 pub mod recovery;
 pub mod results;
 pub mod test_set_templates;
 pub mod test_sets;
+pub mod totp;
 pub mod user_auth;
+pub mod workspace_members;
 pub mod workspaces;
@@ -13,19 +13,22 @@ use utoipa::ToSchema;
 use crate::api::middleware::CurrentUser;
 use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
 use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
+use crate::api::routes::workspace_members::ensure_workspace_writable;
 use crate::api::state::AppState;
 use crate::domain::models::{
-    AdvancedDataSplitConfig, DataSplitPercentConfig, DiversityConfig, EvaluatorConfig,
-    ExecutionMode, ExecutionTargetType, OPTIMIZATION_TASK_CONFIG_SCHEMA_VERSION,
+    AdvancedDataSplitConfig, CommandTargetConfig, DataSplitPercentConfig, DiversityConfig,
+    EvaluatorConfig, ExecutionCacheConfig, ExecutionMode, ExecutionRetryConfig,
+    ExecutionTargetType, HttpTargetConfig, OPTIMIZATION_TASK_CONFIG_SCHEMA_VERSION,
     OptimizationTaskConfig, OptimizationTaskMode, OptimizationTaskStatus, OutputConfig,
     TaskReference, TeacherLlmConfig,
 };
 use crate::infra::db::repositories::{
-    CreateOptimizationTaskInput, OptimizationTaskRepo, OptimizationTaskRepoError,
-    TeacherPromptRepo, TeacherPromptRepoError, TestSetRepo, TestSetRepoError, WorkspaceRepo,
-    WorkspaceRepoError,
+    CreateOptimizationTaskInput, CredentialRepo, CredentialRepoError, CredentialType,
+    OptimizationTaskRepo, OptimizationTaskRepoError, TeacherPromptRepo, TeacherPromptRepoError,
+    TestSetRepo, TestSetRepoError, WorkspaceRepo, WorkspaceRepoError,
 };
 use crate::shared::error_codes;
+use crate::shared::url_validator::resolve_and_validate_base_url;
 
 #[derive(Debug, Deserialize, ToSchema, TS)]
 #[ts(export_to = "api/")]
@@ -109,6 +112,17 @@ pub struct UpdateOptimizationTaskConfigRequest {
     #[serde(default)]
     pub teacher_llm: TeacherLlmConfig,
     pub advanced_data_split: AdvancedDataSplitConfig,
+    #[serde(default)]
+    pub execution_cache: ExecutionCacheConfig,
+    #[serde(default)]
+    pub execution_retry: ExecutionRetryConfig,
+    #[serde(default)]
+    pub command_target: CommandTargetConfig,
+    #[serde(default)]
+    pub http_target: HttpTargetConfig,
+    /// 执行凭证 ID（仅 dify / generic 执行目标可用；None 表示使用默认凭证）
+    #[serde(default)]
+    pub execution_credential_id: Option<String>,
 }
 
 fn default_max_concurrency() -> u32 {
@@ -232,10 +246,12 @@ fn parse_execution_target_type<T: Serialize>(
         "dify" => Ok(ExecutionTargetType::Dify),
         "generic" => Ok(ExecutionTargetType::Generic),
         "example" => Ok(ExecutionTargetType::Example),
+        "command" => Ok(ExecutionTargetType::Command),
+        "http" => Ok(ExecutionTargetType::Http),
         _ => Err(ApiResponse::err(
             StatusCode::BAD_REQUEST,
             error_codes::VALIDATION_ERROR,
-            "execution_target_type 仅允许 dify | generic | example",
+            "execution_target_type 仅允许 dify | generic | example | command | http",
         )),
     }
 }
@@ -359,6 +375,66 @@ fn validate_task_config<T: Serialize>(
     Ok(())
 }
 
+/// 校验任务指定的执行凭证：必须属于当前用户，且类型与任务执行目标匹配
+async fn validate_execution_credential<T: Serialize>(
+    state: &AppState,
+    user_id: &str,
+    workspace_id: &str,
+    task_id: &str,
+    credential_id: &str,
+) -> Result<(), ApiResponse<T>> {
+    let task =
+        match OptimizationTaskRepo::find_by_id_scoped(&state.db, user_id, workspace_id, task_id)
+            .await
+        {
+            Ok(task) => task.task,
+            Err(OptimizationTaskRepoError::NotFound) => return Err(optimization_task_not_found()),
+            Err(OptimizationTaskRepoError::WorkspaceNotFound) => return Err(workspace_not_found()),
+            Err(e) => {
+                warn!(error = %e, "获取优化任务失败");
+                return Err(ApiResponse::err(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    error_codes::DATABASE_ERROR,
+                    "获取优化任务失败",
+                ));
+            }
+        };
+
+    let credential_type = match task.execution_target_type {
+        ExecutionTargetType::Dify => CredentialType::Dify,
+        ExecutionTargetType::Generic => CredentialType::GenericLlm,
+        _ => {
+            return Err(ApiResponse::err(
+                StatusCode::BAD_REQUEST,
+                error_codes::VALIDATION_ERROR,
+                "当前执行目标类型不支持指定执行凭证",
+            ));
+        }
+    };
+
+    match CredentialRepo::resolve(&state.db, user_id, credential_type, Some(credential_id)).await {
+        Ok(_) => Ok(()),
+        Err(CredentialRepoError::ProfileNotFound(_)) => Err(ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            "执行凭证不存在",
+        )),
+        Err(CredentialRepoError::TypeMismatch { .. }) => Err(ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            "执行凭证类型与任务执行目标不匹配",
+        )),
+        Err(e) => {
+            warn!(error = %e, "查询执行凭证失败");
+            Err(ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "查询执行凭证失败",
+            ))
+        }
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/workspaces/{workspace_id}/optimization-tasks",
@@ -392,6 +468,12 @@ pub(crate) async fn create_optimization_task(
     {
         return resp;
     }
+    if let Err(resp) =
+        ensure_workspace_writable::<OptimizationTaskResponse>(&state.db, &workspace_id, user_id)
+            .await
+    {
+        return resp;
+    }
 
     if let Err(resp) = validate_name::<OptimizationTaskResponse>(&req.name) {
         return resp;
@@ -710,6 +792,12 @@ pub(crate) async fn update_optimization_task_config(
     {
         return resp;
     }
+    if let Err(resp) =
+        ensure_workspace_writable::<OptimizationTaskResponse>(&state.db, &workspace_id, user_id)
+            .await
+    {
+        return resp;
+    }
 
     let config = OptimizationTaskConfig {
         schema_version: OPTIMIZATION_TASK_CONFIG_SCHEMA_VERSION,
@@ -730,6 +818,11 @@ pub(crate) async fn update_optimization_task_config(
         diversity_config: req.diversity_config,
         teacher_llm: req.teacher_llm,
         advanced_data_split: req.advanced_data_split,
+        execution_cache: req.execution_cache,
+        execution_retry: req.execution_retry,
+        command_target: req.command_target,
+        http_target: req.http_target,
+        execution_credential_id: req.execution_credential_id,
     }
     .normalized();
 
@@ -737,6 +830,36 @@ pub(crate) async fn update_optimization_task_config(
         return resp;
     }
 
+    // HTTP 执行目标 URL：SSRF 防护（基于配置策略；未填写时允许先保存其他配置）
+    if !config.http_target.url.trim().is_empty() {
+        if let Err(e) = resolve_and_validate_base_url(
+            &config.http_target.url,
+            state.config.base_url_validation_options(),
+        )
+        .await
+        {
+            return ApiResponse::err(
+                StatusCode::BAD_REQUEST,
+                error_codes::VALIDATION_ERROR,
+                format!("HTTP 执行目标 URL 不合法: {e}"),
+            );
+        }
+    }
+
+    if let Some(credential_id) = config.execution_credential_id.as_deref() {
+        if let Err(resp) = validate_execution_credential::<OptimizationTaskResponse>(
+            &state,
+            user_id,
+            &workspace_id,
+            &task_id,
+            credential_id,
+        )
+        .await
+        {
+            return resp;
+        }
+    }
+
     match OptimizationTaskRepo::update_config_scoped(
         &state.db,
         user_id,
@@ -0,0 +1,122 @@
+//! 限流统计路由
+//! 暴露按路由类别聚合的限流规则与放行 / 拒绝计数，供监控使用（仅限登录会话调用）
+//!
+//! 仅实例管理员（首个注册用户）可查看；计数为服务启动以来的累计值，重启后清零。
+
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::{Router, routing::get};
+use serde::Serialize;
+use tracing::warn;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use crate::api::middleware::CurrentUser;
+use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
+use crate::api::middleware::rate_limit::RateLimitClassStats;
+use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
+use crate::api::state::AppState;
+use crate::infra::db::repositories::UserRepo;
+use crate::shared::error_codes;
+
+/// 单个路由类别的限流统计
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct RateLimitClassInfo {
+    /// prompt_execution / upstream_query
+    pub class: String,
+    /// 每分钟配额（0 = 不限制）
+    pub per_minute: u32,
+    /// 突发上限
+    pub burst: u32,
+    #[ts(type = "number")]
+    pub allowed_total: u64,
+    #[ts(type = "number")]
+    pub rejected_total: u64,
+    #[ts(type = "number")]
+    pub active_buckets: usize,
+}
+
+/// 限流统计响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct RateLimitStatsResponse {
+    pub classes: Vec<RateLimitClassInfo>,
+}
+
+impl From<RateLimitClassStats> for RateLimitClassInfo {
+    fn from(stats: RateLimitClassStats) -> Self {
+        Self {
+            class: stats.class.as_str().to_string(),
+            per_minute: stats.rule.per_minute,
+            burst: stats.rule.burst,
+            allowed_total: stats.allowed_total,
+            rejected_total: stats.rejected_total,
+            active_buckets: stats.active_buckets,
+        }
+    }
+}
+
+fn extract_correlation_id(headers: &HeaderMap) -> String {
+    headers
+        .get(CORRELATION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// 查看限流统计
+///
+/// GET /api/v1/auth/rate-limits
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/rate-limits",
+    responses(
+        (status = 200, description = "查询成功", body = ApiSuccess<RateLimitStatsResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 403, description = "仅实例管理员可查看", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn get_rate_limit_stats(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    current_user: CurrentUser,
+) -> ApiResponse<RateLimitStatsResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    let user_id = &current_user.user_id;
+
+    let is_admin = match UserRepo::get_first_user(&state.db).await {
+        Ok(first_user) => first_user.is_some_and(|u| &u.id == user_id),
+        Err(e) => {
+            warn!(correlation_id = %correlation_id, error = %e, "查询实例管理员失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "查询实例管理员失败",
+            );
+        }
+    };
+    if !is_admin {
+        return ApiResponse::err(
+            StatusCode::FORBIDDEN,
+            error_codes::FORBIDDEN,
+            "仅实例管理员可查看限流统计",
+        );
+    }
+
+    ApiResponse::ok(RateLimitStatsResponse {
+        classes: state
+            .rate_limiter
+            .stats()
+            .into_iter()
+            .map(RateLimitClassInfo::from)
+            .collect(),
+    })
+}
+
+/// 创建限流统计路由（需鉴权）
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(get_rate_limit_stats))
+}
@@ -13,6 +13,7 @@ use utoipa::ToSchema;
 use crate::api::middleware::CurrentUser;
 use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
 use crate::api::response::{ApiResponse, ApiSuccess};
+use crate::api::routes::workspace_members::ensure_task_writable;
 use crate::api::state::AppState;
 use crate::core::iteration_engine::events::record_event_async;
 use crate::core::iteration_engine::recovery as recovery_core;
@@ -129,6 +130,11 @@ pub(crate) async fn recover_task(
     let user_id = current_user.user_id;
     let checkpoint_id = body.checkpoint_id;
 
+    if let Err(resp) = ensure_task_writable::<RecoveryResponse>(&state.db, &task_id, &user_id).await
+    {
+        return resp;
+    }
+
     let result = recovery_core::recover_task_with_pool(
         &state.db,
         &task_id,
@@ -281,6 +287,11 @@ pub(crate) async fn rollback_task(
         );
     }
 
+    if let Err(resp) = ensure_task_writable::<RollbackResponse>(&state.db, &task_id, &user_id).await
+    {
+        return resp;
+    }
+
     let result = recovery_core::rollback_to_checkpoint_with_pool(
         &state.db,
         &task_id,
@@ -353,6 +364,12 @@ pub(crate) async fn abort_recovery(
     let correlation_id = extract_correlation_id(&headers);
     let user_id = current_user.user_id;
 
+    if let Err(resp) =
+        ensure_task_writable::<AbortRecoveryResponse>(&state.db, &task_id, &user_id).await
+    {
+        return resp;
+    }
+
     match recovery_core::abort_task_with_pool(&state.db, &task_id, &user_id, &correlation_id).await
     {
         Ok(_) => ApiResponse::ok(AbortRecoveryResponse {
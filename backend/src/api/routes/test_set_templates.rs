@@ -11,6 +11,7 @@ use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
 use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
 use crate::api::routes::dify::DifyConfig;
 use crate::api::routes::generic::GenericConfig;
+use crate::api::routes::workspace_members::ensure_workspace_writable;
 use crate::api::state::AppState;
 use crate::domain::models::TestCase;
 use crate::infra::db::repositories::{
@@ -335,6 +336,13 @@ pub(crate) async fn save_as_template(
         }
     }
 
+    if let Err(err) =
+        ensure_workspace_writable::<TestSetTemplateResponse>(&state.db, &workspace_id, user_id)
+            .await
+    {
+        return err;
+    }
+
     let created = match TestSetRepo::create_template_from_test_set_scoped(
         &state.db,
         user_id,
@@ -12,8 +12,9 @@ use ts_rs::TS;
 use utoipa::ToSchema;
 
 use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
-use crate::api::middleware::{CurrentUser, connectivity_middleware};
+use crate::api::middleware::{CurrentUser, UpstreamQueryRateLimit, connectivity_middleware};
 use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
+use crate::api::routes::auth::resolve_credential_keys;
 use crate::api::routes::dify::{
     DifyBindingSource, DifyConfig, SaveDifyConfigRequest, SaveDifyConfigResponse,
 };
@@ -22,6 +23,7 @@ use crate::api::routes::generic::{
     SaveGenericConfigRequest, SaveGenericConfigResponse,
 };
 use crate::api::routes::test_set_templates;
+use crate::api::routes::workspace_members::ensure_workspace_writable;
 use crate::api::state::AppState;
 use crate::domain::models::TestCase;
 use crate::infra::db::repositories::{CredentialRepo, CredentialRepoError, CredentialType};
@@ -178,6 +180,44 @@ fn validate_name<T: Serialize>(name: &str) -> Result<(), ApiResponse<T>> {
     Ok(())
 }
 
+const DIFY_OUTPUT_SELECTOR_MAX_CHARS: usize = 256;
+
+fn normalize_output_selector(raw: Option<&str>) -> Option<String> {
+    raw.map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// 校验测试集指定的 Dify 凭证（必须属于当前用户且为 Dify 类型），返回规范化后的 ID
+async fn validate_dify_credential_id<T: Serialize>(
+    state: &AppState,
+    user_id: &str,
+    raw: Option<&str>,
+) -> Result<Option<String>, ApiResponse<T>> {
+    let Some(id) = raw.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    let id = id.to_string();
+    match CredentialRepo::resolve(&state.db, user_id, CredentialType::Dify, Some(&id)).await {
+        Ok(_) => Ok(Some(id)),
+        Err(CredentialRepoError::ProfileNotFound(_) | CredentialRepoError::TypeMismatch { .. }) => {
+            Err(ApiResponse::err(
+                StatusCode::BAD_REQUEST,
+                error_codes::VALIDATION_ERROR,
+                "credentialId 不存在或不是 Dify 凭证",
+            ))
+        }
+        Err(e) => {
+            warn!(error = %e, "查询 Dify 凭证失败");
+            Err(ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "查询 Dify 凭证失败",
+            ))
+        }
+    }
+}
+
 fn validate_dify_config_request<T: Serialize>(
     req: &SaveDifyConfigRequest,
     snapshot: Option<&DifyConfig>,
@@ -198,6 +238,29 @@ fn validate_dify_config_request<T: Serialize>(
         ));
     }
 
+    if let Some(selector) = normalize_output_selector(req.output_selector.as_deref()) {
+        if selector.chars().count() > DIFY_OUTPUT_SELECTOR_MAX_CHARS {
+            return Err(ApiResponse::err(
+                StatusCode::BAD_REQUEST,
+                error_codes::VALIDATION_ERROR,
+                format!("outputSelector 不能超过 {DIFY_OUTPUT_SELECTOR_MAX_CHARS} 个字符"),
+            ));
+        }
+        // JSON Pointer 不校验；变量名在已知声明输出时需命中其一
+        let declared = snapshot
+            .and_then(|c| c.outputs_snapshot.as_ref())
+            .filter(|outputs| !outputs.is_empty());
+        if !selector.starts_with('/')
+            && declared.is_some_and(|outputs| !outputs.iter().any(|o| o.name == selector))
+        {
+            return Err(ApiResponse::err(
+                StatusCode::BAD_REQUEST,
+                error_codes::VALIDATION_ERROR,
+                format!("outputSelector 不在工作流声明的输出变量中: {selector}"),
+            ));
+        }
+    }
+
     if req.bindings.contains_key(target) {
         return Err(ApiResponse::err(
             StatusCode::BAD_REQUEST,
@@ -355,14 +418,28 @@ fn validate_generic_config_request<T: Serialize>(
 }
 
 fn parse_cases<T: Serialize>(cases: serde_json::Value) -> Result<Vec<TestCase>, ApiResponse<T>> {
-    serde_json::from_value::<Vec<TestCase>>(cases).map_err(|e| {
+    let cases = serde_json::from_value::<Vec<TestCase>>(cases).map_err(|e| {
         ApiResponse::err_with_details(
             StatusCode::BAD_REQUEST,
             error_codes::VALIDATION_ERROR,
             "cases 格式错误：必须是 TestCase 数组",
             serde_json::json!({ "error": e.to_string() }),
         )
-    })
+    })?;
+
+    // 多轮对话用例（input.conversation）需满足轮次格式约定
+    for (index, case) in cases.iter().enumerate() {
+        if let Err(message) = case.conversation() {
+            return Err(ApiResponse::err_with_details(
+                StatusCode::BAD_REQUEST,
+                error_codes::VALIDATION_ERROR,
+                "cases 格式错误：多轮对话输入不合法",
+                serde_json::json!({ "index": index, "id": case.id, "error": message }),
+            ));
+        }
+    }
+
+    Ok(cases)
 }
 
 #[utoipa::path(
@@ -375,7 +452,9 @@ fn parse_cases<T: Serialize>(cases: serde_json::Value) -> Result<Vec<TestCase>,
     responses(
         (status = 200, description = "获取成功", body = ApiSuccess<DifyVariablesResponse>),
         (status = 401, description = "未授权", body = ApiError),
+        (status = 403, description = "只读成员无权修改", body = ApiError),
         (status = 404, description = "测试集不存在", body = ApiError),
+        (status = 429, description = "请求过于频繁（响应头 Retry-After 给出重试秒数）", body = ApiError),
         (status = 502, description = "上游错误", body = ApiError),
         (status = 500, description = "服务器错误", body = ApiError)
     ),
@@ -387,19 +466,14 @@ pub(crate) async fn refresh_dify_variables(
     headers: HeaderMap,
     Path((workspace_id, test_set_id)): Path<(String, String)>,
     current_user: CurrentUser,
+    _rate_limit: UpstreamQueryRateLimit,
 ) -> ApiResponse<DifyVariablesResponse> {
     let correlation_id = extract_correlation_id(&headers);
     let user_id = &current_user.user_id;
 
-    let user_password = match current_user.unlock_context.as_ref() {
-        Some(ctx) => ctx.password_bytes(),
-        None => {
-            return ApiResponse::err(
-                StatusCode::UNAUTHORIZED,
-                error_codes::UNAUTHORIZED,
-                "会话已过期，请重新登录",
-            );
-        }
+    let credential_keys = match resolve_credential_keys(&state, &current_user).await {
+        Ok(keys) => keys,
+        Err(resp) => return resp,
     };
 
     if let Err(err) =
@@ -407,6 +481,11 @@ pub(crate) async fn refresh_dify_variables(
     {
         return err;
     }
+    if let Err(err) =
+        ensure_workspace_writable::<DifyVariablesResponse>(&state.db, &workspace_id, user_id).await
+    {
+        return err;
+    }
 
     let loaded =
         match TestSetRepo::find_by_id_scoped(&state.db, user_id, &workspace_id, &test_set_id).await
@@ -423,26 +502,43 @@ pub(crate) async fn refresh_dify_variables(
             }
         };
 
-    let dify_credential =
-        match CredentialRepo::find_by_user_and_type(&state.db, user_id, CredentialType::Dify).await
-        {
-            Ok(cred) => cred,
-            Err(CredentialRepoError::NotFound { .. }) => {
-                return ApiResponse::err(
-                    StatusCode::BAD_REQUEST,
-                    error_codes::VALIDATION_ERROR,
-                    "请先在“配置”中保存 Dify 凭证",
-                );
-            }
-            Err(e) => {
-                warn!(error = %e, "查询 Dify 凭证失败");
-                return ApiResponse::err(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    error_codes::DATABASE_ERROR,
-                    "查询 Dify 凭证失败",
-                );
-            }
-        };
+    let configured_credential_id = parse_dify_config(loaded.dify_config_json.clone())
+        .ok()
+        .flatten()
+        .and_then(|cfg| cfg.credential_id);
+
+    let dify_credential = match CredentialRepo::resolve(
+        &state.db,
+        user_id,
+        CredentialType::Dify,
+        configured_credential_id.as_deref(),
+    )
+    .await
+    {
+        Ok(cred) => cred,
+        Err(CredentialRepoError::NotFound { .. }) => {
+            return ApiResponse::err(
+                StatusCode::BAD_REQUEST,
+                error_codes::VALIDATION_ERROR,
+                "请先在“配置”中保存 Dify 凭证",
+            );
+        }
+        Err(CredentialRepoError::ProfileNotFound(_) | CredentialRepoError::TypeMismatch { .. }) => {
+            return ApiResponse::err(
+                StatusCode::BAD_REQUEST,
+                error_codes::VALIDATION_ERROR,
+                "测试集指定的 Dify 凭证不存在，请重新选择",
+            );
+        }
+        Err(e) => {
+            warn!(error = %e, "查询 Dify 凭证失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "查询 Dify 凭证失败",
+            );
+        }
+    };
 
     let encrypted = EncryptedApiKey {
         ciphertext: dify_credential.encrypted_api_key,
@@ -452,7 +548,7 @@ pub(crate) async fn refresh_dify_variables(
 
     let api_key_bytes = match state
         .api_key_manager
-        .decrypt_bytes(user_password, &encrypted)
+        .decrypt_credential(&credential_keys, &encrypted)
     {
         Ok(v) => v,
         Err(e) => {
@@ -518,6 +614,7 @@ pub(crate) async fn refresh_dify_variables(
                 })
                 .collect(),
         );
+        cfg.outputs_snapshot = Some(resp.outputs.clone());
         if let Ok(cfg_json) = serde_json::to_string(&cfg) {
             if cfg_json.len() <= DIFY_CONFIG_JSON_MAX_BYTES {
                 let _ = TestSetRepo::update_dify_config_json_scoped(
@@ -579,6 +676,11 @@ pub(crate) async fn save_dify_config(
     {
         return err;
     }
+    if let Err(err) =
+        ensure_workspace_writable::<SaveDifyConfigResponse>(&state.db, &workspace_id, user_id).await
+    {
+        return err;
+    }
 
     let loaded =
         match TestSetRepo::find_by_id_scoped(&state.db, user_id, &workspace_id, &test_set_id).await
@@ -609,10 +711,26 @@ pub(crate) async fn save_dify_config(
         return err;
     }
 
+    let credential_id = match validate_dify_credential_id::<SaveDifyConfigResponse>(
+        &state,
+        user_id,
+        req.credential_id.as_deref(),
+    )
+    .await
+    {
+        Ok(v) => v,
+        Err(err) => return err,
+    };
+
     let dify_config = DifyConfig {
         target_prompt_variable: req.target_prompt_variable.trim().to_string(),
         bindings: req.bindings.clone(),
+        output_selector: normalize_output_selector(req.output_selector.as_deref()),
+        outputs_snapshot: existing.as_ref().and_then(|c| c.outputs_snapshot.clone()),
         parameters_snapshot: existing.and_then(|c| c.parameters_snapshot),
+        app_type: req.app_type,
+        response_mode: req.response_mode,
+        credential_id,
     };
 
     let cfg_json = match serde_json::to_string(&dify_config) {
@@ -686,6 +804,12 @@ pub(crate) async fn save_generic_config(
     {
         return err;
     }
+    if let Err(err) =
+        ensure_workspace_writable::<SaveGenericConfigResponse>(&state.db, &workspace_id, user_id)
+            .await
+    {
+        return err;
+    }
 
     match TestSetRepo::find_by_id_scoped(&state.db, user_id, &workspace_id, &test_set_id).await {
         Ok(_) => {}
@@ -782,6 +906,12 @@ pub(crate) async fn delete_generic_config(
     {
         return err;
     }
+    if let Err(err) =
+        ensure_workspace_writable::<DeleteGenericConfigResponse>(&state.db, &workspace_id, user_id)
+            .await
+    {
+        return err;
+    }
 
     match TestSetRepo::update_generic_config_json_scoped(
         &state.db,
@@ -917,10 +1047,26 @@ pub(crate) async fn create_test_set(
             return err;
         }
 
+        let credential_id = match validate_dify_credential_id::<TestSetResponse>(
+            &state,
+            user_id,
+            dify_req.credential_id.as_deref(),
+        )
+        .await
+        {
+            Ok(v) => v,
+            Err(err) => return err,
+        };
+
         let dify_config = DifyConfig {
             target_prompt_variable: dify_req.target_prompt_variable.trim().to_string(),
             bindings: dify_req.bindings.clone(),
             parameters_snapshot: None,
+            output_selector: normalize_output_selector(dify_req.output_selector.as_deref()),
+            outputs_snapshot: None,
+            app_type: dify_req.app_type,
+            response_mode: dify_req.response_mode,
+            credential_id,
         };
 
         match serde_json::to_string(&dify_config) {
@@ -982,6 +1128,11 @@ pub(crate) async fn create_test_set(
             );
         }
     }
+    if let Err(err) =
+        ensure_workspace_writable::<TestSetResponse>(&state.db, &workspace_id, user_id).await
+    {
+        return err;
+    }
 
     let created = match TestSetRepo::create(
         &state.db,
@@ -1128,6 +1279,11 @@ pub(crate) async fn update_test_set(
     {
         return err;
     }
+    if let Err(err) =
+        ensure_workspace_writable::<TestSetResponse>(&state.db, &workspace_id, user_id).await
+    {
+        return err;
+    }
 
     let cases = match parse_cases::<TestSetResponse>(req.cases) {
         Ok(c) => c,
@@ -1213,6 +1369,11 @@ pub(crate) async fn delete_test_set(
     {
         return err;
     }
+    if let Err(err) =
+        ensure_workspace_writable::<DeleteTestSetResponse>(&state.db, &workspace_id, user_id).await
+    {
+        return err;
+    }
 
     match TestSetRepo::delete_scoped(&state.db, user_id, &workspace_id, &test_set_id).await {
         Ok(true) => ApiResponse::ok(DeleteTestSetResponse {
@@ -0,0 +1,748 @@
+//! 两步验证（TOTP）路由
+//! 提供启用 / 停用 TOTP、重新生成恢复码，以及实例管理员重置他人两步验证的 API（仅限登录会话调用）
+//!
+//! 流程：`setup`（校验密码，生成待启用密钥与配置 URI）→ `enable`（用认证器验证码确认，返回恢复码）。
+//! 停用、重新生成恢复码与管理员重置均需重新校验密码；已启用两步验证时还需验证码或恢复码。
+//! 实例管理员即首个注册用户。
+
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::{
+    Json, Router,
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use crate::api::middleware::CurrentUser;
+use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
+use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
+use crate::api::routes::user_auth::password_attempt_key;
+use crate::api::state::AppState;
+use crate::domain::models::User;
+use crate::infra::db::repositories::{
+    TotpRepo, TotpRepoError, UserRepo, UserRepoError, UserTotpRecord,
+};
+use crate::infra::external::api_key_manager::{DataKey, EncryptedApiKey};
+use crate::shared::error_codes;
+use crate::shared::password::PasswordService;
+use crate::shared::time::now_millis;
+use crate::shared::totp;
+
+/// 两步验证状态
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct TotpStatusResponse {
+    pub enabled: bool,
+    /// 已生成密钥但尚未确认
+    pub pending: bool,
+    /// 剩余未使用的恢复码数量
+    pub recovery_codes_remaining: u32,
+}
+
+/// 生成两步验证密钥请求
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct TotpSetupRequest {
+    /// 当前登录密码（重新认证）
+    pub password: String,
+}
+
+/// 生成两步验证密钥响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct TotpSetupResponse {
+    /// Base32 密钥（供手动输入）
+    pub secret: String,
+    /// `otpauth://` 配置 URI（渲染为二维码供认证器扫描）
+    pub provisioning_uri: String,
+}
+
+/// 确认启用两步验证请求
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct TotpEnableRequest {
+    /// 认证器当前显示的 6 位验证码
+    pub code: String,
+}
+
+/// 恢复码响应（明文仅返回一次）
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct TotpRecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// 重新认证请求（停用 / 重新生成恢复码）
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct TotpReauthRequest {
+    /// 当前登录密码
+    pub password: String,
+    /// 6 位验证码或恢复码（已启用两步验证时必填）
+    #[serde(default)]
+    #[ts(optional)]
+    pub code: Option<String>,
+}
+
+/// 管理员重置他人两步验证请求
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct AdminResetTotpRequest {
+    /// 需要重置的用户名
+    pub username: String,
+    /// 管理员本人的登录密码
+    pub password: String,
+    /// 管理员本人的验证码或恢复码（管理员已启用两步验证时必填）
+    #[serde(default)]
+    #[ts(optional)]
+    pub code: Option<String>,
+}
+
+/// 停用两步验证响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct DisableTotpResponse {
+    pub message: String,
+}
+
+/// 第二因素校验失败
+#[derive(Debug)]
+pub(crate) enum SecondFactorError {
+    /// 验证码 / 恢复码错误，或验证码已被使用
+    Invalid,
+    /// 数据密钥不可用，无法解密 TOTP 密钥
+    Unavailable(String),
+    Database(TotpRepoError),
+}
+
+impl From<TotpRepoError> for SecondFactorError {
+    fn from(err: TotpRepoError) -> Self {
+        SecondFactorError::Database(err)
+    }
+}
+
+/// 校验第二因素：6 位数字按 TOTP 校验（需要数据密钥解密密钥），其余按恢复码校验
+///
+/// 通过的验证码时间步与恢复码均被标记为已使用，不可重复使用。
+pub(crate) async fn verify_second_factor(
+    state: &AppState,
+    record: &UserTotpRecord,
+    code: &str,
+    data_key: Option<&DataKey>,
+) -> Result<(), SecondFactorError> {
+    let now = now_millis();
+    if !totp::is_totp_code(code) {
+        let code_hash = totp::hash_recovery_code(code);
+        return match TotpRepo::consume_recovery_code(&state.db, &record.user_id, &code_hash, now)
+            .await?
+        {
+            true => {
+                info!(user_id = %record.user_id, "已使用两步验证恢复码");
+                Ok(())
+            }
+            false => Err(SecondFactorError::Invalid),
+        };
+    }
+
+    let data_key =
+        data_key.ok_or_else(|| SecondFactorError::Unavailable("数据密钥未解锁".to_string()))?;
+    let secret = open_secret(record, data_key)?;
+    let step = totp::verify_code(&secret, code, now, record.last_used_step)
+        .ok_or(SecondFactorError::Invalid)?;
+    if !TotpRepo::record_used_step(&state.db, &record.user_id, step, now).await? {
+        return Err(SecondFactorError::Invalid);
+    }
+    Ok(())
+}
+
+fn open_secret(
+    record: &UserTotpRecord,
+    data_key: &DataKey,
+) -> Result<zeroize::Zeroizing<Vec<u8>>, SecondFactorError> {
+    data_key
+        .open(&EncryptedApiKey {
+            ciphertext: record.encrypted_secret.clone(),
+            nonce: record.secret_nonce.clone(),
+            salt: Vec::new(),
+        })
+        .map_err(|e| SecondFactorError::Unavailable(e.to_string()))
+}
+
+fn extract_correlation_id(headers: &HeaderMap) -> String {
+    headers
+        .get(CORRELATION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+fn database_error<T: Serialize>(message: &str) -> ApiResponse<T> {
+    ApiResponse::err(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        error_codes::DATABASE_ERROR,
+        message,
+    )
+}
+
+/// 重新认证：校验登录密码，已启用两步验证时同时校验验证码或恢复码
+///
+/// 与登录共用 `LoginAttemptStore` 的失败计数与冷却（按用户计，与修改密码共享）。
+async fn reauthenticate<T: Serialize>(
+    state: &AppState,
+    user_id: &str,
+    password: &str,
+    code: Option<&str>,
+) -> Result<(User, Option<UserTotpRecord>), ApiResponse<T>> {
+    let attempt_key = password_attempt_key(user_id);
+    if state.login_attempt_store.is_blocked(&attempt_key).await {
+        return Err(ApiResponse::err(
+            StatusCode::TOO_MANY_REQUESTS,
+            error_codes::RATE_LIMITED,
+            "验证失败次数过多，请稍后重试",
+        ));
+    }
+
+    let user = UserRepo::find_by_id(&state.db, user_id)
+        .await
+        .map_err(|e| {
+            warn!(error = %e, user_id = %user_id, "查询用户失败");
+            database_error("查询用户失败")
+        })?;
+    if PasswordService::verify_password(password, &user.password_hash).is_err() {
+        state.login_attempt_store.record_failure(attempt_key).await;
+        warn!(user_id = %user_id, "重新认证失败：密码错误");
+        return Err(ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::AUTH_FAILED,
+            "密码错误",
+        ));
+    }
+
+    let record = TotpRepo::find(&state.db, user_id).await.map_err(|e| {
+        warn!(error = %e, user_id = %user_id, "查询两步验证配置失败");
+        database_error("查询两步验证配置失败")
+    })?;
+    if let Some(enabled) = record.as_ref().filter(|r| r.is_enabled()) {
+        let Some(code) = code.map(str::trim).filter(|c| !c.is_empty()) else {
+            return Err(ApiResponse::err(
+                StatusCode::BAD_REQUEST,
+                error_codes::AUTH_TOTP_REQUIRED,
+                "请输入两步验证码或恢复码",
+            ));
+        };
+        let data_key = if totp::is_totp_code(code) {
+            state
+                .api_key_manager
+                .unlock_data_key(&state.db, user_id, password.as_bytes())
+                .await
+                .map_err(|e| warn!(error = %e, user_id = %user_id, "解锁数据密钥失败"))
+                .ok()
+        } else {
+            None
+        };
+        match verify_second_factor(state, enabled, code, data_key.as_ref()).await {
+            Ok(()) => {}
+            Err(SecondFactorError::Invalid) => {
+                state.login_attempt_store.record_failure(attempt_key).await;
+                warn!(user_id = %user_id, "重新认证失败：两步验证码错误");
+                return Err(ApiResponse::err(
+                    StatusCode::BAD_REQUEST,
+                    error_codes::AUTH_TOTP_INVALID,
+                    "两步验证码错误",
+                ));
+            }
+            Err(SecondFactorError::Unavailable(reason)) => {
+                warn!(user_id = %user_id, reason = %reason, "两步验证密钥无法解密");
+                return Err(ApiResponse::err(
+                    StatusCode::CONFLICT,
+                    error_codes::ENCRYPTION_ERROR,
+                    "暂时无法校验验证码，请改用恢复码",
+                ));
+            }
+            Err(SecondFactorError::Database(e)) => {
+                warn!(error = %e, user_id = %user_id, "校验两步验证码失败");
+                return Err(database_error("校验两步验证码失败"));
+            }
+        }
+    }
+
+    state.login_attempt_store.reset(&attempt_key).await;
+    Ok((user, record))
+}
+
+/// 生成一组新恢复码，返回（明文，摘要）
+fn new_recovery_codes() -> (Vec<String>, Vec<String>) {
+    let codes = totp::generate_recovery_codes();
+    let hashes = codes.iter().map(|c| totp::hash_recovery_code(c)).collect();
+    (codes, hashes)
+}
+
+/// 查询两步验证状态
+///
+/// GET /api/v1/auth/totp
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/totp",
+    responses(
+        (status = 200, description = "查询成功", body = ApiSuccess<TotpStatusResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn get_totp_status(
+    State(state): State<AppState>,
+    current_user: CurrentUser,
+) -> ApiResponse<TotpStatusResponse> {
+    let user_id = &current_user.user_id;
+    let record = match TotpRepo::find(&state.db, user_id).await {
+        Ok(record) => record,
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "查询两步验证配置失败");
+            return database_error("查询两步验证状态失败");
+        }
+    };
+    let enabled = record.as_ref().is_some_and(UserTotpRecord::is_enabled);
+    let recovery_codes_remaining = if enabled {
+        match TotpRepo::count_unused_recovery_codes(&state.db, user_id).await {
+            Ok(count) => count,
+            Err(e) => {
+                warn!(error = %e, user_id = %user_id, "统计恢复码失败");
+                return database_error("查询两步验证状态失败");
+            }
+        }
+    } else {
+        0
+    };
+
+    ApiResponse::ok(TotpStatusResponse {
+        enabled,
+        pending: record.is_some() && !enabled,
+        recovery_codes_remaining,
+    })
+}
+
+/// 生成两步验证密钥
+///
+/// POST /api/v1/auth/totp/setup
+///
+/// 校验登录密码后生成新的待启用密钥（覆盖之前未确认的密钥），需调用 `enable` 确认后才生效。
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/totp/setup",
+    request_body = TotpSetupRequest,
+    responses(
+        (status = 200, description = "生成成功", body = ApiSuccess<TotpSetupResponse>),
+        (status = 400, description = "密码错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 409, description = "两步验证已启用", body = ApiError),
+        (status = 429, description = "验证失败次数过多", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn setup_totp(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    current_user: CurrentUser,
+    Json(req): Json<TotpSetupRequest>,
+) -> ApiResponse<TotpSetupResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    let user_id = &current_user.user_id;
+
+    // 已启用时直接报告状态冲突，而不是要求再输入验证码
+    match TotpRepo::find(&state.db, user_id).await {
+        Ok(Some(record)) if record.is_enabled() => {
+            return ApiResponse::err(
+                StatusCode::CONFLICT,
+                error_codes::AUTH_TOTP_STATE_CONFLICT,
+                "两步验证已启用，如需更换设备请先停用",
+            );
+        }
+        Ok(_) => {}
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "查询两步验证配置失败");
+            return database_error("生成两步验证密钥失败");
+        }
+    }
+
+    let (user, _) = match reauthenticate(&state, user_id, &req.password, None).await {
+        Ok(result) => result,
+        Err(resp) => return resp,
+    };
+
+    let data_key = match state
+        .api_key_manager
+        .unlock_data_key(&state.db, user_id, req.password.as_bytes())
+        .await
+    {
+        Ok(data_key) => data_key,
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "生成两步验证密钥失败：数据密钥解锁失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::ENCRYPTION_ERROR,
+                "数据密钥解锁失败，请稍后重试",
+            );
+        }
+    };
+
+    let secret = zeroize::Zeroizing::new(totp::generate_secret());
+    let sealed = match data_key.seal(&secret) {
+        Ok(sealed) => sealed,
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "加密两步验证密钥失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::ENCRYPTION_ERROR,
+                "生成两步验证密钥失败，请稍后重试",
+            );
+        }
+    };
+
+    match TotpRepo::save_pending(
+        &state.db,
+        user_id,
+        &sealed.ciphertext,
+        &sealed.nonce,
+        now_millis(),
+    )
+    .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            return ApiResponse::err(
+                StatusCode::CONFLICT,
+                error_codes::AUTH_TOTP_STATE_CONFLICT,
+                "两步验证已启用，如需更换设备请先停用",
+            );
+        }
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "保存两步验证密钥失败");
+            return database_error("生成两步验证密钥失败");
+        }
+    }
+
+    info!(correlation_id = %correlation_id, user_id = %user_id, "已生成待启用的两步验证密钥");
+
+    ApiResponse::ok(TotpSetupResponse {
+        secret: totp::encode_base32(&secret),
+        provisioning_uri: totp::provisioning_uri(&user.username, &secret),
+    })
+}
+
+/// 确认启用两步验证
+///
+/// POST /api/v1/auth/totp/enable
+///
+/// 使用认证器当前验证码确认待启用密钥；成功后返回一组一次性恢复码（仅此一次）。
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/totp/enable",
+    request_body = TotpEnableRequest,
+    responses(
+        (status = 200, description = "启用成功", body = ApiSuccess<TotpRecoveryCodesResponse>),
+        (status = 400, description = "验证码错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 409, description = "未生成密钥、已启用或数据密钥未解锁", body = ApiError),
+        (status = 429, description = "验证失败次数过多", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn enable_totp(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    current_user: CurrentUser,
+    Json(req): Json<TotpEnableRequest>,
+) -> ApiResponse<TotpRecoveryCodesResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    let user_id = &current_user.user_id;
+
+    let attempt_key = password_attempt_key(user_id);
+    if state.login_attempt_store.is_blocked(&attempt_key).await {
+        return ApiResponse::err(
+            StatusCode::TOO_MANY_REQUESTS,
+            error_codes::RATE_LIMITED,
+            "验证失败次数过多，请稍后重试",
+        );
+    }
+
+    let record = match TotpRepo::find(&state.db, user_id).await {
+        Ok(Some(record)) if !record.is_enabled() => record,
+        Ok(_) => {
+            return ApiResponse::err(
+                StatusCode::CONFLICT,
+                error_codes::AUTH_TOTP_STATE_CONFLICT,
+                "没有待启用的两步验证密钥，请重新生成",
+            );
+        }
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "查询两步验证配置失败");
+            return database_error("启用两步验证失败");
+        }
+    };
+
+    let data_key = match current_user
+        .unlock_context
+        .as_ref()
+        .and_then(|ctx| ctx.data_key())
+    {
+        Some(data_key) => Some(data_key.clone()),
+        None => state
+            .api_key_manager
+            .unlock_data_key_unattended(&state.db, user_id)
+            .await
+            .ok(),
+    };
+    let Some(data_key) = data_key else {
+        return ApiResponse::err(
+            StatusCode::CONFLICT,
+            error_codes::ENCRYPTION_ERROR,
+            "当前会话未解锁数据密钥，请重新登录后再试",
+        );
+    };
+    let secret = match open_secret(&record, &data_key) {
+        Ok(secret) => secret,
+        Err(_) => {
+            warn!(user_id = %user_id, "两步验证密钥无法解密");
+            return ApiResponse::err(
+                StatusCode::CONFLICT,
+                error_codes::ENCRYPTION_ERROR,
+                "两步验证密钥无法解密，请重新生成",
+            );
+        }
+    };
+
+    let now = now_millis();
+    let Some(step) = totp::verify_code(&secret, &req.code, now, None) else {
+        state.login_attempt_store.record_failure(attempt_key).await;
+        return ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::AUTH_TOTP_INVALID,
+            "验证码错误，请确认认证器时间准确",
+        );
+    };
+
+    let (recovery_codes, hashes) = new_recovery_codes();
+    match TotpRepo::enable(&state.db, user_id, step, &hashes, now).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return ApiResponse::err(
+                StatusCode::CONFLICT,
+                error_codes::AUTH_TOTP_STATE_CONFLICT,
+                "没有待启用的两步验证密钥，请重新生成",
+            );
+        }
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "启用两步验证失败");
+            return database_error("启用两步验证失败");
+        }
+    }
+
+    state.login_attempt_store.reset(&attempt_key).await;
+    info!(correlation_id = %correlation_id, user_id = %user_id, "两步验证已启用");
+
+    ApiResponse::ok(TotpRecoveryCodesResponse { recovery_codes })
+}
+
+/// 停用两步验证
+///
+/// POST /api/v1/auth/totp/disable
+///
+/// 需要登录密码；已启用时还需验证码或恢复码。
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/totp/disable",
+    request_body = TotpReauthRequest,
+    responses(
+        (status = 200, description = "停用成功", body = ApiSuccess<DisableTotpResponse>),
+        (status = 400, description = "密码或验证码错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 429, description = "验证失败次数过多", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn disable_totp(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    current_user: CurrentUser,
+    Json(req): Json<TotpReauthRequest>,
+) -> ApiResponse<DisableTotpResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    let user_id = &current_user.user_id;
+
+    if let Err(resp) = reauthenticate(&state, user_id, &req.password, req.code.as_deref()).await {
+        return resp;
+    }
+
+    match TotpRepo::disable(&state.db, user_id).await {
+        Ok(_) => {
+            info!(correlation_id = %correlation_id, user_id = %user_id, "两步验证已停用");
+            ApiResponse::ok(DisableTotpResponse {
+                message: "两步验证已停用".to_string(),
+            })
+        }
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "停用两步验证失败");
+            database_error("停用两步验证失败")
+        }
+    }
+}
+
+/// 重新生成恢复码
+///
+/// POST /api/v1/auth/totp/recovery-codes
+///
+/// 需要登录密码与验证码（或恢复码）；旧恢复码全部失效。
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/totp/recovery-codes",
+    request_body = TotpReauthRequest,
+    responses(
+        (status = 200, description = "生成成功", body = ApiSuccess<TotpRecoveryCodesResponse>),
+        (status = 400, description = "密码或验证码错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 409, description = "尚未启用两步验证", body = ApiError),
+        (status = 429, description = "验证失败次数过多", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    current_user: CurrentUser,
+    Json(req): Json<TotpReauthRequest>,
+) -> ApiResponse<TotpRecoveryCodesResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    let user_id = &current_user.user_id;
+
+    let record = match reauthenticate(&state, user_id, &req.password, req.code.as_deref()).await {
+        Ok((_, record)) => record,
+        Err(resp) => return resp,
+    };
+    if !record.as_ref().is_some_and(UserTotpRecord::is_enabled) {
+        return ApiResponse::err(
+            StatusCode::CONFLICT,
+            error_codes::AUTH_TOTP_STATE_CONFLICT,
+            "尚未启用两步验证",
+        );
+    }
+
+    let (recovery_codes, hashes) = new_recovery_codes();
+    if let Err(e) =
+        TotpRepo::replace_recovery_codes(&state.db, user_id, &hashes, now_millis()).await
+    {
+        warn!(error = %e, user_id = %user_id, "重新生成恢复码失败");
+        return database_error("重新生成恢复码失败");
+    }
+
+    info!(correlation_id = %correlation_id, user_id = %user_id, "已重新生成两步验证恢复码");
+
+    ApiResponse::ok(TotpRecoveryCodesResponse { recovery_codes })
+}
+
+/// 管理员重置他人的两步验证
+///
+/// POST /api/v1/auth/totp/admin-reset
+///
+/// 仅实例管理员（首个注册用户）可调用，需校验管理员本人的密码与两步验证；
+/// 用于成员丢失认证器且恢复码用尽的情况。
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/totp/admin-reset",
+    request_body = AdminResetTotpRequest,
+    responses(
+        (status = 200, description = "重置成功", body = ApiSuccess<DisableTotpResponse>),
+        (status = 400, description = "密码或验证码错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 403, description = "非实例管理员", body = ApiError),
+        (status = 404, description = "用户不存在或未配置两步验证", body = ApiError),
+        (status = 429, description = "验证失败次数过多", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn admin_reset_totp(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    current_user: CurrentUser,
+    Json(req): Json<AdminResetTotpRequest>,
+) -> ApiResponse<DisableTotpResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    let admin_id = &current_user.user_id;
+
+    match UserRepo::get_first_user(&state.db).await {
+        Ok(Some(first_user)) if &first_user.id == admin_id => {}
+        Ok(_) => {
+            warn!(correlation_id = %correlation_id, user_id = %admin_id, "非管理员尝试重置两步验证");
+            return ApiResponse::err(
+                StatusCode::FORBIDDEN,
+                error_codes::FORBIDDEN,
+                "仅实例管理员可重置他人的两步验证",
+            );
+        }
+        Err(e) => {
+            warn!(error = %e, "查询实例管理员失败");
+            return database_error("重置两步验证失败");
+        }
+    }
+
+    if let Err(resp) = reauthenticate(&state, admin_id, &req.password, req.code.as_deref()).await {
+        return resp;
+    }
+
+    let target = match UserRepo::find_by_username(&state.db, &req.username).await {
+        Ok(user) => user,
+        Err(UserRepoError::NotFound) => {
+            return ApiResponse::err(
+                StatusCode::NOT_FOUND,
+                error_codes::RESOURCE_NOT_FOUND,
+                "用户不存在",
+            );
+        }
+        Err(e) => {
+            warn!(error = %e, "查询用户失败");
+            return database_error("重置两步验证失败");
+        }
+    };
+
+    match TotpRepo::disable(&state.db, &target.id).await {
+        Ok(true) => {
+            info!(
+                correlation_id = %correlation_id,
+                admin_id = %admin_id,
+                user_id = %target.id,
+                "管理员已重置用户两步验证"
+            );
+            ApiResponse::ok(DisableTotpResponse {
+                message: format!("已重置用户「{}」的两步验证", target.username),
+            })
+        }
+        Ok(false) => ApiResponse::err(
+            StatusCode::NOT_FOUND,
+            error_codes::RESOURCE_NOT_FOUND,
+            "该用户未配置两步验证",
+        ),
+        Err(e) => {
+            warn!(error = %e, user_id = %target.id, "重置两步验证失败");
+            database_error("重置两步验证失败")
+        }
+    }
+}
+
+/// 创建两步验证路由（挂载于 `/api/v1/auth/totp`）
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_totp_status))
+        .route("/setup", post(setup_totp))
+        .route("/enable", post(enable_totp))
+        .route("/disable", post(disable_totp))
+        .route("/recovery-codes", post(regenerate_recovery_codes))
+        .route("/admin-reset", post(admin_reset_totp))
+}
@@ -1,26 +1,31 @@
 //! 用户认证路由
-//! 提供注册、登录、登出、获取当前用户等 API
+//! 提供注册、登录、登出、获取当前用户、会话管理等 API
 
-use axum::extract::ConnectInfo;
-use axum::extract::State;
-use axum::http::{HeaderMap, StatusCode};
+use axum::extract::{ConnectInfo, Path, Query, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
 use axum::{
     Json, Router,
-    routing::{get, post},
+    routing::{delete, get, post},
 };
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 use tracing::{info, warn};
 use ts_rs::TS;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
-use crate::api::middleware::CurrentUser;
 use crate::api::middleware::LoginAttemptStore;
 use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
-use crate::api::middleware::session::UnlockContext;
+use crate::api::middleware::session::{SessionMetadata, UnlockContext, hash_session_token};
+use crate::api::middleware::{AuditContext, CurrentUser};
 use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
+use crate::api::routes::totp::{SecondFactorError, verify_second_factor};
 use crate::api::state::AppState;
-use crate::infra::db::repositories::{MigrationRepo, UserRepo, UserRepoError};
+use crate::infra::db::repositories::{
+    AuditEventType, CredentialRecord, CredentialRepo, MigrationRepo, NewAuditEvent,
+    ReencryptedCredential, TotpRepo, UserRepo, UserRepoError, UserTotpRecord,
+};
+use crate::infra::external::api_key_manager::{ApiKeyError, DataKey, EncryptedApiKey};
 use crate::shared::error_codes;
 use crate::shared::password::{PasswordError, PasswordService};
 
@@ -38,6 +43,10 @@ pub struct RegisterRequest {
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
+    /// 两步验证码或恢复码（已启用两步验证时必填；缺失时返回 `AUTH_TOTP_REQUIRED`）
+    #[serde(default)]
+    #[ts(optional)]
+    pub totp_code: Option<String>,
 }
 
 /// 认证成功响应
@@ -77,6 +86,67 @@ pub struct LogoutResponse {
     pub message: String,
 }
 
+/// 修改密码请求
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct ChangePasswordRequest {
+    pub old_password: String,
+    pub new_password: String,
+}
+
+/// 修改密码响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct ChangePasswordResponse {
+    /// 本次重新加密（从旧格式迁移到数据密钥）的凭证数
+    pub reencrypted_credentials: u32,
+    /// 其中由 legacy `MASTER_PASSWORD` 迁移的凭证数
+    pub migrated_legacy_credentials: u32,
+    /// 已注销的其他会话数
+    pub revoked_sessions: u32,
+}
+
+/// 会话信息（设备列表）
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct SessionInfo {
+    /// 会话 ID（用于吊销）
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+    #[ts(type = "number")]
+    pub last_seen_at: i64,
+    #[ts(type = "number")]
+    pub expires_at: i64,
+    /// 是否为当前请求使用的会话
+    pub current: bool,
+}
+
+/// 会话列表响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct SessionListResponse {
+    pub sessions: Vec<SessionInfo>,
+}
+
+/// 吊销会话响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct RevokeSessionsResponse {
+    /// 已吊销的会话数
+    pub revoked_sessions: u32,
+}
+
+/// 吊销全部会话查询参数
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RevokeAllSessionsQuery {
+    /// 是否同时吊销当前会话（默认 false，仅吊销其他设备）
+    #[serde(default)]
+    pub include_current: bool,
+}
+
 /// 系统状态响应（用于判断是否需要注册）
 #[derive(Debug, Serialize, ToSchema, TS)]
 #[ts(export_to = "api/")]
@@ -94,6 +164,55 @@ fn extract_correlation_id(headers: &HeaderMap) -> String {
         .to_string()
 }
 
+/// 从请求中提取会话设备信息
+fn session_metadata(headers: &HeaderMap, addr: Option<SocketAddr>) -> SessionMetadata {
+    SessionMetadata {
+        user_agent: headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string()),
+        ip_address: addr.map(|addr| addr.ip().to_string()),
+    }
+}
+
+/// 已登录用户校验当前密码（修改密码 / 重新认证）时的失败计数 key（按用户计）
+pub(crate) fn password_attempt_key(user_id: &str) -> String {
+    LoginAttemptStore::make_key("reauth", user_id)
+}
+
+/// 记录登录失败并写入审计事件（本次失败触发冷却时额外记录锁定事件）
+async fn record_login_failure(
+    state: &AppState,
+    audit: &AuditContext,
+    key: &str,
+    username: &str,
+    user_id: Option<&str>,
+    reason: &str,
+) {
+    let locked = state
+        .login_attempt_store
+        .record_failure(key.to_string())
+        .await;
+    let event = |event_type| {
+        let event = NewAuditEvent::new(event_type)
+            .actor_username(username)
+            .details(serde_json::json!({ "reason": reason }));
+        match user_id {
+            Some(user_id) => event.actor(user_id),
+            None => event,
+        }
+    };
+    audit
+        .record(&state.db, event(AuditEventType::LoginFailed))
+        .await;
+    if locked {
+        warn!(username = %username, ip = ?audit.ip_address, "连续登录失败，已进入冷却");
+        audit
+            .record(&state.db, event(AuditEventType::LoginLockedOut))
+            .await;
+    }
+}
+
 /// 从请求头提取 Bearer token
 fn extract_bearer_token(headers: &HeaderMap) -> Option<String> {
     headers
@@ -262,11 +381,26 @@ pub(crate) async fn register(
     }
 
     // 创建会话（包含解锁上下文）
-    let unlock_context = UnlockContext::new(req.password);
-    let session_token = state
+    let unlock_context = establish_unlock_context(&state, &user.id, req.password).await;
+    let session_token = match state
         .session_store
-        .create_session(user.id.clone(), Some(unlock_context))
-        .await;
+        .create_session_with_metadata(
+            user.id.clone(),
+            Some(unlock_context),
+            session_metadata(&headers, None),
+        )
+        .await
+    {
+        Ok(token) => token,
+        Err(e) => {
+            warn!(error = %e, user_id = %user.id, "创建会话失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "注册成功但创建会话失败，请登录",
+            );
+        }
+    };
 
     info!(user_id = %user.id, "用户注册成功");
 
@@ -283,14 +417,14 @@ pub(crate) async fn register(
 ///
 /// POST /api/v1/auth/login
 ///
-/// 验证用户凭证并返回会话 token
+/// 验证用户凭证并返回会话 token；已启用两步验证的用户需在同一请求中附带 `totp_code`
 #[utoipa::path(
     post,
     path = "/api/v1/auth/login",
     request_body = LoginRequest,
     responses(
         (status = 200, description = "登录成功", body = ApiSuccess<AuthResponse>),
-        (status = 401, description = "认证失败", body = ApiError),
+        (status = 401, description = "认证失败或需要两步验证码（AUTH_TOTP_REQUIRED）", body = ApiError),
         (status = 500, description = "服务器错误", body = ApiError)
     ),
     tag = "user"
@@ -304,11 +438,20 @@ pub(crate) async fn login(
     let correlation_id = extract_correlation_id(&headers);
     info!(correlation_id = %correlation_id, username = %req.username, "用户登录");
 
+    let audit = AuditContext::from_request(&headers, Some(addr));
     let ip = addr.ip().to_string();
     let key = LoginAttemptStore::make_key(&ip, &req.username);
 
     if state.login_attempt_store.is_blocked(&key).await {
         warn!(correlation_id = %correlation_id, username = %req.username, ip = %ip, "登录尝试被冷却");
+        audit
+            .record(
+                &state.db,
+                NewAuditEvent::new(AuditEventType::LoginFailed)
+                    .actor_username(&req.username)
+                    .details(serde_json::json!({ "reason": "locked_out" })),
+            )
+            .await;
         return ApiResponse::err(
             StatusCode::UNAUTHORIZED,
             error_codes::AUTH_FAILED,
@@ -320,7 +463,15 @@ pub(crate) async fn login(
     let user = match UserRepo::find_by_username(&state.db, &req.username).await {
         Ok(user) => user,
         Err(UserRepoError::NotFound) => {
-            state.login_attempt_store.record_failure(key.clone()).await;
+            record_login_failure(
+                &state,
+                &audit,
+                &key,
+                &req.username,
+                None,
+                "invalid_credentials",
+            )
+            .await;
             // 不暴露用户是否存在
             warn!("登录失败：用户名或密码错误");
             return ApiResponse::err(
@@ -341,7 +492,15 @@ pub(crate) async fn login(
 
     // 验证密码
     if PasswordService::verify_password(&req.password, &user.password_hash).is_err() {
-        state.login_attempt_store.record_failure(key.clone()).await;
+        record_login_failure(
+            &state,
+            &audit,
+            &key,
+            &req.username,
+            Some(&user.id),
+            "invalid_credentials",
+        )
+        .await;
         // 不暴露具体失败原因
         warn!("登录失败：用户名或密码错误");
         return ApiResponse::err(
@@ -351,16 +510,128 @@ pub(crate) async fn login(
         );
     }
 
+    // 两步验证：密码通过后再校验验证码 / 恢复码
+    let totp_record = match TotpRepo::find(&state.db, &user.id).await {
+        Ok(record) => record.filter(UserTotpRecord::is_enabled),
+        Err(e) => {
+            warn!(error = %e, user_id = %user.id, "查询两步验证配置失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "登录失败，请稍后重试",
+            );
+        }
+    };
+    let totp_code = req
+        .totp_code
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(str::to_string);
+    if totp_record.is_some() && totp_code.is_none() {
+        info!(correlation_id = %correlation_id, user_id = %user.id, "登录需要两步验证码");
+        return ApiResponse::err(
+            StatusCode::UNAUTHORIZED,
+            error_codes::AUTH_TOTP_REQUIRED,
+            "请输入两步验证码",
+        );
+    }
+
+    // 第二因子通过前只读解开数据密钥（仅用于解密两步验证密钥），不创建会话解锁上下文、不迁移凭证
+    if let (Some(record), Some(code)) = (&totp_record, &totp_code) {
+        let data_key = match state
+            .api_key_manager
+            .open_data_key(&state.db, &user.id, req.password.as_bytes())
+            .await
+        {
+            Ok(data_key) => Some(data_key),
+            Err(e) => {
+                warn!(error = %e, user_id = %user.id, "两步验证前解锁数据密钥失败");
+                None
+            }
+        };
+        match verify_second_factor(&state, record, code, data_key.as_ref()).await {
+            Ok(()) => {}
+            Err(SecondFactorError::Invalid) => {
+                record_login_failure(
+                    &state,
+                    &audit,
+                    &key,
+                    &req.username,
+                    Some(&user.id),
+                    "totp_invalid",
+                )
+                .await;
+                warn!(correlation_id = %correlation_id, user_id = %user.id, "登录失败：两步验证码错误");
+                return ApiResponse::err(
+                    StatusCode::UNAUTHORIZED,
+                    error_codes::AUTH_TOTP_INVALID,
+                    "两步验证码错误",
+                );
+            }
+            Err(SecondFactorError::Unavailable(reason)) => {
+                warn!(user_id = %user.id, reason = %reason, "两步验证密钥无法解密");
+                audit
+                    .record(
+                        &state.db,
+                        NewAuditEvent::new(AuditEventType::LoginFailed)
+                            .actor(&user.id)
+                            .actor_username(&user.username)
+                            .details(serde_json::json!({ "reason": "totp_unavailable" })),
+                    )
+                    .await;
+                return ApiResponse::err(
+                    StatusCode::UNAUTHORIZED,
+                    error_codes::AUTH_TOTP_INVALID,
+                    "暂时无法校验验证码，请改用恢复码",
+                );
+            }
+            Err(SecondFactorError::Database(e)) => {
+                warn!(error = %e, user_id = %user.id, "校验两步验证码失败");
+                return ApiResponse::err(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    error_codes::DATABASE_ERROR,
+                    "登录失败，请稍后重试",
+                );
+            }
+        }
+    }
+
     state.login_attempt_store.reset(&key).await;
 
-    // 创建会话（包含解锁上下文）
-    let unlock_context = UnlockContext::new(req.password);
-    let session_token = state
+    // 全部认证因子通过后再创建会话解锁上下文（可能写入数据密钥 / 迁移凭证）
+    let unlock_context = establish_unlock_context(&state, &user.id, req.password).await;
+
+    let session_token = match state
         .session_store
-        .create_session(user.id.clone(), Some(unlock_context))
-        .await;
+        .create_session_with_metadata(
+            user.id.clone(),
+            Some(unlock_context),
+            session_metadata(&headers, Some(addr)),
+        )
+        .await
+    {
+        Ok(token) => token,
+        Err(e) => {
+            warn!(error = %e, user_id = %user.id, "创建会话失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "登录失败，请稍后重试",
+            );
+        }
+    };
 
     info!(user_id = %user.id, "用户登录成功");
+    audit
+        .record(
+            &state.db,
+            NewAuditEvent::new(AuditEventType::LoginSucceeded)
+                .actor(&user.id)
+                .actor_username(&user.username)
+                .details(serde_json::json!({ "second_factor": totp_record.is_some() })),
+        )
+        .await;
 
     ApiResponse::ok(AuthResponse {
         session_token,
@@ -405,12 +676,17 @@ pub(crate) async fn logout(
     };
 
     // 移除会话
-    let removed = state.session_store.remove_session(&token).await;
-
-    if removed {
-        info!("用户登出成功");
-    } else {
-        info!("会话已不存在或已过期");
+    match state.session_store.remove_session(&token).await {
+        Ok(true) => info!("用户登出成功"),
+        Ok(false) => info!("会话已不存在或已过期"),
+        Err(e) => {
+            warn!(error = %e, "移除会话失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "登出失败，请稍后重试",
+            );
+        }
     }
 
     ApiResponse::ok(LogoutResponse {
@@ -418,6 +694,361 @@ pub(crate) async fn logout(
     })
 }
 
+/// 修改密码
+///
+/// POST /api/v1/auth/password
+///
+/// 校验原密码后，在同一事务内更新密码并用新密码重新包装数据密钥；凭证由数据密钥加密，
+/// 仅尚未迁移的旧格式凭证（含 legacy `MASTER_PASSWORD` 凭证）需要重新加密。
+/// 成功后注销该用户的其他会话。
+///
+/// 原密码校验与登录共用 `LoginAttemptStore` 的失败计数与冷却（按用户计，与重新认证共享），
+/// 冷却期内直接返回 429 + `Retry-After`，防止持有会话令牌者暴力猜测当前密码。
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/password",
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "修改成功", body = ApiSuccess<ChangePasswordResponse>),
+        (status = 400, description = "参数错误或原密码错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 409, description = "凭证无法解密或被并发修改", body = ApiError),
+        (status = 429, description = "原密码错误次数过多（响应头 Retry-After 给出重试秒数）", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn change_password(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    current_user: CurrentUser,
+    Json(req): Json<ChangePasswordRequest>,
+) -> Response {
+    let user_id = current_user.user_id;
+    if let Some(retry_after_secs) = state
+        .login_attempt_store
+        .cooldown_remaining_secs(&password_attempt_key(&user_id))
+        .await
+    {
+        warn!(
+            correlation_id = %extract_correlation_id(&headers),
+            user_id = %user_id,
+            "修改密码尝试被冷却"
+        );
+        let mut response = ApiResponse::<ChangePasswordResponse>::err(
+            StatusCode::TOO_MANY_REQUESTS,
+            error_codes::RATE_LIMITED,
+            "验证失败次数过多，请稍后重试",
+        )
+        .into_response();
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        return response;
+    }
+
+    update_password(state, headers, user_id, req)
+        .await
+        .into_response()
+}
+
+/// 校验原密码并更新密码、重新包装数据密钥（调用方已完成冷却检查）
+async fn update_password(
+    state: AppState,
+    headers: HeaderMap,
+    user_id: String,
+    req: ChangePasswordRequest,
+) -> ApiResponse<ChangePasswordResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    info!(correlation_id = %correlation_id, user_id = %user_id, "修改密码");
+
+    let Some(session_token) = extract_bearer_token(&headers) else {
+        return ApiResponse::err(
+            StatusCode::UNAUTHORIZED,
+            error_codes::UNAUTHORIZED,
+            "未提供有效的会话令牌",
+        );
+    };
+
+    if req.new_password.len() < 6 {
+        return ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            "密码长度至少 6 个字符",
+        );
+    }
+    if req.new_password == req.old_password {
+        return ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            "新密码不能与原密码相同",
+        );
+    }
+
+    let user = match UserRepo::find_by_id(&state.db, &user_id).await {
+        Ok(user) => user,
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "查询用户失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "修改密码失败，请稍后重试",
+            );
+        }
+    };
+
+    let attempt_key = password_attempt_key(&user_id);
+    if PasswordService::verify_password(&req.old_password, &user.password_hash).is_err() {
+        state.login_attempt_store.record_failure(attempt_key).await;
+        warn!(correlation_id = %correlation_id, user_id = %user_id, "修改密码失败：原密码错误");
+        return ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::AUTH_FAILED,
+            "原密码错误",
+        );
+    }
+    state.login_attempt_store.reset(&attempt_key).await;
+
+    let records = match CredentialRepo::find_all_by_user(&state.db, &user_id).await {
+        Ok(records) => records,
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "查询凭证失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "修改密码失败，请稍后重试",
+            );
+        }
+    };
+
+    let data_key = match state
+        .api_key_manager
+        .unlock_data_key(&state.db, &user_id, req.old_password.as_bytes())
+        .await
+    {
+        Ok(data_key) => data_key,
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "修改密码失败：数据密钥解锁失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::ENCRYPTION_ERROR,
+                "数据密钥解锁失败，请稍后重试",
+            );
+        }
+    };
+
+    // 任一旧格式凭证无法解密时拒绝修改，避免新密码下留下永久无法解密的数据
+    let mut reencrypted = Vec::new();
+    let mut migrated_legacy_credentials = 0_u32;
+    for record in records {
+        let result = match migrate_credential_to_data_key(
+            &state,
+            &record,
+            req.old_password.as_bytes(),
+            &data_key,
+        ) {
+            Ok(Some(result)) => result,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!(
+                    error = %e,
+                    user_id = %user_id,
+                    credential_id = %record.id,
+                    "修改密码失败：凭证无法解密"
+                );
+                return ApiResponse::err(
+                    StatusCode::CONFLICT,
+                    error_codes::ENCRYPTION_ERROR,
+                    format!(
+                        "凭证「{}」无法解密，请先重新保存或删除该凭证后再修改密码",
+                        record.name
+                    ),
+                );
+            }
+        };
+        let (credential, from_legacy) = result;
+        if from_legacy {
+            migrated_legacy_credentials += 1;
+        }
+        reencrypted.push(credential);
+    }
+
+    let data_key_wrap = match state
+        .api_key_manager
+        .wrap_data_key_for_password(req.new_password.as_bytes(), &data_key)
+    {
+        Ok(wrap) => wrap,
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "修改密码失败：数据密钥包装失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::ENCRYPTION_ERROR,
+                "修改密码失败，请稍后重试",
+            );
+        }
+    };
+
+    let password_hash = match PasswordService::hash_password(&req.new_password) {
+        Ok(hash) => hash,
+        Err(_) => {
+            warn!("密码哈希失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::INTERNAL_ERROR,
+                "修改密码失败，请稍后重试",
+            );
+        }
+    };
+
+    match UserRepo::change_password(
+        &state.db,
+        &user_id,
+        &password_hash,
+        &data_key_wrap,
+        &reencrypted,
+    )
+    .await
+    {
+        Ok(()) => {}
+        Err(UserRepoError::CredentialChanged(credential_id)) => {
+            warn!(user_id = %user_id, credential_id = %credential_id, "修改密码期间凭证被更新");
+            return ApiResponse::err(
+                StatusCode::CONFLICT,
+                error_codes::ENCRYPTION_ERROR,
+                "凭证在修改密码期间被更新，请重试",
+            );
+        }
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "更新密码失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "修改密码失败，请稍后重试",
+            );
+        }
+    }
+
+    // 当前会话改持新密码；其他会话仍持有旧密码，全部注销
+    state
+        .session_store
+        .replace_unlock_context(
+            &session_token,
+            UnlockContext::with_data_key(req.new_password, data_key),
+        )
+        .await;
+    let revoked_sessions = match state
+        .session_store
+        .remove_user_sessions_except(&user_id, &session_token)
+        .await
+    {
+        Ok(revoked) => revoked,
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "注销其他会话失败");
+            0
+        }
+    };
+
+    info!(
+        correlation_id = %correlation_id,
+        user_id = %user_id,
+        reencrypted_credentials = reencrypted.len(),
+        migrated_legacy_credentials = migrated_legacy_credentials,
+        revoked_sessions = revoked_sessions,
+        "修改密码成功"
+    );
+
+    ApiResponse::ok(ChangePasswordResponse {
+        reencrypted_credentials: reencrypted.len() as u32,
+        migrated_legacy_credentials,
+        revoked_sessions: revoked_sessions as u32,
+    })
+}
+
+/// 建立会话解锁上下文：解锁（首次登录时创建）用户数据密钥，并将旧格式凭证迁移到信封加密
+///
+/// 数据密钥不可用时会话仅持有登录密码（凭证按旧格式读写），不阻断登录。
+async fn establish_unlock_context(
+    state: &AppState,
+    user_id: &str,
+    password: String,
+) -> UnlockContext {
+    let data_key = match state
+        .api_key_manager
+        .unlock_data_key(&state.db, user_id, password.as_bytes())
+        .await
+    {
+        Ok(data_key) => data_key,
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "解锁数据密钥失败，会话仅使用登录密码派生");
+            return UnlockContext::new(password);
+        }
+    };
+
+    match CredentialRepo::find_all_by_user(&state.db, user_id).await {
+        Ok(records) => {
+            let mut migrated = Vec::new();
+            for record in records {
+                match migrate_credential_to_data_key(state, &record, password.as_bytes(), &data_key)
+                {
+                    Ok(Some((credential, _))) => migrated.push(credential),
+                    Ok(None) => {}
+                    Err(e) => {
+                        warn!(
+                            error = %e,
+                            user_id = %user_id,
+                            credential_id = %record.id,
+                            "凭证无法迁移到数据密钥，保留原格式"
+                        );
+                    }
+                }
+            }
+            if !migrated.is_empty() {
+                match CredentialRepo::replace_encrypted_keys(&state.db, user_id, &migrated).await {
+                    Ok(count) => {
+                        info!(user_id = %user_id, migrated_credentials = count, "凭证已迁移到信封加密")
+                    }
+                    Err(e) => warn!(error = %e, user_id = %user_id, "凭证迁移到信封加密失败"),
+                }
+            }
+        }
+        Err(e) => warn!(error = %e, user_id = %user_id, "查询凭证失败，跳过信封加密迁移"),
+    }
+
+    UnlockContext::with_data_key(password, data_key)
+}
+
+/// 将旧格式凭证迁移到数据密钥加密；已是信封加密时返回 None
+///
+/// 返回迁移结果与是否来自 legacy `MASTER_PASSWORD`。
+fn migrate_credential_to_data_key(
+    state: &AppState,
+    record: &CredentialRecord,
+    password: &[u8],
+    data_key: &DataKey,
+) -> Result<Option<(ReencryptedCredential, bool)>, ApiKeyError> {
+    let encrypted = EncryptedApiKey {
+        ciphertext: record.encrypted_api_key.clone(),
+        nonce: record.nonce.clone(),
+        salt: record.salt.clone(),
+    };
+    if encrypted.is_envelope() {
+        return Ok(None);
+    }
+    let result = state
+        .api_key_manager
+        .migrate_to_data_key(password, data_key, &encrypted)?;
+    Ok(Some((
+        ReencryptedCredential {
+            id: record.id.clone(),
+            previous_encrypted_api_key: record.encrypted_api_key.clone(),
+            encrypted_api_key: result.encrypted.ciphertext,
+            nonce: result.encrypted.nonce,
+            salt: result.encrypted.salt,
+        },
+        result.from_legacy,
+    )))
+}
+
 /// 获取当前用户信息
 ///
 /// GET /api/v1/auth/me
@@ -454,6 +1085,172 @@ pub(crate) async fn get_me(
     }
 }
 
+/// 列出当前用户的会话
+///
+/// GET /api/v1/auth/sessions
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/sessions",
+    responses(
+        (status = 200, description = "查询成功", body = ApiSuccess<SessionListResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn list_sessions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    current_user: CurrentUser,
+) -> ApiResponse<SessionListResponse> {
+    let current_hash = extract_bearer_token(&headers).map(|t| hash_session_token(&t));
+
+    match state
+        .session_store
+        .list_user_sessions(&current_user.user_id)
+        .await
+    {
+        Ok(records) => ApiResponse::ok(SessionListResponse {
+            sessions: records
+                .into_iter()
+                .map(|record| SessionInfo {
+                    current: current_hash.as_deref() == Some(record.token_hash.as_str()),
+                    id: record.id,
+                    user_agent: record.user_agent,
+                    ip_address: record.ip_address,
+                    created_at: record.created_at,
+                    last_seen_at: record.last_seen_at,
+                    expires_at: record.expires_at,
+                })
+                .collect(),
+        }),
+        Err(e) => {
+            warn!(error = %e, user_id = %current_user.user_id, "查询会话失败");
+            ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "查询会话失败",
+            )
+        }
+    }
+}
+
+/// 吊销指定会话
+///
+/// DELETE /api/v1/auth/sessions/{session_id}
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/sessions/{session_id}",
+    params(("session_id" = String, Path, description = "会话 ID")),
+    responses(
+        (status = 200, description = "吊销成功", body = ApiSuccess<RevokeSessionsResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 404, description = "会话不存在", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn revoke_session(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    current_user: CurrentUser,
+    Path(session_id): Path<String>,
+) -> ApiResponse<RevokeSessionsResponse> {
+    match state
+        .session_store
+        .revoke_user_session(&current_user.user_id, &session_id)
+        .await
+    {
+        Ok(true) => {
+            info!(user_id = %current_user.user_id, session_id = %session_id, "会话已吊销");
+            audit
+                .record(
+                    &state.db,
+                    NewAuditEvent::new(AuditEventType::SessionRevoked)
+                        .actor(&current_user.user_id)
+                        .target("session", &session_id)
+                        .details(serde_json::json!({ "revoked_sessions": 1 })),
+                )
+                .await;
+            ApiResponse::ok(RevokeSessionsResponse {
+                revoked_sessions: 1,
+            })
+        }
+        Ok(false) => ApiResponse::err(StatusCode::NOT_FOUND, error_codes::NOT_FOUND, "会话不存在"),
+        Err(e) => {
+            warn!(error = %e, user_id = %current_user.user_id, "吊销会话失败");
+            ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "吊销会话失败",
+            )
+        }
+    }
+}
+
+/// 吊销当前用户的全部会话
+///
+/// DELETE /api/v1/auth/sessions
+///
+/// 默认保留当前会话（“登出其他设备”）；`include_current=true` 时当前会话一并吊销。
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/sessions",
+    params(RevokeAllSessionsQuery),
+    responses(
+        (status = 200, description = "吊销成功", body = ApiSuccess<RevokeSessionsResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "user"
+)]
+pub(crate) async fn revoke_all_sessions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    audit: AuditContext,
+    current_user: CurrentUser,
+    Query(query): Query<RevokeAllSessionsQuery>,
+) -> ApiResponse<RevokeSessionsResponse> {
+    let user_id = &current_user.user_id;
+    let result = match extract_bearer_token(&headers).filter(|_| !query.include_current) {
+        Some(token) => {
+            state
+                .session_store
+                .remove_user_sessions_except(user_id, &token)
+                .await
+        }
+        None => state.session_store.remove_all_user_sessions(user_id).await,
+    };
+
+    match result {
+        Ok(revoked) => {
+            info!(user_id = %user_id, revoked_sessions = revoked, include_current = query.include_current, "已吊销用户会话");
+            audit
+                .record(
+                    &state.db,
+                    NewAuditEvent::new(AuditEventType::SessionRevoked)
+                        .actor(user_id)
+                        .details(serde_json::json!({
+                            "revoked_sessions": revoked,
+                            "include_current": query.include_current,
+                        })),
+                )
+                .await;
+            ApiResponse::ok(RevokeSessionsResponse {
+                revoked_sessions: revoked as u32,
+            })
+        }
+        Err(e) => {
+            warn!(error = %e, user_id = %user_id, "吊销会话失败");
+            ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "吊销会话失败",
+            )
+        }
+    }
+}
+
 /// 创建用户认证路由
 pub fn public_router() -> Router<AppState> {
     Router::new()
@@ -466,6 +1263,9 @@ pub fn protected_router() -> Router<AppState> {
     Router::new()
         .route("/logout", post(logout))
         .route("/me", get(get_me))
+        .route("/password", post(change_password))
+        .route("/sessions", get(list_sessions).delete(revoke_all_sessions))
+        .route("/sessions/{session_id}", delete(revoke_session))
 }
 
 pub fn router() -> Router<AppState> {
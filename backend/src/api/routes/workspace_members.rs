@@ -0,0 +1,438 @@
+//! 工作区成员路由
+//! 提供成员列出、按用户名邀请、修改角色与移除 API，以及供其他路由使用的写权限校验
+//!
+//! 角色规则：任一成员可读取工作区内容；owner / editor 可修改；仅 owner 可管理成员。
+//! 成员可自行退出（移除自己）。凭证不随工作区共享，任务始终使用发起者本人的凭证。
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::{
+    Json, Router,
+    routing::{get, patch},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tracing::{info, warn};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+use crate::api::middleware::CurrentUser;
+use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
+use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
+use crate::api::state::AppState;
+use crate::infra::db::repositories::{
+    WorkspaceMemberRecord, WorkspaceMemberRepo, WorkspaceMemberRepoError, WorkspaceRepo,
+    WorkspaceRepoError, WorkspaceRole,
+};
+use crate::shared::error_codes;
+
+/// 工作区成员信息
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct WorkspaceMemberInfo {
+    pub user_id: String,
+    pub username: String,
+    /// 角色：owner / editor / viewer
+    pub role: String,
+    pub invited_by: Option<String>,
+    #[ts(type = "number")]
+    pub created_at: i64,
+}
+
+/// 工作区成员列表响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct WorkspaceMemberListResponse {
+    pub members: Vec<WorkspaceMemberInfo>,
+}
+
+/// 邀请成员请求
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct AddWorkspaceMemberRequest {
+    pub username: String,
+    /// 角色：`editor` 或 `viewer`
+    pub role: String,
+}
+
+/// 修改成员角色请求
+#[derive(Debug, Deserialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct UpdateWorkspaceMemberRequest {
+    /// 角色：`editor` 或 `viewer`
+    pub role: String,
+}
+
+/// 移除成员响应
+#[derive(Debug, Serialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct RemoveWorkspaceMemberResponse {
+    pub message: String,
+}
+
+fn extract_correlation_id(headers: &HeaderMap) -> String {
+    headers
+        .get(CORRELATION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+fn workspace_not_found<T: Serialize>() -> ApiResponse<T> {
+    ApiResponse::err(
+        StatusCode::NOT_FOUND,
+        error_codes::WORKSPACE_NOT_FOUND,
+        "工作区不存在",
+    )
+}
+
+fn member_not_found<T: Serialize>() -> ApiResponse<T> {
+    ApiResponse::err(
+        StatusCode::NOT_FOUND,
+        error_codes::RESOURCE_NOT_FOUND,
+        "成员不存在",
+    )
+}
+
+fn read_only_member<T: Serialize>() -> ApiResponse<T> {
+    ApiResponse::err(
+        StatusCode::FORBIDDEN,
+        error_codes::FORBIDDEN,
+        "只读成员无权修改该工作区",
+    )
+}
+
+fn database_error<T: Serialize>(message: &str) -> ApiResponse<T> {
+    ApiResponse::err(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        error_codes::DATABASE_ERROR,
+        message,
+    )
+}
+
+/// 解析可授予的成员角色（owner 仅由创建工作区产生）
+fn parse_grantable_role<T: Serialize>(role: &str) -> Result<WorkspaceRole, ApiResponse<T>> {
+    match WorkspaceRole::parse(role.trim()) {
+        Some(role) if role != WorkspaceRole::Owner => Ok(role),
+        _ => Err(ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            "成员角色只能是 editor 或 viewer",
+        )),
+    }
+}
+
+fn to_member_info(record: WorkspaceMemberRecord) -> WorkspaceMemberInfo {
+    WorkspaceMemberInfo {
+        user_id: record.user_id,
+        username: record.username,
+        role: record.role,
+        invited_by: record.invited_by,
+        created_at: record.created_at,
+    }
+}
+
+/// 校验当前用户对工作区有写权限（owner / editor）
+///
+/// 只拦截只读成员；工作区不存在或非成员时放行，由调用方按原有逻辑返回 404。
+pub(crate) async fn ensure_workspace_writable<T: Serialize>(
+    db: &SqlitePool,
+    workspace_id: &str,
+    user_id: &str,
+) -> Result<(), ApiResponse<T>> {
+    match WorkspaceMemberRepo::find_role(db, workspace_id, user_id).await {
+        Ok(Some(role)) if !role.can_write() => Err(read_only_member()),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            warn!(error = %e, "查询工作区成员角色失败");
+            Err(database_error("查询工作区成员角色失败"))
+        }
+    }
+}
+
+/// 校验当前用户对任务所属工作区有写权限（规则同 [`ensure_workspace_writable`]）
+pub(crate) async fn ensure_task_writable<T: Serialize>(
+    db: &SqlitePool,
+    task_id: &str,
+    user_id: &str,
+) -> Result<(), ApiResponse<T>> {
+    match WorkspaceMemberRepo::find_role_for_task(db, task_id, user_id).await {
+        Ok(Some(role)) if !role.can_write() => Err(read_only_member()),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            warn!(error = %e, "查询工作区成员角色失败");
+            Err(database_error("查询工作区成员角色失败"))
+        }
+    }
+}
+
+/// 查询当前用户角色；非成员返回 404，`manage` 为 true 时要求 owner
+async fn require_role<T: Serialize>(
+    db: &SqlitePool,
+    workspace_id: &str,
+    user_id: &str,
+    manage: bool,
+) -> Result<WorkspaceRole, ApiResponse<T>> {
+    match WorkspaceRepo::find_with_role_by_id(db, workspace_id, user_id).await {
+        Ok((_, role)) if manage && !role.can_manage() => Err(ApiResponse::err(
+            StatusCode::FORBIDDEN,
+            error_codes::FORBIDDEN,
+            "仅工作区所有者可管理成员",
+        )),
+        Ok((_, role)) => Ok(role),
+        Err(WorkspaceRepoError::NotFound) => Err(workspace_not_found()),
+        Err(e) => {
+            warn!(error = %e, "查询工作区失败");
+            Err(database_error("查询工作区失败"))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/workspaces/{workspace_id}/members",
+    params(
+        ("workspace_id" = String, Path, description = "工作区 ID")
+    ),
+    responses(
+        (status = 200, description = "获取成功", body = ApiSuccess<WorkspaceMemberListResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 404, description = "工作区不存在", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "workspaces"
+)]
+pub(crate) async fn list_members(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(workspace_id): Path<String>,
+    current_user: CurrentUser,
+) -> ApiResponse<WorkspaceMemberListResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    let user_id = &current_user.user_id;
+
+    info!(correlation_id = %correlation_id, user_id = %user_id, workspace_id = %workspace_id, "列出工作区成员");
+
+    if let Err(resp) = require_role(&state.db, &workspace_id, user_id, false).await {
+        return resp;
+    }
+
+    match WorkspaceMemberRepo::list_by_workspace(&state.db, &workspace_id).await {
+        Ok(members) => ApiResponse::ok(WorkspaceMemberListResponse {
+            members: members.into_iter().map(to_member_info).collect(),
+        }),
+        Err(e) => {
+            warn!(error = %e, "查询工作区成员失败");
+            database_error("查询工作区成员失败")
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/workspaces/{workspace_id}/members",
+    params(
+        ("workspace_id" = String, Path, description = "工作区 ID")
+    ),
+    request_body = AddWorkspaceMemberRequest,
+    responses(
+        (status = 200, description = "邀请成功", body = ApiSuccess<WorkspaceMemberInfo>),
+        (status = 400, description = "参数错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 403, description = "仅所有者可邀请成员", body = ApiError),
+        (status = 404, description = "工作区或用户不存在", body = ApiError),
+        (status = 409, description = "该用户已是成员", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "workspaces"
+)]
+pub(crate) async fn add_member(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(workspace_id): Path<String>,
+    current_user: CurrentUser,
+    Json(req): Json<AddWorkspaceMemberRequest>,
+) -> ApiResponse<WorkspaceMemberInfo> {
+    let correlation_id = extract_correlation_id(&headers);
+    let user_id = &current_user.user_id;
+
+    let username = req.username.trim();
+    if username.is_empty() {
+        return ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            "用户名不能为空",
+        );
+    }
+    let role = match parse_grantable_role(&req.role) {
+        Ok(role) => role,
+        Err(resp) => return resp,
+    };
+
+    info!(
+        correlation_id = %correlation_id,
+        user_id = %user_id,
+        workspace_id = %workspace_id,
+        role = role.as_str(),
+        "邀请工作区成员"
+    );
+
+    if let Err(resp) = require_role(&state.db, &workspace_id, user_id, true).await {
+        return resp;
+    }
+
+    match WorkspaceMemberRepo::add_by_username(&state.db, &workspace_id, username, role, user_id)
+        .await
+    {
+        Ok(member) => ApiResponse::ok(to_member_info(member)),
+        Err(WorkspaceMemberRepoError::UserNotFound) => ApiResponse::err(
+            StatusCode::NOT_FOUND,
+            error_codes::RESOURCE_NOT_FOUND,
+            "用户不存在",
+        ),
+        Err(WorkspaceMemberRepoError::AlreadyMember) => ApiResponse::err(
+            StatusCode::CONFLICT,
+            error_codes::VALIDATION_ERROR,
+            "该用户已是工作区成员",
+        ),
+        Err(e) => {
+            warn!(error = %e, "邀请工作区成员失败");
+            database_error("邀请工作区成员失败")
+        }
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/workspaces/{workspace_id}/members/{member_user_id}",
+    params(
+        ("workspace_id" = String, Path, description = "工作区 ID"),
+        ("member_user_id" = String, Path, description = "成员用户 ID")
+    ),
+    request_body = UpdateWorkspaceMemberRequest,
+    responses(
+        (status = 200, description = "修改成功", body = ApiSuccess<WorkspaceMemberListResponse>),
+        (status = 400, description = "参数错误", body = ApiError),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 403, description = "仅所有者可修改成员角色", body = ApiError),
+        (status = 404, description = "工作区或成员不存在", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "workspaces"
+)]
+pub(crate) async fn update_member(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((workspace_id, member_user_id)): Path<(String, String)>,
+    current_user: CurrentUser,
+    Json(req): Json<UpdateWorkspaceMemberRequest>,
+) -> ApiResponse<WorkspaceMemberListResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    let user_id = &current_user.user_id;
+
+    let role = match parse_grantable_role(&req.role) {
+        Ok(role) => role,
+        Err(resp) => return resp,
+    };
+
+    info!(
+        correlation_id = %correlation_id,
+        user_id = %user_id,
+        workspace_id = %workspace_id,
+        member_user_id = %member_user_id,
+        role = role.as_str(),
+        "修改工作区成员角色"
+    );
+
+    if let Err(resp) = require_role(&state.db, &workspace_id, user_id, true).await {
+        return resp;
+    }
+
+    match WorkspaceMemberRepo::update_role(&state.db, &workspace_id, &member_user_id, role).await {
+        Ok(()) => {}
+        Err(WorkspaceMemberRepoError::NotFound) => return member_not_found(),
+        Err(e) => {
+            warn!(error = %e, "修改工作区成员角色失败");
+            return database_error("修改工作区成员角色失败");
+        }
+    }
+
+    match WorkspaceMemberRepo::list_by_workspace(&state.db, &workspace_id).await {
+        Ok(members) => ApiResponse::ok(WorkspaceMemberListResponse {
+            members: members.into_iter().map(to_member_info).collect(),
+        }),
+        Err(e) => {
+            warn!(error = %e, "查询工作区成员失败");
+            database_error("查询工作区成员失败")
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/workspaces/{workspace_id}/members/{member_user_id}",
+    params(
+        ("workspace_id" = String, Path, description = "工作区 ID"),
+        ("member_user_id" = String, Path, description = "成员用户 ID")
+    ),
+    responses(
+        (status = 200, description = "移除成功", body = ApiSuccess<RemoveWorkspaceMemberResponse>),
+        (status = 401, description = "未授权", body = ApiError),
+        (status = 403, description = "仅所有者可移除其他成员", body = ApiError),
+        (status = 404, description = "工作区或成员不存在", body = ApiError),
+        (status = 500, description = "服务器错误", body = ApiError)
+    ),
+    tag = "workspaces"
+)]
+pub(crate) async fn remove_member(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((workspace_id, member_user_id)): Path<(String, String)>,
+    current_user: CurrentUser,
+) -> ApiResponse<RemoveWorkspaceMemberResponse> {
+    let correlation_id = extract_correlation_id(&headers);
+    let user_id = &current_user.user_id;
+
+    info!(
+        correlation_id = %correlation_id,
+        user_id = %user_id,
+        workspace_id = %workspace_id,
+        member_user_id = %member_user_id,
+        "移除工作区成员"
+    );
+
+    // 成员可自行退出；移除他人需 owner
+    let leaving = member_user_id == *user_id;
+    if let Err(resp) = require_role(&state.db, &workspace_id, user_id, !leaving).await {
+        return resp;
+    }
+
+    match WorkspaceMemberRepo::remove(&state.db, &workspace_id, &member_user_id).await {
+        Ok(true) => ApiResponse::ok(RemoveWorkspaceMemberResponse {
+            message: if leaving {
+                "已退出工作区".to_string()
+            } else {
+                "成员已移除".to_string()
+            },
+        }),
+        Ok(false) if leaving => ApiResponse::err(
+            StatusCode::BAD_REQUEST,
+            error_codes::VALIDATION_ERROR,
+            "工作区所有者不能退出工作区",
+        ),
+        Ok(false) => member_not_found(),
+        Err(e) => {
+            warn!(error = %e, "移除工作区成员失败");
+            database_error("移除工作区成员失败")
+        }
+    }
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_members).post(add_member))
+        .route(
+            "/{member_user_id}",
+            patch(update_member).delete(remove_member),
+        )
+}
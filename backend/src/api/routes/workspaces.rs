@@ -12,9 +12,10 @@ use utoipa::ToSchema;
 use crate::api::middleware::CurrentUser;
 use crate::api::middleware::correlation_id::CORRELATION_ID_HEADER;
 use crate::api::response::{ApiError, ApiResponse, ApiSuccess};
-use crate::api::routes::{optimization_tasks, test_set_templates, test_sets};
+use crate::api::routes::{optimization_tasks, test_set_templates, test_sets, workspace_members};
 use crate::api::state::AppState;
-use crate::infra::db::repositories::{WorkspaceRepo, WorkspaceRepoError};
+use crate::domain::models::Workspace;
+use crate::infra::db::repositories::{WorkspaceRepo, WorkspaceRepoError, WorkspaceRole};
 use crate::shared::error_codes;
 
 #[derive(Debug, Deserialize, ToSchema, TS)]
@@ -37,12 +38,27 @@ pub struct WorkspaceResponse {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
+    /// 当前用户在该工作区中的角色：owner / editor / viewer
+    pub role: String,
     #[ts(type = "number")]
     pub created_at: i64,
     #[ts(type = "number")]
     pub updated_at: i64,
 }
 
+impl WorkspaceResponse {
+    fn new(workspace: Workspace, role: WorkspaceRole) -> Self {
+        Self {
+            id: workspace.id,
+            name: workspace.name,
+            description: workspace.description,
+            role: role.as_str().to_string(),
+            created_at: workspace.created_at,
+            updated_at: workspace.updated_at,
+        }
+    }
+}
+
 /// 工作区列表响应类型（前端类型别名）
 #[allow(dead_code)]
 #[derive(TS)]
@@ -125,13 +141,7 @@ pub(crate) async fn create_workspace(
             }
         };
 
-    ApiResponse::ok(WorkspaceResponse {
-        id: workspace.id,
-        name: workspace.name,
-        description: workspace.description,
-        created_at: workspace.created_at,
-        updated_at: workspace.updated_at,
-    })
+    ApiResponse::ok(WorkspaceResponse::new(workspace, WorkspaceRole::Owner))
 }
 
 #[utoipa::path(
@@ -154,7 +164,7 @@ pub(crate) async fn list_workspaces(
 
     info!(correlation_id = %correlation_id, user_id = %user_id, "列出工作区");
 
-    let workspaces = match WorkspaceRepo::find_all_by_user(&state.db, user_id).await {
+    let workspaces = match WorkspaceRepo::find_all_with_roles_by_user(&state.db, user_id).await {
         Ok(ws) => ws,
         Err(e) => {
             warn!(error = %e, "查询工作区列表失败");
@@ -169,13 +179,7 @@ pub(crate) async fn list_workspaces(
     ApiResponse::ok(
         workspaces
             .into_iter()
-            .map(|w| WorkspaceResponse {
-                id: w.id,
-                name: w.name,
-                description: w.description,
-                created_at: w.created_at,
-                updated_at: w.updated_at,
-            })
+            .map(|(workspace, role)| WorkspaceResponse::new(workspace, role))
             .collect(),
     )
 }
@@ -205,26 +209,21 @@ pub(crate) async fn get_workspace(
 
     info!(correlation_id = %correlation_id, user_id = %user_id, workspace_id = %workspace_id, "获取工作区");
 
-    let workspace = match WorkspaceRepo::find_by_id(&state.db, &workspace_id, user_id).await {
-        Ok(w) => w,
-        Err(WorkspaceRepoError::NotFound) => return workspace_not_found(),
-        Err(e) => {
-            warn!(error = %e, "查询工作区失败");
-            return ApiResponse::err(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                error_codes::DATABASE_ERROR,
-                "查询工作区失败",
-            );
-        }
-    };
+    let (workspace, role) =
+        match WorkspaceRepo::find_with_role_by_id(&state.db, &workspace_id, user_id).await {
+            Ok(found) => found,
+            Err(WorkspaceRepoError::NotFound) => return workspace_not_found(),
+            Err(e) => {
+                warn!(error = %e, "查询工作区失败");
+                return ApiResponse::err(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    error_codes::DATABASE_ERROR,
+                    "查询工作区失败",
+                );
+            }
+        };
 
-    ApiResponse::ok(WorkspaceResponse {
-        id: workspace.id,
-        name: workspace.name,
-        description: workspace.description,
-        created_at: workspace.created_at,
-        updated_at: workspace.updated_at,
-    })
+    ApiResponse::ok(WorkspaceResponse::new(workspace, role))
 }
 
 #[utoipa::path(
@@ -236,6 +235,7 @@ pub(crate) async fn get_workspace(
     responses(
         (status = 200, description = "删除成功", body = ApiSuccess<DeleteWorkspaceResponse>),
         (status = 401, description = "未授权", body = ApiError),
+        (status = 403, description = "仅所有者可删除", body = ApiError),
         (status = 404, description = "工作区不存在", body = ApiError),
         (status = 500, description = "服务器错误", body = ApiError)
     ),
@@ -252,6 +252,26 @@ pub(crate) async fn delete_workspace(
 
     info!(correlation_id = %correlation_id, user_id = %user_id, workspace_id = %workspace_id, "删除工作区");
 
+    // 共享成员不能删除工作区
+    match WorkspaceRepo::find_with_role_by_id(&state.db, &workspace_id, user_id).await {
+        Ok((_, role)) if !role.can_manage() => {
+            return ApiResponse::err(
+                StatusCode::FORBIDDEN,
+                error_codes::FORBIDDEN,
+                "仅工作区所有者可删除工作区",
+            );
+        }
+        Ok(_) | Err(WorkspaceRepoError::NotFound) => {}
+        Err(e) => {
+            warn!(error = %e, "查询工作区失败");
+            return ApiResponse::err(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::DATABASE_ERROR,
+                "删除工作区失败",
+            );
+        }
+    }
+
     match WorkspaceRepo::delete(&state.db, &workspace_id, user_id).await {
         Ok(true) => ApiResponse::ok(DeleteWorkspaceResponse {
             message: "删除成功".to_string(),
@@ -281,4 +301,5 @@ pub fn router() -> Router<AppState> {
             "/{workspace_id}/test-set-templates",
             test_set_templates::router(),
         )
+        .nest("/{workspace_id}/members", workspace_members::router())
 }
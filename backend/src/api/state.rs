@@ -2,13 +2,13 @@ use reqwest::Client;
 use sqlx::SqlitePool;
 use std::sync::Arc;
 
-use crate::api::middleware::{LoginAttemptStore, SessionStore};
+use crate::api::middleware::{LoginAttemptStore, RateLimiter, SessionStore};
 use crate::infra::external::api_key_manager::ApiKeyManager;
 use crate::shared::config::AppConfig;
 
 /// 应用状态
 ///
-/// 包含共享的数据库连接池、HTTP 客户端、API Key 管理器和会话存储
+/// 包含共享的数据库连接池、HTTP 客户端、API Key 管理器、会话存储与接口限流器
 #[derive(Clone)]
 pub struct AppState {
     /// 数据库连接池
@@ -23,4 +23,6 @@ pub struct AppState {
     pub session_store: SessionStore,
     /// 登录尝试存储（内存）
     pub login_attempt_store: LoginAttemptStore,
+    /// 按用户 + 路由类别的接口限流器（内存）
+    pub rate_limiter: RateLimiter,
 }
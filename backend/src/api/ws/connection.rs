@@ -10,12 +10,17 @@ use futures_util::{SinkExt, StreamExt};
 use serde::Deserialize;
 use tracing::{info, warn};
 
+use crate::api::middleware::access_token::token_allows_task;
+use crate::api::middleware::{AccessTokenGrant, AuditContext};
 use crate::api::state::AppState;
 use crate::core::iteration_engine::events::record_event_async;
 use crate::core::iteration_engine::pause_state::global_pause_registry;
 use crate::domain::models::{Actor, EventType};
 use crate::domain::types::RunControlState;
-use crate::infra::db::repositories::{OptimizationTaskRepo, OptimizationTaskRepoError};
+use crate::infra::db::repositories::{
+    AuditEventType, NewAuditEvent, OptimizationTaskRepo, OptimizationTaskRepoError,
+    WorkspaceMemberRepo, WorkspaceMemberRepoError,
+};
 use crate::shared::ws::{
     ArtifactGetAckPayload, ArtifactGetPayload, ArtifactUpdateAckPayload, ArtifactUpdatePayload,
     ArtifactUpdatedPayload, CMD_ARTIFACT_GET, CMD_ARTIFACT_UPDATE, CMD_GUIDANCE_SEND,
@@ -58,15 +63,83 @@ fn extract_token(headers: &HeaderMap, query: &WsQuery) -> Option<String> {
         .filter(|token| !token.is_empty())
 }
 
+/// 任务访问校验失败
+#[derive(Debug, thiserror::Error)]
+enum TaskAccessError {
+    #[error(transparent)]
+    Repo(#[from] OptimizationTaskRepoError),
+    #[error(transparent)]
+    Member(#[from] WorkspaceMemberRepoError),
+    #[error("个人访问令牌无权执行该操作")]
+    TokenScope,
+    #[error("只读成员无权执行该操作")]
+    ReadOnlyMember,
+}
+
+/// 校验任务访问权限：需为任务所属工作区成员，修改类命令（`write`）要求 owner / editor；
+/// 使用个人访问令牌时同时校验令牌权限范围
 async fn validate_task_ownership(
     state: &AppState,
     user_id: &str,
+    access_token: Option<&AccessTokenGrant>,
     task_id: &str,
-) -> Result<(), OptimizationTaskRepoError> {
-    OptimizationTaskRepo::find_by_id_for_user(&state.db, user_id, task_id).await?;
+    write: bool,
+) -> Result<(), TaskAccessError> {
+    let task = OptimizationTaskRepo::find_by_id_for_user(&state.db, user_id, task_id).await?;
+    if let Some(grant) = access_token
+        && !token_allows_task(grant, &task.workspace_id, write)
+    {
+        return Err(TaskAccessError::TokenScope);
+    }
+    if write
+        && !WorkspaceMemberRepo::find_role(&state.db, &task.workspace_id, user_id)
+            .await?
+            .is_some_and(|role| role.can_write())
+    {
+        return Err(TaskAccessError::ReadOnlyMember);
+    }
     Ok(())
 }
 
+impl TaskAccessError {
+    /// 审计用拒绝原因；数据库错误不属于访问拒绝，返回 None
+    fn denial_reason(&self) -> Option<&'static str> {
+        match self {
+            TaskAccessError::Repo(OptimizationTaskRepoError::NotFound) => {
+                Some("task_not_found_or_forbidden")
+            }
+            TaskAccessError::TokenScope => Some("token_scope"),
+            TaskAccessError::ReadOnlyMember => Some("read_only_member"),
+            TaskAccessError::Repo(_) | TaskAccessError::Member(_) => None,
+        }
+    }
+}
+
+/// 记录任务访问拒绝审计事件
+async fn audit_task_access_denied(
+    state: &AppState,
+    audit: &AuditContext,
+    user_id: &str,
+    task_id: &str,
+    command: &str,
+    correlation_id: &str,
+    err: &TaskAccessError,
+) {
+    let Some(reason) = err.denial_reason() else {
+        return;
+    };
+    audit
+        .with_correlation_id(correlation_id)
+        .record(
+            &state.db,
+            NewAuditEvent::new(AuditEventType::TaskAccessDenied)
+                .actor(user_id)
+                .target("task", task_id)
+                .details(serde_json::json!({ "command": command, "reason": reason })),
+        )
+        .await;
+}
+
 fn ack_event_type(event_type: &str) -> Option<&'static str> {
     match event_type {
         CMD_TASK_PAUSE => Some(EVT_TASK_PAUSE_ACK),
@@ -81,6 +154,8 @@ fn ack_event_type(event_type: &str) -> Option<&'static str> {
 async fn handle_command(
     state: &AppState,
     user_id: &str,
+    access_token: Option<&AccessTokenGrant>,
+    audit: &AuditContext,
     text: &str,
     ack_sender: &tokio::sync::mpsc::UnboundedSender<Message>,
 ) {
@@ -96,15 +171,15 @@ async fn handle_command(
     // 根据命令类型分发处理
     match base.event_type.as_str() {
         CMD_ARTIFACT_GET => {
-            handle_artifact_get(state, user_id, text, ack_sender).await;
+            handle_artifact_get(state, user_id, access_token, audit, text, ack_sender).await;
             return;
         }
         CMD_ARTIFACT_UPDATE => {
-            handle_artifact_update(state, user_id, text, ack_sender).await;
+            handle_artifact_update(state, user_id, access_token, audit, text, ack_sender).await;
             return;
         }
         CMD_GUIDANCE_SEND => {
-            handle_guidance_send(state, user_id, text, ack_sender).await;
+            handle_guidance_send(state, user_id, access_token, audit, text, ack_sender).await;
             return;
         }
         _ => {}
@@ -145,7 +220,7 @@ async fn handle_command(
         return;
     }
 
-    if let Err(err) = validate_task_ownership(state, user_id, &task_id).await {
+    if let Err(err) = validate_task_ownership(state, user_id, access_token, &task_id, true).await {
         warn!(
             correlation_id = %cmd.correlation_id,
             user_id = %user_id,
@@ -153,6 +228,16 @@ async fn handle_command(
             error = %err,
             "WS command rejected: task ownership check failed"
         );
+        audit_task_access_denied(
+            state,
+            audit,
+            user_id,
+            &task_id,
+            cmd.event_type.as_str(),
+            &cmd.correlation_id,
+            &err,
+        )
+        .await;
         if let Some(event_type) = ack_event_type(cmd.event_type.as_str()) {
             let ack = TaskControlAckPayload {
                 task_id: task_id.clone(),
@@ -330,6 +415,8 @@ async fn handle_command(
 async fn handle_artifact_get(
     state: &AppState,
     user_id: &str,
+    access_token: Option<&AccessTokenGrant>,
+    audit: &AuditContext,
     text: &str,
     ack_sender: &tokio::sync::mpsc::UnboundedSender<Message>,
 ) {
@@ -362,7 +449,7 @@ async fn handle_artifact_get(
     }
 
     // 权限校验
-    if let Err(err) = validate_task_ownership(state, user_id, &task_id).await {
+    if let Err(err) = validate_task_ownership(state, user_id, access_token, &task_id, false).await {
         warn!(
             correlation_id = %cmd.correlation_id,
             user_id = %user_id,
@@ -370,6 +457,16 @@ async fn handle_artifact_get(
             error = %err,
             "artifact:get rejected: task ownership check failed"
         );
+        audit_task_access_denied(
+            state,
+            audit,
+            user_id,
+            &task_id,
+            CMD_ARTIFACT_GET,
+            &cmd.correlation_id,
+            &err,
+        )
+        .await;
         let ack = ArtifactGetAckPayload {
             task_id: task_id.clone(),
             ok: false,
@@ -413,6 +510,8 @@ async fn handle_artifact_get(
 async fn handle_artifact_update(
     state: &AppState,
     user_id: &str,
+    access_token: Option<&AccessTokenGrant>,
+    audit: &AuditContext,
     text: &str,
     ack_sender: &tokio::sync::mpsc::UnboundedSender<Message>,
 ) {
@@ -446,7 +545,7 @@ async fn handle_artifact_update(
     }
 
     // 权限校验
-    if let Err(err) = validate_task_ownership(state, user_id, &task_id).await {
+    if let Err(err) = validate_task_ownership(state, user_id, access_token, &task_id, true).await {
         warn!(
             correlation_id = %cmd.correlation_id,
             user_id = %user_id,
@@ -454,6 +553,16 @@ async fn handle_artifact_update(
             error = %err,
             "artifact:update rejected: task ownership check failed"
         );
+        audit_task_access_denied(
+            state,
+            audit,
+            user_id,
+            &task_id,
+            CMD_ARTIFACT_UPDATE,
+            &cmd.correlation_id,
+            &err,
+        )
+        .await;
         let ack = ArtifactUpdateAckPayload {
             task_id: task_id.clone(),
             ok: false,
@@ -573,6 +682,8 @@ async fn handle_artifact_update(
 async fn handle_guidance_send(
     state: &AppState,
     user_id: &str,
+    access_token: Option<&AccessTokenGrant>,
+    audit: &AuditContext,
     text: &str,
     ack_sender: &tokio::sync::mpsc::UnboundedSender<Message>,
 ) {
@@ -606,7 +717,7 @@ async fn handle_guidance_send(
     }
 
     // 权限校验
-    if let Err(err) = validate_task_ownership(state, user_id, &task_id).await {
+    if let Err(err) = validate_task_ownership(state, user_id, access_token, &task_id, true).await {
         warn!(
             correlation_id = %cmd.correlation_id,
             user_id = %user_id,
@@ -614,6 +725,16 @@ async fn handle_guidance_send(
             error = %err,
             "guidance:send rejected: task ownership check failed"
         );
+        audit_task_access_denied(
+            state,
+            audit,
+            user_id,
+            &task_id,
+            CMD_GUIDANCE_SEND,
+            &cmd.correlation_id,
+            &err,
+        )
+        .await;
         let ack = GuidanceSendAckPayload {
             task_id: task_id.clone(),
             ok: false,
@@ -774,7 +895,13 @@ async fn send_paused_snapshots(sender: &tokio::sync::mpsc::UnboundedSender<Messa
     true
 }
 
-async fn handle_socket(socket: WebSocket, state: AppState, user_id: String) {
+async fn handle_socket(
+    socket: WebSocket,
+    state: AppState,
+    user_id: String,
+    access_token: Option<AccessTokenGrant>,
+    audit: AuditContext,
+) {
     let (mut ws_sender, mut ws_receiver) = socket.split();
     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
 
@@ -808,7 +935,9 @@ async fn handle_socket(socket: WebSocket, state: AppState, user_id: String) {
 
     while let Some(Ok(msg)) = ws_receiver.next().await {
         match msg {
-            Message::Text(text) => handle_command(&state, &user_id, &text, &tx).await,
+            Message::Text(text) => {
+                handle_command(&state, &user_id, access_token.as_ref(), &audit, &text, &tx).await
+            }
             Message::Close(_) => break,
             _ => {}
         }
@@ -822,6 +951,7 @@ async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
     headers: HeaderMap,
+    audit: AuditContext,
     Query(query): Query<WsQuery>,
 ) -> Response {
     let Some(token) = extract_token(&headers, &query) else {
@@ -834,7 +964,8 @@ async fn ws_handler(
     };
 
     let user_id = session.user_id;
-    ws.on_upgrade(move |socket| handle_socket(socket, state, user_id))
+    let access_token = session.access_token;
+    ws.on_upgrade(move |socket| handle_socket(socket, state, user_id, access_token, audit))
         .into_response()
 }
 
@@ -1,11 +1,19 @@
 use std::path::PathBuf;
 
 use prompt_faster::api::response::{ApiError, ApiSuccess, ErrorDetail, PaginationMeta};
+use prompt_faster::api::routes::access_tokens::{
+    AccessTokenInfo, AccessTokenListResponse, CreateAccessTokenRequest, CreateAccessTokenResponse,
+    RevokeAccessTokenResponse,
+};
+use prompt_faster::api::routes::audit_events::{AuditEventInfo, AuditEventListResponse};
 use prompt_faster::api::routes::auth::{
-    ConfigResponse, CredentialInput, GenericLlmCredentialInput, GenericLlmModelsResponse,
-    SaveConfigRequest, SaveConfigResponse, TeacherSettingsInput, TeacherSettingsResponse,
-    TestDifyConnectionRequest, TestGenericLlmConnectionRequest,
+    ConfigResponse, CreateCredentialRequest, CredentialInput, CredentialListResponse,
+    CredentialProfileResponse, DeleteCredentialResponse, DeleteLlmProviderResponse,
+    GenericLlmCredentialInput, GenericLlmModelsResponse, LlmProvidersResponse, SaveConfigRequest,
+    SaveConfigResponse, TeacherSettingsInput, TeacherSettingsResponse, TestDifyConnectionRequest,
+    TestGenericLlmConnectionRequest, UpdateCredentialRequest,
 };
+use prompt_faster::api::routes::config_diagnostics::ConfigDiagnosticsResponse;
 use prompt_faster::api::routes::dify::{
     DifyBinding, DifyBindingSource, DifyConfig, SaveDifyConfigRequest, SaveDifyConfigResponse,
 };
@@ -18,6 +26,7 @@ use prompt_faster::api::routes::optimization_tasks::{
     CreateOptimizationTaskRequest, MetaOptimizationTaskHint, OptimizationTaskListItemResponse,
     OptimizationTaskResponse, UpdateOptimizationTaskConfigRequest,
 };
+use prompt_faster::api::routes::rate_limits::RateLimitStatsResponse;
 use prompt_faster::api::routes::test_set_templates::{
     SaveAsTemplateRequest, TestSetTemplateListItemResponse, TestSetTemplateResponse,
 };
@@ -25,39 +34,49 @@ use prompt_faster::api::routes::test_sets::{
     CreateTestSetRequest, DeleteTestSetResponse, TestSetListItemResponse, TestSetResponse,
     UpdateTestSetRequest,
 };
+use prompt_faster::api::routes::totp::{
+    AdminResetTotpRequest, DisableTotpResponse, TotpEnableRequest, TotpReauthRequest,
+    TotpRecoveryCodesResponse, TotpSetupRequest, TotpSetupResponse, TotpStatusResponse,
+};
 use prompt_faster::api::routes::user_auth::{
-    AuthResponse, LoginRequest, LogoutResponse, RegisterRequest, SystemStatusResponse, UserInfo,
+    AuthResponse, ChangePasswordRequest, ChangePasswordResponse, LoginRequest, LogoutResponse,
+    RegisterRequest, RevokeSessionsResponse, SessionInfo, SessionListResponse,
+    SystemStatusResponse, UserInfo,
+};
+use prompt_faster::api::routes::workspace_members::{
+    AddWorkspaceMemberRequest, RemoveWorkspaceMemberResponse, UpdateWorkspaceMemberRequest,
+    WorkspaceMemberInfo, WorkspaceMemberListResponse,
 };
 use prompt_faster::api::routes::workspaces::{
     CreateWorkspaceRequest, DeleteWorkspaceResponse, WorkspaceResponse,
 };
 use prompt_faster::api::ws::events::{
     ArtifactGetAckPayload, ArtifactGetPayload, ArtifactUpdateAckPayload, ArtifactUpdatePayload,
-    ArtifactUpdatedPayload, GuidanceAppliedPayload, GuidanceSendAckPayload, GuidanceSendPayload,
-    GuidanceSentPayload, IterationPausedPayload, IterationResumedPayload, TaskControlAckPayload,
-    TaskControlPayload, TaskTerminatedPayload,
+    ArtifactUpdatedPayload, GuidanceAppliedPayload, GuidanceRequestedPayload,
+    GuidanceSendAckPayload, GuidanceSendPayload, GuidanceSentPayload, IterationPausedPayload,
+    IterationResumedPayload, TaskControlAckPayload, TaskControlPayload, TaskTerminatedPayload,
 };
 use prompt_faster::domain::models::{
     Actor, BaselineComparison, BranchInfo, CaseComparisonResult, Checkpoint,
     CheckpointListResponse, CheckpointResponse, CheckpointSummary, CompareSummary,
     ConflictResolutionRecord, ConnectivityResponse, ConnectivityStatus, Constraint,
-    CreateTeacherPromptInput, DataSplit, DiagnosticReport, DiagnosticSummary, DiffSegment,
-    DiffSegmentType, DimensionScore, DiversityAnalysisResult, DiversityBaseline, DiversityConfig,
-    DiversityMetrics, DiversitySuggestion, DiversityTrend, DiversityWarning, DiversityWarningLevel,
-    EvaluationResult, EventType, ExecutionResult, ExecutionTargetType, ExportResultResponse,
-    FailedCaseDetail, FailedCaseSummary, FailurePoint, FailureReasonEntry, HistoryEvent,
-    HistoryEventResponse, HistoryExportData, Iteration, IterationExportEntry, IterationState,
-    IterationSummaryEntry, LineageType, MetaOptimizationOverview, MetaOptimizationTaskSummary,
-    OptimizationTaskEntity, OptimizationTaskMode, OptimizationTaskStatus, OutputLength,
-    PassRateSummary, PromptCompareRequest, PromptCompareResponse, PromptPreviewRequest,
-    PromptPreviewResponse, PromptPreviewResult, PromptValidationRequest, PromptValidationResult,
-    QualityDimension, RecoveryMetrics, RecoveryRequest, RecoveryResponse, ResultExportFormat,
-    RollbackRequest, RollbackResponse, Rule, RuleConflict, RuleConflictType, RuleIR,
-    RuleMergeRecord, RuleSystem, RuleTags, Severity, TaskExportMeta, TaskHistoryResponse,
-    TaskReference, TaskResultView, TeacherPrompt, TeacherPromptStats, TeacherPromptVersion,
-    TestCase, TestSet, TimelineEntry, TimelineEntryType, TimelineResponse, TokenUsage,
-    TurningPoint, TurningPointType, UnfinishedTask, UnfinishedTasksResponse, User,
-    VersionCompareResult, Workspace,
+    ConversationTurn, CreateTeacherPromptInput, DataSplit, DiagnosticReport, DiagnosticSummary,
+    DiffSegment, DiffSegmentType, DimensionScore, DiversityAnalysisResult, DiversityBaseline,
+    DiversityConfig, DiversityMetrics, DiversitySuggestion, DiversityTrend, DiversityWarning,
+    DiversityWarningLevel, EvaluationResult, EventType, ExecutionResult, ExecutionTargetType,
+    ExportResultResponse, FailedCaseDetail, FailedCaseSummary, FailurePoint, FailureReasonEntry,
+    HistoryEvent, HistoryEventResponse, HistoryExportData, Iteration, IterationExportEntry,
+    IterationState, IterationSummaryEntry, LineageType, LlmProviderSpec, MetaOptimizationOverview,
+    MetaOptimizationTaskSummary, OptimizationTaskEntity, OptimizationTaskMode,
+    OptimizationTaskStatus, OutputLength, PassRateSummary, PromptCompareRequest,
+    PromptCompareResponse, PromptPreviewRequest, PromptPreviewResponse, PromptPreviewResult,
+    PromptValidationRequest, PromptValidationResult, QualityDimension, RecoveryMetrics,
+    RecoveryRequest, RecoveryResponse, ResultExportFormat, RollbackRequest, RollbackResponse, Rule,
+    RuleConflict, RuleConflictType, RuleIR, RuleMergeRecord, RuleSystem, RuleTags, Severity,
+    TaskExportMeta, TaskHistoryResponse, TaskReference, TaskResultView, TeacherPrompt,
+    TeacherPromptStats, TeacherPromptVersion, TestCase, TestSet, TimelineEntry, TimelineEntryType,
+    TimelineResponse, TokenUsage, TurningPoint, TurningPointType, UnfinishedTask,
+    UnfinishedTasksResponse, User, VersionCompareResult, Workspace,
 };
 use prompt_faster::domain::types::{
     AddRoundsRequest, AddRoundsResponse, ArtifactSource, CandidatePrompt,
@@ -97,6 +116,28 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     AuthResponse::export_all_to(&out_dir)?;
     UserInfo::export_all_to(&out_dir)?;
     LogoutResponse::export_all_to(&out_dir)?;
+    ChangePasswordRequest::export_all_to(&out_dir)?;
+    ChangePasswordResponse::export_all_to(&out_dir)?;
+    SessionInfo::export_all_to(&out_dir)?;
+    SessionListResponse::export_all_to(&out_dir)?;
+    RevokeSessionsResponse::export_all_to(&out_dir)?;
+    CreateAccessTokenRequest::export_all_to(&out_dir)?;
+    AccessTokenInfo::export_all_to(&out_dir)?;
+    CreateAccessTokenResponse::export_all_to(&out_dir)?;
+    AccessTokenListResponse::export_all_to(&out_dir)?;
+    RevokeAccessTokenResponse::export_all_to(&out_dir)?;
+    TotpStatusResponse::export_all_to(&out_dir)?;
+    TotpSetupRequest::export_all_to(&out_dir)?;
+    TotpSetupResponse::export_all_to(&out_dir)?;
+    TotpEnableRequest::export_all_to(&out_dir)?;
+    TotpRecoveryCodesResponse::export_all_to(&out_dir)?;
+    TotpReauthRequest::export_all_to(&out_dir)?;
+    AdminResetTotpRequest::export_all_to(&out_dir)?;
+    DisableTotpResponse::export_all_to(&out_dir)?;
+    AuditEventInfo::export_all_to(&out_dir)?;
+    AuditEventListResponse::export_all_to(&out_dir)?;
+    ConfigDiagnosticsResponse::export_all_to(&out_dir)?;
+    RateLimitStatsResponse::export_all_to(&out_dir)?;
     SystemStatusResponse::export_all_to(&out_dir)?;
 
     // 配置管理
@@ -108,11 +149,24 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     TeacherSettingsResponse::export_all_to(&out_dir)?;
     SaveConfigResponse::export_all_to(&out_dir)?;
     GenericLlmModelsResponse::export_all_to(&out_dir)?;
+    LlmProvidersResponse::export_all_to(&out_dir)?;
+    DeleteLlmProviderResponse::export_all_to(&out_dir)?;
+    CredentialProfileResponse::export_all_to(&out_dir)?;
+    CredentialListResponse::export_all_to(&out_dir)?;
+    CreateCredentialRequest::export_all_to(&out_dir)?;
+    UpdateCredentialRequest::export_all_to(&out_dir)?;
+    DeleteCredentialResponse::export_all_to(&out_dir)?;
+    LlmProviderSpec::export_all_to(&out_dir)?;
 
     // 工作区
     CreateWorkspaceRequest::export_all_to(&out_dir)?;
     WorkspaceResponse::export_all_to(&out_dir)?;
     DeleteWorkspaceResponse::export_all_to(&out_dir)?;
+    WorkspaceMemberInfo::export_all_to(&out_dir)?;
+    WorkspaceMemberListResponse::export_all_to(&out_dir)?;
+    AddWorkspaceMemberRequest::export_all_to(&out_dir)?;
+    UpdateWorkspaceMemberRequest::export_all_to(&out_dir)?;
+    RemoveWorkspaceMemberResponse::export_all_to(&out_dir)?;
 
     // 测试集
     CreateTestSetRequest::export_all_to(&out_dir)?;
@@ -152,6 +206,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Workspace::export_all_to(&out_dir)?;
     User::export_all_to(&out_dir)?;
     TestCase::export_all_to(&out_dir)?;
+    ConversationTurn::export_all_to(&out_dir)?;
     TestSet::export_all_to(&out_dir)?;
     OptimizationTaskEntity::export_all_to(&out_dir)?;
     Iteration::export_all_to(&out_dir)?;
@@ -257,6 +312,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     GuidanceSendAckPayload::export_all_to(&out_dir)?;
     GuidanceSentPayload::export_all_to(&out_dir)?;
     GuidanceAppliedPayload::export_all_to(&out_dir)?;
+    GuidanceRequestedPayload::export_all_to(&out_dir)?;
     TaskTerminatedPayload::export_all_to(&out_dir)?;
 
     // Artifact 相关类型
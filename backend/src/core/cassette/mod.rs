@@ -0,0 +1,382 @@
+//! Cassette（录制/回放）：为 ExecutionTarget / TeacherModel 提供离线可复现的请求-响应存档
+//!
+//! - Record：透传到真实实现，并将每一对请求/响应追加写入 cassette 文件（JSONL）
+//! - Replay：只从 cassette 读取，不访问网络；未命中直接报错（不降级到真实实现）。
+//!   目录缺失或文件加载失败时所有调用均报错，同样不降级；只有 Record 模式会在配置无效时关闭
+//!
+//! 文件格式：每行一个 `CassetteRecord`；Prompt 只保存 sha256 哈希，不保存原文。
+//! 同一 key 多次出现时按录制顺序回放，耗尽后视为未命中（暴露多出的调用）。
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// Cassette 模式环境变量：`record` / `replay`（其他值或未设置表示关闭）。
+pub const CASSETTE_MODE_ENV: &str = "PROMPT_FASTER_CASSETTE_MODE";
+/// Cassette 目录环境变量（目录下按类型分文件存放）。
+pub const CASSETTE_DIR_ENV: &str = "PROMPT_FASTER_CASSETTE_DIR";
+
+pub const EXECUTION_TARGET_CASSETTE_FILE: &str = "execution_target.jsonl";
+pub const TEACHER_MODEL_CASSETTE_FILE: &str = "teacher_model.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+impl CassetteMode {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "record" => Some(Self::Record),
+            "replay" => Some(Self::Replay),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CassetteError {
+    #[error("cassette IO 错误（{path}）: {message}")]
+    Io { path: String, message: String },
+
+    #[error("cassette 记录解析失败（{path}:{line}）: {message}")]
+    Parse {
+        path: String,
+        line: usize,
+        message: String,
+    },
+
+    #[error("cassette 未命中（kind={kind}, key={key}）：请先在 record 模式下录制")]
+    Miss { kind: String, key: String },
+
+    #[error("cassette 模式不匹配：当前为 {0:?}")]
+    ModeMismatch(CassetteMode),
+
+    #[error("cassette 回放不可用（{0}），已拒绝调用")]
+    Unavailable(String),
+}
+
+/// cassette 文件中的单行记录。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CassetteRecord<T> {
+    pub kind: String,
+    pub key: String,
+    pub entry: T,
+}
+
+#[derive(Debug)]
+pub struct Cassette {
+    mode: CassetteMode,
+    path: PathBuf,
+    writer: Mutex<Option<File>>,
+    entries: Mutex<HashMap<(String, String), VecDeque<serde_json::Value>>>,
+    /// Replay 模式初始化失败的原因（设置后所有回放均报错）
+    unavailable: Option<String>,
+}
+
+impl Cassette {
+    /// 打开 cassette：Record 模式截断重写；Replay 模式一次性加载（文件不存在视为空）。
+    pub fn open(mode: CassetteMode, path: impl Into<PathBuf>) -> Result<Self, CassetteError> {
+        let path = path.into();
+        let io_err = |e: std::io::Error| CassetteError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        };
+
+        let mut writer = None;
+        let mut entries = HashMap::new();
+        match mode {
+            CassetteMode::Record => {
+                if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(parent).map_err(io_err)?;
+                }
+                writer = Some(File::create(&path).map_err(io_err)?);
+            }
+            CassetteMode::Replay => {
+                if path.exists() {
+                    entries = load_entries(&path)?;
+                } else {
+                    tracing::error!(path = %path.display(), "cassette 文件不存在，所有回放请求都将未命中");
+                }
+            }
+        }
+
+        Ok(Self {
+            mode,
+            path,
+            writer: Mutex::new(writer),
+            entries: Mutex::new(entries),
+            unavailable: None,
+        })
+    }
+
+    /// 初始化失败的 Replay cassette：每次回放都返回 `CassetteError::Unavailable`。
+    pub fn unavailable(path: impl Into<PathBuf>, reason: impl Into<String>) -> Self {
+        Self {
+            mode: CassetteMode::Replay,
+            path: path.into(),
+            writer: Mutex::new(None),
+            entries: Mutex::new(HashMap::new()),
+            unavailable: Some(reason.into()),
+        }
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 追加一条记录（仅 Record 模式）。
+    pub fn record<T: Serialize>(
+        &self,
+        kind: &str,
+        key: &str,
+        entry: &T,
+    ) -> Result<(), CassetteError> {
+        if self.mode != CassetteMode::Record {
+            return Err(CassetteError::ModeMismatch(self.mode));
+        }
+        let record = CassetteRecord {
+            kind: kind.to_string(),
+            key: key.to_string(),
+            entry,
+        };
+        let mut line = serde_json::to_string(&record).map_err(|e| CassetteError::Io {
+            path: self.path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        line.push('\n');
+
+        let mut guard = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(file) = guard.as_mut() else {
+            return Err(CassetteError::ModeMismatch(self.mode));
+        };
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.flush())
+            .map_err(|e| CassetteError::Io {
+                path: self.path.display().to_string(),
+                message: e.to_string(),
+            })
+    }
+
+    /// 取出一条记录（仅 Replay 模式）；未命中或该 key 已耗尽返回 `CassetteError::Miss`。
+    pub fn replay<T: DeserializeOwned>(&self, kind: &str, key: &str) -> Result<T, CassetteError> {
+        if self.mode != CassetteMode::Replay {
+            return Err(CassetteError::ModeMismatch(self.mode));
+        }
+        if let Some(reason) = &self.unavailable {
+            return Err(CassetteError::Unavailable(reason.clone()));
+        }
+        let miss = || CassetteError::Miss {
+            kind: kind.to_string(),
+            key: key.to_string(),
+        };
+
+        let value = {
+            let mut guard = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            guard
+                .get_mut(&(kind.to_string(), key.to_string()))
+                .and_then(VecDeque::pop_front)
+                .ok_or_else(miss)?
+        };
+
+        serde_json::from_value(value).map_err(|e| CassetteError::Parse {
+            path: self.path.display().to_string(),
+            line: 0,
+            message: e.to_string(),
+        })
+    }
+}
+
+fn load_entries(
+    path: &Path,
+) -> Result<HashMap<(String, String), VecDeque<serde_json::Value>>, CassetteError> {
+    let file = File::open(path).map_err(|e| CassetteError::Io {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })?;
+
+    let mut entries: HashMap<(String, String), VecDeque<serde_json::Value>> = HashMap::new();
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| CassetteError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: CassetteRecord<serde_json::Value> =
+            serde_json::from_str(&line).map_err(|e| CassetteError::Parse {
+                path: path.display().to_string(),
+                line: idx + 1,
+                message: e.to_string(),
+            })?;
+        entries
+            .entry((record.kind, record.key))
+            .or_default()
+            .push_back(record.entry);
+    }
+    Ok(entries)
+}
+
+/// 计算 cassette key（各部分带长度前缀，避免拼接歧义）。
+pub fn cassette_key(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Prompt 哈希（cassette 中只保存哈希，不保存 Prompt 原文）。
+pub fn prompt_hash(prompt: &str) -> String {
+    format!("sha256:{:x}", Sha256::digest(prompt.as_bytes()))
+}
+
+static CASSETTES: OnceLock<Mutex<HashMap<PathBuf, Arc<Cassette>>>> = OnceLock::new();
+
+/// 按路径复用同一 cassette（同一进程内多个实例共享写入句柄/回放队列）。
+pub fn shared_cassette(
+    mode: CassetteMode,
+    path: impl Into<PathBuf>,
+) -> Result<Arc<Cassette>, CassetteError> {
+    let path = path.into();
+    let registry = CASSETTES.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut guard = registry.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = guard.get(&path).filter(|c| c.mode() == mode) {
+        return Ok(Arc::clone(existing));
+    }
+    let cassette = Arc::new(Cassette::open(mode, path.clone())?);
+    guard.insert(path, Arc::clone(&cassette));
+    Ok(cassette)
+}
+
+/// 从环境变量解析 cassette（未开启时返回 None）。
+///
+/// Replay 模式下配置无效也返回 cassette（所有调用均报错），绝不退回真实网络；
+/// Record 模式配置无效时返回 None（降级为直接调用真实实现）。
+pub fn cassette_from_env(file_name: &str) -> Option<Arc<Cassette>> {
+    let mode = CassetteMode::parse(&std::env::var(CASSETTE_MODE_ENV).ok()?)?;
+    cassette_for(
+        mode,
+        std::env::var(CASSETTE_DIR_ENV).ok().as_deref(),
+        file_name,
+    )
+}
+
+fn cassette_for(mode: CassetteMode, dir: Option<&str>, file_name: &str) -> Option<Arc<Cassette>> {
+    let Some(dir) = dir.map(str::trim).filter(|s| !s.is_empty()) else {
+        return match mode {
+            CassetteMode::Record => {
+                tracing::warn!(
+                    "已设置 {CASSETTE_MODE_ENV} 但缺少 {CASSETTE_DIR_ENV}，cassette 未启用"
+                );
+                None
+            }
+            CassetteMode::Replay => {
+                tracing::error!("回放模式缺少 {CASSETTE_DIR_ENV}，所有调用都将失败");
+                Some(Arc::new(Cassette::unavailable(
+                    file_name,
+                    format!("缺少 {CASSETTE_DIR_ENV}"),
+                )))
+            }
+        };
+    };
+
+    let path = Path::new(dir).join(file_name);
+    match shared_cassette(mode, &path) {
+        Ok(cassette) => Some(cassette),
+        Err(err) => match mode {
+            CassetteMode::Record => {
+                tracing::error!(error = %err, "cassette 打开失败，已禁用录制");
+                None
+            }
+            CassetteMode::Replay => {
+                tracing::error!(error = %err, "cassette 加载失败，所有回放调用都将失败");
+                Some(Arc::new(Cassette::unavailable(path, err.to_string())))
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "prompt_faster_cassette_{}.jsonl",
+            uuid::Uuid::new_v4()
+        ))
+    }
+
+    #[test]
+    fn record_then_replay_in_order_and_miss_when_exhausted() {
+        let path = temp_path();
+        let recorder = Cassette::open(CassetteMode::Record, &path).unwrap();
+        recorder.record("k", "a", &"first").unwrap();
+        recorder.record("k", "a", &"second").unwrap();
+        recorder.record("k", "b", &"other").unwrap();
+        drop(recorder);
+
+        let player = Cassette::open(CassetteMode::Replay, &path).unwrap();
+        assert_eq!(player.replay::<String>("k", "a").unwrap(), "first");
+        assert_eq!(player.replay::<String>("k", "a").unwrap(), "second");
+        assert!(matches!(
+            player.replay::<String>("k", "a").unwrap_err(),
+            CassetteError::Miss { .. }
+        ));
+        assert_eq!(player.replay::<String>("k", "b").unwrap(), "other");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn replay_miss_fails_loudly() {
+        let path = temp_path();
+        let player = Cassette::open(CassetteMode::Replay, &path).unwrap();
+        let err = player.replay::<String>("k", "missing").unwrap_err();
+        assert!(matches!(err, CassetteError::Miss { .. }));
+        assert!(player.record("k", "a", &"x").is_err());
+    }
+
+    #[test]
+    fn replay_setup_failure_fails_every_call_instead_of_disabling() {
+        let cassette = cassette_for(CassetteMode::Replay, None, "x.jsonl").unwrap();
+        assert!(matches!(
+            cassette.replay::<String>("k", "a").unwrap_err(),
+            CassetteError::Unavailable(_)
+        ));
+
+        let path = temp_path();
+        std::fs::write(&path, "not json\n").unwrap();
+        let dir = path.parent().unwrap().to_str().unwrap();
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        let cassette = cassette_for(CassetteMode::Replay, Some(dir), file_name).unwrap();
+        assert!(matches!(
+            cassette.replay::<String>("k", "a").unwrap_err(),
+            CassetteError::Unavailable(_)
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        assert!(cassette_for(CassetteMode::Record, Some("  "), "x.jsonl").is_none());
+    }
+
+    #[test]
+    fn mode_parse_accepts_known_values_only() {
+        assert_eq!(CassetteMode::parse(" Record "), Some(CassetteMode::Record));
+        assert_eq!(CassetteMode::parse("replay"), Some(CassetteMode::Replay));
+        assert_eq!(CassetteMode::parse("off"), None);
+    }
+}
@@ -224,7 +224,7 @@ fn analyze_failure_patterns(iterations: &[DiagnosticIteration]) -> Vec<FailureRe
         })
         .collect();
 
-    entries.sort_by(|a, b| b.count.cmp(&a.count));
+    entries.sort_by_key(|e| std::cmp::Reverse(e.count));
     entries.truncate(10);
     entries
 }
@@ -392,7 +392,7 @@ fn build_failed_case_summaries(
         }
     }
 
-    cases.sort_by(|a, b| b.iteration_round.cmp(&a.iteration_round));
+    cases.sort_by_key(|c| std::cmp::Reverse(c.iteration_round));
     cases.truncate(limit);
     cases
 }
@@ -356,9 +356,11 @@ mod tests {
     use super::*;
 
     fn analyzer_with_threshold(threshold: f64) -> DefaultDiversityAnalyzer {
-        let mut cfg = DiversityConfig::default();
-        cfg.enabled = true;
-        cfg.warning_threshold = threshold;
+        let cfg = DiversityConfig {
+            enabled: true,
+            warning_threshold: threshold,
+            ..DiversityConfig::default()
+        };
         DefaultDiversityAnalyzer::new(cfg)
     }
 
@@ -413,10 +415,12 @@ mod tests {
 
     #[test]
     fn semantic_unavailable_adds_warning() {
-        let mut cfg = DiversityConfig::default();
-        cfg.enabled = true;
-        cfg.compute_semantic = true;
-        cfg.warning_threshold = 0.0;
+        let cfg = DiversityConfig {
+            enabled: true,
+            compute_semantic: true,
+            warning_threshold: 0.0,
+            ..DiversityConfig::default()
+        };
         let analyzer = DefaultDiversityAnalyzer::new(cfg);
         let outputs = vec!["a".to_string(), "b".to_string()];
         let analysis = analyzer.analyze(&outputs, None, None);
@@ -4,7 +4,7 @@ use crate::core::evaluator::{
 };
 use crate::core::traits::{Evaluator, TeacherModel};
 use crate::domain::models::{
-    Constraint, DataSplit, DimensionScore, EvaluationResult,
+    Constraint, ConversationRole, DataSplit, DimensionScore, EvaluationResult,
     EvaluatorConfig as TaskEvaluatorConfig, EvaluatorType, FailurePoint, Severity, TaskReference,
     TestCase,
 };
@@ -1188,16 +1188,37 @@ fn build_teacher_judge_prompt(
             g
         )
     });
+    let conversation_section = build_conversation_section(test_case);
     format!(
-        "你是评估器。请根据 test_case.reference 判断 output 是否满足要求。\\n\\n要求：只返回 JSON（不要输出其它文本）。\\nJSON schema: {{\"passed\":bool,\"score\":number(0..1),\"confidence\"?:number(0..1),\"reasoning\"?:string,\"failure_points\"?:[{{\"dimension\":string,\"description\":string,\"severity\"?:\"Critical\"|\"Major\"|\"Minor\"}}]}}\\n\\nTestCaseId: {}\\n\\nReference: {}\\n\\nOutput: {}\\n{}",
+        "你是评估器。请根据 test_case.reference 判断 output 是否满足要求。\\n\\n要求：只返回 JSON（不要输出其它文本）。\\nJSON schema: {{\"passed\":bool,\"score\":number(0..1),\"confidence\"?:number(0..1),\"reasoning\"?:string,\"failure_points\"?:[{{\"dimension\":string,\"description\":string,\"severity\"?:\"Critical\"|\"Major\"|\"Minor\"}}]}}\\n\\nTestCaseId: {}\\n\\nReference: {}{}\\n\\nOutput: {}\\n{}",
         test_case.id,
         serde_json::to_string(&test_case.reference)
             .unwrap_or_else(|_| "<unserializable reference>".to_string()),
+        conversation_section.unwrap_or_default(),
         output,
         guidance_section.unwrap_or_default()
     )
 }
 
+/// 多轮对话用例：附上对话历史作为上下文，并要求只评估最终 assistant 回复（即 Output）。
+fn build_conversation_section(test_case: &TestCase) -> Option<String> {
+    let turns = test_case.conversation().ok().flatten()?;
+    let history = turns
+        .iter()
+        .map(|t| {
+            let role = match t.role {
+                ConversationRole::User => "user",
+                ConversationRole::Assistant => "assistant",
+            };
+            format!("[{role}] {}", t.content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(format!(
+        "\n\n【对话上下文】\n{history}\n\n【评估范围】\n- Output 是对上述对话最后一条 user 消息的 assistant 回复；仅评估该回复，不评估历史轮次。"
+    ))
+}
+
 fn read_optional_user_guidance(ctx: &OptimizationContext) -> Option<String> {
     ctx.extensions
         .get(EXT_USER_GUIDANCE)
@@ -1829,6 +1850,26 @@ mod tests {
         assert!(selected.contains(&"teacher_model"));
     }
 
+    #[test]
+    fn teacher_model_prompt_scopes_conversation_cases_to_final_reply() {
+        let mut tc = make_exact_case("c1", "fine");
+        tc.input.insert(
+            "conversation".to_string(),
+            serde_json::json!([
+                { "role": "user", "content": "hi" },
+                { "role": "assistant", "content": "hello" },
+                { "role": "user", "content": "how are you?" }
+            ]),
+        );
+        let prompt = build_teacher_judge_prompt(&tc, "OUT", None);
+        assert!(prompt.contains("【对话上下文】"));
+        assert!(prompt.contains("[assistant] hello"));
+        assert!(prompt.contains("仅评估该回复"));
+
+        let plain = build_teacher_judge_prompt(&make_exact_case("c2", "x"), "OUT", None);
+        assert!(!plain.contains("【对话上下文】"));
+    }
+
     #[test]
     fn teacher_model_prompt_includes_user_guidance() {
         let tc = make_exact_case("tc1", "OK");
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::core::cassette::{Cassette, CassetteMode, cassette_key, prompt_hash};
+use crate::core::execution_target::ExecutionError;
+use crate::core::iteration_engine::checkpoint::stable_json_string;
+use crate::core::traits::ExecutionTarget;
+use crate::domain::models::ExecutionResult;
+use crate::domain::types::ExecutionTargetConfig;
+
+const CASSETTE_KIND: &str = "execution";
+
+/// cassette 中单次执行的记录（Prompt 只保存哈希）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionCassetteEntry {
+    pub prompt_hash: String,
+    pub test_case_id: String,
+    pub input: HashMap<String, serde_json::Value>,
+    pub outcome: Result<ExecutionResult, ExecutionError>,
+}
+
+/// 录制/回放包装：Record 透传并落盘；Replay 只读 cassette，未命中即报错。
+///
+/// key 覆盖执行目标名、执行目标配置（不含密钥）、Prompt 原文、输入与 test_case_id，
+/// 保证回放与录制时的请求逐位一致。
+pub struct CassetteExecutionTarget {
+    inner: Arc<dyn ExecutionTarget>,
+    cassette: Arc<Cassette>,
+}
+
+impl CassetteExecutionTarget {
+    pub fn new(inner: Arc<dyn ExecutionTarget>, cassette: Arc<Cassette>) -> Self {
+        Self { inner, cassette }
+    }
+
+    fn key(
+        &self,
+        execution_target_config: &ExecutionTargetConfig,
+        prompt: &str,
+        input: &HashMap<String, serde_json::Value>,
+        test_case_id: &str,
+    ) -> String {
+        cassette_key(&[
+            self.inner.name(),
+            &stable_json_string(execution_target_config),
+            prompt,
+            &stable_json_string(input),
+            test_case_id,
+        ])
+    }
+}
+
+#[async_trait]
+impl ExecutionTarget for CassetteExecutionTarget {
+    async fn execute(
+        &self,
+        execution_target_config: &ExecutionTargetConfig,
+        prompt: &str,
+        input: &HashMap<String, serde_json::Value>,
+        test_case_id: &str,
+    ) -> Result<ExecutionResult, ExecutionError> {
+        let key = self.key(execution_target_config, prompt, input, test_case_id);
+
+        match self.cassette.mode() {
+            CassetteMode::Replay => {
+                let entry: ExecutionCassetteEntry = self
+                    .cassette
+                    .replay(CASSETTE_KIND, &key)
+                    .map_err(|err| {
+                        tracing::error!(test_case_id = %test_case_id, error = %err, "执行目标 cassette 回放失败");
+                        ExecutionError::Internal {
+                            test_case_id: test_case_id.to_string(),
+                            message: err.to_string(),
+                        }
+                    })?;
+                entry.outcome
+            }
+            CassetteMode::Record => {
+                let outcome = self
+                    .inner
+                    .execute(execution_target_config, prompt, input, test_case_id)
+                    .await;
+                let entry = ExecutionCassetteEntry {
+                    prompt_hash: prompt_hash(prompt),
+                    test_case_id: test_case_id.to_string(),
+                    input: input.clone(),
+                    outcome,
+                };
+                self.cassette
+                    .record(CASSETTE_KIND, &key, &entry)
+                    .map_err(|err| ExecutionError::Internal {
+                        test_case_id: test_case_id.to_string(),
+                        message: err.to_string(),
+                    })?;
+                entry.outcome
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::core::execution_target::ExampleExecutionTarget;
+
+    #[derive(Default)]
+    struct CountingTarget {
+        inner: ExampleExecutionTarget,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ExecutionTarget for CountingTarget {
+        async fn execute(
+            &self,
+            execution_target_config: &ExecutionTargetConfig,
+            prompt: &str,
+            input: &HashMap<String, serde_json::Value>,
+            test_case_id: &str,
+        ) -> Result<ExecutionResult, ExecutionError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if test_case_id == "boom" {
+                return Err(ExecutionError::Timeout {
+                    test_case_id: test_case_id.to_string(),
+                    message: "slow".to_string(),
+                });
+            }
+            self.inner
+                .execute(execution_target_config, prompt, input, test_case_id)
+                .await
+        }
+
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+    }
+
+    fn temp_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "prompt_faster_exec_cassette_{}.jsonl",
+            uuid::Uuid::new_v4()
+        ))
+    }
+
+    #[tokio::test]
+    async fn replay_reproduces_recorded_results_and_errors_without_calling_inner() {
+        let path = temp_path();
+        let config = ExecutionTargetConfig::default();
+        let mut input = HashMap::new();
+        input.insert("q".to_string(), serde_json::json!("v"));
+
+        let recorded = {
+            let inner = Arc::new(CountingTarget::default());
+            let cassette = Arc::new(Cassette::open(CassetteMode::Record, &path).unwrap());
+            let target = CassetteExecutionTarget::new(inner.clone(), cassette);
+            let ok = target.execute(&config, "p", &input, "tc-1").await.unwrap();
+            let err = target
+                .execute(&config, "p", &input, "boom")
+                .await
+                .unwrap_err();
+            assert_eq!(inner.calls.load(Ordering::SeqCst), 2);
+            (ok, err)
+        };
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("\"p\""), "cassette 不应保存 Prompt 原文");
+
+        let inner = Arc::new(CountingTarget::default());
+        let cassette = Arc::new(Cassette::open(CassetteMode::Replay, &path).unwrap());
+        let target = CassetteExecutionTarget::new(inner.clone(), cassette);
+        let ok = target.execute(&config, "p", &input, "tc-1").await.unwrap();
+        let err = target
+            .execute(&config, "p", &input, "boom")
+            .await
+            .unwrap_err();
+        assert_eq!(ok.output, recorded.0.output);
+        assert_eq!(ok.raw_response, recorded.0.raw_response);
+        assert_eq!(err, recorded.1);
+
+        let miss = target
+            .execute(&config, "other prompt", &input, "tc-1")
+            .await
+            .unwrap_err();
+        assert!(matches!(miss, ExecutionError::Internal { .. }));
+        assert!(miss.to_string().contains("cassette"));
+        assert_eq!(inner.calls.load(Ordering::SeqCst), 0);
+        let _ = std::fs::remove_file(&path);
+    }
+}
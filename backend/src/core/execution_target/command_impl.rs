@@ -0,0 +1,465 @@
+use std::collections::{HashMap, HashSet};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tokio::time::Instant;
+
+use crate::core::execution_target::ExecutionError;
+use crate::core::traits::ExecutionTarget;
+use crate::domain::models::{ExecutionResult, TokenUsage};
+use crate::domain::types::ExecutionTargetConfig;
+
+/// 服务端白名单：允许作为执行目标启动的程序（逗号分隔，需与配置中的 program 完全一致）。
+///
+/// 未设置时命令执行目标整体不可用（避免通过 API 配置任意命令执行）。
+pub const COMMAND_TARGET_ALLOWED_PROGRAMS_ENV: &str = "COMMAND_TARGET_ALLOWED_PROGRAMS";
+
+/// 服务端白名单：允许透传给子进程的环境变量名（逗号分隔）。
+///
+/// 任务配置中的 env_allowlist 只能在此范围内收窄；未设置时不透传任何环境变量。
+pub const COMMAND_TARGET_ALLOWED_ENV_ENV: &str = "COMMAND_TARGET_ALLOWED_ENV";
+
+const STDERR_EXCERPT_MAX_CHARS: usize = 500;
+
+/// stdout 上限（超过即终止子进程并报错，避免无界缓冲）
+pub const COMMAND_STDOUT_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+/// stderr 只保留开头用于错误摘要，其余读出丢弃
+const STDERR_CAPTURE_MAX_BYTES: usize = 64 * 1024;
+
+/// 子进程 stdin 协议
+#[derive(Debug, Serialize)]
+struct CommandRequest<'a> {
+    prompt: &'a str,
+    input: &'a HashMap<String, serde_json::Value>,
+    test_case_id: &'a str,
+}
+
+/// 子进程 stdout 协议（output 为非字符串 JSON 时按 JSON 文本作为输出）
+#[derive(Debug, Deserialize)]
+struct CommandResponse {
+    output: serde_json::Value,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
+}
+
+/// 本地命令执行目标：每个用例启动一次子进程，stdin 写入请求 JSON，stdout 读取结果 JSON。
+///
+/// 设计约束：
+/// - 仅执行服务端白名单内的程序；子进程环境变量默认清空，
+///   只透传同时出现在 env_allowlist 与服务端白名单中的变量
+/// - 超时或 stdout 超过上限即终止子进程（kill_on_drop）
+/// - 错误信息只包含 stderr 摘要，不回显 prompt / input
+#[derive(Debug, Clone)]
+pub struct CommandExecutionTarget {
+    allowed_programs: Arc<HashSet<String>>,
+    allowed_env: Arc<HashSet<String>>,
+}
+
+impl Default for CommandExecutionTarget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandExecutionTarget {
+    pub fn new() -> Self {
+        let programs = std::env::var(COMMAND_TARGET_ALLOWED_PROGRAMS_ENV).unwrap_or_default();
+        let env = std::env::var(COMMAND_TARGET_ALLOWED_ENV_ENV).unwrap_or_default();
+        Self::with_allowed_programs(programs.split(',')).with_allowed_env(env.split(','))
+    }
+
+    pub fn with_allowed_programs<I, S>(programs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            allowed_programs: Arc::new(non_empty_trimmed(programs)),
+            allowed_env: Arc::new(HashSet::new()),
+        }
+    }
+
+    /// 设置服务端环境变量白名单（覆盖之前的设置）
+    pub fn with_allowed_env<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.allowed_env = Arc::new(non_empty_trimmed(names));
+        self
+    }
+}
+
+fn non_empty_trimmed<I, S>(items: I) -> HashSet<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    items
+        .into_iter()
+        .map(|s| s.as_ref().trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+enum CaptureError {
+    Io(std::io::Error),
+    StdoutTooLarge,
+}
+
+/// 读取 stdout；超过 `limit` 字节立即返回错误（不再继续缓冲）。
+async fn read_stdout_capped<R: AsyncRead + Unpin>(
+    reader: R,
+    limit: usize,
+) -> Result<Vec<u8>, CaptureError> {
+    let mut buf = Vec::new();
+    reader
+        .take(limit as u64 + 1)
+        .read_to_end(&mut buf)
+        .await
+        .map_err(CaptureError::Io)?;
+    if buf.len() > limit {
+        return Err(CaptureError::StdoutTooLarge);
+    }
+    Ok(buf)
+}
+
+/// 读取 stderr：保留前 `limit` 字节，其余读出丢弃（避免子进程写满管道阻塞）。
+async fn read_stderr_capped<R: AsyncRead + Unpin>(
+    mut reader: R,
+    limit: usize,
+) -> Result<Vec<u8>, CaptureError> {
+    let mut buf = Vec::new();
+    (&mut reader)
+        .take(limit as u64)
+        .read_to_end(&mut buf)
+        .await
+        .map_err(CaptureError::Io)?;
+    tokio::io::copy(&mut reader, &mut tokio::io::sink())
+        .await
+        .map_err(CaptureError::Io)?;
+    Ok(buf)
+}
+
+fn stderr_excerpt(stderr: &[u8]) -> String {
+    let text = String::from_utf8_lossy(stderr);
+    let trimmed = text.trim();
+    let mut excerpt: String = trimmed.chars().take(STDERR_EXCERPT_MAX_CHARS).collect();
+    if trimmed.chars().count() > STDERR_EXCERPT_MAX_CHARS {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+#[async_trait]
+impl ExecutionTarget for CommandExecutionTarget {
+    async fn execute(
+        &self,
+        execution_target_config: &ExecutionTargetConfig,
+        prompt: &str,
+        input: &HashMap<String, serde_json::Value>,
+        test_case_id: &str,
+    ) -> Result<ExecutionResult, ExecutionError> {
+        let test_case_id = test_case_id.trim();
+        if test_case_id.is_empty() {
+            return Err(ExecutionError::InvalidRequest {
+                test_case_id: "unknown".to_string(),
+                message: "test_case_id 不能为空".to_string(),
+            });
+        }
+
+        let (program, args, timeout_ms, env_allowlist) = match execution_target_config {
+            ExecutionTargetConfig::Command {
+                program,
+                args,
+                timeout_ms,
+                env_allowlist,
+                ..
+            } => (program.trim(), args, *timeout_ms, env_allowlist),
+            _ => {
+                return Err(ExecutionError::InvalidRequest {
+                    test_case_id: test_case_id.to_string(),
+                    message: "execution_target_config 不是 Command 配置".to_string(),
+                });
+            }
+        };
+
+        if program.is_empty() {
+            return Err(ExecutionError::InvalidRequest {
+                test_case_id: test_case_id.to_string(),
+                message: "program 不能为空".to_string(),
+            });
+        }
+        if !self.allowed_programs.contains(program) {
+            return Err(ExecutionError::InvalidRequest {
+                test_case_id: test_case_id.to_string(),
+                message: format!(
+                    "program 不在服务端白名单中（{COMMAND_TARGET_ALLOWED_PROGRAMS_ENV}）"
+                ),
+            });
+        }
+
+        let payload = serde_json::to_vec(&CommandRequest {
+            prompt,
+            input,
+            test_case_id,
+        })
+        .map_err(|e| ExecutionError::Internal {
+            test_case_id: test_case_id.to_string(),
+            message: format!("序列化命令请求失败: {e}"),
+        })?;
+
+        let mut command = Command::new(program);
+        command
+            .args(args)
+            .env_clear()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        for name in env_allowlist {
+            if !self.allowed_env.contains(name.trim()) {
+                tracing::warn!(
+                    env = %name,
+                    "环境变量不在服务端白名单中（{COMMAND_TARGET_ALLOWED_ENV_ENV}），已忽略"
+                );
+                continue;
+            }
+            if let Ok(value) = std::env::var(name.trim()) {
+                command.env(name.trim(), value);
+            }
+        }
+
+        let start = Instant::now();
+        let mut child = command.spawn().map_err(|e| ExecutionError::Internal {
+            test_case_id: test_case_id.to_string(),
+            message: format!("启动子进程失败: {}", e.kind()),
+        })?;
+
+        // stdin 与 stdout 并发处理，避免大输入/大输出时双向管道互相阻塞。
+        let stdin_task = child.stdin.take().map(|mut stdin| {
+            tokio::spawn(async move {
+                // 子进程可能不读 stdin 直接退出（BrokenPipe），不视为错误。
+                let _ = stdin.write_all(&payload).await;
+                let _ = stdin.shutdown().await;
+            })
+        });
+
+        let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+            return Err(ExecutionError::Internal {
+                test_case_id: test_case_id.to_string(),
+                message: "子进程输出管道不可用".to_string(),
+            });
+        };
+        let run = async {
+            let (stdout, stderr) = tokio::try_join!(
+                read_stdout_capped(stdout, COMMAND_STDOUT_MAX_BYTES),
+                read_stderr_capped(stderr, STDERR_CAPTURE_MAX_BYTES),
+            )?;
+            let status = child.wait().await.map_err(CaptureError::Io)?;
+            Ok::<_, CaptureError>((status, stdout, stderr))
+        };
+        let captured = tokio::time::timeout(Duration::from_millis(timeout_ms.max(1)), run).await;
+        if let Some(task) = stdin_task {
+            task.abort();
+        }
+        let (status, stdout, stderr) = captured
+            .map_err(|_| ExecutionError::Timeout {
+                test_case_id: test_case_id.to_string(),
+                message: format!("命令执行超时（{timeout_ms}ms），子进程已终止"),
+            })?
+            .map_err(|e| match e {
+                CaptureError::StdoutTooLarge => ExecutionError::UpstreamError {
+                    test_case_id: test_case_id.to_string(),
+                    message: format!(
+                        "命令 stdout 超过上限（{COMMAND_STDOUT_MAX_BYTES} 字节），子进程已终止"
+                    ),
+                },
+                CaptureError::Io(e) => ExecutionError::Internal {
+                    test_case_id: test_case_id.to_string(),
+                    message: format!("等待子进程失败: {}", e.kind()),
+                },
+            })?;
+
+        if !status.success() {
+            let code = status
+                .code()
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "signal".to_string());
+            return Err(ExecutionError::UpstreamError {
+                test_case_id: test_case_id.to_string(),
+                message: format!("命令退出码 {code}: {}", stderr_excerpt(&stderr)),
+            });
+        }
+
+        let response: CommandResponse =
+            serde_json::from_slice(&stdout).map_err(|e| ExecutionError::ParseError {
+                test_case_id: test_case_id.to_string(),
+                message: format!("解析命令 stdout JSON 失败: {e}"),
+            })?;
+        let output_text = match response.output {
+            serde_json::Value::String(s) => s,
+            other => serde_json::to_string(&other).unwrap_or_default(),
+        };
+
+        Ok(ExecutionResult {
+            test_case_id: test_case_id.to_string(),
+            output: output_text,
+            latency_ms: start.elapsed().as_millis() as u64,
+            token_usage: response.usage,
+            raw_response: None,
+        })
+    }
+
+    fn name(&self) -> &str {
+        "command"
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SH: &str = "/bin/sh";
+
+    fn command_config(script: &str, timeout_ms: u64, env: &[&str]) -> ExecutionTargetConfig {
+        ExecutionTargetConfig::Command {
+            program: SH.to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            timeout_ms,
+            env_allowlist: env.iter().map(|s| s.to_string()).collect(),
+            max_concurrency: None,
+        }
+    }
+
+    fn target() -> CommandExecutionTarget {
+        CommandExecutionTarget::with_allowed_programs([SH])
+    }
+
+    #[tokio::test]
+    async fn sends_request_on_stdin_and_parses_output_and_usage() {
+        let mut input = HashMap::new();
+        input.insert("q".to_string(), json!("hello"));
+        // 回显 stdin 作为 output（非字符串 JSON → JSON 文本）
+        let script = r#"read -r line; printf '{"output": %s, "usage": {"prompt_tokens": 1, "completion_tokens": 2, "total_tokens": 3}}' "$line""#;
+
+        let result = target()
+            .execute(&command_config(script, 5_000, &[]), "P", &input, "tc-1")
+            .await
+            .unwrap();
+        let echoed: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(echoed["prompt"], "P");
+        assert_eq!(echoed["input"]["q"], "hello");
+        assert_eq!(echoed["test_case_id"], "tc-1");
+        assert_eq!(result.token_usage.unwrap().total_tokens, 3);
+    }
+
+    #[tokio::test]
+    async fn only_allowlisted_env_vars_are_passed() {
+        let Ok(home) = std::env::var("HOME") else {
+            return;
+        };
+        let script = r#"cat >/dev/null; printf '{"output": "%s"}' "${HOME:-none}""#;
+        let input = HashMap::new();
+        let target = target().with_allowed_env(["HOME"]);
+
+        let cleared = target
+            .execute(&command_config(script, 5_000, &[]), "p", &input, "tc")
+            .await
+            .unwrap();
+        assert_eq!(cleared.output, "none");
+
+        let passed = target
+            .execute(&command_config(script, 5_000, &["HOME"]), "p", &input, "tc")
+            .await
+            .unwrap();
+        assert_eq!(passed.output, home);
+    }
+
+    #[tokio::test]
+    async fn task_env_allowlist_cannot_widen_server_allowlist() {
+        if std::env::var("HOME").is_err() {
+            return;
+        }
+        let script = r#"cat >/dev/null; printf '{"output": "%s"}' "${HOME:-none}""#;
+        let input = HashMap::new();
+
+        // 服务端未放行 HOME：任务配置中列出也不透传
+        let result = target()
+            .with_allowed_env(["LANG"])
+            .execute(&command_config(script, 5_000, &["HOME"]), "p", &input, "tc")
+            .await
+            .unwrap();
+        assert_eq!(result.output, "none");
+    }
+
+    #[tokio::test]
+    async fn oversized_stdout_is_rejected_instead_of_buffered() {
+        let script = format!(
+            "cat >/dev/null; head -c {} /dev/zero",
+            COMMAND_STDOUT_MAX_BYTES + 1
+        );
+        let err = target()
+            .execute(
+                &command_config(&script, 10_000, &[]),
+                "p",
+                &HashMap::new(),
+                "tc",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::UpstreamError { .. }));
+        assert!(err.to_string().contains("stdout"));
+    }
+
+    #[tokio::test]
+    async fn maps_timeout_exit_code_parse_and_allowlist_errors() {
+        let input = HashMap::new();
+
+        let err = target()
+            .execute(&command_config("sleep 5", 200, &[]), "p", &input, "tc")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::Timeout { .. }));
+
+        let err = target()
+            .execute(
+                &command_config("echo boom >&2; exit 3", 5_000, &[]),
+                "p",
+                &input,
+                "tc",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::UpstreamError { .. }));
+        assert!(err.to_string().contains("3") && err.to_string().contains("boom"));
+
+        let err = target()
+            .execute(
+                &command_config("echo not-json", 5_000, &[]),
+                "p",
+                &input,
+                "tc",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::ParseError { .. }));
+
+        let err = CommandExecutionTarget::with_allowed_programs(Vec::<String>::new())
+            .execute(&command_config("true", 5_000, &[]), "p", &input, "tc")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::InvalidRequest { .. }));
+        assert!(
+            err.to_string()
+                .contains(COMMAND_TARGET_ALLOWED_PROGRAMS_ENV)
+        );
+    }
+}
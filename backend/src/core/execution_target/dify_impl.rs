@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use reqwest::Client;
@@ -7,26 +8,225 @@ use tokio::time::Instant;
 use url::Url;
 
 use crate::core::execution_target::ExecutionError;
+use crate::core::iteration_engine::checkpoint::stable_json_string;
 use crate::core::traits::ExecutionTarget;
-use crate::domain::models::ExecutionResult;
-use crate::domain::types::ExecutionTargetConfig;
-use crate::infra::external::http_client::create_http_client;
+use crate::domain::models::{
+    ConversationRole, ConversationTurn, ExecutionResult, TEST_CASE_CONVERSATION_INPUT_KEY,
+    TokenUsage, parse_conversation_input,
+};
+use crate::domain::types::{DifyAppType, DifyResponseMode, ExecutionTargetConfig};
+use crate::infra::external::http_client::{HttpClientError, create_http_client};
+use crate::infra::external::upstream_guard::{
+    UpstreamGuard, UpstreamOutcome, estimate_tokens, upstream_guard,
+};
 
 #[derive(Debug, Clone)]
 pub struct DifyExecutionTarget {
     client: Client,
 }
 
-impl Default for DifyExecutionTarget {
-    fn default() -> Self {
-        Self::new()
-    }
+/// 单次 Dify 调用的固定参数
+struct DifyCall<'a> {
+    url: &'a str,
+    api_key: &'a str,
+    app_type: DifyAppType,
+    mode: DifyResponseMode,
+    test_case_id: &'a str,
+    /// 凭证级限流 / 熔断（同一 base_url + API Key 的并发调用共享）
+    guard: &'a UpstreamGuard,
 }
 
 impl DifyExecutionTarget {
-    pub fn new() -> Self {
-        let client = create_http_client().unwrap_or_else(|_| Client::new());
-        Self { client }
+    /// 按出站网络配置创建；配置无效时返回错误（不回退为无 SSRF 防护的默认客户端）
+    pub fn new() -> Result<Self, HttpClientError> {
+        Ok(Self {
+            client: create_http_client()?,
+        })
+    }
+
+    /// 发送一次请求并读取响应（流式模式下还原为阻塞模式同形 JSON）。
+    async fn send(&self, call: &DifyCall<'_>, body: &Value) -> Result<Value, ExecutionError> {
+        let test_case_id = call.test_case_id;
+        let map_send_error = |e: reqwest::Error| {
+            if e.is_timeout() {
+                ExecutionError::Timeout {
+                    test_case_id: test_case_id.to_string(),
+                    message: "Dify 请求超时".to_string(),
+                }
+            } else {
+                ExecutionError::Network {
+                    test_case_id: test_case_id.to_string(),
+                    message: "Dify 网络请求失败".to_string(),
+                }
+            }
+        };
+
+        let mut request = self
+            .client
+            .post(call.url)
+            .header("Authorization", format!("Bearer {}", call.api_key))
+            .header("X-Correlation-Id", test_case_id)
+            .json(body);
+        if call.mode == DifyResponseMode::Streaming {
+            request = request.timeout(DIFY_STREAMING_TOTAL_TIMEOUT);
+        }
+        call.guard
+            .acquire(estimate_tokens(body.to_string().len()))
+            .await
+            .map_err(|e| ExecutionError::UpstreamError {
+                test_case_id: test_case_id.to_string(),
+                message: format!("Dify {e}"),
+            })?;
+        let mut response = match request.send().await {
+            Ok(response) => {
+                call.guard
+                    .record(UpstreamOutcome::from_response(&response))
+                    .await;
+                response
+            }
+            Err(e) => {
+                call.guard
+                    .record(UpstreamOutcome::Failure { retry_after: None })
+                    .await;
+                return Err(map_send_error(e));
+            }
+        };
+
+        let status = response.status().as_u16();
+        if !(200..=299).contains(&status) {
+            // 重要：错误消息不得包含 prompt/input 原文或上游 body（可能回显敏感内容）。
+            return Err(map_status_error(test_case_id, status, None));
+        }
+
+        match call.mode {
+            DifyResponseMode::Blocking => {
+                response
+                    .json::<Value>()
+                    .await
+                    .map_err(|_e| ExecutionError::ParseError {
+                        test_case_id: test_case_id.to_string(),
+                        message: "解析 Dify 响应失败".to_string(),
+                    })
+            }
+            DifyResponseMode::Streaming => {
+                let mut parser = SseLineParser::default();
+                let mut acc = DifyStreamAccumulator::default();
+                loop {
+                    let chunk = tokio::time::timeout(DIFY_STREAMING_IDLE_TIMEOUT, response.chunk())
+                        .await
+                        .map_err(|_| ExecutionError::Timeout {
+                            test_case_id: test_case_id.to_string(),
+                            message: "Dify 流式响应长时间无数据".to_string(),
+                        })?
+                        .map_err(map_send_error)?;
+                    match chunk {
+                        Some(bytes) => parser.feed(&bytes, &mut acc),
+                        None => break,
+                    }
+                    if acc.finished && acc.error.is_some() {
+                        break;
+                    }
+                }
+                parser.finish(&mut acc);
+
+                if let Some(err) = acc.error.take() {
+                    return Err(map_status_error(test_case_id, err.status, Some(&err.code)));
+                }
+                if !acc.finished {
+                    return Err(ExecutionError::ParseError {
+                        test_case_id: test_case_id.to_string(),
+                        message: "Dify 流式响应未返回结束事件".to_string(),
+                    });
+                }
+                Ok(acc.into_response(call.app_type))
+            }
+        }
+    }
+
+    /// 多轮对话回放：按顺序逐条发送历史中的 user 轮，并通过 conversation_id 串联会话；
+    /// 历史中的 assistant 轮无法注入 Dify 会话，由应用自行生成。
+    ///
+    /// 返回最后一轮的响应（即被评估的回复）、各轮 token 用量之和与回放轮数。
+    async fn replay_conversation(
+        &self,
+        call: &DifyCall<'_>,
+        input: &HashMap<String, Value>,
+        prompt_variable: &str,
+        prompt: &str,
+        conversation: &[ConversationTurn],
+    ) -> Result<(Value, Option<TokenUsage>, usize), ExecutionError> {
+        let mut turn_input = input.clone();
+        turn_input.remove(TEST_CASE_CONVERSATION_INPUT_KEY);
+        let mut conversation_id = match turn_input.remove(DIFY_CONVERSATION_ID_INPUT_KEY) {
+            Some(Value::String(id)) if !id.trim().is_empty() => Some(id),
+            _ => None,
+        };
+
+        let user_turns: Vec<&ConversationTurn> = conversation
+            .iter()
+            .filter(|t| t.role == ConversationRole::User)
+            .collect();
+        let mut total_usage: Option<TokenUsage> = None;
+        let mut last: Option<Value> = None;
+        let mut turns = 0;
+        for turn in &user_turns {
+            turn_input.insert(
+                DIFY_QUERY_INPUT_KEY.to_string(),
+                Value::String(turn.content.clone()),
+            );
+            match conversation_id.as_ref() {
+                Some(id) => turn_input.insert(
+                    DIFY_CONVERSATION_ID_INPUT_KEY.to_string(),
+                    Value::String(id.clone()),
+                ),
+                None => turn_input.remove(DIFY_CONVERSATION_ID_INPUT_KEY),
+            };
+            let body = build_request_body(
+                DifyAppType::Chat,
+                call.mode,
+                &turn_input,
+                prompt_variable,
+                prompt,
+            )
+            .map_err(|message| ExecutionError::InvalidRequest {
+                test_case_id: call.test_case_id.to_string(),
+                message,
+            })?;
+
+            let v = self.send(call, &body).await?;
+            turns += 1;
+            if let Some(usage) = extract_token_usage(&v) {
+                let acc = total_usage.get_or_insert(TokenUsage {
+                    prompt_tokens: 0,
+                    completion_tokens: 0,
+                    total_tokens: 0,
+                });
+                acc.prompt_tokens = acc.prompt_tokens.saturating_add(usage.prompt_tokens);
+                acc.completion_tokens = acc
+                    .completion_tokens
+                    .saturating_add(usage.completion_tokens);
+                acc.total_tokens = acc.total_tokens.saturating_add(usage.total_tokens);
+            }
+            if let Some(id) = v
+                .get("conversation_id")
+                .and_then(|x| x.as_str())
+                .filter(|s| !s.is_empty())
+            {
+                conversation_id = Some(id.to_string());
+            } else if turns < user_turns.len() {
+                return Err(ExecutionError::ParseError {
+                    test_case_id: call.test_case_id.to_string(),
+                    message: "Dify 响应缺少 conversation_id，无法继续回放对话".to_string(),
+                });
+            }
+            last = Some(v);
+        }
+
+        let last = last.ok_or_else(|| ExecutionError::InvalidRequest {
+            test_case_id: call.test_case_id.to_string(),
+            message: "对话中没有 user 轮".to_string(),
+        })?;
+        Ok((last, total_usage, turns))
     }
 }
 
@@ -51,6 +251,16 @@ fn normalize_base_url_for_v1(base_url: &str) -> String {
     base
 }
 
+/// Chat 应用：测试用例输入中作为用户消息（query）的保留字段
+pub const DIFY_QUERY_INPUT_KEY: &str = "query";
+/// Chat 应用：测试用例输入中用于续接会话的保留字段
+pub const DIFY_CONVERSATION_ID_INPUT_KEY: &str = "conversation_id";
+
+/// 流式模式下整次运行的超时上限（覆盖客户端默认的 60s 总超时）
+const DIFY_STREAMING_TOTAL_TIMEOUT: Duration = Duration::from_secs(600);
+/// 流式模式下两次数据块之间的最长空闲时间
+const DIFY_STREAMING_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
 fn build_workflow_run_url(base_url: &str, workflow_id: &str) -> String {
     let base_url = normalize_base_url_for_v1(base_url);
     let wf = workflow_id.trim();
@@ -61,6 +271,63 @@ fn build_workflow_run_url(base_url: &str, workflow_id: &str) -> String {
     }
 }
 
+fn build_app_url(base_url: &str, app_type: DifyAppType, workflow_id: &str) -> String {
+    match app_type {
+        DifyAppType::Workflow => build_workflow_run_url(base_url, workflow_id),
+        DifyAppType::Chat => format!("{}/v1/chat-messages", normalize_base_url_for_v1(base_url)),
+        DifyAppType::Completion => format!(
+            "{}/v1/completion-messages",
+            normalize_base_url_for_v1(base_url)
+        ),
+    }
+}
+
+fn response_mode_str(mode: DifyResponseMode) -> &'static str {
+    match mode {
+        DifyResponseMode::Blocking => "blocking",
+        DifyResponseMode::Streaming => "streaming",
+    }
+}
+
+/// 构造请求体；Chat 应用从输入中取出 query / conversation_id（不再作为 inputs 变量传递）。
+fn build_request_body(
+    app_type: DifyAppType,
+    response_mode: DifyResponseMode,
+    input: &HashMap<String, Value>,
+    prompt_variable: &str,
+    prompt: &str,
+) -> Result<Value, String> {
+    let mut inputs = build_inputs_with_prompt(input, prompt_variable, prompt);
+    let mut body = json!({
+        "response_mode": response_mode_str(response_mode),
+        "user": "prompt-faster",
+    });
+    if app_type == DifyAppType::Chat {
+        let query = match inputs.remove(DIFY_QUERY_INPUT_KEY) {
+            Some(Value::String(q)) if !q.trim().is_empty() => q,
+            _ => {
+                return Err(format!(
+                    "Chat 应用需要测试用例输入提供非空字符串字段 {DIFY_QUERY_INPUT_KEY}"
+                ));
+            }
+        };
+        body["query"] = Value::String(query);
+        match inputs.remove(DIFY_CONVERSATION_ID_INPUT_KEY) {
+            Some(Value::String(id)) if !id.trim().is_empty() => {
+                body["conversation_id"] = Value::String(id);
+            }
+            Some(Value::Null) | Some(Value::String(_)) | None => {}
+            Some(_) => {
+                return Err(format!(
+                    "测试用例输入字段 {DIFY_CONVERSATION_ID_INPUT_KEY} 必须为字符串"
+                ));
+            }
+        }
+    }
+    body["inputs"] = Value::Object(inputs);
+    Ok(body)
+}
+
 fn build_inputs_with_prompt(
     input: &HashMap<String, Value>,
     prompt_variable: &str,
@@ -77,6 +344,298 @@ fn build_inputs_with_prompt(
     out
 }
 
+/// 测试集 Dify 配置中与执行相关的设置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifyExecutionSettings {
+    pub prompt_variable: String,
+    pub output_selector: Option<String>,
+    pub app_type: DifyAppType,
+    pub response_mode: DifyResponseMode,
+    /// 测试集绑定的 Dify 凭证 ID（None 使用默认凭证）
+    pub credential_id: Option<String>,
+}
+
+impl Default for DifyExecutionSettings {
+    fn default() -> Self {
+        Self {
+            prompt_variable: "prompt".to_string(),
+            output_selector: None,
+            app_type: DifyAppType::default(),
+            response_mode: DifyResponseMode::default(),
+            credential_id: None,
+        }
+    }
+}
+
+/// 从测试集 `dify_config_json` 中读取执行所需设置。
+///
+/// 兼容 camelCase（当前写入格式）与 snake_case；缺少 prompt 变量名时返回 None；
+/// 无法识别的 appType / responseMode 按默认值处理。
+pub fn parse_dify_execution_settings(raw: &str) -> Option<DifyExecutionSettings> {
+    let value = serde_json::from_str::<Value>(raw).ok()?;
+    let field = |camel: &str, snake: &str| {
+        value
+            .get(camel)
+            .or_else(|| value.get(snake))
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let prompt_variable = field("targetPromptVariable", "target_prompt_variable")?;
+    fn parse_enum<T: serde::de::DeserializeOwned + Default>(raw: Option<String>) -> T {
+        raw.and_then(|s| serde_json::from_value(Value::String(s)).ok())
+            .unwrap_or_default()
+    }
+    Some(DifyExecutionSettings {
+        prompt_variable,
+        output_selector: field("outputSelector", "output_selector"),
+        app_type: parse_enum(field("appType", "app_type")),
+        response_mode: parse_enum(field("responseMode", "response_mode")),
+        credential_id: field("credentialId", "credential_id"),
+    })
+}
+
+/// 提取 token 用量：Chat/Completion 为 `metadata.usage`，工作流仅有 `data.total_tokens`。
+fn extract_token_usage(v: &Value) -> Option<TokenUsage> {
+    let as_u32 = |x: Option<&Value>| {
+        x.and_then(|n| n.as_u64())
+            .map(|n| n.min(u32::MAX as u64) as u32)
+    };
+    if let Some(usage) = v.get("metadata").and_then(|m| m.get("usage")) {
+        let prompt_tokens = as_u32(usage.get("prompt_tokens")).unwrap_or(0);
+        let completion_tokens = as_u32(usage.get("completion_tokens")).unwrap_or(0);
+        let total_tokens = as_u32(usage.get("total_tokens"))
+            .unwrap_or(prompt_tokens.saturating_add(completion_tokens));
+        return Some(TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens,
+        });
+    }
+    as_u32(v.get("data").and_then(|d| d.get("total_tokens"))).map(|total_tokens| TokenUsage {
+        prompt_tokens: 0,
+        completion_tokens: 0,
+        total_tokens,
+    })
+}
+
+/// 提取会话/运行元数据（不含输出正文），写入 `ExecutionResult.raw_response`。
+fn extract_run_metadata(v: &Value) -> Option<Value> {
+    let mut meta = serde_json::Map::new();
+    for key in ["conversation_id", "message_id", "workflow_run_id"] {
+        if let Some(id) = v
+            .get(key)
+            .and_then(|x| x.as_str())
+            .filter(|s| !s.is_empty())
+        {
+            meta.insert(key.to_string(), Value::String(id.to_string()));
+        }
+    }
+    // 上游耗时（秒）：Chat/Completion 在 metadata.usage.latency，工作流在 data.elapsed_time
+    let upstream_secs = v
+        .get("metadata")
+        .and_then(|m| m.get("usage"))
+        .and_then(|u| u.get("latency"))
+        .or_else(|| v.get("data").and_then(|d| d.get("elapsed_time")))
+        .and_then(|x| x.as_f64());
+    if let Some(secs) = upstream_secs.filter(|s| s.is_finite() && *s >= 0.0) {
+        meta.insert(
+            "upstream_latency_ms".to_string(),
+            json!((secs * 1000.0).round() as u64),
+        );
+    }
+    (!meta.is_empty()).then_some(Value::Object(meta))
+}
+
+/// 流式事件中的错误（仅保留状态码与错误码，不回显上游 message）
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DifyStreamError {
+    status: u16,
+    code: String,
+}
+
+/// SSE 事件累积器：把流式事件还原为与阻塞模式同形的响应 JSON，复用同一套输出提取逻辑。
+#[derive(Debug, Default)]
+struct DifyStreamAccumulator {
+    answer: String,
+    has_answer: bool,
+    conversation_id: Option<String>,
+    message_id: Option<String>,
+    workflow_run_id: Option<String>,
+    metadata: Option<Value>,
+    workflow_finished: Option<Value>,
+    finished: bool,
+    error: Option<DifyStreamError>,
+}
+
+impl DifyStreamAccumulator {
+    fn take_id(slot: &mut Option<String>, event: &Value, key: &str) {
+        if let Some(id) = event
+            .get(key)
+            .and_then(|x| x.as_str())
+            .filter(|s| !s.is_empty())
+        {
+            *slot = Some(id.to_string());
+        }
+    }
+
+    /// 处理一条 `data:` 事件；无法解析的事件直接忽略（如 keep-alive）。
+    fn on_event(&mut self, data: &str) {
+        let Ok(event) = serde_json::from_str::<Value>(data) else {
+            return;
+        };
+        Self::take_id(&mut self.conversation_id, &event, "conversation_id");
+        Self::take_id(&mut self.message_id, &event, "message_id");
+        Self::take_id(&mut self.workflow_run_id, &event, "workflow_run_id");
+
+        match event.get("event").and_then(|x| x.as_str()).unwrap_or("") {
+            "message" | "agent_message" => {
+                if let Some(chunk) = event.get("answer").and_then(|x| x.as_str()) {
+                    self.answer.push_str(chunk);
+                    self.has_answer = true;
+                }
+            }
+            "message_replace" => {
+                if let Some(answer) = event.get("answer").and_then(|x| x.as_str()) {
+                    self.answer = answer.to_string();
+                    self.has_answer = true;
+                }
+            }
+            "text_chunk" => {
+                if let Some(chunk) = event
+                    .get("data")
+                    .and_then(|d| d.get("text"))
+                    .and_then(|x| x.as_str())
+                {
+                    self.answer.push_str(chunk);
+                }
+            }
+            "message_end" => {
+                self.metadata = event.get("metadata").cloned();
+                self.finished = true;
+            }
+            "workflow_finished" => {
+                self.workflow_finished = event.get("data").cloned();
+                self.finished = true;
+            }
+            "error" => {
+                self.error = Some(DifyStreamError {
+                    status: event
+                        .get("status")
+                        .and_then(|x| x.as_u64())
+                        .and_then(|x| u16::try_from(x).ok())
+                        .unwrap_or(500),
+                    code: event
+                        .get("code")
+                        .and_then(|x| x.as_str())
+                        .unwrap_or("unknown")
+                        .chars()
+                        .take(64)
+                        .collect(),
+                });
+                self.finished = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// 还原为阻塞模式响应形状：工作流为 `{ data: {...} }`，Chat/Completion 为 `{ answer, metadata }`。
+    fn into_response(self, app_type: DifyAppType) -> Value {
+        let mut out = serde_json::Map::new();
+        if app_type == DifyAppType::Workflow {
+            let mut data = self.workflow_finished.unwrap_or_else(|| json!({}));
+            if data.get("outputs").is_none_or(Value::is_null) && !self.answer.is_empty() {
+                data["text"] = Value::String(self.answer);
+            }
+            out.insert("data".to_string(), data);
+        } else {
+            if self.has_answer {
+                out.insert("answer".to_string(), Value::String(self.answer));
+            }
+            if let Some(metadata) = self.metadata {
+                out.insert("metadata".to_string(), metadata);
+            }
+        }
+        for (key, id) in [
+            ("conversation_id", self.conversation_id),
+            ("message_id", self.message_id),
+            ("workflow_run_id", self.workflow_run_id),
+        ] {
+            if let Some(id) = id {
+                out.insert(key.to_string(), Value::String(id));
+            }
+        }
+        Value::Object(out)
+    }
+}
+
+/// 逐行解析 SSE：`data:` 行累积，空行分隔事件。
+#[derive(Debug, Default)]
+struct SseLineParser {
+    buffer: Vec<u8>,
+    data: String,
+}
+
+impl SseLineParser {
+    fn feed(&mut self, chunk: &[u8], acc: &mut DifyStreamAccumulator) {
+        self.buffer.extend_from_slice(chunk);
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            self.on_line(line.trim_end_matches(['\r', '\n']), acc);
+        }
+    }
+
+    fn on_line(&mut self, line: &str, acc: &mut DifyStreamAccumulator) {
+        if line.is_empty() {
+            self.dispatch(acc);
+        } else if let Some(rest) = line.strip_prefix("data:") {
+            if !self.data.is_empty() {
+                self.data.push('\n');
+            }
+            self.data.push_str(rest.strip_prefix(' ').unwrap_or(rest));
+        }
+        // 其他字段（event:/id:/注释行）Dify 未使用，忽略
+    }
+
+    fn dispatch(&mut self, acc: &mut DifyStreamAccumulator) {
+        if !self.data.is_empty() {
+            acc.on_event(&self.data);
+            self.data.clear();
+        }
+    }
+
+    fn finish(&mut self, acc: &mut DifyStreamAccumulator) {
+        let rest = std::mem::take(&mut self.buffer);
+        let rest = String::from_utf8_lossy(&rest);
+        self.on_line(rest.trim_end_matches(['\r', '\n']), acc);
+        self.dispatch(acc);
+    }
+}
+
+/// 输出值转文本：字符串原样返回，其余（对象/数组/数字等）序列化为规范 JSON（键有序），保证评估稳定。
+fn output_value_to_text(v: &Value) -> Option<String> {
+    match v {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(stable_json_string(other)),
+    }
+}
+
+/// 按显式选择器提取输出：`/` 开头为 JSON Pointer（作用于整个响应），否则为输出变量名。
+fn extract_output_with_selector(v: &Value, selector: &str) -> Option<String> {
+    let selector = selector.trim();
+    if selector.starts_with('/') {
+        return v.pointer(selector).and_then(output_value_to_text);
+    }
+    v.get("data")
+        .and_then(|d| d.get("outputs"))
+        .or_else(|| v.get("outputs"))
+        .and_then(|outputs| outputs.get(selector))
+        .and_then(output_value_to_text)
+}
+
 fn extract_output_from_dify_response(v: &Value) -> Option<String> {
     // Prefer common shapes:
     // - { answer: "..." } (chat-style)
@@ -114,6 +673,10 @@ fn extract_output_from_dify_response(v: &Value) -> Option<String> {
             if string_fields.len() == 1 {
                 return Some(string_fields[0].clone());
             }
+            // 单个结构化输出（对象/数组）：按规范 JSON 评估
+            if obj.len() == 1 {
+                return obj.values().next().and_then(output_value_to_text);
+            }
         }
     }
     if let Some(text) = v
@@ -129,6 +692,28 @@ fn extract_output_from_dify_response(v: &Value) -> Option<String> {
     None
 }
 
+/// 状态码 → 执行错误（HTTP 状态或流式 error 事件）；不回显上游 message。
+fn map_status_error(test_case_id: &str, status: u16, code: Option<&str>) -> ExecutionError {
+    let detail = match code {
+        Some(code) => format!("HTTP {status}, code={code}"),
+        None => format!("HTTP {status}"),
+    };
+    match status {
+        401 | 403 => ExecutionError::InvalidCredentials {
+            test_case_id: test_case_id.to_string(),
+            message: format!("Dify 鉴权失败（{detail}）"),
+        },
+        400 => ExecutionError::InvalidRequest {
+            test_case_id: test_case_id.to_string(),
+            message: format!("Dify 请求参数错误（{detail}）"),
+        },
+        _ => ExecutionError::UpstreamError {
+            test_case_id: test_case_id.to_string(),
+            message: format!("Dify 上游错误（{detail}）"),
+        },
+    }
+}
+
 #[async_trait]
 impl ExecutionTarget for DifyExecutionTarget {
     async fn execute(
@@ -146,20 +731,32 @@ impl ExecutionTarget for DifyExecutionTarget {
             });
         }
 
-        let (api_url, workflow_id, prompt_variable, api_key) = match execution_target_config {
-            ExecutionTargetConfig::Dify {
-                api_url,
-                workflow_id,
-                prompt_variable,
-                api_key,
-            } => (api_url, workflow_id, prompt_variable, api_key.as_deref()),
-            _ => {
-                return Err(ExecutionError::InvalidRequest {
-                    test_case_id: test_case_id.to_string(),
-                    message: "execution_target_config 不是 Dify 配置".to_string(),
-                });
-            }
-        };
+        let (api_url, workflow_id, prompt_variable, api_key, output_selector, app_type, mode) =
+            match execution_target_config {
+                ExecutionTargetConfig::Dify {
+                    api_url,
+                    workflow_id,
+                    prompt_variable,
+                    api_key,
+                    output_selector,
+                    app_type,
+                    response_mode,
+                } => (
+                    api_url,
+                    workflow_id,
+                    prompt_variable,
+                    api_key.as_deref(),
+                    output_selector.as_deref().filter(|s| !s.trim().is_empty()),
+                    *app_type,
+                    *response_mode,
+                ),
+                _ => {
+                    return Err(ExecutionError::InvalidRequest {
+                        test_case_id: test_case_id.to_string(),
+                        message: "execution_target_config 不是 Dify 配置".to_string(),
+                    });
+                }
+            };
 
         let Some(api_key) = api_key else {
             return Err(ExecutionError::InvalidCredentials {
@@ -168,77 +765,84 @@ impl ExecutionTarget for DifyExecutionTarget {
             });
         };
 
-        let url = build_workflow_run_url(api_url, workflow_id);
-        let inputs = build_inputs_with_prompt(input, prompt_variable, prompt);
-
-        let body = json!({
-            "inputs": inputs,
-            "response_mode": "blocking",
-            "user": "prompt-faster",
-        });
+        let url = build_app_url(api_url, app_type, workflow_id);
+        let guard = upstream_guard(&normalize_base_url_for_v1(api_url), api_key);
+        let call = DifyCall {
+            url: &url,
+            api_key,
+            app_type,
+            mode,
+            test_case_id,
+            guard: &guard,
+        };
+        let conversation =
+            parse_conversation_input(input).map_err(|message| ExecutionError::InvalidRequest {
+                test_case_id: test_case_id.to_string(),
+                message,
+            })?;
 
         let start = Instant::now();
-        let response = self
-            .client
-            .post(url)
-            .header("Authorization", format!("Bearer {api_key}"))
-            .header("X-Correlation-Id", test_case_id)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    ExecutionError::Timeout {
+        let (v, token_usage, turns) = match conversation {
+            None => {
+                let body = build_request_body(app_type, mode, input, prompt_variable, prompt)
+                    .map_err(|message| ExecutionError::InvalidRequest {
                         test_case_id: test_case_id.to_string(),
-                        message: "Dify 请求超时".to_string(),
-                    }
-                } else {
-                    ExecutionError::Network {
+                        message,
+                    })?;
+                let v = self.send(&call, &body).await?;
+                let usage = extract_token_usage(&v);
+                (v, usage, 1)
+            }
+            Some(conversation) => {
+                if app_type != DifyAppType::Chat {
+                    return Err(ExecutionError::InvalidRequest {
                         test_case_id: test_case_id.to_string(),
-                        message: "Dify 网络请求失败".to_string(),
-                    }
+                        message: "多轮对话用例仅支持 Dify 对话（chat）应用".to_string(),
+                    });
                 }
-            })?;
+                self.replay_conversation(&call, input, prompt_variable, prompt, &conversation)
+                    .await?
+            }
+        };
 
-        let status = response.status().as_u16();
-        if !(200..=299).contains(&status) {
-            // 重要：错误消息不得包含 prompt/input 原文或上游 body（可能回显敏感内容）。
-            return Err(match status {
-                401 | 403 => ExecutionError::InvalidCredentials {
-                    test_case_id: test_case_id.to_string(),
-                    message: format!("Dify 鉴权失败（HTTP {status}）"),
-                },
-                400 => ExecutionError::InvalidRequest {
-                    test_case_id: test_case_id.to_string(),
-                    message: "Dify 请求参数错误（HTTP 400）".to_string(),
-                },
-                _ => ExecutionError::UpstreamError {
-                    test_case_id: test_case_id.to_string(),
-                    message: format!("Dify 上游错误（HTTP {status}）"),
-                },
+        if let Some(run_status) = v
+            .get("data")
+            .and_then(|d| d.get("status"))
+            .and_then(|x| x.as_str())
+            .filter(|s| matches!(*s, "failed" | "stopped"))
+        {
+            return Err(ExecutionError::UpstreamError {
+                test_case_id: test_case_id.to_string(),
+                message: format!("Dify 工作流运行未成功（status={run_status}）"),
             });
         }
 
-        let v = response
-            .json::<Value>()
-            .await
-            .map_err(|_e| ExecutionError::ParseError {
-                test_case_id: test_case_id.to_string(),
-                message: "解析 Dify 响应失败".to_string(),
-            })?;
-
-        let output =
-            extract_output_from_dify_response(&v).ok_or_else(|| ExecutionError::ParseError {
-                test_case_id: test_case_id.to_string(),
-                message: "Dify 响应缺少可识别的输出字段".to_string(),
-            })?;
+        let output = match output_selector {
+            Some(selector) => extract_output_with_selector(&v, selector).ok_or_else(|| {
+                ExecutionError::ParseError {
+                    test_case_id: test_case_id.to_string(),
+                    message: format!("Dify 响应缺少输出选择器指定的字段（{selector}）"),
+                }
+            })?,
+            None => {
+                extract_output_from_dify_response(&v).ok_or_else(|| ExecutionError::ParseError {
+                    test_case_id: test_case_id.to_string(),
+                    message: "Dify 响应缺少可识别的输出字段".to_string(),
+                })?
+            }
+        };
 
         Ok(ExecutionResult {
             test_case_id: test_case_id.to_string(),
             output,
             latency_ms: start.elapsed().as_millis() as u64,
-            token_usage: None,
-            raw_response: None,
+            token_usage,
+            raw_response: extract_run_metadata(&v).map(|mut meta| {
+                if turns > 1 {
+                    meta["replayed_turns"] = json!(turns);
+                }
+                meta
+            }),
         })
     }
 
@@ -250,7 +854,7 @@ impl ExecutionTarget for DifyExecutionTarget {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{header, method, path};
+    use wiremock::matchers::{body_partial_json, header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     fn dify_config(server: &MockServer) -> ExecutionTargetConfig {
@@ -259,9 +863,35 @@ mod tests {
             workflow_id: "wf-1".to_string(),
             prompt_variable: "prompt".to_string(),
             api_key: Some("dify_test_key".to_string()),
+            output_selector: None,
+            app_type: DifyAppType::Workflow,
+            response_mode: DifyResponseMode::Blocking,
         }
     }
 
+    fn dify_app_config(
+        server: &MockServer,
+        app_type: DifyAppType,
+        response_mode: DifyResponseMode,
+    ) -> ExecutionTargetConfig {
+        let mut config = dify_config(server);
+        if let ExecutionTargetConfig::Dify {
+            app_type: a,
+            response_mode: m,
+            ..
+        } = &mut config
+        {
+            *a = app_type;
+            *m = response_mode;
+        }
+        config
+    }
+
+    fn sse(events: &[Value]) -> ResponseTemplate {
+        let body: String = events.iter().map(|e| format!("data: {e}\n\n")).collect();
+        ResponseTemplate::new(200).set_body_raw(body, "text/event-stream")
+    }
+
     #[tokio::test]
     async fn dify_execution_target_parses_output_and_injects_prompt_variable() {
         let server = MockServer::start().await;
@@ -275,7 +905,7 @@ mod tests {
             .mount(&server)
             .await;
 
-        let target = DifyExecutionTarget::new();
+        let target = DifyExecutionTarget::new().unwrap();
         let mut input = HashMap::new();
         input.insert("x".to_string(), Value::String("y".to_string()));
 
@@ -297,7 +927,7 @@ mod tests {
             .mount(&server)
             .await;
 
-        let target = DifyExecutionTarget::new();
+        let target = DifyExecutionTarget::new().unwrap();
         let input = HashMap::new();
         let err = target
             .execute(&dify_config(&server), "p", &input, "tc-401")
@@ -316,7 +946,7 @@ mod tests {
             .mount(&server)
             .await;
 
-        let target = DifyExecutionTarget::new();
+        let target = DifyExecutionTarget::new().unwrap();
         let mut input = HashMap::new();
         input.insert(
             "secret".to_string(),
@@ -336,4 +966,346 @@ mod tests {
         let s = err.to_string();
         assert!(!s.contains("TOPSECRET_DO_NOT_ECHO"), "err={s}");
     }
+
+    #[test]
+    fn selector_extracts_named_output_or_json_pointer() {
+        let v = json!({
+            "data": { "outputs": { "summary": "S", "meta": { "b": 2, "a": 1 } } }
+        });
+        assert_eq!(
+            extract_output_with_selector(&v, "summary").as_deref(),
+            Some("S")
+        );
+        assert_eq!(
+            extract_output_with_selector(&v, "/data/outputs/meta/a").as_deref(),
+            Some("1")
+        );
+        assert_eq!(extract_output_with_selector(&v, "missing"), None);
+    }
+
+    #[test]
+    fn object_outputs_are_serialized_as_canonical_json() {
+        let v = json!({ "data": { "outputs": { "result": { "b": [2, 1], "a": "x" } } } });
+        let expected = r#"{"a":"x","b":[2,1]}"#;
+        assert_eq!(
+            extract_output_with_selector(&v, "result").as_deref(),
+            Some(expected)
+        );
+        assert_eq!(
+            extract_output_from_dify_response(&v).as_deref(),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn parse_dify_execution_settings_reads_selector_and_app_settings() {
+        let settings = parse_dify_execution_settings(
+            r#"{"targetPromptVariable":"p","outputSelector":" summary ","appType":"chat","responseMode":"streaming","credentialId":"cred-1"}"#,
+        )
+        .unwrap();
+        assert_eq!(settings.prompt_variable, "p");
+        assert_eq!(settings.credential_id.as_deref(), Some("cred-1"));
+        assert_eq!(settings.output_selector.as_deref(), Some("summary"));
+        assert_eq!(settings.app_type, DifyAppType::Chat);
+        assert_eq!(settings.response_mode, DifyResponseMode::Streaming);
+
+        let settings =
+            parse_dify_execution_settings(r#"{"target_prompt_variable":"p","app_type":"bogus"}"#)
+                .unwrap();
+        assert_eq!(settings.output_selector, None);
+        assert_eq!(settings.credential_id, None);
+        assert_eq!(settings.app_type, DifyAppType::Workflow);
+        assert_eq!(settings.response_mode, DifyResponseMode::Blocking);
+        assert_eq!(
+            parse_dify_execution_settings(r#"{"outputSelector":"x"}"#),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn dify_execution_target_reports_missing_selected_output() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/workflows/wf-1/run"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": { "outputs": { "text": "OK" } }
+            })))
+            .mount(&server)
+            .await;
+
+        let mut config = dify_config(&server);
+        if let ExecutionTargetConfig::Dify {
+            output_selector, ..
+        } = &mut config
+        {
+            *output_selector = Some("summary".to_string());
+        }
+
+        let err = DifyExecutionTarget::new()
+            .unwrap()
+            .execute(&config, "p", &HashMap::new(), "tc-1")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::ParseError { .. }));
+        assert!(err.to_string().contains("summary"));
+    }
+
+    #[tokio::test]
+    async fn chat_blocking_sends_query_and_conversation_id_and_maps_usage() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/chat-messages"))
+            .and(body_partial_json(json!({
+                "query": "hi",
+                "conversation_id": "conv-1",
+                "response_mode": "blocking",
+                "inputs": { "prompt": "SYS", "lang": "zh" }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "answer": "hello",
+                "conversation_id": "conv-1",
+                "message_id": "msg-1",
+                "metadata": { "usage": {
+                    "prompt_tokens": 5, "completion_tokens": 7, "total_tokens": 12, "latency": 1.25
+                } }
+            })))
+            .mount(&server)
+            .await;
+
+        let mut input = HashMap::new();
+        input.insert("query".to_string(), json!("hi"));
+        input.insert("conversation_id".to_string(), json!("conv-1"));
+        input.insert("lang".to_string(), json!("zh"));
+
+        let r = DifyExecutionTarget::new()
+            .unwrap()
+            .execute(
+                &dify_app_config(&server, DifyAppType::Chat, DifyResponseMode::Blocking),
+                "SYS",
+                &input,
+                "tc-1",
+            )
+            .await
+            .unwrap();
+        assert_eq!(r.output, "hello");
+        let usage = r.token_usage.unwrap();
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens), (5, 7));
+        assert_eq!(usage.total_tokens, 12);
+        let meta = r.raw_response.unwrap();
+        assert_eq!(meta["conversation_id"], "conv-1");
+        assert_eq!(meta["message_id"], "msg-1");
+        assert_eq!(meta["upstream_latency_ms"], 1250);
+    }
+
+    #[tokio::test]
+    async fn chat_requires_query_input() {
+        let server = MockServer::start().await;
+        let err = DifyExecutionTarget::new()
+            .unwrap()
+            .execute(
+                &dify_app_config(&server, DifyAppType::Chat, DifyResponseMode::Blocking),
+                "SYS",
+                &HashMap::new(),
+                "tc-1",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::InvalidRequest { .. }));
+        assert!(err.to_string().contains(DIFY_QUERY_INPUT_KEY));
+    }
+
+    #[tokio::test]
+    async fn chat_streaming_concatenates_message_events() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/chat-messages"))
+            .and(body_partial_json(json!({ "response_mode": "streaming" })))
+            .respond_with(sse(&[
+                json!({ "event": "ping" }),
+                json!({ "event": "agent_message", "answer": "Hel", "conversation_id": "c-9", "message_id": "m-9" }),
+                json!({ "event": "message", "answer": "lo" }),
+                json!({ "event": "message_end", "conversation_id": "c-9", "metadata": { "usage": {
+                    "prompt_tokens": 1, "completion_tokens": 2, "total_tokens": 3
+                } } }),
+            ]))
+            .mount(&server)
+            .await;
+
+        let mut input = HashMap::new();
+        input.insert("query".to_string(), json!("hi"));
+        let r = DifyExecutionTarget::new()
+            .unwrap()
+            .execute(
+                &dify_app_config(&server, DifyAppType::Chat, DifyResponseMode::Streaming),
+                "SYS",
+                &input,
+                "tc-1",
+            )
+            .await
+            .unwrap();
+        assert_eq!(r.output, "Hello");
+        assert_eq!(r.token_usage.unwrap().total_tokens, 3);
+        assert_eq!(r.raw_response.unwrap()["conversation_id"], "c-9");
+    }
+
+    #[tokio::test]
+    async fn workflow_streaming_uses_workflow_finished_outputs() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/workflows/wf-1/run"))
+            .and(body_partial_json(json!({ "response_mode": "streaming" })))
+            .respond_with(sse(&[
+                json!({ "event": "workflow_started", "workflow_run_id": "run-1" }),
+                json!({ "event": "text_chunk", "data": { "text": "partial" } }),
+                json!({ "event": "workflow_finished", "workflow_run_id": "run-1", "data": {
+                    "status": "succeeded",
+                    "outputs": { "result": { "b": 1, "a": 2 } },
+                    "total_tokens": 42,
+                    "elapsed_time": 0.5
+                } }),
+            ]))
+            .mount(&server)
+            .await;
+
+        let r = DifyExecutionTarget::new()
+            .unwrap()
+            .execute(
+                &dify_app_config(&server, DifyAppType::Workflow, DifyResponseMode::Streaming),
+                "SYS",
+                &HashMap::new(),
+                "tc-1",
+            )
+            .await
+            .unwrap();
+        assert_eq!(r.output, r#"{"a":2,"b":1}"#);
+        assert_eq!(r.token_usage.unwrap().total_tokens, 42);
+        let meta = r.raw_response.unwrap();
+        assert_eq!(meta["workflow_run_id"], "run-1");
+        assert_eq!(meta["upstream_latency_ms"], 500);
+    }
+
+    #[tokio::test]
+    async fn completion_streaming_error_event_is_mapped_without_leaking_message() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/completion-messages"))
+            .respond_with(sse(&[
+                json!({ "event": "message", "answer": "par" }),
+                json!({ "event": "error", "status": 400, "code": "invalid_param", "message": "TOPSECRET_DO_NOT_ECHO" }),
+            ]))
+            .mount(&server)
+            .await;
+
+        let err = DifyExecutionTarget::new()
+            .unwrap()
+            .execute(
+                &dify_app_config(
+                    &server,
+                    DifyAppType::Completion,
+                    DifyResponseMode::Streaming,
+                ),
+                "TOPSECRET_DO_NOT_ECHO",
+                &HashMap::new(),
+                "tc-1",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::InvalidRequest { .. }));
+        let s = err.to_string();
+        assert!(s.contains("invalid_param"), "err={s}");
+        assert!(!s.contains("TOPSECRET_DO_NOT_ECHO"), "err={s}");
+    }
+
+    #[test]
+    fn sse_parser_handles_events_split_across_chunks() {
+        let mut parser = SseLineParser::default();
+        let mut acc = DifyStreamAccumulator::default();
+        let raw = "data: {\"event\":\"message\",\"answer\":\"a\"}\r\n\r\ndata: {\"event\":\"message\",\"answer\":\"b\"}\n\ndata: {\"event\":\"message_end\"}";
+        for chunk in raw.as_bytes().chunks(7) {
+            parser.feed(chunk, &mut acc);
+        }
+        parser.finish(&mut acc);
+        assert!(acc.finished);
+        assert_eq!(acc.into_response(DifyAppType::Chat)["answer"], "ab");
+    }
+
+    #[tokio::test]
+    async fn chat_conversation_is_replayed_through_conversation_id() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/chat-messages"))
+            .and(body_partial_json(json!({ "query": "hi", "inputs": { "prompt": "SYS" } })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "answer": "hello",
+                "conversation_id": "conv-1",
+                "metadata": { "usage": { "prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2 } }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat-messages"))
+            .and(body_partial_json(
+                json!({ "query": "and then?", "conversation_id": "conv-1" }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "answer": "final",
+                "conversation_id": "conv-1",
+                "metadata": { "usage": { "prompt_tokens": 3, "completion_tokens": 4, "total_tokens": 7 } }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut input = HashMap::new();
+        input.insert(
+            "conversation".to_string(),
+            json!([
+                { "role": "user", "content": "hi" },
+                { "role": "assistant", "content": "ignored by dify" },
+                { "role": "user", "content": "and then?" }
+            ]),
+        );
+
+        let r = DifyExecutionTarget::new()
+            .unwrap()
+            .execute(
+                &dify_app_config(&server, DifyAppType::Chat, DifyResponseMode::Blocking),
+                "SYS",
+                &input,
+                "tc-1",
+            )
+            .await
+            .unwrap();
+        assert_eq!(r.output, "final");
+        let usage = r.token_usage.unwrap();
+        assert_eq!(usage.total_tokens, 9);
+        assert_eq!(usage.prompt_tokens, 4);
+        let meta = r.raw_response.unwrap();
+        assert_eq!(meta["conversation_id"], "conv-1");
+        assert_eq!(meta["replayed_turns"], 2);
+    }
+
+    #[tokio::test]
+    async fn conversation_cases_require_chat_app() {
+        let server = MockServer::start().await;
+        let mut input = HashMap::new();
+        input.insert(
+            "conversation".to_string(),
+            json!([{ "role": "user", "content": "hi" }]),
+        );
+
+        let err = DifyExecutionTarget::new()
+            .unwrap()
+            .execute(&dify_config(&server), "SYS", &input, "tc-1")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::InvalidRequest { .. }));
+    }
 }
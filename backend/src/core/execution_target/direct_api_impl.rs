@@ -6,9 +6,12 @@ use tokio::time::Instant;
 
 use crate::core::execution_target::ExecutionError;
 use crate::core::traits::ExecutionTarget;
-use crate::domain::models::ExecutionResult;
+use crate::domain::models::{
+    ConversationRole, ConversationTurn, ExecutionResult, LlmProviderRegistry,
+    parse_conversation_input,
+};
 use crate::domain::types::ExecutionTargetConfig;
-use crate::infra::external::http_client::create_http_client;
+use crate::infra::external::http_client::{HttpClientError, create_http_client};
 use crate::infra::external::llm_client::{ChatCompletionsRequest, ChatMessage, LlmConnectionError};
 
 #[derive(Debug, Clone)]
@@ -16,16 +19,12 @@ pub struct DirectApiExecutionTarget {
     client: Client,
 }
 
-impl Default for DirectApiExecutionTarget {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl DirectApiExecutionTarget {
-    pub fn new() -> Self {
-        let client = create_http_client().unwrap_or_else(|_| Client::new());
-        Self { client }
+    /// 按出站网络配置创建；配置无效时返回错误（不回退为无 SSRF 防护的默认客户端）
+    pub fn new() -> Result<Self, HttpClientError> {
+        Ok(Self {
+            client: create_http_client()?,
+        })
     }
 }
 
@@ -49,6 +48,38 @@ fn render_user_prompt(
         .replace("{test_case_id}", test_case_id)
 }
 
+/// 构造消息数组：system 为待优化 Prompt；多轮对话用例按原轮次展开（不套用 user_prompt_template），
+/// 否则渲染单条 user 消息。
+fn build_messages(
+    template: &str,
+    prompt: &str,
+    input: &HashMap<String, serde_json::Value>,
+    test_case_id: &str,
+    conversation: Option<Vec<ConversationTurn>>,
+) -> Vec<ChatMessage> {
+    let mut messages = vec![ChatMessage {
+        role: "system".to_string(),
+        content: prompt.to_string(),
+    }];
+    match conversation {
+        Some(turns) => messages.extend(turns.into_iter().map(|turn| {
+            ChatMessage {
+                role: match turn.role {
+                    ConversationRole::User => "user",
+                    ConversationRole::Assistant => "assistant",
+                }
+                .to_string(),
+                content: turn.content,
+            }
+        })),
+        None => messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: render_user_prompt(template, prompt, input, test_case_id),
+        }),
+    }
+    messages
+}
+
 fn map_llm_error(test_case_id: &str, e: LlmConnectionError) -> ExecutionError {
     match e {
         LlmConnectionError::InvalidCredentials | LlmConnectionError::Forbidden => {
@@ -101,25 +132,28 @@ impl ExecutionTarget for DirectApiExecutionTarget {
             });
         }
 
-        let (base_url, model_name, user_prompt_template, api_key) = match execution_target_config {
-            ExecutionTargetConfig::DirectModel {
-                base_url,
-                model_name,
-                user_prompt_template,
-                api_key,
-            } => (
-                base_url.as_str(),
-                model_name.as_str(),
-                user_prompt_template.as_str(),
-                api_key.as_deref(),
-            ),
-            _ => {
-                return Err(ExecutionError::InvalidRequest {
-                    test_case_id: test_case_id.to_string(),
-                    message: "execution_target_config 不是 DirectModel 配置".to_string(),
-                });
-            }
-        };
+        let (base_url, model_name, user_prompt_template, api_key, provider) =
+            match execution_target_config {
+                ExecutionTargetConfig::DirectModel {
+                    base_url,
+                    model_name,
+                    user_prompt_template,
+                    api_key,
+                    provider,
+                } => (
+                    base_url.as_str(),
+                    model_name.as_str(),
+                    user_prompt_template.as_str(),
+                    api_key.as_deref(),
+                    provider.as_ref(),
+                ),
+                _ => {
+                    return Err(ExecutionError::InvalidRequest {
+                        test_case_id: test_case_id.to_string(),
+                        message: "execution_target_config 不是 DirectModel 配置".to_string(),
+                    });
+                }
+            };
 
         let Some(api_key) = api_key else {
             return Err(ExecutionError::InvalidCredentials {
@@ -140,27 +174,34 @@ impl ExecutionTarget for DirectApiExecutionTarget {
             });
         }
 
-        let user_prompt = render_user_prompt(user_prompt_template, prompt, input, test_case_id);
+        let conversation =
+            parse_conversation_input(input).map_err(|message| ExecutionError::InvalidRequest {
+                test_case_id: test_case_id.to_string(),
+                message,
+            })?;
 
         let req = ChatCompletionsRequest {
             model: model_name.to_string(),
-            messages: vec![
-                ChatMessage {
-                    role: "system".to_string(),
-                    content: prompt.to_string(),
-                },
-                ChatMessage {
-                    role: "user".to_string(),
-                    content: user_prompt,
-                },
-            ],
+            messages: build_messages(
+                user_prompt_template,
+                prompt,
+                input,
+                test_case_id,
+                conversation,
+            ),
         };
 
+        // 旧配置未记录 Provider：按通用 OpenAI 兼容协议调用
+        let provider = provider
+            .cloned()
+            .unwrap_or_else(LlmProviderRegistry::openai_compatible);
+
         let start = Instant::now();
-        let output = crate::infra::external::llm_client::chat_completions(
+        let output = crate::infra::external::llm_client::chat_completions_with_provider(
             &self.client,
             base_url,
             api_key,
+            &provider,
             test_case_id,
             &req,
         )
@@ -185,7 +226,7 @@ impl ExecutionTarget for DirectApiExecutionTarget {
 mod tests {
     use super::*;
     use serde_json::json;
-    use wiremock::matchers::{header, method, path};
+    use wiremock::matchers::{body_partial_json, header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     fn direct_config(server: &MockServer) -> ExecutionTargetConfig {
@@ -194,6 +235,7 @@ mod tests {
             model_name: "m1".to_string(),
             user_prompt_template: "INPUT={input}".to_string(),
             api_key: Some("sk-test".to_string()),
+            provider: None,
         }
     }
 
@@ -210,7 +252,7 @@ mod tests {
             .mount(&server)
             .await;
 
-        let target = DirectApiExecutionTarget::new();
+        let target = DirectApiExecutionTarget::new().unwrap();
         let mut input = HashMap::new();
         input.insert("b".to_string(), json!(2));
         input.insert("a".to_string(), json!(1));
@@ -223,6 +265,38 @@ mod tests {
         assert_eq!(r.output, "RESULT");
     }
 
+    #[tokio::test]
+    async fn direct_api_execution_target_uses_provider_deployment_path_and_auth_header() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/openai/deployments/gpt-4o/chat/completions"))
+            .and(header("api-key", "sk-test"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{ "message": { "content": "AZURE" } }]
+            })))
+            .mount(&server)
+            .await;
+
+        let mut config = direct_config(&server);
+        if let ExecutionTargetConfig::DirectModel {
+            model_name,
+            provider,
+            ..
+        } = &mut config
+        {
+            *model_name = "gpt-4o".to_string();
+            *provider = LlmProviderRegistry::builtin().get("azure_openai").cloned();
+        }
+
+        let r = DirectApiExecutionTarget::new()
+            .unwrap()
+            .execute(&config, "SYS", &HashMap::new(), "tc-azure")
+            .await
+            .unwrap();
+        assert_eq!(r.output, "AZURE");
+    }
+
     #[tokio::test]
     async fn direct_api_execution_target_error_message_does_not_leak_prompt_or_input() {
         let server = MockServer::start().await;
@@ -233,7 +307,7 @@ mod tests {
             .mount(&server)
             .await;
 
-        let target = DirectApiExecutionTarget::new();
+        let target = DirectApiExecutionTarget::new().unwrap();
         let mut input = HashMap::new();
         input.insert(
             "secret".to_string(),
@@ -253,4 +327,60 @@ mod tests {
         let s = err.to_string();
         assert!(!s.contains("TOPSECRET_DO_NOT_ECHO"), "err={s}");
     }
+
+    #[tokio::test]
+    async fn direct_api_execution_target_renders_conversation_as_message_array() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(json!({
+                "messages": [
+                    { "role": "system", "content": "SYS" },
+                    { "role": "user", "content": "hi" },
+                    { "role": "assistant", "content": "hello" },
+                    { "role": "user", "content": "how are you?" }
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "choices": [{ "message": { "content": "fine" } }]
+            })))
+            .mount(&server)
+            .await;
+
+        let mut input = HashMap::new();
+        input.insert(
+            "conversation".to_string(),
+            json!([
+                { "role": "user", "content": "hi" },
+                { "role": "assistant", "content": "hello" },
+                { "role": "user", "content": "how are you?" }
+            ]),
+        );
+
+        let r = DirectApiExecutionTarget::new()
+            .unwrap()
+            .execute(&direct_config(&server), "SYS", &input, "tc-1")
+            .await
+            .unwrap();
+        assert_eq!(r.output, "fine");
+    }
+
+    #[tokio::test]
+    async fn direct_api_execution_target_rejects_conversation_not_ending_with_user() {
+        let server = MockServer::start().await;
+        let mut input = HashMap::new();
+        input.insert(
+            "conversation".to_string(),
+            json!([{ "role": "user", "content": "hi" }, { "role": "assistant", "content": "SECRET_REPLY" }]),
+        );
+
+        let err = DirectApiExecutionTarget::new()
+            .unwrap()
+            .execute(&direct_config(&server), "SYS", &input, "tc-1")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::InvalidRequest { .. }));
+        assert!(!err.to_string().contains("SECRET_REPLY"));
+    }
 }
@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[derive(Debug, Error, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum ExecutionError {
     #[error("network error (test_case_id={test_case_id}): {message}")]
     Network {
@@ -49,4 +51,38 @@ pub enum ExecutionError {
         test_case_id: String,
         message: String,
     },
+
+    /// 任务被暂停 / 终止，用例未执行或在途请求已被丢弃
+    #[error("cancelled (test_case_id={test_case_id}): {message}")]
+    Cancelled {
+        test_case_id: String,
+        message: String,
+    },
+}
+
+impl ExecutionError {
+    /// 是否为瞬时错误（网络 / 超时 / 上游错误），可对单条用例重试
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ExecutionError::Network { .. }
+                | ExecutionError::Timeout { .. }
+                | ExecutionError::UpstreamError { .. }
+        )
+    }
+
+    /// 错误类别（用于日志与失败点描述）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ExecutionError::Network { .. } => "network",
+            ExecutionError::InvalidRequest { .. } => "invalid_request",
+            ExecutionError::UpstreamError { .. } => "upstream_error",
+            ExecutionError::InvalidCredentials { .. } => "invalid_credentials",
+            ExecutionError::ParseError { .. } => "parse_error",
+            ExecutionError::Timeout { .. } => "timeout",
+            ExecutionError::NotImplemented { .. } => "not_implemented",
+            ExecutionError::Internal { .. } => "internal",
+            ExecutionError::Cancelled { .. } => "cancelled",
+        }
+    }
 }
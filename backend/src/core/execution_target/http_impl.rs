@@ -0,0 +1,488 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::{Client, Method};
+use serde_json::Value;
+use tokio::time::Instant;
+
+use crate::core::execution_target::ExecutionError;
+use crate::core::traits::ExecutionTarget;
+use crate::domain::models::{ExecutionResult, HTTP_CREDENTIAL_PLACEHOLDER_PREFIX, TokenUsage};
+use crate::domain::types::ExecutionTargetConfig;
+use crate::infra::external::http_client::{
+    HttpClientError, OutboundHttpConfig, create_http_client, create_http_client_with_options,
+    url_validation_from_env,
+};
+use crate::shared::url_validator::{BaseUrlValidationOptions, resolve_and_validate_base_url};
+
+/// 通用 HTTP Webhook 执行目标
+///
+/// - 请求体：按 `body_template` 渲染，占位符以 JSON 字面量替换（避免注入破坏 JSON 结构）
+/// - 请求头：`{{credential.<type>}}` 替换为运行时注入的凭证
+/// - 响应：按 `output_path` / `usage_path`（JSONPath 子集或 JSON Pointer）提取
+/// - URL 每次请求前按 SSRF 策略校验
+#[derive(Debug, Clone)]
+pub struct HttpExecutionTarget {
+    client: Client,
+    url_validation: BaseUrlValidationOptions,
+}
+
+impl HttpExecutionTarget {
+    /// 按出站网络配置创建；配置无效时返回错误（不回退为无 SSRF 防护的默认客户端）
+    pub fn new() -> Result<Self, HttpClientError> {
+        Ok(Self {
+            client: create_http_client()?,
+            url_validation: url_validation_from_env(),
+        })
+    }
+
+    /// 指定 SSRF 策略（直连，不使用出站代理/CA 配置）
+    pub fn with_url_validation(
+        url_validation: BaseUrlValidationOptions,
+    ) -> Result<Self, HttpClientError> {
+        Ok(Self {
+            client: create_http_client_with_options(
+                url_validation,
+                &OutboundHttpConfig::default(),
+            )?,
+            url_validation,
+        })
+    }
+}
+
+/// 逐个替换模板中的 `{{name}}` 占位符；`resolve` 返回替换文本。
+fn render_placeholders(
+    template: &str,
+    mut resolve: impl FnMut(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| "模板占位符缺少结束符 }}".to_string())?;
+        out.push_str(&resolve(after[..end].trim())?);
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn render_body(
+    template: &str,
+    prompt: &str,
+    input: &HashMap<String, Value>,
+    test_case_id: &str,
+) -> Result<Value, String> {
+    let rendered = render_placeholders(template, |name| {
+        let value = match name {
+            "prompt" => Value::String(prompt.to_string()),
+            "test_case_id" => Value::String(test_case_id.to_string()),
+            "input" => Value::Object(input.clone().into_iter().collect()),
+            _ => match name.strip_prefix("input.") {
+                Some(key) => input
+                    .get(key)
+                    .cloned()
+                    .ok_or_else(|| format!("测试用例缺少输入变量 {key}"))?,
+                None => return Err(format!("不支持的模板占位符 {name}")),
+            },
+        };
+        serde_json::to_string(&value).map_err(|e| e.to_string())
+    })?;
+    serde_json::from_str(&rendered).map_err(|e| format!("body_template 渲染结果不是合法 JSON: {e}"))
+}
+
+fn render_header(value: &str, credentials: &BTreeMap<String, String>) -> Result<String, String> {
+    let credential_prefix = HTTP_CREDENTIAL_PLACEHOLDER_PREFIX.trim_start_matches("{{");
+    render_placeholders(value, |name| match name.strip_prefix(credential_prefix) {
+        Some(credential_type) => credentials
+            .get(credential_type.trim())
+            .cloned()
+            .ok_or_else(|| format!("缺少凭证 {}（运行时未注入）", credential_type.trim())),
+        None => Err(format!("请求头不支持占位符 {name}")),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// 解析 JSONPath 子集：`$`、`.key`、`['key']` / `["key"]`、`[0]`。
+fn parse_json_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let invalid = || format!("无法解析 JSONPath: {path}");
+    let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            segments.push(PathSegment::Key(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let inner = after[..end].trim();
+            let quoted = inner
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+            match quoted {
+                Some(key) => segments.push(PathSegment::Key(key.to_string())),
+                None => segments.push(PathSegment::Index(
+                    inner.parse::<usize>().map_err(|_| invalid())?,
+                )),
+            }
+            rest = &after[end + 1..];
+        } else {
+            return Err(invalid());
+        }
+    }
+    Ok(segments)
+}
+
+/// 按 JSONPath 子集（`$...`）或 JSON Pointer（`/...`）提取；空路径表示整个响应。
+fn extract_path<'a>(root: &'a Value, path: &str) -> Result<Option<&'a Value>, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Ok(Some(root));
+    }
+    if path.starts_with('/') {
+        return Ok(root.pointer(path));
+    }
+    let mut current = root;
+    for segment in parse_json_path(path)? {
+        let next = match segment {
+            PathSegment::Key(key) => current.get(key.as_str()),
+            PathSegment::Index(index) => current.get(index),
+        };
+        match next {
+            Some(v) => current = v,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(current))
+}
+
+#[async_trait]
+impl ExecutionTarget for HttpExecutionTarget {
+    async fn execute(
+        &self,
+        execution_target_config: &ExecutionTargetConfig,
+        prompt: &str,
+        input: &HashMap<String, Value>,
+        test_case_id: &str,
+    ) -> Result<ExecutionResult, ExecutionError> {
+        let test_case_id = test_case_id.trim();
+        if test_case_id.is_empty() {
+            return Err(ExecutionError::InvalidRequest {
+                test_case_id: "unknown".to_string(),
+                message: "test_case_id 不能为空".to_string(),
+            });
+        }
+
+        let ExecutionTargetConfig::Http {
+            method,
+            url,
+            headers,
+            body_template,
+            output_path,
+            usage_path,
+            timeout_ms,
+            credentials,
+        } = execution_target_config
+        else {
+            return Err(ExecutionError::InvalidRequest {
+                test_case_id: test_case_id.to_string(),
+                message: "execution_target_config 不是 Http 配置".to_string(),
+            });
+        };
+        let invalid_request = |message: String| ExecutionError::InvalidRequest {
+            test_case_id: test_case_id.to_string(),
+            message,
+        };
+
+        resolve_and_validate_base_url(url, self.url_validation)
+            .await
+            .map_err(|e| invalid_request(format!("HTTP 执行目标 URL 不合法: {e}")))?;
+        let method = Method::from_bytes(method.trim().to_ascii_uppercase().as_bytes())
+            .map_err(|_| invalid_request("HTTP 执行目标 method 不合法".to_string()))?;
+
+        let mut request = self
+            .client
+            .request(method.clone(), url.trim())
+            .timeout(Duration::from_millis((*timeout_ms).max(1)))
+            .header("X-Correlation-Id", test_case_id);
+        for (name, value) in headers {
+            let value = render_header(value, credentials).map_err(|message| {
+                ExecutionError::InvalidCredentials {
+                    test_case_id: test_case_id.to_string(),
+                    message,
+                }
+            })?;
+            request = request.header(name.as_str(), value);
+        }
+        if method != Method::GET {
+            let body =
+                render_body(body_template, prompt, input, test_case_id).map_err(invalid_request)?;
+            request = request.json(&body);
+        }
+
+        let start = Instant::now();
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                ExecutionError::Timeout {
+                    test_case_id: test_case_id.to_string(),
+                    message: format!("HTTP 执行目标请求超时（{timeout_ms}ms）"),
+                }
+            } else {
+                ExecutionError::Network {
+                    test_case_id: test_case_id.to_string(),
+                    message: "HTTP 执行目标网络请求失败".to_string(),
+                }
+            }
+        })?;
+
+        let status = response.status().as_u16();
+        if !(200..=299).contains(&status) {
+            // 重要：错误消息不得包含 prompt/input 原文或上游 body（可能回显敏感内容）。
+            return Err(match status {
+                401 | 403 => ExecutionError::InvalidCredentials {
+                    test_case_id: test_case_id.to_string(),
+                    message: format!("HTTP 执行目标鉴权失败（HTTP {status}）"),
+                },
+                400 | 404 | 422 => ExecutionError::InvalidRequest {
+                    test_case_id: test_case_id.to_string(),
+                    message: format!("HTTP 执行目标请求错误（HTTP {status}）"),
+                },
+                _ => ExecutionError::UpstreamError {
+                    test_case_id: test_case_id.to_string(),
+                    message: format!("HTTP 执行目标上游错误（HTTP {status}）"),
+                },
+            });
+        }
+
+        let parse_error = |message: String| ExecutionError::ParseError {
+            test_case_id: test_case_id.to_string(),
+            message,
+        };
+        let body = response
+            .json::<Value>()
+            .await
+            .map_err(|_| parse_error("HTTP 执行目标响应不是合法 JSON".to_string()))?;
+
+        let output = match extract_path(&body, output_path).map_err(invalid_request)? {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => {
+                return Err(parse_error(format!(
+                    "HTTP 执行目标响应缺少输出字段（{output_path}）"
+                )));
+            }
+            Some(other) => serde_json::to_string(other).unwrap_or_default(),
+        };
+
+        let token_usage = match usage_path.as_deref() {
+            Some(path) => extract_path(&body, path)
+                .map_err(invalid_request)?
+                .and_then(|v| serde_json::from_value::<TokenUsage>(v.clone()).ok()),
+            None => None,
+        };
+
+        Ok(ExecutionResult {
+            test_case_id: test_case_id.to_string(),
+            output,
+            latency_ms: start.elapsed().as_millis() as u64,
+            token_usage,
+            raw_response: None,
+        })
+    }
+
+    fn name(&self) -> &str {
+        "http"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const PERMISSIVE: BaseUrlValidationOptions = BaseUrlValidationOptions {
+        allow_http: true,
+        allow_localhost: true,
+        allow_private_network: true,
+    };
+
+    fn http_config(url: String, output_path: &str) -> ExecutionTargetConfig {
+        let mut headers = BTreeMap::new();
+        headers.insert(
+            "Authorization".to_string(),
+            "Bearer {{credential.generic_llm}}".to_string(),
+        );
+        let mut credentials = BTreeMap::new();
+        credentials.insert("generic_llm".to_string(), "secret-key".to_string());
+        ExecutionTargetConfig::Http {
+            method: "POST".to_string(),
+            url,
+            headers,
+            body_template: r#"{"q": {{input.q}}, "system": {{prompt}}, "all": {{input}}}"#
+                .to_string(),
+            output_path: output_path.to_string(),
+            usage_path: Some("/meta/usage".to_string()),
+            timeout_ms: 5_000,
+            credentials,
+        }
+    }
+
+    fn input() -> HashMap<String, Value> {
+        let mut input = HashMap::new();
+        input.insert("q".to_string(), json!("say \"hi\""));
+        input
+    }
+
+    #[test]
+    fn json_path_subset_and_pointer_extract_values() {
+        let body = json!({"data": {"choices": [{"text": "a"}, {"text": "b"}], "k.x": 1}});
+        let get = |p: &str| extract_path(&body, p).unwrap().cloned();
+        assert_eq!(get("$.data.choices[1].text"), Some(json!("b")));
+        assert_eq!(get("$['data']['k.x']"), Some(json!(1)));
+        assert_eq!(get("/data/choices/0/text"), Some(json!("a")));
+        assert_eq!(get("$.data.missing"), None);
+        assert_eq!(get("$"), Some(body.clone()));
+        assert!(extract_path(&body, "$.data[").is_err());
+        assert!(extract_path(&body, "data").is_err());
+    }
+
+    #[tokio::test]
+    async fn renders_body_injects_credentials_and_extracts_output_and_usage() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header("Authorization", "Bearer secret-key"))
+            .and(body_json(json!({
+                "q": "say \"hi\"",
+                "system": "PROMPT",
+                "all": {"q": "say \"hi\""}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "result": {"items": [{"text": "OK"}]},
+                "meta": {"usage": {"prompt_tokens": 3, "completion_tokens": 4, "total_tokens": 7}}
+            })))
+            .mount(&server)
+            .await;
+
+        let target = HttpExecutionTarget::with_url_validation(PERMISSIVE).unwrap();
+        let config = http_config(format!("{}/hook", server.uri()), "$.result.items[0].text");
+        let r = target
+            .execute(&config, "PROMPT", &input(), "tc-1")
+            .await
+            .unwrap();
+        assert_eq!(r.output, "OK");
+        assert_eq!(r.token_usage.unwrap().total_tokens, 7);
+    }
+
+    #[tokio::test]
+    async fn non_string_output_is_serialized_as_json() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"result": {"label": "x"}})),
+            )
+            .mount(&server)
+            .await;
+
+        let target = HttpExecutionTarget::with_url_validation(PERMISSIVE).unwrap();
+        let config = http_config(format!("{}/hook", server.uri()), "/result");
+        let r = target
+            .execute(&config, "p", &input(), "tc-1")
+            .await
+            .unwrap();
+        assert_eq!(r.output, r#"{"label":"x"}"#);
+        assert!(r.token_usage.is_none());
+    }
+
+    #[tokio::test]
+    async fn maps_validation_credential_status_and_extraction_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/denied"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("TOPSECRET_DO_NOT_ECHO"))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"other": 1})))
+            .mount(&server)
+            .await;
+
+        let strict = HttpExecutionTarget::with_url_validation(BaseUrlValidationOptions {
+            allow_http: false,
+            allow_localhost: false,
+            allow_private_network: false,
+        })
+        .unwrap();
+        let err = strict
+            .execute(
+                &http_config(format!("{}/hook", server.uri()), "$.output"),
+                "p",
+                &input(),
+                "tc",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::InvalidRequest { .. }));
+
+        let target = HttpExecutionTarget::with_url_validation(PERMISSIVE).unwrap();
+        let mut missing_secret = http_config(format!("{}/hook", server.uri()), "$.output");
+        if let ExecutionTargetConfig::Http { credentials, .. } = &mut missing_secret {
+            credentials.clear();
+        }
+        let err = target
+            .execute(&missing_secret, "p", &input(), "tc")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::InvalidCredentials { .. }));
+
+        let err = target
+            .execute(
+                &http_config(format!("{}/denied", server.uri()), "$.output"),
+                "TOPSECRET_DO_NOT_ECHO",
+                &input(),
+                "tc",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::InvalidCredentials { .. }));
+        assert!(!err.to_string().contains("TOPSECRET_DO_NOT_ECHO"));
+
+        let err = target
+            .execute(
+                &http_config(format!("{}/hook", server.uri()), "$.output"),
+                "p",
+                &input(),
+                "tc",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::ParseError { .. }));
+
+        let err = target
+            .execute(
+                &http_config(format!("{}/hook", server.uri()), "$.output"),
+                "p",
+                &HashMap::new(),
+                "tc",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::InvalidRequest { .. }));
+    }
+}
@@ -1,26 +1,46 @@
+mod cassette_impl;
+mod command_impl;
 mod dify_impl;
 mod direct_api_impl;
 mod error;
 mod example_impl;
+mod http_impl;
 
 use std::sync::Arc;
 
+use crate::core::cassette::{EXECUTION_TARGET_CASSETTE_FILE, cassette_from_env};
 use crate::core::traits::ExecutionTarget;
 use crate::domain::models::ExecutionTargetType;
+use crate::infra::external::http_client::HttpClientError;
 
-pub use dify_impl::DifyExecutionTarget;
+pub use cassette_impl::{CassetteExecutionTarget, ExecutionCassetteEntry};
+pub use command_impl::{COMMAND_TARGET_ALLOWED_PROGRAMS_ENV, CommandExecutionTarget};
+pub use dify_impl::{
+    DIFY_CONVERSATION_ID_INPUT_KEY, DIFY_QUERY_INPUT_KEY, DifyExecutionSettings,
+    DifyExecutionTarget, parse_dify_execution_settings,
+};
 pub use direct_api_impl::DirectApiExecutionTarget;
 pub use error::ExecutionError;
 pub use example_impl::ExampleExecutionTarget;
+pub use http_impl::HttpExecutionTarget;
 
+/// ExecutionTarget 工厂：设置 `PROMPT_FASTER_CASSETTE_MODE`/`PROMPT_FASTER_CASSETTE_DIR` 时包装为录制/回放实现。
+///
+/// 出站网络配置无效（如代理 / CA 文件不可用）时返回错误，调用方不得回退为默认客户端。
 pub fn create_execution_target(
     execution_target_type: ExecutionTargetType,
-) -> Arc<dyn ExecutionTarget> {
-    match execution_target_type {
-        ExecutionTargetType::Dify => Arc::new(DifyExecutionTarget::new()),
-        ExecutionTargetType::Generic => Arc::new(DirectApiExecutionTarget::new()),
+) -> Result<Arc<dyn ExecutionTarget>, HttpClientError> {
+    let target: Arc<dyn ExecutionTarget> = match execution_target_type {
+        ExecutionTargetType::Dify => Arc::new(DifyExecutionTarget::new()?),
+        ExecutionTargetType::Generic => Arc::new(DirectApiExecutionTarget::new()?),
         ExecutionTargetType::Example => Arc::new(ExampleExecutionTarget::new()),
-    }
+        ExecutionTargetType::Command => Arc::new(CommandExecutionTarget::new()),
+        ExecutionTargetType::Http => Arc::new(HttpExecutionTarget::new()?),
+    };
+    Ok(match cassette_from_env(EXECUTION_TARGET_CASSETTE_FILE) {
+        Some(cassette) => Arc::new(CassetteExecutionTarget::new(target, cassette)),
+        None => target,
+    })
 }
 
 #[cfg(test)]
@@ -96,18 +116,19 @@ mod tests {
         let mut task_config = task_config;
         task_config.evaluator_config.evaluator_type = EvaluatorType::Example;
 
-        let target = create_execution_target(ExecutionTargetType::Example);
+        let target = create_execution_target(ExecutionTargetType::Example).unwrap();
         let engine = IterationEngine::new(target);
         let batch = ctx.test_cases.clone();
-        let exec_results = engine
+        let outcomes = engine
             .run_tests(&mut ctx, prompt, &batch, &task_config)
             .await
             .unwrap();
 
-        let pairs = IterationEngine::build_evaluation_pairs(&batch, &exec_results).unwrap();
-
         let evaluator = create_evaluator_for_task_config(&task_config, None);
-        let evals = evaluator.evaluate_batch(&ctx, &pairs).await.unwrap();
+        let (pairs, evals) =
+            IterationEngine::evaluate_outcomes(evaluator.as_ref(), &ctx, &batch, &outcomes)
+                .await
+                .unwrap();
 
         let stats = summarize_for_stats(SplitFilter::All, &pairs, &evals).unwrap();
         assert_eq!(stats.total_count, 2);
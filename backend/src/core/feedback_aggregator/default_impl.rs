@@ -5,7 +5,7 @@ use crate::domain::models::{
 };
 use crate::domain::types::{
     CandidateStats, EXT_BEST_CANDIDATE_STATS, EXT_CONSECUTIVE_NO_IMPROVEMENT,
-    EXT_CURRENT_PROMPT_STATS, EXT_EVALUATIONS_BY_TEST_CASE_ID, METRIC_EPS, OptimizationContext,
+    EXT_CURRENT_PROMPT_STATS, EXT_EVALUATIONS_BY_TEST_CASE_ID, OptimizationContext,
 };
 use async_trait::async_trait;
 use std::collections::HashMap;
@@ -460,7 +460,7 @@ fn choose_recommended_action(
         read_stats(ctx, EXT_CURRENT_PROMPT_STATS),
         read_stats(ctx, EXT_BEST_CANDIDATE_STATS),
     ) {
-        let best_is_better = is_better_stats(best, cur);
+        let best_is_better = best.is_better_than(&cur);
         extra.insert(
             "best_is_better".to_string(),
             serde_json::Value::Bool(best_is_better),
@@ -595,14 +595,6 @@ fn read_stats(ctx: &OptimizationContext, key: &str) -> Result<CandidateStats, Ag
         .map_err(|e| AggregatorError::InvalidReflections(format!("{key} 反序列化失败：{e}")))
 }
 
-fn is_better_stats(best: CandidateStats, current: CandidateStats) -> bool {
-    if best.pass_rate > current.pass_rate + METRIC_EPS {
-        return true;
-    }
-    (best.pass_rate - current.pass_rate).abs() <= METRIC_EPS
-        && best.mean_score > current.mean_score + METRIC_EPS
-}
-
 fn read_optional_u32(ctx: &OptimizationContext, key: &str) -> Option<u32> {
     let v = ctx.extensions.get(key)?;
     let n = v.as_u64()?;
@@ -0,0 +1,239 @@
+//! 并行执行的自适应并发控制（AIMD）
+//!
+//! - 起步：从较小的并发开始，未发生退避前每次健康完成 +1（慢启动）
+//! - 加性增：发生过退避后，每次健康完成 +1/limit（约每轮 +1）
+//! - 乘性减：`UpstreamError`（含上游 429 / 熔断）时并发减半；同一批在途请求只触发一次
+//! - 健康判定：延迟不超过观测到的最小延迟的 [`LATENCY_TOLERANCE`] 倍，否则保持不变
+//!
+//! 并发上限始终不超过任务级 `max_concurrency`（及执行目标自身声明的上限）。
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+use crate::core::execution_target::ExecutionError;
+use crate::domain::types::ExecutionConcurrencyStats;
+
+/// 慢启动初始并发
+pub const INITIAL_CONCURRENCY: u32 = 4;
+/// 退避系数
+const BACKOFF_FACTOR: f64 = 0.5;
+/// 延迟健康阈值（相对最小延迟的倍数）
+const LATENCY_TOLERANCE: f64 = 2.0;
+/// 延迟基线下限（避免极快响应导致阈值过紧）
+const LATENCY_FLOOR: Duration = Duration::from_millis(50);
+
+#[derive(Debug)]
+struct ControllerState {
+    limit: f64,
+    in_flight: u32,
+    peak_in_flight: u32,
+    slow_start: bool,
+    epoch: u64,
+    backoff_count: u64,
+    min_latency: Option<Duration>,
+}
+
+/// AIMD 并发控制器（同一任务的多个批次可复用同一状态）
+#[derive(Debug)]
+pub struct AdaptiveConcurrency {
+    max: u32,
+    state: Mutex<ControllerState>,
+    notify: Notify,
+}
+
+/// 一次执行占用的并发许可；调用 [`AdaptiveConcurrency::complete`] 归还
+#[derive(Debug)]
+pub struct ConcurrencyPermit {
+    epoch: u64,
+    started_at: Instant,
+}
+
+impl AdaptiveConcurrency {
+    /// 以慢启动方式创建（初始并发 `min(max, INITIAL_CONCURRENCY)`）
+    pub fn new(max: u32) -> Self {
+        let max = max.max(1);
+        Self::with_state(max, max.min(INITIAL_CONCURRENCY) as f64, true, 0)
+    }
+
+    /// 从上一批次的统计恢复（沿用已学习到的并发与累计退避次数）
+    pub fn resume(max: u32, previous: Option<&ExecutionConcurrencyStats>) -> Self {
+        let max = max.max(1);
+        match previous {
+            Some(prev) if prev.effective_concurrency > 0 => Self::with_state(
+                max,
+                prev.effective_concurrency.min(max) as f64,
+                prev.backoff_count == 0,
+                prev.backoff_count,
+            ),
+            _ => Self::new(max),
+        }
+    }
+
+    fn with_state(max: u32, limit: f64, slow_start: bool, backoff_count: u64) -> Self {
+        Self {
+            max,
+            state: Mutex::new(ControllerState {
+                limit: limit.clamp(1.0, max as f64),
+                in_flight: 0,
+                peak_in_flight: 0,
+                slow_start,
+                epoch: 0,
+                backoff_count,
+                min_latency: None,
+            }),
+            notify: Notify::new(),
+        }
+    }
+
+    /// 当前并发上限（取整）
+    pub fn limit(&self) -> u32 {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.limit.floor() as u32
+    }
+
+    /// 等待并获取一个并发许可
+    pub async fn acquire(&self) -> ConcurrencyPermit {
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            {
+                let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                if state.in_flight < state.limit.floor() as u32 {
+                    state.in_flight += 1;
+                    state.peak_in_flight = state.peak_in_flight.max(state.in_flight);
+                    return ConcurrencyPermit {
+                        epoch: state.epoch,
+                        started_at: Instant::now(),
+                    };
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// 归还许可并按执行结果调整并发
+    pub fn complete<T>(&self, permit: ConcurrencyPermit, result: &Result<T, ExecutionError>) {
+        let latency = permit.started_at.elapsed();
+        {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.in_flight = state.in_flight.saturating_sub(1);
+            match result {
+                Err(ExecutionError::UpstreamError { .. }) => {
+                    // 同一批在途请求的连带失败只退避一次
+                    if permit.epoch == state.epoch {
+                        state.limit = (state.limit * BACKOFF_FACTOR).max(1.0);
+                        state.slow_start = false;
+                        state.epoch += 1;
+                        state.backoff_count += 1;
+                    }
+                }
+                Ok(_) => {
+                    let baseline = state
+                        .min_latency
+                        .map_or(latency, |min| min.min(latency))
+                        .max(LATENCY_FLOOR);
+                    state.min_latency = Some(state.min_latency.map_or(latency, |m| m.min(latency)));
+                    if latency.as_secs_f64() <= baseline.as_secs_f64() * LATENCY_TOLERANCE {
+                        let step = if state.slow_start {
+                            1.0
+                        } else {
+                            1.0 / state.limit
+                        };
+                        state.limit = (state.limit + step).min(self.max as f64);
+                    }
+                }
+                // 其余错误（参数 / 鉴权 / 解析等）与上游负载无关，不调整
+                Err(_) => {}
+            }
+        }
+        self.notify.notify_waiters();
+    }
+
+    /// 当前统计快照
+    pub fn stats(&self) -> ExecutionConcurrencyStats {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        ExecutionConcurrencyStats {
+            max_concurrency: self.max,
+            effective_concurrency: state.limit.floor() as u32,
+            peak_in_flight: state.peak_in_flight,
+            backoff_count: state.backoff_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upstream_error() -> Result<(), ExecutionError> {
+        Err(ExecutionError::UpstreamError {
+            test_case_id: "t".to_string(),
+            message: "HTTP 429".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn slow_start_grows_until_max() {
+        let controller = AdaptiveConcurrency::new(8);
+        assert_eq!(controller.limit(), 4);
+        for _ in 0..10 {
+            let permit = controller.acquire().await;
+            controller.complete(permit, &Ok::<(), ExecutionError>(()));
+        }
+        assert_eq!(controller.limit(), 8);
+    }
+
+    #[tokio::test]
+    async fn upstream_error_halves_once_per_in_flight_generation() {
+        let controller = AdaptiveConcurrency::new(8);
+        let permits = [
+            controller.acquire().await,
+            controller.acquire().await,
+            controller.acquire().await,
+        ];
+        for permit in permits {
+            controller.complete(permit, &upstream_error());
+        }
+        let stats = controller.stats();
+        assert_eq!(stats.effective_concurrency, 2);
+        assert_eq!(stats.backoff_count, 1);
+        assert_eq!(stats.peak_in_flight, 3);
+
+        // 退避后为加性增：一次成功不足以 +1
+        let permit = controller.acquire().await;
+        controller.complete(permit, &Ok::<(), ExecutionError>(()));
+        assert_eq!(controller.limit(), 2);
+    }
+
+    #[tokio::test]
+    async fn non_upstream_errors_do_not_change_limit() {
+        let controller = AdaptiveConcurrency::new(8);
+        let permit = controller.acquire().await;
+        controller.complete(
+            permit,
+            &Err::<(), _>(ExecutionError::InvalidRequest {
+                test_case_id: "t".to_string(),
+                message: "bad".to_string(),
+            }),
+        );
+        assert_eq!(controller.limit(), 4);
+    }
+
+    #[test]
+    fn resume_reuses_learned_limit_within_max() {
+        let prev = ExecutionConcurrencyStats {
+            max_concurrency: 16,
+            effective_concurrency: 12,
+            peak_in_flight: 12,
+            backoff_count: 2,
+        };
+        let controller = AdaptiveConcurrency::resume(8, Some(&prev));
+        assert_eq!(controller.limit(), 8);
+        assert_eq!(controller.stats().backoff_count, 2);
+        assert_eq!(AdaptiveConcurrency::resume(8, None).limit(), 4);
+    }
+}
@@ -13,6 +13,7 @@ use tracing::{info, warn};
 
 use crate::core::iteration_engine::events::record_event_async;
 use crate::core::iteration_engine::pause_state::global_pause_registry;
+use crate::core::iteration_engine::shutdown::global_shutdown_coordinator;
 use crate::domain::models::{
     Actor, CheckpointCreateRequest, CheckpointEntity, EvaluationResult, EventType,
     FailureArchiveEntry, LineageType, PassRateSummary,
@@ -270,9 +271,16 @@ pub fn start_idle_autosave_task() {
         return;
     }
 
+    let shutdown = global_shutdown_coordinator().shutdown_token();
     tokio::spawn(async move {
         loop {
-            sleep(Duration::from_secs(IDLE_AUTOSAVE_TICK_SECONDS)).await;
+            tokio::select! {
+                _ = shutdown.cancelled() => {
+                    info!("停机：空闲自动保存任务已退出");
+                    break;
+                }
+                _ = sleep(Duration::from_secs(IDLE_AUTOSAVE_TICK_SECONDS)) => {}
+            }
             if let Err(err) = run_idle_autosave().await {
                 tracing::error!(
                     correlation_id = "idle-auto-save",
@@ -443,7 +451,7 @@ fn iteration_state_label(state: crate::domain::models::IterationState) -> String
         .unwrap_or_else(|| "unknown".to_string())
 }
 
-fn stable_json_string<T: Serialize>(value: &T) -> String {
+pub(crate) fn stable_json_string<T: Serialize>(value: &T) -> String {
     let value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
     let normalized = canonicalize_json(&value);
     serde_json::to_string(&normalized).unwrap_or_default()
@@ -0,0 +1,227 @@
+//! 执行结果缓存（Layer 1 run_tests 前置）
+//!
+//! - key：sha256(用户 + 凭证指纹 + 执行目标名 + 规范化 Prompt + 规范化输入 + 执行目标配置)
+//! - 执行目标配置的序列化不含 api_key / credentials（`#[serde(skip_serializing)]`），
+//!   因此单独计算凭证指纹参与 key：不同用户、不同密钥（不同应用/租户）之间互不命中
+//! - 不包含 test_case_id：相同输入的用例可共享结果，命中后由调用方覆盖 test_case_id
+
+use std::collections::HashMap;
+
+use sha2::{Digest, Sha256};
+
+use crate::core::iteration_engine::checkpoint::stable_json_string;
+use crate::domain::models::algorithm::normalize_prompt_for_fingerprint;
+use crate::domain::types::{
+    EXT_EXECUTION_CACHE_STATS, ExecutionCacheStats, ExecutionTargetConfig, OptimizationContext,
+};
+
+const CACHE_KEY_VERSION: &str = "v2";
+
+/// 凭证指纹的域分隔前缀（避免与其他用途的 sha256 摘要混用）
+const CREDENTIAL_FINGERPRINT_DOMAIN: &str = "execution-cache-credential";
+
+/// 计算运行时注入凭证的指纹（以用户 id 加盐；未注入凭证时为固定值）。
+///
+/// 指纹只参与缓存 key 的计算，不单独落库。
+fn credential_fingerprint(user_id: &str, config: &ExecutionTargetConfig) -> String {
+    let secrets: Vec<(&str, &str)> = match config {
+        ExecutionTargetConfig::Dify { api_key, .. }
+        | ExecutionTargetConfig::DirectModel { api_key, .. } => api_key
+            .as_deref()
+            .map(|key| vec![("api_key", key)])
+            .unwrap_or_default(),
+        ExecutionTargetConfig::Http { credentials, .. } => credentials
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect(),
+        ExecutionTargetConfig::Command { .. } => Vec::new(),
+    };
+    if secrets.is_empty() {
+        return "none".to_string();
+    }
+
+    let mut hasher = Sha256::new();
+    for part in [CREDENTIAL_FINGERPRINT_DOMAIN, user_id]
+        .into_iter()
+        .chain(secrets.iter().flat_map(|(name, value)| [*name, *value]))
+    {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// 计算执行结果缓存 key（确定性；输入 key 顺序与 Prompt 空白差异不影响结果）。
+///
+/// `user_id` 为任务所属用户：缓存按用户隔离，且同一用户使用不同凭证时也不会互相命中。
+pub fn execution_cache_key(
+    user_id: &str,
+    execution_target: &str,
+    execution_target_config: &ExecutionTargetConfig,
+    prompt: &str,
+    input: &HashMap<String, serde_json::Value>,
+) -> String {
+    let credential = credential_fingerprint(user_id, execution_target_config);
+    let mut hasher = Sha256::new();
+    for part in [
+        CACHE_KEY_VERSION,
+        user_id,
+        &credential,
+        execution_target,
+        &normalize_prompt_for_fingerprint(prompt),
+        &stable_json_string(input),
+        &stable_json_string(execution_target_config),
+    ] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    format!("{CACHE_KEY_VERSION}:sha256:{:x}", hasher.finalize())
+}
+
+/// 读取任务级累计的缓存统计（缺失/格式错误时视为 0）。
+pub fn read_execution_cache_stats(ctx: &OptimizationContext) -> ExecutionCacheStats {
+    ctx.extensions
+        .get(EXT_EXECUTION_CACHE_STATS)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// 将本次 run_tests 的统计累加到 ctx，返回累计值。
+pub fn accumulate_execution_cache_stats(
+    ctx: &mut OptimizationContext,
+    delta: ExecutionCacheStats,
+) -> ExecutionCacheStats {
+    let mut stats = read_execution_cache_stats(ctx);
+    stats.hits += delta.hits;
+    stats.misses += delta.misses;
+    stats.errors += delta.errors;
+    ctx.extensions.insert(
+        EXT_EXECUTION_CACHE_STATS.to_string(),
+        serde_json::to_value(stats).unwrap_or(serde_json::Value::Null),
+    );
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn direct_config(model_name: &str, api_key: Option<&str>) -> ExecutionTargetConfig {
+        ExecutionTargetConfig::DirectModel {
+            base_url: "https://api.example.com".to_string(),
+            model_name: model_name.to_string(),
+            user_prompt_template: "{input}".to_string(),
+            api_key: api_key.map(str::to_string),
+            provider: None,
+        }
+    }
+
+    fn input(pairs: &[(&str, serde_json::Value)]) -> HashMap<String, serde_json::Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn key_ignores_prompt_whitespace_and_input_order() {
+        let a = execution_cache_key(
+            "u1",
+            "direct_api",
+            &direct_config("m", Some("sk-aaa")),
+            "  hello   world\n",
+            &input(&[("a", json!({"x": 1, "y": 2})), ("b", json!("v"))]),
+        );
+        let b = execution_cache_key(
+            "u1",
+            "direct_api",
+            &direct_config("m", Some("sk-aaa")),
+            "hello world",
+            &input(&[("b", json!("v")), ("a", json!({"y": 2, "x": 1}))]),
+        );
+        assert_eq!(a, b);
+        assert!(a.starts_with("v2:sha256:"));
+    }
+
+    #[test]
+    fn key_changes_with_prompt_input_config_or_target() {
+        let base_input = input(&[("q", json!("1"))]);
+        let base = execution_cache_key(
+            "u1",
+            "direct_api",
+            &direct_config("m", None),
+            "p",
+            &base_input,
+        );
+
+        let variants = [
+            execution_cache_key(
+                "u1",
+                "direct_api",
+                &direct_config("m", None),
+                "p2",
+                &base_input,
+            ),
+            execution_cache_key(
+                "u1",
+                "direct_api",
+                &direct_config("m", None),
+                "p",
+                &input(&[("q", json!("2"))]),
+            ),
+            execution_cache_key(
+                "u1",
+                "direct_api",
+                &direct_config("m2", None),
+                "p",
+                &base_input,
+            ),
+            execution_cache_key("u1", "example", &direct_config("m", None), "p", &base_input),
+        ];
+        for v in variants {
+            assert_ne!(base, v);
+        }
+    }
+
+    #[test]
+    fn key_is_scoped_by_user_and_credential() {
+        let q = input(&[("q", json!("1"))]);
+        let base = execution_cache_key("u1", "dify", &direct_config("m", Some("sk-a")), "p", &q);
+
+        assert_ne!(
+            base,
+            execution_cache_key("u2", "dify", &direct_config("m", Some("sk-a")), "p", &q)
+        );
+        assert_ne!(
+            base,
+            execution_cache_key("u1", "dify", &direct_config("m", Some("sk-b")), "p", &q)
+        );
+        assert_ne!(
+            base,
+            execution_cache_key("u1", "dify", &direct_config("m", None), "p", &q)
+        );
+    }
+
+    #[test]
+    fn credential_fingerprint_covers_http_credentials() {
+        let http = |token: &str| ExecutionTargetConfig::Http {
+            method: "POST".to_string(),
+            url: "https://hook.example.com".to_string(),
+            headers: Default::default(),
+            body_template: "{}".to_string(),
+            output_path: "$.out".to_string(),
+            usage_path: None,
+            timeout_ms: 1000,
+            credentials: [("bearer".to_string(), token.to_string())].into(),
+        };
+        assert_ne!(
+            credential_fingerprint("u1", &http("t1")),
+            credential_fingerprint("u1", &http("t2"))
+        );
+        assert_ne!(
+            credential_fingerprint("u1", &http("t1")),
+            credential_fingerprint("u2", &http("t1"))
+        );
+    }
+}
@@ -1,13 +1,225 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
-use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
 
 use crate::core::execution_target::ExecutionError;
+use crate::core::iteration_engine::adaptive_concurrency::AdaptiveConcurrency;
 use crate::core::traits::ExecutionTarget;
 use crate::domain::models::{ExecutionResult, TestCase};
 use crate::domain::types::ExecutionTargetConfig;
+use crate::infra::external::retry::{RetryPolicy, with_retry};
+
+/// 单用例重试的基础退避
+const CASE_RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+/// 单用例重试的最大退避
+const CASE_RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// 单条用例的执行结果（与输入 batch 一一对应）
+#[derive(Debug, Clone)]
+pub enum CaseOutcome {
+    Success(ExecutionResult),
+    /// 瞬时错误（网络 / 超时 / 上游），重试用尽后仍失败
+    RetryableFailure(ExecutionError),
+    /// 非瞬时错误（参数 / 鉴权 / 解析等），不重试
+    PermanentFailure(ExecutionError),
+    /// 任务被暂停 / 终止：用例未执行，或在途请求已被丢弃
+    Cancelled(ExecutionError),
+}
+
+impl CaseOutcome {
+    pub fn from_result(result: Result<ExecutionResult, ExecutionError>) -> Self {
+        match result {
+            Ok(r) => CaseOutcome::Success(r),
+            Err(err) if err.is_retryable() => CaseOutcome::RetryableFailure(err),
+            Err(err) => CaseOutcome::PermanentFailure(err),
+        }
+    }
+
+    pub fn success(&self) -> Option<&ExecutionResult> {
+        match self {
+            CaseOutcome::Success(r) => Some(r),
+            _ => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&ExecutionError> {
+        match self {
+            CaseOutcome::Success(_) => None,
+            CaseOutcome::RetryableFailure(err)
+            | CaseOutcome::PermanentFailure(err)
+            | CaseOutcome::Cancelled(err) => Some(err),
+        }
+    }
+
+    pub fn cancelled(test_case_id: &str) -> Self {
+        CaseOutcome::Cancelled(ExecutionError::Cancelled {
+            test_case_id: test_case_id.to_string(),
+            message: "任务已暂停或终止，用例未执行".to_string(),
+        })
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, CaseOutcome::Cancelled(_))
+    }
+
+    pub fn is_failure(&self) -> bool {
+        self.error().is_some()
+    }
+}
+
+fn case_retry_policy(max_retries: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_retries,
+        base_delay: CASE_RETRY_BASE_DELAY,
+        use_exponential_backoff: true,
+        max_delay: CASE_RETRY_MAX_DELAY,
+    }
+}
+
+/// 执行单条用例：瞬时错误按 `max_retries` 重试；每次尝试都单独占用并发许可。
+///
+/// `cancel` 被取消时立即丢弃在途请求（含等待并发许可 / 重试退避），用例记为未执行。
+pub async fn execute_case(
+    execution_target: &dyn ExecutionTarget,
+    execution_target_config: &ExecutionTargetConfig,
+    prompt: &str,
+    test_case: &TestCase,
+    max_retries: u32,
+    controller: Option<&AdaptiveConcurrency>,
+    cancel: &CancellationToken,
+) -> CaseOutcome {
+    if cancel.is_cancelled() {
+        return CaseOutcome::cancelled(&test_case.id);
+    }
+    let policy = case_retry_policy(max_retries);
+    let attempts = with_retry(
+        &policy,
+        &test_case.id,
+        "execute_test_case",
+        || async move {
+            let permit = match controller {
+                Some(controller) => Some(controller.acquire().await),
+                None => None,
+            };
+            let result = execution_target
+                .execute(
+                    execution_target_config,
+                    prompt,
+                    &test_case.input,
+                    &test_case.id,
+                )
+                .await;
+            if let (Some(controller), Some(permit)) = (controller, permit) {
+                controller.complete(permit, &result);
+            }
+            result
+        },
+        ExecutionError::is_retryable,
+        ExecutionError::kind,
+    );
+    tokio::select! {
+        biased;
+        _ = cancel.cancelled() => CaseOutcome::cancelled(&test_case.id),
+        result = attempts => CaseOutcome::from_result(result),
+    }
+}
+
+/// 串行执行（容错）：单条用例失败不影响其余用例。
+pub async fn serial_execute_outcomes(
+    execution_target: &dyn ExecutionTarget,
+    execution_target_config: &ExecutionTargetConfig,
+    prompt: &str,
+    batch: &[TestCase],
+    max_retries: u32,
+    cancel: &CancellationToken,
+) -> Vec<CaseOutcome> {
+    let mut outcomes = Vec::with_capacity(batch.len());
+    for test_case in batch {
+        outcomes.push(
+            execute_case(
+                execution_target,
+                execution_target_config,
+                prompt,
+                test_case,
+                max_retries,
+                None,
+                cancel,
+            )
+            .await,
+        );
+    }
+    outcomes
+}
+
+/// 并行执行（容错）：并发由 [`AdaptiveConcurrency`] 控制，单条用例失败不中止整批。
+pub async fn parallel_execute_outcomes(
+    execution_target: Arc<dyn ExecutionTarget>,
+    execution_target_config: &ExecutionTargetConfig,
+    prompt: &str,
+    batch: &[TestCase],
+    max_retries: u32,
+    controller: Arc<AdaptiveConcurrency>,
+    cancel: &CancellationToken,
+) -> Result<Vec<CaseOutcome>, ExecutionError> {
+    let prompt = Arc::new(prompt.to_string());
+    let execution_target_config = Arc::new(execution_target_config.clone());
+
+    let mut join_set = JoinSet::new();
+    for (index, test_case) in batch.iter().enumerate() {
+        let execution_target = Arc::clone(&execution_target);
+        let controller = Arc::clone(&controller);
+        let prompt = Arc::clone(&prompt);
+        let execution_target_config = Arc::clone(&execution_target_config);
+        let test_case = test_case.clone();
+        let cancel = cancel.clone();
+
+        join_set.spawn(async move {
+            let outcome = execute_case(
+                execution_target.as_ref(),
+                &execution_target_config,
+                &prompt,
+                &test_case,
+                max_retries,
+                Some(&controller),
+                &cancel,
+            )
+            .await;
+            (index, outcome)
+        });
+    }
+
+    let mut out: Vec<Option<CaseOutcome>> = vec![None; batch.len()];
+    loop {
+        let joined = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => {
+                // 立即丢弃所有在途请求；未返回的用例记为未执行
+                join_set.abort_all();
+                break;
+            }
+            joined = join_set.join_next() => joined,
+        };
+        let Some(joined) = joined else {
+            break;
+        };
+        let (index, outcome) = joined.map_err(|e| ExecutionError::Internal {
+            test_case_id: "unknown".to_string(),
+            message: format!("join error: {e}"),
+        })?;
+        out[index] = Some(outcome);
+    }
+
+    Ok(out
+        .into_iter()
+        .zip(batch.iter())
+        .map(|(outcome, test_case)| {
+            outcome.unwrap_or_else(|| CaseOutcome::cancelled(&test_case.id))
+        })
+        .collect())
+}
 
 pub async fn serial_execute(
     execution_target: &dyn ExecutionTarget,
@@ -31,54 +243,74 @@ pub async fn serial_execute(
     Ok(results)
 }
 
-pub async fn parallel_execute(
-    execution_target: Arc<dyn ExecutionTarget>,
+/// 按任务级 `max_concurrency` 与执行目标自身声明的上限计算并行执行的并发上限。
+pub fn resolve_max_concurrency(
     execution_target_config: &ExecutionTargetConfig,
-    prompt: &str,
-    batch: &[TestCase],
     max_concurrency: u32,
-) -> Result<Vec<ExecutionResult>, ExecutionError> {
+) -> Result<u32, ExecutionError> {
     if max_concurrency < 1 {
         return Err(ExecutionError::InvalidRequest {
             test_case_id: "unknown".to_string(),
             message: "max_concurrency must be >= 1".to_string(),
         });
     }
-    let max_concurrency = max_concurrency as usize;
+    // 执行目标自身声明的并发上限（如本地命令）优先于任务级配置。
+    Ok(match execution_target_config.max_concurrency_cap() {
+        Some(cap) => max_concurrency.min(cap.max(1)),
+        None => max_concurrency,
+    })
+}
+
+/// 并行执行（每次调用使用新的自适应并发控制器，从慢启动开始）。
+pub async fn parallel_execute(
+    execution_target: Arc<dyn ExecutionTarget>,
+    execution_target_config: &ExecutionTargetConfig,
+    prompt: &str,
+    batch: &[TestCase],
+    max_concurrency: u32,
+) -> Result<Vec<ExecutionResult>, ExecutionError> {
+    let max_concurrency = resolve_max_concurrency(execution_target_config, max_concurrency)?;
+    let controller = Arc::new(AdaptiveConcurrency::new(max_concurrency));
+    parallel_execute_adaptive(
+        execution_target,
+        execution_target_config,
+        prompt,
+        batch,
+        controller,
+    )
+    .await
+}
+
+/// 并行执行：并发由 [`AdaptiveConcurrency`] 按上游健康度动态调整（AIMD）。
+pub async fn parallel_execute_adaptive(
+    execution_target: Arc<dyn ExecutionTarget>,
+    execution_target_config: &ExecutionTargetConfig,
+    prompt: &str,
+    batch: &[TestCase],
+    controller: Arc<AdaptiveConcurrency>,
+) -> Result<Vec<ExecutionResult>, ExecutionError> {
     if batch.is_empty() {
         return Ok(vec![]);
     }
 
-    let semaphore = Arc::new(Semaphore::new(max_concurrency));
     let prompt = Arc::new(prompt.to_string());
     let execution_target_config = Arc::new(execution_target_config.clone());
 
     let mut join_set = JoinSet::new();
     for (index, test_case) in batch.iter().enumerate() {
         let execution_target = Arc::clone(&execution_target);
-        let semaphore = Arc::clone(&semaphore);
+        let controller = Arc::clone(&controller);
         let prompt = Arc::clone(&prompt);
         let execution_target_config = Arc::clone(&execution_target_config);
         let input: HashMap<String, serde_json::Value> = test_case.input.clone();
         let test_case_id = test_case.id.clone();
 
         join_set.spawn(async move {
-            let _permit = match semaphore.acquire_owned().await {
-                Ok(permit) => permit,
-                Err(_) => {
-                    return (
-                        index,
-                        Err(ExecutionError::Internal {
-                            test_case_id: test_case_id.clone(),
-                            message: "semaphore closed".to_string(),
-                        }),
-                    );
-                }
-            };
-
+            let permit = controller.acquire().await;
             let result = execution_target
                 .execute(&execution_target_config, &prompt, &input, &test_case_id)
                 .await;
+            controller.complete(permit, &result);
             (index, result)
         });
     }
@@ -320,6 +552,28 @@ mod tests {
         assert!(max_seen >= 2, "max_seen={max_seen}"); // sanity: should overlap
     }
 
+    #[tokio::test]
+    async fn parallel_execute_honors_execution_target_concurrency_cap() {
+        let target = Arc::new(MockExecutionTarget::new(5));
+        let batch = (0..6)
+            .map(|i| test_case(&i.to_string()))
+            .collect::<Vec<_>>();
+        let config = ExecutionTargetConfig::Command {
+            program: "/bin/true".to_string(),
+            args: vec![],
+            timeout_ms: 1_000,
+            env_allowlist: vec![],
+            max_concurrency: Some(1),
+        };
+
+        let execution_target: Arc<dyn ExecutionTarget> = target.clone();
+        let _ = parallel_execute(execution_target, &config, "p", &batch, 8)
+            .await
+            .unwrap();
+
+        assert_eq!(target.max_in_flight.load(Ordering::SeqCst), 1);
+    }
+
     #[tokio::test]
     async fn parallel_execute_allows_max_concurrency_greater_than_batch_size() {
         let target = Arc::new(MockExecutionTarget::new(5));
@@ -343,6 +597,102 @@ mod tests {
         assert!(max_seen >= 2, "max_seen={max_seen}"); // sanity: should overlap
     }
 
+    #[tokio::test]
+    async fn serial_execute_outcomes_isolates_failed_case() {
+        let target = MockExecutionTarget::new(1).with_failure("b");
+        let batch = vec![test_case("a"), test_case("b"), test_case("c")];
+
+        let outcomes = serial_execute_outcomes(
+            &target,
+            &ExecutionTargetConfig::default(),
+            "p",
+            &batch,
+            1,
+            &CancellationToken::new(),
+        )
+        .await;
+        assert!(outcomes[0].success().is_some());
+        assert!(matches!(
+            &outcomes[1],
+            CaseOutcome::RetryableFailure(ExecutionError::UpstreamError { test_case_id, .. })
+                if test_case_id == "b"
+        ));
+        assert!(outcomes[2].success().is_some());
+    }
+
+    #[tokio::test]
+    async fn parallel_execute_outcomes_continues_after_failure() {
+        let target = Arc::new(MockExecutionTarget::new(5).with_failure("b"));
+        let batch = vec![test_case("a"), test_case("b"), test_case("c")];
+
+        let outcomes = parallel_execute_outcomes(
+            target,
+            &ExecutionTargetConfig::default(),
+            "p",
+            &batch,
+            0,
+            Arc::new(AdaptiveConcurrency::new(2)),
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+        let ids = outcomes
+            .iter()
+            .map(|o| o.success().map(|r| r.test_case_id.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![Some("a"), None, Some("c")]);
+    }
+
+    #[tokio::test]
+    async fn parallel_execute_outcomes_drops_in_flight_calls_on_cancel() {
+        let target = Arc::new(MockExecutionTarget::new(10_000));
+        let batch = (0..8)
+            .map(|i| test_case(&i.to_string()))
+            .collect::<Vec<_>>();
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            trigger.cancel();
+        });
+
+        let start = Instant::now();
+        let outcomes = parallel_execute_outcomes(
+            target,
+            &ExecutionTargetConfig::default(),
+            "p",
+            &batch,
+            0,
+            Arc::new(AdaptiveConcurrency::new(4)),
+            &cancel,
+        )
+        .await
+        .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(outcomes.len(), 8);
+        assert!(outcomes.iter().all(CaseOutcome::is_cancelled));
+    }
+
+    #[tokio::test]
+    async fn serial_execute_outcomes_skips_cases_after_cancel() {
+        let target = MockExecutionTarget::new(1);
+        let batch = vec![test_case("a"), test_case("b")];
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let outcomes = serial_execute_outcomes(
+            &target,
+            &ExecutionTargetConfig::default(),
+            "p",
+            &batch,
+            0,
+            &cancel,
+        )
+        .await;
+        assert!(outcomes.iter().all(CaseOutcome::is_cancelled));
+        assert_eq!(target.max_in_flight.load(Ordering::SeqCst), 0);
+    }
+
     #[tokio::test]
     async fn parallel_execute_is_all_or_nothing_on_error() {
         let target = Arc::new(MockExecutionTarget::new(5).with_failure("b"));
@@ -1,6 +1,9 @@
+pub mod adaptive_concurrency;
 pub mod checkpoint;
 pub mod events;
+pub mod execution_cache;
 pub mod executor;
 pub mod orchestrator;
 pub mod pause_state;
 pub mod recovery;
+pub mod shutdown;
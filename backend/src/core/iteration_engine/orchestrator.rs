@@ -4,8 +4,15 @@ use std::sync::Arc;
 use crate::core::evaluator::EvaluatorError;
 use crate::core::execution_target::ExecutionError;
 use crate::core::feedback_aggregator::AggregatorError;
+use crate::core::iteration_engine::adaptive_concurrency::AdaptiveConcurrency;
 use crate::core::iteration_engine::events::record_event_async;
-use crate::core::iteration_engine::executor::{parallel_execute, serial_execute};
+use crate::core::iteration_engine::execution_cache::{
+    accumulate_execution_cache_stats, execution_cache_key,
+};
+use crate::core::iteration_engine::executor::{
+    CaseOutcome, parallel_execute_outcomes, resolve_max_concurrency, serial_execute_outcomes,
+};
+use crate::core::iteration_engine::pause_state::task_cancellation_token;
 use crate::core::prompt_generator::{EXT_CANDIDATE_INDEX, GeneratorError, TEMPLATE_VARIANT_COUNT};
 use crate::core::traits::Evaluator;
 use crate::core::traits::ExecutionTarget;
@@ -13,17 +20,25 @@ use crate::core::traits::FeedbackAggregator;
 use crate::core::traits::PromptGenerator;
 use crate::domain::models::{
     Actor, EvaluationResult, EventType, ExecutionMode, ExecutionResult, FailureArchiveEntry,
-    FailureType, IterationState, OptimizationTaskConfig, RecommendedAction, ReflectionResult,
-    Suggestion, SuggestionType, TestCase, UnifiedReflection,
+    FailurePoint, FailureType, IterationState, OptimizationTaskConfig, RecommendedAction,
+    ReflectionResult, Severity, Suggestion, SuggestionType, TestCase, UnifiedReflection,
 };
 use crate::domain::types::{
     CandidateStats, EXT_BEST_CANDIDATE_STATS, EXT_CONSECUTIVE_NO_IMPROVEMENT,
-    EXT_CURRENT_PROMPT_STATS, EXT_EVALUATIONS_BY_TEST_CASE_ID, EXT_FAILURE_ARCHIVE,
-    FAILURE_ARCHIVE_MAX_ENTRIES, METRIC_EPS, OptimizationContext,
+    EXT_CONSECUTIVE_NO_IMPROVEMENT_ITERATION, EXT_CURRENT_PROMPT_STATS,
+    EXT_EVALUATIONS_BY_TEST_CASE_ID, EXT_EXECUTION_CONCURRENCY, EXT_FAILURE_ARCHIVE,
+    EXT_TASK_OWNER_ID, ExecutionCacheStats, ExecutionConcurrencyStats, FAILURE_ARCHIVE_MAX_ENTRIES,
+    OptimizationContext,
 };
+use crate::infra::db::pool::global_db_pool;
+use crate::infra::db::repositories::ExecutionCacheRepo;
 use serde_json::json;
+use sqlx::SqlitePool;
 use thiserror::Error;
 
+/// 执行失败用例的失败维度
+pub const EXECUTION_FAILURE_DIMENSION: &str = "execution";
+
 #[derive(Clone)]
 pub struct IterationEngine {
     execution_target: Arc<dyn ExecutionTarget>,
@@ -34,39 +49,36 @@ impl IterationEngine {
         Self { execution_target }
     }
 
+    /// 执行一批用例，返回与 `batch` 顺序一致的逐用例结果。
+    ///
+    /// 单条用例失败不中止整批；仅当失败占比超过 `execution_retry.max_failure_percent` 时
+    /// 返回首个失败用例的错误（本轮迭代中止）。
     pub async fn run_tests(
         &self,
         ctx: &mut OptimizationContext,
         prompt: &str,
         batch: &[TestCase],
         task_config: &OptimizationTaskConfig,
-    ) -> Result<Vec<ExecutionResult>, ExecutionError> {
+    ) -> Result<Vec<CaseOutcome>, ExecutionError> {
         ctx.state = IterationState::RunningTests;
 
-        let results = match task_config.execution_mode {
-            ExecutionMode::Serial => {
-                serial_execute(
-                    self.execution_target.as_ref(),
-                    &ctx.execution_target_config,
-                    prompt,
-                    batch,
-                )
-                .await?
+        let outcomes = match (task_config.execution_cache.enabled, global_db_pool()) {
+            (true, Some(pool)) => {
+                self.run_tests_with_cache(&pool, ctx, prompt, batch, task_config)
+                    .await?
             }
-            ExecutionMode::Parallel => {
-                parallel_execute(
-                    Arc::clone(&self.execution_target),
-                    &ctx.execution_target_config,
-                    prompt,
-                    batch,
-                    task_config.max_concurrency,
-                )
-                .await?
+            (true, None) => {
+                tracing::debug!(task_id = %ctx.task_id, "数据库未初始化，跳过执行结果缓存");
+                self.execute_batch(ctx, prompt, batch, task_config).await?
             }
+            (false, _) => self.execute_batch(ctx, prompt, batch, task_config).await?,
         };
 
         // Hard contract: results must align with input batch order AND be self-identifying.
-        for (idx, (tc, r)) in batch.iter().zip(results.iter()).enumerate() {
+        for (idx, (tc, outcome)) in batch.iter().zip(outcomes.iter()).enumerate() {
+            let Some(r) = outcome.success() else {
+                continue;
+            };
             if tc.id != r.test_case_id {
                 return Err(ExecutionError::Internal {
                     test_case_id: r.test_case_id.clone(),
@@ -78,7 +90,220 @@ impl IterationEngine {
             }
         }
 
-        Ok(results)
+        // 暂停 / 终止：本批结果不完整，交由引擎在安全点处理（不计入失败占比）
+        let not_run = outcomes.iter().filter(|o| o.is_cancelled()).count();
+        if let Some(err) = outcomes
+            .iter()
+            .find(|o| o.is_cancelled())
+            .and_then(CaseOutcome::error)
+        {
+            tracing::info!(
+                task_id = %ctx.task_id,
+                iteration = ctx.iteration,
+                not_run,
+                total = batch.len(),
+                "任务已暂停或终止，已丢弃在途请求"
+            );
+            return Err(err.clone());
+        }
+
+        let failed = outcomes.iter().filter(|o| o.is_failure()).count();
+        if failed > 0 {
+            let retry_config = &task_config.execution_retry;
+            let abort = retry_config.exceeds_failure_threshold(failed, batch.len());
+            tracing::warn!(
+                task_id = %ctx.task_id,
+                iteration = ctx.iteration,
+                failed,
+                total = batch.len(),
+                max_failure_percent = retry_config.max_failure_percent,
+                abort,
+                "部分用例执行失败"
+            );
+            if abort && let Some(err) = outcomes.iter().find_map(CaseOutcome::error) {
+                return Err(err.clone());
+            }
+        }
+
+        Ok(outcomes)
+    }
+
+    async fn execute_batch(
+        &self,
+        ctx: &mut OptimizationContext,
+        prompt: &str,
+        batch: &[TestCase],
+        task_config: &OptimizationTaskConfig,
+    ) -> Result<Vec<CaseOutcome>, ExecutionError> {
+        let max_retries = task_config.execution_retry.max_retries;
+        let cancel = task_cancellation_token(&ctx.task_id).await;
+        match task_config.execution_mode {
+            ExecutionMode::Serial => Ok(serial_execute_outcomes(
+                self.execution_target.as_ref(),
+                &ctx.execution_target_config,
+                prompt,
+                batch,
+                max_retries,
+                &cancel,
+            )
+            .await),
+            ExecutionMode::Parallel => {
+                let max_concurrency = resolve_max_concurrency(
+                    &ctx.execution_target_config,
+                    task_config.max_concurrency,
+                )?;
+                // 同一任务的后续批次沿用已学习到的并发（而非每批从慢启动开始）
+                let controller = Arc::new(AdaptiveConcurrency::resume(
+                    max_concurrency,
+                    read_execution_concurrency_stats(ctx).as_ref(),
+                ));
+                let result = parallel_execute_outcomes(
+                    Arc::clone(&self.execution_target),
+                    &ctx.execution_target_config,
+                    prompt,
+                    batch,
+                    max_retries,
+                    Arc::clone(&controller),
+                    &cancel,
+                )
+                .await;
+
+                let stats = controller.stats();
+                ctx.extensions.insert(
+                    EXT_EXECUTION_CONCURRENCY.to_string(),
+                    serde_json::to_value(stats).unwrap_or(serde_json::Value::Null),
+                );
+                tracing::info!(
+                    task_id = %ctx.task_id,
+                    iteration = ctx.iteration,
+                    max_concurrency = stats.max_concurrency,
+                    effective_concurrency = stats.effective_concurrency,
+                    peak_in_flight = stats.peak_in_flight,
+                    backoff_count = stats.backoff_count,
+                    "自适应并发统计"
+                );
+                result
+            }
+        }
+    }
+
+    /// 带执行结果缓存的 run_tests：命中直接复用，仅对未命中用例调用执行目标。
+    ///
+    /// 缓存读写失败只计入 `errors` 并降级为直接执行，不影响本轮结果。
+    pub(crate) async fn run_tests_with_cache(
+        &self,
+        pool: &SqlitePool,
+        ctx: &mut OptimizationContext,
+        prompt: &str,
+        batch: &[TestCase],
+        task_config: &OptimizationTaskConfig,
+    ) -> Result<Vec<CaseOutcome>, ExecutionError> {
+        // 缓存按任务所属用户隔离；未注入所属用户时不使用缓存，避免跨用户共享分区
+        let Some(user_id) = ctx
+            .extensions
+            .get(EXT_TASK_OWNER_ID)
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+        else {
+            tracing::warn!(task_id = %ctx.task_id, "任务所属用户缺失，跳过执行结果缓存");
+            return self.execute_batch(ctx, prompt, batch, task_config).await;
+        };
+        let target_name = self.execution_target.name().to_string();
+        let keys = batch
+            .iter()
+            .map(|tc| {
+                execution_cache_key(
+                    &user_id,
+                    &target_name,
+                    &ctx.execution_target_config,
+                    prompt,
+                    &tc.input,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut delta = ExecutionCacheStats::default();
+        let mut slots: Vec<Option<CaseOutcome>> = Vec::with_capacity(batch.len());
+        for (tc, key) in batch.iter().zip(keys.iter()) {
+            match ExecutionCacheRepo::get(pool, &user_id, key).await {
+                Ok(Some(mut cached)) => {
+                    cached.test_case_id = tc.id.clone();
+                    delta.hits += 1;
+                    slots.push(Some(CaseOutcome::Success(cached)));
+                }
+                Ok(None) => {
+                    delta.misses += 1;
+                    slots.push(None);
+                }
+                Err(err) => {
+                    tracing::warn!(task_id = %ctx.task_id, error = %err, "读取执行结果缓存失败");
+                    delta.errors += 1;
+                    delta.misses += 1;
+                    slots.push(None);
+                }
+            }
+        }
+
+        let miss_indices = slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.is_none())
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        if !miss_indices.is_empty() {
+            let miss_batch = miss_indices
+                .iter()
+                .map(|&idx| batch[idx].clone())
+                .collect::<Vec<_>>();
+            let executed = self
+                .execute_batch(ctx, prompt, &miss_batch, task_config)
+                .await?;
+            for (idx, outcome) in miss_indices.into_iter().zip(executed) {
+                // 仅缓存成功且自洽的结果（不自洽由 run_tests 的契约校验负责报错）。
+                if let Some(result) = outcome.success()
+                    && result.test_case_id == batch[idx].id
+                {
+                    if let Err(err) = ExecutionCacheRepo::put(
+                        pool,
+                        &user_id,
+                        &keys[idx],
+                        &target_name,
+                        result,
+                        task_config.execution_cache.ttl_seconds,
+                    )
+                    .await
+                    {
+                        tracing::warn!(task_id = %ctx.task_id, error = %err, "写入执行结果缓存失败");
+                        delta.errors += 1;
+                    }
+                }
+                slots[idx] = Some(outcome);
+            }
+        }
+
+        let total = accumulate_execution_cache_stats(ctx, delta);
+        tracing::info!(
+            task_id = %ctx.task_id,
+            iteration = ctx.iteration,
+            hits = delta.hits,
+            misses = delta.misses,
+            errors = delta.errors,
+            total_hits = total.hits,
+            total_misses = total.misses,
+            hit_rate = total.hit_rate(),
+            "执行结果缓存统计"
+        );
+
+        slots
+            .into_iter()
+            .map(|slot| {
+                slot.ok_or_else(|| ExecutionError::Internal {
+                    test_case_id: "unknown".to_string(),
+                    message: "missing execution result".to_string(),
+                })
+            })
+            .collect()
     }
 
     /// Build `Evaluator.evaluate_batch(ctx, results)` input pairs in stable order.
@@ -117,6 +342,81 @@ impl IterationEngine {
         }
         Ok(out)
     }
+
+    /// 评估 `run_tests` 的逐用例结果，返回与 `batch` 顺序一致的 `(pairs, evaluations)`。
+    ///
+    /// - 成功用例交给 Evaluator 批量评估
+    /// - 执行失败的用例不调用 Evaluator，直接记为未通过（输出为空，失败维度 `execution`）
+    pub async fn evaluate_outcomes<E: Evaluator + ?Sized>(
+        evaluator: &E,
+        ctx: &OptimizationContext,
+        batch: &[TestCase],
+        outcomes: &[CaseOutcome],
+    ) -> Result<(Vec<(TestCase, String)>, Vec<EvaluationResult>), EvaluatorError> {
+        if batch.len() != outcomes.len() {
+            return Err(EvaluatorError::InvalidInput(format!(
+                "batch/outcomes length mismatch: {} vs {}",
+                batch.len(),
+                outcomes.len()
+            )));
+        }
+
+        let pairs = batch
+            .iter()
+            .zip(outcomes.iter())
+            .map(|(tc, outcome)| {
+                let output = outcome
+                    .success()
+                    .map(|r| r.output.clone())
+                    .unwrap_or_default();
+                (tc.clone(), output)
+            })
+            .collect::<Vec<_>>();
+        let succeeded = pairs
+            .iter()
+            .zip(outcomes.iter())
+            .filter(|(_, outcome)| !outcome.is_failure())
+            .map(|(pair, _)| pair.clone())
+            .collect::<Vec<_>>();
+        let mut succeeded_evals = if succeeded.is_empty() {
+            Vec::new()
+        } else {
+            evaluator.evaluate_batch(ctx, &succeeded).await?
+        }
+        .into_iter();
+
+        let mut evaluations = Vec::with_capacity(batch.len());
+        for outcome in outcomes {
+            match outcome.error() {
+                Some(err) => evaluations.push(execution_failure_evaluation(err)),
+                None => evaluations.push(succeeded_evals.next().ok_or_else(|| {
+                    EvaluatorError::Internal("evaluate_batch returned too few results".to_string())
+                })?),
+            }
+        }
+        Ok((pairs, evaluations))
+    }
+}
+
+/// 执行失败用例的评估结果：未通过、0 分，失败维度为 `execution`。
+pub fn execution_failure_evaluation(err: &ExecutionError) -> EvaluationResult {
+    EvaluationResult {
+        passed: false,
+        score: 0.0,
+        dimensions: HashMap::new(),
+        failure_points: vec![FailurePoint {
+            dimension: EXECUTION_FAILURE_DIMENSION.to_string(),
+            description: err.to_string(),
+            severity: Severity::Critical,
+            expected: None,
+            actual: None,
+        }],
+        evaluator_type: EXECUTION_FAILURE_DIMENSION.to_string(),
+        confidence: None,
+        reasoning: None,
+        diversity_analysis: None,
+        extra: HashMap::from([("execution_error_kind".to_string(), json!(err.kind()))]),
+    }
 }
 
 pub fn record_iteration_started(ctx: &OptimizationContext) {
@@ -130,15 +430,28 @@ pub fn record_iteration_started(ctx: &OptimizationContext) {
     );
 }
 
+/// 读取自适应并发统计（并行执行过才存在）
+pub fn read_execution_concurrency_stats(
+    ctx: &OptimizationContext,
+) -> Option<ExecutionConcurrencyStats> {
+    ctx.extensions
+        .get(EXT_EXECUTION_CONCURRENCY)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+}
+
 pub fn record_iteration_completed(ctx: &OptimizationContext, should_terminate: bool) {
+    let mut payload = json!({
+        "iteration": ctx.iteration,
+        "should_terminate": should_terminate,
+    });
+    if let Some(stats) = read_execution_concurrency_stats(ctx) {
+        payload["execution_concurrency"] = json!(stats);
+    }
     record_event_async(
         ctx.task_id.clone(),
         EventType::IterationCompleted,
         Actor::System,
-        Some(json!({
-            "iteration": ctx.iteration,
-            "should_terminate": should_terminate,
-        })),
+        Some(payload),
         Some(ctx.iteration),
         ctx_correlation_id(ctx),
     );
@@ -173,18 +486,24 @@ fn ctx_correlation_id(ctx: &OptimizationContext) -> Option<String> {
 
 /// 编排层契约：维护连续无提升计数（只写 extensions；Layer 4 只读使用）。
 ///
+/// 计数按迭代轮次计入：同一 `ctx.iteration` 只生效第一次调用，之后直接返回当前值，
+/// 避免评估链路与编排 step 重复计数（含取消后重跑本轮）。
+///
 /// - best_is_better=true → 重置为 0
 /// - best_is_better=false → +1
 pub fn update_consecutive_no_improvement(
     ctx: &mut OptimizationContext,
     best_is_better: bool,
 ) -> u32 {
-    let prev = ctx
+    let prev = read_consecutive_no_improvement(ctx);
+    let counted_iteration = ctx
         .extensions
-        .get(EXT_CONSECUTIVE_NO_IMPROVEMENT)
+        .get(EXT_CONSECUTIVE_NO_IMPROVEMENT_ITERATION)
         .and_then(|v| v.as_u64())
-        .and_then(|n| u32::try_from(n).ok())
-        .unwrap_or(0);
+        .and_then(|n| u32::try_from(n).ok());
+    if counted_iteration == Some(ctx.iteration) {
+        return prev;
+    }
 
     let next = if best_is_better {
         0
@@ -196,9 +515,31 @@ pub fn update_consecutive_no_improvement(
         EXT_CONSECUTIVE_NO_IMPROVEMENT.to_string(),
         serde_json::Value::Number(serde_json::Number::from(next as u64)),
     );
+    ctx.extensions.insert(
+        EXT_CONSECUTIVE_NO_IMPROVEMENT_ITERATION.to_string(),
+        serde_json::Value::Number(serde_json::Number::from(ctx.iteration as u64)),
+    );
     next
 }
 
+/// 编排层契约：停滞处理（多样性注入 / 请求人工引导）后清零连续无提升计数。
+///
+/// 不影响本轮的计入标记：清零后同一轮不会再被重新计数。
+pub fn reset_consecutive_no_improvement(ctx: &mut OptimizationContext) {
+    ctx.extensions.insert(
+        EXT_CONSECUTIVE_NO_IMPROVEMENT.to_string(),
+        serde_json::Value::Number(serde_json::Number::from(0u64)),
+    );
+}
+
+fn read_consecutive_no_improvement(ctx: &OptimizationContext) -> u32 {
+    ctx.extensions
+        .get(EXT_CONSECUTIVE_NO_IMPROVEMENT)
+        .and_then(|v| v.as_u64())
+        .and_then(|n| u32::try_from(n).ok())
+        .unwrap_or(0)
+}
+
 /// 编排层契约：写入失败档案（Failure Archive）。
 ///
 /// 写入时机：某个候选 Prompt 完成评估后，对所有失败用例生成条目并写入：
@@ -429,15 +770,15 @@ pub async fn run_failure_archive_and_diversity_injection_step<
     let current_exec = iteration_engine
         .run_tests(ctx, &current_prompt, &batch, task_config)
         .await?;
-    let current_pairs = IterationEngine::build_evaluation_pairs(&batch, &current_exec)?;
-    let current_evals = evaluator.evaluate_batch(ctx, &current_pairs).await?;
+    let (_, current_evals) =
+        IterationEngine::evaluate_outcomes(evaluator, ctx, &batch, &current_exec).await?;
 
     // 评估候选（用于失败档案 + best_candidate_stats）
     let candidate_exec = iteration_engine
         .run_tests(ctx, &candidate_prompt, &batch, task_config)
         .await?;
-    let candidate_pairs = IterationEngine::build_evaluation_pairs(&batch, &candidate_exec)?;
-    let candidate_evals = evaluator.evaluate_batch(ctx, &candidate_pairs).await?;
+    let (_, candidate_evals) =
+        IterationEngine::evaluate_outcomes(evaluator, ctx, &batch, &candidate_exec).await?;
 
     let current_stats = summarize_stats(&current_evals);
     let candidate_stats = summarize_stats(&candidate_evals);
@@ -451,7 +792,7 @@ pub async fn run_failure_archive_and_diversity_injection_step<
         serde_json::to_value(candidate_stats).unwrap_or_default(),
     );
 
-    let best_is_better = is_better_stats(candidate_stats, current_stats);
+    let best_is_better = candidate_stats.is_better_than(&current_stats);
     let consecutive = update_consecutive_no_improvement(ctx, best_is_better);
 
     // 写入失败档案（仅对候选失败用例）
@@ -495,14 +836,6 @@ fn summarize_stats(evals: &[EvaluationResult]) -> CandidateStats {
     }
 }
 
-fn is_better_stats(best: CandidateStats, current: CandidateStats) -> bool {
-    if best.pass_rate > current.pass_rate + METRIC_EPS {
-        return true;
-    }
-    (best.pass_rate - current.pass_rate).abs() <= METRIC_EPS
-        && best.mean_score > current.mean_score + METRIC_EPS
-}
-
 fn evaluations_map(
     batch: &[TestCase],
     evals: &[EvaluationResult],
@@ -560,7 +893,10 @@ mod tests {
     use crate::core::traits::Evaluator as EvaluatorTrait;
     use crate::domain::models::TaskReference;
     use crate::domain::models::{FailurePoint, Severity};
-    use crate::domain::types::{ExecutionTargetConfig, OptimizationConfig};
+    use crate::domain::types::{
+        EXT_EXECUTION_CACHE_STATS, ExecutionTargetConfig, OptimizationConfig,
+    };
+    use crate::infra::db::pool::create_pool;
 
     #[derive(Debug)]
     struct InFlightTarget {
@@ -672,6 +1008,13 @@ mod tests {
         }
     }
 
+    fn successes(outcomes: &[CaseOutcome]) -> Vec<&ExecutionResult> {
+        outcomes
+            .iter()
+            .map(|o| o.success().expect("用例应执行成功"))
+            .collect()
+    }
+
     #[tokio::test]
     async fn run_tests_serial_does_not_overlap() {
         let target = Arc::new(InFlightTarget::new(10));
@@ -721,6 +1064,66 @@ mod tests {
         assert!(max_seen >= 2, "max_seen={max_seen}"); // sanity: should overlap
     }
 
+    #[derive(Debug)]
+    struct UpstreamErrorTarget;
+
+    #[async_trait::async_trait]
+    impl ExecutionTarget for UpstreamErrorTarget {
+        async fn execute(
+            &self,
+            _execution_target_config: &ExecutionTargetConfig,
+            _prompt: &str,
+            _input: &HashMap<String, serde_json::Value>,
+            test_case_id: &str,
+        ) -> Result<ExecutionResult, ExecutionError> {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            Err(ExecutionError::UpstreamError {
+                test_case_id: test_case_id.to_string(),
+                message: "HTTP 429".to_string(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "upstream_error"
+        }
+    }
+
+    #[tokio::test]
+    async fn run_tests_parallel_records_adaptive_concurrency_across_batches() {
+        let batch = (0..20)
+            .map(|i| test_case(&format!("tc-{i}")))
+            .collect::<Vec<_>>();
+        let mut ctx = base_ctx(batch.clone());
+        let mut config = OptimizationTaskConfig {
+            execution_mode: ExecutionMode::Parallel,
+            max_concurrency: 16,
+            ..OptimizationTaskConfig::default()
+        };
+        config.execution_retry.max_retries = 0;
+
+        let target = Arc::new(InFlightTarget::new(5));
+        IterationEngine::new(target.clone())
+            .run_tests(&mut ctx, "p", &batch, &config)
+            .await
+            .unwrap();
+        let stats = read_execution_concurrency_stats(&ctx).expect("并行执行后应记录并发统计");
+        assert_eq!(stats.max_concurrency, 16);
+        assert_eq!(stats.effective_concurrency, 16);
+        assert_eq!(stats.backoff_count, 0);
+        assert!(target.max_in_flight() <= 16);
+
+        // 上游限流：下一批从已学习的并发（16）开始；首批 16 个在途请求失败只减半一次（→8），
+        // 其余 4 个用例在新一代并发下继续执行并再次失败（→4）
+        let err = IterationEngine::new(Arc::new(UpstreamErrorTarget))
+            .run_tests(&mut ctx, "p", &batch, &config)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::UpstreamError { .. }));
+        let stats = read_execution_concurrency_stats(&ctx).unwrap();
+        assert_eq!(stats.effective_concurrency, 4);
+        assert_eq!(stats.backoff_count, 2);
+    }
+
     #[tokio::test]
     async fn run_tests_rejects_mismatched_test_case_id() {
         let target = Arc::new(WrongIdTarget);
@@ -738,6 +1141,284 @@ mod tests {
         assert!(err.to_string().contains("mismatch"));
     }
 
+    /// `flaky_*` 首次调用返回上游错误、重试成功；`broken_*` 始终返回参数错误。
+    #[derive(Debug, Default)]
+    struct FlakyTarget {
+        attempts: std::sync::Mutex<HashMap<String, u32>>,
+    }
+
+    impl FlakyTarget {
+        fn total_attempts(&self) -> u32 {
+            self.attempts.lock().unwrap().values().sum()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ExecutionTarget for FlakyTarget {
+        async fn execute(
+            &self,
+            _execution_target_config: &ExecutionTargetConfig,
+            prompt: &str,
+            _input: &HashMap<String, serde_json::Value>,
+            test_case_id: &str,
+        ) -> Result<ExecutionResult, ExecutionError> {
+            let attempt = {
+                let mut attempts = self.attempts.lock().unwrap();
+                let n = attempts.entry(test_case_id.to_string()).or_default();
+                *n += 1;
+                *n
+            };
+            if test_case_id.starts_with("broken") {
+                return Err(ExecutionError::InvalidRequest {
+                    test_case_id: test_case_id.to_string(),
+                    message: "missing variable".to_string(),
+                });
+            }
+            if test_case_id.starts_with("flaky") && attempt == 1 {
+                return Err(ExecutionError::UpstreamError {
+                    test_case_id: test_case_id.to_string(),
+                    message: "HTTP 503".to_string(),
+                });
+            }
+            Ok(ExecutionResult {
+                test_case_id: test_case_id.to_string(),
+                output: prompt.to_string(),
+                latency_ms: 1,
+                token_usage: None,
+                raw_response: None,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "flaky"
+        }
+    }
+
+    #[tokio::test]
+    async fn run_tests_retries_transient_failures_per_case() {
+        let target = Arc::new(FlakyTarget::default());
+        let engine = IterationEngine::new(target.clone());
+        let batch = vec![
+            test_case("flaky-1"),
+            test_case("ok-1"),
+            test_case("flaky-2"),
+        ];
+        let mut ctx = base_ctx(batch.clone());
+        let config = OptimizationTaskConfig::default();
+
+        let outcomes = engine
+            .run_tests(&mut ctx, "p", &batch, &config)
+            .await
+            .unwrap();
+        assert!(outcomes.iter().all(|o| o.success().is_some()));
+        assert_eq!(target.total_attempts(), 5);
+    }
+
+    #[tokio::test]
+    async fn run_tests_keeps_batch_when_failures_within_threshold() {
+        let target = Arc::new(FlakyTarget::default());
+        let engine = IterationEngine::new(target.clone());
+        let mut batch = (0..9)
+            .map(|i| test_case(&format!("ok-{i}")))
+            .collect::<Vec<_>>();
+        batch.insert(4, test_case("broken-1"));
+        let mut ctx = base_ctx(batch.clone());
+        let config = OptimizationTaskConfig {
+            execution_mode: ExecutionMode::Parallel,
+            ..OptimizationTaskConfig::default()
+        };
+
+        let outcomes = engine
+            .run_tests(&mut ctx, "p", &batch, &config)
+            .await
+            .unwrap();
+        assert!(matches!(outcomes[4], CaseOutcome::PermanentFailure(_)));
+        // 非瞬时错误不重试
+        assert_eq!(target.total_attempts(), 10);
+
+        let (pairs, evals) =
+            IterationEngine::evaluate_outcomes(&DeterministicEvaluator, &ctx, &batch, &outcomes)
+                .await
+                .unwrap();
+        assert_eq!(pairs.len(), 10);
+        assert_eq!(pairs[4].1, "");
+        assert_eq!(evals.iter().filter(|e| e.passed).count(), 9);
+        assert!(!evals[4].passed);
+        assert_eq!(evals[4].score, 0.0);
+        assert_eq!(
+            evals[4].failure_points[0].dimension,
+            EXECUTION_FAILURE_DIMENSION
+        );
+        assert_eq!(evals[4].extra["execution_error_kind"], "invalid_request");
+    }
+
+    #[tokio::test]
+    async fn run_tests_aborts_when_failure_ratio_exceeds_threshold() {
+        let target = Arc::new(FlakyTarget::default());
+        let engine = IterationEngine::new(target);
+        let batch = vec![
+            test_case("ok-1"),
+            test_case("broken-1"),
+            test_case("ok-2"),
+            test_case("broken-2"),
+        ];
+        let mut ctx = base_ctx(batch.clone());
+        let mut config = OptimizationTaskConfig::default();
+
+        let err = engine
+            .run_tests(&mut ctx, "p", &batch, &config)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::InvalidRequest { .. }));
+        assert!(err.to_string().contains("broken-1"));
+
+        config.execution_retry.max_failure_percent = 50;
+        let outcomes = engine
+            .run_tests(&mut ctx, "p", &batch, &config)
+            .await
+            .unwrap();
+        assert_eq!(outcomes.iter().filter(|o| o.is_failure()).count(), 2);
+    }
+
+    #[derive(Debug, Default)]
+    struct CountingTarget {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl ExecutionTarget for CountingTarget {
+        async fn execute(
+            &self,
+            _execution_target_config: &ExecutionTargetConfig,
+            prompt: &str,
+            input: &HashMap<String, serde_json::Value>,
+            test_case_id: &str,
+        ) -> Result<ExecutionResult, ExecutionError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ExecutionResult {
+                test_case_id: test_case_id.to_string(),
+                output: format!("{prompt}:{}", input.get("q").cloned().unwrap_or_default()),
+                latency_ms: 5,
+                token_usage: None,
+                raw_response: None,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    fn test_case_with_q(id: &str, q: &str) -> TestCase {
+        let mut tc = test_case(id);
+        tc.input
+            .insert("q".to_string(), serde_json::Value::String(q.to_string()));
+        tc
+    }
+
+    #[tokio::test]
+    async fn run_tests_with_cache_reuses_results_and_tracks_stats() {
+        let pool = create_pool("sqlite::memory:").await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let target = Arc::new(CountingTarget::default());
+        let engine = IterationEngine::new(target.clone());
+        let batch = vec![test_case_with_q("a", "1"), test_case_with_q("b", "2")];
+        let mut ctx = base_ctx(batch.clone());
+        ctx.extensions
+            .insert(EXT_TASK_OWNER_ID.to_string(), serde_json::json!("owner"));
+        let mut config = OptimizationTaskConfig::default();
+        config.execution_cache.enabled = true;
+
+        let first = engine
+            .run_tests_with_cache(&pool, &mut ctx, "p", &batch, &config)
+            .await
+            .unwrap();
+        let first = successes(&first);
+        assert_eq!(target.calls.load(Ordering::SeqCst), 2);
+
+        // 第二轮：a/b 命中；c 与 a 输入相同（不同 id）也命中；d 未命中。
+        let batch2 = vec![
+            test_case_with_q("a", "1"),
+            test_case_with_q("c", "1"),
+            test_case_with_q("b", "2"),
+            test_case_with_q("d", "3"),
+        ];
+        let second = engine
+            .run_tests_with_cache(&pool, &mut ctx, "p", &batch2, &config)
+            .await
+            .unwrap();
+        let second = successes(&second);
+        assert_eq!(target.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            second
+                .iter()
+                .map(|r| r.test_case_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "c", "b", "d"]
+        );
+        assert_eq!(second[0].output, first[0].output);
+        assert_eq!(second[1].output, first[0].output);
+        assert_eq!(second[3].output, "p:\"3\"");
+
+        let stats: ExecutionCacheStats =
+            serde_json::from_value(ctx.extensions[EXT_EXECUTION_CACHE_STATS].clone()).unwrap();
+        assert_eq!(stats.hits, 3);
+        assert_eq!(stats.misses, 3);
+        assert_eq!(stats.errors, 0);
+
+        // 不同 Prompt 不命中
+        engine
+            .run_tests_with_cache(&pool, &mut ctx, "p2", &batch, &config)
+            .await
+            .unwrap();
+        assert_eq!(target.calls.load(Ordering::SeqCst), 5);
+        // 其他用户的任务不命中
+        let mut other_ctx = base_ctx(batch.clone());
+        other_ctx.extensions.insert(
+            EXT_TASK_OWNER_ID.to_string(),
+            serde_json::json!("other-user"),
+        );
+        engine
+            .run_tests_with_cache(&pool, &mut other_ctx, "p", &batch, &config)
+            .await
+            .unwrap();
+        assert_eq!(target.calls.load(Ordering::SeqCst), 7);
+
+        // 未注入所属用户：不读写缓存，始终直接执行
+        let mut anonymous_ctx = base_ctx(batch.clone());
+        for _ in 0..2 {
+            engine
+                .run_tests_with_cache(&pool, &mut anonymous_ctx, "p", &batch, &config)
+                .await
+                .unwrap();
+        }
+        assert_eq!(target.calls.load(Ordering::SeqCst), 11);
+        assert!(
+            !anonymous_ctx
+                .extensions
+                .contains_key(EXT_EXECUTION_CACHE_STATS)
+        );
+    }
+
+    #[tokio::test]
+    async fn run_tests_without_cache_config_always_executes() {
+        let target = Arc::new(CountingTarget::default());
+        let engine = IterationEngine::new(target.clone());
+        let batch = vec![test_case_with_q("a", "1")];
+        let mut ctx = base_ctx(batch.clone());
+        let config = OptimizationTaskConfig::default();
+
+        for _ in 0..2 {
+            engine
+                .run_tests(&mut ctx, "p", &batch, &config)
+                .await
+                .unwrap();
+        }
+        assert_eq!(target.calls.load(Ordering::SeqCst), 2);
+        assert!(!ctx.extensions.contains_key(EXT_EXECUTION_CACHE_STATS));
+    }
+
     #[test]
     fn build_evaluation_pairs_preserves_order_and_validates_alignment() {
         let batch = vec![test_case("a"), test_case("b")];
@@ -769,9 +1450,30 @@ mod tests {
     #[test]
     fn consecutive_no_improvement_counter_resets_and_increments() {
         let mut ctx = base_ctx(vec![]);
+        let step = |ctx: &mut OptimizationContext, improved: bool| {
+            ctx.iteration += 1;
+            update_consecutive_no_improvement(ctx, improved)
+        };
+        assert_eq!(step(&mut ctx, false), 1);
+        assert_eq!(step(&mut ctx, false), 2);
+        assert_eq!(step(&mut ctx, true), 0);
+        assert_eq!(step(&mut ctx, false), 1);
+    }
+
+    #[test]
+    fn consecutive_no_improvement_counts_each_iteration_once() {
+        let mut ctx = base_ctx(vec![]);
+        ctx.iteration = 3;
+        assert_eq!(update_consecutive_no_improvement(&mut ctx, false), 1);
+        // 同一轮的第二个调用方（如编排 step）不再重复计数
         assert_eq!(update_consecutive_no_improvement(&mut ctx, false), 1);
-        assert_eq!(update_consecutive_no_improvement(&mut ctx, false), 2);
-        assert_eq!(update_consecutive_no_improvement(&mut ctx, true), 0);
+        assert_eq!(update_consecutive_no_improvement(&mut ctx, true), 1);
+
+        // 停滞处理清零后，本轮也不会被重新计数
+        reset_consecutive_no_improvement(&mut ctx);
+        assert_eq!(update_consecutive_no_improvement(&mut ctx, false), 0);
+
+        ctx.iteration = 4;
         assert_eq!(update_consecutive_no_improvement(&mut ctx, false), 1);
     }
 
@@ -17,6 +17,7 @@ use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, OnceLock};
 use tokio::sync::{Mutex, Notify};
+use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
 /// 暂停状态快照（用于持久化）
@@ -56,6 +57,8 @@ pub struct PauseController {
     is_paused: AtomicBool,
     /// 继续通知器（用于 await 暂停恢复）
     resume_notify: Notify,
+    /// 在途请求取消令牌（暂停 / 终止时取消；继续或复用控制器时换新）
+    cancel_token: std::sync::Mutex<CancellationToken>,
     /// 暂停状态快照（用于持久化）
     snapshot: Mutex<Option<PauseStateSnapshot>>,
     /// 运行中配置变更（仅存内存）
@@ -102,6 +105,7 @@ impl PauseController {
             stop_requested: AtomicBool::new(false),
             is_paused: AtomicBool::new(false),
             resume_notify: Notify::new(),
+            cancel_token: std::sync::Mutex::new(CancellationToken::new()),
             snapshot: Mutex::new(None),
             max_iterations_override: Mutex::new(None),
             last_correlation_id: Mutex::new(None),
@@ -143,6 +147,7 @@ impl PauseController {
         *self.last_correlation_id.lock().await = Some(correlation_id.to_string());
         *self.last_user_id.lock().await = Some(user_id.to_string());
         reset_idle_autosave_timer(&self.task_id).await;
+        self.cancel_in_flight();
 
         info!(
             task_id = %self.task_id,
@@ -170,6 +175,8 @@ impl PauseController {
         *self.last_correlation_id.lock().await = Some(correlation_id.to_string());
         *self.last_user_id.lock().await = Some(user_id.to_string());
 
+        self.cancel_in_flight();
+
         if self.is_paused.load(Ordering::SeqCst) {
             self.is_paused.store(false, Ordering::SeqCst);
             self.pause_requested.store(false, Ordering::SeqCst);
@@ -194,6 +201,33 @@ impl PauseController {
     /// 清理终止请求标志（用于任务结束后复用控制器）
     pub fn clear_stop_requested(&self) {
         self.stop_requested.store(false, Ordering::SeqCst);
+        self.rearm_cancellation();
+    }
+
+    /// 当前在途请求的取消令牌
+    ///
+    /// 执行目标 / 老师模型调用与该令牌竞速：暂停或终止时立即丢弃在途请求，而非等到下一个安全点。
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel_token
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// 取消所有在途请求（幂等）
+    pub fn cancel_in_flight(&self) {
+        self.cancel_token
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .cancel();
+    }
+
+    /// 为后续请求换发新的取消令牌（已取消的令牌不可复用）
+    fn rearm_cancellation(&self) {
+        let mut token = self.cancel_token.lock().unwrap_or_else(|e| e.into_inner());
+        if token.is_cancelled() {
+            *token = CancellationToken::new();
+        }
     }
 
     /// 设置运行中最大迭代轮数（用于增量更新）
@@ -227,6 +261,9 @@ impl PauseController {
         // 清除暂停状态
         self.is_paused.store(false, Ordering::SeqCst);
         self.pause_requested.store(false, Ordering::SeqCst);
+        if !self.is_stop_requested() {
+            self.rearm_cancellation();
+        }
 
         // 先清理落盘快照，避免新的连接误判为已暂停
         if let Err(err) = clear_snapshot_file(&self.task_id) {
@@ -630,6 +667,9 @@ impl PauseController {
         *self.last_correlation_id.lock().await = None;
         *self.last_user_id.lock().await = None;
         let _ = clear_snapshot_file(&self.task_id);
+        if !self.is_stop_requested() {
+            self.rearm_cancellation();
+        }
     }
 }
 
@@ -745,6 +785,34 @@ impl PauseControllerRegistry {
     }
 }
 
+tokio::task_local! {
+    /// 当前运行中任务的暂停控制器（由 OptimizationEngine 在运行期间设置）
+    static CURRENT_PAUSE_CONTROLLER: Arc<PauseController>;
+}
+
+/// 在指定任务的暂停控制器作用域内运行 `fut`（供无法访问 ctx 的下游调用获取取消令牌）
+pub async fn with_pause_controller<F: std::future::Future>(
+    controller: Arc<PauseController>,
+    fut: F,
+) -> F::Output {
+    CURRENT_PAUSE_CONTROLLER.scope(controller, fut).await
+}
+
+/// 当前作用域内任务的取消令牌（不在任务作用域内时为 None）
+pub fn current_cancellation_token() -> Option<CancellationToken> {
+    CURRENT_PAUSE_CONTROLLER
+        .try_with(|controller| controller.cancellation_token())
+        .ok()
+}
+
+/// 指定任务当前的取消令牌（任务尚无暂停控制器时返回永不取消的令牌）
+pub async fn task_cancellation_token(task_id: &str) -> CancellationToken {
+    match global_pause_registry().get(task_id).await {
+        Some(controller) => controller.cancellation_token(),
+        None => CancellationToken::new(),
+    }
+}
+
 static PAUSE_REGISTRY: OnceLock<Arc<PauseControllerRegistry>> = OnceLock::new();
 
 /// 获取全局暂停控制器注册表
@@ -881,6 +949,25 @@ mod tests {
         assert!(!controller.request_resume("cid-3", "user-1").await);
     }
 
+    #[tokio::test]
+    async fn pause_cancels_in_flight_token_and_resume_rearms() {
+        let controller = PauseController::new("task-1");
+        let before = controller.cancellation_token();
+
+        controller.request_pause("cid-1", "user-1").await;
+        assert!(before.is_cancelled());
+
+        let _ = controller
+            .checkpoint_pause(1, "test", Some("cid-1"), serde_json::json!({}))
+            .await
+            .expect("checkpoint pause");
+        assert!(controller.request_resume("cid-2", "user-1").await);
+        assert!(!controller.cancellation_token().is_cancelled());
+
+        controller.request_stop("cid-3", "user-1").await;
+        assert!(controller.cancellation_token().is_cancelled());
+    }
+
     #[tokio::test]
     async fn pause_controller_checkpoint_creates_snapshot() {
         let controller = PauseController::new("task-1");
@@ -5,6 +5,7 @@ use std::collections::HashMap;
 use thiserror::Error;
 use tracing::{error, info, warn};
 
+use crate::core::execution_target::{DifyExecutionSettings, parse_dify_execution_settings};
 use crate::core::iteration_engine::checkpoint::{compute_checksum, verify_checksum};
 use crate::core::iteration_engine::events::record_event_async;
 use crate::core::iteration_engine::pause_state::global_pause_registry;
@@ -16,15 +17,16 @@ use crate::domain::models::{
     Rule, RuleSystem, RuleTags,
 };
 use crate::domain::types::{
-    EXT_BEST_CANDIDATE_INDEX, EXT_BEST_CANDIDATE_PROMPT, EXT_TASK_MODE, EXT_USER_GUIDANCE,
-    ExecutionTargetConfig, OptimizationConfig, OptimizationContext, RunControlState, SplitStrategy,
-    unix_ms_to_iso8601,
+    EXT_BEST_CANDIDATE_INDEX, EXT_BEST_CANDIDATE_PROMPT, EXT_TASK_MODE, EXT_TASK_OWNER_ID,
+    EXT_USER_GUIDANCE, ExecutionTargetConfig, OptimizationConfig, OptimizationContext,
+    RunControlState, SplitStrategy, unix_ms_to_iso8601,
 };
 use crate::infra::db::pool::global_db_pool;
 use crate::infra::db::repositories::{
     CheckpointRepo, CheckpointRepoError, CredentialRepo, CredentialRepoError, CredentialType,
-    OptimizationTaskRepo, OptimizationTaskRepoError, RecoveryMetricsRepo, RecoveryMetricsRepoError,
-    TestSetRepo, TestSetRepoError,
+    LlmProviderRepo, LlmProviderRepoError, OptimizationTaskRepo, OptimizationTaskRepoError,
+    RecoveryMetricsRepo, RecoveryMetricsRepoError, TestSetRepo, TestSetRepoError, WorkspaceRepo,
+    WorkspaceRepoError,
 };
 use crate::shared::time::now_millis;
 
@@ -54,6 +56,10 @@ pub enum RecoveryError {
     TestSetRepo(#[from] TestSetRepoError),
     #[error("凭证仓库错误: {0}")]
     CredentialRepo(#[from] CredentialRepoError),
+    #[error("Provider 仓库错误: {0}")]
+    LlmProviderRepo(#[from] LlmProviderRepoError),
+    #[error("工作区仓库错误: {0}")]
+    WorkspaceRepo(#[from] WorkspaceRepoError),
     #[error("恢复上下文失败: {0}")]
     Context(String),
     #[error("恢复统计写入失败: {0}")]
@@ -280,6 +286,14 @@ pub(crate) async fn recover_task_with_pool(
         );
     }
 
+    if let Err(err) = OptimizationTaskRepo::clear_shutdown_interruption(pool, task_id).await {
+        warn!(
+            task_id = %task_id,
+            error = %err,
+            "清除停机暂停标记失败"
+        );
+    }
+
     Ok(result)
 }
 
@@ -720,6 +734,15 @@ async fn rebuild_optimization_context(
                 other => RecoveryError::Repo(other),
             })?;
 
+    // 任务所属用户 = 工作区 owner（成员代为恢复时同样归属 owner）
+    let owner_id = WorkspaceRepo::find_by_id(pool, &task.workspace_id, user_id)
+        .await
+        .map_err(|err| match err {
+            WorkspaceRepoError::NotFound => RecoveryError::TaskNotFound,
+            other => RecoveryError::WorkspaceRepo(other),
+        })?
+        .user_id;
+
     let task_config =
         OptimizationTaskConfig::normalized_from_config_json(task.config_json.as_deref());
 
@@ -766,6 +789,10 @@ async fn rebuild_optimization_context(
         EXT_TASK_MODE.to_string(),
         serde_json::Value::String(task_mode.to_string()),
     );
+    extensions.insert(
+        EXT_TASK_OWNER_ID.to_string(),
+        serde_json::Value::String(owner_id),
+    );
     if let Some(guidance) = checkpoint.user_guidance.clone() {
         if let Ok(value) = serde_json::to_value(guidance) {
             extensions.insert(EXT_USER_GUIDANCE.to_string(), value);
@@ -878,30 +905,49 @@ async fn build_execution_target_config(
 ) -> Result<ExecutionTargetConfig, RecoveryError> {
     match execution_target_type {
         ExecutionTargetType::Dify => {
-            let prompt_variable = extract_prompt_variable(pool, workspace_id, test_set_ids).await?;
-            let credential =
-                CredentialRepo::find_by_user_and_type(pool, user_id, CredentialType::Dify).await?;
+            let settings = extract_dify_settings(pool, workspace_id, test_set_ids).await?;
+            let credential_id = task_config
+                .execution_credential_id
+                .as_deref()
+                .or(settings.credential_id.as_deref());
+            let credential = CredentialRepo::resolve_personal(
+                pool,
+                user_id,
+                CredentialType::Dify,
+                credential_id,
+            )
+            .await?;
             Ok(ExecutionTargetConfig::Dify {
                 api_url: credential.base_url,
                 workflow_id: String::new(),
-                prompt_variable,
+                prompt_variable: settings.prompt_variable,
                 api_key: None,
+                output_selector: settings.output_selector,
+                app_type: settings.app_type,
+                response_mode: settings.response_mode,
             })
         }
         ExecutionTargetType::Generic => {
-            let credential =
-                CredentialRepo::find_by_user_and_type(pool, user_id, CredentialType::GenericLlm)
-                    .await?;
+            let credential = CredentialRepo::resolve_personal(
+                pool,
+                user_id,
+                CredentialType::GenericLlm,
+                task_config.execution_credential_id.as_deref(),
+            )
+            .await?;
             let model_name = task_config
                 .teacher_llm
                 .model_id
                 .clone()
                 .unwrap_or_else(|| "unknown".to_string());
+            let provider =
+                LlmProviderRepo::resolve(pool, user_id, credential.provider.as_deref()).await?;
             Ok(ExecutionTargetConfig::DirectModel {
                 base_url: credential.base_url,
                 model_name,
                 user_prompt_template: "{input}".to_string(),
                 api_key: None,
+                provider: Some(provider),
             })
         }
         ExecutionTargetType::Example => Ok(ExecutionTargetConfig::DirectModel {
@@ -909,30 +955,30 @@ async fn build_execution_target_config(
             model_name: "example".to_string(),
             user_prompt_template: "{input}".to_string(),
             api_key: None,
+            provider: None,
         }),
+        ExecutionTargetType::Command => Ok(task_config.command_target.to_execution_target_config()),
+        ExecutionTargetType::Http => Ok(task_config.http_target.to_execution_target_config()),
     }
 }
 
-async fn extract_prompt_variable(
+/// 从测试集的 Dify 配置中读取 (prompt 变量名, 输出选择器)。
+async fn extract_dify_settings(
     pool: &sqlx::SqlitePool,
     workspace_id: &str,
     test_set_ids: &[String],
-) -> Result<String, RecoveryError> {
+) -> Result<DifyExecutionSettings, RecoveryError> {
     for test_set_id in test_set_ids {
         let test_set = TestSetRepo::find_by_id(pool, workspace_id, test_set_id).await?;
-        if let Some(raw) = test_set.dify_config_json {
-            if let Ok(value) = serde_json::from_str::<serde_json::Value>(&raw) {
-                if let Some(variable) = value
-                    .get("targetPromptVariable")
-                    .and_then(|v| v.as_str())
-                    .or_else(|| value.get("target_prompt_variable").and_then(|v| v.as_str()))
-                {
-                    return Ok(variable.to_string());
-                }
-            }
+        if let Some(settings) = test_set
+            .dify_config_json
+            .as_deref()
+            .and_then(parse_dify_execution_settings)
+        {
+            return Ok(settings);
         }
     }
-    Ok("prompt".to_string())
+    Ok(DifyExecutionSettings::default())
 }
 
 #[cfg(test)]
@@ -1089,6 +1135,112 @@ mod tests {
         assert_eq!(ctx.iteration, valid.iteration);
     }
 
+    async fn create_example_task(pool: &sqlx::SqlitePool, owner_id: &str) -> (String, String) {
+        let workspace = WorkspaceRepo::create(pool, owner_id, "ws", None)
+            .await
+            .expect("创建工作区失败");
+        let test_set = TestSetRepo::create(
+            pool,
+            &workspace.id,
+            "ts",
+            None,
+            &[sample_case()],
+            None,
+            None,
+        )
+        .await
+        .expect("创建测试集失败");
+        let created = OptimizationTaskRepo::create_scoped(
+            pool,
+            crate::infra::db::repositories::CreateOptimizationTaskInput {
+                user_id: owner_id,
+                workspace_id: &workspace.id,
+                name: "task",
+                description: None,
+                goal: "goal",
+                execution_target_type: ExecutionTargetType::Example,
+                task_mode: crate::domain::models::OptimizationTaskMode::Fixed,
+                test_set_ids: std::slice::from_ref(&test_set.id),
+                teacher_prompt_version_id: None,
+            },
+        )
+        .await
+        .expect("创建任务失败");
+        (workspace.id, created.task.id)
+    }
+
+    #[tokio::test]
+    async fn rebuilt_context_scopes_execution_cache_to_task_owner() {
+        use crate::core::execution_target::create_execution_target;
+        use crate::core::iteration_engine::orchestrator::IterationEngine;
+        use crate::infra::db::repositories::{WorkspaceMemberRepo, WorkspaceRole};
+
+        let pool = setup_db().await;
+        insert_user(&pool, "owner", "owner").await;
+        insert_user(&pool, "editor", "editor").await;
+        insert_user(&pool, "stranger", "stranger").await;
+
+        let (workspace_id, task_id) = create_example_task(&pool, "owner").await;
+        WorkspaceMemberRepo::add_by_username(
+            &pool,
+            &workspace_id,
+            "editor",
+            WorkspaceRole::Editor,
+            "owner",
+        )
+        .await
+        .expect("添加成员失败");
+        let checkpoint = build_checkpoint(&task_id, 1, None);
+        CheckpointRepo::create_checkpoint(&pool, checkpoint.clone())
+            .await
+            .expect("创建 checkpoint 失败");
+
+        // 成员代为恢复：缓存仍归属任务 owner
+        let mut ctx = rebuild_optimization_context(&pool, "editor", &checkpoint, None)
+            .await
+            .expect("重建上下文失败");
+        assert_eq!(ctx.extensions[EXT_TASK_OWNER_ID], json!("owner"));
+
+        let mut config = OptimizationTaskConfig::default();
+        config.execution_cache.enabled = true;
+        let engine = IterationEngine::new(
+            create_execution_target(ExecutionTargetType::Example).expect("创建执行目标失败"),
+        );
+        let batch = ctx.test_cases.clone();
+        engine
+            .run_tests_with_cache(&pool, &mut ctx, "p", &batch, &config)
+            .await
+            .expect("执行失败");
+
+        let owners: Vec<String> =
+            sqlx::query_scalar("SELECT DISTINCT user_id FROM execution_result_cache")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(owners, vec!["owner".to_string()]);
+
+        // 其他用户的同内容任务不命中 owner 的缓存
+        let (_, other_task_id) = create_example_task(&pool, "stranger").await;
+        let other_checkpoint = build_checkpoint(&other_task_id, 1, None);
+        CheckpointRepo::create_checkpoint(&pool, other_checkpoint.clone())
+            .await
+            .expect("创建 checkpoint 失败");
+        let mut other_ctx =
+            rebuild_optimization_context(&pool, "stranger", &other_checkpoint, None)
+                .await
+                .expect("重建上下文失败");
+        engine
+            .run_tests_with_cache(&pool, &mut other_ctx, "p", &batch, &config)
+            .await
+            .expect("执行失败");
+        let stats: crate::domain::types::ExecutionCacheStats = serde_json::from_value(
+            other_ctx.extensions[crate::domain::types::EXT_EXECUTION_CACHE_STATS].clone(),
+        )
+        .unwrap();
+        assert_eq!(stats.hits, 0);
+        assert_eq!(stats.misses, batch.len() as u64);
+    }
+
     #[tokio::test]
     async fn recover_task_uses_pause_state_compensation() {
         let pool = setup_db().await;
@@ -1479,4 +1631,98 @@ mod tests {
             .expect("查询任务失败");
         assert_eq!(loaded.status, OptimizationTaskStatus::Terminated);
     }
+
+    #[tokio::test]
+    async fn build_execution_target_config_prefers_task_then_test_set_credential() {
+        use crate::infra::db::repositories::CreateCredentialInput;
+
+        let pool = setup_db().await;
+        insert_user(&pool, "u1", "user1").await;
+        let mut ids = Vec::new();
+        for name in ["default", "from-test-set", "from-task"] {
+            let record = CredentialRepo::create(
+                &pool,
+                CreateCredentialInput {
+                    user_id: "u1".to_string(),
+                    credential_type: CredentialType::Dify,
+                    name: name.to_string(),
+                    provider: None,
+                    base_url: format!("https://{name}.example.com"),
+                    encrypted_api_key: vec![1],
+                    nonce: vec![2],
+                    salt: vec![3],
+                    make_default: false,
+                },
+            )
+            .await
+            .expect("创建凭证失败");
+            ids.push(record.id);
+        }
+
+        let workspace = WorkspaceRepo::create(&pool, "u1", "ws", None)
+            .await
+            .expect("创建工作区失败");
+        let dify_config = json!({
+            "targetPromptVariable": "prompt",
+            "bindings": {},
+            "parametersSnapshot": null,
+            "credentialId": ids[1],
+        })
+        .to_string();
+        let test_set = TestSetRepo::create(
+            &pool,
+            &workspace.id,
+            "ts",
+            None,
+            &[sample_case()],
+            Some(&dify_config),
+            None,
+        )
+        .await
+        .expect("创建测试集失败");
+        let test_set_ids = vec![test_set.id];
+
+        let api_url = |config: ExecutionTargetConfig| match config {
+            ExecutionTargetConfig::Dify { api_url, .. } => api_url,
+            other => panic!("unexpected config: {other:?}"),
+        };
+
+        let mut task_config = OptimizationTaskConfig::default();
+        let config = build_execution_target_config(
+            &pool,
+            "u1",
+            &workspace.id,
+            ExecutionTargetType::Dify,
+            &task_config,
+            &test_set_ids,
+        )
+        .await
+        .expect("构建执行目标失败");
+        assert_eq!(api_url(config), "https://from-test-set.example.com");
+
+        task_config.execution_credential_id = Some(ids[2].clone());
+        let config = build_execution_target_config(
+            &pool,
+            "u1",
+            &workspace.id,
+            ExecutionTargetType::Dify,
+            &task_config,
+            &test_set_ids,
+        )
+        .await
+        .expect("构建执行目标失败");
+        assert_eq!(api_url(config), "https://from-task.example.com");
+
+        let config = build_execution_target_config(
+            &pool,
+            "u1",
+            &workspace.id,
+            ExecutionTargetType::Dify,
+            &OptimizationTaskConfig::default(),
+            &[],
+        )
+        .await
+        .expect("构建执行目标失败");
+        assert_eq!(api_url(config), "https://default.example.com");
+    }
 }
@@ -0,0 +1,319 @@
+//! 优雅停机协调
+//!
+//! 收到停机信号后，通知所有运行中任务在下一个安全点暂停：由引擎在安全点写入
+//! Checkpoint 并将任务标记为“停机暂停”，协调器在宽限期内等待全部任务落盘。
+//! 启动时按 [`StartupRecoveryPolicy`] 自动恢复这些任务，或仅保留在未完成任务列表中。
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::core::iteration_engine::events::record_event_async;
+use crate::core::iteration_engine::pause_state::global_pause_registry;
+use crate::core::iteration_engine::recovery::{RecoveryError, recover_task_with_pool};
+use crate::domain::models::{Actor, EventType};
+use crate::infra::db::pool::global_db_pool;
+use crate::infra::db::repositories::OptimizationTaskRepo;
+
+/// 默认停机宽限期（秒）
+pub const SHUTDOWN_GRACE_PERIOD_SECS_DEFAULT: u64 = 30;
+
+/// 停机暂停使用的 correlation_id / user_id
+pub const SHUTDOWN_CORRELATION_ID: &str = "graceful-shutdown";
+const SHUTDOWN_USER_ID: &str = "system";
+const STARTUP_RECOVERY_CORRELATION_ID: &str = "startup-recovery";
+
+/// 启动时对停机暂停任务的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StartupRecoveryPolicy {
+    /// 仅在 `/recovery/unfinished-tasks` 中列出，由用户决定是否恢复
+    #[default]
+    ListOnly,
+    /// 启动后自动通过 `recovery::recover_task` 恢复
+    AutoResume,
+}
+
+impl StartupRecoveryPolicy {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "list" | "list_only" => Some(Self::ListOnly),
+            "auto" | "auto_resume" => Some(Self::AutoResume),
+            _ => None,
+        }
+    }
+}
+
+/// 停机结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// 收到暂停通知的运行中任务数
+    pub signalled: usize,
+    /// 宽限期内未到达安全点的任务
+    pub timed_out: Vec<String>,
+}
+
+/// 停机协调器：跟踪运行中任务，停机时通知其暂停并等待落盘
+#[derive(Default)]
+pub struct ShutdownCoordinator {
+    shutting_down: AtomicBool,
+    token: CancellationToken,
+    running: Mutex<HashSet<String>>,
+    changed: Notify,
+}
+
+/// 运行中任务登记；drop 时自动注销
+pub struct RunningTaskGuard {
+    coordinator: Arc<ShutdownCoordinator>,
+    task_id: String,
+}
+
+impl Drop for RunningTaskGuard {
+    fn drop(&mut self) {
+        self.coordinator.settle(&self.task_id);
+    }
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记运行中任务（引擎 `run` 期间持有）
+    pub fn register(self: &Arc<Self>, task_id: &str) -> RunningTaskGuard {
+        self.running
+            .lock()
+            .expect("running tasks lock poisoned")
+            .insert(task_id.to_string());
+        RunningTaskGuard {
+            coordinator: Arc::clone(self),
+            task_id: task_id.to_string(),
+        }
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// 停机信号（供后台循环退出）
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn running_tasks(&self) -> Vec<String> {
+        let mut tasks: Vec<String> = self
+            .running
+            .lock()
+            .expect("running tasks lock poisoned")
+            .iter()
+            .cloned()
+            .collect();
+        tasks.sort();
+        tasks
+    }
+
+    /// 任务已到达安全点（或已结束），不再需要等待
+    pub fn settle(&self, task_id: &str) {
+        let removed = self
+            .running
+            .lock()
+            .expect("running tasks lock poisoned")
+            .remove(task_id);
+        if removed {
+            self.changed.notify_waiters();
+        }
+    }
+
+    /// 通知所有运行中任务在下一个安全点暂停，并在宽限期内等待其写入 Checkpoint
+    pub async fn shutdown(&self, grace: Duration) -> ShutdownReport {
+        self.shutting_down.store(true, Ordering::SeqCst);
+        self.token.cancel();
+
+        let tasks = self.running_tasks();
+        let registry = global_pause_registry();
+        for task_id in &tasks {
+            let controller = registry.get_or_create(task_id).await;
+            if controller.is_paused() {
+                // 已处于暂停（状态已落盘），无需等待
+                self.settle(task_id);
+                continue;
+            }
+            controller
+                .request_pause(SHUTDOWN_CORRELATION_ID, SHUTDOWN_USER_ID)
+                .await;
+        }
+        info!(
+            running_tasks = tasks.len(),
+            grace_secs = grace.as_secs(),
+            "停机：已通知运行中任务在安全点暂停"
+        );
+
+        let deadline = tokio::time::Instant::now() + grace;
+        loop {
+            let notified = self.changed.notified();
+            if self.running_tasks().is_empty() {
+                break;
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                break;
+            }
+        }
+
+        let timed_out = self.running_tasks();
+        if !timed_out.is_empty() {
+            warn!(
+                tasks = ?timed_out,
+                "停机宽限期已到，仍有任务未到达安全点"
+            );
+        }
+        ShutdownReport {
+            signalled: tasks.len(),
+            timed_out,
+        }
+    }
+}
+
+static SHUTDOWN_COORDINATOR: OnceLock<Arc<ShutdownCoordinator>> = OnceLock::new();
+
+/// 获取全局停机协调器
+pub fn global_shutdown_coordinator() -> Arc<ShutdownCoordinator> {
+    SHUTDOWN_COORDINATOR
+        .get_or_init(|| Arc::new(ShutdownCoordinator::new()))
+        .clone()
+}
+
+/// 安全点钩子：停机期间任务暂停并写入 Checkpoint 后调用，标记任务状态并通知协调器
+pub async fn park_for_shutdown(task_id: &str) {
+    let coordinator = global_shutdown_coordinator();
+    if !coordinator.is_shutting_down() {
+        return;
+    }
+    if let Some(pool) = global_db_pool()
+        && let Err(err) = OptimizationTaskRepo::mark_interrupted_by_shutdown(&pool, task_id).await
+    {
+        warn!(task_id = %task_id, error = %err, "停机暂停：标记任务状态失败");
+    }
+    info!(
+        correlation_id = SHUTDOWN_CORRELATION_ID,
+        user_id = SHUTDOWN_USER_ID,
+        task_id = %task_id,
+        action = "task_parked_for_shutdown",
+        "停机：任务已在安全点暂停并写入 Checkpoint"
+    );
+    coordinator.settle(task_id);
+}
+
+/// 启动时处理上次停机暂停的任务；返回自动恢复成功的任务数
+pub async fn resume_interrupted_tasks(
+    pool: &sqlx::SqlitePool,
+    policy: StartupRecoveryPolicy,
+) -> Result<usize, RecoveryError> {
+    let tasks = OptimizationTaskRepo::list_interrupted_by_shutdown(pool).await?;
+    if tasks.is_empty() {
+        return Ok(0);
+    }
+
+    if policy == StartupRecoveryPolicy::ListOnly {
+        info!(
+            tasks = tasks.len(),
+            "检测到上次停机暂停的任务，已保留在未完成任务列表中等待用户恢复"
+        );
+        return Ok(0);
+    }
+
+    let mut resumed = 0;
+    for (task_id, user_id) in tasks {
+        match recover_task_with_pool(
+            pool,
+            &task_id,
+            &user_id,
+            STARTUP_RECOVERY_CORRELATION_ID,
+            None,
+        )
+        .await
+        {
+            Ok((ctx, checkpoint)) => {
+                resumed += 1;
+                record_event_async(
+                    task_id.clone(),
+                    EventType::CheckpointRecovered,
+                    Actor::System,
+                    Some(serde_json::json!({
+                        "checkpoint_id": checkpoint.id,
+                        "source": "startup_auto_resume",
+                    })),
+                    Some(ctx.iteration),
+                    Some(STARTUP_RECOVERY_CORRELATION_ID.to_string()),
+                );
+                info!(
+                    task_id = %task_id,
+                    checkpoint_id = %checkpoint.id,
+                    iteration = ctx.iteration,
+                    "启动：已自动恢复停机暂停的任务"
+                );
+            }
+            Err(err) => {
+                warn!(
+                    task_id = %task_id,
+                    error = %err,
+                    "启动：自动恢复停机暂停的任务失败，保留在未完成任务列表中"
+                );
+            }
+        }
+    }
+
+    Ok(resumed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_startup_recovery_policy() {
+        assert_eq!(
+            StartupRecoveryPolicy::parse("auto_resume"),
+            Some(StartupRecoveryPolicy::AutoResume)
+        );
+        assert_eq!(
+            StartupRecoveryPolicy::parse(" LIST "),
+            Some(StartupRecoveryPolicy::ListOnly)
+        );
+        assert_eq!(StartupRecoveryPolicy::parse("bogus"), None);
+    }
+
+    #[tokio::test]
+    async fn shutdown_returns_once_running_tasks_settle() {
+        let coordinator = Arc::new(ShutdownCoordinator::new());
+        let guard = coordinator.register("shutdown-settle-task");
+
+        let waiter = Arc::clone(&coordinator);
+        let handle = tokio::spawn(async move { waiter.shutdown(Duration::from_secs(10)).await });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(coordinator.is_shutting_down());
+        assert!(coordinator.shutdown_token().is_cancelled());
+        let controller = global_pause_registry()
+            .get_or_create("shutdown-settle-task")
+            .await;
+        assert!(controller.is_pause_requested());
+
+        drop(guard);
+        let report = handle.await.expect("join");
+        assert_eq!(report.signalled, 1);
+        assert!(report.timed_out.is_empty());
+    }
+
+    #[tokio::test]
+    async fn shutdown_reports_tasks_that_miss_the_grace_period() {
+        let coordinator = Arc::new(ShutdownCoordinator::new());
+        let _guard = coordinator.register("shutdown-stuck-task");
+
+        let report = coordinator.shutdown(Duration::from_millis(20)).await;
+        assert_eq!(report.timed_out, vec!["shutdown-stuck-task".to_string()]);
+    }
+}
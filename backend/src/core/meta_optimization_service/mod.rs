@@ -7,7 +7,9 @@ use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 use crate::core::evaluator::{EXT_TASK_EVALUATOR_CONFIG, create_evaluator_for_task_config};
-use crate::core::execution_target::{ExecutionError, create_execution_target};
+use crate::core::execution_target::{
+    DifyExecutionSettings, ExecutionError, create_execution_target, parse_dify_execution_settings,
+};
 use crate::core::iteration_engine::orchestrator::IterationEngine;
 use crate::core::teacher_model::{TeacherModelType, create_teacher_model};
 use crate::domain::models::{
@@ -18,14 +20,16 @@ use crate::domain::models::{
     TeacherPrompt, TeacherPromptStats, TeacherPromptVersion, TestCase, VersionCompareResult,
 };
 use crate::domain::types::{
-    ExecutionTargetConfig, OptimizationConfig, OptimizationContext, unix_ms_to_iso8601,
+    EXT_TASK_OWNER_ID, ExecutionTargetConfig, OptimizationConfig, OptimizationContext,
+    unix_ms_to_iso8601,
 };
 use crate::infra::db::repositories::{
     CreateTeacherPromptRecordInput, CredentialRepo, CredentialRepoError, CredentialType,
-    OptimizationTaskRepo, OptimizationTaskRepoError, TeacherPromptRecord, TeacherPromptRepo,
-    TeacherPromptRepoError, TeacherPromptVersionWithStatsRecord, TestSetRepo, TestSetRepoError,
+    LlmProviderRepo, LlmProviderRepoError, OptimizationTaskRepo, OptimizationTaskRepoError,
+    TeacherPromptRecord, TeacherPromptRepo, TeacherPromptRepoError,
+    TeacherPromptVersionWithStatsRecord, TestSetRepo, TestSetRepoError, WorkspaceRepo,
 };
-use crate::infra::external::api_key_manager::{ApiKeyManager, EncryptedApiKey};
+use crate::infra::external::api_key_manager::{ApiKeyManager, CredentialKeys, EncryptedApiKey};
 
 #[derive(Debug, Error)]
 pub enum MetaOptimizationServiceError {
@@ -33,6 +37,8 @@ pub enum MetaOptimizationServiceError {
     NotFoundOrForbidden(Option<String>),
     #[error("请求参数错误: {0}")]
     InvalidRequest(String),
+    #[error("{0}")]
+    CredentialNotAccessible(String),
     #[error("预览执行失败: {0}")]
     ExecutionFailed(String),
     #[error("预览执行超时")]
@@ -146,7 +152,8 @@ fn map_execution_error(err: ExecutionError) -> MetaOptimizationServiceError {
         | ExecutionError::UpstreamError { message, .. }
         | ExecutionError::ParseError { message, .. }
         | ExecutionError::NotImplemented { message, .. }
-        | ExecutionError::Internal { message, .. } => {
+        | ExecutionError::Internal { message, .. }
+        | ExecutionError::Cancelled { message, .. } => {
             MetaOptimizationServiceError::ExecutionFailed(message)
         }
     }
@@ -187,10 +194,28 @@ fn map_credential_repo_error(err: CredentialRepoError) -> MetaOptimizationServic
         CredentialRepoError::NotFound { .. } => {
             MetaOptimizationServiceError::InvalidRequest("缺少执行所需的 API Key 配置".to_string())
         }
+        err @ (CredentialRepoError::ProfileNotFound(_)
+        | CredentialRepoError::TypeMismatch { .. }
+        | CredentialRepoError::NameConflict(_)) => {
+            MetaOptimizationServiceError::InvalidRequest(err.to_string())
+        }
+        err @ CredentialRepoError::NotAccessible(_) => {
+            MetaOptimizationServiceError::CredentialNotAccessible(err.to_string())
+        }
         CredentialRepoError::DatabaseError(err) => MetaOptimizationServiceError::Database(err),
     }
 }
 
+fn map_llm_provider_repo_error(err: LlmProviderRepoError) -> MetaOptimizationServiceError {
+    match err {
+        LlmProviderRepoError::NotFound(id) => MetaOptimizationServiceError::InvalidRequest(
+            format!("通用大模型凭证引用的 Provider 不存在: {id}"),
+        ),
+        LlmProviderRepoError::DatabaseError(err) => MetaOptimizationServiceError::Database(err),
+        LlmProviderRepoError::Corrupted(msg) => MetaOptimizationServiceError::Repo(msg),
+    }
+}
+
 fn build_preview_error_message(
     passed: bool,
     failure_points: &[crate::domain::models::FailurePoint],
@@ -371,8 +396,8 @@ pub async fn get_historical_tasks_for_meta_optimization(
         r#"
         SELECT ot.id, ot.workspace_id, ot.name, ot.status, ot.created_at
         FROM optimization_tasks ot
-        JOIN workspaces w ON ot.workspace_id = w.id
-        WHERE w.user_id = ?1
+        JOIN workspace_members m ON m.workspace_id = ot.workspace_id
+        WHERE m.user_id = ?1
         ORDER BY ot.created_at DESC
         LIMIT ?2 OFFSET ?3
         "#,
@@ -595,7 +620,7 @@ async fn execute_prompt_preview(
     pool: &SqlitePool,
     api_key_manager: &ApiKeyManager,
     user_id: &str,
-    user_password: &[u8],
+    credential_keys: &CredentialKeys,
     ctx: &PreviewContext,
     prompt: &str,
     correlation_id: Option<String>,
@@ -605,7 +630,7 @@ async fn execute_prompt_preview(
         pool,
         api_key_manager,
         user_id,
-        user_password,
+        credential_keys,
         workspace_id: &ctx.workspace_id,
     };
     let execution_target_config = build_execution_target_config(
@@ -642,6 +667,16 @@ async fn execute_prompt_preview(
             .insert("correlation_id".to_string(), serde_json::Value::String(cid));
     }
 
+    // 执行结果缓存按任务所属用户（工作区 owner）分区
+    let owner_id = WorkspaceRepo::find_by_id(pool, &ctx.workspace_id, user_id)
+        .await
+        .map_err(|err| MetaOptimizationServiceError::Repo(err.to_string()))?
+        .user_id;
+    context.extensions.insert(
+        EXT_TASK_OWNER_ID.to_string(),
+        serde_json::Value::String(owner_id),
+    );
+
     let evaluator_cfg_value =
         serde_json::to_value(&ctx.task_config.evaluator_config).map_err(|_| {
             MetaOptimizationServiceError::ExecutionFailed("evaluator_config 序列化失败".to_string())
@@ -652,34 +687,32 @@ async fn execute_prompt_preview(
 
     let teacher_model = create_teacher_model(TeacherModelType::Example);
     let evaluator = create_evaluator_for_task_config(&ctx.task_config, Some(teacher_model));
-    let execution_target = create_execution_target(ctx.execution_target_type);
+    let execution_target = create_execution_target(ctx.execution_target_type)
+        .map_err(|err| MetaOptimizationServiceError::ExecutionFailed(err.to_string()))?;
     let engine = IterationEngine::new(execution_target);
     let batch = context.test_cases.clone();
 
     let output = tokio::time::timeout(timeout, async {
-        let exec_results = engine
+        let outcomes = engine
             .run_tests(&mut context, prompt, &batch, &ctx.task_config)
             .await
             .map_err(map_execution_error)?;
 
-        let pairs = IterationEngine::build_evaluation_pairs(&batch, &exec_results)
-            .map_err(map_execution_error)?;
-
-        let evals = evaluator
-            .evaluate_batch(&context, &pairs)
-            .await
-            .map_err(|err| match err {
-                crate::core::evaluator::EvaluatorError::InvalidInput(msg) => {
-                    MetaOptimizationServiceError::InvalidRequest(msg)
-                }
-                crate::core::evaluator::EvaluatorError::Timeout(_) => {
-                    MetaOptimizationServiceError::Timeout
-                }
-                crate::core::evaluator::EvaluatorError::ModelFailure(msg)
-                | crate::core::evaluator::EvaluatorError::Internal(msg) => {
-                    MetaOptimizationServiceError::ExecutionFailed(msg)
-                }
-            })?;
+        let (pairs, evals) =
+            IterationEngine::evaluate_outcomes(evaluator.as_ref(), &context, &batch, &outcomes)
+                .await
+                .map_err(|err| match err {
+                    crate::core::evaluator::EvaluatorError::InvalidInput(msg) => {
+                        MetaOptimizationServiceError::InvalidRequest(msg)
+                    }
+                    crate::core::evaluator::EvaluatorError::Timeout(_) => {
+                        MetaOptimizationServiceError::Timeout
+                    }
+                    crate::core::evaluator::EvaluatorError::ModelFailure(msg)
+                    | crate::core::evaluator::EvaluatorError::Internal(msg) => {
+                        MetaOptimizationServiceError::ExecutionFailed(msg)
+                    }
+                })?;
 
         let mut results = Vec::with_capacity(batch.len());
         let mut total_passed = 0;
@@ -687,15 +720,14 @@ async fn execute_prompt_preview(
         let mut total_time_ms = 0i64;
 
         for idx in 0..pairs.len() {
-            let (test_case, _output) = &pairs[idx];
-            let exec_result = &exec_results[idx];
+            let (test_case, output) = &pairs[idx];
             let eval = &evals[idx];
             if eval.passed {
                 total_passed += 1;
             } else {
                 total_failed += 1;
             }
-            let exec_time = exec_result.latency_ms as i64;
+            let exec_time = outcomes[idx].success().map_or(0, |r| r.latency_ms) as i64;
             total_time_ms += exec_time;
 
             let error_message =
@@ -705,7 +737,7 @@ async fn execute_prompt_preview(
                 test_case_id: test_case.id.clone(),
                 input: test_case.input.clone(),
                 reference: test_case.reference.clone(),
-                actual_output: exec_result.output.clone(),
+                actual_output: output.clone(),
                 passed: eval.passed,
                 execution_time_ms: exec_time,
                 error_message,
@@ -731,7 +763,7 @@ pub async fn preview_prompt(
     pool: &SqlitePool,
     api_key_manager: &ApiKeyManager,
     user_id: &str,
-    user_password: &[u8],
+    credential_keys: &CredentialKeys,
     request: PromptPreviewRequest,
     correlation_id: Option<String>,
 ) -> Result<PromptPreviewResponse, MetaOptimizationServiceError> {
@@ -748,7 +780,7 @@ pub async fn preview_prompt(
         pool,
         api_key_manager,
         user_id,
-        user_password,
+        credential_keys,
         &ctx,
         &request.content,
         correlation_id,
@@ -815,7 +847,7 @@ pub async fn compare_prompts(
     pool: &SqlitePool,
     api_key_manager: &ApiKeyManager,
     user_id: &str,
-    user_password: &[u8],
+    credential_keys: &CredentialKeys,
     request: PromptCompareRequest,
     correlation_id: Option<String>,
 ) -> Result<PromptCompareResponse, MetaOptimizationServiceError> {
@@ -844,7 +876,7 @@ pub async fn compare_prompts(
         pool,
         api_key_manager,
         user_id,
-        user_password,
+        credential_keys,
         &ctx,
         &version_a.content,
         correlation_id.clone(),
@@ -861,7 +893,7 @@ pub async fn compare_prompts(
         pool,
         api_key_manager,
         user_id,
-        user_password,
+        credential_keys,
         &ctx,
         &version_b.content,
         correlation_id.clone(),
@@ -978,7 +1010,7 @@ struct ExecutionTargetContext<'a> {
     pool: &'a SqlitePool,
     api_key_manager: &'a ApiKeyManager,
     user_id: &'a str,
-    user_password: &'a [u8],
+    credential_keys: &'a CredentialKeys,
     workspace_id: &'a str,
 }
 
@@ -990,27 +1022,39 @@ async fn build_execution_target_config(
 ) -> Result<ExecutionTargetConfig, MetaOptimizationServiceError> {
     match execution_target_type {
         ExecutionTargetType::Dify => {
-            let prompt_variable =
-                extract_prompt_variable(ctx.pool, ctx.user_id, ctx.workspace_id, test_set_ids)
+            let settings =
+                extract_dify_settings(ctx.pool, ctx.user_id, ctx.workspace_id, test_set_ids)
                     .await?;
-            let credential =
-                CredentialRepo::find_by_user_and_type(ctx.pool, ctx.user_id, CredentialType::Dify)
-                    .await
-                    .map_err(map_credential_repo_error)?;
-            let api_key = decrypt_api_key(ctx.api_key_manager, ctx.user_password, &credential)
+            let credential_id = task_config
+                .execution_credential_id
+                .as_deref()
+                .or(settings.credential_id.as_deref());
+            let credential = CredentialRepo::resolve_personal(
+                ctx.pool,
+                ctx.user_id,
+                CredentialType::Dify,
+                credential_id,
+            )
+            .await
+            .map_err(map_credential_repo_error)?;
+            let api_key = decrypt_api_key(ctx.api_key_manager, ctx.credential_keys, &credential)
                 .map_err(MetaOptimizationServiceError::Encryption)?;
             Ok(ExecutionTargetConfig::Dify {
                 api_url: credential.base_url,
                 workflow_id: String::new(),
-                prompt_variable,
+                prompt_variable: settings.prompt_variable,
                 api_key: Some(api_key),
+                output_selector: settings.output_selector,
+                app_type: settings.app_type,
+                response_mode: settings.response_mode,
             })
         }
         ExecutionTargetType::Generic => {
-            let credential = CredentialRepo::find_by_user_and_type(
+            let credential = CredentialRepo::resolve_personal(
                 ctx.pool,
                 ctx.user_id,
                 CredentialType::GenericLlm,
+                task_config.execution_credential_id.as_deref(),
             )
             .await
             .map_err(map_credential_repo_error)?;
@@ -1019,22 +1063,52 @@ async fn build_execution_target_config(
                 .model_id
                 .clone()
                 .unwrap_or_else(|| "unknown".to_string());
-            let api_key = decrypt_api_key(ctx.api_key_manager, ctx.user_password, &credential)
+            let provider =
+                LlmProviderRepo::resolve(ctx.pool, ctx.user_id, credential.provider.as_deref())
+                    .await
+                    .map_err(map_llm_provider_repo_error)?;
+            let api_key = decrypt_api_key(ctx.api_key_manager, ctx.credential_keys, &credential)
                 .map_err(MetaOptimizationServiceError::Encryption)?;
             Ok(ExecutionTargetConfig::DirectModel {
                 base_url: credential.base_url,
                 model_name,
                 user_prompt_template: "{input}".to_string(),
                 api_key: Some(api_key),
+                provider: Some(provider),
             })
         }
         ExecutionTargetType::Example => Ok(ExecutionTargetConfig::default()),
+        ExecutionTargetType::Command => Ok(task_config.command_target.to_execution_target_config()),
+        ExecutionTargetType::Http => {
+            let mut config = task_config.http_target.to_execution_target_config();
+            if let ExecutionTargetConfig::Http { credentials, .. } = &mut config {
+                for name in task_config.http_target.referenced_credentials() {
+                    let credential_type = CredentialType::parse(&name).ok_or_else(|| {
+                        MetaOptimizationServiceError::InvalidRequest(format!(
+                            "HTTP 执行目标引用了未知凭证类型: {name}"
+                        ))
+                    })?;
+                    let credential = CredentialRepo::find_by_user_and_type(
+                        ctx.pool,
+                        ctx.user_id,
+                        credential_type,
+                    )
+                    .await
+                    .map_err(map_credential_repo_error)?;
+                    let api_key =
+                        decrypt_api_key(ctx.api_key_manager, ctx.credential_keys, &credential)
+                            .map_err(MetaOptimizationServiceError::Encryption)?;
+                    credentials.insert(name, api_key);
+                }
+            }
+            Ok(config)
+        }
     }
 }
 
 fn decrypt_api_key(
     api_key_manager: &ApiKeyManager,
-    user_password: &[u8],
+    credential_keys: &CredentialKeys,
     credential: &crate::infra::db::repositories::CredentialRecord,
 ) -> Result<String, String> {
     let encrypted = EncryptedApiKey {
@@ -1044,7 +1118,7 @@ fn decrypt_api_key(
     };
 
     let api_key_bytes = api_key_manager
-        .decrypt_bytes(user_password, &encrypted)
+        .decrypt_credential(credential_keys, &encrypted)
         .map_err(|_| "解密 API Key 失败".to_string())?;
 
     std::str::from_utf8(api_key_bytes.as_slice())
@@ -1052,29 +1126,26 @@ fn decrypt_api_key(
         .map_err(|_| "解密后的 API Key 非法".to_string())
 }
 
-async fn extract_prompt_variable(
+/// 从测试集的 Dify 配置中读取 (prompt 变量名, 输出选择器)。
+async fn extract_dify_settings(
     pool: &SqlitePool,
     user_id: &str,
     workspace_id: &str,
     test_set_ids: &[String],
-) -> Result<String, MetaOptimizationServiceError> {
+) -> Result<DifyExecutionSettings, MetaOptimizationServiceError> {
     for test_set_id in test_set_ids {
         let test_set = TestSetRepo::find_by_id_scoped(pool, user_id, workspace_id, test_set_id)
             .await
             .map_err(map_test_set_repo_error)?;
-        if let Some(raw) = test_set.dify_config_json {
-            if let Ok(value) = serde_json::from_str::<serde_json::Value>(&raw) {
-                if let Some(variable) = value
-                    .get("targetPromptVariable")
-                    .and_then(|v| v.as_str())
-                    .or_else(|| value.get("target_prompt_variable").and_then(|v| v.as_str()))
-                {
-                    return Ok(variable.to_string());
-                }
-            }
+        if let Some(settings) = test_set
+            .dify_config_json
+            .as_deref()
+            .and_then(parse_dify_execution_settings)
+        {
+            return Ok(settings);
         }
     }
-    Ok("prompt".to_string())
+    Ok(DifyExecutionSettings::default())
 }
 
 #[cfg(test)]
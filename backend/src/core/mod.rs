@@ -1,5 +1,6 @@
 //! 核心业务逻辑模块（7 Trait + IterationEngine）
 
+pub mod cassette;
 pub mod diagnostic_service;
 pub mod diversity_analyzer;
 pub mod evaluator;
@@ -6,6 +6,8 @@ use async_trait::async_trait;
 use crate::core::iteration_engine::orchestrator::{
     record_iteration_completed, record_iteration_started,
 };
+use crate::core::iteration_engine::pause_state::{global_pause_registry, with_pause_controller};
+use crate::core::iteration_engine::shutdown::global_shutdown_coordinator;
 use crate::core::traits::{
     Evaluator, ExecutionTarget, FeedbackAggregator, Optimizer, RuleEngine, TeacherModel,
 };
@@ -20,8 +22,8 @@ use crate::domain::types::{
 
 use super::common::{
     apply_checkpoint, checkpoint_pause_if_requested, clear_user_guidance_from_context,
-    run_tests_and_evaluate, save_checkpoint_after_layer, set_iteration_state, stop_if_requested,
-    sync_max_iterations, validate_ctx_for_run,
+    recover_from_cancellation, run_tests_and_evaluate, save_checkpoint_after_layer,
+    set_iteration_state, stop_if_requested, sync_max_iterations, validate_ctx_for_run,
 };
 use super::{OptimizationEngine, OptimizationEngineError};
 
@@ -109,7 +111,7 @@ impl AlternateOptimizationEngine {
                 primary: PromptCandidate {
                     id: "current".to_string(),
                     content: ctx.current_prompt.clone(),
-                    score: stats.pass_rate,
+                    score: Some(stats.pass_rate),
                     source: CandidateSource::ExpressionRefinement,
                     failure_fingerprints: Vec::new(),
                 },
@@ -197,11 +199,8 @@ impl AlternateOptimizationEngine {
         clear_user_guidance_from_context(ctx);
         Ok(out)
     }
-}
 
-#[async_trait]
-impl OptimizationEngine for AlternateOptimizationEngine {
-    async fn run(
+    async fn run_iterations(
         &self,
         ctx: &mut OptimizationContext,
     ) -> Result<OptimizationResult, OptimizationEngineError> {
@@ -224,7 +223,17 @@ impl OptimizationEngine for AlternateOptimizationEngine {
 
             ctx.iteration = ctx.iteration.saturating_add(1);
             record_iteration_started(ctx);
-            let out = self.run_one_iteration(ctx).await?;
+            let out = match self.run_one_iteration(ctx).await {
+                Ok(out) => out,
+                Err(err) => match recover_from_cancellation(ctx, last.clone(), err).await? {
+                    Some(stopped) => return Ok(stopped),
+                    None => {
+                        // 暂停已恢复：重跑被取消的本轮
+                        ctx.iteration = ctx.iteration.saturating_sub(1);
+                        continue;
+                    }
+                },
+            };
             record_iteration_completed(ctx, out.should_terminate);
             last = Some(out.clone());
             if out.should_terminate {
@@ -249,6 +258,20 @@ impl OptimizationEngine for AlternateOptimizationEngine {
             .try_transition_to(RunControlState::Idle);
         Ok(last)
     }
+}
+
+#[async_trait]
+impl OptimizationEngine for AlternateOptimizationEngine {
+    async fn run(
+        &self,
+        ctx: &mut OptimizationContext,
+    ) -> Result<OptimizationResult, OptimizationEngineError> {
+        // 下游老师模型调用经由该作用域获取任务的取消令牌
+        let controller = global_pause_registry().get_or_create(&ctx.task_id).await;
+        // 登记为运行中任务：停机时由协调器通知其在安全点暂停
+        let _running = global_shutdown_coordinator().register(&ctx.task_id);
+        with_pause_controller(controller, self.run_iterations(ctx)).await
+    }
 
     async fn resume(
         &self,
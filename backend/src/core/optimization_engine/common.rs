@@ -6,24 +6,34 @@ use crate::core::evaluator::EXT_TASK_EVALUATOR_CONFIG;
 use crate::core::evaluator::{SplitFilter, build_evaluations_by_test_case_id, summarize_for_stats};
 use crate::core::iteration_engine::checkpoint::save_checkpoint;
 use crate::core::iteration_engine::events::record_event_async;
-use crate::core::iteration_engine::orchestrator::{IterationEngine, record_evaluation_completed};
+use crate::core::iteration_engine::executor::CaseOutcome;
+use crate::core::iteration_engine::orchestrator::{
+    IterationEngine, read_execution_concurrency_stats, record_evaluation_completed,
+    reset_consecutive_no_improvement, update_consecutive_no_improvement,
+};
 use crate::core::iteration_engine::pause_state::global_pause_registry;
-use crate::core::traits::{Evaluator, ExecutionTarget};
+use crate::core::iteration_engine::shutdown::park_for_shutdown;
+use crate::core::prompt_generator::generate_diversity_candidates;
+use crate::core::traits::{Evaluator, ExecutionTarget, TeacherModel};
 use crate::domain::models::{
     Actor, CandidateSource, Checkpoint, DiversityConfig, EvaluationResult, EventType,
     ExecutionResult, FailureArchiveEntry, IterationState, OptimizationResult,
-    OptimizationTaskConfig, PromptCandidate, TerminationReason, TestCase,
+    OptimizationTaskConfig, PromptCandidate, TerminationReason, TestCase, failure_fingerprint_v1,
 };
 use crate::domain::types::{
-    ArtifactSource, CandidatePrompt, CandidateStats, EXT_BEST_CANDIDATE_INDEX,
-    EXT_BEST_CANDIDATE_PROMPT, EXT_BEST_CANDIDATE_STATS, EXT_CANDIDATE_RANKING,
-    EXT_CURRENT_PROMPT_STATS, EXT_DIVERSITY_ANALYSIS, EXT_EVALUATIONS_BY_TEST_CASE_ID,
+    ArtifactSource, CandidatePrompt, CandidateStats, DiversityInjectionRecord,
+    EXT_BEST_CANDIDATE_INDEX, EXT_BEST_CANDIDATE_PROMPT, EXT_BEST_CANDIDATE_STATS,
+    EXT_CANDIDATE_RANKING, EXT_CONSECUTIVE_NO_IMPROVEMENT, EXT_CURRENT_PROMPT_STATS,
+    EXT_DIVERSITY_ANALYSIS, EXT_DIVERSITY_INJECTIONS, EXT_EVALUATIONS_BY_TEST_CASE_ID,
     EXT_FAILURE_ARCHIVE, EXT_PREV_ITERATION_STATE, EXT_TASK_MODE, EXT_USER_GUIDANCE,
-    IterationArtifacts, OptimizationContext, PatternHypothesis, RunControlState,
+    IterationArtifacts, OptimizationContext, OscillationAction, PatternHypothesis, RunControlState,
 };
 use crate::infra::db::repositories::{IterationRepo, IterationRepoError};
 use crate::shared::ws::chrono_timestamp;
-use crate::shared::ws::{EVT_GUIDANCE_APPLIED, GuidanceAppliedPayload, WsMessage};
+use crate::shared::ws::{
+    EVT_GUIDANCE_APPLIED, EVT_GUIDANCE_REQUESTED, GuidanceAppliedPayload, GuidanceRequestedPayload,
+    WsMessage,
+};
 use crate::shared::ws_bus::global_ws_bus;
 use serde_json::json;
 use tokio::time::{Duration, sleep, timeout};
@@ -363,6 +373,8 @@ pub async fn checkpoint_pause_if_requested(
                 "Checkpoint 保存失败（已降级，继续执行）"
             );
         }
+        // 停机期间：标记任务并通知协调器，随后保持暂停直至进程退出
+        park_for_shutdown(&ctx.task_id).await;
         controller.wait_for_resume().await;
         if let Some(artifacts) = controller.get_artifacts().await {
             apply_artifacts_to_context(ctx, &artifacts);
@@ -434,7 +446,7 @@ pub async fn stop_if_requested(
             primary: PromptCandidate {
                 id: "current".to_string(),
                 content: ctx.current_prompt.clone(),
-                score: 0.0,
+                score: None,
                 source: CandidateSource::InitialGeneration,
                 failure_fingerprints: Vec::new(),
             },
@@ -450,6 +462,212 @@ pub async fn stop_if_requested(
     Ok(Some(result))
 }
 
+/// 处理暂停 / 终止取消在途请求导致的本轮中途失败。
+///
+/// - 非取消导致的失败：原样返回错误
+/// - 已请求终止：返回终止结果
+/// - 已请求暂停：在此安全点暂停，恢复后返回 `Ok(None)`，由调用方重跑本轮
+pub async fn recover_from_cancellation(
+    ctx: &mut OptimizationContext,
+    last: Option<OptimizationResult>,
+    err: OptimizationEngineError,
+) -> Result<Option<OptimizationResult>, OptimizationEngineError> {
+    let controller = global_pause_registry().get_or_create(&ctx.task_id).await;
+    if !controller.cancellation_token().is_cancelled() {
+        return Err(err);
+    }
+    tracing::info!(
+        task_id = %ctx.task_id,
+        iteration = ctx.iteration,
+        error = %err,
+        "本轮在途请求已取消"
+    );
+
+    if let Some(stopped) = stop_if_requested(ctx, last).await? {
+        return Ok(Some(stopped));
+    }
+    if checkpoint_pause_if_requested(ctx).await? {
+        return Ok(None);
+    }
+    Err(err)
+}
+
+/// 停滞/震荡处理：`RecommendedAction::InjectDiversity` 触发后，按 `config.oscillation.action` 执行。
+///
+/// - `DiversityInject`：生成多样性候选，采用第一个作为下一轮 current_prompt，其余放入 alternatives
+/// - `HumanIntervention`：请求在下一个安全点暂停，并推送 `guidance:requested` 提示用户引导
+/// - `Stop`：不处理（由 Optimizer 的震荡检测产出终止信号）
+///
+/// 返回是否执行了处理；处理后连续无提升计数归零，避免每轮重复触发。
+pub async fn handle_stagnation(
+    ctx: &mut OptimizationContext,
+    teacher_model: &dyn TeacherModel,
+    out: &mut OptimizationResult,
+) -> Result<bool, OptimizationEngineError> {
+    match ctx.config.oscillation.action {
+        OscillationAction::DiversityInject => inject_diversity(ctx, teacher_model, out).await,
+        OscillationAction::HumanIntervention => {
+            request_human_guidance(ctx, out).await;
+            Ok(true)
+        }
+        OscillationAction::Stop => Ok(false),
+    }
+}
+
+async fn inject_diversity(
+    ctx: &mut OptimizationContext,
+    teacher_model: &dyn TeacherModel,
+    out: &mut OptimizationResult,
+) -> Result<bool, OptimizationEngineError> {
+    let candidates = generate_diversity_candidates(ctx, Some(teacher_model)).await?;
+    let mut candidates = candidates.into_iter();
+    let Some(chosen) = candidates.next() else {
+        out.extra.insert(
+            "diversity_injection".to_string(),
+            json!({ "status": "no_candidate" }),
+        );
+        tracing::warn!(
+            task_id = %ctx.task_id,
+            iteration = ctx.iteration,
+            "多样性注入未产出可用候选（全部命中失败档案或重复）"
+        );
+        return Ok(false);
+    };
+
+    let score_before = out.primary.score.unwrap_or_default();
+    let record = DiversityInjectionRecord {
+        iteration: ctx.iteration,
+        strategy: chosen.strategy.as_str().to_string(),
+        via_teacher: chosen.via_teacher,
+        fingerprint: chosen.fingerprint.clone(),
+        score_before,
+        score_after: None,
+    };
+
+    // 探索候选尚未评估：score 留空，待下一轮评估后由 `settle_diversity_injection` 回填效果。
+    let candidate_id =
+        |index: usize, strategy: &str| format!("diversity:{}:{index}:{strategy}", ctx.iteration);
+    out.alternatives = candidates
+        .enumerate()
+        .map(|(index, c)| PromptCandidate {
+            id: candidate_id(index + 1, c.strategy.as_str()),
+            content: c.content,
+            score: None,
+            source: c.source,
+            failure_fingerprints: vec![c.fingerprint],
+        })
+        .collect();
+    out.extra.insert(
+        "diversity_injection".to_string(),
+        json!({
+            "status": "injected",
+            "strategy": record.strategy,
+            "via_teacher": record.via_teacher,
+            "alternatives": out.alternatives.len(),
+        }),
+    );
+    out.primary = PromptCandidate {
+        id: candidate_id(0, chosen.strategy.as_str()),
+        content: chosen.content.clone(),
+        score: None,
+        source: chosen.source,
+        failure_fingerprints: vec![chosen.fingerprint],
+    };
+    ctx.current_prompt = chosen.content;
+
+    let mut records = read_diversity_injections(ctx);
+    records.push(record);
+    ctx.extensions.insert(
+        EXT_DIVERSITY_INJECTIONS.to_string(),
+        serde_json::to_value(&records).unwrap_or(serde_json::Value::Null),
+    );
+    reset_consecutive_no_improvement(ctx);
+
+    tracing::info!(
+        task_id = %ctx.task_id,
+        iteration = ctx.iteration,
+        strategy = chosen.strategy.as_str(),
+        "已注入多样性候选作为下一轮 current_prompt"
+    );
+    Ok(true)
+}
+
+async fn request_human_guidance(ctx: &mut OptimizationContext, out: &mut OptimizationResult) {
+    let consecutive = ctx
+        .extensions
+        .get(EXT_CONSECUTIVE_NO_IMPROVEMENT)
+        .and_then(|v| v.as_u64())
+        .and_then(|n| u32::try_from(n).ok())
+        .unwrap_or(0);
+    let correlation_id = read_optional_string(ctx, "correlation_id")
+        .unwrap_or_else(|| format!("guidance-requested-{}", ctx.task_id));
+
+    let controller = global_pause_registry().get_or_create(&ctx.task_id).await;
+    controller.request_pause(&correlation_id, "system").await;
+
+    let payload = GuidanceRequestedPayload {
+        task_id: ctx.task_id.clone(),
+        iteration: ctx.iteration,
+        reason: "no_improvement_consecutive".to_string(),
+        consecutive_no_improvement: consecutive,
+        requested_at: chrono_timestamp(),
+    };
+    let msg = WsMessage::new(EVT_GUIDANCE_REQUESTED, payload, correlation_id);
+    if let Ok(text) = serde_json::to_string(&msg) {
+        global_ws_bus().publish(text);
+    }
+
+    out.extra.insert(
+        "human_intervention".to_string(),
+        json!({
+            "status": "pause_requested",
+            "consecutive_no_improvement": consecutive,
+        }),
+    );
+    reset_consecutive_no_improvement(ctx);
+
+    tracing::info!(
+        task_id = %ctx.task_id,
+        iteration = ctx.iteration,
+        consecutive_no_improvement = consecutive,
+        "优化停滞：已请求暂停并等待用户引导"
+    );
+}
+
+fn read_diversity_injections(ctx: &OptimizationContext) -> Vec<DiversityInjectionRecord> {
+    ctx.extensions
+        .get(EXT_DIVERSITY_INJECTIONS)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// 回填最近一次多样性注入的效果（仅当 current_prompt 仍是该注入候选时）。
+fn settle_diversity_injection(ctx: &mut OptimizationContext, stats: CandidateStats) {
+    let mut records = read_diversity_injections(ctx);
+    let Some(last) = records.last_mut() else {
+        return;
+    };
+    if last.score_after.is_some() || last.fingerprint != failure_fingerprint_v1(&ctx.current_prompt)
+    {
+        return;
+    }
+    // 与 Optimizer 的 primary.score 口径一致：通过率优先，其次均分。
+    let score_after = stats.combined_score();
+    last.score_after = Some(score_after);
+    tracing::info!(
+        task_id = %ctx.task_id,
+        iteration = ctx.iteration,
+        strategy = %last.strategy,
+        score_before = last.score_before,
+        score_after = score_after,
+        "多样性注入效果已回填"
+    );
+    ctx.extensions.insert(
+        EXT_DIVERSITY_INJECTIONS.to_string(),
+        serde_json::to_value(&records).unwrap_or(serde_json::Value::Null),
+    );
+}
+
 pub async fn run_tests_and_evaluate(
     ctx: &mut OptimizationContext,
     execution_target: Arc<dyn ExecutionTarget>,
@@ -461,18 +679,19 @@ pub async fn run_tests_and_evaluate(
     let prompt = ctx.current_prompt.clone();
     let batch = ctx.test_cases.clone();
     let engine = IterationEngine::new(execution_target);
-    let exec_results = engine
+    let outcomes = engine
         .run_tests(ctx, &prompt, &batch, task_config)
         .await
         .map_err(|err| {
             record_error_event(ctx, "run_tests", &err.to_string());
             OptimizationEngineError::from(err)
         })?;
-
-    let pairs = IterationEngine::build_evaluation_pairs(&batch, &exec_results).map_err(|err| {
-        record_error_event(ctx, "build_evaluation_pairs", &err.to_string());
-        OptimizationEngineError::from(err)
-    })?;
+    // 执行失败的用例不参与执行追溯与多样性分析（其评估结果由 evaluate_outcomes 合成）。
+    let exec_results = outcomes
+        .iter()
+        .filter_map(CaseOutcome::success)
+        .cloned()
+        .collect::<Vec<_>>();
 
     set_iteration_state(ctx, IterationState::Evaluating);
     // DefaultEvaluator 依赖 task 级 evaluator_config（写入方约定为编排层）。
@@ -484,10 +703,13 @@ pub async fn run_tests_and_evaluate(
     ctx.extensions
         .insert(EXT_TASK_EVALUATOR_CONFIG.to_string(), evaluator_cfg_value);
 
-    let evaluations = evaluator.evaluate_batch(ctx, &pairs).await.map_err(|err| {
-        record_error_event(ctx, "evaluate_batch", &err.to_string());
-        OptimizationEngineError::from(err)
-    })?;
+    let (pairs, evaluations) =
+        IterationEngine::evaluate_outcomes(evaluator.as_ref(), ctx, &batch, &outcomes)
+            .await
+            .map_err(|err| {
+                record_error_event(ctx, "evaluate_batch", &err.to_string());
+                OptimizationEngineError::from(err)
+            })?;
 
     let evaluations_by_id = build_evaluations_by_test_case_id(&pairs, &evaluations)?;
     let executions_by_id: HashMap<String, _> = exec_results
@@ -518,7 +740,17 @@ pub async fn run_tests_and_evaluate(
         mean_score: stats.mean_score,
     };
 
+    // 连续无提升计数：与上一轮 current_prompt 的统计比较（首轮视为有提升）。
+    let prev_stats = ctx
+        .extensions
+        .get(EXT_CURRENT_PROMPT_STATS)
+        .and_then(|v| serde_json::from_value::<CandidateStats>(v.clone()).ok());
+    let improved = prev_stats.is_none_or(|prev| candidate_stats.is_better_than(&prev));
+    update_consecutive_no_improvement(ctx, improved);
+    settle_diversity_injection(ctx, candidate_stats);
+
     record_evaluation_completed(ctx, stats.pass_rate, stats.total_count, stats.passed_count);
+    spawn_persist_effective_concurrency(ctx);
 
     ensure_task_mode(ctx).await;
     if should_compute_diversity(ctx, task_config) {
@@ -637,44 +869,97 @@ async fn persist_diversity_analysis(
         return;
     };
 
-    let mut delay = Duration::from_millis(200);
-    let max_delay = Duration::from_secs(5);
-    let max_attempts = 6;
-    for attempt in 0..max_attempts {
-        match IterationRepo::update_diversity_analysis_for_round(
+    match retry_until_iteration_ready(|| {
+        IterationRepo::update_diversity_analysis_for_round(
             &pool,
             &context.task_id,
             context.iteration,
             analysis,
         )
+    })
+    .await
+    {
+        Ok(true) => {}
+        Ok(false) => tracing::warn!(
+            correlation_id = ?context.correlation_id,
+            task_id = %context.task_id,
+            iteration = context.iteration,
+            attempts = ITERATION_WRITE_MAX_ATTEMPTS,
+            "迭代产物仍未就绪，多样性分析写入放弃"
+        ),
+        Err(err) => tracing::warn!(
+            correlation_id = ?context.correlation_id,
+            task_id = %context.task_id,
+            iteration = context.iteration,
+            error = %err,
+            "写入多样性分析产物失败"
+        ),
+    }
+}
+
+/// 将本轮有效并发写入迭代历史（仅并行执行过的任务才有统计）。
+fn spawn_persist_effective_concurrency(ctx: &OptimizationContext) {
+    let Some(stats) = read_execution_concurrency_stats(ctx) else {
+        return;
+    };
+    let context = build_diversity_context(ctx);
+    tokio::spawn(async move {
+        let Some(pool) = crate::infra::db::pool::global_db_pool() else {
+            return;
+        };
+        match retry_until_iteration_ready(|| {
+            IterationRepo::update_effective_concurrency_for_round(
+                &pool,
+                &context.task_id,
+                context.iteration,
+                stats.effective_concurrency,
+            )
+        })
         .await
         {
-            Ok(_) => return,
-            Err(IterationRepoError::NotFound) if attempt + 1 < max_attempts => {
+            Ok(true) => {}
+            Ok(false) => tracing::warn!(
+                correlation_id = ?context.correlation_id,
+                task_id = %context.task_id,
+                iteration = context.iteration,
+                attempts = ITERATION_WRITE_MAX_ATTEMPTS,
+                "迭代记录仍未就绪，有效并发写入放弃"
+            ),
+            Err(err) => tracing::warn!(
+                correlation_id = ?context.correlation_id,
+                task_id = %context.task_id,
+                iteration = context.iteration,
+                error = %err,
+                "写入有效并发失败"
+            ),
+        }
+    });
+}
+
+const ITERATION_WRITE_MAX_ATTEMPTS: u32 = 6;
+
+/// 迭代记录可能晚于本轮评估落库：写入返回 NotFound 时指数退避重试。
+///
+/// 返回 `Ok(false)` 表示重试耗尽仍未找到迭代记录。
+async fn retry_until_iteration_ready<F, Fut>(mut write: F) -> Result<bool, IterationRepoError>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<(), IterationRepoError>>,
+{
+    let mut delay = Duration::from_millis(200);
+    let max_delay = Duration::from_secs(5);
+    for attempt in 0..ITERATION_WRITE_MAX_ATTEMPTS {
+        match write().await {
+            Ok(()) => return Ok(true),
+            Err(IterationRepoError::NotFound) if attempt + 1 < ITERATION_WRITE_MAX_ATTEMPTS => {
                 sleep(delay).await;
                 delay = delay.saturating_mul(2).min(max_delay);
             }
             Err(IterationRepoError::NotFound) => break,
-            Err(err) => {
-                tracing::warn!(
-                    correlation_id = ?context.correlation_id,
-                    task_id = %context.task_id,
-                    iteration = context.iteration,
-                    error = %err,
-                    "写入多样性分析产物失败"
-                );
-                return;
-            }
+            Err(err) => return Err(err),
         }
     }
-
-    tracing::warn!(
-        correlation_id = ?context.correlation_id,
-        task_id = %context.task_id,
-        iteration = context.iteration,
-        attempts = max_attempts,
-        "迭代产物仍未就绪，多样性分析写入放弃"
-    );
+    Ok(false)
 }
 
 async fn compute_diversity_analysis(
@@ -948,7 +1233,7 @@ mod diversity_gate_tests {
         let mut cfg = OptimizationTaskConfig::default();
         cfg.diversity_config.enabled = true;
         let ctx = base_ctx();
-        let exec_results = vec![ExecutionResult {
+        let exec_results = [ExecutionResult {
             test_case_id: "case-1".to_string(),
             output: "only one".to_string(),
             latency_ms: 0,
@@ -968,3 +1253,159 @@ mod diversity_gate_tests {
         assert!(analysis.warnings.is_empty());
     }
 }
+
+#[cfg(test)]
+mod stagnation_tests {
+    use super::*;
+    use crate::core::teacher_model::ExampleTeacherModel;
+    use crate::domain::models::RuleSystem;
+    use crate::domain::types::{ExecutionTargetConfig, OptimizationConfig};
+
+    const PROMPT: &str = "你是一个严格的助手。\n- 输出 JSON\n- 字段 name 必填\n- 不要输出解释";
+
+    fn base_ctx(task_id: &str, action: OscillationAction) -> OptimizationContext {
+        let mut config = OptimizationConfig::default();
+        config.oscillation.action = action;
+        OptimizationContext {
+            task_id: task_id.to_string(),
+            execution_target_config: ExecutionTargetConfig::default(),
+            current_prompt: PROMPT.to_string(),
+            rule_system: RuleSystem {
+                rules: vec![],
+                conflict_resolution_log: vec![],
+                merge_log: vec![],
+                coverage_map: HashMap::new(),
+                version: 1,
+            },
+            iteration: 4,
+            state: IterationState::Optimizing,
+            run_control_state: Default::default(),
+            test_cases: vec![],
+            config,
+            checkpoints: vec![],
+            extensions: HashMap::from([(EXT_CONSECUTIVE_NO_IMPROVEMENT.to_string(), json!(3))]),
+        }
+    }
+
+    fn base_out(ctx: &OptimizationContext) -> OptimizationResult {
+        OptimizationResult {
+            primary: PromptCandidate {
+                id: "current".to_string(),
+                content: ctx.current_prompt.clone(),
+                score: Some(0.4),
+                source: CandidateSource::DiversityInjection,
+                failure_fingerprints: Vec::new(),
+            },
+            alternatives: Vec::new(),
+            should_terminate: false,
+            termination_reason: None,
+            iteration: ctx.iteration,
+            improvement_summary: None,
+            extra: HashMap::new(),
+        }
+    }
+
+    fn consecutive(ctx: &OptimizationContext) -> u64 {
+        ctx.extensions
+            .get(EXT_CONSECUTIVE_NO_IMPROVEMENT)
+            .and_then(|v| v.as_u64())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn diversity_inject_adopts_candidate_and_records_effectiveness() {
+        let mut ctx = base_ctx("stagnation-inject", OscillationAction::DiversityInject);
+        let mut out = base_out(&ctx);
+        let teacher = ExampleTeacherModel::new_default();
+
+        let handled = handle_stagnation(&mut ctx, &teacher, &mut out)
+            .await
+            .unwrap();
+        assert!(handled);
+        assert_ne!(ctx.current_prompt, PROMPT);
+        assert_eq!(out.primary.content, ctx.current_prompt);
+        assert_eq!(out.primary.source, CandidateSource::DiversityInjection);
+        assert!(out.primary.id.starts_with("diversity:4:"));
+        assert_eq!(out.primary.score, None);
+        assert!(!out.alternatives.is_empty());
+        assert!(out.alternatives.iter().all(|c| c.score.is_none()));
+        let mut ids = std::iter::once(&out.primary)
+            .chain(&out.alternatives)
+            .map(|c| c.id.as_str())
+            .collect::<Vec<_>>();
+        let total = ids.len();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), total, "候选 id 不应重复");
+        assert_eq!(consecutive(&ctx), 0);
+
+        let records = read_diversity_injections(&ctx);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].iteration, 4);
+        assert_eq!(records[0].score_after, None);
+
+        settle_diversity_injection(
+            &mut ctx,
+            CandidateStats {
+                pass_rate: 1.0,
+                mean_score: 1.0,
+            },
+        );
+        let records = read_diversity_injections(&ctx);
+        assert_eq!(records[0].score_after, Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn settle_ignores_prompt_that_is_no_longer_the_injected_candidate() {
+        let mut ctx = base_ctx("stagnation-settle", OscillationAction::DiversityInject);
+        let mut out = base_out(&ctx);
+        let teacher = ExampleTeacherModel::new_default();
+        handle_stagnation(&mut ctx, &teacher, &mut out)
+            .await
+            .unwrap();
+
+        ctx.current_prompt = "manually edited".to_string();
+        settle_diversity_injection(
+            &mut ctx,
+            CandidateStats {
+                pass_rate: 1.0,
+                mean_score: 1.0,
+            },
+        );
+        assert_eq!(read_diversity_injections(&ctx)[0].score_after, None);
+    }
+
+    #[tokio::test]
+    async fn human_intervention_requests_pause_instead_of_injecting() {
+        let task_id = "stagnation-human";
+        let mut ctx = base_ctx(task_id, OscillationAction::HumanIntervention);
+        let mut out = base_out(&ctx);
+        let teacher = ExampleTeacherModel::new_default();
+
+        let handled = handle_stagnation(&mut ctx, &teacher, &mut out)
+            .await
+            .unwrap();
+        assert!(handled);
+        assert_eq!(ctx.current_prompt, PROMPT);
+        assert!(out.extra.contains_key("human_intervention"));
+        assert_eq!(consecutive(&ctx), 0);
+
+        let controller = global_pause_registry().get_or_create(task_id).await;
+        assert!(controller.is_pause_requested());
+        controller.reset().await;
+    }
+
+    #[tokio::test]
+    async fn stop_action_is_left_to_optimizer() {
+        let mut ctx = base_ctx("stagnation-stop", OscillationAction::Stop);
+        let mut out = base_out(&ctx);
+        let teacher = ExampleTeacherModel::new_default();
+
+        let handled = handle_stagnation(&mut ctx, &teacher, &mut out)
+            .await
+            .unwrap();
+        assert!(!handled);
+        assert_eq!(ctx.current_prompt, PROMPT);
+        assert_eq!(consecutive(&ctx), 3);
+    }
+}
@@ -6,6 +6,8 @@ use async_trait::async_trait;
 use crate::core::iteration_engine::orchestrator::{
     record_iteration_completed, record_iteration_started,
 };
+use crate::core::iteration_engine::pause_state::{global_pause_registry, with_pause_controller};
+use crate::core::iteration_engine::shutdown::global_shutdown_coordinator;
 use crate::core::traits::{
     Evaluator, ExecutionTarget, FeedbackAggregator, Optimizer, PromptGenerator, RuleEngine,
     TeacherModel,
@@ -20,8 +22,9 @@ use crate::domain::types::{
 
 use super::common::{
     apply_checkpoint, checkpoint_pause_if_requested, clear_user_guidance_from_context,
-    run_tests_and_evaluate, save_checkpoint_after_layer, set_iteration_state, stop_if_requested,
-    sync_max_iterations, validate_ctx_for_run,
+    handle_stagnation, recover_from_cancellation, run_tests_and_evaluate,
+    save_checkpoint_after_layer, set_iteration_state, stop_if_requested, sync_max_iterations,
+    validate_ctx_for_run,
 };
 use super::{OptimizationEngine, OptimizationEngineError};
 
@@ -138,7 +141,7 @@ impl DefaultOptimizationEngine {
         }
 
         set_iteration_state(ctx, IterationState::Optimizing);
-        let out = self
+        let mut out = self
             .optimizer
             .optimize_step(ctx, &unified_reflection)
             .await?;
@@ -163,14 +166,26 @@ impl DefaultOptimizationEngine {
             }
         }
 
+        // 停滞/震荡：生成探索型候选或请求人工引导（人工介入时在此安全点暂停）。
+        if !out.should_terminate
+            && matches!(
+                unified_reflection.recommended_action,
+                RecommendedAction::InjectDiversity
+            )
+            && handle_stagnation(ctx, self.teacher_model.as_ref(), &mut out).await?
+        {
+            save_checkpoint_after_layer(ctx).await;
+            checkpoint_pause_if_requested(ctx).await?;
+            if let Some(stopped) = stop_if_requested(ctx, Some(out.clone())).await? {
+                return Ok(stopped);
+            }
+        }
+
         clear_user_guidance_from_context(ctx);
         Ok(out)
     }
-}
 
-#[async_trait]
-impl OptimizationEngine for DefaultOptimizationEngine {
-    async fn run(
+    async fn run_iterations(
         &self,
         ctx: &mut OptimizationContext,
     ) -> Result<OptimizationResult, OptimizationEngineError> {
@@ -193,7 +208,17 @@ impl OptimizationEngine for DefaultOptimizationEngine {
 
             ctx.iteration = ctx.iteration.saturating_add(1);
             record_iteration_started(ctx);
-            let out = self.run_one_iteration(ctx).await?;
+            let out = match self.run_one_iteration(ctx).await {
+                Ok(out) => out,
+                Err(err) => match recover_from_cancellation(ctx, last.clone(), err).await? {
+                    Some(stopped) => return Ok(stopped),
+                    None => {
+                        // 暂停已恢复：重跑被取消的本轮
+                        ctx.iteration = ctx.iteration.saturating_sub(1);
+                        continue;
+                    }
+                },
+            };
             record_iteration_completed(ctx, out.should_terminate);
             last = Some(out.clone());
             if out.should_terminate {
@@ -218,6 +243,20 @@ impl OptimizationEngine for DefaultOptimizationEngine {
             .try_transition_to(RunControlState::Idle);
         Ok(last)
     }
+}
+
+#[async_trait]
+impl OptimizationEngine for DefaultOptimizationEngine {
+    async fn run(
+        &self,
+        ctx: &mut OptimizationContext,
+    ) -> Result<OptimizationResult, OptimizationEngineError> {
+        // 下游老师模型调用经由该作用域获取任务的取消令牌
+        let controller = global_pause_registry().get_or_create(&ctx.task_id).await;
+        // 登记为运行中任务：停机时由协调器通知其在安全点暂停
+        let _running = global_shutdown_coordinator().register(&ctx.task_id);
+        with_pause_controller(controller, self.run_iterations(ctx)).await
+    }
 
     async fn resume(
         &self,
@@ -42,18 +42,21 @@ pub trait OptimizationEngine: Send + Sync {
 }
 
 /// OptimizationEngine 工厂：算法替换的**单一入口点**（feature gate）。
+///
+/// 执行目标的出站网络配置无效时返回 `Internal` 错误。
 pub fn create_optimization_engine(
     execution_target_type: ExecutionTargetType,
     task_config: OptimizationTaskConfig,
-) -> Arc<dyn OptimizationEngine> {
+) -> Result<Arc<dyn OptimizationEngine>, OptimizationEngineError> {
     // 默认使用确定性 TeacherModel，保证本地/CI 可复现（不出网）。
     let teacher_model = create_teacher_model(TeacherModelType::Example);
-    let execution_target = create_execution_target(execution_target_type);
+    let execution_target = create_execution_target(execution_target_type)
+        .map_err(|err| OptimizationEngineError::Internal(format!("创建执行目标失败: {err}")))?;
     let evaluator =
         create_evaluator_for_task_config(&task_config, Some(Arc::clone(&teacher_model)));
 
     #[cfg(feature = "alt-optimization-engine")]
-    return Arc::new(alternate_impl::AlternateOptimizationEngine::new(
+    return Ok(Arc::new(alternate_impl::AlternateOptimizationEngine::new(
         alternate_impl::AlternateOptimizationEngineParts {
             rule_engine: Arc::new(DefaultRuleEngine::new()),
             evaluator,
@@ -63,12 +66,12 @@ pub fn create_optimization_engine(
             execution_target,
             task_config,
         },
-    ));
+    )));
 
     #[cfg(not(feature = "alt-optimization-engine"))]
     {
         use crate::core::prompt_generator::DefaultPromptGenerator;
-        Arc::new(default_impl::DefaultOptimizationEngine::new(
+        Ok(Arc::new(default_impl::DefaultOptimizationEngine::new(
             default_impl::DefaultOptimizationEngineParts {
                 rule_engine: Arc::new(DefaultRuleEngine::new()),
                 prompt_generator: Arc::new(DefaultPromptGenerator::new()),
@@ -79,6 +82,6 @@ pub fn create_optimization_engine(
                 execution_target,
                 task_config,
             },
-        ))
+        )))
     }
 }
@@ -87,11 +87,12 @@ impl crate::core::traits::Optimizer for DefaultOptimizer {
             ctx.current_prompt.clone()
         };
 
-        let primary_score = combined_score(if best_better {
+        let primary_score = if best_better {
             best_stats
         } else {
             current_stats
-        });
+        }
+        .combined_score();
 
         let improvement_summary = if best_better {
             Some(format!(
@@ -138,7 +139,7 @@ impl crate::core::traits::Optimizer for DefaultOptimizer {
                     "current".to_string()
                 },
                 content: primary_content,
-                score: primary_score,
+                score: Some(primary_score),
                 source: primary_source,
                 failure_fingerprints: vec![format!(
                     "primary_failure_type:{:?}",
@@ -168,7 +169,11 @@ impl crate::core::traits::Optimizer for DefaultOptimizer {
         }
 
         // 2) OscillationDetected（仅当 action=Stop；否则由编排层继续推进）
-        let scores = history.iter().map(|r| r.primary.score).collect::<Vec<_>>();
+        // 未评估的轮次（如多样性注入的探索候选）不参与震荡判定
+        let scores = history
+            .iter()
+            .filter_map(|r| r.primary.score)
+            .collect::<Vec<_>>();
         if oscillation_detected_scores(ctx, &scores) {
             return Some(TerminationReason::OscillationDetected);
         }
@@ -229,20 +234,6 @@ fn approx_eq(a: f64, b: f64) -> bool {
     (a - b).abs() <= METRIC_EPS
 }
 
-fn combined_score(stats: CandidateStats) -> f64 {
-    // 让 score 与“通过率优先，其次均分”保持一致的单调性。
-    let pr = clamp_01(stats.pass_rate);
-    let ms = clamp_01(stats.mean_score);
-    (pr * 0.7 + ms * 0.3).clamp(0.0, 1.0)
-}
-
-fn clamp_01(v: f64) -> f64 {
-    if !v.is_finite() {
-        return 0.0;
-    }
-    v.clamp(0.0, 1.0)
-}
-
 fn candidate_source_for_action(action: &RecommendedAction) -> CandidateSource {
     match action {
         RecommendedAction::UpdateRulesAndRegenerate => CandidateSource::RuleSystemUpdate,
@@ -537,7 +528,7 @@ mod tests {
                 primary: PromptCandidate {
                     id: "a".to_string(),
                     content: "p".to_string(),
-                    score: 0.5,
+                    score: Some(0.5),
                     source: CandidateSource::ExpressionRefinement,
                     failure_fingerprints: vec![],
                 },
@@ -552,7 +543,7 @@ mod tests {
                 primary: PromptCandidate {
                     id: "b".to_string(),
                     content: "p".to_string(),
-                    score: 0.5,
+                    score: Some(0.5),
                     source: CandidateSource::ExpressionRefinement,
                     failure_fingerprints: vec![],
                 },
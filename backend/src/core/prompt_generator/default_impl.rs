@@ -150,13 +150,13 @@ fn reject_duplicate_candidate(
     Ok(())
 }
 
-struct RuleClassification<'a> {
-    success: Vec<&'a Rule>,
-    failure: Vec<&'a Rule>,
-    all_passed: Vec<&'a Rule>,
+pub(super) struct RuleClassification<'a> {
+    pub(super) success: Vec<&'a Rule>,
+    pub(super) failure: Vec<&'a Rule>,
+    pub(super) all_passed: Vec<&'a Rule>,
 }
 
-fn classify_rules(rules: &[Rule]) -> Result<RuleClassification<'_>, GeneratorError> {
+pub(super) fn classify_rules(rules: &[Rule]) -> Result<RuleClassification<'_>, GeneratorError> {
     let mut success = Vec::new();
     let mut failure = Vec::new();
     let mut all_passed = Vec::new();
@@ -240,7 +240,7 @@ fn read_required_string(
     Ok(s.to_string())
 }
 
-fn read_optional_user_guidance(ctx: &OptimizationContext) -> Option<String> {
+pub(super) fn read_optional_user_guidance(ctx: &OptimizationContext) -> Option<String> {
     ctx.extensions
         .get(EXT_USER_GUIDANCE)
         .and_then(|v| serde_json::from_value::<UserGuidance>(v.clone()).ok())
@@ -259,7 +259,7 @@ fn validate_candidate_index(candidate_index: u32) -> Result<(), GeneratorError>
     Ok(())
 }
 
-fn build_keep_section(success_rules: &[&Rule]) -> String {
+pub(super) fn build_keep_section(success_rules: &[&Rule]) -> String {
     if success_rules.is_empty() {
         return "（无 success 规律可保留；请保持现有 Prompt 的已知正确行为，并避免引入不必要改动。）".to_string();
     }
@@ -304,7 +304,7 @@ fn build_keep_section(success_rules: &[&Rule]) -> String {
     lines.join("\n")
 }
 
-fn build_fix_section(failure_rules: &[&Rule]) -> String {
+pub(super) fn build_fix_section(failure_rules: &[&Rule]) -> String {
     use crate::domain::models::RuleIR;
 
     if failure_rules.is_empty() {
@@ -410,7 +410,7 @@ fn failure_dimension(rule: &Rule) -> String {
     "unknown".to_string()
 }
 
-fn render_candidate_variant(
+pub(super) fn render_candidate_variant(
     candidate_index: u32,
     keep: &str,
     fix: &str,
@@ -738,7 +738,7 @@ fn build_initial_prompt(
     format!("{}{}", base, guidance_section)
 }
 
-fn summarize_test_cases(test_cases: &[TestCase]) -> String {
+pub(super) fn summarize_test_cases(test_cases: &[TestCase]) -> String {
     use crate::domain::models::TaskReference;
 
     let mut constraints: BTreeSet<String> = BTreeSet::new();
@@ -0,0 +1,437 @@
+//! 多样性注入（Diversity Injection）
+//!
+//! 当优化停滞/震荡（`RecommendedAction::InjectDiversity`）时，生成与当前 Prompt
+//! “真正不同”的探索型候选，而不是在同一组模板变体里打转：
+//! - 结构改写（优先老师模型；不可用时回退确定性模板/清单化改写）
+//! - 替换推理脚手架（按轮次轮换）
+//! - 丢弃/重排指令条目（规则行）
+//!
+//! 所有候选都会与 `current_prompt` 及 `EXT_FAILURE_ARCHIVE` 的指纹比对，命中即丢弃。
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::core::prompt_generator::GeneratorError;
+use crate::core::prompt_generator::TEMPLATE_VARIANT_COUNT;
+use crate::core::prompt_generator::default_impl::{
+    build_fix_section, build_keep_section, classify_rules, read_optional_user_guidance,
+    render_candidate_variant, summarize_test_cases,
+};
+use crate::core::traits::TeacherModel;
+use crate::domain::models::{CandidateSource, FailureArchiveEntry, failure_fingerprint_v1};
+use crate::domain::types::{EXT_FAILURE_ARCHIVE, OptimizationContext};
+
+/// 多样性注入策略（按固定顺序尝试，保证可复现）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiversityStrategy {
+    /// 改写整体结构（老师模型改写或确定性重组）
+    RephraseStructure,
+    /// 替换推理脚手架
+    AlternateReasoningScaffold,
+    /// 丢弃部分指令条目
+    DropRules,
+    /// 重排指令条目
+    ReorderRules,
+}
+
+impl DiversityStrategy {
+    pub const ALL: [DiversityStrategy; 4] = [
+        DiversityStrategy::RephraseStructure,
+        DiversityStrategy::AlternateReasoningScaffold,
+        DiversityStrategy::DropRules,
+        DiversityStrategy::ReorderRules,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RephraseStructure => "rephrase_structure",
+            Self::AlternateReasoningScaffold => "alternate_reasoning_scaffold",
+            Self::DropRules => "drop_rules",
+            Self::ReorderRules => "reorder_rules",
+        }
+    }
+}
+
+/// 多样性注入产出的探索型候选。
+#[derive(Debug, Clone)]
+pub struct DiversityCandidate {
+    pub strategy: DiversityStrategy,
+    pub content: String,
+    pub fingerprint: String,
+    /// 是否由老师模型生成（false 表示确定性改写）
+    pub via_teacher: bool,
+    /// 固定为 `CandidateSource::DiversityInjection`，便于下游统计效果
+    pub source: CandidateSource,
+}
+
+/// 推理脚手架（按 `ctx.iteration` 轮换，避免每次注入同一脚手架）。
+const REASONING_SCAFFOLDS: [(&str, &str); 3] = [
+    (
+        "【推理脚手架｜拆解-求解-核对】先把任务拆成若干子问题（不要输出拆解过程），逐个求解后再合并为最终输出。",
+        "【提交前核对】逐条确认上述要求均已满足，再只输出最终结果。",
+    ),
+    (
+        "【推理脚手架｜假设-验证】先给出一个候选答案的内部假设，再用下述要求逐条验证并修正（不要输出验证过程）。",
+        "【提交前核对】若任何一条要求未通过验证，先修正再输出。",
+    ),
+    (
+        "【推理脚手架｜反例排查】先设想最可能导致输出不合格的 3 种情形（不要输出），并在作答时逐一规避。",
+        "【提交前核对】确认输出不落入任何已设想的不合格情形。",
+    ),
+];
+
+/// 生成多样性候选（按 `DiversityStrategy::ALL` 顺序，每种策略至多 1 个）。
+///
+/// - `teacher_model` 为 `None` 或输出不可用时，结构改写回退为确定性实现
+/// - 与 `current_prompt` / 失败档案 / 本批次已生成候选指纹重复的候选会被丢弃
+pub async fn generate_diversity_candidates(
+    ctx: &OptimizationContext,
+    teacher_model: Option<&dyn TeacherModel>,
+) -> Result<Vec<DiversityCandidate>, GeneratorError> {
+    if ctx.current_prompt.trim().is_empty() {
+        return Err(GeneratorError::InvalidContext {
+            reason: "多样性注入要求 ctx.current_prompt 非空".to_string(),
+        });
+    }
+
+    let mut blocked = read_archive_fingerprints(ctx)?;
+    blocked.insert(failure_fingerprint_v1(&ctx.current_prompt));
+
+    let mut out = Vec::new();
+    for strategy in DiversityStrategy::ALL {
+        let (content, via_teacher) = match strategy {
+            DiversityStrategy::RephraseStructure => {
+                match rephrase_with_teacher(ctx, teacher_model).await {
+                    Some(p) => (Some(p), true),
+                    None => (rephrase_deterministic(ctx, &blocked), false),
+                }
+            }
+            DiversityStrategy::AlternateReasoningScaffold => {
+                (Some(apply_reasoning_scaffold(ctx)), false)
+            }
+            DiversityStrategy::DropRules => {
+                (drop_directives(&ctx.current_prompt, ctx.iteration), false)
+            }
+            DiversityStrategy::ReorderRules => (reorder_directives(&ctx.current_prompt), false),
+        };
+
+        let Some(content) = content else {
+            continue;
+        };
+        let fingerprint = failure_fingerprint_v1(&content);
+        if !blocked.insert(fingerprint.clone()) {
+            info!(
+                strategy = strategy.as_str(),
+                "多样性候选命中失败档案/重复指纹，已丢弃"
+            );
+            continue;
+        }
+        out.push(DiversityCandidate {
+            strategy,
+            content,
+            fingerprint,
+            via_teacher,
+            source: CandidateSource::DiversityInjection,
+        });
+    }
+
+    info!(
+        iteration = ctx.iteration,
+        candidate_count = out.len(),
+        "多样性注入候选生成完成"
+    );
+    Ok(out)
+}
+
+fn read_archive_fingerprints(ctx: &OptimizationContext) -> Result<HashSet<String>, GeneratorError> {
+    let Some(v) = ctx.extensions.get(EXT_FAILURE_ARCHIVE) else {
+        return Ok(HashSet::new());
+    };
+    let archive: Vec<FailureArchiveEntry> =
+        serde_json::from_value(v.clone()).map_err(|e| GeneratorError::InvalidContext {
+            reason: format!("{EXT_FAILURE_ARCHIVE} 反序列化失败：{e}"),
+        })?;
+    Ok(archive.into_iter().map(|e| e.failure_fingerprint).collect())
+}
+
+async fn rephrase_with_teacher(
+    ctx: &OptimizationContext,
+    teacher_model: Option<&dyn TeacherModel>,
+) -> Option<String> {
+    let teacher_model = teacher_model?;
+    let request = [
+        "你是 Prompt 改写专家。当前 Prompt 的优化已经停滞，请在保留全部任务要求的前提下，",
+        "用完全不同的组织结构重写它（例如：改变段落顺序、改用角色/目标/约束/输出格式分节、合并或拆分要求）。",
+        "只输出改写后的 Prompt 正文，不要任何解释。",
+        "",
+        "【当前 Prompt】",
+        &ctx.current_prompt,
+    ]
+    .join("\n");
+
+    match teacher_model.generate(&request).await {
+        Ok(text) => {
+            let text = text.trim();
+            // 老师模型返回空文本或结构化 JSON（例如评估型响应）时视为不可用，回退确定性改写。
+            if text.is_empty() || serde_json::from_str::<serde_json::Value>(text).is_ok() {
+                warn!("老师模型结构改写输出不可用，回退确定性改写");
+                return None;
+            }
+            Some(text.to_string())
+        }
+        Err(err) => {
+            warn!(error = %err, "老师模型结构改写失败，回退确定性改写");
+            None
+        }
+    }
+}
+
+/// 确定性结构改写：
+/// - 规律可用（含 failure 规律）时：从 `ctx.iteration` 起轮换模板变体，取首个未被阻断的变体
+/// - 否则：把当前 Prompt 的非空行重组为“任务清单”结构
+fn rephrase_deterministic(ctx: &OptimizationContext, blocked: &HashSet<String>) -> Option<String> {
+    if let Ok(classification) = classify_rules(&ctx.rule_system.rules) {
+        if !classification.failure.is_empty() {
+            let keep = build_keep_section(&classification.success);
+            let fix = build_fix_section(&classification.failure);
+            let summary = summarize_test_cases(&ctx.test_cases);
+            let user_guidance = read_optional_user_guidance(ctx);
+            for offset in 0..TEMPLATE_VARIANT_COUNT {
+                let candidate_index = ctx.iteration.wrapping_add(offset) % TEMPLATE_VARIANT_COUNT;
+                let prompt = render_candidate_variant(
+                    candidate_index,
+                    &keep,
+                    &fix,
+                    &summary,
+                    user_guidance.as_deref(),
+                );
+                if !blocked.contains(&failure_fingerprint_v1(&prompt)) {
+                    return Some(prompt);
+                }
+            }
+        }
+    }
+
+    let lines: Vec<&str> = ctx
+        .current_prompt
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let mut out = vec![
+        "【任务清单】请把以下每一项视为独立、必须满足的要求，按编号顺序逐项落实：".to_string(),
+    ];
+    for (i, line) in lines.iter().enumerate() {
+        out.push(format!("{}. {}", i + 1, strip_directive_marker(line)));
+    }
+    out.push(String::new());
+    out.push("【输出】只输出最终结果，不要复述上述清单。".to_string());
+    Some(out.join("\n"))
+}
+
+fn apply_reasoning_scaffold(ctx: &OptimizationContext) -> String {
+    let idx = ctx.iteration as usize % REASONING_SCAFFOLDS.len();
+    let (head, tail) = REASONING_SCAFFOLDS[idx];
+    [head, "", ctx.current_prompt.trim(), "", tail].join("\n")
+}
+
+/// 丢弃部分指令条目：从 `iteration % n` 起丢弃 `max(1, n/3)` 条（环形），至少保留 1 条。
+///
+/// 指令条目少于 2 条时返回 `None`（无可丢弃的余量）。
+fn drop_directives(prompt: &str, iteration: u32) -> Option<String> {
+    let lines: Vec<&str> = prompt.lines().collect();
+    let directive_positions = directive_positions(&lines);
+    let n = directive_positions.len();
+    if n < 2 {
+        return None;
+    }
+
+    let drop_count = (n / 3).max(1);
+    let start = iteration as usize % n;
+    let dropped: HashSet<usize> = (0..drop_count)
+        .map(|k| directive_positions[(start + k) % n])
+        .collect();
+
+    let kept: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !dropped.contains(i))
+        .map(|(_, l)| *l)
+        .collect();
+    Some(kept.join("\n"))
+}
+
+/// 重排指令条目：保持非指令行位置不变，把指令条目整体逆序。
+fn reorder_directives(prompt: &str) -> Option<String> {
+    let mut lines: Vec<&str> = prompt.lines().collect();
+    let positions = directive_positions(&lines);
+    if positions.len() < 2 {
+        return None;
+    }
+    let reversed: Vec<&str> = positions.iter().rev().map(|&i| lines[i]).collect();
+    for (pos, line) in positions.iter().zip(reversed) {
+        lines[*pos] = line;
+    }
+    Some(lines.join("\n"))
+}
+
+fn directive_positions(lines: &[&str]) -> Vec<usize> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| is_directive_line(l))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// 指令条目：以 `- ` / `* ` / `• ` 或 `1.` / `1)` / `1、` 开头的行。
+fn is_directive_line(line: &str) -> bool {
+    let t = line.trim_start();
+    if t.starts_with("- ") || t.starts_with("* ") || t.starts_with("• ") {
+        return true;
+    }
+    let digits = t.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && matches!(t[digits..].chars().next(), Some('.' | ')' | '、'))
+}
+
+fn strip_directive_marker(line: &str) -> &str {
+    if !is_directive_line(line) {
+        return line;
+    }
+    let t = line.trim_start();
+    for prefix in ["- ", "* ", "• "] {
+        if let Some(rest) = t.strip_prefix(prefix) {
+            return rest.trim_start();
+        }
+    }
+    let digits = t.chars().take_while(|c| c.is_ascii_digit()).count();
+    let mut rest = t[digits..].chars();
+    rest.next();
+    rest.as_str().trim_start()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::teacher_model::ExampleTeacherModel;
+    use crate::domain::models::RuleSystem;
+    use crate::domain::types::{ExecutionTargetConfig, OptimizationConfig};
+    use std::collections::HashMap;
+
+    const PROMPT: &str =
+        "你是一个严格的助手。\n- 输出 JSON\n- 字段 name 必填\n- 不要输出解释\n结束。";
+
+    fn make_ctx(prompt: &str, iteration: u32) -> OptimizationContext {
+        OptimizationContext {
+            task_id: "task-1".to_string(),
+            execution_target_config: ExecutionTargetConfig::default(),
+            current_prompt: prompt.to_string(),
+            rule_system: RuleSystem {
+                rules: vec![],
+                conflict_resolution_log: vec![],
+                merge_log: vec![],
+                coverage_map: HashMap::new(),
+                version: 0,
+            },
+            iteration,
+            state: crate::domain::models::IterationState::Idle,
+            run_control_state: Default::default(),
+            test_cases: vec![],
+            config: OptimizationConfig::default(),
+            checkpoints: vec![],
+            extensions: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn generates_one_candidate_per_strategy_without_teacher() {
+        let ctx = make_ctx(PROMPT, 0);
+        let out = generate_diversity_candidates(&ctx, None).await.unwrap();
+
+        let strategies: Vec<_> = out.iter().map(|c| c.strategy).collect();
+        assert_eq!(strategies, DiversityStrategy::ALL.to_vec());
+        assert!(out.iter().all(|c| !c.via_teacher));
+        assert!(
+            out.iter()
+                .all(|c| c.source == CandidateSource::DiversityInjection)
+        );
+        assert!(out.iter().all(|c| c.content != PROMPT));
+
+        let unique: HashSet<_> = out.iter().map(|c| c.fingerprint.clone()).collect();
+        assert_eq!(unique.len(), out.len());
+    }
+
+    #[tokio::test]
+    async fn drops_candidates_that_hit_failure_archive() {
+        let mut ctx = make_ctx(PROMPT, 0);
+        let reordered = reorder_directives(PROMPT).unwrap();
+        let archive = vec![FailureArchiveEntry::new(&reordered, "tc-1", "failed")];
+        ctx.extensions.insert(
+            EXT_FAILURE_ARCHIVE.to_string(),
+            serde_json::to_value(archive).unwrap(),
+        );
+
+        let out = generate_diversity_candidates(&ctx, None).await.unwrap();
+        assert!(
+            !out.iter()
+                .any(|c| c.strategy == DiversityStrategy::ReorderRules)
+        );
+        assert_eq!(out.len(), DiversityStrategy::ALL.len() - 1);
+    }
+
+    #[tokio::test]
+    async fn teacher_rewrite_is_used_when_usable() {
+        let ctx = make_ctx(PROMPT, 0);
+        let teacher = ExampleTeacherModel::new("【角色】严格助手\n【输出】JSON，含 name 字段");
+        let out = generate_diversity_candidates(&ctx, Some(&teacher))
+            .await
+            .unwrap();
+        let rephrased = out
+            .iter()
+            .find(|c| c.strategy == DiversityStrategy::RephraseStructure)
+            .unwrap();
+        assert!(rephrased.via_teacher);
+        assert!(rephrased.content.starts_with("【角色】"));
+    }
+
+    #[tokio::test]
+    async fn teacher_json_output_falls_back_to_deterministic_rewrite() {
+        let ctx = make_ctx(PROMPT, 0);
+        let teacher = ExampleTeacherModel::new_default();
+        let out = generate_diversity_candidates(&ctx, Some(&teacher))
+            .await
+            .unwrap();
+        let rephrased = out
+            .iter()
+            .find(|c| c.strategy == DiversityStrategy::RephraseStructure)
+            .unwrap();
+        assert!(!rephrased.via_teacher);
+        assert!(rephrased.content.starts_with("【任务清单】"));
+    }
+
+    #[test]
+    fn drop_and_reorder_require_at_least_two_directives() {
+        assert!(drop_directives("单行 Prompt", 0).is_none());
+        assert!(reorder_directives("- 只有一条").is_none());
+
+        let dropped = drop_directives(PROMPT, 1).unwrap();
+        assert!(!dropped.contains("字段 name 必填"));
+        assert!(dropped.contains("输出 JSON"));
+
+        let reordered = reorder_directives(PROMPT).unwrap();
+        let lines: Vec<&str> = reordered.lines().collect();
+        assert_eq!(lines[1], "- 不要输出解释");
+        assert_eq!(lines[3], "- 输出 JSON");
+        assert_eq!(lines[4], "结束。");
+    }
+
+    #[tokio::test]
+    async fn empty_current_prompt_returns_invalid_context() {
+        let ctx = make_ctx("  ", 0);
+        let err = generate_diversity_candidates(&ctx, None).await.unwrap_err();
+        assert!(matches!(err, GeneratorError::InvalidContext { .. }));
+    }
+}
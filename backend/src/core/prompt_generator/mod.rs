@@ -1,7 +1,9 @@
 mod default_impl;
+mod diversity;
 mod error;
 
 pub use default_impl::DefaultPromptGenerator;
+pub use diversity::{DiversityCandidate, DiversityStrategy, generate_diversity_candidates};
 pub use error::GeneratorError;
 
 /// `ctx.extensions` 中用于注入“优化目标”的 key。
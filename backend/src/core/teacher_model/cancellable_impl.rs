@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::core::iteration_engine::pause_state::current_cancellation_token;
+use crate::core::traits::TeacherModel;
+
+/// 取消包装：在任务作用域内运行时，与任务的取消令牌竞速（暂停 / 终止时立即丢弃在途调用）。
+///
+/// 不在任务作用域内（如预览、单元测试）时直接透传。
+pub struct CancellableTeacherModel {
+    inner: Arc<dyn TeacherModel>,
+}
+
+impl CancellableTeacherModel {
+    pub fn new(inner: Arc<dyn TeacherModel>) -> Self {
+        Self { inner }
+    }
+}
+
+fn cancelled_error() -> anyhow::Error {
+    anyhow::anyhow!("老师模型调用已取消（任务已暂停或终止）")
+}
+
+#[async_trait]
+impl TeacherModel for CancellableTeacherModel {
+    async fn generate(&self, prompt: &str) -> anyhow::Result<String> {
+        let Some(token) = current_cancellation_token() else {
+            return self.inner.generate(prompt).await;
+        };
+        tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(cancelled_error()),
+            result = self.inner.generate(prompt) => result,
+        }
+    }
+
+    async fn generate_stream(&self, prompt: &str) -> anyhow::Result<mpsc::Receiver<String>> {
+        let Some(token) = current_cancellation_token() else {
+            return self.inner.generate_stream(prompt).await;
+        };
+        tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(cancelled_error()),
+            result = self.inner.generate_stream(prompt) => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::iteration_engine::pause_state::{PauseController, with_pause_controller};
+    use std::time::Duration;
+
+    struct SlowTeacherModel;
+
+    #[async_trait]
+    impl TeacherModel for SlowTeacherModel {
+        async fn generate(&self, _prompt: &str) -> anyhow::Result<String> {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            Ok("late".to_string())
+        }
+
+        async fn generate_stream(&self, _prompt: &str) -> anyhow::Result<mpsc::Receiver<String>> {
+            anyhow::bail!("unsupported")
+        }
+    }
+
+    #[tokio::test]
+    async fn stop_request_drops_in_flight_generate() {
+        let controller = Arc::new(PauseController::new("cancel-teacher"));
+        let tm = CancellableTeacherModel::new(Arc::new(SlowTeacherModel));
+
+        let stopper = Arc::clone(&controller);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            stopper.request_stop("cid", "user").await;
+        });
+
+        let started = std::time::Instant::now();
+        let err = with_pause_controller(controller, tm.generate("PROMPT"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("已取消"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::core::cassette::{Cassette, CassetteMode, cassette_key, prompt_hash};
+use crate::core::traits::TeacherModel;
+
+const KIND_GENERATE: &str = "teacher.generate";
+const KIND_STREAM: &str = "teacher.generate_stream";
+
+/// cassette 中单次老师模型调用的记录（Prompt 只保存哈希）。
+///
+/// `outcome` 的分片对 generate 为单元素；对 generate_stream 保留录制时的分片边界。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeacherCassetteEntry {
+    pub prompt_hash: String,
+    pub outcome: Result<Vec<String>, String>,
+}
+
+/// 录制/回放包装：Record 透传并落盘；Replay 只读 cassette，未命中即报错。
+pub struct CassetteTeacherModel {
+    inner: Arc<dyn TeacherModel>,
+    cassette: Arc<Cassette>,
+}
+
+impl CassetteTeacherModel {
+    pub fn new(inner: Arc<dyn TeacherModel>, cassette: Arc<Cassette>) -> Self {
+        Self { inner, cassette }
+    }
+
+    fn replay(&self, kind: &str, prompt: &str) -> anyhow::Result<Vec<String>> {
+        let entry: TeacherCassetteEntry = self
+            .cassette
+            .replay(kind, &cassette_key(&[prompt]))
+            .inspect_err(|err| tracing::error!(error = %err, "老师模型 cassette 回放失败"))?;
+        entry.outcome.map_err(anyhow::Error::msg)
+    }
+
+    fn record(&self, kind: &str, prompt: &str, outcome: Result<Vec<String>, String>) {
+        let entry = TeacherCassetteEntry {
+            prompt_hash: prompt_hash(prompt),
+            outcome,
+        };
+        if let Err(err) = self.cassette.record(kind, &cassette_key(&[prompt]), &entry) {
+            tracing::error!(error = %err, "老师模型 cassette 写入失败");
+        }
+    }
+}
+
+#[async_trait]
+impl TeacherModel for CassetteTeacherModel {
+    async fn generate(&self, prompt: &str) -> anyhow::Result<String> {
+        match self.cassette.mode() {
+            CassetteMode::Replay => Ok(self.replay(KIND_GENERATE, prompt)?.concat()),
+            CassetteMode::Record => {
+                let result = self.inner.generate(prompt).await;
+                let outcome = match &result {
+                    Ok(text) => Ok(vec![text.clone()]),
+                    Err(err) => Err(err.to_string()),
+                };
+                self.record(KIND_GENERATE, prompt, outcome);
+                result
+            }
+        }
+    }
+
+    async fn generate_stream(&self, prompt: &str) -> anyhow::Result<mpsc::Receiver<String>> {
+        match self.cassette.mode() {
+            CassetteMode::Replay => {
+                let chunks = self.replay(KIND_STREAM, prompt)?;
+                let (tx, rx) = mpsc::channel(chunks.len().max(1));
+                tokio::spawn(async move {
+                    for chunk in chunks {
+                        if tx.send(chunk).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+                Ok(rx)
+            }
+            CassetteMode::Record => {
+                let mut upstream = match self.inner.generate_stream(prompt).await {
+                    Ok(rx) => rx,
+                    Err(err) => {
+                        self.record(KIND_STREAM, prompt, Err(err.to_string()));
+                        return Err(err);
+                    }
+                };
+                // 转发分片的同时收集，流结束后整体落盘。
+                let (tx, rx) = mpsc::channel(8);
+                let cassette = Arc::clone(&self.cassette);
+                let key = cassette_key(&[prompt]);
+                let prompt_hash = prompt_hash(prompt);
+                tokio::spawn(async move {
+                    let mut chunks = Vec::new();
+                    while let Some(chunk) = upstream.recv().await {
+                        chunks.push(chunk.clone());
+                        let _ = tx.send(chunk).await;
+                    }
+                    let entry = TeacherCassetteEntry {
+                        prompt_hash,
+                        outcome: Ok(chunks),
+                    };
+                    if let Err(err) = cassette.record(KIND_STREAM, &key, &entry) {
+                        tracing::error!(error = %err, "老师模型 cassette 写入失败");
+                    }
+                });
+                Ok(rx)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::teacher_model::ExampleTeacherModel;
+
+    fn temp_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "prompt_faster_teacher_cassette_{}.jsonl",
+            uuid::Uuid::new_v4()
+        ))
+    }
+
+    async fn collect(mut rx: mpsc::Receiver<String>) -> Vec<String> {
+        let mut out = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+            out.push(chunk);
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn replay_serves_recorded_responses_and_fails_on_miss() {
+        let path = temp_path();
+        {
+            let cassette = Arc::new(Cassette::open(CassetteMode::Record, &path).unwrap());
+            let model =
+                CassetteTeacherModel::new(Arc::new(ExampleTeacherModel::new("recorded")), cassette);
+            assert_eq!(model.generate("SECRET_PROMPT").await.unwrap(), "recorded");
+            let chunks = collect(model.generate_stream("SECRET_PROMPT").await.unwrap()).await;
+            // 转发任务在落盘后才释放发送端，collect 结束即已写入。
+            assert_eq!(chunks, vec!["recorded".to_string()]);
+        }
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("SECRET_PROMPT"));
+
+        let cassette = Arc::new(Cassette::open(CassetteMode::Replay, &path).unwrap());
+        let model = CassetteTeacherModel::new(
+            Arc::new(ExampleTeacherModel::new("live-should-not-be-used")),
+            cassette,
+        );
+        assert_eq!(model.generate("SECRET_PROMPT").await.unwrap(), "recorded");
+        let chunks = collect(model.generate_stream("SECRET_PROMPT").await.unwrap()).await;
+        assert_eq!(chunks, vec!["recorded".to_string()]);
+
+        let err = model.generate("unrecorded").await.unwrap_err();
+        assert!(err.to_string().contains("cassette 未命中"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
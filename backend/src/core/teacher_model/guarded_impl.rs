@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::core::traits::TeacherModel;
+use crate::infra::external::upstream_guard::{UpstreamGuard, UpstreamOutcome, estimate_tokens};
+
+/// 限流 / 熔断包装：调用前申请配额，调用后按结果回报（任何错误视为上游失败）。
+pub struct GuardedTeacherModel {
+    inner: Arc<dyn TeacherModel>,
+    guard: Arc<UpstreamGuard>,
+}
+
+impl GuardedTeacherModel {
+    pub fn new(inner: Arc<dyn TeacherModel>, guard: Arc<UpstreamGuard>) -> Self {
+        Self { inner, guard }
+    }
+
+    async fn acquire(&self, prompt: &str) -> anyhow::Result<()> {
+        self.guard
+            .acquire(estimate_tokens(prompt.len()))
+            .await
+            .map_err(|e| anyhow::anyhow!("老师模型{e}"))
+    }
+
+    async fn record<T>(&self, result: &anyhow::Result<T>) {
+        let outcome = match result {
+            Ok(_) => UpstreamOutcome::Success,
+            Err(_) => UpstreamOutcome::Failure { retry_after: None },
+        };
+        self.guard.record(outcome).await;
+    }
+}
+
+#[async_trait]
+impl TeacherModel for GuardedTeacherModel {
+    async fn generate(&self, prompt: &str) -> anyhow::Result<String> {
+        self.acquire(prompt).await?;
+        let result = self.inner.generate(prompt).await;
+        self.record(&result).await;
+        result
+    }
+
+    async fn generate_stream(&self, prompt: &str) -> anyhow::Result<mpsc::Receiver<String>> {
+        self.acquire(prompt).await?;
+        let result = self.inner.generate_stream(prompt).await;
+        self.record(&result).await;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::external::upstream_guard::{CircuitState, UpstreamGuardConfig};
+
+    struct FailingTeacherModel;
+
+    #[async_trait]
+    impl TeacherModel for FailingTeacherModel {
+        async fn generate(&self, _prompt: &str) -> anyhow::Result<String> {
+            anyhow::bail!("upstream down")
+        }
+
+        async fn generate_stream(&self, _prompt: &str) -> anyhow::Result<mpsc::Receiver<String>> {
+            anyhow::bail!("upstream down")
+        }
+    }
+
+    #[tokio::test]
+    async fn failures_below_threshold_pass_through_inner_error() {
+        let guard = Arc::new(UpstreamGuard::new(
+            "teacher-test",
+            UpstreamGuardConfig {
+                failure_threshold: u32::MAX,
+                ..UpstreamGuardConfig::default()
+            },
+        ));
+        let tm = GuardedTeacherModel::new(Arc::new(FailingTeacherModel), guard.clone());
+
+        let err = tm.generate("PROMPT").await.unwrap_err();
+        assert!(err.to_string().contains("upstream down"));
+        assert_eq!(guard.circuit_state().await, CircuitState::Closed);
+    }
+}
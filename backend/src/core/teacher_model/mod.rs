@@ -1,11 +1,22 @@
+mod cancellable_impl;
+mod cassette_impl;
 mod example_impl;
+mod guarded_impl;
 
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::core::cassette::{TEACHER_MODEL_CASSETTE_FILE, cassette_from_env};
 use crate::core::traits::TeacherModel;
+use crate::infra::external::upstream_guard::upstream_guard_for_key;
 
+pub use cancellable_impl::CancellableTeacherModel;
+pub use cassette_impl::{CassetteTeacherModel, TeacherCassetteEntry};
 pub use example_impl::ExampleTeacherModel;
+pub use guarded_impl::GuardedTeacherModel;
+
+/// 老师模型共享的限流 / 熔断键
+const TEACHER_MODEL_GUARD_KEY: &str = "teacher_model";
 
 /// TeacherModel 实现选择（用于扩展点示例与本地验证；非用户级 `teacher_llm.model_id`）。
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,8 +25,12 @@ pub enum TeacherModelType {
 }
 
 /// TeacherModel 工厂：新增实现仅需在此处注册（单一入口点）。
+///
+/// 实际实现统一包装限流 / 熔断（[`GuardedTeacherModel`]）；
+/// 设置 `PROMPT_FASTER_CASSETTE_MODE`/`PROMPT_FASTER_CASSETTE_DIR` 时再包装为录制/回放实现（回放不占用配额）；
+/// 最外层为 [`CancellableTeacherModel`]，任务暂停 / 终止时丢弃在途调用。
 pub fn create_teacher_model(teacher_model_type: TeacherModelType) -> Arc<dyn TeacherModel> {
-    match teacher_model_type {
+    let model: Arc<dyn TeacherModel> = match teacher_model_type {
         TeacherModelType::Example => {
             let mut model = ExampleTeacherModel::new_default();
             if let Ok(delay_ms) = std::env::var("PROMPT_FASTER_TEACHER_MODEL_DELAY_MS") {
@@ -25,5 +40,14 @@ pub fn create_teacher_model(teacher_model_type: TeacherModelType) -> Arc<dyn Tea
             }
             Arc::new(model)
         }
-    }
+    };
+    let model: Arc<dyn TeacherModel> = Arc::new(GuardedTeacherModel::new(
+        model,
+        upstream_guard_for_key(TEACHER_MODEL_GUARD_KEY),
+    ));
+    let model: Arc<dyn TeacherModel> = match cassette_from_env(TEACHER_MODEL_CASSETTE_FILE) {
+        Some(cassette) => Arc::new(CassetteTeacherModel::new(model, cassette)),
+        None => model,
+    };
+    Arc::new(CancellableTeacherModel::new(model))
 }
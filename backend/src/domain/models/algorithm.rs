@@ -26,6 +26,72 @@ pub struct TestCase {
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
+/// 多轮对话用例：`TestCase.input` 中承载对话历史的保留字段
+pub const TEST_CASE_CONVERSATION_INPUT_KEY: &str = "conversation";
+/// 单个对话用例允许的最大轮数
+pub const TEST_CASE_CONVERSATION_MAX_TURNS: usize = 200;
+
+/// 对话角色（system 由待优化 Prompt 提供，不出现在用例中）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[ts(export_to = "models/")]
+pub enum ConversationRole {
+    User,
+    Assistant,
+}
+
+/// 对话中的一轮
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS, ToSchema)]
+#[ts(export_to = "models/")]
+pub struct ConversationTurn {
+    pub role: ConversationRole,
+    pub content: String,
+}
+
+impl TestCase {
+    /// 读取多轮对话输入（`input.conversation`）。
+    ///
+    /// 约定：按时间顺序排列的 user/assistant 轮次，最后一轮必须是 user（即待回复的消息）；
+    /// 执行目标生成的回复即最终 assistant 轮，评估只针对该轮。
+    /// 非对话用例返回 `Ok(None)`；格式不合法时返回错误描述（不含对话原文）。
+    pub fn conversation(&self) -> Result<Option<Vec<ConversationTurn>>, String> {
+        parse_conversation_input(&self.input)
+    }
+}
+
+/// 从输入变量中解析多轮对话（规则见 [`TestCase::conversation`]）。
+pub fn parse_conversation_input(
+    input: &HashMap<String, serde_json::Value>,
+) -> Result<Option<Vec<ConversationTurn>>, String> {
+    let Some(raw) = input.get(TEST_CASE_CONVERSATION_INPUT_KEY) else {
+        return Ok(None);
+    };
+    let turns: Vec<ConversationTurn> = serde_json::from_value(raw.clone()).map_err(|_| {
+        format!(
+            "{TEST_CASE_CONVERSATION_INPUT_KEY} 必须是 {{role: \"user\"|\"assistant\", content: string}} 数组"
+        )
+    })?;
+    if turns.is_empty() {
+        return Err(format!("{TEST_CASE_CONVERSATION_INPUT_KEY} 不能为空"));
+    }
+    if turns.len() > TEST_CASE_CONVERSATION_MAX_TURNS {
+        return Err(format!(
+            "{TEST_CASE_CONVERSATION_INPUT_KEY} 不能超过 {TEST_CASE_CONVERSATION_MAX_TURNS} 轮"
+        ));
+    }
+    if let Some(index) = turns.iter().position(|t| t.content.trim().is_empty()) {
+        return Err(format!(
+            "{TEST_CASE_CONVERSATION_INPUT_KEY}[{index}].content 不能为空"
+        ));
+    }
+    if turns.last().map(|t| t.role) != Some(ConversationRole::User) {
+        return Err(format!(
+            "{TEST_CASE_CONVERSATION_INPUT_KEY} 的最后一轮必须是 user"
+        ));
+    }
+    Ok(Some(turns))
+}
+
 /// 数据划分类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, ToSchema)]
 #[serde(rename_all = "snake_case")]
@@ -200,7 +266,7 @@ pub fn failure_fingerprint_v1(prompt: &str) -> String {
     format!("v1:fnv1a64:{hash:016x}")
 }
 
-fn normalize_prompt_for_fingerprint(prompt: &str) -> String {
+pub(crate) fn normalize_prompt_for_fingerprint(prompt: &str) -> String {
     let mut out = String::new();
     let mut last_was_ws = false;
     for ch in prompt.trim().chars() {
@@ -0,0 +1,386 @@
+//! 大模型 Provider 注册表
+//!
+//! 描述一个 Provider 的调用方式（默认 Base URL、鉴权头风格、模型列表端点、对话端点与协议格式）。
+//! 内置 Provider 由 [`LlmProviderRegistry::builtin`] 提供；用户可按同一结构自定义 Provider
+//! （例如自建 vLLM / Ollama 等 OpenAI 兼容服务）。
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// 对话端点路径中的模型占位符（Azure 风格部署名等场景）
+pub const LLM_PROVIDER_MODEL_PLACEHOLDER: &str = "{model}";
+/// Provider ID 最大长度
+pub const LLM_PROVIDER_ID_MAX_CHARS: usize = 64;
+/// Provider 显示名最大长度
+pub const LLM_PROVIDER_DISPLAY_NAME_MAX_CHARS: usize = 64;
+/// 端点路径最大长度
+pub const LLM_PROVIDER_PATH_MAX_CHARS: usize = 256;
+
+/// 鉴权头风格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export_to = "models/")]
+pub enum LlmAuthStyle {
+    /// `Authorization: Bearer <key>`（OpenAI 兼容）
+    Bearer,
+    /// `api-key: <key>`（Azure OpenAI）
+    ApiKeyHeader,
+    /// `x-api-key: <key>`（Anthropic）
+    XApiKey,
+    /// 不发送鉴权头（本地无鉴权服务）
+    None,
+}
+
+/// 对话接口协议格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export_to = "models/")]
+pub enum LlmApiFormat {
+    /// OpenAI Chat Completions（`choices[0].message.content`）
+    OpenaiChat,
+    /// Anthropic Messages（`content[].text`）
+    AnthropicMessages,
+}
+
+/// Provider 描述
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export_to = "models/")]
+pub struct LlmProviderSpec {
+    /// Provider 标识（小写字母、数字、`_`、`-`）
+    pub id: String,
+    pub display_name: String,
+    /// 默认 Base URL（None 表示必须由用户填写）
+    pub default_base_url: Option<String>,
+    pub auth_style: LlmAuthStyle,
+    /// 模型列表端点路径（相对 Base URL，可包含查询串）
+    pub models_path: String,
+    /// 对话端点路径（相对 Base URL，可包含查询串与 `{model}` 占位符）
+    pub chat_path: String,
+    pub api_format: LlmApiFormat,
+    /// 是否为内置 Provider（用户自定义 Provider 恒为 false）
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl LlmProviderSpec {
+    /// 拼接模型列表端点 URL
+    pub fn models_url(&self, base_url: &str) -> String {
+        format!("{}{}", base_url.trim_end_matches('/'), self.models_path)
+    }
+
+    /// 拼接对话端点 URL（`{model}` 占位符替换为百分号编码后的模型名）
+    pub fn chat_url(&self, base_url: &str, model: &str) -> String {
+        let path = self
+            .chat_path
+            .replace(LLM_PROVIDER_MODEL_PLACEHOLDER, &encode_path_segment(model));
+        format!("{}{}", base_url.trim_end_matches('/'), path)
+    }
+
+    /// 校验 Provider 描述（用户自定义 Provider 保存前调用）
+    pub fn validate(&self) -> Result<(), String> {
+        let id = self.id.as_str();
+        if id.is_empty() || id.chars().count() > LLM_PROVIDER_ID_MAX_CHARS {
+            return Err(format!(
+                "Provider ID 长度必须在 1 ~ {} 之间",
+                LLM_PROVIDER_ID_MAX_CHARS
+            ));
+        }
+        if !id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        {
+            return Err("Provider ID 只能包含小写字母、数字、_ 和 -".to_string());
+        }
+        let display_name = self.display_name.trim();
+        if display_name.is_empty()
+            || display_name.chars().count() > LLM_PROVIDER_DISPLAY_NAME_MAX_CHARS
+        {
+            return Err(format!(
+                "Provider 显示名长度必须在 1 ~ {} 之间",
+                LLM_PROVIDER_DISPLAY_NAME_MAX_CHARS
+            ));
+        }
+        validate_endpoint_path("models_path", &self.models_path)?;
+        validate_endpoint_path("chat_path", &self.chat_path)?;
+        if self.models_path.contains(LLM_PROVIDER_MODEL_PLACEHOLDER) {
+            return Err("models_path 不支持 {model} 占位符".to_string());
+        }
+        if let Some(url) = &self.default_base_url {
+            let parsed = url::Url::parse(url.trim())
+                .map_err(|_| "default_base_url 不是合法的 URL".to_string())?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err("default_base_url 仅支持 http/https".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_endpoint_path(field: &str, path: &str) -> Result<(), String> {
+    if !path.starts_with('/') {
+        return Err(format!("{field} 必须以 / 开头"));
+    }
+    if path.chars().count() > LLM_PROVIDER_PATH_MAX_CHARS {
+        return Err(format!(
+            "{field} 长度不能超过 {}",
+            LLM_PROVIDER_PATH_MAX_CHARS
+        ));
+    }
+    // 路径只能相对 Base URL：禁止协议/主机切换、目录穿越与空白字符
+    if path.starts_with("//")
+        || path.contains("://")
+        || path.contains("..")
+        || path.contains('#')
+        || path.chars().any(|c| c.is_whitespace() || c.is_control())
+    {
+        return Err(format!("{field} 包含非法字符"));
+    }
+    Ok(())
+}
+
+/// 百分号编码单个路径段（保留 RFC 3986 unreserved 字符）
+fn encode_path_segment(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+fn builtin_spec(
+    id: &str,
+    display_name: &str,
+    default_base_url: Option<&str>,
+    auth_style: LlmAuthStyle,
+    models_path: &str,
+    chat_path: &str,
+    api_format: LlmApiFormat,
+) -> LlmProviderSpec {
+    LlmProviderSpec {
+        id: id.to_string(),
+        display_name: display_name.to_string(),
+        default_base_url: default_base_url.map(str::to_string),
+        auth_style,
+        models_path: models_path.to_string(),
+        chat_path: chat_path.to_string(),
+        api_format,
+        builtin: true,
+    }
+}
+
+/// 通用 OpenAI 兼容 Provider 的 ID（vLLM / Ollama / 其他自建服务）
+pub const OPENAI_COMPATIBLE_PROVIDER_ID: &str = "openai_compatible";
+
+/// Azure OpenAI 使用的 API 版本
+const AZURE_OPENAI_API_VERSION: &str = "2024-10-21";
+
+/// Provider 注册表（内置 + 用户自定义）
+#[derive(Debug, Clone)]
+pub struct LlmProviderRegistry {
+    providers: Vec<LlmProviderSpec>,
+}
+
+impl LlmProviderRegistry {
+    /// 仅包含内置 Provider 的注册表
+    pub fn builtin() -> Self {
+        let openai_chat = "/v1/chat/completions";
+        let openai_models = "/v1/models";
+        let azure_models = format!("/openai/models?api-version={AZURE_OPENAI_API_VERSION}");
+        let azure_chat = format!(
+            "/openai/deployments/{LLM_PROVIDER_MODEL_PLACEHOLDER}/chat/completions?api-version={AZURE_OPENAI_API_VERSION}"
+        );
+        Self {
+            providers: vec![
+                builtin_spec(
+                    "siliconflow",
+                    "硅基流动",
+                    Some("https://api.siliconflow.cn"),
+                    LlmAuthStyle::Bearer,
+                    openai_models,
+                    openai_chat,
+                    LlmApiFormat::OpenaiChat,
+                ),
+                builtin_spec(
+                    "modelscope",
+                    "魔搭社区",
+                    Some("https://dashscope.aliyuncs.com/compatible-mode"),
+                    LlmAuthStyle::Bearer,
+                    openai_models,
+                    openai_chat,
+                    LlmApiFormat::OpenaiChat,
+                ),
+                builtin_spec(
+                    "openai",
+                    "OpenAI",
+                    Some("https://api.openai.com"),
+                    LlmAuthStyle::Bearer,
+                    openai_models,
+                    openai_chat,
+                    LlmApiFormat::OpenaiChat,
+                ),
+                builtin_spec(
+                    "deepseek",
+                    "DeepSeek",
+                    Some("https://api.deepseek.com"),
+                    LlmAuthStyle::Bearer,
+                    openai_models,
+                    openai_chat,
+                    LlmApiFormat::OpenaiChat,
+                ),
+                builtin_spec(
+                    "azure_openai",
+                    "Azure OpenAI",
+                    None,
+                    LlmAuthStyle::ApiKeyHeader,
+                    &azure_models,
+                    &azure_chat,
+                    LlmApiFormat::OpenaiChat,
+                ),
+                builtin_spec(
+                    "anthropic",
+                    "Anthropic",
+                    Some("https://api.anthropic.com"),
+                    LlmAuthStyle::XApiKey,
+                    openai_models,
+                    "/v1/messages",
+                    LlmApiFormat::AnthropicMessages,
+                ),
+                builtin_spec(
+                    OPENAI_COMPATIBLE_PROVIDER_ID,
+                    "OpenAI 兼容（自建）",
+                    None,
+                    LlmAuthStyle::Bearer,
+                    openai_models,
+                    openai_chat,
+                    LlmApiFormat::OpenaiChat,
+                ),
+            ],
+        }
+    }
+
+    /// 内置 Provider + 用户自定义 Provider（与内置 ID 冲突的自定义项被忽略）
+    pub fn with_custom(custom: impl IntoIterator<Item = LlmProviderSpec>) -> Self {
+        let mut registry = Self::builtin();
+        for mut spec in custom {
+            if registry.get(&spec.id).is_some() {
+                continue;
+            }
+            spec.builtin = false;
+            registry.providers.push(spec);
+        }
+        registry
+    }
+
+    pub fn get(&self, id: &str) -> Option<&LlmProviderSpec> {
+        self.providers.iter().find(|p| p.id == id)
+    }
+
+    pub fn list(&self) -> &[LlmProviderSpec] {
+        &self.providers
+    }
+
+    /// 是否为内置 Provider ID
+    pub fn is_builtin_id(id: &str) -> bool {
+        Self::builtin().get(id).is_some()
+    }
+
+    /// 通用 OpenAI 兼容 Provider（旧配置未记录 Provider 时的兜底）
+    pub fn openai_compatible() -> LlmProviderSpec {
+        Self::builtin()
+            .get(OPENAI_COMPATIBLE_PROVIDER_ID)
+            .cloned()
+            .expect("内置 OpenAI 兼容 Provider 必须存在")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_specs_are_valid_and_unique() {
+        let registry = LlmProviderRegistry::builtin();
+        let mut ids = std::collections::HashSet::new();
+        for spec in registry.list() {
+            assert!(spec.validate().is_ok(), "{} 应通过校验", spec.id);
+            assert!(spec.builtin);
+            assert!(
+                ids.insert(spec.id.clone()),
+                "重复的 Provider ID: {}",
+                spec.id
+            );
+        }
+        for id in [
+            "siliconflow",
+            "modelscope",
+            "openai",
+            "deepseek",
+            "azure_openai",
+            "anthropic",
+        ] {
+            assert!(
+                LlmProviderRegistry::is_builtin_id(id),
+                "缺少内置 Provider: {id}"
+            );
+        }
+    }
+
+    #[test]
+    fn azure_chat_url_substitutes_encoded_deployment() {
+        let registry = LlmProviderRegistry::builtin();
+        let azure = registry.get("azure_openai").unwrap();
+        assert_eq!(
+            azure.chat_url("https://res.openai.azure.com/", "gpt 4o/mini"),
+            "https://res.openai.azure.com/openai/deployments/gpt%204o%2Fmini/chat/completions?api-version=2024-10-21"
+        );
+        let openai = registry.get("openai").unwrap();
+        assert_eq!(
+            openai.models_url("https://api.openai.com/"),
+            "https://api.openai.com/v1/models"
+        );
+    }
+
+    #[test]
+    fn custom_spec_validation_rejects_host_switching_paths() {
+        let mut spec = LlmProviderRegistry::openai_compatible();
+        spec.id = "my-vllm".to_string();
+        assert!(spec.validate().is_ok());
+
+        for bad in ["v1/models", "//evil.com/v1", "/v1/../admin", "/a b", "/x#y"] {
+            let mut s = spec.clone();
+            s.models_path = bad.to_string();
+            assert!(s.validate().is_err(), "应拒绝路径: {bad}");
+        }
+
+        let mut s = spec.clone();
+        s.id = "Bad ID".to_string();
+        assert!(s.validate().is_err());
+
+        let mut s = spec.clone();
+        s.models_path = "/v1/{model}".to_string();
+        assert!(s.validate().is_err());
+    }
+
+    #[test]
+    fn custom_providers_cannot_shadow_builtin_ids() {
+        let mut shadow = LlmProviderRegistry::openai_compatible();
+        shadow.id = "openai".to_string();
+        shadow.chat_path = "/evil".to_string();
+        let mut ollama = LlmProviderRegistry::openai_compatible();
+        ollama.id = "ollama".to_string();
+        ollama.builtin = true;
+
+        let registry = LlmProviderRegistry::with_custom(vec![shadow, ollama]);
+        assert_eq!(
+            registry.get("openai").unwrap().chat_path,
+            "/v1/chat/completions"
+        );
+        let ollama = registry.get("ollama").unwrap();
+        assert!(!ollama.builtin);
+    }
+}
@@ -8,6 +8,7 @@ pub mod evaluation_result;
 pub mod history;
 pub mod history_event;
 pub mod iteration_stage;
+pub mod llm_provider;
 pub mod optimization_task;
 pub mod optimization_task_config;
 pub mod recovery;
@@ -18,10 +19,12 @@ pub mod user;
 pub mod workspace;
 
 pub use algorithm::{
-    ConflictResolutionRecord, Constraint, DataSplit, DimensionScore, EvaluationResult,
-    ExecutionResult, FailureArchiveEntry, FailurePoint, Iteration, IterationState, LineageType,
-    OutputLength, QualityDimension, Rule, RuleConflict, RuleConflictType, RuleIR, RuleMergeRecord,
-    RuleSystem, RuleTags, Severity, TaskReference, TestCase, TokenUsage, failure_fingerprint_v1,
+    ConflictResolutionRecord, Constraint, ConversationRole, ConversationTurn, DataSplit,
+    DimensionScore, EvaluationResult, ExecutionResult, FailureArchiveEntry, FailurePoint,
+    Iteration, IterationState, LineageType, OutputLength, QualityDimension, Rule, RuleConflict,
+    RuleConflictType, RuleIR, RuleMergeRecord, RuleSystem, RuleTags, Severity,
+    TEST_CASE_CONVERSATION_INPUT_KEY, TEST_CASE_CONVERSATION_MAX_TURNS, TaskReference, TestCase,
+    TokenUsage, failure_fingerprint_v1, parse_conversation_input,
 };
 pub use checkpoint::{
     Checkpoint, CheckpointCreateRequest, CheckpointEntity, CheckpointFull, CheckpointListResponse,
@@ -46,15 +49,20 @@ pub use history_event::{Actor, EventType, HistoryEvent, HistoryEventFilter};
 pub use iteration_stage::{
     IterationStageDescriptor, all_stages as all_iteration_stages, stage_for_state,
 };
+pub use llm_provider::{
+    LLM_PROVIDER_MODEL_PLACEHOLDER, LlmApiFormat, LlmAuthStyle, LlmProviderRegistry,
+    LlmProviderSpec, OPENAI_COMPATIBLE_PROVIDER_ID,
+};
 pub use optimization_task::{
     ExecutionTargetType, OptimizationTaskEntity, OptimizationTaskMode, OptimizationTaskStatus,
 };
 pub use optimization_task_config::{
-    AdvancedDataSplitConfig, AdvancedDataSplitStrategy, ConstraintCheckEvaluatorConfig,
-    DataSplitPercentConfig, EvaluatorConfig, EvaluatorType, ExactMatchEvaluatorConfig,
-    ExecutionMode, OPTIMIZATION_TASK_CONFIG_SCHEMA_VERSION, OptimizationTaskConfig, OutputConfig,
-    OutputStrategy, SamplingStrategy, SemanticSimilarityEvaluatorConfig, TeacherLlmConfig,
-    TeacherModelEvaluatorConfig,
+    AdvancedDataSplitConfig, AdvancedDataSplitStrategy, CommandTargetConfig,
+    ConstraintCheckEvaluatorConfig, DataSplitPercentConfig, EvaluatorConfig, EvaluatorType,
+    ExactMatchEvaluatorConfig, ExecutionCacheConfig, ExecutionMode, ExecutionRetryConfig,
+    HTTP_CREDENTIAL_PLACEHOLDER_PREFIX, HttpTargetConfig, OPTIMIZATION_TASK_CONFIG_SCHEMA_VERSION,
+    OptimizationTaskConfig, OutputConfig, OutputStrategy, SamplingStrategy,
+    SemanticSimilarityEvaluatorConfig, TeacherLlmConfig, TeacherModelEvaluatorConfig,
 };
 pub use recovery::{
     CheckpointSummary, CheckpointWithSummary, ConnectivityResponse, ConnectivityStatus,
@@ -11,6 +11,10 @@ pub enum ExecutionTargetType {
     Dify,
     Generic,
     Example,
+    /// 本地命令/子进程（stdin/stdout JSON 协议）
+    Command,
+    /// 通用 HTTP Webhook（请求体模板 + JSONPath/JSON Pointer 提取输出）
+    Http,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, ToSchema)]
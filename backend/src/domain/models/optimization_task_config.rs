@@ -9,6 +9,7 @@ use ts_rs::TS;
 use utoipa::ToSchema;
 
 use super::diversity_analysis::DiversityConfig;
+use crate::domain::types::ExecutionTargetConfig;
 
 pub const OPTIMIZATION_TASK_CONFIG_SCHEMA_VERSION: u32 = 1;
 
@@ -42,6 +43,34 @@ pub const OPTIMIZATION_TASK_CONFIG_MAX_CONCURRENCY_MIN: u32 = 1;
 pub const OPTIMIZATION_TASK_CONFIG_MAX_CONCURRENCY_MAX: u32 = 64;
 pub const OPTIMIZATION_TASK_CONFIG_MAX_CONCURRENCY_DEFAULT: u32 = 4;
 
+pub const OPTIMIZATION_TASK_CONFIG_EXECUTION_CACHE_TTL_SECONDS_MIN: u32 = 60;
+pub const OPTIMIZATION_TASK_CONFIG_EXECUTION_CACHE_TTL_SECONDS_MAX: u32 = 30 * 24 * 3600;
+pub const OPTIMIZATION_TASK_CONFIG_EXECUTION_CACHE_TTL_SECONDS_DEFAULT: u32 = 7 * 24 * 3600;
+
+pub const OPTIMIZATION_TASK_CONFIG_EXECUTION_MAX_RETRIES_MAX: u32 = 5;
+pub const OPTIMIZATION_TASK_CONFIG_EXECUTION_MAX_RETRIES_DEFAULT: u32 = 1;
+pub const OPTIMIZATION_TASK_CONFIG_EXECUTION_MAX_FAILURE_PERCENT_MAX: u8 = 100;
+pub const OPTIMIZATION_TASK_CONFIG_EXECUTION_MAX_FAILURE_PERCENT_DEFAULT: u8 = 20;
+
+pub const OPTIMIZATION_TASK_CONFIG_COMMAND_TIMEOUT_MS_MIN: u64 = 100;
+pub const OPTIMIZATION_TASK_CONFIG_COMMAND_TIMEOUT_MS_MAX: u64 = 10 * 60 * 1000;
+pub const OPTIMIZATION_TASK_CONFIG_COMMAND_TIMEOUT_MS_DEFAULT: u64 = 30 * 1000;
+pub const OPTIMIZATION_TASK_CONFIG_COMMAND_PROGRAM_MAX_LEN: usize = 512;
+pub const OPTIMIZATION_TASK_CONFIG_COMMAND_ARGS_MAX: usize = 32;
+pub const OPTIMIZATION_TASK_CONFIG_COMMAND_ENV_ALLOWLIST_MAX: usize = 32;
+
+pub const OPTIMIZATION_TASK_CONFIG_HTTP_TIMEOUT_MS_MIN: u64 = 100;
+pub const OPTIMIZATION_TASK_CONFIG_HTTP_TIMEOUT_MS_MAX: u64 = 10 * 60 * 1000;
+pub const OPTIMIZATION_TASK_CONFIG_HTTP_TIMEOUT_MS_DEFAULT: u64 = 60 * 1000;
+pub const OPTIMIZATION_TASK_CONFIG_HTTP_URL_MAX_LEN: usize = 2048;
+pub const OPTIMIZATION_TASK_CONFIG_HTTP_HEADERS_MAX: usize = 32;
+pub const OPTIMIZATION_TASK_CONFIG_HTTP_BODY_TEMPLATE_MAX_BYTES: usize = 8 * 1024;
+pub const OPTIMIZATION_TASK_CONFIG_HTTP_PATH_MAX_LEN: usize = 256;
+pub const OPTIMIZATION_TASK_CONFIG_HTTP_METHODS: [&str; 4] = ["GET", "POST", "PUT", "PATCH"];
+
+/// HTTP 请求头中引用凭证的占位符前缀：`{{credential.<credential_type>}}`
+pub const HTTP_CREDENTIAL_PLACEHOLDER_PREFIX: &str = "{{credential.";
+
 /// 防止 config_json 膨胀（未来可根据产品需要调整）
 pub const OPTIMIZATION_TASK_CONFIG_MAX_JSON_BYTES: usize = 32 * 1024; // 32KB
 
@@ -183,6 +212,304 @@ pub struct TeacherLlmConfig {
     pub model_id: Option<String>,
 }
 
+/// 执行结果缓存配置
+///
+/// 命中条件：规范化 Prompt + 规范化输入 + 执行目标配置（不含密钥）完全一致，且未过期。
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(default, rename_all = "snake_case")]
+#[ts(export_to = "models/")]
+pub struct ExecutionCacheConfig {
+    /// 是否启用（默认关闭：执行目标可能非确定性，需用户显式开启）
+    pub enabled: bool,
+    /// 缓存有效期（秒）
+    pub ttl_seconds: u32,
+}
+
+impl Default for ExecutionCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_seconds: OPTIMIZATION_TASK_CONFIG_EXECUTION_CACHE_TTL_SECONDS_DEFAULT,
+        }
+    }
+}
+
+/// 用例级执行容错配置
+///
+/// 单条用例执行失败不再中止整批：可重试错误（网络 / 超时 / 上游）先按 `max_retries` 重试，
+/// 仍失败的用例记为未通过（失败维度 `execution`）；仅当失败用例占比超过阈值时中止本轮。
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(default, rename_all = "snake_case")]
+#[ts(export_to = "models/")]
+pub struct ExecutionRetryConfig {
+    /// 单条用例的最大重试次数（0 表示不重试）
+    pub max_retries: u32,
+    /// 允许的失败用例占比（百分比）；超过时中止本轮迭代，0 表示任一失败即中止
+    pub max_failure_percent: u8,
+}
+
+impl Default for ExecutionRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: OPTIMIZATION_TASK_CONFIG_EXECUTION_MAX_RETRIES_DEFAULT,
+            max_failure_percent: OPTIMIZATION_TASK_CONFIG_EXECUTION_MAX_FAILURE_PERCENT_DEFAULT,
+        }
+    }
+}
+
+impl ExecutionRetryConfig {
+    /// 失败用例数是否超过允许占比
+    pub fn exceeds_failure_threshold(&self, failed: usize, total: usize) -> bool {
+        failed > 0 && failed * 100 > total * self.max_failure_percent as usize
+    }
+}
+
+/// 本地命令执行目标配置（仅 execution_target_type = command 时使用）
+///
+/// 安全边界：program 还必须出现在服务端白名单 `COMMAND_TARGET_ALLOWED_PROGRAMS` 中才会被执行。
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(default, rename_all = "snake_case")]
+#[ts(export_to = "models/")]
+pub struct CommandTargetConfig {
+    pub program: String,
+    pub args: Vec<String>,
+    #[ts(type = "number")]
+    pub timeout_ms: u64,
+    pub env_allowlist: Vec<String>,
+    /// 并发上限；None 表示沿用任务级 max_concurrency
+    pub max_concurrency: Option<u32>,
+}
+
+impl Default for CommandTargetConfig {
+    fn default() -> Self {
+        Self {
+            program: String::new(),
+            args: Vec::new(),
+            timeout_ms: OPTIMIZATION_TASK_CONFIG_COMMAND_TIMEOUT_MS_DEFAULT,
+            env_allowlist: Vec::new(),
+            max_concurrency: None,
+        }
+    }
+}
+
+impl CommandTargetConfig {
+    pub fn to_execution_target_config(&self) -> ExecutionTargetConfig {
+        ExecutionTargetConfig::Command {
+            program: self.program.trim().to_string(),
+            args: self.args.clone(),
+            timeout_ms: self.timeout_ms,
+            env_allowlist: self.env_allowlist.clone(),
+            max_concurrency: self.max_concurrency,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.program.chars().count() > OPTIMIZATION_TASK_CONFIG_COMMAND_PROGRAM_MAX_LEN {
+            return Err(format!(
+                "命令执行目标 program 过长（最多 {} 字符）",
+                OPTIMIZATION_TASK_CONFIG_COMMAND_PROGRAM_MAX_LEN
+            ));
+        }
+        if self.program.chars().any(|c| c.is_control()) {
+            return Err("命令执行目标 program 不允许包含控制字符".to_string());
+        }
+        if self.args.len() > OPTIMIZATION_TASK_CONFIG_COMMAND_ARGS_MAX {
+            return Err(format!(
+                "命令执行目标 args 最多 {} 个",
+                OPTIMIZATION_TASK_CONFIG_COMMAND_ARGS_MAX
+            ));
+        }
+        if self.args.iter().any(|a| a.contains('\0')) {
+            return Err("命令执行目标 args 不允许包含 NUL 字符".to_string());
+        }
+        if !(OPTIMIZATION_TASK_CONFIG_COMMAND_TIMEOUT_MS_MIN
+            ..=OPTIMIZATION_TASK_CONFIG_COMMAND_TIMEOUT_MS_MAX)
+            .contains(&self.timeout_ms)
+        {
+            return Err(format!(
+                "命令执行目标超时仅允许 {}-{} 毫秒",
+                OPTIMIZATION_TASK_CONFIG_COMMAND_TIMEOUT_MS_MIN,
+                OPTIMIZATION_TASK_CONFIG_COMMAND_TIMEOUT_MS_MAX
+            ));
+        }
+        if self.env_allowlist.len() > OPTIMIZATION_TASK_CONFIG_COMMAND_ENV_ALLOWLIST_MAX {
+            return Err(format!(
+                "命令执行目标 env_allowlist 最多 {} 项",
+                OPTIMIZATION_TASK_CONFIG_COMMAND_ENV_ALLOWLIST_MAX
+            ));
+        }
+        if self.env_allowlist.iter().any(|name| {
+            name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }) {
+            return Err(
+                "命令执行目标 env_allowlist 仅允许字母、数字与下划线组成的变量名".to_string(),
+            );
+        }
+        if let Some(max_concurrency) = self.max_concurrency {
+            if !(OPTIMIZATION_TASK_CONFIG_MAX_CONCURRENCY_MIN
+                ..=OPTIMIZATION_TASK_CONFIG_MAX_CONCURRENCY_MAX)
+                .contains(&max_concurrency)
+            {
+                return Err(format!(
+                    "命令执行目标并发上限仅允许 {}-{}",
+                    OPTIMIZATION_TASK_CONFIG_MAX_CONCURRENCY_MIN,
+                    OPTIMIZATION_TASK_CONFIG_MAX_CONCURRENCY_MAX
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 通用 HTTP Webhook 执行目标配置（仅 execution_target_type = http 时使用）
+///
+/// 请求头中的 `{{credential.dify}}` / `{{credential.generic_llm}}` 在运行时替换为 CredentialRepo 中解密的凭证，
+/// 配置本身不保存任何密钥。
+#[derive(Debug, Clone, Serialize, Deserialize, TS, ToSchema)]
+#[serde(default, rename_all = "snake_case")]
+#[ts(export_to = "models/")]
+pub struct HttpTargetConfig {
+    pub method: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    /// 渲染后必须为合法 JSON；`{{prompt}}` / `{{input}}` / `{{input.<key>}}` / `{{test_case_id}}` 以 JSON 字面量替换
+    pub body_template: String,
+    /// `$.a.b[0]`（JSONPath 子集）或 `/a/b/0`（JSON Pointer）
+    pub output_path: String,
+    pub usage_path: Option<String>,
+    #[ts(type = "number")]
+    pub timeout_ms: u64,
+}
+
+impl Default for HttpTargetConfig {
+    fn default() -> Self {
+        Self {
+            method: "POST".to_string(),
+            url: String::new(),
+            headers: BTreeMap::new(),
+            body_template: r#"{"prompt": {{prompt}}, "input": {{input}}}"#.to_string(),
+            output_path: "$.output".to_string(),
+            usage_path: None,
+            timeout_ms: OPTIMIZATION_TASK_CONFIG_HTTP_TIMEOUT_MS_DEFAULT,
+        }
+    }
+}
+
+impl HttpTargetConfig {
+    pub fn to_execution_target_config(&self) -> ExecutionTargetConfig {
+        ExecutionTargetConfig::Http {
+            method: self.method.trim().to_ascii_uppercase(),
+            url: self.url.trim().to_string(),
+            headers: self.headers.clone(),
+            body_template: self.body_template.clone(),
+            output_path: self.output_path.trim().to_string(),
+            usage_path: self
+                .usage_path
+                .as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+            timeout_ms: self.timeout_ms,
+            credentials: BTreeMap::new(),
+        }
+    }
+
+    /// 请求头中引用的凭证类型（去重、有序）。
+    pub fn referenced_credentials(&self) -> Vec<String> {
+        let mut out: Vec<String> = self
+            .headers
+            .values()
+            .flat_map(|value| credential_placeholders(value))
+            .collect();
+        out.sort();
+        out.dedup();
+        out
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let method = self.method.trim().to_ascii_uppercase();
+        if !OPTIMIZATION_TASK_CONFIG_HTTP_METHODS.contains(&method.as_str()) {
+            return Err(format!(
+                "HTTP 执行目标 method 仅允许 {}",
+                OPTIMIZATION_TASK_CONFIG_HTTP_METHODS.join(" | ")
+            ));
+        }
+        if self.url.chars().count() > OPTIMIZATION_TASK_CONFIG_HTTP_URL_MAX_LEN {
+            return Err(format!(
+                "HTTP 执行目标 url 过长（最多 {} 字符）",
+                OPTIMIZATION_TASK_CONFIG_HTTP_URL_MAX_LEN
+            ));
+        }
+        if self.headers.len() > OPTIMIZATION_TASK_CONFIG_HTTP_HEADERS_MAX {
+            return Err(format!(
+                "HTTP 执行目标 headers 最多 {} 项",
+                OPTIMIZATION_TASK_CONFIG_HTTP_HEADERS_MAX
+            ));
+        }
+        for (name, value) in &self.headers {
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err("HTTP 执行目标 header 名仅允许字母、数字、- 与 _".to_string());
+            }
+            if value.chars().any(|c| c.is_control()) {
+                return Err("HTTP 执行目标 header 值不允许包含控制字符".to_string());
+            }
+            if credential_placeholders(value).any(|name| name.is_empty()) {
+                return Err("HTTP 执行目标 header 中的凭证占位符缺少凭证类型".to_string());
+            }
+        }
+        if self.body_template.len() > OPTIMIZATION_TASK_CONFIG_HTTP_BODY_TEMPLATE_MAX_BYTES {
+            return Err(format!(
+                "HTTP 执行目标 body_template 过大（最多 {} 字节）",
+                OPTIMIZATION_TASK_CONFIG_HTTP_BODY_TEMPLATE_MAX_BYTES
+            ));
+        }
+        validate_http_extract_path(&self.output_path, "output_path")?;
+        if let Some(usage_path) = self.usage_path.as_deref().filter(|s| !s.trim().is_empty()) {
+            validate_http_extract_path(usage_path, "usage_path")?;
+        }
+        if !(OPTIMIZATION_TASK_CONFIG_HTTP_TIMEOUT_MS_MIN
+            ..=OPTIMIZATION_TASK_CONFIG_HTTP_TIMEOUT_MS_MAX)
+            .contains(&self.timeout_ms)
+        {
+            return Err(format!(
+                "HTTP 执行目标超时仅允许 {}-{} 毫秒",
+                OPTIMIZATION_TASK_CONFIG_HTTP_TIMEOUT_MS_MIN,
+                OPTIMIZATION_TASK_CONFIG_HTTP_TIMEOUT_MS_MAX
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn credential_placeholders(value: &str) -> impl Iterator<Item = String> + '_ {
+    value
+        .match_indices(HTTP_CREDENTIAL_PLACEHOLDER_PREFIX)
+        .filter_map(move |(idx, prefix)| {
+            let rest = &value[idx + prefix.len()..];
+            rest.find("}}").map(|end| rest[..end].trim().to_string())
+        })
+}
+
+fn validate_http_extract_path(path: &str, field: &str) -> Result<(), String> {
+    let path = path.trim();
+    if path.chars().count() > OPTIMIZATION_TASK_CONFIG_HTTP_PATH_MAX_LEN {
+        return Err(format!(
+            "HTTP 执行目标 {field} 过长（最多 {} 字符）",
+            OPTIMIZATION_TASK_CONFIG_HTTP_PATH_MAX_LEN
+        ));
+    }
+    if !path.is_empty() && !path.starts_with('$') && !path.starts_with('/') {
+        return Err(format!(
+            "HTTP 执行目标 {field} 必须以 $（JSONPath）或 /（JSON Pointer）开头"
+        ));
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, ToSchema, Default)]
 #[serde(rename_all = "snake_case")]
 #[ts(export_to = "models/")]
@@ -261,6 +588,17 @@ pub struct OptimizationTaskConfig {
     #[serde(default)]
     pub teacher_llm: TeacherLlmConfig,
     pub advanced_data_split: AdvancedDataSplitConfig,
+    #[serde(default)]
+    pub execution_cache: ExecutionCacheConfig,
+    #[serde(default)]
+    pub execution_retry: ExecutionRetryConfig,
+    #[serde(default)]
+    pub command_target: CommandTargetConfig,
+    #[serde(default)]
+    pub http_target: HttpTargetConfig,
+    /// 执行时使用的命名凭证 ID（None 表示使用该类型的默认凭证）
+    #[serde(default)]
+    pub execution_credential_id: Option<String>,
 }
 
 impl Default for OptimizationTaskConfig {
@@ -280,6 +618,11 @@ impl Default for OptimizationTaskConfig {
             diversity_config: DiversityConfig::default(),
             teacher_llm: TeacherLlmConfig::default(),
             advanced_data_split: AdvancedDataSplitConfig::default(),
+            execution_cache: ExecutionCacheConfig::default(),
+            execution_retry: ExecutionRetryConfig::default(),
+            command_target: CommandTargetConfig::default(),
+            http_target: HttpTargetConfig::default(),
+            execution_credential_id: None,
         }
     }
 }
@@ -294,6 +637,10 @@ impl OptimizationTaskConfig {
     pub fn normalized(mut self) -> Self {
         self.initial_prompt = normalize_initial_prompt(self.initial_prompt);
         self.teacher_llm.model_id = normalize_teacher_llm_model_id(self.teacher_llm.model_id);
+        self.execution_credential_id = self
+            .execution_credential_id
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty());
         self
     }
 
@@ -446,6 +793,33 @@ impl OptimizationTaskConfig {
             return Err("多样性告警阈值仅允许 0.1-0.9".to_string());
         }
 
+        if self.execution_cache.enabled
+            && !(OPTIMIZATION_TASK_CONFIG_EXECUTION_CACHE_TTL_SECONDS_MIN
+                ..=OPTIMIZATION_TASK_CONFIG_EXECUTION_CACHE_TTL_SECONDS_MAX)
+                .contains(&self.execution_cache.ttl_seconds)
+        {
+            return Err(format!(
+                "执行结果缓存有效期仅允许 {}-{} 秒",
+                OPTIMIZATION_TASK_CONFIG_EXECUTION_CACHE_TTL_SECONDS_MIN,
+                OPTIMIZATION_TASK_CONFIG_EXECUTION_CACHE_TTL_SECONDS_MAX
+            ));
+        }
+
+        if self.execution_retry.max_retries > OPTIMIZATION_TASK_CONFIG_EXECUTION_MAX_RETRIES_MAX {
+            return Err(format!(
+                "单用例重试次数仅允许 0-{}",
+                OPTIMIZATION_TASK_CONFIG_EXECUTION_MAX_RETRIES_MAX
+            ));
+        }
+        if self.execution_retry.max_failure_percent
+            > OPTIMIZATION_TASK_CONFIG_EXECUTION_MAX_FAILURE_PERCENT_MAX
+        {
+            return Err("失败用例占比阈值仅允许 0-100".to_string());
+        }
+
+        self.command_target.validate()?;
+        self.http_target.validate()?;
+
         Ok(())
     }
 }
@@ -507,6 +881,16 @@ struct OptimizationTaskConfigStorage {
     #[serde(default)]
     pub teacher_llm: TeacherLlmConfig,
     pub advanced_data_split: AdvancedDataSplitConfig,
+    #[serde(default)]
+    pub execution_cache: ExecutionCacheConfig,
+    #[serde(default)]
+    pub execution_retry: ExecutionRetryConfig,
+    #[serde(default)]
+    pub command_target: CommandTargetConfig,
+    #[serde(default)]
+    pub http_target: HttpTargetConfig,
+    #[serde(default)]
+    pub execution_credential_id: Option<String>,
     #[serde(flatten)]
     pub extra: BTreeMap<String, serde_json::Value>,
 }
@@ -529,6 +913,11 @@ impl Default for OptimizationTaskConfigStorage {
             diversity_config: base.diversity_config,
             teacher_llm: base.teacher_llm,
             advanced_data_split: base.advanced_data_split,
+            execution_cache: base.execution_cache,
+            execution_retry: base.execution_retry,
+            command_target: base.command_target,
+            http_target: base.http_target,
+            execution_credential_id: base.execution_credential_id,
             extra: BTreeMap::new(),
         }
     }
@@ -576,6 +965,11 @@ impl OptimizationTaskConfigStorage {
             diversity_config: self.diversity_config,
             teacher_llm: self.teacher_llm,
             advanced_data_split: self.advanced_data_split,
+            execution_cache: self.execution_cache,
+            execution_retry: self.execution_retry,
+            command_target: self.command_target,
+            http_target: self.http_target,
+            execution_credential_id: self.execution_credential_id,
         }
     }
 
@@ -598,6 +992,11 @@ impl OptimizationTaskConfigStorage {
             diversity_config: config.diversity_config,
             teacher_llm: config.teacher_llm,
             advanced_data_split: config.advanced_data_split,
+            execution_cache: config.execution_cache,
+            execution_retry: config.execution_retry,
+            command_target: config.command_target,
+            http_target: config.http_target,
+            execution_credential_id: config.execution_credential_id,
             extra: existing.extra,
         }
     }
@@ -244,8 +244,9 @@ pub struct PromptCandidate {
     pub id: String,
     /// Prompt 内容
     pub content: String,
-    /// 综合评分 0.0-1.0
-    pub score: f64,
+    /// 综合评分 0.0-1.0（尚未评估时为 None，如多样性注入的探索候选）
+    #[serde(default)]
+    pub score: Option<f64>,
     /// 来源（首次生成 / 规律更新 / 表达优化）
     pub source: CandidateSource,
     /// 失败指纹（用于去重）
@@ -11,6 +11,12 @@ use serde::{Deserialize, Serialize};
 /// 这些指标被设计为 `[0, 1]`，使用绝对误差足够。
 pub const METRIC_EPS: f64 = 1e-12;
 
+/// 综合分中通过率的权重（通过率优先）。
+pub const COMBINED_SCORE_PASS_RATE_WEIGHT: f64 = 0.7;
+
+/// 综合分中均分的权重。
+pub const COMBINED_SCORE_MEAN_SCORE_WEIGHT: f64 = 0.3;
+
 /// Layer 4：候选排序（来自 Layer 3 `rank_candidates` 输出）。
 pub const EXT_CANDIDATE_RANKING: &str = "layer4.candidate_ranking";
 pub const EXT_BEST_CANDIDATE_INDEX: &str = "layer4.best_candidate_index";
@@ -33,6 +39,27 @@ pub const EXT_FAILURE_ARCHIVE: &str = "layer4.failure_archive";
 /// 形状：number（u32）
 pub const EXT_CONSECUTIVE_NO_IMPROVEMENT: &str = "layer4.consecutive_no_improvement";
 
+/// Layer 4：连续无提升计数最近一次计入的迭代轮次（同一轮只计一次）。
+///
+/// 形状：number（u32）
+pub const EXT_CONSECUTIVE_NO_IMPROVEMENT_ITERATION: &str =
+    "layer4.consecutive_no_improvement_iteration";
+
+/// Layer 4：多样性注入记录（用于衡量注入候选的实际效果）。
+///
+/// 形状：`Vec<DiversityInjectionRecord>`；编排层注入时追加，下一轮评估后回填 `score_after`。
+pub const EXT_DIVERSITY_INJECTIONS: &str = "layer4.diversity_injections";
+
+/// Layer 1：执行结果缓存统计（由 IterationEngine 在 run_tests 中累加，跨迭代累计）。
+///
+/// 形状：`ExecutionCacheStats`
+pub const EXT_EXECUTION_CACHE_STATS: &str = "layer1.execution_cache_stats";
+
+/// Layer 1：并行执行的自适应并发统计（由 IterationEngine 在每批并行执行后写入，下一批沿用）。
+///
+/// 形状：`ExecutionConcurrencyStats`
+pub const EXT_EXECUTION_CONCURRENCY: &str = "layer1.execution_concurrency";
+
 /// 失败档案条目上限（FIFO 丢弃最旧；避免无界增长）。
 pub const FAILURE_ARCHIVE_MAX_ENTRIES: usize = 200;
 
@@ -63,6 +90,11 @@ pub const EXT_USER_GUIDANCE: &str = "user_guidance";
 /// 任务模式（fixed/creative），由编排层注入用于特性开关。
 pub const EXT_TASK_MODE: &str = "task_mode";
 
+/// 任务所属用户（工作区 owner）ID，由编排层按任务记录注入。
+///
+/// 执行结果缓存按此分区；缺失时跳过缓存，不会落入共享分区。
+pub const EXT_TASK_OWNER_ID: &str = "task.owner_user_id";
+
 /// Layer 4：候选统计（由编排层根据 Layer 3 的统计口径注入）。
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct CandidateStats {
@@ -70,6 +102,81 @@ pub struct CandidateStats {
     pub mean_score: f64,
 }
 
+impl CandidateStats {
+    /// 通过率优先、均分次之的严格优于判定（编排层与 Layer 4 共用此唯一口径）。
+    pub fn is_better_than(&self, other: &CandidateStats) -> bool {
+        if self.pass_rate > other.pass_rate + METRIC_EPS {
+            return true;
+        }
+        (self.pass_rate - other.pass_rate).abs() <= METRIC_EPS
+            && self.mean_score > other.mean_score + METRIC_EPS
+    }
+
+    /// 综合分（`OptimizationResult.primary.score` 口径）：与“通过率优先，其次均分”保持一致的单调性。
+    pub fn combined_score(&self) -> f64 {
+        let clamp_01 = |v: f64| {
+            if v.is_finite() {
+                v.clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+        (clamp_01(self.pass_rate) * COMBINED_SCORE_PASS_RATE_WEIGHT
+            + clamp_01(self.mean_score) * COMBINED_SCORE_MEAN_SCORE_WEIGHT)
+            .clamp(0.0, 1.0)
+    }
+}
+
+/// 单次多样性注入的记录（只保存指纹与分数，不保存 Prompt 原文）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiversityInjectionRecord {
+    /// 注入发生的迭代轮次
+    pub iteration: u32,
+    /// 采用的策略（`DiversityStrategy::as_str`）
+    pub strategy: String,
+    /// 是否由老师模型生成
+    pub via_teacher: bool,
+    /// 被采用候选的指纹
+    pub fingerprint: String,
+    /// 注入前 current_prompt 的综合分
+    pub score_before: f64,
+    /// 注入候选在下一轮评估后的综合分（未评估时为 None）
+    #[serde(default)]
+    pub score_after: Option<f64>,
+}
+
+/// 执行结果缓存的命中统计（任务级累计）。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ExecutionCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// 读写缓存失败次数（失败时降级为直接执行，不影响结果）
+    pub errors: u64,
+}
+
+impl ExecutionCacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / total as f64
+    }
+}
+
+/// 自适应并发统计（任务级；`effective_concurrency` 为最近一批结束时的并发上限）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionConcurrencyStats {
+    /// 并发上限（任务级 max_concurrency 与执行目标上限的较小值）
+    pub max_concurrency: u32,
+    /// 当前有效并发
+    pub effective_concurrency: u32,
+    /// 最近一批的峰值在途请求数
+    pub peak_in_flight: u32,
+    /// 累计退避次数
+    pub backoff_count: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,6 +196,7 @@ mod tests {
             EXT_BEST_CANDIDATE_STATS,
             EXT_CURRENT_PROMPT_STATS,
             EXT_CONSECUTIVE_NO_IMPROVEMENT,
+            EXT_CONSECUTIVE_NO_IMPROVEMENT_ITERATION,
             EXT_RECENT_PRIMARY_SCORES,
             EXT_FAILURE_ARCHIVE,
             EXT_CANDIDATE_RANKING,
@@ -99,6 +207,10 @@ mod tests {
             EXT_USER_GUIDANCE,
             EXT_BRANCH_ID,
             EXT_TASK_MODE,
+            EXT_TASK_OWNER_ID,
+            EXT_DIVERSITY_INJECTIONS,
+            EXT_EXECUTION_CACHE_STATS,
+            EXT_EXECUTION_CONCURRENCY,
         ];
         let unique: std::collections::HashSet<_> = keys.iter().collect();
         assert_eq!(keys.len(), unique.len(), "Extension keys must be unique");
@@ -91,6 +91,10 @@ pub struct IterationHistorySummary {
     pub passed_cases: i32,
     /// 迭代状态
     pub status: IterationStatus,
+    /// 本轮结束时的自适应有效并发（仅并行执行的轮次有值）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub effective_concurrency: Option<u32>,
 }
 
 /// 历史迭代详情响应
@@ -192,12 +196,14 @@ mod tests {
             total_cases: 10,
             passed_cases: 8,
             status: IterationStatus::Completed,
+            effective_concurrency: Some(4),
         };
 
         let json = serde_json::to_string(&summary).unwrap();
         assert!(json.contains("\"id\":\"iter-1\""));
         assert!(json.contains("\"round\":1"));
         assert!(json.contains("\"passRate\":0.85"));
+        assert!(json.contains("\"effectiveConcurrency\":4"));
     }
 
     #[test]
@@ -11,11 +11,15 @@ pub use artifacts::{
     UserGuidance,
 };
 pub use extensions::{
-    CandidateStats, EXT_BEST_CANDIDATE_INDEX, EXT_BEST_CANDIDATE_PROMPT, EXT_BEST_CANDIDATE_STATS,
-    EXT_BRANCH_ID, EXT_CANDIDATE_RANKING, EXT_CONSECUTIVE_NO_IMPROVEMENT, EXT_CURRENT_PROMPT_STATS,
-    EXT_DIVERSITY_ANALYSIS, EXT_EVALUATIONS_BY_TEST_CASE_ID, EXT_FAILURE_ARCHIVE,
-    EXT_PREV_ITERATION_STATE, EXT_RECENT_PRIMARY_SCORES, EXT_TASK_MODE, EXT_USER_GUIDANCE,
-    EXTRA_ADOPT_BEST_CANDIDATE, FAILURE_ARCHIVE_MAX_ENTRIES, METRIC_EPS,
+    COMBINED_SCORE_MEAN_SCORE_WEIGHT, COMBINED_SCORE_PASS_RATE_WEIGHT, CandidateStats,
+    DiversityInjectionRecord, EXT_BEST_CANDIDATE_INDEX, EXT_BEST_CANDIDATE_PROMPT,
+    EXT_BEST_CANDIDATE_STATS, EXT_BRANCH_ID, EXT_CANDIDATE_RANKING, EXT_CONSECUTIVE_NO_IMPROVEMENT,
+    EXT_CONSECUTIVE_NO_IMPROVEMENT_ITERATION, EXT_CURRENT_PROMPT_STATS, EXT_DIVERSITY_ANALYSIS,
+    EXT_DIVERSITY_INJECTIONS, EXT_EVALUATIONS_BY_TEST_CASE_ID, EXT_EXECUTION_CACHE_STATS,
+    EXT_EXECUTION_CONCURRENCY, EXT_FAILURE_ARCHIVE, EXT_PREV_ITERATION_STATE,
+    EXT_RECENT_PRIMARY_SCORES, EXT_TASK_MODE, EXT_TASK_OWNER_ID, EXT_USER_GUIDANCE,
+    EXTRA_ADOPT_BEST_CANDIDATE, ExecutionCacheStats, ExecutionConcurrencyStats,
+    FAILURE_ARCHIVE_MAX_ENTRIES, METRIC_EPS,
 };
 pub use iteration_control::{
     AddRoundsRequest, AddRoundsResponse, CandidatePromptListResponse, CandidatePromptSummary,
@@ -26,7 +30,7 @@ pub use iteration_history::{
     unix_ms_to_iso8601,
 };
 pub use optimization_context::{
-    ExecutionTargetConfig, OptimizationConfig, OptimizationContext, OscillationAction,
-    OscillationConfig, OutputConfig, OutputStrategy, RacingConfig, RuleConfig, RunControlState,
-    RunControlStateTransitionError, SplitStrategy,
+    DifyAppType, DifyResponseMode, ExecutionTargetConfig, OptimizationConfig, OptimizationContext,
+    OscillationAction, OscillationConfig, OutputConfig, OutputStrategy, RacingConfig, RuleConfig,
+    RunControlState, RunControlStateTransitionError, SplitStrategy,
 };
@@ -1,8 +1,9 @@
-use crate::domain::models::{Checkpoint, IterationState, RuleSystem, TestCase};
+use crate::domain::models::{Checkpoint, IterationState, LlmProviderSpec, RuleSystem, TestCase};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use thiserror::Error;
 use ts_rs::TS;
+use utoipa::ToSchema;
 
 /// 运行控制状态（与 IterationState 正交，控制整体运行/暂停/停止）
 ///
@@ -117,6 +118,32 @@ pub struct OptimizationContext {
     pub extensions: HashMap<String, serde_json::Value>,
 }
 
+/// Dify 应用类型（决定调用的 API 端点）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export_to = "models/")]
+pub enum DifyAppType {
+    /// 工作流：`/v1/workflows/run`
+    #[default]
+    Workflow,
+    /// 对话型应用（Chatflow / Agent / 聊天助手）：`/v1/chat-messages`
+    Chat,
+    /// 文本生成应用：`/v1/completion-messages`
+    Completion,
+}
+
+/// Dify 响应模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export_to = "models/")]
+pub enum DifyResponseMode {
+    /// 阻塞模式：一次性返回完整结果
+    #[default]
+    Blocking,
+    /// 流式模式（SSE）：Agent 应用必需；长耗时运行可避免网关超时
+    Streaming,
+}
+
 /// 执行目标配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ExecutionTargetConfig {
@@ -129,6 +156,16 @@ pub enum ExecutionTargetConfig {
         /// 说明：凭证应从 CredentialRepo 解密后注入，仅在本次执行生命周期内存在。
         #[serde(skip_serializing, skip_deserializing)]
         api_key: Option<String>,
+        /// 输出选择器：输出变量名（取 `data.outputs.<name>`）或 JSON Pointer（以 `/` 开头，作用于整个响应）。
+        ///
+        /// None 时按常见字段猜测输出（兼容旧配置）。
+        #[serde(default)]
+        output_selector: Option<String>,
+        /// 应用类型（旧配置缺省为工作流）
+        #[serde(default)]
+        app_type: DifyAppType,
+        #[serde(default)]
+        response_mode: DifyResponseMode,
     },
     DirectModel {
         /// OpenAI 兼容 API Base URL（例如 https://api.siliconflow.cn）。
@@ -138,9 +175,56 @@ pub enum ExecutionTargetConfig {
         /// 运行时注入的 API Key（不允许序列化/持久化）。
         #[serde(skip_serializing, skip_deserializing)]
         api_key: Option<String>,
+        /// Provider 描述（决定端点路径/鉴权头/协议格式；None 按通用 OpenAI 兼容处理，兼容旧配置）
+        #[serde(default)]
+        provider: Option<LlmProviderSpec>,
+    },
+    /// 本地命令：每个用例启动一次子进程，stdin 输入 JSON，stdout 输出 JSON。
+    Command {
+        /// 可执行程序路径（必须在服务端白名单 `COMMAND_TARGET_ALLOWED_PROGRAMS` 中）
+        program: String,
+        args: Vec<String>,
+        /// 单个用例的超时（毫秒），超时后终止子进程
+        timeout_ms: u64,
+        /// 允许透传给子进程的环境变量名（须同时在服务端白名单 `COMMAND_TARGET_ALLOWED_ENV` 中，其余一律清空）
+        env_allowlist: Vec<String>,
+        /// 并发上限（None 表示沿用任务级 max_concurrency）
+        max_concurrency: Option<u32>,
+    },
+    /// 通用 HTTP Webhook：按模板渲染请求体，按路径表达式提取输出。
+    Http {
+        /// 请求方法（GET / POST / PUT / PATCH）
+        method: String,
+        url: String,
+        /// 请求头；值中的 `{{credential.<type>}}` 在请求时替换为运行时注入的凭证
+        headers: BTreeMap<String, String>,
+        /// 请求体模板（渲染后必须为合法 JSON），支持 `{{prompt}}` / `{{input}}` / `{{input.<key>}}` / `{{test_case_id}}`
+        body_template: String,
+        /// 输出提取路径：`$.a.b[0]`（JSONPath 子集）或 `/a/b/0`（JSON Pointer）
+        output_path: String,
+        /// token 用量提取路径（可选，指向 `{prompt_tokens, completion_tokens, total_tokens}` 对象）
+        usage_path: Option<String>,
+        timeout_ms: u64,
+        /// 运行时注入的凭证（credential_type -> 明文，不允许序列化/持久化）。
+        ///
+        /// 说明：凭证应从 CredentialRepo 解密后注入，仅在本次执行生命周期内存在。
+        #[serde(skip_serializing, skip_deserializing)]
+        credentials: BTreeMap<String, String>,
     },
 }
 
+impl ExecutionTargetConfig {
+    /// 执行目标自身声明的并发上限（并行执行时与任务级 max_concurrency 取较小值）。
+    pub fn max_concurrency_cap(&self) -> Option<u32> {
+        match self {
+            Self::Command {
+                max_concurrency, ..
+            } => *max_concurrency,
+            _ => None,
+        }
+    }
+}
+
 impl Default for ExecutionTargetConfig {
     fn default() -> Self {
         Self::DirectModel {
@@ -148,6 +232,7 @@ impl Default for ExecutionTargetConfig {
             model_name: "unknown".to_string(),
             user_prompt_template: "{input}".to_string(),
             api_key: None,
+            provider: None,
         }
     }
 }
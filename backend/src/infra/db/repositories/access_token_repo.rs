@@ -0,0 +1,222 @@
+//! 个人访问令牌仓储
+//! 负责 personal_access_tokens 表的数据访问（仅保存 token 摘要）
+
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+
+/// 令牌名称最大长度
+pub const ACCESS_TOKEN_NAME_MAX_CHARS: usize = 64;
+
+/// 令牌权限范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessTokenScope {
+    /// 只读：仅允许 GET 请求
+    Read,
+    /// 运行：允许启动 / 控制任务与导出结果
+    Run,
+}
+
+impl AccessTokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessTokenScope::Read => "read",
+            AccessTokenScope::Run => "run",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "read" => Some(AccessTokenScope::Read),
+            "run" => Some(AccessTokenScope::Run),
+            _ => None,
+        }
+    }
+}
+
+/// 令牌派生密钥包装后的数据密钥
+#[derive(Debug, Clone)]
+pub struct TokenWrappedDataKey {
+    pub wrapped_key: Vec<u8>,
+    pub nonce: Vec<u8>,
+}
+
+/// 个人访问令牌记录（数据库行）
+#[derive(Debug, Clone, FromRow)]
+pub struct AccessTokenRecord {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub token_prefix: String,
+    pub token_hash: String,
+    pub scope: String,
+    pub workspace_id: Option<String>,
+    pub wrapped_data_key: Option<Vec<u8>>,
+    pub data_key_nonce: Option<Vec<u8>>,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+}
+
+impl AccessTokenRecord {
+    /// 附带的数据密钥包装（仅 run 令牌可能存在）
+    pub fn wrapped_data_key(&self) -> Option<TokenWrappedDataKey> {
+        match (&self.wrapped_data_key, &self.data_key_nonce) {
+            (Some(wrapped_key), Some(nonce)) => Some(TokenWrappedDataKey {
+                wrapped_key: wrapped_key.clone(),
+                nonce: nonce.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// 创建令牌输入
+#[derive(Debug, Clone)]
+pub struct CreateAccessTokenInput {
+    pub user_id: String,
+    pub name: String,
+    pub token_prefix: String,
+    pub token_hash: String,
+    pub scope: AccessTokenScope,
+    pub workspace_id: Option<String>,
+    pub wrapped_data_key: Option<TokenWrappedDataKey>,
+    pub expires_at: Option<i64>,
+}
+
+/// 个人访问令牌仓储错误
+#[derive(Error, Debug)]
+pub enum AccessTokenRepoError {
+    #[error("数据库错误: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+const SELECT_COLUMNS: &str = "id, user_id, name, token_prefix, token_hash, scope, workspace_id, \
+     wrapped_data_key, data_key_nonce, created_at, expires_at, last_used_at";
+
+/// 个人访问令牌仓储
+pub struct AccessTokenRepo;
+
+impl AccessTokenRepo {
+    /// 创建令牌
+    pub async fn create(
+        pool: &SqlitePool,
+        input: CreateAccessTokenInput,
+        now: i64,
+    ) -> Result<AccessTokenRecord, AccessTokenRepoError> {
+        let (wrapped_data_key, data_key_nonce) = match input.wrapped_data_key {
+            Some(wrap) => (Some(wrap.wrapped_key), Some(wrap.nonce)),
+            None => (None, None),
+        };
+        let record = AccessTokenRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: input.user_id,
+            name: input.name,
+            token_prefix: input.token_prefix,
+            token_hash: input.token_hash,
+            scope: input.scope.as_str().to_string(),
+            workspace_id: input.workspace_id,
+            wrapped_data_key,
+            data_key_nonce,
+            created_at: now,
+            expires_at: input.expires_at,
+            last_used_at: None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO personal_access_tokens (
+                id, user_id, name, token_prefix, token_hash, scope, workspace_id,
+                wrapped_data_key, data_key_nonce, created_at, expires_at, last_used_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, NULL)
+            "#,
+        )
+        .bind(&record.id)
+        .bind(&record.user_id)
+        .bind(&record.name)
+        .bind(&record.token_prefix)
+        .bind(&record.token_hash)
+        .bind(&record.scope)
+        .bind(&record.workspace_id)
+        .bind(&record.wrapped_data_key)
+        .bind(&record.data_key_nonce)
+        .bind(record.created_at)
+        .bind(record.expires_at)
+        .execute(pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// 按 token 摘要查询未过期的令牌
+    pub async fn find_active_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+        now: i64,
+    ) -> Result<Option<AccessTokenRecord>, AccessTokenRepoError> {
+        let record = sqlx::query_as::<_, AccessTokenRecord>(&format!(
+            r#"
+            SELECT {SELECT_COLUMNS}
+            FROM personal_access_tokens
+            WHERE token_hash = ?1 AND (expires_at IS NULL OR expires_at > ?2)
+            "#
+        ))
+        .bind(token_hash)
+        .bind(now)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// 列出用户的全部令牌（含已过期，最新创建的在前）
+    pub async fn list_by_user(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> Result<Vec<AccessTokenRecord>, AccessTokenRepoError> {
+        let records = sqlx::query_as::<_, AccessTokenRecord>(&format!(
+            r#"
+            SELECT {SELECT_COLUMNS}
+            FROM personal_access_tokens
+            WHERE user_id = ?1
+            ORDER BY created_at DESC
+            "#
+        ))
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// 更新最近使用时间
+    pub async fn touch(
+        pool: &SqlitePool,
+        id: &str,
+        last_used_at: i64,
+    ) -> Result<(), AccessTokenRepoError> {
+        sqlx::query("UPDATE personal_access_tokens SET last_used_at = ?1 WHERE id = ?2")
+            .bind(last_used_at)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 吊销（删除）用户的指定令牌
+    pub async fn delete_by_id(
+        pool: &SqlitePool,
+        user_id: &str,
+        id: &str,
+    ) -> Result<bool, AccessTokenRepoError> {
+        let result =
+            sqlx::query("DELETE FROM personal_access_tokens WHERE user_id = ?1 AND id = ?2")
+                .bind(user_id)
+                .bind(id)
+                .execute(pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
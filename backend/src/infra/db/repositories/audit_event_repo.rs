@@ -0,0 +1,397 @@
+//! 安全审计事件仓储
+//! 负责 audit_events 表的数据访问（仅追加；details 写入前统一脱敏）
+
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool};
+use thiserror::Error;
+
+use crate::shared::log_sanitizer::sanitize_json_value;
+
+/// 操作者用户名最大保存长度（登录失败时用户名来自客户端输入）
+const ACTOR_USERNAME_MAX_CHARS: usize = 128;
+
+/// 审计事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditEventType {
+    /// 登录成功
+    LoginSucceeded,
+    /// 登录失败（密码 / 两步验证码错误、冷却期内尝试）
+    LoginFailed,
+    /// 连续失败触发登录冷却
+    LoginLockedOut,
+    /// 凭证创建（含保存配置时写入默认凭证）
+    CredentialCreated,
+    /// 凭证更新
+    CredentialUpdated,
+    /// 凭证删除
+    CredentialDeleted,
+    /// 会话吊销（单个 / 批量）
+    SessionRevoked,
+    /// WebSocket 任务访问被拒绝
+    TaskAccessDenied,
+}
+
+impl AuditEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEventType::LoginSucceeded => "login_succeeded",
+            AuditEventType::LoginFailed => "login_failed",
+            AuditEventType::LoginLockedOut => "login_locked_out",
+            AuditEventType::CredentialCreated => "credential_created",
+            AuditEventType::CredentialUpdated => "credential_updated",
+            AuditEventType::CredentialDeleted => "credential_deleted",
+            AuditEventType::SessionRevoked => "session_revoked",
+            AuditEventType::TaskAccessDenied => "task_access_denied",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "login_succeeded" => Some(AuditEventType::LoginSucceeded),
+            "login_failed" => Some(AuditEventType::LoginFailed),
+            "login_locked_out" => Some(AuditEventType::LoginLockedOut),
+            "credential_created" => Some(AuditEventType::CredentialCreated),
+            "credential_updated" => Some(AuditEventType::CredentialUpdated),
+            "credential_deleted" => Some(AuditEventType::CredentialDeleted),
+            "session_revoked" => Some(AuditEventType::SessionRevoked),
+            "task_access_denied" => Some(AuditEventType::TaskAccessDenied),
+            _ => None,
+        }
+    }
+
+    /// 默认结果（成功类事件为 success，失败类为 failure，拒绝类为 denied）
+    pub fn default_outcome(&self) -> AuditOutcome {
+        match self {
+            AuditEventType::LoginFailed | AuditEventType::LoginLockedOut => AuditOutcome::Failure,
+            AuditEventType::TaskAccessDenied => AuditOutcome::Denied,
+            _ => AuditOutcome::Success,
+        }
+    }
+}
+
+/// 审计事件结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+    Denied,
+}
+
+impl AuditOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOutcome::Success => "success",
+            AuditOutcome::Failure => "failure",
+            AuditOutcome::Denied => "denied",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "success" => Some(AuditOutcome::Success),
+            "failure" => Some(AuditOutcome::Failure),
+            "denied" => Some(AuditOutcome::Denied),
+            _ => None,
+        }
+    }
+}
+
+/// 新审计事件
+#[derive(Debug, Clone)]
+pub struct NewAuditEvent {
+    pub event_type: AuditEventType,
+    pub outcome: AuditOutcome,
+    pub actor_user_id: Option<String>,
+    pub actor_username: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub correlation_id: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub details: Option<serde_json::Value>,
+}
+
+impl NewAuditEvent {
+    /// 以事件类型的默认结果创建，其余字段为空
+    pub fn new(event_type: AuditEventType) -> Self {
+        Self {
+            event_type,
+            outcome: event_type.default_outcome(),
+            actor_user_id: None,
+            actor_username: None,
+            ip_address: None,
+            user_agent: None,
+            correlation_id: None,
+            target_type: None,
+            target_id: None,
+            details: None,
+        }
+    }
+
+    pub fn actor(mut self, user_id: impl Into<String>) -> Self {
+        self.actor_user_id = Some(user_id.into());
+        self
+    }
+
+    pub fn actor_username(mut self, username: &str) -> Self {
+        self.actor_username = Some(username.chars().take(ACTOR_USERNAME_MAX_CHARS).collect());
+        self
+    }
+
+    pub fn target(mut self, target_type: &str, target_id: impl Into<String>) -> Self {
+        self.target_type = Some(target_type.to_string());
+        self.target_id = Some(target_id.into());
+        self
+    }
+
+    pub fn details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+/// 审计事件记录（数据库行）
+#[derive(Debug, Clone, FromRow)]
+pub struct AuditEventRecord {
+    pub id: String,
+    pub event_type: String,
+    pub outcome: String,
+    pub actor_user_id: Option<String>,
+    pub actor_username: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub correlation_id: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub details: Option<String>,
+    pub created_at: i64,
+}
+
+/// 审计事件筛选条件
+#[derive(Debug, Clone, Default)]
+pub struct AuditEventFilter {
+    pub event_types: Option<Vec<AuditEventType>>,
+    pub outcome: Option<AuditOutcome>,
+    pub actor_user_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub correlation_id: Option<String>,
+    pub time_start: Option<i64>,
+    pub time_end: Option<i64>,
+}
+
+/// 审计事件仓储错误
+#[derive(Error, Debug)]
+pub enum AuditEventRepoError {
+    #[error("数据库错误: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("JSON 序列化错误: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+const SELECT_COLUMNS: &str = "id, event_type, outcome, actor_user_id, actor_username, ip_address, \
+     user_agent, correlation_id, target_type, target_id, details, created_at";
+
+/// 审计事件仓储
+pub struct AuditEventRepo;
+
+impl AuditEventRepo {
+    /// 追加审计事件（details 经 `log_sanitizer` 脱敏后落库）
+    pub async fn append(
+        pool: &SqlitePool,
+        event: &NewAuditEvent,
+        now: i64,
+    ) -> Result<String, AuditEventRepoError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let details = event
+            .details
+            .as_ref()
+            .map(|value| serde_json::to_string(&sanitize_json_value(value)))
+            .transpose()?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_events (
+                id, event_type, outcome, actor_user_id, actor_username, ip_address,
+                user_agent, correlation_id, target_type, target_id, details, created_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            "#,
+        )
+        .bind(&id)
+        .bind(event.event_type.as_str())
+        .bind(event.outcome.as_str())
+        .bind(&event.actor_user_id)
+        .bind(&event.actor_username)
+        .bind(&event.ip_address)
+        .bind(&event.user_agent)
+        .bind(&event.correlation_id)
+        .bind(&event.target_type)
+        .bind(&event.target_id)
+        .bind(details)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// 按条件分页查询（按时间倒序）
+    pub async fn list(
+        pool: &SqlitePool,
+        filter: &AuditEventFilter,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AuditEventRecord>, AuditEventRepoError> {
+        let mut qb: QueryBuilder<Sqlite> = QueryBuilder::new("SELECT ");
+        qb.push(SELECT_COLUMNS);
+        qb.push(" FROM audit_events WHERE 1 = 1");
+        apply_filters(&mut qb, filter);
+        qb.push(" ORDER BY created_at DESC, rowid DESC LIMIT ");
+        qb.push_bind(limit as i64);
+        qb.push(" OFFSET ");
+        qb.push_bind(offset as i64);
+
+        Ok(qb.build_query_as().fetch_all(pool).await?)
+    }
+
+    /// 按条件统计
+    pub async fn count(
+        pool: &SqlitePool,
+        filter: &AuditEventFilter,
+    ) -> Result<u32, AuditEventRepoError> {
+        let mut qb: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT COUNT(*) FROM audit_events WHERE 1 = 1");
+        apply_filters(&mut qb, filter);
+
+        let row: (i64,) = qb.build_query_as().fetch_one(pool).await?;
+        Ok(row.0.max(0) as u32)
+    }
+
+    /// 清理早于 `before` 的审计事件（保留期清理，返回删除条数）
+    pub async fn delete_before(pool: &SqlitePool, before: i64) -> Result<u64, AuditEventRepoError> {
+        let result = sqlx::query("DELETE FROM audit_events WHERE created_at < ?1")
+            .bind(before)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+fn apply_filters(qb: &mut QueryBuilder<Sqlite>, filter: &AuditEventFilter) {
+    if let Some(event_types) = filter
+        .event_types
+        .as_ref()
+        .filter(|items| !items.is_empty())
+    {
+        qb.push(" AND event_type IN (");
+        let mut separated = qb.separated(", ");
+        for event_type in event_types {
+            separated.push_bind(event_type.as_str());
+        }
+        qb.push(")");
+    }
+    if let Some(outcome) = filter.outcome {
+        qb.push(" AND outcome = ");
+        qb.push_bind(outcome.as_str());
+    }
+    if let Some(actor_user_id) = filter.actor_user_id.as_ref() {
+        qb.push(" AND actor_user_id = ");
+        qb.push_bind(actor_user_id.clone());
+    }
+    if let Some(ip_address) = filter.ip_address.as_ref() {
+        qb.push(" AND ip_address = ");
+        qb.push_bind(ip_address.clone());
+    }
+    if let Some(correlation_id) = filter.correlation_id.as_ref() {
+        qb.push(" AND correlation_id = ");
+        qb.push_bind(correlation_id.clone());
+    }
+    if let Some(start) = filter.time_start {
+        qb.push(" AND created_at >= ");
+        qb.push_bind(start);
+    }
+    if let Some(end) = filter.time_end {
+        qb.push(" AND created_at <= ");
+        qb.push_bind(end);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::db::pool::create_pool;
+    use serde_json::json;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = create_pool("sqlite::memory:")
+            .await
+            .expect("创建测试数据库失败");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("运行 migrations 失败");
+        pool
+    }
+
+    #[tokio::test]
+    async fn append_sanitizes_details_and_filters() {
+        let pool = setup_test_db().await;
+
+        let login = NewAuditEvent::new(AuditEventType::LoginFailed)
+            .actor_username("alice")
+            .details(json!({"reason": "invalid_credentials", "password": "hunter2"}));
+        AuditEventRepo::append(&pool, &login, 1_000).await.unwrap();
+        let deleted = NewAuditEvent::new(AuditEventType::CredentialDeleted)
+            .actor("u1")
+            .target("credential", "c1");
+        AuditEventRepo::append(&pool, &deleted, 2_000)
+            .await
+            .unwrap();
+
+        let all = AuditEventRepo::list(&pool, &AuditEventFilter::default(), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].event_type, "credential_deleted");
+        let details: serde_json::Value =
+            serde_json::from_str(all[1].details.as_deref().unwrap()).unwrap();
+        assert_eq!(details["password"], "****");
+        assert_eq!(details["reason"], "invalid_credentials");
+
+        let failures = AuditEventFilter {
+            outcome: Some(AuditOutcome::Failure),
+            ..Default::default()
+        };
+        assert_eq!(AuditEventRepo::count(&pool, &failures).await.unwrap(), 1);
+        let by_actor = AuditEventFilter {
+            actor_user_id: Some("u1".to_string()),
+            time_start: Some(1_500),
+            ..Default::default()
+        };
+        assert_eq!(AuditEventRepo::count(&pool, &by_actor).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn audit_events_are_append_only_but_prunable() {
+        let pool = setup_test_db().await;
+        let event = NewAuditEvent::new(AuditEventType::LoginSucceeded).actor("u1");
+        let id = AuditEventRepo::append(&pool, &event, 1_000).await.unwrap();
+        AuditEventRepo::append(&pool, &event, 5_000).await.unwrap();
+
+        let updated = sqlx::query("UPDATE audit_events SET outcome = 'failure' WHERE id = ?1")
+            .bind(&id)
+            .execute(&pool)
+            .await;
+        assert!(updated.is_err());
+
+        assert_eq!(
+            AuditEventRepo::delete_before(&pool, 2_000).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            AuditEventRepo::count(&pool, &AuditEventFilter::default())
+                .await
+                .unwrap(),
+            1
+        );
+    }
+}
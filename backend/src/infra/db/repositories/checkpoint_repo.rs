@@ -146,8 +146,8 @@ impl CheckpointRepo {
                    c.archived_at, c.archive_reason, c.pass_rate_summary
             FROM checkpoints c
             JOIN optimization_tasks t ON t.id = c.task_id
-            JOIN workspaces w ON w.id = t.workspace_id
-            WHERE c.id = ?1 AND w.user_id = ?2
+            JOIN workspace_members m ON m.workspace_id = t.workspace_id
+            WHERE c.id = ?1 AND m.user_id = ?2
             "#,
         )
         .bind(checkpoint_id)
@@ -1,11 +1,18 @@
 //! API 凭证仓储
 //! 负责 api_credentials 表的数据访问
+//!
+//! 每个用户每种凭证类型可保存多条命名凭证，其中一条为默认凭证（未显式引用凭证 ID 时使用）。
 
 use sqlx::SqlitePool;
 use thiserror::Error;
 
 use crate::shared::time::now_millis;
 
+/// 默认凭证名称（旧版单凭证写入 / 首次保存配置时使用）
+pub const DEFAULT_CREDENTIAL_NAME: &str = "default";
+/// 凭证名称最大长度
+pub const CREDENTIAL_NAME_MAX_CHARS: usize = 64;
+
 /// 凭证类型
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CredentialType {
@@ -36,6 +43,8 @@ pub struct CredentialRecord {
     pub id: String,
     pub user_id: String,
     pub credential_type: String,
+    pub name: String,
+    pub is_default: bool,
     pub provider: Option<String>,
     pub base_url: String,
     pub encrypted_api_key: Vec<u8>,
@@ -45,7 +54,7 @@ pub struct CredentialRecord {
     pub updated_at: i64,
 }
 
-/// 新建或更新凭证的输入
+/// 新建或更新默认凭证的输入
 #[derive(Debug, Clone)]
 pub struct UpsertCredentialInput {
     pub user_id: String,
@@ -57,6 +66,42 @@ pub struct UpsertCredentialInput {
     pub salt: Vec<u8>,
 }
 
+/// 新建命名凭证的输入
+#[derive(Debug, Clone)]
+pub struct CreateCredentialInput {
+    pub user_id: String,
+    pub credential_type: CredentialType,
+    pub name: String,
+    pub provider: Option<String>,
+    pub base_url: String,
+    pub encrypted_api_key: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub salt: Vec<u8>,
+    /// 是否设为默认凭证（该类型尚无默认凭证时总是设为默认）
+    pub make_default: bool,
+}
+
+/// 更新命名凭证的输入
+#[derive(Debug, Clone)]
+pub struct UpdateCredentialInput {
+    pub name: String,
+    pub provider: Option<String>,
+    pub base_url: String,
+    /// 新的加密 API Key（None 表示保留原值）：(ciphertext, nonce, salt)
+    pub encrypted_api_key: Option<(Vec<u8>, Vec<u8>, Vec<u8>)>,
+}
+
+/// 重新加密后的凭证（修改登录密码时整体替换密文）
+#[derive(Debug, Clone)]
+pub struct ReencryptedCredential {
+    pub id: String,
+    /// 重新加密前的密文（用于检测并发修改）
+    pub previous_encrypted_api_key: Vec<u8>,
+    pub encrypted_api_key: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub salt: Vec<u8>,
+}
+
 /// 凭证仓储错误
 #[derive(Error, Debug)]
 pub enum CredentialRepoError {
@@ -68,59 +113,286 @@ pub enum CredentialRepoError {
         user_id: String,
         credential_type: String,
     },
+
+    #[error("凭证不存在或无权访问: {0}")]
+    ProfileNotFound(String),
+
+    #[error("凭证属于工作区其他成员，不能用于当前用户的运行，请绑定自己的凭证: {0}")]
+    NotAccessible(String),
+
+    #[error("凭证类型不匹配: id={id}, 期望类型={expected}")]
+    TypeMismatch { id: String, expected: String },
+
+    #[error("凭证名称已存在: {0}")]
+    NameConflict(String),
+}
+
+type CredentialRow = (
+    String,
+    String,
+    String,
+    String,
+    bool,
+    Option<String>,
+    String,
+    Vec<u8>,
+    Vec<u8>,
+    Vec<u8>,
+    i64,
+    i64,
+);
+
+const SELECT_COLUMNS: &str = "id, user_id, credential_type, name, is_default, provider, base_url, \
+     encrypted_api_key, nonce, salt, created_at, updated_at";
+
+fn row_to_record(row: CredentialRow) -> CredentialRecord {
+    let (
+        id,
+        user_id,
+        credential_type,
+        name,
+        is_default,
+        provider,
+        base_url,
+        encrypted_api_key,
+        nonce,
+        salt,
+        created_at,
+        updated_at,
+    ) = row;
+    CredentialRecord {
+        id,
+        user_id,
+        credential_type,
+        name,
+        is_default,
+        provider,
+        base_url,
+        encrypted_api_key,
+        nonce,
+        salt,
+        created_at,
+        updated_at,
+    }
+}
+
+fn map_unique_violation(err: sqlx::Error, name: &str) -> CredentialRepoError {
+    match &err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            CredentialRepoError::NameConflict(name.to_string())
+        }
+        _ => CredentialRepoError::DatabaseError(err),
+    }
 }
 
 /// 凭证仓储
 pub struct CredentialRepo;
 
 impl CredentialRepo {
-    /// Upsert 凭证（插入或更新）
+    /// Upsert 默认凭证（插入或更新）
     ///
-    /// 使用 `ON CONFLICT DO UPDATE` 实现 upsert，确保每个用户每种凭证类型只有一条记录。
+    /// 已有默认凭证时原地更新；否则以 [`DEFAULT_CREDENTIAL_NAME`] 新建并设为默认。
     pub async fn upsert(
         pool: &SqlitePool,
         input: UpsertCredentialInput,
+    ) -> Result<CredentialRecord, CredentialRepoError> {
+        let now = now_millis();
+        let credential_type = input.credential_type.as_str();
+        let mut tx = pool.begin().await?;
+
+        let updated = sqlx::query(
+            r#"
+            UPDATE api_credentials
+            SET provider = ?3, base_url = ?4, encrypted_api_key = ?5, nonce = ?6, salt = ?7,
+                updated_at = ?8
+            WHERE user_id = ?1 AND credential_type = ?2 AND is_default = 1
+            "#,
+        )
+        .bind(&input.user_id)
+        .bind(credential_type)
+        .bind(&input.provider)
+        .bind(&input.base_url)
+        .bind(&input.encrypted_api_key)
+        .bind(&input.nonce)
+        .bind(&input.salt)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if updated == 0 {
+            // 尚无默认凭证：新建（若同名凭证已存在则覆盖并设为默认，保留原有的 created_at）
+            sqlx::query(
+                r#"
+                INSERT INTO api_credentials (
+                    id, user_id, credential_type, name, is_default, provider, base_url,
+                    encrypted_api_key, nonce, salt, created_at, updated_at
+                )
+                VALUES (?1, ?2, ?3, ?4, 1, ?5, ?6, ?7, ?8, ?9, ?10, ?10)
+                ON CONFLICT(user_id, credential_type, name) DO UPDATE SET
+                    is_default = 1,
+                    provider = excluded.provider,
+                    base_url = excluded.base_url,
+                    encrypted_api_key = excluded.encrypted_api_key,
+                    nonce = excluded.nonce,
+                    salt = excluded.salt,
+                    updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&input.user_id)
+            .bind(credential_type)
+            .bind(DEFAULT_CREDENTIAL_NAME)
+            .bind(&input.provider)
+            .bind(&input.base_url)
+            .bind(&input.encrypted_api_key)
+            .bind(&input.nonce)
+            .bind(&input.salt)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        // 查询刚刚插入/更新的记录
+        Self::find_by_user_and_type(pool, &input.user_id, input.credential_type).await
+    }
+
+    /// 新建命名凭证
+    pub async fn create(
+        pool: &SqlitePool,
+        input: CreateCredentialInput,
     ) -> Result<CredentialRecord, CredentialRepoError> {
         let now = now_millis();
         let id = uuid::Uuid::new_v4().to_string();
         let credential_type = input.credential_type.as_str();
+        let mut tx = pool.begin().await?;
+
+        let has_default: Option<i64> = sqlx::query_scalar(
+            r#"
+            SELECT 1 FROM api_credentials
+            WHERE user_id = ?1 AND credential_type = ?2 AND is_default = 1
+            "#,
+        )
+        .bind(&input.user_id)
+        .bind(credential_type)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let is_default = input.make_default || has_default.is_none();
+        if is_default {
+            clear_default(&mut tx, &input.user_id, credential_type, now).await?;
+        }
 
-        // SQLite upsert: INSERT OR REPLACE
-        // 注意：使用 ON CONFLICT 更新时保留原有的 created_at
         sqlx::query(
             r#"
             INSERT INTO api_credentials (
-                id, user_id, credential_type, provider, base_url,
+                id, user_id, credential_type, name, is_default, provider, base_url,
                 encrypted_api_key, nonce, salt, created_at, updated_at
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
-            ON CONFLICT(user_id, credential_type) DO UPDATE SET
-                provider = excluded.provider,
-                base_url = excluded.base_url,
-                encrypted_api_key = excluded.encrypted_api_key,
-                nonce = excluded.nonce,
-                salt = excluded.salt,
-                updated_at = excluded.updated_at
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11)
             "#,
         )
         .bind(&id)
         .bind(&input.user_id)
         .bind(credential_type)
+        .bind(&input.name)
+        .bind(is_default)
         .bind(&input.provider)
         .bind(&input.base_url)
         .bind(&input.encrypted_api_key)
         .bind(&input.nonce)
         .bind(&input.salt)
         .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| map_unique_violation(e, &input.name))?;
+        tx.commit().await?;
+
+        Self::find_by_id(pool, &input.user_id, &id).await
+    }
+
+    /// 更新命名凭证（名称 / provider / base_url / 可选的新 API Key）
+    pub async fn update(
+        pool: &SqlitePool,
+        user_id: &str,
+        id: &str,
+        input: UpdateCredentialInput,
+    ) -> Result<CredentialRecord, CredentialRepoError> {
+        let now = now_millis();
+        let result = match &input.encrypted_api_key {
+            Some((ciphertext, nonce, salt)) => {
+                sqlx::query(
+                    r#"
+                    UPDATE api_credentials
+                    SET name = ?3, provider = ?4, base_url = ?5,
+                        encrypted_api_key = ?6, nonce = ?7, salt = ?8, updated_at = ?9
+                    WHERE user_id = ?1 AND id = ?2
+                    "#,
+                )
+                .bind(user_id)
+                .bind(id)
+                .bind(&input.name)
+                .bind(&input.provider)
+                .bind(&input.base_url)
+                .bind(ciphertext)
+                .bind(nonce)
+                .bind(salt)
+                .bind(now)
+                .execute(pool)
+                .await
+            }
+            None => {
+                sqlx::query(
+                    r#"
+                    UPDATE api_credentials
+                    SET name = ?3, provider = ?4, base_url = ?5, updated_at = ?6
+                    WHERE user_id = ?1 AND id = ?2
+                    "#,
+                )
+                .bind(user_id)
+                .bind(id)
+                .bind(&input.name)
+                .bind(&input.provider)
+                .bind(&input.base_url)
+                .bind(now)
+                .execute(pool)
+                .await
+            }
+        }
+        .map_err(|e| map_unique_violation(e, &input.name))?;
+
+        if result.rows_affected() == 0 {
+            return Err(CredentialRepoError::ProfileNotFound(id.to_string()));
+        }
+        Self::find_by_id(pool, user_id, id).await
+    }
+
+    /// 将指定凭证设为其类型的默认凭证
+    pub async fn set_default(
+        pool: &SqlitePool,
+        user_id: &str,
+        id: &str,
+    ) -> Result<CredentialRecord, CredentialRepoError> {
+        let record = Self::find_by_id(pool, user_id, id).await?;
+        let now = now_millis();
+        let mut tx = pool.begin().await?;
+        clear_default(&mut tx, user_id, &record.credential_type, now).await?;
+        sqlx::query(
+            r#"
+            UPDATE api_credentials SET is_default = 1, updated_at = ?3
+            WHERE user_id = ?1 AND id = ?2
+            "#,
+        )
+        .bind(user_id)
+        .bind(id)
         .bind(now)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
-
-        // 查询刚刚插入/更新的记录
-        Self::find_by_user_and_type(pool, &input.user_id, input.credential_type).await
+        tx.commit().await?;
+        Self::find_by_id(pool, user_id, id).await
     }
 
-    /// 根据用户 ID 和凭证类型查找凭证
+    /// 根据用户 ID 和凭证类型查找默认凭证
     pub async fn find_by_user_and_type(
         pool: &SqlitePool,
         user_id: &str,
@@ -128,57 +400,20 @@ impl CredentialRepo {
     ) -> Result<CredentialRecord, CredentialRepoError> {
         let type_str = credential_type.as_str();
 
-        let row = sqlx::query_as::<
-            _,
-            (
-                String,
-                String,
-                String,
-                Option<String>,
-                String,
-                Vec<u8>,
-                Vec<u8>,
-                Vec<u8>,
-                i64,
-                i64,
-            ),
-        >(
+        let row = sqlx::query_as::<_, CredentialRow>(&format!(
             r#"
-            SELECT id, user_id, credential_type, provider, base_url,
-                   encrypted_api_key, nonce, salt, created_at, updated_at
+            SELECT {SELECT_COLUMNS}
             FROM api_credentials
-            WHERE user_id = ?1 AND credential_type = ?2
-            "#,
-        )
+            WHERE user_id = ?1 AND credential_type = ?2 AND is_default = 1
+            "#
+        ))
         .bind(user_id)
         .bind(type_str)
         .fetch_optional(pool)
         .await?;
 
         match row {
-            Some((
-                id,
-                user_id,
-                credential_type,
-                provider,
-                base_url,
-                encrypted_api_key,
-                nonce,
-                salt,
-                created_at,
-                updated_at,
-            )) => Ok(CredentialRecord {
-                id,
-                user_id,
-                credential_type,
-                provider,
-                base_url,
-                encrypted_api_key,
-                nonce,
-                salt,
-                created_at,
-                updated_at,
-            }),
+            Some(row) => Ok(row_to_record(row)),
             None => Err(CredentialRepoError::NotFound {
                 user_id: user_id.to_string(),
                 credential_type: type_str.to_string(),
@@ -186,70 +421,123 @@ impl CredentialRepo {
         }
     }
 
+    /// 根据凭证 ID 查找（限定用户）
+    pub async fn find_by_id(
+        pool: &SqlitePool,
+        user_id: &str,
+        id: &str,
+    ) -> Result<CredentialRecord, CredentialRepoError> {
+        let row = sqlx::query_as::<_, CredentialRow>(&format!(
+            r#"
+            SELECT {SELECT_COLUMNS}
+            FROM api_credentials
+            WHERE user_id = ?1 AND id = ?2
+            "#
+        ))
+        .bind(user_id)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        row.map(row_to_record)
+            .ok_or_else(|| CredentialRepoError::ProfileNotFound(id.to_string()))
+    }
+
+    /// 解析执行所需凭证：指定 ID 时按 ID 查找并校验类型，否则使用默认凭证
+    pub async fn resolve(
+        pool: &SqlitePool,
+        user_id: &str,
+        credential_type: CredentialType,
+        credential_id: Option<&str>,
+    ) -> Result<CredentialRecord, CredentialRepoError> {
+        let Some(id) = credential_id.map(str::trim).filter(|s| !s.is_empty()) else {
+            return Self::find_by_user_and_type(pool, user_id, credential_type).await;
+        };
+        let record = Self::find_by_id(pool, user_id, id).await?;
+        if record.credential_type != credential_type.as_str() {
+            return Err(CredentialRepoError::TypeMismatch {
+                id: id.to_string(),
+                expected: credential_type.as_str().to_string(),
+            });
+        }
+        Ok(record)
+    }
+
+    /// 解析共享工作区中任务 / 测试集绑定的凭证
+    ///
+    /// 凭证不随工作区共享：绑定的凭证属于其他成员时返回 [`CredentialRepoError::NotAccessible`]，
+    /// 由成员改绑自己的凭证（不静默切换到其他应用 / 账号）；其他情况按 [`Self::resolve`] 报错。
+    pub async fn resolve_personal(
+        pool: &SqlitePool,
+        user_id: &str,
+        credential_type: CredentialType,
+        credential_id: Option<&str>,
+    ) -> Result<CredentialRecord, CredentialRepoError> {
+        match Self::resolve(pool, user_id, credential_type.clone(), credential_id).await {
+            Err(CredentialRepoError::ProfileNotFound(id))
+                if Self::is_owned_by_other_user(pool, user_id, &id).await? =>
+            {
+                Err(CredentialRepoError::NotAccessible(id))
+            }
+            result => result,
+        }
+    }
+
+    async fn is_owned_by_other_user(
+        pool: &SqlitePool,
+        user_id: &str,
+        id: &str,
+    ) -> Result<bool, CredentialRepoError> {
+        let exists: Option<i64> =
+            sqlx::query_scalar("SELECT 1 FROM api_credentials WHERE id = ?1 AND user_id != ?2")
+                .bind(id)
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await?;
+        Ok(exists.is_some())
+    }
+
     /// 查找用户的所有凭证
     pub async fn find_all_by_user(
         pool: &SqlitePool,
         user_id: &str,
     ) -> Result<Vec<CredentialRecord>, CredentialRepoError> {
-        let rows = sqlx::query_as::<
-            _,
-            (
-                String,
-                String,
-                String,
-                Option<String>,
-                String,
-                Vec<u8>,
-                Vec<u8>,
-                Vec<u8>,
-                i64,
-                i64,
-            ),
-        >(
+        let rows = sqlx::query_as::<_, CredentialRow>(&format!(
             r#"
-            SELECT id, user_id, credential_type, provider, base_url,
-                   encrypted_api_key, nonce, salt, created_at, updated_at
+            SELECT {SELECT_COLUMNS}
             FROM api_credentials
             WHERE user_id = ?1
-            "#,
-        )
+            ORDER BY credential_type ASC, is_default DESC, name ASC
+            "#
+        ))
         .bind(user_id)
         .fetch_all(pool)
         .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(
-                |(
-                    id,
-                    user_id,
-                    credential_type,
-                    provider,
-                    base_url,
-                    encrypted_api_key,
-                    nonce,
-                    salt,
-                    created_at,
-                    updated_at,
-                )| {
-                    CredentialRecord {
-                        id,
-                        user_id,
-                        credential_type,
-                        provider,
-                        base_url,
-                        encrypted_api_key,
-                        nonce,
-                        salt,
-                        created_at,
-                        updated_at,
-                    }
-                },
-            )
-            .collect())
+        Ok(rows.into_iter().map(row_to_record).collect())
     }
 
-    /// 删除凭证
+    /// 批量替换凭证密文（迁移到信封加密）
+    ///
+    /// 仅替换密文未被并发修改的凭证（被修改的凭证已由新写入覆盖，无需迁移）；返回实际替换数。
+    pub async fn replace_encrypted_keys(
+        pool: &SqlitePool,
+        user_id: &str,
+        credentials: &[ReencryptedCredential],
+    ) -> Result<usize, CredentialRepoError> {
+        let now = now_millis();
+        let mut tx = pool.begin().await?;
+        let mut replaced = 0;
+        for credential in credentials {
+            if replace_encrypted_key_tx(&mut tx, user_id, credential, now).await? {
+                replaced += 1;
+            }
+        }
+        tx.commit().await?;
+        Ok(replaced)
+    }
+
+    /// 删除用户某类型的全部凭证
     pub async fn delete(
         pool: &SqlitePool,
         user_id: &str,
@@ -270,4 +558,326 @@ impl CredentialRepo {
 
         Ok(result.rows_affected() > 0)
     }
+
+    /// 按 ID 删除凭证
+    ///
+    /// 删除的是默认凭证时，将同类型中最近更新的凭证提升为默认凭证。
+    pub async fn delete_by_id(
+        pool: &SqlitePool,
+        user_id: &str,
+        id: &str,
+    ) -> Result<(), CredentialRepoError> {
+        let record = Self::find_by_id(pool, user_id, id).await?;
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM api_credentials WHERE user_id = ?1 AND id = ?2")
+            .bind(user_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if record.is_default {
+            sqlx::query(
+                r#"
+                UPDATE api_credentials SET is_default = 1
+                WHERE id = (
+                    SELECT id FROM api_credentials
+                    WHERE user_id = ?1 AND credential_type = ?2
+                    ORDER BY updated_at DESC, id ASC
+                    LIMIT 1
+                )
+                "#,
+            )
+            .bind(user_id)
+            .bind(&record.credential_type)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// 在事务内替换凭证密文；密文已被并发修改时返回 `false`
+pub(crate) async fn replace_encrypted_key_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    user_id: &str,
+    credential: &ReencryptedCredential,
+    now: i64,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE api_credentials
+        SET encrypted_api_key = ?1, nonce = ?2, salt = ?3, updated_at = ?4
+        WHERE user_id = ?5 AND id = ?6 AND encrypted_api_key = ?7
+        "#,
+    )
+    .bind(&credential.encrypted_api_key)
+    .bind(&credential.nonce)
+    .bind(&credential.salt)
+    .bind(now)
+    .bind(user_id)
+    .bind(&credential.id)
+    .bind(&credential.previous_encrypted_api_key)
+    .execute(&mut **tx)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+async fn clear_default(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    user_id: &str,
+    credential_type: &str,
+    now: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE api_credentials SET is_default = 0, updated_at = ?3
+        WHERE user_id = ?1 AND credential_type = ?2 AND is_default = 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(credential_type)
+    .bind(now)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::db::pool::create_pool;
+
+    async fn setup_db() -> SqlitePool {
+        let pool = create_pool("sqlite::memory:")
+            .await
+            .expect("创建测试数据库失败");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("运行 migrations 失败");
+        pool
+    }
+
+    fn create_input(credential_type: CredentialType, name: &str) -> CreateCredentialInput {
+        CreateCredentialInput {
+            user_id: "u1".to_string(),
+            credential_type,
+            name: name.to_string(),
+            provider: None,
+            base_url: format!("https://{name}.example.com"),
+            encrypted_api_key: vec![1],
+            nonce: vec![2],
+            salt: vec![3],
+            make_default: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn first_profile_becomes_default_and_set_default_switches() {
+        let pool = setup_db().await;
+        let a = CredentialRepo::create(&pool, create_input(CredentialType::Dify, "app-a"))
+            .await
+            .unwrap();
+        let b = CredentialRepo::create(&pool, create_input(CredentialType::Dify, "app-b"))
+            .await
+            .unwrap();
+        assert!(a.is_default);
+        assert!(!b.is_default);
+
+        CredentialRepo::set_default(&pool, "u1", &b.id)
+            .await
+            .unwrap();
+        let default = CredentialRepo::find_by_user_and_type(&pool, "u1", CredentialType::Dify)
+            .await
+            .unwrap();
+        assert_eq!(default.id, b.id);
+        let a = CredentialRepo::find_by_id(&pool, "u1", &a.id)
+            .await
+            .unwrap();
+        assert!(!a.is_default);
+    }
+
+    #[tokio::test]
+    async fn replace_encrypted_keys_skips_concurrently_changed_rows() {
+        let pool = setup_db().await;
+        let a = CredentialRepo::create(&pool, create_input(CredentialType::Dify, "app-a"))
+            .await
+            .unwrap();
+        let b = CredentialRepo::create(&pool, create_input(CredentialType::Dify, "app-b"))
+            .await
+            .unwrap();
+
+        let replacement = |id: &str, previous: Vec<u8>| ReencryptedCredential {
+            id: id.to_string(),
+            previous_encrypted_api_key: previous,
+            encrypted_api_key: vec![9],
+            nonce: vec![8],
+            salt: vec![],
+        };
+        let replaced = CredentialRepo::replace_encrypted_keys(
+            &pool,
+            "u1",
+            &[replacement(&a.id, vec![1]), replacement(&b.id, vec![7])],
+        )
+        .await
+        .unwrap();
+        assert_eq!(replaced, 1);
+
+        let a = CredentialRepo::find_by_id(&pool, "u1", &a.id)
+            .await
+            .unwrap();
+        assert_eq!(a.encrypted_api_key, vec![9]);
+        assert!(a.salt.is_empty());
+        let b = CredentialRepo::find_by_id(&pool, "u1", &b.id)
+            .await
+            .unwrap();
+        assert_eq!(b.encrypted_api_key, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn deleting_default_promotes_remaining_profile() {
+        let pool = setup_db().await;
+        let a = CredentialRepo::create(&pool, create_input(CredentialType::Dify, "app-a"))
+            .await
+            .unwrap();
+        let b = CredentialRepo::create(&pool, create_input(CredentialType::Dify, "app-b"))
+            .await
+            .unwrap();
+
+        CredentialRepo::delete_by_id(&pool, "u1", &a.id)
+            .await
+            .unwrap();
+        let default = CredentialRepo::find_by_user_and_type(&pool, "u1", CredentialType::Dify)
+            .await
+            .unwrap();
+        assert_eq!(default.id, b.id);
+
+        let err = CredentialRepo::delete_by_id(&pool, "u1", &a.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CredentialRepoError::ProfileNotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn duplicate_name_within_type_is_conflict() {
+        let pool = setup_db().await;
+        CredentialRepo::create(&pool, create_input(CredentialType::Dify, "shared"))
+            .await
+            .unwrap();
+        let err = CredentialRepo::create(&pool, create_input(CredentialType::Dify, "shared"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CredentialRepoError::NameConflict(_)));
+
+        // 不同类型可以同名
+        CredentialRepo::create(&pool, create_input(CredentialType::GenericLlm, "shared"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn resolve_checks_owner_and_type() {
+        let pool = setup_db().await;
+        let dify = CredentialRepo::create(&pool, create_input(CredentialType::Dify, "app-a"))
+            .await
+            .unwrap();
+        let llm =
+            CredentialRepo::create(&pool, create_input(CredentialType::GenericLlm, "billing"))
+                .await
+                .unwrap();
+
+        let resolved = CredentialRepo::resolve(&pool, "u1", CredentialType::Dify, Some(&dify.id))
+            .await
+            .unwrap();
+        assert_eq!(resolved.id, dify.id);
+        let resolved = CredentialRepo::resolve(&pool, "u1", CredentialType::GenericLlm, None)
+            .await
+            .unwrap();
+        assert_eq!(resolved.id, llm.id);
+
+        let err = CredentialRepo::resolve(&pool, "u1", CredentialType::Dify, Some(&llm.id))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CredentialRepoError::TypeMismatch { .. }));
+        let err = CredentialRepo::resolve(&pool, "u2", CredentialType::Dify, Some(&dify.id))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CredentialRepoError::ProfileNotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn resolve_personal_rejects_bindings_owned_by_other_members() {
+        let pool = setup_db().await;
+        let owner_dify = CredentialRepo::create(&pool, create_input(CredentialType::Dify, "app-a"))
+            .await
+            .unwrap();
+        CredentialRepo::create(
+            &pool,
+            CreateCredentialInput {
+                user_id: "u2".to_string(),
+                ..create_input(CredentialType::Dify, "mine")
+            },
+        )
+        .await
+        .unwrap();
+
+        // 成员自己有默认凭证也不静默替换
+        let err = CredentialRepo::resolve_personal(
+            &pool,
+            "u2",
+            CredentialType::Dify,
+            Some(&owner_dify.id),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, CredentialRepoError::NotAccessible(id) if id == owner_dify.id));
+
+        let resolved = CredentialRepo::resolve_personal(
+            &pool,
+            "u1",
+            CredentialType::Dify,
+            Some(&owner_dify.id),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resolved.id, owner_dify.id);
+
+        let err =
+            CredentialRepo::resolve_personal(&pool, "u2", CredentialType::Dify, Some("deleted"))
+                .await
+                .unwrap_err();
+        assert!(matches!(err, CredentialRepoError::ProfileNotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn upsert_updates_default_profile_in_place() {
+        let pool = setup_db().await;
+        let named = CredentialRepo::create(&pool, create_input(CredentialType::Dify, "app-a"))
+            .await
+            .unwrap();
+        let upserted = CredentialRepo::upsert(
+            &pool,
+            UpsertCredentialInput {
+                user_id: "u1".to_string(),
+                credential_type: CredentialType::Dify,
+                provider: None,
+                base_url: "https://updated.example.com".to_string(),
+                encrypted_api_key: vec![9],
+                nonce: vec![9],
+                salt: vec![9],
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(upserted.id, named.id);
+        assert_eq!(upserted.name, "app-a");
+        assert_eq!(upserted.base_url, "https://updated.example.com");
+        assert_eq!(
+            CredentialRepo::find_all_by_user(&pool, "u1")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
 }
@@ -0,0 +1,198 @@
+use sqlx::{Row, SqlitePool};
+use thiserror::Error;
+
+use crate::domain::models::ExecutionResult;
+use crate::shared::time::now_millis;
+
+#[derive(Error, Debug)]
+pub enum ExecutionCacheRepoError {
+    #[error("数据库错误: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+
+    #[error("缓存结果解析失败: {0}")]
+    ParseError(#[from] serde_json::Error),
+}
+
+pub struct ExecutionCacheRepo;
+
+impl ExecutionCacheRepo {
+    /// 读取当前用户未过期的缓存结果（命中时累加 hit_count）。
+    ///
+    /// 返回的 `test_case_id` 为写入时的值，调用方需按当前用例覆盖。
+    pub async fn get(
+        pool: &SqlitePool,
+        user_id: &str,
+        cache_key: &str,
+    ) -> Result<Option<ExecutionResult>, ExecutionCacheRepoError> {
+        let now = now_millis();
+        let row = sqlx::query(
+            r#"
+            UPDATE execution_result_cache
+            SET hit_count = hit_count + 1
+            WHERE cache_key = ?1 AND user_id = ?2 AND expires_at > ?3
+            RETURNING result_json
+            "#,
+        )
+        .bind(cache_key)
+        .bind(user_id)
+        .bind(now)
+        .fetch_optional(pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let result_json: String = row.try_get("result_json")?;
+                Ok(Some(serde_json::from_str(&result_json)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 写入/覆盖缓存结果，并重置有效期。
+    pub async fn put(
+        pool: &SqlitePool,
+        user_id: &str,
+        cache_key: &str,
+        execution_target: &str,
+        result: &ExecutionResult,
+        ttl_seconds: u32,
+    ) -> Result<(), ExecutionCacheRepoError> {
+        let now = now_millis();
+        let expires_at = now + i64::from(ttl_seconds) * 1000;
+        let result_json = serde_json::to_string(result)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO execution_result_cache
+                (cache_key, user_id, execution_target, result_json, hit_count, created_at, expires_at)
+            VALUES (?1, ?2, ?3, ?4, 0, ?5, ?6)
+            ON CONFLICT(cache_key) DO UPDATE SET
+                user_id = excluded.user_id,
+                execution_target = excluded.execution_target,
+                result_json = excluded.result_json,
+                hit_count = 0,
+                created_at = excluded.created_at,
+                expires_at = excluded.expires_at
+            "#,
+        )
+        .bind(cache_key)
+        .bind(user_id)
+        .bind(execution_target)
+        .bind(result_json)
+        .bind(now)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 清理已过期条目，返回删除行数。
+    pub async fn purge_expired(pool: &SqlitePool) -> Result<u64, ExecutionCacheRepoError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM execution_result_cache
+            WHERE expires_at <= ?1
+            "#,
+        )
+        .bind(now_millis())
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::db::pool::create_pool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = create_pool("sqlite::memory:")
+            .await
+            .expect("创建测试数据库失败");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("运行 migrations 失败");
+        pool
+    }
+
+    fn result(output: &str) -> ExecutionResult {
+        ExecutionResult {
+            test_case_id: "tc-1".to_string(),
+            output: output.to_string(),
+            latency_ms: 42,
+            token_usage: None,
+            raw_response: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn put_then_get_returns_result_and_counts_hits() {
+        let pool = setup_test_db().await;
+        ExecutionCacheRepo::put(&pool, "u1", "k1", "example", &result("out"), 60)
+            .await
+            .unwrap();
+
+        let cached = ExecutionCacheRepo::get(&pool, "u1", "k1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.output, "out");
+        assert_eq!(cached.latency_ms, 42);
+        let _ = ExecutionCacheRepo::get(&pool, "u1", "k1").await.unwrap();
+
+        let hit_count: i64 =
+            sqlx::query_scalar("SELECT hit_count FROM execution_result_cache WHERE cache_key = ?1")
+                .bind("k1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(hit_count, 2);
+        assert!(
+            ExecutionCacheRepo::get(&pool, "u1", "missing")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_not_served_and_can_be_purged() {
+        let pool = setup_test_db().await;
+        ExecutionCacheRepo::put(&pool, "u1", "k1", "example", &result("out"), 0)
+            .await
+            .unwrap();
+
+        assert!(
+            ExecutionCacheRepo::get(&pool, "u1", "k1")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(ExecutionCacheRepo::purge_expired(&pool).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn entries_are_not_served_to_other_users() {
+        let pool = setup_test_db().await;
+        ExecutionCacheRepo::put(&pool, "u1", "k1", "example", &result("out"), 60)
+            .await
+            .unwrap();
+
+        assert!(
+            ExecutionCacheRepo::get(&pool, "u2", "k1")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            ExecutionCacheRepo::get(&pool, "u1", "k1")
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+}
@@ -28,6 +28,7 @@ pub struct IterationRow {
     pub total_cases: i32,
     pub passed_cases: i32,
     pub created_at: i64,
+    pub effective_concurrency: Option<i64>,
 }
 
 /// 迭代摘要 + 产物
@@ -90,8 +91,8 @@ impl IterationRepo {
             r#"
             SELECT ot.id
             FROM optimization_tasks ot
-            JOIN workspaces w ON ot.workspace_id = w.id
-            WHERE ot.id = ? AND w.user_id = ?
+            JOIN workspace_members m ON m.workspace_id = ot.workspace_id
+            WHERE ot.id = ? AND m.user_id = ?
             "#,
         )
         .bind(task_id)
@@ -107,7 +108,8 @@ impl IterationRepo {
             r#"
             SELECT id, task_id, round, started_at, completed_at, status,
                    artifacts, evaluation_results, reflection_summary,
-                   pass_rate, total_cases, passed_cases, created_at
+                   pass_rate, total_cases, passed_cases, created_at,
+                   effective_concurrency
             FROM iterations
             WHERE task_id = ?
             ORDER BY round DESC
@@ -151,8 +153,8 @@ impl IterationRepo {
             r#"
             SELECT ot.id
             FROM optimization_tasks ot
-            JOIN workspaces w ON ot.workspace_id = w.id
-            WHERE ot.id = ? AND w.user_id = ?
+            JOIN workspace_members m ON m.workspace_id = ot.workspace_id
+            WHERE ot.id = ? AND m.user_id = ?
             "#,
         )
         .bind(task_id)
@@ -168,7 +170,8 @@ impl IterationRepo {
             r#"
             SELECT id, task_id, round, started_at, completed_at, status,
                    artifacts, evaluation_results, reflection_summary,
-                   pass_rate, total_cases, passed_cases, created_at
+                   pass_rate, total_cases, passed_cases, created_at,
+                   effective_concurrency
             FROM iterations
             WHERE task_id = ?
             "#,
@@ -212,8 +215,8 @@ impl IterationRepo {
             r#"
             SELECT ot.id
             FROM optimization_tasks ot
-            JOIN workspaces w ON ot.workspace_id = w.id
-            WHERE ot.id = ? AND w.user_id = ?
+            JOIN workspace_members m ON m.workspace_id = ot.workspace_id
+            WHERE ot.id = ? AND m.user_id = ?
             "#,
         )
         .bind(task_id)
@@ -229,7 +232,8 @@ impl IterationRepo {
             r#"
             SELECT id, task_id, round, started_at, completed_at, status,
                    artifacts, evaluation_results, reflection_summary,
-                   pass_rate, total_cases, passed_cases, created_at
+                   pass_rate, total_cases, passed_cases, created_at,
+                   effective_concurrency
             FROM iterations
             WHERE task_id = ?
             "#,
@@ -275,8 +279,8 @@ impl IterationRepo {
             r#"
             SELECT ot.id
             FROM optimization_tasks ot
-            JOIN workspaces w ON ot.workspace_id = w.id
-            WHERE ot.id = ? AND w.user_id = ?
+            JOIN workspace_members m ON m.workspace_id = ot.workspace_id
+            WHERE ot.id = ? AND m.user_id = ?
             "#,
         )
         .bind(task_id)
@@ -307,8 +311,8 @@ impl IterationRepo {
             r#"
             SELECT ot.id
             FROM optimization_tasks ot
-            JOIN workspaces w ON ot.workspace_id = w.id
-            WHERE ot.id = ? AND w.user_id = ?
+            JOIN workspace_members m ON m.workspace_id = ot.workspace_id
+            WHERE ot.id = ? AND m.user_id = ?
             "#,
         )
         .bind(task_id)
@@ -324,7 +328,8 @@ impl IterationRepo {
             r#"
             SELECT id, task_id, round, started_at, completed_at, status,
                    artifacts, evaluation_results, reflection_summary,
-                   pass_rate, total_cases, passed_cases, created_at
+                   pass_rate, total_cases, passed_cases, created_at,
+                   effective_concurrency
             FROM iterations
             WHERE task_id = ? AND status = 'completed'
             ORDER BY pass_rate DESC, round DESC
@@ -359,8 +364,8 @@ impl IterationRepo {
             r#"
             SELECT ot.id
             FROM optimization_tasks ot
-            JOIN workspaces w ON ot.workspace_id = w.id
-            WHERE ot.id = ? AND w.user_id = ?
+            JOIN workspace_members m ON m.workspace_id = ot.workspace_id
+            WHERE ot.id = ? AND m.user_id = ?
             "#,
         )
         .bind(task_id)
@@ -376,7 +381,8 @@ impl IterationRepo {
             r#"
             SELECT id, task_id, round, started_at, completed_at, status,
                    artifacts, evaluation_results, reflection_summary,
-                   pass_rate, total_cases, passed_cases, created_at
+                   pass_rate, total_cases, passed_cases, created_at,
+                   effective_concurrency
             FROM iterations
             WHERE id = ? AND task_id = ?
             "#,
@@ -441,6 +447,37 @@ impl IterationRepo {
         Ok(())
     }
 
+    /// 更新指定轮次的有效并发（内部使用，无权限校验）
+    pub async fn update_effective_concurrency_for_round(
+        pool: &SqlitePool,
+        task_id: &str,
+        round: u32,
+        effective_concurrency: u32,
+    ) -> Result<(), IterationRepoError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE iterations
+            SET effective_concurrency = ?1
+            WHERE id = (
+                SELECT id FROM iterations
+                WHERE task_id = ?2 AND round = ?3
+                ORDER BY created_at DESC
+                LIMIT 1
+            )
+            "#,
+        )
+        .bind(effective_concurrency as i64)
+        .bind(task_id)
+        .bind(round as i32)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(IterationRepoError::NotFound);
+        }
+        Ok(())
+    }
+
     /// 将数据库行转换为摘要
     fn row_to_summary(row: IterationRow) -> IterationHistorySummary {
         IterationHistorySummary {
@@ -452,6 +489,9 @@ impl IterationRepo {
             total_cases: row.total_cases,
             passed_cases: row.passed_cases,
             status: row.status.parse().unwrap_or_default(),
+            effective_concurrency: row
+                .effective_concurrency
+                .and_then(|v| u32::try_from(v).ok()),
         }
     }
 
@@ -465,6 +505,9 @@ impl IterationRepo {
             total_cases: row.total_cases,
             passed_cases: row.passed_cases,
             status: row.status.parse().unwrap_or_default(),
+            effective_concurrency: row
+                .effective_concurrency
+                .and_then(|v| u32::try_from(v).ok()),
         }
     }
 
@@ -537,10 +580,12 @@ mod tests {
             total_cases: 10,
             passed_cases: 8,
             created_at: 1705507200000,
+            effective_concurrency: Some(3),
         };
 
         let summary = IterationRepo::row_to_summary(row);
         assert_eq!(summary.id, "iter-1");
+        assert_eq!(summary.effective_concurrency, Some(3));
         assert_eq!(summary.round, 1);
         assert_eq!(summary.pass_rate, 0.85);
         assert_eq!(summary.status, IterationStatus::Completed);
@@ -562,6 +607,7 @@ mod tests {
             total_cases: 0,
             passed_cases: 0,
             created_at: 1705507200000,
+            effective_concurrency: None,
         };
 
         let detail = IterationRepo::row_to_detail(row).unwrap();
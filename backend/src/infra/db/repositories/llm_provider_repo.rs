@@ -0,0 +1,243 @@
+//! 用户自定义大模型 Provider 仓储
+//! 负责 llm_providers 表的数据访问
+
+use sqlx::SqlitePool;
+use thiserror::Error;
+
+use crate::domain::models::{LlmProviderRegistry, LlmProviderSpec, OPENAI_COMPATIBLE_PROVIDER_ID};
+use crate::shared::time::now_millis;
+
+/// 用户自定义 Provider 仓储错误
+#[derive(Error, Debug)]
+pub enum LlmProviderRepoError {
+    #[error("数据库错误: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+
+    #[error("Provider 未找到: {0}")]
+    NotFound(String),
+
+    #[error("Provider 配置损坏: {0}")]
+    Corrupted(String),
+}
+
+/// 用户自定义 Provider 仓储
+pub struct LlmProviderRepo;
+
+impl LlmProviderRepo {
+    /// 列出用户自定义 Provider（按 provider_id 排序）
+    pub async fn list_by_user(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> Result<Vec<LlmProviderSpec>, LlmProviderRepoError> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT provider_id, spec_json
+            FROM llm_providers
+            WHERE user_id = ?1
+            ORDER BY provider_id ASC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(provider_id, spec_json)| parse_spec(&provider_id, &spec_json))
+            .collect()
+    }
+
+    /// 查找用户自定义 Provider
+    pub async fn find_by_user_and_id(
+        pool: &SqlitePool,
+        user_id: &str,
+        provider_id: &str,
+    ) -> Result<LlmProviderSpec, LlmProviderRepoError> {
+        let row = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT provider_id, spec_json
+            FROM llm_providers
+            WHERE user_id = ?1 AND provider_id = ?2
+            "#,
+        )
+        .bind(user_id)
+        .bind(provider_id)
+        .fetch_optional(pool)
+        .await?;
+
+        match row {
+            Some((provider_id, spec_json)) => parse_spec(&provider_id, &spec_json),
+            None => Err(LlmProviderRepoError::NotFound(provider_id.to_string())),
+        }
+    }
+
+    /// Upsert 用户自定义 Provider（调用方负责校验 spec 且 ID 不与内置冲突）
+    pub async fn upsert(
+        pool: &SqlitePool,
+        user_id: &str,
+        spec: &LlmProviderSpec,
+    ) -> Result<LlmProviderSpec, LlmProviderRepoError> {
+        let now = now_millis();
+        let mut spec = spec.clone();
+        spec.builtin = false;
+        let spec_json = serde_json::to_string(&spec)
+            .map_err(|e| LlmProviderRepoError::Corrupted(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO llm_providers (id, user_id, provider_id, spec_json, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(user_id, provider_id) DO UPDATE SET
+                spec_json = excluded.spec_json,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(&spec.id)
+        .bind(&spec_json)
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        Ok(spec)
+    }
+
+    /// 删除用户自定义 Provider
+    pub async fn delete(
+        pool: &SqlitePool,
+        user_id: &str,
+        provider_id: &str,
+    ) -> Result<bool, LlmProviderRepoError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM llm_providers
+            WHERE user_id = ?1 AND provider_id = ?2
+            "#,
+        )
+        .bind(user_id)
+        .bind(provider_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 用户可见的完整注册表（内置 + 自定义）
+    pub async fn registry_for_user(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> Result<LlmProviderRegistry, LlmProviderRepoError> {
+        let custom = Self::list_by_user(pool, user_id).await?;
+        Ok(LlmProviderRegistry::with_custom(custom))
+    }
+
+    /// 解析凭证记录的 provider 字段
+    ///
+    /// - None/空：按通用 OpenAI 兼容处理（兼容旧凭证）
+    /// - 内置 ID：返回内置描述
+    /// - 其他：查找用户自定义 Provider，不存在时返回 NotFound
+    pub async fn resolve(
+        pool: &SqlitePool,
+        user_id: &str,
+        provider_id: Option<&str>,
+    ) -> Result<LlmProviderSpec, LlmProviderRepoError> {
+        let provider_id = provider_id
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(OPENAI_COMPATIBLE_PROVIDER_ID);
+        if let Some(spec) = LlmProviderRegistry::builtin().get(provider_id) {
+            return Ok(spec.clone());
+        }
+        Self::find_by_user_and_id(pool, user_id, provider_id).await
+    }
+}
+
+fn parse_spec(provider_id: &str, spec_json: &str) -> Result<LlmProviderSpec, LlmProviderRepoError> {
+    let mut spec: LlmProviderSpec = serde_json::from_str(spec_json)
+        .map_err(|e| LlmProviderRepoError::Corrupted(format!("{provider_id}: {e}")))?;
+    spec.id = provider_id.to_string();
+    spec.builtin = false;
+    Ok(spec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::db::pool::create_pool;
+
+    async fn setup_db() -> SqlitePool {
+        let pool = create_pool("sqlite::memory:")
+            .await
+            .expect("创建测试数据库失败");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("运行 migrations 失败");
+        pool
+    }
+
+    fn ollama() -> LlmProviderSpec {
+        let mut spec = LlmProviderRegistry::openai_compatible();
+        spec.id = "ollama".to_string();
+        spec.display_name = "Ollama".to_string();
+        spec.default_base_url = Some("http://localhost:11434".to_string());
+        spec
+    }
+
+    #[tokio::test]
+    async fn custom_providers_are_scoped_per_user_and_resolvable() {
+        let pool = setup_db().await;
+        let saved = LlmProviderRepo::upsert(&pool, "u1", &ollama())
+            .await
+            .unwrap();
+        assert!(!saved.builtin);
+
+        let list = LlmProviderRepo::list_by_user(&pool, "u1").await.unwrap();
+        assert_eq!(list, vec![saved.clone()]);
+        assert!(
+            LlmProviderRepo::list_by_user(&pool, "u2")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let resolved = LlmProviderRepo::resolve(&pool, "u1", Some("ollama"))
+            .await
+            .unwrap();
+        assert_eq!(
+            resolved.default_base_url.as_deref(),
+            Some("http://localhost:11434")
+        );
+        assert!(matches!(
+            LlmProviderRepo::resolve(&pool, "u2", Some("ollama")).await,
+            Err(LlmProviderRepoError::NotFound(_))
+        ));
+        let builtin = LlmProviderRepo::resolve(&pool, "u2", Some("anthropic"))
+            .await
+            .unwrap();
+        assert!(builtin.builtin);
+        let legacy = LlmProviderRepo::resolve(&pool, "u2", None).await.unwrap();
+        assert_eq!(legacy.id, OPENAI_COMPATIBLE_PROVIDER_ID);
+
+        let mut updated = ollama();
+        updated.display_name = "Ollama (LAN)".to_string();
+        LlmProviderRepo::upsert(&pool, "u1", &updated)
+            .await
+            .unwrap();
+        let list = LlmProviderRepo::list_by_user(&pool, "u1").await.unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].display_name, "Ollama (LAN)");
+
+        assert!(
+            LlmProviderRepo::delete(&pool, "u1", "ollama")
+                .await
+                .unwrap()
+        );
+        assert!(
+            !LlmProviderRepo::delete(&pool, "u1", "ollama")
+                .await
+                .unwrap()
+        );
+    }
+}
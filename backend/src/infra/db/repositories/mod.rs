@@ -1,35 +1,56 @@
 //! Repository 模块
 //! 数据库访问的唯一入口
 
+pub mod access_token_repo;
+pub mod audit_event_repo;
 pub mod checkpoint_repo;
 pub mod credential_repo;
 pub mod diversity_baseline_repo;
+pub mod execution_cache_repo;
 pub mod history_event_repo;
 pub mod iteration_repo;
+pub mod llm_provider_repo;
 pub mod migration_repo;
 pub mod optimization_task_repo;
 pub mod recovery_metrics_repo;
+pub mod session_repo;
 pub mod teacher_prompt_repo;
 pub mod teacher_settings_repo;
 pub mod test_set_repo;
+pub mod totp_repo;
+pub mod user_data_key_repo;
 pub mod user_repo;
+pub mod workspace_member_repo;
 pub mod workspace_repo;
 
+pub use access_token_repo::{
+    ACCESS_TOKEN_NAME_MAX_CHARS, AccessTokenRecord, AccessTokenRepo, AccessTokenRepoError,
+    AccessTokenScope, CreateAccessTokenInput, TokenWrappedDataKey,
+};
+pub use audit_event_repo::{
+    AuditEventFilter, AuditEventRecord, AuditEventRepo, AuditEventRepoError, AuditEventType,
+    AuditOutcome, NewAuditEvent,
+};
 pub use checkpoint_repo::{CheckpointRepo, CheckpointRepoError};
 pub use credential_repo::{
-    CredentialRecord, CredentialRepo, CredentialRepoError, CredentialType, UpsertCredentialInput,
+    CREDENTIAL_NAME_MAX_CHARS, CreateCredentialInput, CredentialRecord, CredentialRepo,
+    CredentialRepoError, CredentialType, DEFAULT_CREDENTIAL_NAME, ReencryptedCredential,
+    UpdateCredentialInput, UpsertCredentialInput,
 };
 pub use diversity_baseline_repo::{DiversityBaselineRepo, DiversityBaselineRepoError};
+pub use execution_cache_repo::{ExecutionCacheRepo, ExecutionCacheRepoError};
 pub use history_event_repo::{HistoryEventRepo, HistoryEventRepoError};
 pub use iteration_repo::{
     IterationRepo, IterationRepoError, IterationSummaryWithArtifacts,
     IterationSummaryWithArtifactsAndEvaluations,
 };
+pub use llm_provider_repo::{LlmProviderRepo, LlmProviderRepoError};
 pub use migration_repo::{MigrationRepo, MigrationRepoError, MigrationResult};
 pub use optimization_task_repo::{
     CreateOptimizationTaskInput, OptimizationTaskRepo, OptimizationTaskRepoError,
 };
 pub use recovery_metrics_repo::{RecoveryMetricsRepo, RecoveryMetricsRepoError};
+pub use session_repo::{SessionRecord, SessionRepo, SessionRepoError};
 pub use teacher_prompt_repo::{
     CreateTeacherPromptRecordInput, TeacherPromptRecord, TeacherPromptRepo, TeacherPromptRepoError,
     TeacherPromptVersionRecord, TeacherPromptVersionWithStatsRecord,
@@ -39,5 +60,13 @@ pub use teacher_settings_repo::{
     UpsertTeacherSettingsInput,
 };
 pub use test_set_repo::{TestSetRepo, TestSetRepoError};
+pub use totp_repo::{TotpRepo, TotpRepoError, UserTotpRecord};
+pub use user_data_key_repo::{
+    PasswordWrappedDataKey, ServerWrappedDataKey, UserDataKeyRecord, UserDataKeyRepo,
+    UserDataKeyRepoError,
+};
 pub use user_repo::{UserRepo, UserRepoError};
+pub use workspace_member_repo::{
+    WorkspaceMemberRecord, WorkspaceMemberRepo, WorkspaceMemberRepoError, WorkspaceRole,
+};
 pub use workspace_repo::{WorkspaceRepo, WorkspaceRepoError};
@@ -58,6 +58,8 @@ fn parse_execution_target_type(raw: &str) -> Option<ExecutionTargetType> {
         "dify" => Some(ExecutionTargetType::Dify),
         "generic" => Some(ExecutionTargetType::Generic),
         "example" => Some(ExecutionTargetType::Example),
+        "command" => Some(ExecutionTargetType::Command),
+        "http" => Some(ExecutionTargetType::Http),
         _ => None,
     }
 }
@@ -86,6 +88,8 @@ fn serialize_execution_target_type(value: ExecutionTargetType) -> &'static str {
         ExecutionTargetType::Dify => "dify",
         ExecutionTargetType::Generic => "generic",
         ExecutionTargetType::Example => "example",
+        ExecutionTargetType::Command => "command",
+        ExecutionTargetType::Http => "http",
     }
 }
 
@@ -168,6 +172,9 @@ fn row_to_entity(row: &SqliteRow) -> Result<OptimizationTaskEntity, Optimization
     })
 }
 
+/// 当前用户在工作区中具备写权限（owner / editor）
+const WRITABLE_WORKSPACE_SQL: &str = "SELECT 1 FROM workspace_members WHERE workspace_id = ?1 AND user_id = ?2 AND role IN ('owner', 'editor')";
+
 impl OptimizationTaskRepo {
     pub async fn create_scoped(
         pool: &SqlitePool,
@@ -178,13 +185,12 @@ impl OptimizationTaskRepo {
 
         let mut tx = pool.begin().await?;
 
-        let workspace_exists =
-            sqlx::query_scalar::<_, i64>("SELECT 1 FROM workspaces WHERE id = ?1 AND user_id = ?2")
-                .bind(input.workspace_id)
-                .bind(input.user_id)
-                .fetch_optional(&mut *tx)
-                .await?
-                .is_some();
+        let workspace_exists = sqlx::query_scalar::<_, i64>(WRITABLE_WORKSPACE_SQL)
+            .bind(input.workspace_id)
+            .bind(input.user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some();
         if !workspace_exists {
             return Err(OptimizationTaskRepoError::WorkspaceNotFound);
         }
@@ -271,9 +277,9 @@ impl OptimizationTaskRepo {
             r#"
             SELECT t.*, otts.test_set_id AS test_set_id
             FROM optimization_tasks t
-            JOIN workspaces w ON w.id = t.workspace_id
+            JOIN workspace_members m ON m.workspace_id = t.workspace_id
             LEFT JOIN optimization_task_test_sets otts ON otts.optimization_task_id = t.id
-            WHERE t.workspace_id = ?1 AND w.user_id = ?2
+            WHERE t.workspace_id = ?1 AND m.user_id = ?2
             ORDER BY t.updated_at DESC, otts.created_at ASC
             "#,
         )
@@ -319,8 +325,8 @@ impl OptimizationTaskRepo {
             r#"
             SELECT t.*
             FROM optimization_tasks t
-            JOIN workspaces w ON w.id = t.workspace_id
-            WHERE t.id = ?1 AND w.user_id = ?2
+            JOIN workspace_members m ON m.workspace_id = t.workspace_id
+            WHERE t.id = ?1 AND m.user_id = ?2
             "#,
         )
         .bind(task_id)
@@ -364,8 +370,8 @@ impl OptimizationTaskRepo {
             r#"
             SELECT t.*
             FROM optimization_tasks t
-            JOIN workspaces w ON w.id = t.workspace_id
-            WHERE t.id = ?1 AND t.workspace_id = ?2 AND w.user_id = ?3
+            JOIN workspace_members m ON m.workspace_id = t.workspace_id
+            WHERE t.id = ?1 AND t.workspace_id = ?2 AND m.user_id = ?3
             "#,
         )
         .bind(task_id)
@@ -407,13 +413,12 @@ impl OptimizationTaskRepo {
         let now = now_millis();
         let mut tx = pool.begin().await?;
 
-        let workspace_exists =
-            sqlx::query_scalar::<_, i64>("SELECT 1 FROM workspaces WHERE id = ?1 AND user_id = ?2")
-                .bind(workspace_id)
-                .bind(user_id)
-                .fetch_optional(&mut *tx)
-                .await?
-                .is_some();
+        let workspace_exists = sqlx::query_scalar::<_, i64>(WRITABLE_WORKSPACE_SQL)
+            .bind(workspace_id)
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some();
         if !workspace_exists {
             return Err(OptimizationTaskRepoError::WorkspaceNotFound);
         }
@@ -500,7 +505,7 @@ impl OptimizationTaskRepo {
                    c.state as state,
                    c.run_control_state as run_control_state
             FROM optimization_tasks t
-            JOIN workspaces w ON w.id = t.workspace_id
+            JOIN workspace_members m ON m.workspace_id = t.workspace_id
             JOIN (
                 SELECT task_id, MAX(created_at) as max_created_at
                 FROM checkpoints
@@ -509,7 +514,8 @@ impl OptimizationTaskRepo {
             JOIN checkpoints c
               ON c.task_id = latest.task_id
              AND c.created_at = latest.max_created_at
-            WHERE w.user_id = ?1
+            WHERE m.user_id = ?1
+              AND m.role IN ('owner', 'editor')
               AND t.status IN ('running', 'paused')
             ORDER BY c.created_at DESC
             "#,
@@ -542,6 +548,70 @@ impl OptimizationTaskRepo {
         Ok(tasks)
     }
 
+    /// 标记任务因服务停机而在安全点暂停（仅对运行中 / 已暂停任务生效）
+    pub async fn mark_interrupted_by_shutdown(
+        pool: &SqlitePool,
+        task_id: &str,
+    ) -> Result<(), OptimizationTaskRepoError> {
+        let now = now_millis();
+        let result = sqlx::query(
+            r#"
+            UPDATE optimization_tasks
+            SET status = 'paused',
+                interrupted_by_shutdown_at = ?1,
+                updated_at = ?1
+            WHERE id = ?2
+              AND status IN ('running', 'paused')
+            "#,
+        )
+        .bind(now)
+        .bind(task_id)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(OptimizationTaskRepoError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// 列出因服务停机而暂停、尚未恢复的任务（跨用户；返回 `(task_id, user_id)`）
+    pub async fn list_interrupted_by_shutdown(
+        pool: &SqlitePool,
+    ) -> Result<Vec<(String, String)>, OptimizationTaskRepoError> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT t.id, w.user_id
+            FROM optimization_tasks t
+            JOIN workspaces w ON w.id = t.workspace_id
+            WHERE t.interrupted_by_shutdown_at IS NOT NULL
+              AND t.status = 'paused'
+            ORDER BY t.interrupted_by_shutdown_at ASC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// 清除停机打断标记（任务已恢复）
+    pub async fn clear_shutdown_interruption(
+        pool: &SqlitePool,
+        task_id: &str,
+    ) -> Result<(), OptimizationTaskRepoError> {
+        sqlx::query(
+            r#"
+            UPDATE optimization_tasks
+            SET interrupted_by_shutdown_at = NULL
+            WHERE id = ?1
+            "#,
+        )
+        .bind(task_id)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn update_status(
         pool: &SqlitePool,
         task_id: &str,
@@ -847,6 +917,83 @@ mod tests {
         assert!(names.contains(&"task-paused".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_shutdown_interruption_marker_roundtrip() {
+        let pool = setup_test_db().await;
+
+        insert_user(&pool, "u1", "user1").await;
+        let workspace = WorkspaceRepo::create(&pool, "u1", "ws", None)
+            .await
+            .expect("创建工作区失败");
+        let test_set = TestSetRepo::create(
+            &pool,
+            &workspace.id,
+            "ts",
+            None,
+            &[sample_exact_case()],
+            None,
+            None,
+        )
+        .await
+        .expect("创建测试集失败");
+
+        let mut task_ids = Vec::new();
+        for name in ["task-running", "task-done"] {
+            let created = OptimizationTaskRepo::create_scoped(
+                &pool,
+                CreateOptimizationTaskInput {
+                    user_id: "u1",
+                    workspace_id: &workspace.id,
+                    name,
+                    description: None,
+                    goal: "goal",
+                    execution_target_type: ExecutionTargetType::Example,
+                    task_mode: OptimizationTaskMode::Fixed,
+                    test_set_ids: std::slice::from_ref(&test_set.id),
+                    teacher_prompt_version_id: None,
+                },
+            )
+            .await
+            .expect("创建任务失败");
+            task_ids.push(created.task.id);
+        }
+        OptimizationTaskRepo::update_status(&pool, &task_ids[0], OptimizationTaskStatus::Running)
+            .await
+            .expect("更新状态失败");
+        OptimizationTaskRepo::update_status(&pool, &task_ids[1], OptimizationTaskStatus::Completed)
+            .await
+            .expect("更新状态失败");
+
+        OptimizationTaskRepo::mark_interrupted_by_shutdown(&pool, &task_ids[0])
+            .await
+            .expect("标记失败");
+        // 已完成任务不应被标记
+        assert!(matches!(
+            OptimizationTaskRepo::mark_interrupted_by_shutdown(&pool, &task_ids[1]).await,
+            Err(OptimizationTaskRepoError::NotFound)
+        ));
+
+        let task = OptimizationTaskRepo::find_by_id_for_user(&pool, "u1", &task_ids[0])
+            .await
+            .expect("查询失败");
+        assert_eq!(task.status, OptimizationTaskStatus::Paused);
+
+        let interrupted = OptimizationTaskRepo::list_interrupted_by_shutdown(&pool)
+            .await
+            .expect("查询失败");
+        assert_eq!(interrupted, vec![(task_ids[0].clone(), "u1".to_string())]);
+
+        OptimizationTaskRepo::clear_shutdown_interruption(&pool, &task_ids[0])
+            .await
+            .expect("清除失败");
+        assert!(
+            OptimizationTaskRepo::list_interrupted_by_shutdown(&pool)
+                .await
+                .expect("查询失败")
+                .is_empty()
+        );
+    }
+
     fn build_checkpoint(task_id: &str, iteration: u32) -> CheckpointEntity {
         let req = CheckpointCreateRequest {
             task_id: task_id.to_string(),
@@ -0,0 +1,376 @@
+//! 会话仓储
+//! 负责 user_sessions 表的数据访问（仅保存 token 摘要）
+
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+
+/// 会话记录（数据库行）
+#[derive(Debug, Clone, FromRow)]
+pub struct SessionRecord {
+    pub id: String,
+    pub token_hash: String,
+    pub user_id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: i64,
+    pub last_seen_at: i64,
+    pub expires_at: i64,
+}
+
+/// 会话仓储错误
+#[derive(Error, Debug)]
+pub enum SessionRepoError {
+    #[error("数据库错误: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+const SELECT_COLUMNS: &str =
+    "id, token_hash, user_id, user_agent, ip_address, created_at, last_seen_at, expires_at";
+
+/// 会话仓储
+pub struct SessionRepo;
+
+impl SessionRepo {
+    /// 写入新会话
+    pub async fn insert(pool: &SqlitePool, record: &SessionRecord) -> Result<(), SessionRepoError> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_sessions (
+                id, token_hash, user_id, user_agent, ip_address,
+                created_at, last_seen_at, expires_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+        )
+        .bind(&record.id)
+        .bind(&record.token_hash)
+        .bind(&record.user_id)
+        .bind(&record.user_agent)
+        .bind(&record.ip_address)
+        .bind(record.created_at)
+        .bind(record.last_seen_at)
+        .bind(record.expires_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 按 token 摘要查询未过期的会话
+    pub async fn find_active_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+        now: i64,
+    ) -> Result<Option<SessionRecord>, SessionRepoError> {
+        let record = sqlx::query_as::<_, SessionRecord>(&format!(
+            r#"
+            SELECT {SELECT_COLUMNS}
+            FROM user_sessions
+            WHERE token_hash = ?1 AND expires_at > ?2
+            "#
+        ))
+        .bind(token_hash)
+        .bind(now)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// 列出用户未过期的会话（最近使用的在前）
+    pub async fn list_active_by_user(
+        pool: &SqlitePool,
+        user_id: &str,
+        now: i64,
+    ) -> Result<Vec<SessionRecord>, SessionRepoError> {
+        let records = sqlx::query_as::<_, SessionRecord>(&format!(
+            r#"
+            SELECT {SELECT_COLUMNS}
+            FROM user_sessions
+            WHERE user_id = ?1 AND expires_at > ?2
+            ORDER BY last_seen_at DESC, created_at DESC
+            "#
+        ))
+        .bind(user_id)
+        .bind(now)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// 更新最近使用时间并顺延过期时间（滑动过期）
+    pub async fn touch(
+        pool: &SqlitePool,
+        id: &str,
+        last_seen_at: i64,
+        expires_at: i64,
+    ) -> Result<(), SessionRepoError> {
+        sqlx::query(
+            r#"
+            UPDATE user_sessions SET last_seen_at = ?1, expires_at = ?2
+            WHERE id = ?3
+            "#,
+        )
+        .bind(last_seen_at)
+        .bind(expires_at)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 按 token 摘要删除会话（登出），返回被删除的会话 ID
+    pub async fn delete_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<String>, SessionRepoError> {
+        let id = sqlx::query_scalar::<_, String>(
+            "DELETE FROM user_sessions WHERE token_hash = ?1 RETURNING id",
+        )
+        .bind(token_hash)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// 删除用户的指定会话
+    pub async fn delete_by_id(
+        pool: &SqlitePool,
+        user_id: &str,
+        id: &str,
+    ) -> Result<bool, SessionRepoError> {
+        let result = sqlx::query("DELETE FROM user_sessions WHERE user_id = ?1 AND id = ?2")
+            .bind(user_id)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 删除用户的会话（可保留 token 摘要为 `keep_token_hash` 的会话），返回被删除的会话 ID
+    pub async fn delete_by_user_except(
+        pool: &SqlitePool,
+        user_id: &str,
+        keep_token_hash: Option<&str>,
+    ) -> Result<Vec<String>, SessionRepoError> {
+        let ids = sqlx::query_scalar::<_, String>(
+            r#"
+            DELETE FROM user_sessions
+            WHERE user_id = ?1 AND (?2 IS NULL OR token_hash != ?2)
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(keep_token_hash)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// 删除已过期会话，返回被删除的会话 ID
+    pub async fn delete_expired(
+        pool: &SqlitePool,
+        now: i64,
+    ) -> Result<Vec<String>, SessionRepoError> {
+        let ids = sqlx::query_scalar::<_, String>(
+            "DELETE FROM user_sessions WHERE expires_at <= ?1 RETURNING id",
+        )
+        .bind(now)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// 统计未过期会话数量
+    pub async fn count_active(pool: &SqlitePool, now: i64) -> Result<i64, SessionRepoError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM user_sessions WHERE expires_at > ?1",
+        )
+        .bind(now)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::db::pool::create_pool;
+
+    async fn setup_db() -> SqlitePool {
+        let pool = create_pool("sqlite::memory:")
+            .await
+            .expect("创建测试数据库失败");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("运行 migrations 失败");
+        for user_id in ["u1", "u2"] {
+            sqlx::query(
+                "INSERT INTO users (id, username, password_hash, created_at, updated_at) VALUES (?1, ?1, 'hash', 0, 0)",
+            )
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .expect("插入测试用户失败");
+        }
+        pool
+    }
+
+    fn record(id: &str, user_id: &str, created_at: i64, expires_at: i64) -> SessionRecord {
+        SessionRecord {
+            id: id.to_string(),
+            token_hash: format!("hash-{id}"),
+            user_id: user_id.to_string(),
+            user_agent: Some("test-agent".to_string()),
+            ip_address: Some("127.0.0.1".to_string()),
+            created_at,
+            last_seen_at: created_at,
+            expires_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn insert_and_find_active_by_token_hash() {
+        let pool = setup_db().await;
+        SessionRepo::insert(&pool, &record("s1", "u1", 100, 1_000))
+            .await
+            .unwrap();
+
+        let found = SessionRepo::find_active_by_token_hash(&pool, "hash-s1", 200)
+            .await
+            .unwrap()
+            .expect("应找到会话");
+        assert_eq!(found.id, "s1");
+        assert_eq!(found.user_id, "u1");
+        assert_eq!(found.user_agent.as_deref(), Some("test-agent"));
+        assert_eq!(found.ip_address.as_deref(), Some("127.0.0.1"));
+
+        assert!(
+            SessionRepo::find_active_by_token_hash(&pool, "hash-unknown", 200)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn insert_rejects_unknown_user() {
+        let pool = setup_db().await;
+        let result = SessionRepo::insert(&pool, &record("s1", "ghost", 100, 1_000)).await;
+        assert!(result.is_err(), "不存在的用户不应写入会话");
+    }
+
+    #[tokio::test]
+    async fn touch_slides_expiry() {
+        let pool = setup_db().await;
+        SessionRepo::insert(&pool, &record("s1", "u1", 100, 1_000))
+            .await
+            .unwrap();
+
+        SessionRepo::touch(&pool, "s1", 900, 2_000).await.unwrap();
+
+        // 原过期时间之后仍然有效
+        let found = SessionRepo::find_active_by_token_hash(&pool, "hash-s1", 1_500)
+            .await
+            .unwrap()
+            .expect("顺延后应仍有效");
+        assert_eq!(found.last_seen_at, 900);
+        assert_eq!(found.expires_at, 2_000);
+    }
+
+    #[tokio::test]
+    async fn expired_sessions_are_hidden_and_purged() {
+        let pool = setup_db().await;
+        SessionRepo::insert(&pool, &record("old", "u1", 100, 500))
+            .await
+            .unwrap();
+        SessionRepo::insert(&pool, &record("live", "u1", 200, 5_000))
+            .await
+            .unwrap();
+
+        assert!(
+            SessionRepo::find_active_by_token_hash(&pool, "hash-old", 500)
+                .await
+                .unwrap()
+                .is_none(),
+            "expires_at 到达即视为过期"
+        );
+        let active = SessionRepo::list_active_by_user(&pool, "u1", 1_000)
+            .await
+            .unwrap();
+        assert_eq!(
+            active.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(),
+            vec!["live"]
+        );
+        assert_eq!(SessionRepo::count_active(&pool, 1_000).await.unwrap(), 1);
+
+        let purged = SessionRepo::delete_expired(&pool, 1_000).await.unwrap();
+        assert_eq!(purged, vec!["old".to_string()]);
+        assert_eq!(SessionRepo::count_active(&pool, 0).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn delete_by_user_except_revokes_other_sessions_only() {
+        let pool = setup_db().await;
+        SessionRepo::insert(&pool, &record("current", "u1", 100, 5_000))
+            .await
+            .unwrap();
+        SessionRepo::insert(&pool, &record("other", "u1", 200, 5_000))
+            .await
+            .unwrap();
+        SessionRepo::insert(&pool, &record("foreign", "u2", 300, 5_000))
+            .await
+            .unwrap();
+
+        let revoked = SessionRepo::delete_by_user_except(&pool, "u1", Some("hash-current"))
+            .await
+            .unwrap();
+        assert_eq!(revoked, vec!["other".to_string()]);
+
+        let remaining = SessionRepo::list_active_by_user(&pool, "u1", 0)
+            .await
+            .unwrap();
+        assert_eq!(
+            remaining.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(),
+            vec!["current"]
+        );
+        // 其他用户的会话不受影响
+        assert_eq!(
+            SessionRepo::list_active_by_user(&pool, "u2", 0)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // 不保留任何会话时全部吊销
+        let revoked = SessionRepo::delete_by_user_except(&pool, "u1", None)
+            .await
+            .unwrap();
+        assert_eq!(revoked, vec!["current".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn deleting_user_cascades_to_sessions() {
+        let pool = setup_db().await;
+        SessionRepo::insert(&pool, &record("s1", "u1", 100, 5_000))
+            .await
+            .unwrap();
+
+        sqlx::query("DELETE FROM users WHERE id = 'u1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(SessionRepo::count_active(&pool, 0).await.unwrap(), 0);
+    }
+}
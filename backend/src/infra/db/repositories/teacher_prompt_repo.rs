@@ -154,7 +154,7 @@ impl TeacherPromptRepo {
                     ot.teacher_prompt_version_id AS version_id,
                     COALESCE(selected.pass_rate, latest.pass_rate) AS pass_rate
                 FROM optimization_tasks ot
-                JOIN workspaces w ON w.id = ot.workspace_id
+                JOIN workspace_members wm ON wm.workspace_id = ot.workspace_id
                 LEFT JOIN iterations selected ON selected.id = ot.selected_iteration_id
                 LEFT JOIN (
                     SELECT i1.task_id, i1.pass_rate
@@ -167,7 +167,7 @@ impl TeacherPromptRepo {
                     ) m ON m.task_id = i1.task_id AND i1.round = m.max_round
                     WHERE i1.status = 'completed'
                 ) latest ON latest.task_id = ot.id
-                WHERE w.user_id = ?1 AND ot.teacher_prompt_version_id IS NOT NULL
+                WHERE wm.user_id = ?1 AND ot.teacher_prompt_version_id IS NOT NULL
             )
             SELECT
                 tp.id,
@@ -386,7 +386,7 @@ impl TeacherPromptRepo {
                     ot.teacher_prompt_version_id AS version_id,
                     COALESCE(selected.pass_rate, latest.pass_rate) AS pass_rate
                 FROM optimization_tasks ot
-                JOIN workspaces w ON w.id = ot.workspace_id
+                JOIN workspace_members wm ON wm.workspace_id = ot.workspace_id
                 LEFT JOIN iterations selected ON selected.id = ot.selected_iteration_id
                 LEFT JOIN (
                     SELECT i1.task_id, i1.pass_rate
@@ -399,7 +399,7 @@ impl TeacherPromptRepo {
                     ) m ON m.task_id = i1.task_id AND i1.round = m.max_round
                     WHERE i1.status = 'completed'
                 ) latest ON latest.task_id = ot.id
-                WHERE w.user_id = ?1 AND ot.teacher_prompt_version_id = ?2
+                WHERE wm.user_id = ?1 AND ot.teacher_prompt_version_id = ?2
             )
             SELECT
                 tp.version,
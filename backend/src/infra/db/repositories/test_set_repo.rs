@@ -290,8 +290,8 @@ impl TestSetRepo {
             r#"
             SELECT ts.id, ts.workspace_id, ts.name, ts.description, ts.cases_json, ts.dify_config_json, ts.generic_config_json, ts.created_at, ts.updated_at
             FROM test_sets ts
-            JOIN workspaces w ON w.id = ts.workspace_id
-            WHERE ts.workspace_id = ?1 AND ts.id = ?2 AND ts.is_template = 0 AND w.user_id = ?3
+            JOIN workspace_members m ON m.workspace_id = ts.workspace_id
+            WHERE ts.workspace_id = ?1 AND ts.id = ?2 AND ts.is_template = 0 AND m.user_id = ?3
             "#,
         )
         .bind(workspace_id)
@@ -383,7 +383,10 @@ impl TestSetRepo {
             SET name = ?1, description = ?2, cases_json = ?3, updated_at = ?4
             WHERE workspace_id = ?5 AND id = ?6
               AND is_template = 0
-              AND EXISTS (SELECT 1 FROM workspaces w WHERE w.id = ?5 AND w.user_id = ?7)
+              AND EXISTS (
+                SELECT 1 FROM workspace_members m
+                WHERE m.workspace_id = ?5 AND m.user_id = ?7 AND m.role IN ('owner', 'editor')
+              )
             "#,
         )
         .bind(name)
@@ -433,7 +436,10 @@ impl TestSetRepo {
             DELETE FROM test_sets
             WHERE workspace_id = ?1 AND id = ?2
               AND is_template = 0
-              AND EXISTS (SELECT 1 FROM workspaces w WHERE w.id = ?1 AND w.user_id = ?3)
+              AND EXISTS (
+                SELECT 1 FROM workspace_members m
+                WHERE m.workspace_id = ?1 AND m.user_id = ?3 AND m.role IN ('owner', 'editor')
+              )
             "#,
         )
         .bind(workspace_id)
@@ -454,10 +460,10 @@ impl TestSetRepo {
             r#"
             SELECT ts.id, ts.workspace_id, ts.name, ts.description, ts.cases_json, ts.created_at, ts.updated_at
             FROM test_sets ts
-            JOIN workspaces w ON w.id = ts.workspace_id
+            JOIN workspace_members m ON m.workspace_id = ts.workspace_id
             WHERE ts.workspace_id = ?1
               AND ts.is_template = 1
-              AND w.user_id = ?2
+              AND m.user_id = ?2
             ORDER BY ts.created_at DESC
             "#,
         )
@@ -506,11 +512,11 @@ impl TestSetRepo {
             r#"
             SELECT ts.id, ts.workspace_id, ts.name, ts.description, ts.cases_json, ts.dify_config_json, ts.generic_config_json, ts.created_at, ts.updated_at
             FROM test_sets ts
-            JOIN workspaces w ON w.id = ts.workspace_id
+            JOIN workspace_members m ON m.workspace_id = ts.workspace_id
             WHERE ts.workspace_id = ?1
               AND ts.id = ?2
               AND ts.is_template = 1
-              AND w.user_id = ?3
+              AND m.user_id = ?3
             "#,
         )
         .bind(workspace_id)
@@ -561,11 +567,12 @@ impl TestSetRepo {
             r#"
             SELECT ts.cases_json, ts.dify_config_json, ts.generic_config_json
             FROM test_sets ts
-            JOIN workspaces w ON w.id = ts.workspace_id
+            JOIN workspace_members m ON m.workspace_id = ts.workspace_id
             WHERE ts.workspace_id = ?1
               AND ts.id = ?2
               AND ts.is_template = 0
-              AND w.user_id = ?3
+              AND m.user_id = ?3
+              AND m.role IN ('owner', 'editor')
             "#,
         )
         .bind(workspace_id)
@@ -630,7 +637,10 @@ impl TestSetRepo {
             SET dify_config_json = ?1, updated_at = ?2
             WHERE workspace_id = ?3 AND id = ?4
               AND is_template = 0
-              AND EXISTS (SELECT 1 FROM workspaces w WHERE w.id = ?3 AND w.user_id = ?5)
+              AND EXISTS (
+                SELECT 1 FROM workspace_members m
+                WHERE m.workspace_id = ?3 AND m.user_id = ?5 AND m.role IN ('owner', 'editor')
+              )
             "#,
         )
         .bind(dify_config_json)
@@ -663,7 +673,10 @@ impl TestSetRepo {
             SET generic_config_json = ?1, updated_at = ?2
             WHERE workspace_id = ?3 AND id = ?4
               AND is_template = 0
-              AND EXISTS (SELECT 1 FROM workspaces w WHERE w.id = ?3 AND w.user_id = ?5)
+              AND EXISTS (
+                SELECT 1 FROM workspace_members m
+                WHERE m.workspace_id = ?3 AND m.user_id = ?5 AND m.role IN ('owner', 'editor')
+              )
             "#,
         )
         .bind(generic_config_json)
@@ -781,4 +794,51 @@ mod tests {
             .expect("删除失败");
         assert!(deleted);
     }
+
+    #[tokio::test]
+    async fn test_scoped_access_follows_member_role() {
+        use crate::infra::db::repositories::{WorkspaceMemberRepo, WorkspaceRole};
+
+        let pool = setup_test_db().await;
+        insert_user(&pool, "u1", "user1").await;
+        insert_user(&pool, "u2", "user2").await;
+        insert_user(&pool, "u3", "user3").await;
+        insert_workspace(&pool, "w1", "u1").await;
+        WorkspaceMemberRepo::add_by_username(&pool, "w1", "user2", WorkspaceRole::Viewer, "u1")
+            .await
+            .expect("邀请成员失败");
+
+        let created = TestSetRepo::create(&pool, "w1", "ts1", None, &sample_cases(), None, None)
+            .await
+            .expect("创建失败");
+
+        // viewer 可读不可写
+        TestSetRepo::find_by_id_scoped(&pool, "u2", "w1", &created.id)
+            .await
+            .expect("viewer 应可读取");
+        let err = TestSetRepo::update_scoped(&pool, "u2", "w1", &created.id, "x", None, &[])
+            .await
+            .expect_err("viewer 不应可修改");
+        assert!(matches!(err, TestSetRepoError::NotFound));
+        assert!(
+            !TestSetRepo::delete_scoped(&pool, "u2", "w1", &created.id)
+                .await
+                .expect("删除失败")
+        );
+
+        // 非成员不可见
+        let err = TestSetRepo::find_by_id_scoped(&pool, "u3", "w1", &created.id)
+            .await
+            .expect_err("非成员不应可读取");
+        assert!(matches!(err, TestSetRepoError::NotFound));
+
+        // 升级为 editor 后可修改
+        WorkspaceMemberRepo::update_role(&pool, "w1", "u2", WorkspaceRole::Editor)
+            .await
+            .expect("修改角色失败");
+        let updated = TestSetRepo::update_scoped(&pool, "u2", "w1", &created.id, "x", None, &[])
+            .await
+            .expect("editor 应可修改");
+        assert_eq!(updated.name, "x");
+    }
 }
@@ -0,0 +1,367 @@
+//! TOTP 两步验证仓储
+//! 负责 user_totp / user_totp_recovery_codes 表的数据访问（密钥已加密、恢复码仅存摘要）
+
+use sqlx::{FromRow, Sqlite, SqlitePool, Transaction};
+use thiserror::Error;
+
+/// 用户 TOTP 记录（数据库行）
+#[derive(Debug, Clone, FromRow)]
+pub struct UserTotpRecord {
+    pub user_id: String,
+    pub encrypted_secret: Vec<u8>,
+    pub secret_nonce: Vec<u8>,
+    pub enabled_at: Option<i64>,
+    pub last_used_step: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl UserTotpRecord {
+    /// 是否已启用（待启用的密钥不参与登录校验）
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+}
+
+/// TOTP 仓储错误
+#[derive(Error, Debug)]
+pub enum TotpRepoError {
+    #[error("数据库错误: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+/// TOTP 仓储
+pub struct TotpRepo;
+
+impl TotpRepo {
+    /// 查询用户的 TOTP 记录（含待启用）
+    pub async fn find(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> Result<Option<UserTotpRecord>, TotpRepoError> {
+        let record = sqlx::query_as::<_, UserTotpRecord>(
+            r#"
+            SELECT user_id, encrypted_secret, secret_nonce, enabled_at, last_used_step, created_at, updated_at
+            FROM user_totp
+            WHERE user_id = ?1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// 保存待启用的密钥（覆盖旧的待启用密钥）
+    ///
+    /// 已启用时不覆盖并返回 false。
+    pub async fn save_pending(
+        pool: &SqlitePool,
+        user_id: &str,
+        encrypted_secret: &[u8],
+        secret_nonce: &[u8],
+        now: i64,
+    ) -> Result<bool, TotpRepoError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO user_totp (user_id, encrypted_secret, secret_nonce, enabled_at, last_used_step, created_at, updated_at)
+            VALUES (?1, ?2, ?3, NULL, NULL, ?4, ?4)
+            ON CONFLICT(user_id) DO UPDATE SET
+                encrypted_secret = excluded.encrypted_secret,
+                secret_nonce = excluded.secret_nonce,
+                last_used_step = NULL,
+                created_at = excluded.created_at,
+                updated_at = excluded.updated_at
+            WHERE user_totp.enabled_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(encrypted_secret)
+        .bind(secret_nonce)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 启用待启用的密钥并写入恢复码（同一事务）
+    ///
+    /// `step` 为确认时通过校验的时间步；不存在待启用密钥时返回 false。
+    pub async fn enable(
+        pool: &SqlitePool,
+        user_id: &str,
+        step: i64,
+        recovery_code_hashes: &[String],
+        now: i64,
+    ) -> Result<bool, TotpRepoError> {
+        let mut tx = pool.begin().await?;
+        let result = sqlx::query(
+            r#"
+            UPDATE user_totp
+            SET enabled_at = ?1, last_used_step = ?2, updated_at = ?1
+            WHERE user_id = ?3 AND enabled_at IS NULL
+            "#,
+        )
+        .bind(now)
+        .bind(step)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        replace_recovery_codes_tx(&mut tx, user_id, recovery_code_hashes, now).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// 记录通过校验的时间步
+    ///
+    /// 仅当 `step` 晚于上次使用的时间步时更新；返回 false 表示验证码已被使用（重放）。
+    pub async fn record_used_step(
+        pool: &SqlitePool,
+        user_id: &str,
+        step: i64,
+        now: i64,
+    ) -> Result<bool, TotpRepoError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_totp
+            SET last_used_step = ?1, updated_at = ?2
+            WHERE user_id = ?3
+              AND enabled_at IS NOT NULL
+              AND (last_used_step IS NULL OR last_used_step < ?1)
+            "#,
+        )
+        .bind(step)
+        .bind(now)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 使用恢复码（原子地标记为已使用）；返回 false 表示无效或已使用
+    pub async fn consume_recovery_code(
+        pool: &SqlitePool,
+        user_id: &str,
+        code_hash: &str,
+        now: i64,
+    ) -> Result<bool, TotpRepoError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_totp_recovery_codes
+            SET used_at = ?1
+            WHERE user_id = ?2 AND code_hash = ?3 AND used_at IS NULL
+            "#,
+        )
+        .bind(now)
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 剩余未使用的恢复码数量
+    pub async fn count_unused_recovery_codes(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> Result<u32, TotpRepoError> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM user_totp_recovery_codes WHERE user_id = ?1 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count as u32)
+    }
+
+    /// 重新生成恢复码（旧恢复码全部失效）
+    pub async fn replace_recovery_codes(
+        pool: &SqlitePool,
+        user_id: &str,
+        recovery_code_hashes: &[String],
+        now: i64,
+    ) -> Result<(), TotpRepoError> {
+        let mut tx = pool.begin().await?;
+        replace_recovery_codes_tx(&mut tx, user_id, recovery_code_hashes, now).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 停用两步验证（删除密钥与全部恢复码）；返回 false 表示本就未配置
+    pub async fn disable(pool: &SqlitePool, user_id: &str) -> Result<bool, TotpRepoError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM user_totp_recovery_codes WHERE user_id = ?1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM user_totp WHERE user_id = ?1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+async fn replace_recovery_codes_tx(
+    tx: &mut Transaction<'_, Sqlite>,
+    user_id: &str,
+    recovery_code_hashes: &[String],
+    now: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM user_totp_recovery_codes WHERE user_id = ?1")
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await?;
+    for code_hash in recovery_code_hashes {
+        sqlx::query(
+            r#"
+            INSERT INTO user_totp_recovery_codes (id, user_id, code_hash, used_at, created_at)
+            VALUES (?1, ?2, ?3, NULL, ?4)
+            "#,
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(code_hash)
+        .bind(now)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::db::pool::create_pool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = create_pool("sqlite::memory:")
+            .await
+            .expect("创建测试数据库失败");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("运行 migrations 失败");
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, username, password_hash, created_at, updated_at)
+            VALUES ('u1', 'user1', 'test_hash', 0, 0)
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .expect("插入测试用户失败");
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_pending_secret_is_replaced_until_enabled() {
+        let pool = setup_test_db().await;
+
+        assert!(
+            TotpRepo::save_pending(&pool, "u1", b"secret-1", b"nonce-1", 1)
+                .await
+                .expect("保存失败")
+        );
+        assert!(
+            TotpRepo::save_pending(&pool, "u1", b"secret-2", b"nonce-2", 2)
+                .await
+                .expect("保存失败")
+        );
+        let record = TotpRepo::find(&pool, "u1")
+            .await
+            .expect("查询失败")
+            .expect("应存在记录");
+        assert_eq!(record.encrypted_secret, b"secret-2");
+        assert!(!record.is_enabled());
+
+        let hashes = vec!["h1".to_string(), "h2".to_string()];
+        assert!(
+            TotpRepo::enable(&pool, "u1", 100, &hashes, 3)
+                .await
+                .expect("启用失败")
+        );
+        assert!(
+            !TotpRepo::enable(&pool, "u1", 101, &hashes, 4)
+                .await
+                .expect("启用失败")
+        );
+        assert!(
+            !TotpRepo::save_pending(&pool, "u1", b"secret-3", b"nonce-3", 5)
+                .await
+                .expect("保存失败"),
+            "已启用时不得覆盖密钥"
+        );
+        let record = TotpRepo::find(&pool, "u1")
+            .await
+            .expect("查询失败")
+            .expect("应存在记录");
+        assert!(record.is_enabled());
+        assert_eq!(record.encrypted_secret, b"secret-2");
+        assert_eq!(record.last_used_step, Some(100));
+    }
+
+    #[tokio::test]
+    async fn test_steps_and_recovery_codes_are_single_use() {
+        let pool = setup_test_db().await;
+        TotpRepo::save_pending(&pool, "u1", b"secret", b"nonce", 1)
+            .await
+            .expect("保存失败");
+        TotpRepo::enable(&pool, "u1", 100, &["h1".to_string(), "h2".to_string()], 2)
+            .await
+            .expect("启用失败");
+
+        assert!(
+            !TotpRepo::record_used_step(&pool, "u1", 100, 3)
+                .await
+                .expect("更新失败")
+        );
+        assert!(
+            TotpRepo::record_used_step(&pool, "u1", 101, 3)
+                .await
+                .expect("更新失败")
+        );
+
+        assert!(
+            TotpRepo::consume_recovery_code(&pool, "u1", "h1", 4)
+                .await
+                .expect("使用失败")
+        );
+        assert!(
+            !TotpRepo::consume_recovery_code(&pool, "u1", "h1", 5)
+                .await
+                .expect("使用失败")
+        );
+        assert_eq!(
+            TotpRepo::count_unused_recovery_codes(&pool, "u1")
+                .await
+                .expect("统计失败"),
+            1
+        );
+
+        assert!(TotpRepo::disable(&pool, "u1").await.expect("停用失败"));
+        assert!(
+            TotpRepo::find(&pool, "u1")
+                .await
+                .expect("查询失败")
+                .is_none()
+        );
+        assert_eq!(
+            TotpRepo::count_unused_recovery_codes(&pool, "u1")
+                .await
+                .expect("统计失败"),
+            0
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! 用户数据密钥仓储
+//! 负责 user_data_keys 表的数据访问（信封加密的 DEK 包装结果）
+
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+
+use crate::shared::time::now_millis;
+
+/// 用户数据密钥记录（数据库行）
+#[derive(Debug, Clone, FromRow)]
+pub struct UserDataKeyRecord {
+    pub user_id: String,
+    pub password_wrapped_key: Vec<u8>,
+    pub password_nonce: Vec<u8>,
+    pub password_salt: Vec<u8>,
+    pub server_wrapped_key: Option<Vec<u8>>,
+    pub server_nonce: Option<Vec<u8>>,
+    pub server_kek_id: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 登录密码包装结果
+#[derive(Debug, Clone)]
+pub struct PasswordWrappedDataKey {
+    pub wrapped_key: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub salt: Vec<u8>,
+}
+
+/// 服务端 KEK 包装结果
+#[derive(Debug, Clone)]
+pub struct ServerWrappedDataKey {
+    pub wrapped_key: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub kek_id: String,
+}
+
+/// 用户数据密钥仓储错误
+#[derive(Error, Debug)]
+pub enum UserDataKeyRepoError {
+    #[error("数据库错误: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+/// 用户数据密钥仓储
+pub struct UserDataKeyRepo;
+
+impl UserDataKeyRepo {
+    /// 查询用户的数据密钥
+    pub async fn find_by_user(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> Result<Option<UserDataKeyRecord>, UserDataKeyRepoError> {
+        let record = sqlx::query_as::<_, UserDataKeyRecord>(
+            r#"
+            SELECT user_id, password_wrapped_key, password_nonce, password_salt,
+                   server_wrapped_key, server_nonce, server_kek_id, created_at, updated_at
+            FROM user_data_keys
+            WHERE user_id = ?1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// 写入新数据密钥；用户已存在数据密钥时不覆盖（并发首次登录只保留一把）
+    ///
+    /// 返回是否实际写入。
+    pub async fn insert_if_absent(
+        pool: &SqlitePool,
+        user_id: &str,
+        password: &PasswordWrappedDataKey,
+        server: Option<&ServerWrappedDataKey>,
+    ) -> Result<bool, UserDataKeyRepoError> {
+        let now = now_millis();
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO user_data_keys (
+                user_id, password_wrapped_key, password_nonce, password_salt,
+                server_wrapped_key, server_nonce, server_kek_id, created_at, updated_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+        )
+        .bind(user_id)
+        .bind(&password.wrapped_key)
+        .bind(&password.nonce)
+        .bind(&password.salt)
+        .bind(server.map(|s| &s.wrapped_key))
+        .bind(server.map(|s| &s.nonce))
+        .bind(server.map(|s| &s.kek_id))
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// 更新服务端 KEK 包装（首次配置 KEK 或 KEK 轮换后重新包装）
+    pub async fn update_server_wrap(
+        pool: &SqlitePool,
+        user_id: &str,
+        server: &ServerWrappedDataKey,
+    ) -> Result<bool, UserDataKeyRepoError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_data_keys
+            SET server_wrapped_key = ?1, server_nonce = ?2, server_kek_id = ?3, updated_at = ?4
+            WHERE user_id = ?5
+            "#,
+        )
+        .bind(&server.wrapped_key)
+        .bind(&server.nonce)
+        .bind(&server.kek_id)
+        .bind(now_millis())
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+/// 在事务内更新登录密码包装（修改密码时与密码哈希一并提交）
+pub(crate) async fn update_password_wrap_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    user_id: &str,
+    password: &PasswordWrappedDataKey,
+    now: i64,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE user_data_keys
+        SET password_wrapped_key = ?1, password_nonce = ?2, password_salt = ?3, updated_at = ?4
+        WHERE user_id = ?5
+        "#,
+    )
+    .bind(&password.wrapped_key)
+    .bind(&password.nonce)
+    .bind(&password.salt)
+    .bind(now)
+    .bind(user_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::db::pool::create_pool;
+
+    async fn setup_db() -> SqlitePool {
+        let pool = create_pool("sqlite::memory:")
+            .await
+            .expect("创建测试数据库失败");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("运行 migrations 失败");
+        sqlx::query(
+            "INSERT INTO users (id, username, password_hash, created_at, updated_at) VALUES ('u1', 'u1', 'h', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    fn password_wrap(marker: u8) -> PasswordWrappedDataKey {
+        PasswordWrappedDataKey {
+            wrapped_key: vec![marker; 48],
+            nonce: vec![marker; 12],
+            salt: vec![marker; 16],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_if_absent_keeps_first_key_and_updates_wraps() {
+        let pool = setup_db().await;
+
+        assert!(
+            UserDataKeyRepo::insert_if_absent(&pool, "u1", &password_wrap(1), None)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !UserDataKeyRepo::insert_if_absent(&pool, "u1", &password_wrap(2), None)
+                .await
+                .unwrap()
+        );
+        let record = UserDataKeyRepo::find_by_user(&pool, "u1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.password_wrapped_key, vec![1; 48]);
+        assert!(record.server_kek_id.is_none());
+
+        let server = ServerWrappedDataKey {
+            wrapped_key: vec![3; 48],
+            nonce: vec![3; 12],
+            kek_id: "kek-1".to_string(),
+        };
+        assert!(
+            UserDataKeyRepo::update_server_wrap(&pool, "u1", &server)
+                .await
+                .unwrap()
+        );
+
+        let mut tx = pool.begin().await.unwrap();
+        assert!(
+            update_password_wrap_tx(&mut tx, "u1", &password_wrap(4), 1)
+                .await
+                .unwrap()
+        );
+        tx.commit().await.unwrap();
+
+        let record = UserDataKeyRepo::find_by_user(&pool, "u1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.password_wrapped_key, vec![4; 48]);
+        assert_eq!(record.server_wrapped_key, Some(vec![3; 48]));
+        assert_eq!(record.server_kek_id.as_deref(), Some("kek-1"));
+        assert!(
+            UserDataKeyRepo::find_by_user(&pool, "missing")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -5,6 +5,12 @@ use sqlx::SqlitePool;
 use thiserror::Error;
 
 use crate::domain::models::User;
+use crate::infra::db::repositories::credential_repo::{
+    ReencryptedCredential, replace_encrypted_key_tx,
+};
+use crate::infra::db::repositories::user_data_key_repo::{
+    PasswordWrappedDataKey, update_password_wrap_tx,
+};
 use crate::shared::time::now_millis;
 
 /// 用户仓储错误
@@ -18,6 +24,12 @@ pub enum UserRepoError {
 
     #[error("用户名已存在")]
     UsernameConflict,
+
+    #[error("凭证在修改密码期间被更新: {0}")]
+    CredentialChanged(String),
+
+    #[error("用户数据密钥不存在")]
+    DataKeyMissing,
 }
 
 /// 用户仓储
@@ -174,4 +186,46 @@ impl UserRepo {
             },
         ))
     }
+
+    /// 修改密码：在同一事务内更新密码哈希、数据密钥的密码包装，并替换迁移到数据密钥的凭证
+    ///
+    /// 任一凭证在此期间被并发修改时整体回滚（返回 `CredentialChanged`），避免留下无法解密的数据。
+    pub async fn change_password(
+        pool: &SqlitePool,
+        user_id: &str,
+        password_hash: &str,
+        data_key_wrap: &PasswordWrappedDataKey,
+        credentials: &[ReencryptedCredential],
+    ) -> Result<(), UserRepoError> {
+        let now = now_millis();
+        let mut tx = pool.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE users SET password_hash = ?1, updated_at = ?2
+            WHERE id = ?3
+            "#,
+        )
+        .bind(password_hash)
+        .bind(now)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(UserRepoError::NotFound);
+        }
+
+        if !update_password_wrap_tx(&mut tx, user_id, data_key_wrap, now).await? {
+            return Err(UserRepoError::DataKeyMissing);
+        }
+
+        for credential in credentials {
+            if !replace_encrypted_key_tx(&mut tx, user_id, credential, now).await? {
+                return Err(UserRepoError::CredentialChanged(credential.id.clone()));
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
 }
@@ -0,0 +1,380 @@
+//! 工作区成员仓储
+//! 负责 workspace_members 表的数据访问（owner / editor / viewer）
+
+use sqlx::{FromRow, SqlitePool};
+use thiserror::Error;
+
+use crate::shared::time::now_millis;
+
+/// 工作区成员角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceRole {
+    /// 创建者：可管理成员、删除工作区
+    Owner,
+    /// 编辑者：可修改测试集、创建 / 控制任务
+    Editor,
+    /// 查看者：只读
+    Viewer,
+}
+
+impl WorkspaceRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkspaceRole::Owner => "owner",
+            WorkspaceRole::Editor => "editor",
+            WorkspaceRole::Viewer => "viewer",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "owner" => Some(WorkspaceRole::Owner),
+            "editor" => Some(WorkspaceRole::Editor),
+            "viewer" => Some(WorkspaceRole::Viewer),
+            _ => None,
+        }
+    }
+
+    /// 是否可修改工作区内容
+    pub fn can_write(&self) -> bool {
+        matches!(self, WorkspaceRole::Owner | WorkspaceRole::Editor)
+    }
+
+    /// 是否可管理成员 / 删除工作区
+    pub fn can_manage(&self) -> bool {
+        matches!(self, WorkspaceRole::Owner)
+    }
+}
+
+/// 工作区成员（含用户名）
+#[derive(Debug, Clone, FromRow)]
+pub struct WorkspaceMemberRecord {
+    pub workspace_id: String,
+    pub user_id: String,
+    pub username: String,
+    pub role: String,
+    pub invited_by: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Error, Debug)]
+pub enum WorkspaceMemberRepoError {
+    #[error("数据库错误: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+
+    #[error("用户不存在")]
+    UserNotFound,
+
+    #[error("该用户已是工作区成员")]
+    AlreadyMember,
+
+    #[error("成员不存在")]
+    NotFound,
+}
+
+pub struct WorkspaceMemberRepo;
+
+impl WorkspaceMemberRepo {
+    /// 查询用户在工作区中的角色（非成员返回 None）
+    pub async fn find_role(
+        pool: &SqlitePool,
+        workspace_id: &str,
+        user_id: &str,
+    ) -> Result<Option<WorkspaceRole>, WorkspaceMemberRepoError> {
+        let role: Option<String> = sqlx::query_scalar(
+            "SELECT role FROM workspace_members WHERE workspace_id = ?1 AND user_id = ?2",
+        )
+        .bind(workspace_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(role.as_deref().and_then(WorkspaceRole::parse))
+    }
+
+    /// 查询用户在任务所属工作区中的角色（任务不存在或非成员返回 None）
+    pub async fn find_role_for_task(
+        pool: &SqlitePool,
+        task_id: &str,
+        user_id: &str,
+    ) -> Result<Option<WorkspaceRole>, WorkspaceMemberRepoError> {
+        let role: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT m.role
+            FROM optimization_tasks t
+            JOIN workspace_members m ON m.workspace_id = t.workspace_id
+            WHERE t.id = ?1 AND m.user_id = ?2
+            "#,
+        )
+        .bind(task_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(role.as_deref().and_then(WorkspaceRole::parse))
+    }
+
+    /// 列出工作区成员（owner 在前，其余按加入时间）
+    pub async fn list_by_workspace(
+        pool: &SqlitePool,
+        workspace_id: &str,
+    ) -> Result<Vec<WorkspaceMemberRecord>, WorkspaceMemberRepoError> {
+        let members = sqlx::query_as::<_, WorkspaceMemberRecord>(
+            r#"
+            SELECT m.workspace_id, m.user_id, u.username, m.role, m.invited_by, m.created_at, m.updated_at
+            FROM workspace_members m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.workspace_id = ?1
+            ORDER BY CASE m.role WHEN 'owner' THEN 0 ELSE 1 END, m.created_at ASC
+            "#,
+        )
+        .bind(workspace_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    /// 按用户名邀请成员（不可授予 owner）
+    pub async fn add_by_username(
+        pool: &SqlitePool,
+        workspace_id: &str,
+        username: &str,
+        role: WorkspaceRole,
+        invited_by: &str,
+    ) -> Result<WorkspaceMemberRecord, WorkspaceMemberRepoError> {
+        debug_assert!(role != WorkspaceRole::Owner, "owner 仅由创建工作区产生");
+
+        let user: Option<(String, String)> =
+            sqlx::query_as("SELECT id, username FROM users WHERE username = ?1")
+                .bind(username)
+                .fetch_optional(pool)
+                .await?;
+        let Some((user_id, username)) = user else {
+            return Err(WorkspaceMemberRepoError::UserNotFound);
+        };
+
+        let now = now_millis();
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO workspace_members (workspace_id, user_id, role, invited_by, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+            "#,
+        )
+        .bind(workspace_id)
+        .bind(&user_id)
+        .bind(role.as_str())
+        .bind(invited_by)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(WorkspaceMemberRepoError::AlreadyMember);
+        }
+
+        Ok(WorkspaceMemberRecord {
+            workspace_id: workspace_id.to_string(),
+            user_id,
+            username,
+            role: role.as_str().to_string(),
+            invited_by: Some(invited_by.to_string()),
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// 修改非 owner 成员的角色
+    pub async fn update_role(
+        pool: &SqlitePool,
+        workspace_id: &str,
+        user_id: &str,
+        role: WorkspaceRole,
+    ) -> Result<(), WorkspaceMemberRepoError> {
+        debug_assert!(role != WorkspaceRole::Owner, "owner 仅由创建工作区产生");
+
+        let result = sqlx::query(
+            r#"
+            UPDATE workspace_members
+            SET role = ?1, updated_at = ?2
+            WHERE workspace_id = ?3 AND user_id = ?4 AND role != 'owner'
+            "#,
+        )
+        .bind(role.as_str())
+        .bind(now_millis())
+        .bind(workspace_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(WorkspaceMemberRepoError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// 移除非 owner 成员
+    pub async fn remove(
+        pool: &SqlitePool,
+        workspace_id: &str,
+        user_id: &str,
+    ) -> Result<bool, WorkspaceMemberRepoError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM workspace_members
+            WHERE workspace_id = ?1 AND user_id = ?2 AND role != 'owner'
+            "#,
+        )
+        .bind(workspace_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::db::pool::create_pool;
+    use crate::infra::db::repositories::WorkspaceRepo;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = create_pool("sqlite::memory:")
+            .await
+            .expect("创建测试数据库失败");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("运行 migrations 失败");
+        pool
+    }
+
+    async fn insert_user(pool: &SqlitePool, id: &str, username: &str) {
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, username, password_hash, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(username)
+        .bind("test_hash")
+        .bind(0_i64)
+        .bind(0_i64)
+        .execute(pool)
+        .await
+        .expect("插入测试用户失败");
+    }
+
+    #[tokio::test]
+    async fn test_creator_becomes_owner_and_members_can_be_invited() {
+        let pool = setup_test_db().await;
+        insert_user(&pool, "u1", "alice").await;
+        insert_user(&pool, "u2", "bob").await;
+
+        let workspace = WorkspaceRepo::create(&pool, "u1", "ws", None)
+            .await
+            .expect("创建工作区失败");
+        assert_eq!(
+            WorkspaceMemberRepo::find_role(&pool, &workspace.id, "u1")
+                .await
+                .expect("查询角色失败"),
+            Some(WorkspaceRole::Owner)
+        );
+
+        let member = WorkspaceMemberRepo::add_by_username(
+            &pool,
+            &workspace.id,
+            "bob",
+            WorkspaceRole::Viewer,
+            "u1",
+        )
+        .await
+        .expect("邀请成员失败");
+        assert_eq!(member.user_id, "u2");
+
+        let err = WorkspaceMemberRepo::add_by_username(
+            &pool,
+            &workspace.id,
+            "bob",
+            WorkspaceRole::Editor,
+            "u1",
+        )
+        .await
+        .expect_err("重复邀请应失败");
+        assert!(matches!(err, WorkspaceMemberRepoError::AlreadyMember));
+
+        let err = WorkspaceMemberRepo::add_by_username(
+            &pool,
+            &workspace.id,
+            "nobody",
+            WorkspaceRole::Editor,
+            "u1",
+        )
+        .await
+        .expect_err("不存在的用户应失败");
+        assert!(matches!(err, WorkspaceMemberRepoError::UserNotFound));
+
+        let members = WorkspaceMemberRepo::list_by_workspace(&pool, &workspace.id)
+            .await
+            .expect("列出成员失败");
+        let roles: Vec<_> = members.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["owner", "viewer"]);
+    }
+
+    #[tokio::test]
+    async fn test_owner_cannot_be_demoted_or_removed() {
+        let pool = setup_test_db().await;
+        insert_user(&pool, "u1", "alice").await;
+        insert_user(&pool, "u2", "bob").await;
+
+        let workspace = WorkspaceRepo::create(&pool, "u1", "ws", None)
+            .await
+            .expect("创建工作区失败");
+        WorkspaceMemberRepo::add_by_username(
+            &pool,
+            &workspace.id,
+            "bob",
+            WorkspaceRole::Viewer,
+            "u1",
+        )
+        .await
+        .expect("邀请成员失败");
+
+        WorkspaceMemberRepo::update_role(&pool, &workspace.id, "u2", WorkspaceRole::Editor)
+            .await
+            .expect("修改角色失败");
+        assert_eq!(
+            WorkspaceMemberRepo::find_role(&pool, &workspace.id, "u2")
+                .await
+                .expect("查询角色失败"),
+            Some(WorkspaceRole::Editor)
+        );
+
+        let err =
+            WorkspaceMemberRepo::update_role(&pool, &workspace.id, "u1", WorkspaceRole::Viewer)
+                .await
+                .expect_err("不能修改 owner 角色");
+        assert!(matches!(err, WorkspaceMemberRepoError::NotFound));
+        assert!(
+            !WorkspaceMemberRepo::remove(&pool, &workspace.id, "u1")
+                .await
+                .expect("移除失败")
+        );
+        assert!(
+            WorkspaceMemberRepo::remove(&pool, &workspace.id, "u2")
+                .await
+                .expect("移除失败")
+        );
+        assert_eq!(
+            WorkspaceMemberRepo::find_role(&pool, &workspace.id, "u2")
+                .await
+                .expect("查询角色失败"),
+            None
+        );
+    }
+}
@@ -2,6 +2,7 @@ use sqlx::SqlitePool;
 use thiserror::Error;
 
 use crate::domain::models::Workspace;
+use crate::infra::db::repositories::WorkspaceRole;
 use crate::shared::time::now_millis;
 
 #[derive(Error, Debug)]
@@ -50,16 +51,29 @@ impl WorkspaceRepo {
         })
     }
 
+    /// 查询当前用户可访问（任一成员角色）的工作区
     pub async fn find_by_id(
         pool: &SqlitePool,
         workspace_id: &str,
         user_id: &str,
     ) -> Result<Workspace, WorkspaceRepoError> {
-        let row = sqlx::query_as::<_, (String, String, String, Option<String>, i64, i64)>(
+        Self::find_with_role_by_id(pool, workspace_id, user_id)
+            .await
+            .map(|(workspace, _)| workspace)
+    }
+
+    /// 查询工作区及当前用户的成员角色（非成员返回 NotFound）
+    pub async fn find_with_role_by_id(
+        pool: &SqlitePool,
+        workspace_id: &str,
+        user_id: &str,
+    ) -> Result<(Workspace, WorkspaceRole), WorkspaceRepoError> {
+        let row = sqlx::query_as::<_, WorkspaceRow>(
             r#"
-            SELECT id, user_id, name, description, created_at, updated_at
-            FROM workspaces
-            WHERE id = ?1 AND user_id = ?2
+            SELECT w.id, w.user_id, w.name, w.description, w.created_at, w.updated_at, m.role
+            FROM workspaces w
+            JOIN workspace_members m ON m.workspace_id = w.id
+            WHERE w.id = ?1 AND m.user_id = ?2
             "#,
         )
         .bind(workspace_id)
@@ -67,50 +81,44 @@ impl WorkspaceRepo {
         .fetch_optional(pool)
         .await?;
 
-        match row {
-            Some((id, user_id, name, description, created_at, updated_at)) => Ok(Workspace {
-                id,
-                user_id,
-                name,
-                description,
-                created_at,
-                updated_at,
-            }),
-            None => Err(WorkspaceRepoError::NotFound),
-        }
+        row.and_then(row_to_workspace)
+            .ok_or(WorkspaceRepoError::NotFound)
     }
 
+    /// 列出当前用户参与的全部工作区
     pub async fn find_all_by_user(
         pool: &SqlitePool,
         user_id: &str,
     ) -> Result<Vec<Workspace>, WorkspaceRepoError> {
-        let rows = sqlx::query_as::<_, (String, String, String, Option<String>, i64, i64)>(
+        Ok(Self::find_all_with_roles_by_user(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|(workspace, _)| workspace)
+            .collect())
+    }
+
+    /// 列出当前用户参与的全部工作区及其成员角色
+    pub async fn find_all_with_roles_by_user(
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> Result<Vec<(Workspace, WorkspaceRole)>, WorkspaceRepoError> {
+        let rows = sqlx::query_as::<_, WorkspaceRow>(
             r#"
-            SELECT id, user_id, name, description, created_at, updated_at
-            FROM workspaces
-            WHERE user_id = ?1
-            ORDER BY created_at DESC
+            SELECT w.id, w.user_id, w.name, w.description, w.created_at, w.updated_at, m.role
+            FROM workspaces w
+            JOIN workspace_members m ON m.workspace_id = w.id
+            WHERE m.user_id = ?1
+            ORDER BY w.created_at DESC
             "#,
         )
         .bind(user_id)
         .fetch_all(pool)
         .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(
-                |(id, user_id, name, description, created_at, updated_at)| Workspace {
-                    id,
-                    user_id,
-                    name,
-                    description,
-                    created_at,
-                    updated_at,
-                },
-            )
-            .collect())
+        Ok(rows.into_iter().filter_map(row_to_workspace).collect())
     }
 
+    /// 删除工作区（仅 owner）
     pub async fn delete(
         pool: &SqlitePool,
         workspace_id: &str,
@@ -119,7 +127,11 @@ impl WorkspaceRepo {
         let result = sqlx::query(
             r#"
             DELETE FROM workspaces
-            WHERE id = ?1 AND user_id = ?2
+            WHERE id = ?1
+              AND EXISTS (
+                SELECT 1 FROM workspace_members m
+                WHERE m.workspace_id = ?1 AND m.user_id = ?2 AND m.role = 'owner'
+              )
             "#,
         )
         .bind(workspace_id)
@@ -131,10 +143,30 @@ impl WorkspaceRepo {
     }
 }
 
+type WorkspaceRow = (String, String, String, Option<String>, i64, i64, String);
+
+fn row_to_workspace(
+    (id, user_id, name, description, created_at, updated_at, role): WorkspaceRow,
+) -> Option<(Workspace, WorkspaceRole)> {
+    let role = WorkspaceRole::parse(&role)?;
+    Some((
+        Workspace {
+            id,
+            user_id,
+            name,
+            description,
+            created_at,
+            updated_at,
+        },
+        role,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::infra::db::pool::create_pool;
+    use crate::infra::db::repositories::WorkspaceMemberRepo;
 
     async fn setup_test_db() -> SqlitePool {
         let pool = create_pool("sqlite::memory:")
@@ -249,4 +281,42 @@ mod tests {
             .expect("删除失败");
         assert!(deleted_ok);
     }
+
+    #[tokio::test]
+    async fn test_shared_member_can_read_but_not_delete() {
+        let pool = setup_test_db().await;
+
+        insert_user(&pool, "u1", "user1").await;
+        insert_user(&pool, "u2", "user2").await;
+
+        let created = WorkspaceRepo::create(&pool, "u1", "ws", None)
+            .await
+            .expect("创建工作区失败");
+        WorkspaceMemberRepo::add_by_username(
+            &pool,
+            &created.id,
+            "user2",
+            WorkspaceRole::Editor,
+            "u1",
+        )
+        .await
+        .expect("邀请成员失败");
+
+        let (loaded, role) = WorkspaceRepo::find_with_role_by_id(&pool, &created.id, "u2")
+            .await
+            .expect("成员应可访问工作区");
+        assert_eq!(loaded.user_id, "u1");
+        assert_eq!(role, WorkspaceRole::Editor);
+
+        let list_u2 = WorkspaceRepo::find_all_with_roles_by_user(&pool, "u2")
+            .await
+            .expect("查询列表失败");
+        assert_eq!(list_u2.len(), 1);
+        assert_eq!(list_u2[0].1, WorkspaceRole::Editor);
+
+        let deleted = WorkspaceRepo::delete(&pool, &created.id, "u2")
+            .await
+            .expect("删除失败");
+        assert!(!deleted);
+    }
 }
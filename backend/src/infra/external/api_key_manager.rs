@@ -1,15 +1,21 @@
 //! API Key 加密管理
-//! 使用 AES-GCM + Argon2 派生密钥 (NFR9)
+//! 使用 AES-GCM + Argon2 派生密钥 (NFR9)，凭证采用信封加密
 //!
 //! # 安全说明
-//! - 使用 Argon2id 从用户登录密码（会话内存副本）派生 256 位密钥
-//! - 使用 AES-256-GCM 加密 API Key
-//! - 每次加密生成随机 12 字节 nonce
-//! - 每条记录使用独立的 salt
+//! - 每个用户一把随机 256 位数据密钥（DEK），凭证使用 AES-256-GCM 由 DEK 直接加密
+//! - DEK 由用户登录密码（会话内存副本）经 Argon2id 派生的密钥包装，存于 `user_data_keys`
+//! - 如配置服务端密钥加密密钥（KEK），DEK 同时由 KEK 包装，后台任务可在无登录会话时解锁
+//! - 个人访问令牌可携带由令牌本身派生的密钥包装的 DEK（见 [`DataKey::wrap_for_token`]）
+//! - 每次加密生成随机 12 字节 nonce；密码派生使用每条记录独立的 salt
+//!
+//! # 存储格式
+//! - 信封加密的凭证 `salt` 为空（密钥来自 DEK，无需派生）
+//! - `salt` 为 16 字节的凭证为旧格式（直接由登录密码派生），登录时迁移到信封加密
 //!
 //! # 向后兼容
 //! - 如设置 `MASTER_PASSWORD`，可用于解密旧数据（旧版本使用全局 master_password 派生）
-//! - 新写入数据始终使用用户登录密码派生（UnlockContext）
+
+use std::sync::Arc;
 
 use aes_gcm::{
     Aes256Gcm, Key, Nonce,
@@ -17,9 +23,15 @@ use aes_gcm::{
 };
 use argon2::{Argon2, Params, Version};
 use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
 use thiserror::Error;
 use zeroize::Zeroizing;
 
+use crate::infra::db::repositories::{
+    PasswordWrappedDataKey, ServerWrappedDataKey, TokenWrappedDataKey, UserDataKeyRecord,
+    UserDataKeyRepo,
+};
 use crate::shared::log_sanitizer::sanitize_api_key;
 
 /// AES-GCM nonce 长度（12 字节 = 96 bits，AES-GCM 标准）
@@ -31,6 +43,15 @@ pub const SALT_LENGTH: usize = 16;
 /// 派生密钥长度（32 字节 = 256 bits，用于 AES-256）
 const KEY_LENGTH: usize = 32;
 
+/// 服务端 KEK（十六进制）环境变量
+pub const CREDENTIAL_KEK_ENV: &str = "CREDENTIAL_KEK";
+
+/// 服务端 KEK 文件路径环境变量（文件内容为十六进制）
+pub const CREDENTIAL_KEK_FILE_ENV: &str = "CREDENTIAL_KEK_FILE";
+
+/// 个人访问令牌包装密钥的派生域
+const TOKEN_KEY_DOMAIN: &[u8] = b"prompt-faster/access-token-data-key/v1:";
+
 /// API Key 加解密错误
 #[derive(Error, Debug)]
 pub enum ApiKeyError {
@@ -48,6 +69,15 @@ pub enum ApiKeyError {
 
     #[error("无效的 salt 长度: 期望 {expected}，实际 {actual}")]
     InvalidSaltLength { expected: usize, actual: usize },
+
+    #[error("数据密钥不可用: {0}")]
+    DataKeyUnavailable(String),
+
+    #[error("无效的服务端密钥: {0}")]
+    InvalidServerKek(String),
+
+    #[error("数据密钥存储失败: {0}")]
+    Storage(String),
 }
 
 /// 加密后的 API Key 数据
@@ -57,17 +87,221 @@ pub struct EncryptedApiKey {
     pub ciphertext: Vec<u8>,
     /// 12 字节随机 nonce
     pub nonce: Vec<u8>,
-    /// 16 字节随机 salt（用于 Argon2 派生密钥）
+    /// 16 字节随机 salt（用于 Argon2 派生密钥）；信封加密时为空
     pub salt: Vec<u8>,
 }
 
+impl EncryptedApiKey {
+    /// 是否为信封加密（由 DEK 直接加密）
+    pub fn is_envelope(&self) -> bool {
+        self.salt.is_empty()
+    }
+}
+
+/// 迁移到数据密钥后的加密结果
+#[derive(Debug, Clone)]
+pub struct ReencryptedApiKey {
+    /// 数据密钥加密后的数据
+    pub encrypted: EncryptedApiKey,
+    /// 原数据是否由 legacy `MASTER_PASSWORD` 加密（本次已迁移）
+    pub from_legacy: bool,
+}
+
+/// 用户数据密钥（DEK），Drop 时清零
+#[derive(Clone)]
+pub struct DataKey(Arc<Zeroizing<[u8; KEY_LENGTH]>>);
+
+impl std::fmt::Debug for DataKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DataKey(<redacted>)")
+    }
+}
+
+impl DataKey {
+    /// 生成随机数据密钥
+    pub fn generate() -> Self {
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        rand::thread_rng().fill_bytes(&mut key[..]);
+        Self(Arc::new(key))
+    }
+
+    fn from_slice(bytes: &[u8]) -> Result<Self, ApiKeyError> {
+        let key: [u8; KEY_LENGTH] = bytes
+            .try_into()
+            .map_err(|_| ApiKeyError::DecryptionFailed("数据密钥长度无效".to_string()))?;
+        Ok(Self(Arc::new(Zeroizing::new(key))))
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.0[..]
+    }
+
+    /// 使用个人访问令牌派生的密钥包装数据密钥
+    ///
+    /// 令牌为高熵随机串，包装密钥直接由带域分隔的 SHA-256 派生；数据库只保存令牌摘要，
+    /// 无法据此解包。
+    pub fn wrap_for_token(&self, token: &str) -> Result<TokenWrappedDataKey, ApiKeyError> {
+        let key = derive_token_key(token);
+        let (wrapped_key, nonce) = encrypt_with_key(&key[..], self.as_bytes())?;
+        Ok(TokenWrappedDataKey { wrapped_key, nonce })
+    }
+
+    /// 使用个人访问令牌解包数据密钥
+    pub fn unwrap_for_token(
+        token: &str,
+        wrapped: &TokenWrappedDataKey,
+    ) -> Result<Self, ApiKeyError> {
+        validate_nonce(&wrapped.nonce)?;
+        let key = derive_token_key(token);
+        let encrypted = EncryptedApiKey {
+            ciphertext: wrapped.wrapped_key.clone(),
+            nonce: wrapped.nonce.clone(),
+            salt: Vec::new(),
+        };
+        let bytes = decrypt_with_key(&key[..], &encrypted)
+            .map(Zeroizing::new)
+            .map_err(|e| ApiKeyError::DecryptionFailed(e.to_string()))?;
+        Self::from_slice(&bytes)
+    }
+
+    /// 使用数据密钥加密其他账户机密（如 TOTP 密钥）
+    pub fn seal(&self, plaintext: &[u8]) -> Result<EncryptedApiKey, ApiKeyError> {
+        encrypt_with_data_key(self, plaintext)
+    }
+
+    /// 解密由 [`DataKey::seal`] 加密的数据
+    pub fn open(&self, encrypted: &EncryptedApiKey) -> Result<Zeroizing<Vec<u8>>, ApiKeyError> {
+        validate_nonce(&encrypted.nonce)?;
+        decrypt_with_key(self.as_bytes(), encrypted)
+            .map(Zeroizing::new)
+            .map_err(|e| ApiKeyError::DecryptionFailed(e.to_string()))
+    }
+}
+
+/// 服务端密钥加密密钥（KEK）
+///
+/// 32 字节，以十六进制从 `CREDENTIAL_KEK` 或 `CREDENTIAL_KEK_FILE` 指向的文件读取；
+/// `id` 为密钥指纹，用于识别 DEK 由哪把 KEK 包装（轮换时重新包装）。
+pub struct ServerKek {
+    key: Zeroizing<[u8; KEY_LENGTH]>,
+    id: String,
+}
+
+impl std::fmt::Debug for ServerKek {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerKek")
+            .field("id", &self.id)
+            .field("key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl ServerKek {
+    /// 从十六进制字符串解析（64 个十六进制字符）
+    pub fn from_hex(hex: &str) -> Result<Self, ApiKeyError> {
+        let hex = hex.trim();
+        if hex.len() != KEY_LENGTH * 2 {
+            return Err(ApiKeyError::InvalidServerKek(format!(
+                "需要 {} 个十六进制字符，实际 {}",
+                KEY_LENGTH * 2,
+                hex.len()
+            )));
+        }
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .map_err(|_| ApiKeyError::InvalidServerKek("包含非十六进制字符".to_string()))?;
+        }
+        let digest = Sha256::digest(&key[..]);
+        let id = digest[..8].iter().map(|b| format!("{b:02x}")).collect();
+        Ok(Self { key, id })
+    }
+
+    /// 从环境变量读取（优先 `CREDENTIAL_KEK`，其次 `CREDENTIAL_KEK_FILE`）；均未设置时返回 None
+    pub fn from_env() -> Result<Option<Self>, ApiKeyError> {
+        if let Some(hex) = std::env::var(CREDENTIAL_KEK_ENV)
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+        {
+            return Self::from_hex(&hex).map(Some);
+        }
+        let Some(path) = std::env::var(CREDENTIAL_KEK_FILE_ENV)
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+        else {
+            return Ok(None);
+        };
+        let content = Zeroizing::new(
+            std::fs::read_to_string(&path)
+                .map_err(|e| ApiKeyError::InvalidServerKek(format!("读取 {path} 失败: {e}")))?,
+        );
+        Self::from_hex(&content).map(Some)
+    }
+
+    /// 密钥指纹
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// 凭证解锁材料：数据密钥（信封加密）与登录密码（解密旧格式数据）
+///
+/// 登录会话同时持有两者；无会话的后台任务仅持有经服务端 KEK 解锁的数据密钥。
+#[derive(Clone, Default)]
+pub struct CredentialKeys {
+    data_key: Option<DataKey>,
+    password: Option<Arc<Zeroizing<Vec<u8>>>>,
+}
+
+impl std::fmt::Debug for CredentialKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CredentialKeys")
+            .field("data_key", &self.data_key.is_some())
+            .field("password", &self.password.is_some())
+            .finish()
+    }
+}
+
+impl CredentialKeys {
+    /// 仅使用登录密码（尚无数据密钥时，写入旧格式数据）
+    pub fn from_password(password: &[u8]) -> Self {
+        Self {
+            data_key: None,
+            password: Some(Arc::new(Zeroizing::new(password.to_vec()))),
+        }
+    }
+
+    /// 仅使用数据密钥（后台任务）
+    pub fn from_data_key(data_key: DataKey) -> Self {
+        Self {
+            data_key: Some(data_key),
+            password: None,
+        }
+    }
+
+    /// 附加数据密钥
+    pub fn with_data_key(mut self, data_key: DataKey) -> Self {
+        self.data_key = Some(data_key);
+        self
+    }
+
+    pub fn data_key(&self) -> Option<&DataKey> {
+        self.data_key.as_ref()
+    }
+
+    fn password(&self) -> Option<&[u8]> {
+        self.password.as_deref().map(|p| p.as_slice())
+    }
+}
+
 /// API Key 管理器
 ///
-/// 负责 API Key 的加密和解密操作。
-/// 使用 Argon2id 从用户登录密码派生密钥，AES-256-GCM 加密数据。
+/// 负责 API Key 的加密和解密，以及用户数据密钥的创建、包装与解锁。
 pub struct ApiKeyManager {
     /// legacy 主密码（仅用于向后兼容解密旧数据）
     legacy_master_password: Option<Zeroizing<Vec<u8>>>,
+    /// 服务端 KEK（可选，用于无会话解锁数据密钥）
+    server_kek: Option<ServerKek>,
 }
 
 impl ApiKeyManager {
@@ -80,10 +314,22 @@ impl ApiKeyManager {
             legacy_master_password: legacy_master_password
                 .filter(|s| !s.is_empty())
                 .map(|s| Zeroizing::new(s.into_bytes())),
+            server_kek: None,
         }
     }
 
-    /// 加密 API Key
+    /// 配置服务端 KEK
+    pub fn with_server_kek(mut self, server_kek: Option<ServerKek>) -> Self {
+        self.server_kek = server_kek;
+        self
+    }
+
+    /// 当前服务端 KEK 指纹（未配置时为 None）
+    pub fn server_kek_id(&self) -> Option<&str> {
+        self.server_kek.as_ref().map(ServerKek::id)
+    }
+
+    /// 加密 API Key（登录密码派生，旧格式）
     ///
     /// # Arguments
     /// * `user_password` - 用户登录密码（来自 UnlockContext，仅存在内存）
@@ -100,30 +346,7 @@ impl ApiKeyManager {
             api_key = %sanitize_api_key(api_key),
             "加密 API Key"
         );
-
-        // 生成随机 salt
-        let salt = generate_random_bytes(SALT_LENGTH);
-
-        // 生成随机 nonce
-        let nonce_bytes = generate_random_bytes(NONCE_LENGTH);
-
-        // 派生密钥
-        let key = derive_key(user_password, &salt)?;
-
-        // 创建 AES-GCM 密码器
-        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key[..]));
-        let nonce = Nonce::from_slice(&nonce_bytes);
-
-        // 加密
-        let ciphertext = cipher
-            .encrypt(nonce, api_key.as_bytes())
-            .map_err(|e| ApiKeyError::EncryptionFailed(e.to_string()))?;
-
-        Ok(EncryptedApiKey {
-            ciphertext,
-            nonce: nonce_bytes,
-            salt,
-        })
+        encrypt_with_password(user_password, api_key.as_bytes())
     }
 
     /// 解密 API Key
@@ -157,13 +380,216 @@ impl ApiKeyManager {
         user_password: &[u8],
         encrypted: &EncryptedApiKey,
     ) -> Result<Zeroizing<Vec<u8>>, ApiKeyError> {
+        self.decrypt_with_source(Some(user_password), encrypted)
+            .map(|(plaintext, _)| plaintext)
+    }
+
+    /// 加密凭证 API Key
+    ///
+    /// 持有数据密钥时使用信封加密；否则回退到登录密码派生（旧格式，下次登录时迁移）。
+    pub fn encrypt_credential(
+        &self,
+        keys: &CredentialKeys,
+        api_key: &str,
+    ) -> Result<EncryptedApiKey, ApiKeyError> {
+        match (keys.data_key(), keys.password()) {
+            (Some(data_key), _) => {
+                tracing::debug!(
+                    api_key = %sanitize_api_key(api_key),
+                    "使用数据密钥加密 API Key"
+                );
+                encrypt_with_data_key(data_key, api_key.as_bytes())
+            }
+            (None, Some(password)) => self.encrypt(password, api_key),
+            (None, None) => Err(ApiKeyError::DataKeyUnavailable(
+                "缺少数据密钥与登录密码".to_string(),
+            )),
+        }
+    }
+
+    /// 解密凭证 API Key（字节形式）
+    ///
+    /// 信封加密数据使用数据密钥；旧格式数据使用登录密码派生（失败时回退 legacy 主密码）。
+    pub fn decrypt_credential(
+        &self,
+        keys: &CredentialKeys,
+        encrypted: &EncryptedApiKey,
+    ) -> Result<Zeroizing<Vec<u8>>, ApiKeyError> {
+        if !encrypted.is_envelope() {
+            return self
+                .decrypt_with_source(keys.password(), encrypted)
+                .map(|(plaintext, _)| plaintext);
+        }
+
+        validate_nonce(&encrypted.nonce)?;
+        let data_key = keys.data_key().ok_or_else(|| {
+            ApiKeyError::DataKeyUnavailable("凭证已使用数据密钥加密，需要解锁数据密钥".to_string())
+        })?;
+        decrypt_with_key(data_key.as_bytes(), encrypted)
+            .map(Zeroizing::new)
+            .map_err(|e| ApiKeyError::DecryptionFailed(e.to_string()))
+    }
+
+    /// 将旧格式数据迁移到数据密钥加密
+    ///
+    /// 先用登录密码解密（失败时回退 legacy `MASTER_PASSWORD`），再由数据密钥加密。
+    pub fn migrate_to_data_key(
+        &self,
+        user_password: &[u8],
+        data_key: &DataKey,
+        encrypted: &EncryptedApiKey,
+    ) -> Result<ReencryptedApiKey, ApiKeyError> {
+        let (plaintext, from_legacy) = self.decrypt_with_source(Some(user_password), encrypted)?;
+        Ok(ReencryptedApiKey {
+            encrypted: encrypt_with_data_key(data_key, &plaintext)?,
+            from_legacy,
+        })
+    }
+
+    /// 使用登录密码派生的密钥包装数据密钥
+    pub fn wrap_data_key_for_password(
+        &self,
+        user_password: &[u8],
+        data_key: &DataKey,
+    ) -> Result<PasswordWrappedDataKey, ApiKeyError> {
+        let wrapped = encrypt_with_password(user_password, data_key.as_bytes())?;
+        Ok(PasswordWrappedDataKey {
+            wrapped_key: wrapped.ciphertext,
+            nonce: wrapped.nonce,
+            salt: wrapped.salt,
+        })
+    }
+
+    /// 使用登录密码解锁用户数据密钥
+    ///
+    /// - 用户尚无数据密钥时生成并保存（同时由服务端 KEK 包装，如已配置）
+    /// - 已配置 KEK 但数据密钥未由当前 KEK 包装时（首次配置或 KEK 轮换），重新包装
+    pub async fn unlock_data_key(
+        &self,
+        pool: &SqlitePool,
+        user_id: &str,
+        user_password: &[u8],
+    ) -> Result<DataKey, ApiKeyError> {
+        let record = match find_data_key(pool, user_id).await? {
+            Some(record) => record,
+            None => {
+                let data_key = DataKey::generate();
+                let password_wrap = self.wrap_data_key_for_password(user_password, &data_key)?;
+                let server_wrap = self
+                    .server_kek
+                    .as_ref()
+                    .map(|kek| wrap_data_key_for_server(kek, &data_key))
+                    .transpose()?;
+                let inserted = UserDataKeyRepo::insert_if_absent(
+                    pool,
+                    user_id,
+                    &password_wrap,
+                    server_wrap.as_ref(),
+                )
+                .await
+                .map_err(|e| ApiKeyError::Storage(e.to_string()))?;
+                if inserted {
+                    tracing::info!(user_id = %user_id, "已创建用户数据密钥");
+                    return Ok(data_key);
+                }
+                // 并发创建：以先写入者为准
+                find_data_key(pool, user_id)
+                    .await?
+                    .ok_or_else(|| ApiKeyError::Storage("数据密钥写入后未找到".to_string()))?
+            }
+        };
+
+        let data_key = unwrap_password_wrapped_data_key(&record, user_password)?;
+
+        if let Some(kek) = &self.server_kek
+            && record.server_kek_id.as_deref() != Some(kek.id())
+        {
+            let server_wrap = wrap_data_key_for_server(kek, &data_key)?;
+            match UserDataKeyRepo::update_server_wrap(pool, user_id, &server_wrap).await {
+                Ok(_) => tracing::info!(
+                    user_id = %user_id,
+                    kek_id = %kek.id(),
+                    "已使用当前服务端密钥重新包装数据密钥"
+                ),
+                Err(e) => tracing::warn!(
+                    user_id = %user_id,
+                    error = %e,
+                    "服务端密钥包装数据密钥失败"
+                ),
+            }
+        }
+
+        Ok(data_key)
+    }
+
+    /// 使用登录密码只读解锁已有的数据密钥（不创建、不重新包装，无任何写入）
+    ///
+    /// 用于完成全部认证因子之前的校验（如登录时解密两步验证密钥）。
+    pub async fn open_data_key(
+        &self,
+        pool: &SqlitePool,
+        user_id: &str,
+        user_password: &[u8],
+    ) -> Result<DataKey, ApiKeyError> {
+        let record = find_data_key(pool, user_id)
+            .await?
+            .ok_or_else(|| ApiKeyError::DataKeyUnavailable("用户尚未创建数据密钥".to_string()))?;
+        unwrap_password_wrapped_data_key(&record, user_password)
+    }
+
+    /// 使用服务端 KEK 解锁用户数据密钥（无登录会话的后台任务）
+    ///
+    /// 要求已配置 KEK，且数据密钥由当前 KEK 包装（用户在配置/轮换 KEK 后至少登录过一次）。
+    pub async fn unlock_data_key_unattended(
+        &self,
+        pool: &SqlitePool,
+        user_id: &str,
+    ) -> Result<DataKey, ApiKeyError> {
+        let kek = self
+            .server_kek
+            .as_ref()
+            .ok_or_else(|| ApiKeyError::DataKeyUnavailable("未配置服务端密钥".to_string()))?;
+        let record = find_data_key(pool, user_id)
+            .await?
+            .ok_or_else(|| ApiKeyError::DataKeyUnavailable("用户尚未创建数据密钥".to_string()))?;
+        let (Some(wrapped_key), Some(nonce)) = (record.server_wrapped_key, record.server_nonce)
+        else {
+            return Err(ApiKeyError::DataKeyUnavailable(
+                "数据密钥未由服务端密钥包装，需用户重新登录".to_string(),
+            ));
+        };
+        if record.server_kek_id.as_deref() != Some(kek.id()) {
+            return Err(ApiKeyError::DataKeyUnavailable(
+                "数据密钥由其他服务端密钥包装，需用户重新登录".to_string(),
+            ));
+        }
+
+        validate_nonce(&nonce)?;
+        let wrapped = EncryptedApiKey {
+            ciphertext: wrapped_key,
+            nonce,
+            salt: Vec::new(),
+        };
+        let bytes = decrypt_with_key(&kek.key[..], &wrapped)
+            .map(Zeroizing::new)
+            .map_err(|e| ApiKeyError::DecryptionFailed(e.to_string()))?;
+        DataKey::from_slice(&bytes)
+    }
+
+    /// 解密并返回明文是否来自 legacy 主密码
+    fn decrypt_with_source(
+        &self,
+        user_password: Option<&[u8]>,
+        encrypted: &EncryptedApiKey,
+    ) -> Result<(Zeroizing<Vec<u8>>, bool), ApiKeyError> {
         validate_encrypted(encrypted)?;
 
         // 主路径：用户登录密码派生
-        if let Ok(key) = derive_key(user_password, &encrypted.salt) {
-            if let Ok(plaintext) = decrypt_with_key(&key[..], encrypted) {
-                return Ok(Zeroizing::new(plaintext));
-            }
+        if let Some(user_password) = user_password
+            && let Ok(key) = derive_key(user_password, &encrypted.salt)
+            && let Ok(plaintext) = decrypt_with_key(&key[..], encrypted)
+        {
+            return Ok((Zeroizing::new(plaintext), false));
         }
 
         // 向后兼容：legacy MASTER_PASSWORD
@@ -171,7 +597,7 @@ impl ApiKeyManager {
             let key = derive_key(legacy_pwd.as_slice(), &encrypted.salt)?;
             let plaintext = decrypt_with_key(&key[..], encrypted)
                 .map_err(|e| ApiKeyError::DecryptionFailed(e.to_string()))?;
-            return Ok(Zeroizing::new(plaintext));
+            return Ok((Zeroizing::new(plaintext), true));
         }
 
         Err(ApiKeyError::DecryptionFailed(
@@ -180,13 +606,45 @@ impl ApiKeyManager {
     }
 }
 
-fn validate_encrypted(encrypted: &EncryptedApiKey) -> Result<(), ApiKeyError> {
-    if encrypted.nonce.len() != NONCE_LENGTH {
+async fn find_data_key(
+    pool: &SqlitePool,
+    user_id: &str,
+) -> Result<Option<UserDataKeyRecord>, ApiKeyError> {
+    UserDataKeyRepo::find_by_user(pool, user_id)
+        .await
+        .map_err(|e| ApiKeyError::Storage(e.to_string()))
+}
+
+/// 用登录密码派生的密钥解开数据密钥
+fn unwrap_password_wrapped_data_key(
+    record: &UserDataKeyRecord,
+    user_password: &[u8],
+) -> Result<DataKey, ApiKeyError> {
+    let wrapped = EncryptedApiKey {
+        ciphertext: record.password_wrapped_key.clone(),
+        nonce: record.password_nonce.clone(),
+        salt: record.password_salt.clone(),
+    };
+    validate_encrypted(&wrapped)?;
+    let key = derive_key(user_password, &wrapped.salt)?;
+    decrypt_with_key(&key[..], &wrapped)
+        .map_err(|e| ApiKeyError::DecryptionFailed(e.to_string()))
+        .map(Zeroizing::new)
+        .and_then(|bytes| DataKey::from_slice(&bytes))
+}
+
+fn validate_nonce(nonce: &[u8]) -> Result<(), ApiKeyError> {
+    if nonce.len() != NONCE_LENGTH {
         return Err(ApiKeyError::InvalidNonceLength {
             expected: NONCE_LENGTH,
-            actual: encrypted.nonce.len(),
+            actual: nonce.len(),
         });
     }
+    Ok(())
+}
+
+fn validate_encrypted(encrypted: &EncryptedApiKey) -> Result<(), ApiKeyError> {
+    validate_nonce(&encrypted.nonce)?;
 
     if encrypted.salt.len() != SALT_LENGTH {
         return Err(ApiKeyError::InvalidSaltLength {
@@ -198,6 +656,55 @@ fn validate_encrypted(encrypted: &EncryptedApiKey) -> Result<(), ApiKeyError> {
     Ok(())
 }
 
+/// 使用登录密码派生的密钥加密（随机 salt + nonce）
+fn encrypt_with_password(
+    password: &[u8],
+    plaintext: &[u8],
+) -> Result<EncryptedApiKey, ApiKeyError> {
+    let salt = generate_random_bytes(SALT_LENGTH);
+    let key = derive_key(password, &salt)?;
+    let (ciphertext, nonce) = encrypt_with_key(&key[..], plaintext)?;
+    Ok(EncryptedApiKey {
+        ciphertext,
+        nonce,
+        salt,
+    })
+}
+
+/// 使用数据密钥加密（信封加密，salt 为空）
+fn encrypt_with_data_key(
+    data_key: &DataKey,
+    plaintext: &[u8],
+) -> Result<EncryptedApiKey, ApiKeyError> {
+    let (ciphertext, nonce) = encrypt_with_key(data_key.as_bytes(), plaintext)?;
+    Ok(EncryptedApiKey {
+        ciphertext,
+        nonce,
+        salt: Vec::new(),
+    })
+}
+
+fn wrap_data_key_for_server(
+    kek: &ServerKek,
+    data_key: &DataKey,
+) -> Result<ServerWrappedDataKey, ApiKeyError> {
+    let (wrapped_key, nonce) = encrypt_with_key(&kek.key[..], data_key.as_bytes())?;
+    Ok(ServerWrappedDataKey {
+        wrapped_key,
+        nonce,
+        kek_id: kek.id().to_string(),
+    })
+}
+
+/// 由个人访问令牌派生包装密钥（域分隔，避免与令牌摘要相同）
+fn derive_token_key(token: &str) -> Zeroizing<[u8; KEY_LENGTH]> {
+    let digest = Sha256::new()
+        .chain_update(TOKEN_KEY_DOMAIN)
+        .chain_update(token.as_bytes())
+        .finalize();
+    Zeroizing::new(digest.into())
+}
+
 /// 使用 Argon2id 从指定密码派生密钥（Drop 时自动清零）
 fn derive_key(password: &[u8], salt: &[u8]) -> Result<Zeroizing<[u8; KEY_LENGTH]>, ApiKeyError> {
     let params = Params::new(65536, 3, 4, Some(KEY_LENGTH))
@@ -213,6 +720,16 @@ fn derive_key(password: &[u8], salt: &[u8]) -> Result<Zeroizing<[u8; KEY_LENGTH]
     Ok(key)
 }
 
+/// AES-256-GCM 加密，返回 (密文, 随机 nonce)
+fn encrypt_with_key(key: &[u8], plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>), ApiKeyError> {
+    let nonce_bytes = generate_random_bytes(NONCE_LENGTH);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), plaintext)
+        .map_err(|e| ApiKeyError::EncryptionFailed(e.to_string()))?;
+    Ok((ciphertext, nonce_bytes))
+}
+
 fn decrypt_with_key(key: &[u8], encrypted: &EncryptedApiKey) -> Result<Vec<u8>, aes_gcm::Error> {
     let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
     let nonce = Nonce::from_slice(&encrypted.nonce);
@@ -271,6 +788,200 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_envelope_encrypt_decrypt_roundtrip() {
+        let manager = ApiKeyManager::new(None);
+        let keys = CredentialKeys::from_data_key(DataKey::generate());
+        let api_key = "sk-1234567890abcdef";
+
+        let encrypted = manager.encrypt_credential(&keys, api_key).unwrap();
+        assert!(encrypted.is_envelope());
+        assert_eq!(encrypted.nonce.len(), NONCE_LENGTH);
+
+        let decrypted = manager.decrypt_credential(&keys, &encrypted).unwrap();
+        assert_eq!(decrypted.as_slice(), api_key.as_bytes());
+
+        // 仅有登录密码无法解密信封加密数据
+        let password_only = CredentialKeys::from_password(b"test_password");
+        assert!(matches!(
+            manager.decrypt_credential(&password_only, &encrypted),
+            Err(ApiKeyError::DataKeyUnavailable(_))
+        ));
+        let other_key = CredentialKeys::from_data_key(DataKey::generate());
+        assert!(manager.decrypt_credential(&other_key, &encrypted).is_err());
+    }
+
+    #[test]
+    fn test_credential_keys_without_data_key_use_password_format() {
+        let manager = ApiKeyManager::new(None);
+        let keys = CredentialKeys::from_password(b"test_password");
+
+        let encrypted = manager.encrypt_credential(&keys, "sk-password").unwrap();
+        assert!(!encrypted.is_envelope());
+
+        let with_data_key = keys.with_data_key(DataKey::generate());
+        let decrypted = manager
+            .decrypt_credential(&with_data_key, &encrypted)
+            .unwrap();
+        assert_eq!(decrypted.as_slice(), b"sk-password");
+    }
+
+    #[test]
+    fn test_migrate_to_data_key_moves_password_data() {
+        let manager = ApiKeyManager::new(None);
+        let data_key = DataKey::generate();
+        let encrypted = manager.encrypt(b"password", "sk-1234567890abcdef").unwrap();
+
+        let migrated = manager
+            .migrate_to_data_key(b"password", &data_key, &encrypted)
+            .unwrap();
+
+        assert!(!migrated.from_legacy);
+        assert!(migrated.encrypted.is_envelope());
+        let keys = CredentialKeys::from_data_key(data_key);
+        assert_eq!(
+            manager
+                .decrypt_credential(&keys, &migrated.encrypted)
+                .unwrap()
+                .as_slice(),
+            b"sk-1234567890abcdef"
+        );
+    }
+
+    #[test]
+    fn test_migrate_to_data_key_migrates_legacy_master_password_data() {
+        let legacy_writer = ApiKeyManager::new(None);
+        let encrypted = legacy_writer
+            .encrypt(b"legacy_master", "sk-legacy")
+            .unwrap();
+
+        let manager = ApiKeyManager::new(Some("legacy_master".to_string()));
+        let data_key = DataKey::generate();
+        let migrated = manager
+            .migrate_to_data_key(b"password", &data_key, &encrypted)
+            .unwrap();
+
+        assert!(migrated.from_legacy);
+        let without_legacy = ApiKeyManager::new(None);
+        assert_eq!(
+            without_legacy
+                .decrypt_credential(
+                    &CredentialKeys::from_data_key(data_key),
+                    &migrated.encrypted
+                )
+                .unwrap()
+                .as_slice(),
+            b"sk-legacy"
+        );
+    }
+
+    #[test]
+    fn test_server_kek_from_hex() {
+        let hex = "11".repeat(KEY_LENGTH);
+        let kek = ServerKek::from_hex(&format!("  {hex}\n")).unwrap();
+        assert_eq!(kek.id().len(), 16);
+        assert_eq!(kek.id(), ServerKek::from_hex(&hex).unwrap().id());
+        assert_ne!(
+            kek.id(),
+            ServerKek::from_hex(&"22".repeat(KEY_LENGTH)).unwrap().id()
+        );
+
+        assert!(matches!(
+            ServerKek::from_hex("abcd"),
+            Err(ApiKeyError::InvalidServerKek(_))
+        ));
+        assert!(matches!(
+            ServerKek::from_hex(&"zz".repeat(KEY_LENGTH)),
+            Err(ApiKeyError::InvalidServerKek(_))
+        ));
+    }
+
+    async fn setup_db() -> SqlitePool {
+        let pool = crate::infra::db::pool::create_pool("sqlite::memory:")
+            .await
+            .expect("创建测试数据库失败");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("运行 migrations 失败");
+        sqlx::query(
+            "INSERT INTO users (id, username, password_hash, created_at, updated_at) VALUES ('u1', 'u1', 'h', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .expect("插入用户失败");
+        pool
+    }
+
+    fn kek(byte: &str) -> ServerKek {
+        ServerKek::from_hex(&byte.repeat(KEY_LENGTH)).unwrap()
+    }
+
+    #[test]
+    fn test_data_key_token_wrap_roundtrip() {
+        let data_key = DataKey::generate();
+        let wrapped = data_key.wrap_for_token("pf_pat_token").unwrap();
+
+        let unwrapped = DataKey::unwrap_for_token("pf_pat_token", &wrapped).unwrap();
+        assert_eq!(unwrapped.as_bytes(), data_key.as_bytes());
+        assert!(DataKey::unwrap_for_token("pf_pat_other", &wrapped).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unlock_data_key_creates_once_and_reuses() {
+        let pool = setup_db().await;
+        let manager = ApiKeyManager::new(None);
+
+        let first = manager
+            .unlock_data_key(&pool, "u1", b"password")
+            .await
+            .unwrap();
+        let second = manager
+            .unlock_data_key(&pool, "u1", b"password")
+            .await
+            .unwrap();
+        assert_eq!(first.as_bytes(), second.as_bytes());
+
+        assert!(
+            manager
+                .unlock_data_key(&pool, "u1", b"wrong")
+                .await
+                .is_err()
+        );
+        // 未配置 KEK：无法无会话解锁
+        assert!(matches!(
+            manager.unlock_data_key_unattended(&pool, "u1").await,
+            Err(ApiKeyError::DataKeyUnavailable(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_server_kek_unlocks_unattended_and_rewraps_on_rotation() {
+        let pool = setup_db().await;
+        let old = ApiKeyManager::new(None).with_server_kek(Some(kek("11")));
+
+        let data_key = old.unlock_data_key(&pool, "u1", b"password").await.unwrap();
+        let unattended = old.unlock_data_key_unattended(&pool, "u1").await.unwrap();
+        assert_eq!(unattended.as_bytes(), data_key.as_bytes());
+
+        // KEK 轮换：登录前无法无会话解锁，登录后重新包装
+        let rotated = ApiKeyManager::new(None).with_server_kek(Some(kek("22")));
+        assert!(matches!(
+            rotated.unlock_data_key_unattended(&pool, "u1").await,
+            Err(ApiKeyError::DataKeyUnavailable(_))
+        ));
+        rotated
+            .unlock_data_key(&pool, "u1", b"password")
+            .await
+            .unwrap();
+        let unattended = rotated
+            .unlock_data_key_unattended(&pool, "u1")
+            .await
+            .unwrap();
+        assert_eq!(unattended.as_bytes(), data_key.as_bytes());
+        assert!(old.unlock_data_key_unattended(&pool, "u1").await.is_err());
+    }
+
     #[test]
     fn test_nonce_length() {
         let manager = ApiKeyManager::new(None);
@@ -5,9 +5,11 @@ use std::time::Duration;
 
 use reqwest::Client;
 use tokio::sync::Mutex;
+use tracing::warn;
 
 use crate::domain::models::{ConnectivityResponse, ConnectivityStatus};
 use crate::domain::types::unix_ms_to_iso8601;
+use crate::infra::external::http_client::{OutboundHttpConfig, apply_outbound_config};
 use crate::shared::time::now_millis;
 
 const CACHE_TTL: Duration = Duration::from_secs(30);
@@ -38,8 +40,28 @@ static CONNECTIVITY_CACHE: OnceLock<Mutex<ConnectivityCache>> = OnceLock::new();
 static CONNECTIVITY_PROBE_URL: OnceLock<String> = OnceLock::new();
 static CONNECTIVITY_CLIENT: OnceLock<Client> = OnceLock::new();
 
-pub fn init_connectivity_probe(url: impl Into<String>) {
-    let _ = CONNECTIVITY_PROBE_URL.set(url.into());
+/// 初始化主动探测地址与探测客户端（沿用出站 CA/mTLS 配置；探测地址本身始终绕过代理）
+pub fn init_connectivity_probe(url: impl Into<String>, outbound: &OutboundHttpConfig) {
+    let url = url.into();
+    let mut outbound = outbound.clone();
+    if let Some(host) = url::Url::parse(&url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+    {
+        outbound.no_proxy = Some(match outbound.no_proxy.take() {
+            Some(list) => format!("{list},{host}"),
+            None => host,
+        });
+    }
+    match apply_outbound_config(Client::builder().timeout(PROBE_TIMEOUT), &outbound)
+        .and_then(|builder| Ok(builder.build()?))
+    {
+        Ok(probe_client) => {
+            let _ = CONNECTIVITY_CLIENT.set(probe_client);
+        }
+        Err(e) => warn!(error = %e, "创建 connectivity 探测客户端失败，使用默认配置"),
+    }
+    let _ = CONNECTIVITY_PROBE_URL.set(url);
 }
 
 fn client() -> Client {
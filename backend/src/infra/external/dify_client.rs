@@ -10,6 +10,8 @@ use ts_rs::TS;
 use url::Url;
 use utoipa::ToSchema;
 
+use crate::infra::external::upstream_guard::{UpstreamOutcome, upstream_guard};
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, TS)]
 #[ts(export_to = "api/")]
 #[serde(rename_all = "lowercase")]
@@ -37,10 +39,21 @@ pub struct DifyInputVariable {
     pub raw: Option<serde_json::Value>,
 }
 
+/// Dify 工作流声明的输出变量（用于前端选择输出选择器）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, TS)]
+#[ts(export_to = "api/")]
+pub struct DifyOutputVariable {
+    pub name: String,
+    pub r#type: DifyValueType,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, TS)]
 #[ts(export_to = "api/")]
 pub struct DifyVariablesResponse {
     pub variables: Vec<DifyInputVariable>,
+    /// 工作流声明的输出变量；上游未声明时为空（前端退化为手动填写输出选择器）
+    #[serde(default)]
+    pub outputs: Vec<DifyOutputVariable>,
 }
 
 /// 连接测试结果
@@ -84,6 +97,9 @@ pub enum ConnectionError {
 struct DifyParametersRaw {
     #[serde(default)]
     user_input_form: Vec<serde_json::Map<String, serde_json::Value>>,
+    /// 工作流 End 节点声明的输出（部分 Dify 版本提供）：`["text"]` 或 `[{variable|name, value_type|type}]`
+    #[serde(default)]
+    outputs: Vec<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,6 +157,47 @@ fn infer_value_type(component: &str, default_value: Option<&serde_json::Value>)
     }
 }
 
+fn parse_output_value_type(raw: Option<&str>) -> DifyValueType {
+    match raw.map(|s| s.trim().to_ascii_lowercase()).as_deref() {
+        Some("string" | "text" | "paragraph") => DifyValueType::String,
+        Some("number" | "integer") => DifyValueType::Number,
+        Some("boolean" | "bool") => DifyValueType::Bool,
+        Some("object") => DifyValueType::Object,
+        Some(s) if s.starts_with("array") => DifyValueType::Array,
+        _ => DifyValueType::Unknown,
+    }
+}
+
+/// 解析声明的输出变量（容错：无法识别的条目直接跳过，重复名称只保留第一个）。
+fn parse_declared_outputs(raw: &[serde_json::Value]) -> Vec<DifyOutputVariable> {
+    let mut out: Vec<DifyOutputVariable> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for item in raw {
+        let (name, value_type) = match item {
+            serde_json::Value::String(name) => (Some(name.as_str()), None),
+            serde_json::Value::Object(obj) => (
+                obj.get("variable")
+                    .or_else(|| obj.get("name"))
+                    .and_then(|v| v.as_str()),
+                obj.get("value_type")
+                    .or_else(|| obj.get("type"))
+                    .and_then(|v| v.as_str()),
+            ),
+            _ => (None, None),
+        };
+        let Some(name) = name.map(str::trim).filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        if seen.insert(name.to_string()) {
+            out.push(DifyOutputVariable {
+                name: name.to_string(),
+                r#type: parse_output_value_type(value_type),
+            });
+        }
+    }
+    out
+}
+
 fn parse_parameters_variables(
     raw: DifyParametersRaw,
 ) -> Result<Vec<DifyInputVariable>, ConnectionError> {
@@ -190,6 +247,46 @@ fn parse_parameters_variables(
     Ok(out)
 }
 
+/// 经凭证限流 / 熔断保护后请求 `/v1/parameters`
+async fn get_parameters_response(
+    client: &Client,
+    base_url: &str,
+    url: &str,
+    api_key: &str,
+    correlation_id: &str,
+) -> Result<reqwest::Response, ConnectionError> {
+    let guard = upstream_guard(base_url, api_key);
+    guard
+        .acquire(0)
+        .await
+        .map_err(|e| ConnectionError::UpstreamError(e.to_string()))?;
+
+    let result = client
+        .get(url)
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header("X-Correlation-Id", correlation_id)
+        .send()
+        .await;
+    match result {
+        Ok(response) => {
+            guard
+                .record(UpstreamOutcome::from_response(&response))
+                .await;
+            Ok(response)
+        }
+        Err(e) => {
+            guard
+                .record(UpstreamOutcome::Failure { retry_after: None })
+                .await;
+            if e.is_timeout() {
+                Err(ConnectionError::Timeout)
+            } else {
+                Err(ConnectionError::RequestFailed(e))
+            }
+        }
+    }
+}
+
 /// 获取 Dify 应用的输入变量结构
 ///
 /// GET /v1/parameters
@@ -202,19 +299,8 @@ pub async fn get_parameters(
     let base_url = normalize_base_url_for_parameters(base_url);
     let url = format!("{}/v1/parameters", base_url);
 
-    let response = client
-        .get(&url)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("X-Correlation-Id", correlation_id)
-        .send()
-        .await
-        .map_err(|e| {
-            if e.is_timeout() {
-                ConnectionError::Timeout
-            } else {
-                ConnectionError::RequestFailed(e)
-            }
-        })?;
+    let response =
+        get_parameters_response(client, &base_url, &url, api_key, correlation_id).await?;
 
     match response.status().as_u16() {
         200..=299 => {
@@ -222,8 +308,9 @@ pub async fn get_parameters(
                 ConnectionError::ParseError(format!("解析 /parameters 响应失败: {}", e))
             })?;
 
+            let outputs = parse_declared_outputs(&json.outputs);
             let variables = parse_parameters_variables(json)?;
-            Ok(DifyVariablesResponse { variables })
+            Ok(DifyVariablesResponse { variables, outputs })
         }
         401 => Err(ConnectionError::InvalidCredentials),
         403 => Err(ConnectionError::Forbidden),
@@ -253,19 +340,8 @@ pub async fn test_connection(
     let base_url = normalize_base_url_for_parameters(base_url);
     let url = format!("{}/v1/parameters", base_url);
 
-    let response = client
-        .get(&url)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("X-Correlation-Id", correlation_id)
-        .send()
-        .await
-        .map_err(|e| {
-            if e.is_timeout() {
-                ConnectionError::Timeout
-            } else {
-                ConnectionError::RequestFailed(e)
-            }
-        })?;
+    let response =
+        get_parameters_response(client, &base_url, &url, api_key, correlation_id).await?;
 
     match response.status().as_u16() {
         200..=299 => Ok(TestConnectionResult {
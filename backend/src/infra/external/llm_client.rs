@@ -1,28 +1,45 @@
 //! LLM API 调用客户端
 //! 唯一的外部 LLM 调用点
-//! 支持 OpenAI 兼容 API（硅基流动、魔搭社区等）
+//! 按 Provider 注册表（`LlmProviderSpec`）决定端点路径、鉴权头与协议格式：
+//! 支持 OpenAI 兼容 API（硅基流动、魔搭社区、OpenAI、DeepSeek、Azure、自建服务）与 Anthropic Messages。
 
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::domain::models::ConnectivityStatus;
+use crate::domain::models::{
+    ConnectivityStatus, LlmApiFormat, LlmAuthStyle, LlmProviderRegistry, LlmProviderSpec,
+};
 use crate::infra::external::connectivity::{
     record_connectivity_failure, record_connectivity_success,
 };
 use crate::infra::external::retry::{RetryPolicy, with_retry};
+use crate::infra::external::upstream_guard::{
+    UpstreamGuard, UpstreamOutcome, estimate_tokens, upstream_guard,
+};
 
 use super::dify_client::TestConnectionResult;
-/// Provider 允许列表（白名单验证）
-pub const ALLOWED_PROVIDERS: &[&str] = &["siliconflow", "modelscope"];
-
-/// Provider 默认 Base URL
-pub fn get_default_base_url(provider: &str) -> Option<&'static str> {
-    match provider {
-        "siliconflow" => Some("https://api.siliconflow.cn"),
-        "modelscope" => Some("https://dashscope.aliyuncs.com/compatible-mode"),
-        _ => None,
-    }
+/// 内置 Provider 允许列表（白名单验证；用户自定义 Provider 另行按用户解析）
+pub const ALLOWED_PROVIDERS: &[&str] = &[
+    "siliconflow",
+    "modelscope",
+    "openai",
+    "deepseek",
+    "azure_openai",
+    "anthropic",
+    "openai_compatible",
+];
+
+/// Anthropic Messages API 版本头
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// Anthropic Messages 必填的 max_tokens（请求未指定时使用）
+const ANTHROPIC_DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// 内置 Provider 默认 Base URL
+pub fn get_default_base_url(provider: &str) -> Option<String> {
+    LlmProviderRegistry::builtin()
+        .get(provider)
+        .and_then(|spec| spec.default_base_url.clone())
 }
 
 /// OpenAI 兼容 API 模型列表响应
@@ -71,6 +88,30 @@ struct ChatChoiceMessage {
     content: Option<String>,
 }
 
+/// Anthropic Messages - 请求
+#[derive(Debug, Serialize)]
+struct AnthropicMessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<&'a ChatMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicMessagesResponse {
+    #[serde(default)]
+    content: Vec<AnthropicContentBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicContentBlock {
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
 /// LLM 连接错误类型
 #[derive(Debug, Error)]
 pub enum LlmConnectionError {
@@ -99,26 +140,47 @@ pub enum LlmConnectionError {
     ClientError(String),
 }
 
-/// 验证 provider 是否在允许列表中
+/// 验证 provider 是否为内置 Provider
 pub fn validate_provider(provider: &str) -> Result<(), LlmConnectionError> {
-    if !ALLOWED_PROVIDERS.contains(&provider) {
-        return Err(LlmConnectionError::ValidationError(format!(
-            "不支持的 Provider: {}，支持的 Provider: {:?}",
-            provider, ALLOWED_PROVIDERS
-        )));
+    resolve_builtin_provider(provider).map(|_| ())
+}
+
+/// 按 ID 解析内置 Provider
+pub fn resolve_builtin_provider(provider: &str) -> Result<LlmProviderSpec, LlmConnectionError> {
+    LlmProviderRegistry::builtin()
+        .get(provider)
+        .cloned()
+        .ok_or_else(|| {
+            LlmConnectionError::ValidationError(format!(
+                "不支持的 Provider: {}，支持的 Provider: {:?}",
+                provider, ALLOWED_PROVIDERS
+            ))
+        })
+}
+
+/// 按 Provider 鉴权风格附加鉴权头
+fn apply_auth(builder: RequestBuilder, spec: &LlmProviderSpec, api_key: &str) -> RequestBuilder {
+    let builder = match spec.auth_style {
+        LlmAuthStyle::Bearer => builder.header("Authorization", format!("Bearer {}", api_key)),
+        LlmAuthStyle::ApiKeyHeader => builder.header("api-key", api_key),
+        LlmAuthStyle::XApiKey => builder.header("x-api-key", api_key),
+        LlmAuthStyle::None => builder,
+    };
+    match spec.api_format {
+        LlmApiFormat::AnthropicMessages => builder.header("anthropic-version", ANTHROPIC_VERSION),
+        LlmApiFormat::OpenaiChat => builder,
     }
-    Ok(())
 }
 
 /// 测试通用大模型 API 连接
 ///
-/// 调用 OpenAI 兼容的 /v1/models 端点获取模型列表验证凭证
+/// 调用内置 Provider 的模型列表端点验证凭证
 ///
 /// # Arguments
 /// * `client` - 共享的 HTTP 客户端（复用连接池）
 /// * `base_url` - API 基础 URL（已通过 SSRF 验证）
 /// * `api_key` - API Key（已通过非空验证）
-/// * `provider` - Provider 标识 ("siliconflow" | "modelscope")
+/// * `provider` - 内置 Provider 标识（见 [`ALLOWED_PROVIDERS`]）
 /// * `correlation_id` - 请求追踪 ID（透传到上游）
 ///
 /// # Returns
@@ -131,7 +193,20 @@ pub async fn test_connection(
     provider: &str,
     correlation_id: &str,
 ) -> Result<TestConnectionResult, LlmConnectionError> {
-    let model_ids = list_models(client, base_url, api_key, provider, correlation_id).await?;
+    let spec = resolve_builtin_provider(provider)?;
+    test_connection_with_provider(client, base_url, api_key, &spec, correlation_id).await
+}
+
+/// 按 Provider 描述测试连接（支持用户自定义 Provider）
+pub async fn test_connection_with_provider(
+    client: &Client,
+    base_url: &str,
+    api_key: &str,
+    spec: &LlmProviderSpec,
+    correlation_id: &str,
+) -> Result<TestConnectionResult, LlmConnectionError> {
+    let model_ids =
+        list_models_with_provider(client, base_url, api_key, spec, correlation_id).await?;
     let model_count = model_ids.len();
 
     Ok(TestConnectionResult {
@@ -140,9 +215,7 @@ pub async fn test_connection(
     })
 }
 
-/// 获取通用大模型可用模型列表
-///
-/// 调用 OpenAI 兼容的 `/v1/models` 端点。
+/// 获取通用大模型可用模型列表（内置 Provider）
 pub async fn list_models(
     client: &Client,
     base_url: &str,
@@ -150,10 +223,22 @@ pub async fn list_models(
     provider: &str,
     correlation_id: &str,
 ) -> Result<Vec<String>, LlmConnectionError> {
-    // 验证 provider
-    validate_provider(provider)?;
+    let spec = resolve_builtin_provider(provider)?;
+    list_models_with_provider(client, base_url, api_key, &spec, correlation_id).await
+}
 
-    let url = format!("{}/v1/models", base_url.trim_end_matches('/'));
+/// 按 Provider 描述获取模型列表
+///
+/// OpenAI / Azure / Anthropic 的模型列表响应均为 `{"data": [{"id": ...}]}`。
+pub async fn list_models_with_provider(
+    client: &Client,
+    base_url: &str,
+    api_key: &str,
+    spec: &LlmProviderSpec,
+    correlation_id: &str,
+) -> Result<Vec<String>, LlmConnectionError> {
+    let url = spec.models_url(base_url);
+    let guard = upstream_guard(base_url, api_key);
 
     let policy = RetryPolicy::default();
     with_retry(
@@ -161,31 +246,9 @@ pub async fn list_models(
         correlation_id,
         "llm:list_models",
         || async {
-            let response = match client
-                .get(&url)
-                .header("Authorization", format!("Bearer {}", api_key))
-                .header("X-Correlation-Id", correlation_id)
-                .send()
-                .await
-            {
-                Ok(resp) => resp,
-                Err(e) => {
-                    if e.is_timeout() {
-                        record_connectivity_failure(
-                            ConnectivityStatus::Offline,
-                            "上游请求超时".to_string(),
-                        )
-                        .await;
-                        return Err(LlmConnectionError::Timeout);
-                    }
-                    record_connectivity_failure(
-                        ConnectivityStatus::Offline,
-                        format!("上游网络错误: {}", e),
-                    )
-                    .await;
-                    return Err(LlmConnectionError::RequestFailed(e));
-                }
-            };
+            let request = apply_auth(client.get(&url), spec, api_key)
+                .header("X-Correlation-Id", correlation_id);
+            let response = guarded_send(&guard, request, 0).await?;
 
             match response.status().as_u16() {
                 200..=299 => {
@@ -195,20 +258,7 @@ pub async fn list_models(
                     record_connectivity_success().await;
                     Ok(models.data.into_iter().map(|m| m.id).collect())
                 }
-                401 => Err(LlmConnectionError::InvalidCredentials),
-                403 => Err(LlmConnectionError::Forbidden),
-                status => {
-                    record_connectivity_failure(
-                        ConnectivityStatus::Limited,
-                        format!("上游返回 HTTP {}", status),
-                    )
-                    .await;
-                    // 不回显上游 body（可能包含敏感信息，如 prompt/testcase/token）。
-                    Err(LlmConnectionError::UpstreamError(format!(
-                        "HTTP {}",
-                        status
-                    )))
-                }
+                status => Err(map_error_status(status).await),
             }
         },
         is_retryable_llm_error,
@@ -227,88 +277,54 @@ pub async fn chat_completions(
     correlation_id: &str,
     req: &ChatCompletionsRequest,
 ) -> Result<String, LlmConnectionError> {
-    let url = format!("{}/v1/chat/completions", base_url.trim_end_matches('/'));
+    let spec = LlmProviderRegistry::openai_compatible();
+    chat_completions_with_provider(client, base_url, api_key, &spec, correlation_id, req).await
+}
+
+/// 按 Provider 描述调用对话端点获取输出文本（阻塞模式）。
+///
+/// Anthropic Messages 格式下，`system` 消息合并为顶层 `system` 字段。
+pub async fn chat_completions_with_provider(
+    client: &Client,
+    base_url: &str,
+    api_key: &str,
+    spec: &LlmProviderSpec,
+    correlation_id: &str,
+    req: &ChatCompletionsRequest,
+) -> Result<String, LlmConnectionError> {
+    let url = spec.chat_url(base_url, &req.model);
+    let body = match spec.api_format {
+        LlmApiFormat::OpenaiChat => serde_json::to_value(req),
+        LlmApiFormat::AnthropicMessages => serde_json::to_value(build_anthropic_request(req)),
+    }
+    .map_err(|e| LlmConnectionError::ClientError(format!("序列化请求失败: {}", e)))?;
+    let guard = upstream_guard(base_url, api_key);
+    let estimated_tokens = estimate_tokens(req.messages.iter().map(|m| m.content.len()).sum());
+
     let policy = RetryPolicy::default();
     with_retry(
         &policy,
         correlation_id,
         "llm:chat_completions",
         || async {
-            let response = match client
-                .post(&url)
-                .header("Authorization", format!("Bearer {}", api_key))
+            let request = apply_auth(client.post(&url), spec, api_key)
                 .header("X-Correlation-Id", correlation_id)
-                .json(req)
-                .send()
-                .await
-            {
-                Ok(resp) => resp,
-                Err(e) => {
-                    if e.is_timeout() {
-                        record_connectivity_failure(
-                            ConnectivityStatus::Offline,
-                            "上游请求超时".to_string(),
-                        )
-                        .await;
-                        return Err(LlmConnectionError::Timeout);
-                    }
-                    record_connectivity_failure(
-                        ConnectivityStatus::Offline,
-                        format!("上游网络错误: {}", e),
-                    )
-                    .await;
-                    return Err(LlmConnectionError::RequestFailed(e));
-                }
-            };
+                .json(&body);
+            let response = guarded_send(&guard, request, estimated_tokens).await?;
 
             match response.status().as_u16() {
                 200..=299 => {
-                    let json = response
-                        .json::<ChatCompletionsResponse>()
-                        .await
-                        .map_err(|e| {
-                            LlmConnectionError::ParseError(format!(
-                                "解析 chat/completions 响应失败: {}",
-                                e
-                            ))
-                        })?;
-                    let choice = json.choices.into_iter().next().ok_or_else(|| {
-                        LlmConnectionError::ParseError(
-                            "chat/completions 缺少 choices[0]".to_string(),
-                        )
-                    })?;
-
-                    if let Some(s) = choice
-                        .message
-                        .and_then(|m| m.content)
-                        .filter(|s| !s.trim().is_empty())
-                    {
-                        record_connectivity_success().await;
-                        return Ok(s);
-                    }
-                    if let Some(s) = choice.text.filter(|s| !s.trim().is_empty()) {
-                        record_connectivity_success().await;
-                        return Ok(s);
-                    }
-
-                    Err(LlmConnectionError::ParseError(
-                        "chat/completions 缺少 message.content/text".to_string(),
-                    ))
-                }
-                401 => Err(LlmConnectionError::InvalidCredentials),
-                403 => Err(LlmConnectionError::Forbidden),
-                status => {
-                    record_connectivity_failure(
-                        ConnectivityStatus::Limited,
-                        format!("上游返回 HTTP {}", status),
-                    )
-                    .await;
-                    // 不读取/拼接 body，避免上游回显敏感内容。
-                    Err(LlmConnectionError::UpstreamError(format!(
-                        "HTTP {}",
-                        status
-                    )))
+                    let output = match spec.api_format {
+                        LlmApiFormat::OpenaiChat => parse_openai_chat_output(response).await?,
+                        LlmApiFormat::AnthropicMessages => {
+                            parse_anthropic_messages_output(response).await?
+                        }
+                    };
+                    record_connectivity_success().await;
+                    Ok(output)
                 }
+                // 不读取/拼接 body，避免上游回显敏感内容。
+                status => Err(map_error_status(status).await),
             }
         },
         is_retryable_llm_error,
@@ -317,6 +333,121 @@ pub async fn chat_completions(
     .await
 }
 
+fn build_anthropic_request(req: &ChatCompletionsRequest) -> AnthropicMessagesRequest<'_> {
+    let system_parts: Vec<&str> = req
+        .messages
+        .iter()
+        .filter(|m| m.role == "system")
+        .map(|m| m.content.as_str())
+        .collect();
+    AnthropicMessagesRequest {
+        model: &req.model,
+        max_tokens: ANTHROPIC_DEFAULT_MAX_TOKENS,
+        system: (!system_parts.is_empty()).then(|| system_parts.join("\n\n")),
+        messages: req.messages.iter().filter(|m| m.role != "system").collect(),
+    }
+}
+
+async fn parse_openai_chat_output(
+    response: reqwest::Response,
+) -> Result<String, LlmConnectionError> {
+    let json = response
+        .json::<ChatCompletionsResponse>()
+        .await
+        .map_err(|e| {
+            LlmConnectionError::ParseError(format!("解析 chat/completions 响应失败: {}", e))
+        })?;
+    let choice = json.choices.into_iter().next().ok_or_else(|| {
+        LlmConnectionError::ParseError("chat/completions 缺少 choices[0]".to_string())
+    })?;
+
+    if let Some(s) = choice
+        .message
+        .and_then(|m| m.content)
+        .filter(|s| !s.trim().is_empty())
+    {
+        return Ok(s);
+    }
+    if let Some(s) = choice.text.filter(|s| !s.trim().is_empty()) {
+        return Ok(s);
+    }
+
+    Err(LlmConnectionError::ParseError(
+        "chat/completions 缺少 message.content/text".to_string(),
+    ))
+}
+
+async fn parse_anthropic_messages_output(
+    response: reqwest::Response,
+) -> Result<String, LlmConnectionError> {
+    let json = response
+        .json::<AnthropicMessagesResponse>()
+        .await
+        .map_err(|e| LlmConnectionError::ParseError(format!("解析 messages 响应失败: {}", e)))?;
+    let text: String = json
+        .content
+        .into_iter()
+        .filter(|block| block.kind == "text")
+        .filter_map(|block| block.text)
+        .collect();
+    if text.trim().is_empty() {
+        return Err(LlmConnectionError::ParseError(
+            "messages 响应缺少 text 内容".to_string(),
+        ));
+    }
+    Ok(text)
+}
+
+/// 经凭证限流 / 熔断保护后发送请求，并回报调用结果
+async fn guarded_send(
+    guard: &UpstreamGuard,
+    request: RequestBuilder,
+    estimated_tokens: u64,
+) -> Result<reqwest::Response, LlmConnectionError> {
+    guard
+        .acquire(estimated_tokens)
+        .await
+        .map_err(|e| LlmConnectionError::UpstreamError(e.to_string()))?;
+    match request.send().await {
+        Ok(resp) => {
+            guard.record(UpstreamOutcome::from_response(&resp)).await;
+            Ok(resp)
+        }
+        Err(e) => {
+            guard
+                .record(UpstreamOutcome::Failure { retry_after: None })
+                .await;
+            Err(record_send_error(e).await)
+        }
+    }
+}
+
+/// 记录发送失败并映射错误
+async fn record_send_error(e: reqwest::Error) -> LlmConnectionError {
+    if e.is_timeout() {
+        record_connectivity_failure(ConnectivityStatus::Offline, "上游请求超时".to_string()).await;
+        return LlmConnectionError::Timeout;
+    }
+    record_connectivity_failure(ConnectivityStatus::Offline, format!("上游网络错误: {}", e)).await;
+    LlmConnectionError::RequestFailed(e)
+}
+
+/// 映射非 2xx 状态码（不回显上游 body：可能包含敏感信息，如 prompt/testcase/token）
+async fn map_error_status(status: u16) -> LlmConnectionError {
+    match status {
+        401 => LlmConnectionError::InvalidCredentials,
+        403 => LlmConnectionError::Forbidden,
+        status => {
+            record_connectivity_failure(
+                ConnectivityStatus::Limited,
+                format!("上游返回 HTTP {}", status),
+            )
+            .await;
+            LlmConnectionError::UpstreamError(format!("HTTP {}", status))
+        }
+    }
+}
+
 fn is_retryable_llm_error(err: &LlmConnectionError) -> bool {
     matches!(
         err,
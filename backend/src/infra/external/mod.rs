@@ -7,3 +7,4 @@ pub mod http_client;
 pub mod llm_client;
 pub mod observability;
 pub mod retry;
+pub mod upstream_guard;
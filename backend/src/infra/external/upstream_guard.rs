@@ -0,0 +1,556 @@
+//! 上游调用保护：按凭证共享的限流器与熔断器
+//!
+//! 同一凭证（base_url + API Key 指纹）的所有调用共享一个 [`UpstreamGuard`]：
+//! - 限流：滑动 60 秒窗口内的请求数（RPM）与 token 数（TPM）上限，超出时排队等待；
+//!   token 数按请求体字符数估算（上游用量在响应前未知）。
+//! - Retry-After：上游返回 429（或带 `Retry-After` 的 5xx）时记录冷却截止时间，
+//!   冷却期内该凭证的后续请求统一等待，避免并发执行时形成 429 风暴。
+//! - 熔断：连续失败（网络错误 / 超时 / 5xx）达到阈值后打开，打开期间快速失败；
+//!   冷却结束后进入半开状态放行一个探测请求，成功则关闭，失败则重新打开。
+//!   熔断打开时同步写入网络连通性状态（见 [`record_connectivity_failure`]）。
+//!
+//! 保护器按凭证缓存在进程内，空闲条目由 [`cleanup_idle_upstream_guards`] 定期清理。
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tokio::time::{Instant, sleep};
+
+use crate::domain::models::ConnectivityStatus;
+use crate::infra::external::connectivity::record_connectivity_failure;
+
+/// 限流窗口
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+/// 429 未携带 Retry-After 时的默认冷却时间
+const DEFAULT_RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(2);
+/// Retry-After 上限（避免异常响应头导致长时间阻塞）
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+/// 默认熔断阈值（连续失败次数）
+pub const CIRCUIT_FAILURE_THRESHOLD_DEFAULT: u32 = 5;
+/// 默认熔断打开时长（秒）
+pub const CIRCUIT_OPEN_SECS_DEFAULT: u64 = 30;
+
+/// 上游保护配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpstreamGuardConfig {
+    /// 每分钟请求数上限（None = 不限制）
+    pub rpm_limit: Option<u32>,
+    /// 每分钟 token 数上限（None = 不限制）
+    pub tpm_limit: Option<u32>,
+    /// 连续失败多少次后熔断（最小 1）
+    pub failure_threshold: u32,
+    /// 熔断打开时长
+    pub open_duration: Duration,
+}
+
+impl Default for UpstreamGuardConfig {
+    fn default() -> Self {
+        Self {
+            rpm_limit: None,
+            tpm_limit: None,
+            failure_threshold: CIRCUIT_FAILURE_THRESHOLD_DEFAULT,
+            open_duration: Duration::from_secs(CIRCUIT_OPEN_SECS_DEFAULT),
+        }
+    }
+}
+
+static UPSTREAM_GUARD_DEFAULTS: OnceLock<UpstreamGuardConfig> = OnceLock::new();
+static UPSTREAM_GUARDS: OnceLock<std::sync::Mutex<HashMap<String, Arc<UpstreamGuard>>>> =
+    OnceLock::new();
+
+/// 初始化上游保护默认配置（启动时调用一次；未调用时使用 [`UpstreamGuardConfig::default`]）
+pub fn init_upstream_guard_defaults(config: UpstreamGuardConfig) {
+    let _ = UPSTREAM_GUARD_DEFAULTS.set(UpstreamGuardConfig {
+        failure_threshold: config.failure_threshold.max(1),
+        ..config
+    });
+}
+
+/// 获取上游保护默认配置
+pub fn upstream_guard_defaults() -> UpstreamGuardConfig {
+    UPSTREAM_GUARD_DEFAULTS.get().copied().unwrap_or_default()
+}
+
+/// 凭证键：规范化 base_url + API Key 的 SHA-256 指纹前缀（不保存明文 Key）
+pub fn credential_key(base_url: &str, api_key: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(api_key.as_bytes()));
+    format!(
+        "{}#{}",
+        base_url.trim().trim_end_matches('/'),
+        &digest[..16]
+    )
+}
+
+/// 获取凭证对应的共享保护器
+pub fn upstream_guard(base_url: &str, api_key: &str) -> Arc<UpstreamGuard> {
+    upstream_guard_for_key(&credential_key(base_url, api_key))
+}
+
+/// 按任意键获取共享保护器（用于无 base_url/Key 的上游，如 Teacher Model）
+pub fn upstream_guard_for_key(key: &str) -> Arc<UpstreamGuard> {
+    let guards = UPSTREAM_GUARDS.get_or_init(|| std::sync::Mutex::new(HashMap::new()));
+    let mut guards = guards.lock().unwrap_or_else(|e| e.into_inner());
+    guards
+        .entry(key.to_string())
+        .or_insert_with(|| Arc::new(UpstreamGuard::new(key, upstream_guard_defaults())))
+        .clone()
+}
+
+/// 清理空闲的共享保护器，避免凭证轮换后旧条目常驻内存；返回清理数量
+///
+/// 仅清理无调用方持有、限流窗口内无请求、无冷却且未处于熔断打开期的保护器
+/// （这些状态与新建保护器等价）。
+pub fn cleanup_idle_upstream_guards() -> usize {
+    let Some(guards) = UPSTREAM_GUARDS.get() else {
+        return 0;
+    };
+    let mut guards = guards.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    let before = guards.len();
+    guards.retain(|_, guard| Arc::strong_count(guard) > 1 || !guard.is_idle(now));
+    before - guards.len()
+}
+
+/// 按请求体字符数粗略估算 token 数（约 4 字符 / token）
+pub fn estimate_tokens(chars: usize) -> u64 {
+    (chars as u64).div_ceil(4)
+}
+
+/// 上游保护错误
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum UpstreamGuardError {
+    #[error("上游熔断中，约 {retry_after_secs} 秒后重试")]
+    CircuitOpen { retry_after_secs: u64 },
+}
+
+/// 熔断器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// 单次上游调用结果（供限流 / 熔断统计）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpstreamOutcome {
+    /// 上游可达且已处理请求（含 2xx 与 429 以外的 4xx）
+    Success,
+    /// 上游限流（429），按 Retry-After 冷却，不计入熔断
+    RateLimited { retry_after: Option<Duration> },
+    /// 上游不可用（网络错误 / 超时 / 5xx），计入熔断；5xx 可携带 Retry-After
+    Failure { retry_after: Option<Duration> },
+}
+
+impl UpstreamOutcome {
+    /// 按 HTTP 状态码与响应头归类
+    pub fn from_status(status: u16, headers: &HeaderMap) -> Self {
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+        match status {
+            429 => Self::RateLimited { retry_after },
+            500..=599 => Self::Failure { retry_after },
+            _ => Self::Success,
+        }
+    }
+
+    /// 按响应归类
+    pub fn from_response(response: &reqwest::Response) -> Self {
+        Self::from_status(response.status().as_u16(), response.headers())
+    }
+}
+
+/// 解析 `Retry-After`：秒数或 HTTP-date；结果上限 [`MAX_RETRY_AFTER`]
+pub fn parse_retry_after(raw: &str) -> Option<Duration> {
+    let raw = raw.trim();
+    let delay = match raw.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => {
+            let at = chrono::DateTime::parse_from_rfc2822(raw).ok()?;
+            let millis = at.timestamp_millis() - chrono::Utc::now().timestamp_millis();
+            Duration::from_millis(millis.max(0) as u64)
+        }
+    };
+    Some(delay.min(MAX_RETRY_AFTER))
+}
+
+#[derive(Debug)]
+struct GuardState {
+    requests: VecDeque<Instant>,
+    tokens: VecDeque<(Instant, u64)>,
+    cooldown_until: Option<Instant>,
+    circuit: CircuitState,
+    open_until: Option<Instant>,
+    consecutive_failures: u32,
+    probe_started_at: Option<Instant>,
+}
+
+/// 单个凭证的限流器 + 熔断器
+#[derive(Debug)]
+pub struct UpstreamGuard {
+    key: String,
+    /// 日志标识：凭证键只记录 base_url 主机名，不输出 API Key 指纹
+    label: String,
+    config: UpstreamGuardConfig,
+    state: Mutex<GuardState>,
+}
+
+impl UpstreamGuard {
+    pub fn new(key: impl Into<String>, config: UpstreamGuardConfig) -> Self {
+        let key = key.into();
+        Self {
+            label: log_label(&key),
+            key,
+            config,
+            state: Mutex::new(GuardState {
+                requests: VecDeque::new(),
+                tokens: VecDeque::new(),
+                cooldown_until: None,
+                circuit: CircuitState::Closed,
+                open_until: None,
+                consecutive_failures: 0,
+                probe_started_at: None,
+            }),
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// 当前熔断器状态
+    pub async fn circuit_state(&self) -> CircuitState {
+        self.state.lock().await.circuit
+    }
+
+    /// 申请一次调用配额：熔断打开时立即失败；限流 / 冷却时等待。
+    pub async fn acquire(&self, estimated_tokens: u64) -> Result<(), UpstreamGuardError> {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().await;
+                let now = Instant::now();
+                self.check_circuit(&mut state, now)?;
+                match self.rate_wait(&mut state, now, estimated_tokens) {
+                    Some(wait) => wait,
+                    None => {
+                        state.requests.push_back(now);
+                        if estimated_tokens > 0 {
+                            state.tokens.push_back((now, estimated_tokens));
+                        }
+                        if state.circuit == CircuitState::HalfOpen {
+                            state.probe_started_at = Some(now);
+                        }
+                        return Ok(());
+                    }
+                }
+            };
+            tracing::debug!(
+                upstream = %self.label,
+                wait_ms = wait.as_millis(),
+                "上游限流，等待配额"
+            );
+            sleep(wait).await;
+        }
+    }
+
+    /// 记录一次调用结果
+    pub async fn record(&self, outcome: UpstreamOutcome) {
+        let opened = {
+            let mut state = self.state.lock().await;
+            let now = Instant::now();
+            match outcome {
+                UpstreamOutcome::Success => {
+                    state.consecutive_failures = 0;
+                    state.circuit = CircuitState::Closed;
+                    state.open_until = None;
+                    state.probe_started_at = None;
+                    None
+                }
+                UpstreamOutcome::RateLimited { retry_after } => {
+                    let until = now + retry_after.unwrap_or(DEFAULT_RATE_LIMIT_COOLDOWN);
+                    extend_cooldown(&mut state, until);
+                    if state.circuit == CircuitState::HalfOpen {
+                        state.probe_started_at = None;
+                    }
+                    None
+                }
+                UpstreamOutcome::Failure { retry_after } => {
+                    if let Some(retry_after) = retry_after {
+                        extend_cooldown(&mut state, now + retry_after);
+                    }
+                    state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+                    let should_open = state.circuit == CircuitState::HalfOpen
+                        || (state.circuit == CircuitState::Closed
+                            && state.consecutive_failures >= self.config.failure_threshold);
+                    if should_open {
+                        state.circuit = CircuitState::Open;
+                        state.open_until = Some(now + self.config.open_duration);
+                        state.probe_started_at = None;
+                        Some(state.consecutive_failures)
+                    } else {
+                        None
+                    }
+                }
+            }
+        };
+
+        if let Some(failures) = opened {
+            tracing::warn!(
+                upstream = %self.label,
+                consecutive_failures = failures,
+                open_secs = self.config.open_duration.as_secs(),
+                "上游连续失败，熔断器打开"
+            );
+            record_connectivity_failure(
+                ConnectivityStatus::Limited,
+                format!(
+                    "上游连续失败 {} 次，已暂停请求 {} 秒",
+                    failures,
+                    self.config.open_duration.as_secs()
+                ),
+            )
+            .await;
+        }
+    }
+
+    fn check_circuit(
+        &self,
+        state: &mut GuardState,
+        now: Instant,
+    ) -> Result<(), UpstreamGuardError> {
+        match state.circuit {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open => {
+                let until = state.open_until.unwrap_or(now);
+                if now < until {
+                    return Err(UpstreamGuardError::CircuitOpen {
+                        retry_after_secs: ceil_secs(until - now),
+                    });
+                }
+                state.circuit = CircuitState::HalfOpen;
+                state.probe_started_at = None;
+                Ok(())
+            }
+            CircuitState::HalfOpen => match state.probe_started_at {
+                // 探测请求进行中：其余请求快速失败；探测超过打开时长未回报则允许新的探测
+                Some(started) if now.duration_since(started) < self.config.open_duration => {
+                    Err(UpstreamGuardError::CircuitOpen {
+                        retry_after_secs: 1,
+                    })
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// 是否与新建保护器等价（正在使用中的保护器视为非空闲）
+    fn is_idle(&self, now: Instant) -> bool {
+        let Ok(state) = self.state.try_lock() else {
+            return false;
+        };
+        let expired = |t: &Instant| now.duration_since(*t) >= RATE_WINDOW;
+        state.requests.back().is_none_or(expired)
+            && state.tokens.back().is_none_or(|(t, _)| expired(t))
+            && state.cooldown_until.is_none_or(|until| now >= until)
+            && state.open_until.is_none_or(|until| now >= until)
+    }
+
+    /// 返回需要等待的时长；None 表示可立即发送
+    fn rate_wait(
+        &self,
+        state: &mut GuardState,
+        now: Instant,
+        estimated_tokens: u64,
+    ) -> Option<Duration> {
+        if let Some(until) = state.cooldown_until {
+            if now < until {
+                return Some(until - now);
+            }
+            state.cooldown_until = None;
+        }
+
+        while state
+            .requests
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW)
+        {
+            state.requests.pop_front();
+        }
+        while state
+            .tokens
+            .front()
+            .is_some_and(|(t, _)| now.duration_since(*t) >= RATE_WINDOW)
+        {
+            state.tokens.pop_front();
+        }
+
+        let mut wait: Option<Duration> = None;
+        if let Some(rpm) = self.config.rpm_limit.filter(|v| *v > 0)
+            && state.requests.len() >= rpm as usize
+        {
+            let freed_at = state.requests[state.requests.len() - rpm as usize] + RATE_WINDOW;
+            wait = Some(freed_at - now);
+        }
+        if let Some(tpm) = self.config.tpm_limit.filter(|v| *v > 0) {
+            // 单次估算超过上限时按上限计，避免永久等待
+            let needed = estimated_tokens.min(tpm as u64);
+            let mut used: u64 = state.tokens.iter().map(|(_, n)| *n).sum();
+            if used + needed > tpm as u64 {
+                for (at, n) in &state.tokens {
+                    used -= n;
+                    if used + needed <= tpm as u64 {
+                        let token_wait = (*at + RATE_WINDOW) - now;
+                        wait = Some(wait.map_or(token_wait, |w| w.max(token_wait)));
+                        break;
+                    }
+                }
+            }
+        }
+        wait
+    }
+}
+
+fn log_label(key: &str) -> String {
+    match key.split_once('#') {
+        Some((base_url, _)) => url::Url::parse(base_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| "unknown".to_string()),
+        None => key.to_string(),
+    }
+}
+
+fn extend_cooldown(state: &mut GuardState, until: Instant) {
+    state.cooldown_until = Some(state.cooldown_until.map_or(until, |cur| cur.max(until)));
+}
+
+fn ceil_secs(d: Duration) -> u64 {
+    d.as_millis().div_ceil(1000).max(1) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn config(rpm: Option<u32>, tpm: Option<u32>) -> UpstreamGuardConfig {
+        UpstreamGuardConfig {
+            rpm_limit: rpm,
+            tpm_limit: tpm,
+            ..UpstreamGuardConfig::default()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rpm_limit_delays_requests_beyond_window_capacity() {
+        let guard = UpstreamGuard::new("rpm", config(Some(2), None));
+        let start = Instant::now();
+        guard.acquire(0).await.unwrap();
+        guard.acquire(0).await.unwrap();
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        guard.acquire(0).await.unwrap();
+        assert!(start.elapsed() >= RATE_WINDOW);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tpm_limit_waits_until_tokens_are_released() {
+        let guard = UpstreamGuard::new("tpm", config(None, Some(100)));
+        let start = Instant::now();
+        guard.acquire(60).await.unwrap();
+        guard.acquire(60).await.unwrap();
+        assert!(start.elapsed() >= RATE_WINDOW);
+
+        // 单次估算超过上限时按上限计，不会永久等待
+        let guard = UpstreamGuard::new("tpm-oversized", config(None, Some(100)));
+        guard.acquire(1_000).await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limited_outcome_honours_retry_after() {
+        let guard = UpstreamGuard::new("retry-after", config(None, None));
+        guard
+            .record(UpstreamOutcome::RateLimited {
+                retry_after: Some(Duration::from_secs(7)),
+            })
+            .await;
+        let start = Instant::now();
+        guard.acquire(0).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_secs(7));
+        assert_eq!(guard.circuit_state().await, CircuitState::Closed);
+    }
+
+    #[test]
+    fn outcome_classification_and_retry_after_parsing() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        assert_eq!(
+            UpstreamOutcome::from_status(429, &headers),
+            UpstreamOutcome::RateLimited {
+                retry_after: Some(Duration::from_secs(3))
+            }
+        );
+        assert_eq!(
+            UpstreamOutcome::from_status(503, &HeaderMap::new()),
+            UpstreamOutcome::Failure { retry_after: None }
+        );
+        assert_eq!(
+            UpstreamOutcome::from_status(401, &HeaderMap::new()),
+            UpstreamOutcome::Success
+        );
+
+        assert_eq!(parse_retry_after("99999"), Some(MAX_RETRY_AFTER));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cleanup_evicts_only_idle_unreferenced_guards() {
+        let busy = upstream_guard("https://busy.example.com", "sk-busy");
+        busy.acquire(0).await.unwrap();
+        let guard = upstream_guard("https://idle.example.com", "sk-idle");
+        guard.acquire(0).await.unwrap();
+        let idle = Arc::downgrade(&guard);
+        drop(guard);
+
+        // 窗口内仍有请求：不清理
+        cleanup_idle_upstream_guards();
+        assert!(idle.upgrade().is_some());
+
+        tokio::time::advance(RATE_WINDOW).await;
+        cleanup_idle_upstream_guards();
+        assert!(idle.upgrade().is_none());
+
+        // 仍被调用方持有的保护器保留原实例
+        let again = upstream_guard("https://busy.example.com", "sk-busy");
+        assert!(Arc::ptr_eq(&busy, &again));
+    }
+
+    #[test]
+    fn log_label_omits_api_key_fingerprint() {
+        let guard = UpstreamGuard::new(
+            credential_key("https://api.example.com/v1", "sk-SECRET"),
+            UpstreamGuardConfig::default(),
+        );
+        assert_eq!(guard.label, "api.example.com");
+        assert_eq!(log_label("teacher_model"), "teacher_model");
+    }
+
+    #[test]
+    fn credential_key_does_not_contain_api_key() {
+        let key = credential_key("https://api.example.com/", "sk-SECRET");
+        assert!(key.starts_with("https://api.example.com#"));
+        assert!(!key.contains("sk-SECRET"));
+        assert_ne!(key, credential_key("https://api.example.com", "sk-OTHER"));
+    }
+}
@@ -13,22 +13,31 @@ use prompt_faster::api::middleware::correlation_id::{
     CORRELATION_ID_HEADER, correlation_id_middleware,
 };
 use prompt_faster::api::middleware::{
-    LoginAttemptStore, SessionStore, auth_middleware, connectivity_middleware,
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware, connectivity_middleware,
 };
 use prompt_faster::api::routes::{
-    auth, checkpoints, diagnostic, diversity, docs, health, history, iteration_control, iterations,
-    meta, meta_optimization, recovery, results, user_auth, workspaces,
+    access_tokens, audit_events, auth, checkpoints, config_diagnostics, diagnostic, diversity,
+    docs, health, history, iteration_control, iterations, meta, meta_optimization, rate_limits,
+    recovery, results, totp, user_auth, workspaces,
 };
 use prompt_faster::api::state::AppState;
 use prompt_faster::api::ws;
 use prompt_faster::core::iteration_engine::checkpoint::{
     init_checkpoint_cache_defaults, start_idle_autosave_task,
 };
+use prompt_faster::core::iteration_engine::shutdown::{
+    global_shutdown_coordinator, resume_interrupted_tasks,
+};
 use prompt_faster::infra::db::pool::{create_pool, init_global_db_pool};
-use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
+use prompt_faster::infra::db::repositories::{AuditEventRepo, ExecutionCacheRepo};
+use prompt_faster::infra::external::api_key_manager::{ApiKeyManager, ServerKek};
 use prompt_faster::infra::external::connectivity::init_connectivity_probe;
-use prompt_faster::infra::external::http_client::create_http_client;
+use prompt_faster::infra::external::http_client::create_http_client_from_config;
+use prompt_faster::infra::external::upstream_guard::{
+    cleanup_idle_upstream_guards, init_upstream_guard_defaults,
+};
 use prompt_faster::shared::config::AppConfig;
+use prompt_faster::shared::time::now_millis;
 use prompt_faster::shared::tracing_setup::init_tracing;
 
 #[tokio::main]
@@ -57,19 +66,38 @@ async fn main() -> anyhow::Result<()> {
     // 自动运行 migrations（确保 schema 就绪）
     sqlx::migrate!().run(&db).await?;
 
-    // 初始化 HTTP 客户端（复用连接池，提高性能）
-    let http_client = create_http_client()?;
+    // 处理上次停机时暂停的任务（按策略自动恢复或仅列出；失败不影响启动）
+    match resume_interrupted_tasks(&db, config.startup_recovery_policy).await {
+        Ok(resumed) if resumed > 0 => info!(resumed = resumed, "已自动恢复上次停机暂停的任务"),
+        Ok(_) => {}
+        Err(err) => tracing::warn!(error = %err, "处理上次停机暂停的任务失败"),
+    }
+
+    // 清理过期的执行结果缓存（失败不影响启动）
+    match ExecutionCacheRepo::purge_expired(&db).await {
+        Ok(purged) if purged > 0 => info!(purged = purged, "已清理过期执行结果缓存"),
+        Ok(_) => {}
+        Err(err) => tracing::warn!(error = %err, "清理过期执行结果缓存失败"),
+    }
+    // 初始化 HTTP 客户端（复用连接池；出站代理/CA 按配置；连接与重定向按 SSRF 策略复核）
+    let http_client = create_http_client_from_config(&config)?;
     info!("HTTP 客户端初始化成功");
 
+    // 初始化上游限流 / 熔断默认配置
+    init_upstream_guard_defaults(config.upstream_guard_config());
+
     // 初始化 connectivity 探测地址
-    init_connectivity_probe(format!("http://{}/api/v1/health", config.server_addr()));
+    init_connectivity_probe(
+        format!("http://{}/api/v1/health", config.server_addr()),
+        &config.outbound_http_config(),
+    );
 
     // 初始化 API Key 管理器
     //
-    // 当前策略（Critical Prep / 2026-01-03）：
-    // - 主路径使用用户登录密码派生（UnlockContext 在 SessionStore 中保存密码的内存副本）
+    // 当前策略（信封加密）：
+    // - 凭证由每用户随机数据密钥加密；数据密钥由登录密码派生密钥包装（登录时解锁并保存在 UnlockContext）
+    // - 如配置 CREDENTIAL_KEK / CREDENTIAL_KEK_FILE，数据密钥同时由服务端 KEK 包装，供无会话的后台任务解锁
     // - 如设置 MASTER_PASSWORD，则仅用于向后兼容解密旧数据（旧版本写入）
-    // - 新写入数据始终使用用户登录密码派生
     let legacy_master_password = std::env::var("MASTER_PASSWORD")
         .ok()
         .filter(|s| !s.is_empty());
@@ -81,29 +109,43 @@ async fn main() -> anyhow::Result<()> {
         );
     }
 
-    let api_key_manager = Arc::new(ApiKeyManager::new(legacy_master_password));
+    let server_kek = ServerKek::from_env()?;
+    match &server_kek {
+        Some(kek) => info!(kek_id = %kek.id(), "已加载服务端 KEK：数据密钥将同时由其包装"),
+        None => info!("未配置服务端 KEK：凭证仅可在登录会话中解锁"),
+    }
+
+    let api_key_manager =
+        Arc::new(ApiKeyManager::new(legacy_master_password).with_server_kek(server_kek));
     info!("API Key 管理器初始化成功");
 
-    // 初始化会话存储（24 小时过期）
-    let session_store = SessionStore::new(24);
+    // 初始化会话存储（持久化到 SQLite，空闲 24 小时过期）
+    let session_store = SessionStore::new(db.clone(), 24);
     info!("会话存储初始化成功");
 
     let login_attempt_store = LoginAttemptStore::default();
+    let rate_limiter = RateLimiter::new(config.rate_limit_config());
 
     // 克隆 session_store 用于 auth_middleware 和后台清理任务（在移动到 AppState 之前）
     let session_store_for_middleware = session_store.clone();
     let session_store_for_cleanup = session_store.clone();
     let login_attempt_store_for_cleanup = login_attempt_store.clone();
+    let rate_limiter_for_cleanup = rate_limiter.clone();
+    let db_for_cleanup = db.clone();
+    let audit_retention = config.audit_retention();
 
     // 启动会话过期清理后台任务（Code Review Fix: Issue #5）
-    // 每 5 分钟清理一次过期会话，避免内存泄漏
+    // 每 5 分钟清理一次过期会话、登录尝试记录、空闲上游保护器与限流令牌桶（避免内存泄漏），并清理超出保留期的审计事件
     tokio::spawn(async move {
         let cleanup_interval = std::time::Duration::from_secs(5 * 60); // 5 分钟
         loop {
             tokio::time::sleep(cleanup_interval).await;
-            let removed = session_store_for_cleanup.cleanup_expired_sessions().await;
-            if removed > 0 {
-                tracing::info!(removed_count = removed, "已清理过期会话");
+            match session_store_for_cleanup.cleanup_expired_sessions().await {
+                Ok(removed) if removed > 0 => {
+                    tracing::info!(removed_count = removed, "已清理过期会话");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = %e, "清理过期会话失败"),
             }
 
             let removed_login_attempts = login_attempt_store_for_cleanup.cleanup_expired().await;
@@ -113,6 +155,31 @@ async fn main() -> anyhow::Result<()> {
                     "已清理过期登录尝试记录"
                 );
             }
+
+            let removed_upstream_guards = cleanup_idle_upstream_guards();
+            if removed_upstream_guards > 0 {
+                tracing::debug!(
+                    removed_count = removed_upstream_guards,
+                    "已清理空闲上游保护器"
+                );
+            }
+
+            let removed_rate_limit_buckets = rate_limiter_for_cleanup.cleanup_idle();
+            if removed_rate_limit_buckets > 0 {
+                tracing::debug!(
+                    removed_count = removed_rate_limit_buckets,
+                    "已清理空闲限流令牌桶"
+                );
+            }
+
+            let audit_cutoff = now_millis() - audit_retention.as_millis() as i64;
+            match AuditEventRepo::delete_before(&db_for_cleanup, audit_cutoff).await {
+                Ok(removed) if removed > 0 => {
+                    tracing::info!(removed_count = removed, "已清理超出保留期的审计事件");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = %e, "清理审计事件失败"),
+            }
         }
     });
 
@@ -124,6 +191,7 @@ async fn main() -> anyhow::Result<()> {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter,
     };
 
     // 允许的前端 Origin（从配置读取）
@@ -147,6 +215,28 @@ async fn main() -> anyhow::Result<()> {
         middleware::from_fn_with_state(session_store_for_middleware.clone(), auth_middleware),
     );
 
+    let protected_access_token_routes = access_tokens::router().layer(
+        middleware::from_fn_with_state(session_store_for_middleware.clone(), auth_middleware),
+    );
+
+    let protected_audit_event_routes = audit_events::router().layer(
+        middleware::from_fn_with_state(session_store_for_middleware.clone(), auth_middleware),
+    );
+
+    let protected_config_diagnostics_routes = config_diagnostics::router().layer(
+        middleware::from_fn_with_state(session_store_for_middleware.clone(), auth_middleware),
+    );
+
+    let protected_rate_limit_routes = rate_limits::router().layer(middleware::from_fn_with_state(
+        session_store_for_middleware.clone(),
+        auth_middleware,
+    ));
+
+    let protected_totp_routes = totp::router().layer(middleware::from_fn_with_state(
+        session_store_for_middleware.clone(),
+        auth_middleware,
+    ));
+
     let protected_workspaces_routes = workspaces::router().layer(middleware::from_fn_with_state(
         session_store_for_middleware.clone(),
         auth_middleware,
@@ -201,6 +291,14 @@ async fn main() -> anyhow::Result<()> {
         .nest("/api/v1/auth", protected_routes) // 受保护路由：配置管理
         .nest("/api/v1/auth", user_auth::public_router())
         .nest("/api/v1/auth", protected_user_auth_routes)
+        .nest("/api/v1/auth/tokens", protected_access_token_routes)
+        .nest("/api/v1/auth/totp", protected_totp_routes)
+        .nest("/api/v1/auth/audit-events", protected_audit_event_routes)
+        .nest(
+            "/api/v1/auth/config-diagnostics",
+            protected_config_diagnostics_routes,
+        )
+        .nest("/api/v1/auth/rate-limits", protected_rate_limit_routes)
         .nest("/api/v1/workspaces", protected_workspaces_routes)
         .nest(
             "/api/v1/tasks/{task_id}/iterations",
@@ -268,7 +366,48 @@ async fn main() -> anyhow::Result<()> {
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
     )
+    .with_graceful_shutdown(shutdown_signal(config.shutdown_grace_period()))
     .await?;
 
+    info!("Prompt Faster 已停止");
     Ok(())
 }
+
+/// 等待停机信号（Ctrl+C / SIGTERM），随后通知运行中任务在安全点暂停并写入 Checkpoint
+async fn shutdown_signal(grace: std::time::Duration) {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!(error = %err, "监听 Ctrl+C 失败");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                tracing::error!(error = %err, "监听 SIGTERM 失败");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+
+    info!("收到停机信号，等待运行中任务暂停...");
+    let report = global_shutdown_coordinator().shutdown(grace).await;
+    info!(
+        signalled = report.signalled,
+        timed_out = report.timed_out.len(),
+        "运行中任务已处理，停止接收新请求"
+    );
+}
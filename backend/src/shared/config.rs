@@ -3,6 +3,23 @@
 
 use std::env;
 use std::path::Path;
+use std::time::Duration;
+
+use crate::api::middleware::rate_limit::{
+    PROMPT_EXECUTION_PER_MINUTE_DEFAULT, RateLimitConfig, RateLimitRule,
+    UPSTREAM_QUERY_PER_MINUTE_DEFAULT,
+};
+use crate::core::iteration_engine::shutdown::{
+    SHUTDOWN_GRACE_PERIOD_SECS_DEFAULT, StartupRecoveryPolicy,
+};
+use crate::infra::external::http_client::OutboundHttpConfig;
+use crate::infra::external::upstream_guard::{
+    CIRCUIT_FAILURE_THRESHOLD_DEFAULT, CIRCUIT_OPEN_SECS_DEFAULT, UpstreamGuardConfig,
+};
+use crate::shared::url_validator::BaseUrlValidationOptions;
+
+/// 安全审计事件默认保留天数
+pub const AUDIT_RETENTION_DAYS_DEFAULT: u64 = 90;
 
 /// 应用配置
 #[derive(Debug, Clone)]
@@ -31,6 +48,82 @@ pub struct AppConfig {
     pub checkpoint_cache_limit: usize,
     /// Checkpoint 缓存告警阈值（默认 10）
     pub checkpoint_memory_alert_threshold: usize,
+    /// 每个凭证每分钟请求数上限（未配置则不限制）
+    pub upstream_rpm_limit: Option<u32>,
+    /// 每个凭证每分钟 token 数上限（按请求体估算；未配置则不限制）
+    pub upstream_tpm_limit: Option<u32>,
+    /// 上游连续失败多少次后熔断（默认 5）
+    pub upstream_circuit_failure_threshold: u32,
+    /// 熔断打开时长（秒，默认 30）
+    pub upstream_circuit_open_secs: u64,
+    /// 停机宽限期（秒，默认 30）：等待运行中任务在安全点暂停并写入 Checkpoint
+    pub shutdown_grace_period_secs: u64,
+    /// 启动时对上次停机暂停任务的处理策略（默认仅列出）
+    pub startup_recovery_policy: StartupRecoveryPolicy,
+    /// 安全审计事件保留天数（默认 90，超期记录由后台任务清理）
+    pub audit_retention_days: u64,
+    /// 出站代理地址（http/https/socks5/socks5h，未配置则直连，不读取系统代理环境变量）
+    pub outbound_proxy_url: Option<String>,
+    /// 不走代理的主机列表（逗号分隔，语义同 NO_PROXY）
+    pub outbound_no_proxy: Option<String>,
+    /// 额外信任的 CA 证书文件（PEM，可含多张；与内置根证书同时生效）
+    pub outbound_ca_bundle_path: Option<String>,
+    /// mTLS 客户端证书文件（PEM）
+    pub outbound_client_cert_path: Option<String>,
+    /// mTLS 客户端私钥文件（PEM；证书文件已包含私钥时可不配置）
+    pub outbound_client_key_path: Option<String>,
+    /// 是否允许由代理解析目标主机（http/https/socks5h 代理；目标 IP 无法在连接阶段复核，默认 false）
+    pub outbound_proxy_remote_dns: bool,
+    /// Prompt 执行类接口（预览、对比）每用户每分钟配额（默认 10，0 = 不限制）
+    pub rate_limit_prompt_execution_per_minute: u32,
+    /// Prompt 执行类接口突发上限（默认等于每分钟配额）
+    pub rate_limit_prompt_execution_burst: u32,
+    /// 上游查询类接口（Dify 变量刷新、模型列表）每用户每分钟配额（默认 30，0 = 不限制）
+    pub rate_limit_upstream_query_per_minute: u32,
+    /// 上游查询类接口突发上限（默认等于每分钟配额）
+    pub rate_limit_upstream_query_burst: u32,
+}
+
+/// 内置默认值（与未设置任何环境变量时的 `from_env` 一致，Docker 检测除外）
+///
+/// 主要供测试以结构体更新语法构造配置，新增字段只需在此补充默认值。
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            database_url: "sqlite:data/prompt_faster.db?mode=rwc".to_string(),
+            server_host: "127.0.0.1".to_string(),
+            server_port: 3000,
+            log_level: "info".to_string(),
+            is_dev: true,
+            cors_origins: vec![
+                "http://localhost:5173".to_string(),
+                "http://127.0.0.1:5173".to_string(),
+            ],
+            is_docker: false,
+            allow_http_base_url: true,
+            allow_localhost_base_url: true,
+            allow_private_network_base_url: true,
+            checkpoint_cache_limit: 10,
+            checkpoint_memory_alert_threshold: 10,
+            upstream_rpm_limit: None,
+            upstream_tpm_limit: None,
+            upstream_circuit_failure_threshold: CIRCUIT_FAILURE_THRESHOLD_DEFAULT,
+            upstream_circuit_open_secs: CIRCUIT_OPEN_SECS_DEFAULT,
+            shutdown_grace_period_secs: SHUTDOWN_GRACE_PERIOD_SECS_DEFAULT,
+            startup_recovery_policy: StartupRecoveryPolicy::default(),
+            audit_retention_days: AUDIT_RETENTION_DAYS_DEFAULT,
+            outbound_proxy_url: None,
+            outbound_no_proxy: None,
+            outbound_ca_bundle_path: None,
+            outbound_client_cert_path: None,
+            outbound_client_key_path: None,
+            outbound_proxy_remote_dns: false,
+            rate_limit_prompt_execution_per_minute: PROMPT_EXECUTION_PER_MINUTE_DEFAULT,
+            rate_limit_prompt_execution_burst: PROMPT_EXECUTION_PER_MINUTE_DEFAULT,
+            rate_limit_upstream_query_per_minute: UPSTREAM_QUERY_PER_MINUTE_DEFAULT,
+            rate_limit_upstream_query_burst: UPSTREAM_QUERY_PER_MINUTE_DEFAULT,
+        }
+    }
 }
 
 impl AppConfig {
@@ -42,6 +135,13 @@ impl AppConfig {
             .map(|v| v == "development")
             .unwrap_or(true);
 
+        let rate_limit_prompt_execution_per_minute =
+            parse_u32_env("RATE_LIMIT_PROMPT_EXECUTION_PER_MINUTE")
+                .unwrap_or(PROMPT_EXECUTION_PER_MINUTE_DEFAULT);
+        let rate_limit_upstream_query_per_minute =
+            parse_u32_env("RATE_LIMIT_UPSTREAM_QUERY_PER_MINUTE")
+                .unwrap_or(UPSTREAM_QUERY_PER_MINUTE_DEFAULT);
+
         Ok(Self {
             database_url: env::var("DATABASE_URL")
                 .unwrap_or_else(|_| "sqlite:data/prompt_faster.db?mode=rwc".to_string()),
@@ -84,6 +184,60 @@ impl AppConfig {
                 .as_deref()
                 .and_then(parse_usize_env)
                 .unwrap_or(10),
+            upstream_rpm_limit: env::var("UPSTREAM_RPM_LIMIT")
+                .ok()
+                .as_deref()
+                .and_then(parse_usize_env)
+                .map(|v| v.min(u32::MAX as usize) as u32),
+            upstream_tpm_limit: env::var("UPSTREAM_TPM_LIMIT")
+                .ok()
+                .as_deref()
+                .and_then(parse_usize_env)
+                .map(|v| v.min(u32::MAX as usize) as u32),
+            upstream_circuit_failure_threshold: env::var("UPSTREAM_CIRCUIT_FAILURE_THRESHOLD")
+                .ok()
+                .as_deref()
+                .and_then(parse_usize_env)
+                .map(|v| v.min(u32::MAX as usize) as u32)
+                .unwrap_or(CIRCUIT_FAILURE_THRESHOLD_DEFAULT),
+            upstream_circuit_open_secs: env::var("UPSTREAM_CIRCUIT_OPEN_SECS")
+                .ok()
+                .as_deref()
+                .and_then(parse_usize_env)
+                .map(|v| v as u64)
+                .unwrap_or(CIRCUIT_OPEN_SECS_DEFAULT),
+            shutdown_grace_period_secs: env::var("SHUTDOWN_GRACE_PERIOD_SECS")
+                .ok()
+                .as_deref()
+                .and_then(parse_usize_env)
+                .map(|v| v as u64)
+                .unwrap_or(SHUTDOWN_GRACE_PERIOD_SECS_DEFAULT),
+            startup_recovery_policy: env::var("STARTUP_RECOVERY_POLICY")
+                .ok()
+                .as_deref()
+                .and_then(StartupRecoveryPolicy::parse)
+                .unwrap_or_default(),
+            audit_retention_days: env::var("AUDIT_RETENTION_DAYS")
+                .ok()
+                .as_deref()
+                .and_then(parse_usize_env)
+                .map(|v| v as u64)
+                .unwrap_or(AUDIT_RETENTION_DAYS_DEFAULT),
+            outbound_proxy_url: non_empty_env("OUTBOUND_PROXY_URL"),
+            outbound_no_proxy: non_empty_env("OUTBOUND_NO_PROXY"),
+            outbound_ca_bundle_path: non_empty_env("OUTBOUND_CA_BUNDLE"),
+            outbound_client_cert_path: non_empty_env("OUTBOUND_CLIENT_CERT"),
+            outbound_client_key_path: non_empty_env("OUTBOUND_CLIENT_KEY"),
+            outbound_proxy_remote_dns: env::var("OUTBOUND_PROXY_REMOTE_DNS")
+                .ok()
+                .as_deref()
+                .is_some_and(parse_bool_env),
+            rate_limit_prompt_execution_per_minute,
+            rate_limit_prompt_execution_burst: parse_u32_env("RATE_LIMIT_PROMPT_EXECUTION_BURST")
+                .unwrap_or(rate_limit_prompt_execution_per_minute),
+            rate_limit_upstream_query_per_minute,
+            rate_limit_upstream_query_burst: parse_u32_env("RATE_LIMIT_UPSTREAM_QUERY_BURST")
+                .unwrap_or(rate_limit_upstream_query_per_minute),
         })
     }
 
@@ -91,6 +245,73 @@ impl AppConfig {
     pub fn server_addr(&self) -> String {
         format!("{}:{}", self.server_host, self.server_port)
     }
+
+    /// 上游限流 / 熔断默认配置
+    pub fn upstream_guard_config(&self) -> UpstreamGuardConfig {
+        UpstreamGuardConfig {
+            rpm_limit: self.upstream_rpm_limit,
+            tpm_limit: self.upstream_tpm_limit,
+            failure_threshold: self.upstream_circuit_failure_threshold,
+            open_duration: Duration::from_secs(self.upstream_circuit_open_secs),
+        }
+    }
+
+    /// 停机宽限期
+    pub fn shutdown_grace_period(&self) -> Duration {
+        Duration::from_secs(self.shutdown_grace_period_secs)
+    }
+
+    /// 审计事件保留期
+    pub fn audit_retention(&self) -> Duration {
+        Duration::from_secs(self.audit_retention_days.saturating_mul(24 * 60 * 60))
+    }
+
+    /// base_url 校验策略（SSRF 防护）
+    pub fn base_url_validation_options(&self) -> BaseUrlValidationOptions {
+        BaseUrlValidationOptions {
+            allow_http: self.allow_http_base_url,
+            allow_localhost: self.allow_localhost_base_url,
+            allow_private_network: self.allow_private_network_base_url,
+        }
+    }
+
+    /// 按用户 + 路由类别的接口限流规则
+    pub fn rate_limit_config(&self) -> RateLimitConfig {
+        RateLimitConfig {
+            prompt_execution: RateLimitRule {
+                per_minute: self.rate_limit_prompt_execution_per_minute,
+                burst: self.rate_limit_prompt_execution_burst,
+            },
+            upstream_query: RateLimitRule {
+                per_minute: self.rate_limit_upstream_query_per_minute,
+                burst: self.rate_limit_upstream_query_burst,
+            },
+        }
+    }
+
+    /// 出站 HTTP 网络配置（代理 / 自定义 CA / mTLS）
+    pub fn outbound_http_config(&self) -> OutboundHttpConfig {
+        OutboundHttpConfig {
+            proxy_url: self.outbound_proxy_url.clone(),
+            no_proxy: self.outbound_no_proxy.clone(),
+            ca_bundle_path: self.outbound_ca_bundle_path.clone(),
+            client_cert_path: self.outbound_client_cert_path.clone(),
+            client_key_path: self.outbound_client_key_path.clone(),
+            allow_remote_dns: self.outbound_proxy_remote_dns,
+        }
+    }
+}
+
+/// 读取非负整数（允许 0，用于"0 = 不限制"语义）
+fn parse_u32_env(key: &str) -> Option<u32> {
+    env::var(key).ok()?.trim().parse::<u32>().ok()
+}
+
+fn non_empty_env(key: &str) -> Option<String> {
+    env::var(key)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
 }
 
 fn parse_bool_env(v: &str) -> bool {
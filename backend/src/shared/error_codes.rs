@@ -74,6 +74,15 @@ pub const AUTH_INTERNAL_ERROR: &str = "AUTH_INTERNAL_ERROR";
 /// 认证失败（用户名或密码错误）
 pub const AUTH_FAILED: &str = "AUTH_FAILED";
 
+/// 需要两步验证码（密码已通过校验）
+pub const AUTH_TOTP_REQUIRED: &str = "AUTH_TOTP_REQUIRED";
+
+/// 两步验证码或恢复码无效
+pub const AUTH_TOTP_INVALID: &str = "AUTH_TOTP_INVALID";
+
+/// 两步验证状态冲突（已启用 / 尚未生成密钥）
+pub const AUTH_TOTP_STATE_CONFLICT: &str = "AUTH_TOTP_STATE_CONFLICT";
+
 // ============================================================================
 // 资源错误码 (RESOURCE)
 // ============================================================================
@@ -93,6 +102,9 @@ pub const RESOURCE_NOT_FOUND: &str = "RESOURCE_NOT_FOUND";
 /// 资源禁止访问
 pub const RESOURCE_FORBIDDEN: &str = "RESOURCE_FORBIDDEN";
 
+/// 绑定的凭证属于工作区其他成员，当前用户不可使用
+pub const CREDENTIAL_NOT_ACCESSIBLE: &str = "CREDENTIAL_NOT_ACCESSIBLE";
+
 // ============================================================================
 // 用户错误码 (USER)
 // ============================================================================
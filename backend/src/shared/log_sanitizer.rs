@@ -22,6 +22,69 @@ pub fn sanitize_api_key(api_key: &str) -> String {
     format!("{}****{}", &api_key[..4], &api_key[len - 4..])
 }
 
+/// 视为敏感的字段名片段（小写匹配）
+const SENSITIVE_KEY_PARTS: &[&str] = &[
+    "password",
+    "secret",
+    "token",
+    "api_key",
+    "apikey",
+    "authorization",
+    "cookie",
+    "private_key",
+    "totp",
+    "recovery_code",
+];
+
+/// 形似密钥的字符串前缀（即使出现在非敏感字段中也脱敏）
+const SECRET_VALUE_PREFIXES: &[&str] = &["pf_pat_", "sk-", "Bearer "];
+
+/// 判断字段名是否敏感
+pub fn is_sensitive_key(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    SENSITIVE_KEY_PARTS.iter().any(|part| lower.contains(part))
+}
+
+/// 递归脱敏 JSON 值（用于审计记录等需要落库的结构化数据）
+///
+/// 规则：
+/// - 敏感字段名对应的值整体替换为 `****`（不保留前后缀）
+/// - 其余字段中形似密钥的字符串按 [`sanitize_api_key`] 脱敏
+///
+/// # Examples
+/// ```
+/// use prompt_faster::shared::log_sanitizer::sanitize_json_value;
+/// use serde_json::json;
+///
+/// let value = sanitize_json_value(&json!({"name": "prod", "api_key": "sk-1234567890"}));
+/// assert_eq!(value, json!({"name": "prod", "api_key": "****"}));
+/// ```
+pub fn sanitize_json_value(value: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let sanitized = if is_sensitive_key(key) && !value.is_null() {
+                        Value::String("****".to_string())
+                    } else {
+                        sanitize_json_value(value)
+                    };
+                    (key.clone(), sanitized)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(sanitize_json_value).collect()),
+        Value::String(s)
+            if s.is_ascii() && SECRET_VALUE_PREFIXES.iter().any(|p| s.starts_with(p)) =>
+        {
+            Value::String(sanitize_api_key(s))
+        }
+        other => other.clone(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -48,4 +111,25 @@ mod tests {
         // 恰好 9 个字符
         assert_eq!(sanitize_api_key("123456789"), "1234****6789");
     }
+
+    #[test]
+    fn test_sanitize_json_value_redacts_nested_secrets() {
+        let value = serde_json::json!({
+            "username": "alice",
+            "Password": "hunter2",
+            "nested": {"session_token": "abc", "reason": "invalid_credentials"},
+            "items": [{"apiKey": "x"}, "pf_pat_0123456789abcdef"],
+            "totp_code": null
+        });
+
+        let sanitized = sanitize_json_value(&value);
+
+        assert_eq!(sanitized["username"], "alice");
+        assert_eq!(sanitized["Password"], "****");
+        assert_eq!(sanitized["nested"]["session_token"], "****");
+        assert_eq!(sanitized["nested"]["reason"], "invalid_credentials");
+        assert_eq!(sanitized["items"][0]["apiKey"], "****");
+        assert_eq!(sanitized["items"][1], "pf_p****cdef");
+        assert!(sanitized["totp_code"].is_null());
+    }
 }
@@ -6,6 +6,7 @@ pub mod error_codes;
 pub mod log_sanitizer;
 pub mod password;
 pub mod time;
+pub mod totp;
 pub mod tracing_setup;
 pub mod url_validator;
 pub mod ws;
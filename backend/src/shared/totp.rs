@@ -0,0 +1,267 @@
+//! TOTP 两步验证（RFC 6238）
+//!
+//! - HMAC-SHA1、6 位数字、30 秒步长，兼容主流认证器 App
+//! - 密钥以 Base32（RFC 4648，无填充）写入 `otpauth://` 配置 URI
+//! - 恢复码为一次性随机串，仅以 SHA-256 摘要落库
+
+use hmac::{Hmac, Mac};
+use rand::{Rng, RngCore};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// 验证码位数
+pub const TOTP_DIGITS: u32 = 6;
+/// 步长（秒）
+pub const TOTP_PERIOD_SECS: i64 = 30;
+/// 展示给认证器的签发方名称
+pub const TOTP_ISSUER: &str = "Prompt Faster";
+/// 每次生成的恢复码数量
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// 允许的时钟偏差（前后各 1 个步长）
+const TOTP_SKEW_STEPS: i64 = 1;
+/// 密钥长度（RFC 4226 推荐 160 bit）
+const TOTP_SECRET_BYTES: usize = 20;
+/// 恢复码字符集（去除易混淆的 0/o/1/l/i）
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+/// 恢复码分组长度（格式：xxxxx-xxxxx）
+const RECOVERY_CODE_GROUP_LEN: usize = 5;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// 生成随机 TOTP 密钥
+pub fn generate_secret() -> Vec<u8> {
+    let mut secret = vec![0u8; TOTP_SECRET_BYTES];
+    rand::thread_rng().fill_bytes(&mut secret);
+    secret
+}
+
+/// Base32 编码（RFC 4648，无填充）
+pub fn encode_base32(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+/// Base32 解码（忽略大小写、空格与填充）；含非法字符时返回 None
+pub fn decode_base32(encoded: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(encoded.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in encoded.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        if !c.is_ascii() {
+            return None;
+        }
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&b| b == c.to_ascii_uppercase() as u8)?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// 时间戳（毫秒）所在的步数
+pub fn step_at(now_millis: i64) -> i64 {
+    now_millis.div_euclid(1000) / TOTP_PERIOD_SECS
+}
+
+/// 计算指定步数的验证码（RFC 4226 动态截断）
+pub fn code_at(secret: &[u8], step: i64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC 接受任意长度密钥");
+    mac.update(&(step as u64).to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    binary % 10_u32.pow(TOTP_DIGITS)
+}
+
+/// 是否为 TOTP 验证码格式（6 位数字，允许中间空格）
+pub fn is_totp_code(code: &str) -> bool {
+    let digits: Vec<char> = code.chars().filter(|c| !c.is_whitespace()).collect();
+    digits.len() == TOTP_DIGITS as usize && digits.iter().all(|c| c.is_ascii_digit())
+}
+
+/// 校验验证码，返回匹配的步数
+///
+/// 允许前后各 1 个步长的时钟偏差；`last_used_step` 及之前的步数视为已使用（防重放）。
+pub fn verify_code(
+    secret: &[u8],
+    code: &str,
+    now_millis: i64,
+    last_used_step: Option<i64>,
+) -> Option<i64> {
+    if !is_totp_code(code) {
+        return None;
+    }
+    let code: u32 = code
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .parse()
+        .ok()?;
+    let current = step_at(now_millis);
+    (current - TOTP_SKEW_STEPS..=current + TOTP_SKEW_STEPS)
+        .filter(|step| last_used_step.is_none_or(|last| *step > last))
+        .find(|step| code_at(secret, *step) == code)
+}
+
+/// 生成认证器配置 URI（可直接渲染为二维码）
+pub fn provisioning_uri(account: &str, secret: &[u8]) -> String {
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_PERIOD_SECS}",
+        issuer = percent_encode(TOTP_ISSUER),
+        account = percent_encode(account),
+        secret = encode_base32(secret),
+    )
+}
+
+/// 生成一组一次性恢复码（明文，仅展示一次）
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut code = String::with_capacity(RECOVERY_CODE_GROUP_LEN * 2 + 1);
+            for i in 0..RECOVERY_CODE_GROUP_LEN * 2 {
+                if i == RECOVERY_CODE_GROUP_LEN {
+                    code.push('-');
+                }
+                let idx = rng.gen_range(0..RECOVERY_CODE_ALPHABET.len());
+                code.push(RECOVERY_CODE_ALPHABET[idx] as char);
+            }
+            code
+        })
+        .collect()
+}
+
+/// 恢复码摘要（忽略大小写、空格与连字符）
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect();
+    format!("{:x}", Sha256::digest(normalized.as_bytes()))
+}
+
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 6238 附录 B 的 SHA1 测试密钥
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn test_code_matches_rfc6238_vectors() {
+        // 附录 B 为 8 位验证码，取后 6 位
+        for (unix_secs, expected) in [
+            (59_i64, 94_287_082_u32),
+            (1_111_111_109, 7_081_804),
+            (1_234_567_890, 89_005_924),
+            (2_000_000_000, 69_279_037),
+        ] {
+            let step = step_at(unix_secs * 1000);
+            assert_eq!(code_at(RFC_SECRET, step), expected % 1_000_000);
+        }
+    }
+
+    #[test]
+    fn test_base32_encoding() {
+        assert_eq!(encode_base32(b""), "");
+        assert_eq!(encode_base32(b"f"), "MY");
+        assert_eq!(encode_base32(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(
+            encode_base32(RFC_SECRET),
+            "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ"
+        );
+        assert_eq!(
+            decode_base32("gezd gnbv gy3t qojq gezd gnbv gy3t qojq").as_deref(),
+            Some(RFC_SECRET)
+        );
+        assert_eq!(
+            decode_base32("MZXW6YTBOI======").as_deref(),
+            Some(&b"foobar"[..])
+        );
+        assert_eq!(decode_base32("MZ1W"), None);
+    }
+
+    #[test]
+    fn test_verify_code_allows_skew_and_rejects_replay() {
+        let now = 1_234_567_890_000;
+        let step = step_at(now);
+        let previous = format!("{:06}", code_at(RFC_SECRET, step - 1));
+        let current = format!("{:06}", code_at(RFC_SECRET, step));
+        let stale = format!("{:06}", code_at(RFC_SECRET, step - 3));
+
+        assert_eq!(
+            verify_code(RFC_SECRET, &previous, now, None),
+            Some(step - 1)
+        );
+        assert_eq!(verify_code(RFC_SECRET, &current, now, None), Some(step));
+        assert_eq!(verify_code(RFC_SECRET, &stale, now, None), None);
+        assert_eq!(verify_code(RFC_SECRET, &current, now, Some(step)), None);
+        assert_eq!(verify_code(RFC_SECRET, "abcdef", now, None), None);
+    }
+
+    #[test]
+    fn test_provisioning_uri_encodes_label() {
+        let uri = provisioning_uri("alice smith", RFC_SECRET);
+        assert_eq!(
+            uri,
+            "otpauth://totp/Prompt%20Faster:alice%20smith?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Prompt%20Faster&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn test_recovery_codes_are_unique_and_hash_normalized() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        let unique: std::collections::HashSet<_> = codes.iter().collect();
+        assert_eq!(unique.len(), RECOVERY_CODE_COUNT);
+        assert!(
+            codes
+                .iter()
+                .all(|c| c.len() == 11 && c.as_bytes()[5] == b'-')
+        );
+
+        assert_eq!(
+            hash_recovery_code("abcde-fghjk"),
+            hash_recovery_code(" ABCDE FGHJK ")
+        );
+        assert_ne!(
+            hash_recovery_code("abcde-fghjk"),
+            hash_recovery_code("abcde-fghjm")
+        );
+    }
+}
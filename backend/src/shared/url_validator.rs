@@ -1,7 +1,14 @@
 //! URL 验证工具
 //! 防止 SSRF 攻击，确保 base_url 安全
 
-use std::net::IpAddr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+use tracing::debug;
+use url::Host;
+
+/// 校验阶段 DNS 解析超时（超时视为无法解析，交由连接阶段的解析器兜底）
+const DNS_RESOLVE_TIMEOUT: Duration = Duration::from_secs(3);
 
 /// URL 验证错误
 #[derive(Debug, Clone, PartialEq)]
@@ -43,6 +50,15 @@ pub struct BaseUrlValidationOptions {
     pub allow_private_network: bool,
 }
 
+impl BaseUrlValidationOptions {
+    /// 最严格策略：仅允许 HTTPS 公网地址（配置加载失败时的兜底）
+    pub const STRICT: Self = Self {
+        allow_http: false,
+        allow_localhost: false,
+        allow_private_network: false,
+    };
+}
+
 /// 验证 base_url 是否安全（防 SSRF）
 ///
 /// 规则：
@@ -61,8 +77,7 @@ pub fn validate_base_url(base_url: &str, allow_http: bool) -> Result<(), UrlVali
         base_url,
         BaseUrlValidationOptions {
             allow_http,
-            allow_localhost: false,
-            allow_private_network: false,
+            ..BaseUrlValidationOptions::STRICT
         },
     )
 }
@@ -88,61 +103,176 @@ pub fn validate_base_url_with_options(
         return Err(UrlValidationError::HttpsRequired);
     }
 
-    // 获取 host
-    let host = url.host_str().ok_or(UrlValidationError::InvalidFormat)?;
-
-    // 检查 localhost
-    if !opts.allow_localhost && is_localhost(host) {
-        return Err(UrlValidationError::LocalhostForbidden);
+    // 检查 host：IP 字面量按网段判断，域名按名称判断（解析后的地址见 `resolve_and_validate_base_url`）
+    match url.host().ok_or(UrlValidationError::InvalidFormat)? {
+        Host::Ipv4(ip) => validate_ip(IpAddr::V4(ip), opts),
+        Host::Ipv6(ip) => validate_ip(IpAddr::V6(ip), opts),
+        Host::Domain(domain) => {
+            if !opts.allow_localhost && is_localhost(domain) {
+                return Err(UrlValidationError::LocalhostForbidden);
+            }
+            Ok(())
+        }
     }
+}
 
-    // 检查私有网络
-    if !opts.allow_private_network && is_private_network(host) {
-        return Err(UrlValidationError::PrivateNetworkForbidden);
+/// 验证 base_url 并解析域名，逐个检查解析出的 IP（防 DNS 指向内网的 SSRF）
+///
+/// 在 `validate_base_url_with_options` 的基础上：
+/// - 域名解析出的**每一个**地址都必须通过 `validate_ip`，任一命中禁止网段即拒绝
+/// - 同时允许 localhost 与私有网络时不做解析
+/// - 解析失败/超时不视为校验失败：此时无法连接，连接阶段的解析器仍会再次校验
+pub async fn resolve_and_validate_base_url(
+    base_url: &str,
+    opts: BaseUrlValidationOptions,
+) -> Result<(), UrlValidationError> {
+    validate_base_url_with_options(base_url, opts)?;
+    if opts.allow_localhost && opts.allow_private_network {
+        return Ok(());
     }
 
+    let url = url::Url::parse(base_url.trim()).map_err(|_| UrlValidationError::InvalidFormat)?;
+    let Some(Host::Domain(domain)) = url.host() else {
+        return Ok(());
+    };
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let addrs =
+        match tokio::time::timeout(DNS_RESOLVE_TIMEOUT, tokio::net::lookup_host((domain, port)))
+            .await
+        {
+            Ok(Ok(addrs)) => addrs,
+            Ok(Err(e)) => {
+                debug!(host = %domain, error = %e, "base_url 域名解析失败，跳过解析校验");
+                return Ok(());
+            }
+            Err(_) => {
+                debug!(host = %domain, "base_url 域名解析超时，跳过解析校验");
+                return Ok(());
+            }
+        };
+
+    for addr in addrs {
+        validate_ip(addr.ip(), opts)?;
+    }
     Ok(())
 }
 
-/// 检查是否为 localhost
+/// 按 SSRF 策略检查单个 IP 地址（连接阶段的解析器与重定向校验共用）
+pub fn validate_ip(ip: IpAddr, opts: BaseUrlValidationOptions) -> Result<(), UrlValidationError> {
+    match classify_ip(ip) {
+        IpClass::Loopback if !opts.allow_localhost => Err(UrlValidationError::LocalhostForbidden),
+        IpClass::Private if !opts.allow_private_network => {
+            Err(UrlValidationError::PrivateNetworkForbidden)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// IP 地址分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IpClass {
+    /// 公网地址
+    Public,
+    /// 回环地址（127.0.0.0/8、::1）
+    Loopback,
+    /// 私有网络及其它不应由服务端主动访问的网段
+    Private,
+}
+
+/// 检查是否为 localhost 域名
 fn is_localhost(host: &str) -> bool {
-    let lower = host.to_lowercase();
-    lower == "localhost"
-        || lower == "127.0.0.1"
-        || lower == "::1"
-        || lower == "[::1]"
-        || lower.ends_with(".localhost")
-        || lower.ends_with(".local")
+    let lower = host.trim_end_matches('.').to_lowercase();
+    lower == "localhost" || lower.ends_with(".localhost") || lower.ends_with(".local")
 }
 
-/// 检查是否为私有网络地址
-fn is_private_network(host: &str) -> bool {
-    // 尝试解析为 IP 地址
-    if let Ok(ip) = host.parse::<IpAddr>() {
-        return match ip {
-            IpAddr::V4(ipv4) => {
-                let octets = ipv4.octets();
-                // 10.0.0.0/8
-                octets[0] == 10
-                // 172.16.0.0/12
-                || (octets[0] == 172 && (16..=31).contains(&octets[1]))
-                // 192.168.0.0/16
-                || (octets[0] == 192 && octets[1] == 168)
-                // 169.254.0.0/16 (link-local)
-                || (octets[0] == 169 && octets[1] == 254)
-                // 0.0.0.0
-                || octets == [0, 0, 0, 0]
-            }
-            IpAddr::V6(ipv6) => {
-                // fe80::/10 (link-local)
-                ipv6.segments()[0] & 0xffc0 == 0xfe80
-                // fc00::/7 (unique local)
-                || ipv6.segments()[0] & 0xfe00 == 0xfc00
-            }
-        };
+fn classify_ip(ip: IpAddr) -> IpClass {
+    match ip {
+        IpAddr::V4(ipv4) => classify_ipv4(ipv4),
+        IpAddr::V6(ipv6) => classify_ipv6(ipv6),
+    }
+}
+
+fn classify_ipv4(ip: Ipv4Addr) -> IpClass {
+    let octets = ip.octets();
+    if octets[0] == 127 {
+        return IpClass::Loopback;
+    }
+    let private =
+        // 0.0.0.0/8（含 0.0.0.0，多数系统上等同本机）
+        octets[0] == 0
+        // 10.0.0.0/8
+        || octets[0] == 10
+        // 100.64.0.0/10 (CGNAT)
+        || (octets[0] == 100 && (64..=127).contains(&octets[1]))
+        // 169.254.0.0/16 (link-local，含云厂商元数据地址 169.254.169.254)
+        || (octets[0] == 169 && octets[1] == 254)
+        // 172.16.0.0/12
+        || (octets[0] == 172 && (16..=31).contains(&octets[1]))
+        // 192.0.0.0/24 (IETF 协议分配)
+        || (octets[0] == 192 && octets[1] == 0 && octets[2] == 0)
+        // 192.168.0.0/16
+        || (octets[0] == 192 && octets[1] == 168)
+        // 198.18.0.0/15 (基准测试)
+        || (octets[0] == 198 && (octets[1] == 18 || octets[1] == 19))
+        // 224.0.0.0/4 (组播) 与 240.0.0.0/4 (保留，含广播)
+        || octets[0] >= 224;
+    if private {
+        IpClass::Private
+    } else {
+        IpClass::Public
+    }
+}
+
+fn classify_ipv6(ip: Ipv6Addr) -> IpClass {
+    if ip.is_loopback() {
+        return IpClass::Loopback;
+    }
+    if ip.is_unspecified() {
+        return IpClass::Private;
+    }
+    // 内嵌 IPv4 的地址按其 IPv4 地址判断，避免用 ::ffff:127.0.0.1 等形式绕过
+    if let Some(ipv4) = embedded_ipv4(ip) {
+        return classify_ipv4(ipv4);
     }
+    let first = ip.segments()[0];
+    let private =
+        // fe80::/10 (link-local)
+        first & 0xffc0 == 0xfe80
+        // fc00::/7 (unique local)
+        || first & 0xfe00 == 0xfc00
+        // fec0::/10 (已废弃的 site-local)
+        || first & 0xffc0 == 0xfec0
+        // ff00::/8 (组播)
+        || first & 0xff00 == 0xff00;
+    if private {
+        IpClass::Private
+    } else {
+        IpClass::Public
+    }
+}
 
-    false
+/// 提取 IPv6 地址中内嵌的 IPv4 地址
+///
+/// 覆盖：IPv4-mapped（::ffff:a.b.c.d）、IPv4-compatible（::a.b.c.d）、
+/// NAT64（64:ff9b::/96）、6to4（2002::/16）。
+fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    if let Some(ipv4) = ip.to_ipv4_mapped() {
+        return Some(ipv4);
+    }
+    let s = ip.segments();
+    let tail =
+        |hi: u16, lo: u16| Ipv4Addr::new((hi >> 8) as u8, hi as u8, (lo >> 8) as u8, lo as u8);
+    if s[..6] == [0, 0, 0, 0, 0, 0] {
+        return Some(tail(s[6], s[7]));
+    }
+    if s[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        return Some(tail(s[6], s[7]));
+    }
+    if s[0] == 0x2002 {
+        return Some(tail(s[1], s[2]));
+    }
+    None
 }
 
 /// 验证 API Key 是否有效（非空）
@@ -260,6 +390,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ipv6_and_mapped_literals_rejected() {
+        assert_eq!(
+            validate_base_url("https://[fe80::1]:8080", false),
+            Err(UrlValidationError::PrivateNetworkForbidden)
+        );
+        assert_eq!(
+            validate_base_url("https://[::ffff:127.0.0.1]/v1", false),
+            Err(UrlValidationError::LocalhostForbidden)
+        );
+        assert_eq!(
+            validate_base_url("https://[::ffff:169.254.169.254]/", false),
+            Err(UrlValidationError::PrivateNetworkForbidden)
+        );
+        assert_eq!(
+            validate_base_url("https://[64:ff9b::a00:1]/", false),
+            Err(UrlValidationError::PrivateNetworkForbidden)
+        );
+        assert_eq!(
+            validate_base_url("https://127.1.2.3/", false),
+            Err(UrlValidationError::LocalhostForbidden)
+        );
+        assert_eq!(
+            validate_base_url("https://0.0.0.0/", false),
+            Err(UrlValidationError::PrivateNetworkForbidden)
+        );
+        assert!(validate_base_url("https://[2606:4700::1111]/", false).is_ok());
+        assert!(validate_base_url("https://1.1.1.1/", false).is_ok());
+    }
+
+    #[test]
+    fn test_validate_ip_respects_options() {
+        let strict = BaseUrlValidationOptions::STRICT;
+        let loopback_only = BaseUrlValidationOptions {
+            allow_localhost: true,
+            ..strict
+        };
+        let metadata: IpAddr = "169.254.169.254".parse().unwrap();
+        let loopback: IpAddr = "::1".parse().unwrap();
+        assert_eq!(
+            validate_ip(metadata, loopback_only),
+            Err(UrlValidationError::PrivateNetworkForbidden)
+        );
+        assert!(validate_ip(loopback, loopback_only).is_ok());
+        assert_eq!(
+            validate_ip(loopback, strict),
+            Err(UrlValidationError::LocalhostForbidden)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_and_validate_base_url() {
+        let strict = BaseUrlValidationOptions {
+            allow_http: true,
+            allow_localhost: false,
+            allow_private_network: false,
+        };
+        // 带根域点的写法不能绕过名称检查
+        assert_eq!(
+            resolve_and_validate_base_url("http://localhost.:8080", strict).await,
+            Err(UrlValidationError::LocalhostForbidden)
+        );
+        assert_eq!(
+            resolve_and_validate_base_url("http://10.0.0.8/v1", strict).await,
+            Err(UrlValidationError::PrivateNetworkForbidden)
+        );
+        assert!(
+            resolve_and_validate_base_url(
+                "http://localhost:8080",
+                BaseUrlValidationOptions {
+                    allow_localhost: true,
+                    allow_private_network: true,
+                    ..strict
+                }
+            )
+            .await
+            .is_ok()
+        );
+    }
+
     #[test]
     fn test_validate_api_key() {
         assert!(validate_api_key("sk-1234567890").is_ok());
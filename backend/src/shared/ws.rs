@@ -42,6 +42,8 @@ pub const EVT_GUIDANCE_SEND_ACK: &str = "guidance:send:ack";
 pub const EVT_GUIDANCE_SENT: &str = "guidance:sent";
 /// 引导已应用事件（Layer 1 开始前触发）
 pub const EVT_GUIDANCE_APPLIED: &str = "guidance:applied";
+/// 请求用户引导事件（优化停滞/震荡且策略为人工介入时触发，任务随后在安全点暂停）
+pub const EVT_GUIDANCE_REQUESTED: &str = "guidance:requested";
 /// 任务已终止事件
 pub const EVT_TASK_TERMINATED: &str = "task:terminated";
 
@@ -247,6 +249,23 @@ pub struct GuidanceAppliedPayload {
     pub iteration: u32,
 }
 
+/// 请求用户引导事件负载
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "ws/")]
+pub struct GuidanceRequestedPayload {
+    /// 任务 ID
+    pub task_id: String,
+    /// 触发时的迭代轮次
+    pub iteration: u32,
+    /// 请求原因（结构化标识，如 `no_improvement_consecutive`）
+    pub reason: String,
+    /// 连续无提升轮数
+    pub consecutive_no_improvement: u32,
+    /// 请求时间（ISO 8601）
+    pub requested_at: String,
+}
+
 /// 任务已终止事件负载
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
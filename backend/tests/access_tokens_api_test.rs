@@ -0,0 +1,368 @@
+mod common;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::middleware;
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
+use prompt_faster::api::routes::{access_tokens, auth, health, user_auth, workspaces};
+use prompt_faster::api::state::AppState;
+use prompt_faster::infra::db::pool::create_pool;
+use prompt_faster::infra::db::repositories::CredentialRepo;
+use prompt_faster::infra::external::api_key_manager::{ApiKeyManager, EncryptedApiKey};
+use prompt_faster::infra::external::http_client::create_http_client;
+
+async fn setup_test_app() -> (Router, AppState) {
+    let db = create_pool("sqlite::memory:")
+        .await
+        .expect("创建测试数据库失败");
+
+    sqlx::migrate!()
+        .run(&db)
+        .await
+        .expect("运行 migrations 失败");
+
+    let http_client = create_http_client().expect("创建 HTTP 客户端失败");
+    let config = Arc::new(common::test_app_config());
+
+    let state = AppState {
+        db: db.clone(),
+        http_client,
+        config,
+        api_key_manager: Arc::new(ApiKeyManager::new(None)),
+        session_store: SessionStore::new(db, 24),
+        login_attempt_store: LoginAttemptStore::default(),
+        rate_limiter: RateLimiter::default(),
+    };
+
+    let session_store_for_middleware = state.session_store.clone();
+    let protected = |router: Router<AppState>| {
+        router.layer(middleware::from_fn_with_state(
+            session_store_for_middleware.clone(),
+            auth_middleware,
+        ))
+    };
+
+    let router = Router::<AppState>::new()
+        .nest("/api/v1", health::router::<AppState>())
+        .nest("/api/v1/auth", auth::public_router())
+        .nest("/api/v1/auth", protected(auth::protected_router()))
+        .nest("/api/v1/auth", user_auth::public_router())
+        .nest("/api/v1/auth", protected(user_auth::protected_router()))
+        .nest("/api/v1/auth/tokens", protected(access_tokens::router()))
+        .nest("/api/v1/workspaces", protected(workspaces::router()))
+        .with_state(state.clone())
+        .layer(middleware::from_fn(correlation_id_middleware));
+
+    (router, state)
+}
+
+async fn read_json_body(response: axum::response::Response) -> Value {
+    let bytes = response
+        .into_body()
+        .collect()
+        .await
+        .expect("读取响应 body 失败")
+        .to_bytes();
+    serde_json::from_slice(&bytes).expect("解析 JSON 失败")
+}
+
+fn request(method: &str, uri: &str, token: &str, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token));
+    match body {
+        Some(body) => builder
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::to_vec(&body).expect("序列化 JSON 失败"),
+            ))
+            .expect("构建请求失败"),
+        None => builder.body(Body::empty()).expect("构建请求失败"),
+    }
+}
+
+async fn register_user(app: &Router, username: &str) -> String {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/register")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            serde_json::to_vec(&json!({"username": username, "password": "TestPass123!"})).unwrap(),
+        ))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    read_json_body(resp).await["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string()
+}
+
+async fn create_workspace(app: &Router, token: &str, name: &str) -> String {
+    let resp = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/v1/workspaces",
+            token,
+            Some(json!({"name": name, "description": null})),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    read_json_body(resp).await["data"]["id"]
+        .as_str()
+        .expect("缺少 id")
+        .to_string()
+}
+
+async fn create_token(app: &Router, session_token: &str, body: Value) -> (StatusCode, Value) {
+    let resp = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/v1/auth/tokens",
+            session_token,
+            Some(body),
+        ))
+        .await
+        .unwrap();
+    let status = resp.status();
+    (status, read_json_body(resp).await)
+}
+
+async fn status_of(app: &Router, method: &str, uri: &str, token: &str) -> StatusCode {
+    let body = (method != "GET" && method != "DELETE").then(|| json!({"name": "ws-x"}));
+    app.clone()
+        .oneshot(request(method, uri, token, body))
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_access_token_scopes_and_revocation() {
+    let (app, _state) = setup_test_app().await;
+    let session = register_user(&app, "pat_user").await;
+    let ws1 = create_workspace(&app, &session, "ws-1").await;
+    let ws2 = create_workspace(&app, &session, "ws-2").await;
+
+    // 只读令牌：可读不可写，不能访问账户 / 凭证管理
+    let (status, json) = create_token(
+        &app,
+        &session,
+        json!({"name": "ci-read", "scope": "read", "expires_in_days": 30}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let read_token = json["data"]["token"].as_str().unwrap().to_string();
+    assert!(read_token.starts_with("pf_pat_"));
+    assert_eq!(json["data"]["access_token"]["scope"], "read");
+    assert!(json["data"]["access_token"]["expires_at"].is_i64());
+
+    assert_eq!(
+        status_of(&app, "GET", "/api/v1/workspaces", &read_token).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status_of(&app, "POST", "/api/v1/workspaces", &read_token).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status_of(&app, "GET", "/api/v1/auth/me", &read_token).await,
+        StatusCode::OK
+    );
+    for uri in ["/api/v1/auth/config", "/api/v1/auth/tokens"] {
+        assert_eq!(
+            status_of(&app, "GET", uri, &read_token).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    // 限定工作区的 run 令牌
+    let (status, json) = create_token(
+        &app,
+        &session,
+        json!({"name": "ci-run", "scope": "run", "workspace_id": ws1}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let run_token = json["data"]["token"].as_str().unwrap().to_string();
+    let run_token_id = json["data"]["access_token"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(json["data"]["access_token"]["expires_at"].is_null());
+
+    assert_eq!(
+        status_of(
+            &app,
+            "GET",
+            &format!("/api/v1/workspaces/{ws1}/test-sets"),
+            &run_token
+        )
+        .await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status_of(
+            &app,
+            "GET",
+            &format!("/api/v1/workspaces/{ws2}/test-sets"),
+            &run_token
+        )
+        .await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status_of(&app, "GET", "/api/v1/workspaces", &run_token).await,
+        StatusCode::FORBIDDEN
+    );
+    // 令牌不能创建新令牌
+    let (status, _) = create_token(&app, &run_token, json!({"name": "x", "scope": "run"})).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // 列表不含令牌明文
+    let resp = app
+        .clone()
+        .oneshot(request("GET", "/api/v1/auth/tokens", &session, None))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let json = read_json_body(resp).await;
+    let tokens = json["data"]["tokens"].as_array().unwrap();
+    assert_eq!(tokens.len(), 2);
+    assert!(!json.to_string().contains(&run_token));
+    assert!(run_token.starts_with(tokens[0]["token_prefix"].as_str().unwrap()));
+
+    // 吊销后立即失效
+    assert_eq!(
+        status_of(
+            &app,
+            "DELETE",
+            &format!("/api/v1/auth/tokens/{run_token_id}"),
+            &session
+        )
+        .await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status_of(
+            &app,
+            "GET",
+            &format!("/api/v1/workspaces/{ws1}/test-sets"),
+            &run_token
+        )
+        .await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        status_of(
+            &app,
+            "DELETE",
+            &format!("/api/v1/auth/tokens/{run_token_id}"),
+            &session
+        )
+        .await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_create_access_token_validation() {
+    let (app, _state) = setup_test_app().await;
+    let session = register_user(&app, "pat_validation_user").await;
+    let other_session = register_user(&app, "pat_validation_other").await;
+    let other_ws = create_workspace(&app, &other_session, "other-ws").await;
+
+    for body in [
+        json!({"name": "", "scope": "run"}),
+        json!({"name": "t", "scope": "admin"}),
+        json!({"name": "t", "scope": "run", "expires_in_days": 0}),
+        json!({"name": "t", "scope": "run", "expires_in_days": 366}),
+    ] {
+        let (status, _) = create_token(&app, &session, body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let (status, _) = create_token(
+        &app,
+        &session,
+        json!({"name": "t", "scope": "run", "workspace_id": other_ws}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_run_token_decrypts_credentials_without_password() {
+    let (app, state) = setup_test_app().await;
+    let session = register_user(&app, "pat_credential_user").await;
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/v1/auth/config",
+            &session,
+            Some(json!({
+                "dify": { "base_url": "https://api.dify.ai", "api_key": "sk-dify-pat-test" },
+                "generic_llm": { "provider": "openai", "base_url": "https://api.openai.com", "api_key": "sk-openai-pat-test" },
+                "teacher_settings": { "temperature": 0.7, "top_p": 0.9, "max_tokens": 2048 }
+            })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let (_, json) = create_token(&app, &session, json!({"name": "run", "scope": "run"})).await;
+    assert_eq!(json["data"]["access_token"]["credential_access"], true);
+    let run_token = json["data"]["token"].as_str().unwrap().to_string();
+    let (_, json) = create_token(&app, &session, json!({"name": "read", "scope": "read"})).await;
+    assert_eq!(json["data"]["access_token"]["credential_access"], false);
+    let read_token = json["data"]["token"].as_str().unwrap().to_string();
+
+    let session_data = state
+        .session_store
+        .validate_session(&run_token)
+        .await
+        .expect("令牌应有效");
+    let unlock_context = session_data.unlock_context.expect("run 令牌应携带数据密钥");
+    assert!(unlock_context.password_str().is_none());
+
+    let credential = CredentialRepo::find_all_by_user(&state.db, &session_data.user_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|c| c.credential_type == "generic_llm")
+        .expect("缺少凭证");
+    let plaintext = state
+        .api_key_manager
+        .decrypt_credential(
+            &unlock_context.credential_keys(),
+            &EncryptedApiKey {
+                ciphertext: credential.encrypted_api_key,
+                nonce: credential.nonce,
+                salt: credential.salt,
+            },
+        )
+        .expect("应可用令牌解密凭证");
+    assert_eq!(plaintext.as_slice(), b"sk-openai-pat-test");
+
+    let read_session = state
+        .session_store
+        .validate_session(&read_token)
+        .await
+        .expect("令牌应有效");
+    assert!(read_session.unlock_context.is_none());
+}
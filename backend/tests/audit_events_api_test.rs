@@ -0,0 +1,309 @@
+mod common;
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::{Request, StatusCode};
+use axum::middleware;
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
+use prompt_faster::api::routes::{audit_events, auth, user_auth};
+use prompt_faster::api::state::AppState;
+use prompt_faster::infra::db::pool::create_pool;
+use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
+use prompt_faster::infra::external::http_client::create_http_client;
+
+const PASSWORD: &str = "TestPass123!";
+
+async fn setup_test_app() -> (Router, sqlx::SqlitePool) {
+    let db = create_pool("sqlite::memory:")
+        .await
+        .expect("创建测试数据库失败");
+
+    sqlx::migrate!()
+        .run(&db)
+        .await
+        .expect("运行 migrations 失败");
+
+    let http_client = create_http_client().expect("创建 HTTP 客户端失败");
+    let config = Arc::new(common::test_app_config());
+
+    let state = AppState {
+        db: db.clone(),
+        http_client,
+        config,
+        api_key_manager: Arc::new(ApiKeyManager::new(None)),
+        session_store: SessionStore::new(db.clone(), 24),
+        login_attempt_store: LoginAttemptStore::default(),
+        rate_limiter: RateLimiter::default(),
+    };
+
+    let session_store_for_middleware = state.session_store.clone();
+    let protected = |router: Router<AppState>| {
+        router.layer(middleware::from_fn_with_state(
+            session_store_for_middleware.clone(),
+            auth_middleware,
+        ))
+    };
+
+    let app = Router::<AppState>::new()
+        .nest("/api/v1/auth", user_auth::public_router())
+        .nest("/api/v1/auth", protected(user_auth::protected_router()))
+        .nest("/api/v1/auth", protected(auth::protected_router()))
+        .nest(
+            "/api/v1/auth/audit-events",
+            protected(audit_events::router()),
+        )
+        .with_state(state)
+        .layer(middleware::from_fn(correlation_id_middleware));
+    (app, db)
+}
+
+async fn read_json_body(response: axum::response::Response) -> Value {
+    let bytes = response
+        .into_body()
+        .collect()
+        .await
+        .expect("读取响应 body 失败")
+        .to_bytes();
+    serde_json::from_slice(&bytes).expect("解析 JSON 失败")
+}
+
+fn request(method: &str, uri: &str, token: Option<&str>, body: Option<Value>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("user-agent", "AuditTest/1.0")
+        .header("x-correlation-id", "cid-audit");
+    if let Some(token) = token {
+        builder = builder.header("Authorization", format!("Bearer {}", token));
+    }
+    let body = match body {
+        Some(body) => Body::from(serde_json::to_vec(&body).expect("序列化 JSON 失败")),
+        None => Body::empty(),
+    };
+    let mut req = builder.body(body).expect("构建请求失败");
+    req.extensions_mut()
+        .insert(ConnectInfo(SocketAddr::from(([10, 1, 2, 3], 40000))));
+    req
+}
+
+async fn send(app: &Router, req: Request<Body>) -> (StatusCode, Value) {
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    (status, read_json_body(resp).await)
+}
+
+async fn register_user(app: &Router, username: &str) -> String {
+    let (status, body) = send(
+        app,
+        request(
+            "POST",
+            "/api/v1/auth/register",
+            None,
+            Some(json!({"username": username, "password": PASSWORD})),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    body["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string()
+}
+
+async fn login(app: &Router, username: &str, password: &str) -> StatusCode {
+    send(
+        app,
+        request(
+            "POST",
+            "/api/v1/auth/login",
+            None,
+            Some(json!({"username": username, "password": password})),
+        ),
+    )
+    .await
+    .0
+}
+
+async fn list_events(app: &Router, token: &str, query: &str) -> (StatusCode, Value) {
+    send(
+        app,
+        request(
+            "GET",
+            &format!("/api/v1/auth/audit-events{query}"),
+            Some(token),
+            None,
+        ),
+    )
+    .await
+}
+
+fn event_types(body: &Value) -> Vec<String> {
+    body["data"]["events"]
+        .as_array()
+        .expect("缺少 events")
+        .iter()
+        .map(|e| e["event_type"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_login_failures_lockout_and_success_are_audited() {
+    let (app, db) = setup_test_app().await;
+    let admin_token = register_user(&app, "admin").await;
+    let bob_token = register_user(&app, "bob").await;
+
+    for _ in 0..5 {
+        assert_eq!(
+            login(&app, "bob", "wrong-password").await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+    // 冷却期内的尝试同样记录
+    assert_eq!(login(&app, "bob", PASSWORD).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        login(&app, "ghost", "whatever").await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(login(&app, "admin", PASSWORD).await, StatusCode::OK);
+
+    // 普通用户只能看到自己的事件
+    let (status, body) = list_events(&app, &bob_token, "?event_types=login_failed").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["data"]["total"], 5);
+    let event = &body["data"]["events"][0];
+    assert_eq!(event["outcome"], "failure");
+    assert_eq!(event["actor_username"], "bob");
+    assert_eq!(event["ip_address"], "10.1.2.3");
+    assert_eq!(event["user_agent"], "AuditTest/1.0");
+    assert_eq!(event["correlation_id"], "cid-audit");
+    assert_eq!(event["details"]["reason"], "invalid_credentials");
+
+    let (_, body) = list_events(&app, &bob_token, "?event_types=login_locked_out").await;
+    assert_eq!(body["data"]["total"], 1);
+
+    let (status, body) = list_events(&app, &bob_token, "?actor_user_id=someone-else").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["code"], "FORBIDDEN");
+
+    // 实例管理员可查看全部事件，包括未知用户名与冷却期内的尝试
+    let (_, body) = list_events(&app, &admin_token, "?outcome=failure&limit=100").await;
+    let failures = body["data"]["events"].as_array().unwrap();
+    assert_eq!(failures.len(), 8);
+    assert!(
+        failures
+            .iter()
+            .any(|e| e["actor_username"] == "ghost" && e["actor_user_id"].is_null())
+    );
+    assert!(
+        failures
+            .iter()
+            .any(|e| e["details"]["reason"] == "locked_out")
+    );
+
+    let (_, body) = list_events(&app, &admin_token, "?event_types=login_succeeded").await;
+    assert_eq!(event_types(&body), vec!["login_succeeded"]);
+
+    let (status, _) = list_events(&app, &admin_token, "?event_types=unknown").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // 审计记录中不得出现口令
+    let leaked: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM audit_events WHERE details LIKE '%wrong-password%'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+    assert_eq!(leaked.0, 0);
+}
+
+#[tokio::test]
+async fn test_credential_changes_and_session_revocation_are_audited() {
+    let (app, db) = setup_test_app().await;
+    let token = register_user(&app, "alice").await;
+
+    let (status, body) = send(
+        &app,
+        request(
+            "POST",
+            "/api/v1/auth/credentials",
+            Some(&token),
+            Some(json!({
+                "credential_type": "dify",
+                "name": "app-a",
+                "base_url": "https://dify.example.com",
+                "api_key": "sk-dify-secret-123456"
+            })),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let credential_id = body["data"]["id"].as_str().unwrap().to_string();
+
+    let (status, _) = send(
+        &app,
+        request(
+            "PUT",
+            &format!("/api/v1/auth/credentials/{credential_id}"),
+            Some(&token),
+            Some(json!({
+                "name": "app-a-renamed",
+                "base_url": "https://dify.example.com",
+                "api_key": "sk-dify-rotated-654321"
+            })),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(
+        &app,
+        request(
+            "DELETE",
+            &format!("/api/v1/auth/credentials/{credential_id}"),
+            Some(&token),
+            None,
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(
+        &app,
+        request("DELETE", "/api/v1/auth/sessions", Some(&token), None),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = list_events(&app, &token, "").await;
+    assert_eq!(
+        event_types(&body),
+        vec![
+            "session_revoked",
+            "credential_deleted",
+            "credential_updated",
+            "credential_created",
+        ]
+    );
+    let events = body["data"]["events"].as_array().unwrap();
+    assert_eq!(events[1]["target_type"], "credential");
+    assert_eq!(events[1]["target_id"], credential_id.as_str());
+    assert_eq!(events[2]["details"]["name"], "app-a-renamed");
+    assert_eq!(events[2]["details"]["key_rotated"], true);
+
+    let leaked: (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM audit_events WHERE details LIKE '%sk-dify%'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+    assert_eq!(leaked.0, 0);
+}
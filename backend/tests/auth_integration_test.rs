@@ -1,3 +1,5 @@
+mod common;
+
 use axum::Router;
 use axum::body::Body;
 use axum::extract::ConnectInfo;
@@ -13,17 +15,25 @@ use wiremock::matchers::{header, header_exists, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
-use prompt_faster::api::middleware::{LoginAttemptStore, SessionStore, auth_middleware};
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
 use prompt_faster::api::routes::{auth, health, user_auth};
 use prompt_faster::api::state::AppState;
 use prompt_faster::infra::db::pool::create_pool;
-use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
+use prompt_faster::infra::external::api_key_manager::{ApiKeyManager, ServerKek};
 use prompt_faster::infra::external::http_client::create_http_client;
-use prompt_faster::shared::config::AppConfig;
 
 const TEST_MASTER_PASSWORD: &str = "test_master_password_for_integration";
 
 async fn setup_test_app_with_db() -> (Router, SqlitePool) {
+    let (router, db, _) = setup_test_app_with_server_kek(None).await;
+    (router, db)
+}
+
+async fn setup_test_app_with_server_kek(
+    server_kek: Option<ServerKek>,
+) -> (Router, SqlitePool, SessionStore) {
     let db = create_pool("sqlite::memory:")
         .await
         .expect("创建测试数据库失败");
@@ -34,25 +44,12 @@ async fn setup_test_app_with_db() -> (Router, SqlitePool) {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-
-        checkpoint_cache_limit: 10,
-
-        checkpoint_memory_alert_threshold: 10,
-    });
-    let api_key_manager = Arc::new(ApiKeyManager::new(Some(TEST_MASTER_PASSWORD.to_string())));
+    let config = Arc::new(common::test_app_config());
+    let api_key_manager = Arc::new(
+        ApiKeyManager::new(Some(TEST_MASTER_PASSWORD.to_string())).with_server_kek(server_kek),
+    );
 
-    let session_store = SessionStore::new(24);
+    let session_store = SessionStore::new(db.clone(), 24);
     let login_attempt_store = LoginAttemptStore::default();
 
     let state = AppState {
@@ -62,6 +59,7 @@ async fn setup_test_app_with_db() -> (Router, SqlitePool) {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: RateLimiter::default(),
     };
 
     let session_store_for_middleware = state.session_store.clone();
@@ -72,7 +70,7 @@ async fn setup_test_app_with_db() -> (Router, SqlitePool) {
     ));
 
     let protected_user_auth_routes = user_auth::protected_router().layer(
-        middleware::from_fn_with_state(session_store_for_middleware, auth_middleware),
+        middleware::from_fn_with_state(session_store_for_middleware.clone(), auth_middleware),
     );
 
     let router = Router::<AppState>::new()
@@ -84,7 +82,7 @@ async fn setup_test_app_with_db() -> (Router, SqlitePool) {
         .with_state(state)
         .layer(middleware::from_fn(correlation_id_middleware));
 
-    (router, db)
+    (router, db, session_store_for_middleware)
 }
 
 async fn setup_test_app() -> Router {
@@ -224,6 +222,181 @@ async fn test_list_generic_llm_models_returns_models_when_configured() {
     assert_eq!(models[1].as_str(), Some("gpt-3.5-turbo"));
 }
 
+#[tokio::test]
+async fn test_custom_llm_provider_lifecycle_and_models_listing() {
+    let app = setup_test_app().await;
+
+    let register_req = build_json_request(
+        "POST",
+        "/api/v1/auth/register",
+        json!({"username": "test_user_custom_provider", "password": "TestPass123!"}),
+    );
+    let register_resp = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(register_resp.status(), StatusCode::OK);
+    let register_json = read_json_body(register_resp).await;
+    let token = register_json["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string();
+
+    let list = |token: String| {
+        with_bearer(
+            Request::builder()
+                .method("GET")
+                .uri("/api/v1/auth/llm-providers")
+                .body(Body::empty())
+                .unwrap(),
+            &token,
+        )
+    };
+
+    // 内置 Provider 始终可见
+    let resp = app.clone().oneshot(list(token.clone())).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json_body(resp).await;
+    let ids: Vec<&str> = body["data"]["providers"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|p| p["id"].as_str())
+        .collect();
+    for id in [
+        "siliconflow",
+        "modelscope",
+        "openai",
+        "deepseek",
+        "azure_openai",
+        "anthropic",
+    ] {
+        assert!(ids.contains(&id), "缺少内置 Provider: {id}");
+    }
+
+    let spec = json!({
+        "id": "ignored",
+        "display_name": "My vLLM",
+        "default_base_url": null,
+        "auth_style": "bearer",
+        "models_path": "/vllm/v1/models",
+        "chat_path": "/vllm/v1/chat/completions",
+        "api_format": "openai_chat",
+        "builtin": true
+    });
+
+    // 不允许覆盖内置 Provider
+    let resp = app
+        .clone()
+        .oneshot(with_bearer(
+            build_json_request("PUT", "/api/v1/auth/llm-providers/openai", spec.clone()),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = app
+        .clone()
+        .oneshot(with_bearer(
+            build_json_request("PUT", "/api/v1/auth/llm-providers/my-vllm", spec),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json_body(resp).await;
+    assert_eq!(body["data"]["id"], "my-vllm");
+    assert_eq!(body["data"]["builtin"], false);
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/vllm/v1/models"))
+        .and(header("Authorization", "Bearer test-api-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [{ "id": "qwen2.5-7b" }]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let save_req = with_bearer(
+        build_json_request(
+            "POST",
+            "/api/v1/auth/config",
+            json!({
+                "dify": { "base_url": "https://api.dify.ai", "api_key": "sk-dify-test" },
+                "generic_llm": { "provider": "my-vllm", "base_url": mock_server.uri(), "api_key": "test-api-key" },
+                "teacher_settings": { "temperature": 0.7, "top_p": 0.9, "max_tokens": 2048 }
+            }),
+        ),
+        &token,
+    );
+    let save_resp = app.clone().oneshot(save_req).await.unwrap();
+    assert_eq!(save_resp.status(), StatusCode::OK);
+
+    let req = with_bearer(
+        Request::builder()
+            .method("GET")
+            .uri("/api/v1/auth/generic-llm/models")
+            .body(Body::empty())
+            .unwrap(),
+        &token,
+    );
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json_body(resp).await;
+    assert_eq!(body["data"]["models"], json!(["qwen2.5-7b"]));
+
+    // 仍被凭证引用时拒绝删除
+    let delete = |token: &str| {
+        with_bearer(
+            Request::builder()
+                .method("DELETE")
+                .uri("/api/v1/auth/llm-providers/my-vllm")
+                .body(Body::empty())
+                .unwrap(),
+            token,
+        )
+    };
+    let resp = app.clone().oneshot(delete(&token)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    // 未知 Provider 不能保存为凭证
+    let save_req = with_bearer(
+        build_json_request(
+            "POST",
+            "/api/v1/auth/config",
+            json!({
+                "dify": { "base_url": "https://api.dify.ai", "api_key": "sk-dify-test" },
+                "generic_llm": { "provider": "nope", "base_url": mock_server.uri(), "api_key": "test-api-key" },
+                "teacher_settings": { "temperature": 0.7, "top_p": 0.9, "max_tokens": 2048 }
+            }),
+        ),
+        &token,
+    );
+    let save_resp = app.clone().oneshot(save_req).await.unwrap();
+    assert_eq!(save_resp.status(), StatusCode::BAD_REQUEST);
+
+    // 切回内置 Provider 后可删除
+    let save_req = with_bearer(
+        build_json_request(
+            "POST",
+            "/api/v1/auth/config",
+            json!({
+                "dify": { "base_url": "https://api.dify.ai", "api_key": "sk-dify-test" },
+                "generic_llm": { "provider": "openai", "base_url": mock_server.uri(), "api_key": "test-api-key" },
+                "teacher_settings": { "temperature": 0.7, "top_p": 0.9, "max_tokens": 2048 }
+            }),
+        ),
+        &token,
+    );
+    assert_eq!(
+        app.clone().oneshot(save_req).await.unwrap().status(),
+        StatusCode::OK
+    );
+    let resp = app.clone().oneshot(delete(&token)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = app.clone().oneshot(delete(&token)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn test_register_login_me_success() {
     let app = setup_test_app().await;
@@ -505,6 +678,170 @@ async fn test_logout_invalidates_token() {
     assert_eq!(me_json["error"]["code"].as_str(), Some("UNAUTHORIZED"));
 }
 
+#[tokio::test]
+async fn test_list_and_revoke_sessions() {
+    let app = setup_test_app().await;
+
+    let username = "test_user_sessions";
+    let password = "TestPass123!";
+
+    let register_req = build_json_request(
+        "POST",
+        "/api/v1/auth/register",
+        json!({"username": username, "password": password}),
+    );
+    let register_resp = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(register_resp.status(), StatusCode::OK);
+    let register_json = read_json_body(register_resp).await;
+    let current_token = register_json["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string();
+
+    let addr: SocketAddr = ([10, 0, 0, 7], 44321).into();
+    let mut device_tokens = Vec::new();
+    for user_agent in ["Laptop/1.0", "Phone/2.0"] {
+        let mut req = with_connect_info(
+            build_json_request(
+                "POST",
+                "/api/v1/auth/login",
+                json!({"username": username, "password": password}),
+            ),
+            addr,
+        );
+        req.headers_mut()
+            .insert("User-Agent", user_agent.parse().unwrap());
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let json = read_json_body(resp).await;
+        device_tokens.push(
+            json["data"]["session_token"]
+                .as_str()
+                .expect("缺少 session_token")
+                .to_string(),
+        );
+    }
+
+    let request = |method: &str, uri: &str, token: &str| {
+        with_bearer(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap(),
+            token,
+        )
+    };
+
+    let resp = app
+        .clone()
+        .oneshot(request("GET", "/api/v1/auth/sessions", &current_token))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let json = read_json_body(resp).await;
+    let sessions = json["data"]["sessions"].as_array().expect("缺少 sessions");
+    assert_eq!(sessions.len(), 3);
+    assert_eq!(
+        sessions
+            .iter()
+            .filter(|s| s["current"].as_bool() == Some(true))
+            .count(),
+        1
+    );
+    let laptop = sessions
+        .iter()
+        .find(|s| s["user_agent"].as_str() == Some("Laptop/1.0"))
+        .expect("缺少 Laptop 会话");
+    assert_eq!(laptop["ip_address"].as_str(), Some("10.0.0.7"));
+    assert_eq!(laptop["current"].as_bool(), Some(false));
+    assert!(
+        sessions
+            .iter()
+            .all(|s| !s.to_string().contains(current_token.as_str()))
+    );
+    let laptop_id = laptop["id"].as_str().unwrap().to_string();
+
+    // 吊销指定会话：该会话立即失效
+    let resp = app
+        .clone()
+        .oneshot(request(
+            "DELETE",
+            &format!("/api/v1/auth/sessions/{laptop_id}"),
+            &current_token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        read_json_body(resp).await["data"]["revoked_sessions"].as_u64(),
+        Some(1)
+    );
+    let resp = app
+        .clone()
+        .oneshot(request("GET", "/api/v1/auth/me", &device_tokens[0]))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = app
+        .clone()
+        .oneshot(request(
+            "DELETE",
+            &format!("/api/v1/auth/sessions/{laptop_id}"),
+            &current_token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // 吊销全部（默认保留当前会话）
+    let resp = app
+        .clone()
+        .oneshot(request("DELETE", "/api/v1/auth/sessions", &current_token))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        read_json_body(resp).await["data"]["revoked_sessions"].as_u64(),
+        Some(1)
+    );
+    let resp = app
+        .clone()
+        .oneshot(request("GET", "/api/v1/auth/me", &device_tokens[1]))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = app
+        .clone()
+        .oneshot(request("GET", "/api/v1/auth/me", &current_token))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // include_current=true 时当前会话一并吊销
+    let resp = app
+        .clone()
+        .oneshot(request(
+            "DELETE",
+            "/api/v1/auth/sessions?include_current=true",
+            &current_token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        read_json_body(resp).await["data"]["revoked_sessions"].as_u64(),
+        Some(1)
+    );
+    let resp = app
+        .clone()
+        .oneshot(request("GET", "/api/v1/auth/me", &current_token))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
 #[tokio::test]
 async fn test_login_attempt_protection_returns_generic_error() {
     let app = setup_test_app().await;
@@ -558,3 +895,508 @@ async fn test_login_attempt_protection_returns_generic_error() {
         Some("用户名或密码错误")
     );
 }
+
+#[tokio::test]
+async fn test_named_credentials_crud_and_default_switching() {
+    let app = setup_test_app().await;
+
+    let register_req = build_json_request(
+        "POST",
+        "/api/v1/auth/register",
+        json!({"username": "test_user_named_credentials", "password": "TestPass123!"}),
+    );
+    let register_resp = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(register_resp.status(), StatusCode::OK);
+    let token = read_json_body(register_resp).await["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string();
+
+    // 旧版保存配置写入默认凭证
+    let save_resp = app
+        .clone()
+        .oneshot(with_bearer(
+            build_json_request(
+                "POST",
+                "/api/v1/auth/config",
+                json!({
+                    "dify": { "base_url": "https://api.dify.ai", "api_key": "sk-dify-app-a" },
+                    "generic_llm": { "provider": "openai", "base_url": "https://api.openai.com", "api_key": "sk-openai-test" },
+                    "teacher_settings": { "temperature": 0.7, "top_p": 0.9, "max_tokens": 2048 }
+                }),
+            ),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(save_resp.status(), StatusCode::OK);
+
+    let create = |body: Value| {
+        with_bearer(
+            build_json_request("POST", "/api/v1/auth/credentials", body),
+            &token,
+        )
+    };
+    let resp = app
+        .clone()
+        .oneshot(create(json!({
+            "credential_type": "dify",
+            "name": "app-b",
+            "base_url": "https://dify.example.com",
+            "api_key": "sk-dify-app-b-123456"
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let app_b = read_json_body(resp).await["data"].clone();
+    let app_b_id = app_b["id"].as_str().unwrap().to_string();
+    assert_eq!(app_b["is_default"], json!(false));
+    assert!(app_b.get("api_key").is_none());
+    assert_ne!(app_b["masked_api_key"], json!("sk-dify-app-b-123456"));
+
+    // 同类型重名冲突
+    let resp = app
+        .clone()
+        .oneshot(create(json!({
+            "credential_type": "dify",
+            "name": "app-b",
+            "base_url": "https://dify.example.com",
+            "api_key": "sk-dify-other"
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    // generic_llm 的 provider 必须有效
+    let resp = app
+        .clone()
+        .oneshot(create(json!({
+            "credential_type": "generic_llm",
+            "name": "billing-b",
+            "provider": "no-such-provider",
+            "base_url": "https://api.openai.com",
+            "api_key": "sk-openai-b"
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let list_dify = || {
+        with_bearer(
+            Request::builder()
+                .method("GET")
+                .uri("/api/v1/auth/credentials?credential_type=dify")
+                .body(Body::empty())
+                .unwrap(),
+            &token,
+        )
+    };
+    let body = read_json_body(app.clone().oneshot(list_dify()).await.unwrap()).await;
+    let creds = body["data"]["credentials"].as_array().unwrap();
+    assert_eq!(creds.len(), 2);
+    assert_eq!(creds[0]["name"], json!("default"));
+    assert_eq!(creds[0]["is_default"], json!(true));
+    let default_id = creds[0]["id"].as_str().unwrap().to_string();
+
+    // 切换默认凭证
+    let resp = app
+        .clone()
+        .oneshot(with_bearer(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/v1/auth/credentials/{app_b_id}/default"))
+                .body(Body::empty())
+                .unwrap(),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json_body(app.clone().oneshot(list_dify()).await.unwrap()).await;
+    let creds = body["data"]["credentials"].as_array().unwrap();
+    assert_eq!(creds[0]["id"], json!(app_b_id));
+    assert_eq!(creds[0]["is_default"], json!(true));
+
+    // 更新名称（不提供 api_key 时保留原值）
+    let resp = app
+        .clone()
+        .oneshot(with_bearer(
+            build_json_request(
+                "PUT",
+                &format!("/api/v1/auth/credentials/{default_id}"),
+                json!({ "name": "app-a", "base_url": "https://api.dify.ai" }),
+            ),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let updated = read_json_body(resp).await["data"].clone();
+    assert_eq!(updated["name"], json!("app-a"));
+    assert!(updated["masked_api_key"].is_string());
+
+    // 删除默认凭证后剩余凭证被提升为默认
+    let resp = app
+        .clone()
+        .oneshot(with_bearer(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/v1/auth/credentials/{app_b_id}"))
+                .body(Body::empty())
+                .unwrap(),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json_body(app.clone().oneshot(list_dify()).await.unwrap()).await;
+    let creds = body["data"]["credentials"].as_array().unwrap();
+    assert_eq!(creds.len(), 1);
+    assert_eq!(creds[0]["id"], json!(default_id));
+    assert_eq!(creds[0]["is_default"], json!(true));
+
+    let resp = app
+        .clone()
+        .oneshot(with_bearer(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/v1/auth/credentials/{app_b_id}"))
+                .body(Body::empty())
+                .unwrap(),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_change_password_reencrypts_credentials_and_revokes_other_sessions() {
+    let (app, db) = setup_test_app_with_db().await;
+    let addr: SocketAddr = "127.0.0.1:12345".parse().unwrap();
+
+    let username = "test_user_change_password";
+    let old_password = "TestPass123!";
+    let new_password = "NewPass456!";
+
+    let register_req = build_json_request(
+        "POST",
+        "/api/v1/auth/register",
+        json!({"username": username, "password": old_password}),
+    );
+    let register_resp = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(register_resp.status(), StatusCode::OK);
+    let register_json = read_json_body(register_resp).await;
+    let token = register_json["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string();
+    let user_id = register_json["data"]["user"]["id"]
+        .as_str()
+        .expect("缺少 user.id")
+        .to_string();
+
+    let login_req = with_connect_info(
+        build_json_request(
+            "POST",
+            "/api/v1/auth/login",
+            json!({"username": username, "password": old_password}),
+        ),
+        addr,
+    );
+    let login_resp = app.clone().oneshot(login_req).await.unwrap();
+    assert_eq!(login_resp.status(), StatusCode::OK);
+    let other_token = read_json_body(login_resp).await["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string();
+
+    // 登录密码派生加密的凭证
+    let save_req = with_bearer(
+        build_json_request(
+            "POST",
+            "/api/v1/auth/config",
+            json!({
+                "dify": { "base_url": "https://api.dify.ai", "api_key": "sk-dify-test" },
+                "generic_llm": { "provider": "siliconflow", "base_url": "https://api.siliconflow.cn", "api_key": "sk-llm-test" },
+                "teacher_settings": { "temperature": 0.7, "top_p": 0.9, "max_tokens": 2048 }
+            }),
+        ),
+        &token,
+    );
+    let save_resp = app.clone().oneshot(save_req).await.unwrap();
+    assert_eq!(save_resp.status(), StatusCode::OK);
+
+    // legacy MASTER_PASSWORD 加密的凭证
+    let legacy = ApiKeyManager::new(None)
+        .encrypt(TEST_MASTER_PASSWORD.as_bytes(), "sk-legacy")
+        .expect("加密 legacy 凭证失败");
+    sqlx::query(
+        r#"
+        INSERT INTO api_credentials (
+            id, user_id, credential_type, name, is_default, provider, base_url,
+            encrypted_api_key, nonce, salt, created_at, updated_at
+        )
+        VALUES (?1, ?2, 'generic_llm', 'legacy', 0, 'siliconflow', ?3, ?4, ?5, ?6, 1, 1)
+        "#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&user_id)
+    .bind("https://legacy.example")
+    .bind(legacy.ciphertext)
+    .bind(legacy.nonce)
+    .bind(legacy.salt)
+    .execute(&db)
+    .await
+    .expect("插入 legacy 凭证失败");
+
+    let wrong_req = with_bearer(
+        build_json_request(
+            "POST",
+            "/api/v1/auth/password",
+            json!({"old_password": "wrong_password", "new_password": new_password}),
+        ),
+        &token,
+    );
+    let wrong_resp = app.clone().oneshot(wrong_req).await.unwrap();
+    assert_eq!(wrong_resp.status(), StatusCode::BAD_REQUEST);
+
+    let change_req = with_bearer(
+        build_json_request(
+            "POST",
+            "/api/v1/auth/password",
+            json!({"old_password": old_password, "new_password": new_password}),
+        ),
+        &token,
+    );
+    let change_resp = app.clone().oneshot(change_req).await.unwrap();
+    assert_eq!(change_resp.status(), StatusCode::OK);
+    let change_json = read_json_body(change_resp).await;
+    // 已由数据密钥加密的凭证无需重新加密，仅迁移 legacy 凭证
+    assert_eq!(change_json["data"]["reencrypted_credentials"], 1);
+    assert_eq!(change_json["data"]["migrated_legacy_credentials"], 1);
+    assert_eq!(change_json["data"]["revoked_sessions"], 1);
+
+    // 全部凭证均为信封加密（不再依赖登录密码或 MASTER_PASSWORD）
+    let salts: Vec<(Vec<u8>,)> =
+        sqlx::query_as("SELECT salt FROM api_credentials WHERE user_id = ?1")
+            .bind(&user_id)
+            .fetch_all(&db)
+            .await
+            .expect("查询凭证失败");
+    assert_eq!(salts.len(), 3);
+    assert!(salts.iter().all(|(salt,)| salt.is_empty()));
+
+    let me = |token: String| {
+        with_bearer(
+            Request::builder()
+                .method("GET")
+                .uri("/api/v1/auth/me")
+                .body(Body::empty())
+                .unwrap(),
+            &token,
+        )
+    };
+    let current_resp = app.clone().oneshot(me(token.clone())).await.unwrap();
+    assert_eq!(current_resp.status(), StatusCode::OK);
+    let other_resp = app.clone().oneshot(me(other_token)).await.unwrap();
+    assert_eq!(other_resp.status(), StatusCode::UNAUTHORIZED);
+
+    let old_login = with_connect_info(
+        build_json_request(
+            "POST",
+            "/api/v1/auth/login",
+            json!({"username": username, "password": old_password}),
+        ),
+        addr,
+    );
+    assert_eq!(
+        app.clone().oneshot(old_login).await.unwrap().status(),
+        StatusCode::UNAUTHORIZED
+    );
+    let new_login = with_connect_info(
+        build_json_request(
+            "POST",
+            "/api/v1/auth/login",
+            json!({"username": username, "password": new_password}),
+        ),
+        addr,
+    );
+    let new_login_resp = app.clone().oneshot(new_login).await.unwrap();
+    assert_eq!(new_login_resp.status(), StatusCode::OK);
+    let new_token = read_json_body(new_login_resp).await["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string();
+
+    // 新密码解锁同一数据密钥，全部凭证可解密
+    let list_resp = app
+        .clone()
+        .oneshot(with_bearer(
+            Request::builder()
+                .method("GET")
+                .uri("/api/v1/auth/credentials")
+                .body(Body::empty())
+                .unwrap(),
+            &new_token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(list_resp.status(), StatusCode::OK);
+    let credentials = read_json_body(list_resp).await["data"]["credentials"]
+        .as_array()
+        .expect("凭证列表")
+        .clone();
+    assert_eq!(credentials.len(), 3);
+    assert!(
+        credentials
+            .iter()
+            .all(|c| c["masked_api_key"].as_str().is_some())
+    );
+}
+
+#[tokio::test]
+async fn test_change_password_old_password_attempts_are_throttled() {
+    let app = setup_test_app().await;
+    let old_password = "OldPass123!";
+    let new_password = "NewPass456!";
+
+    let register_req = build_json_request(
+        "POST",
+        "/api/v1/auth/register",
+        json!({"username": "test_change_password_throttle", "password": old_password}),
+    );
+    let register_resp = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(register_resp.status(), StatusCode::OK);
+    let token = read_json_body(register_resp).await["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string();
+
+    let change = |old: &str| {
+        with_bearer(
+            build_json_request(
+                "POST",
+                "/api/v1/auth/password",
+                json!({"old_password": old, "new_password": new_password}),
+            ),
+            &token,
+        )
+    };
+
+    for _ in 0..5 {
+        let resp = app.clone().oneshot(change("wrong_password")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    // 冷却期内即使原密码正确也返回 429 + Retry-After，且密码未被修改
+    let resp = app.clone().oneshot(change(old_password)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = resp
+        .headers()
+        .get("retry-after")
+        .expect("缺少 Retry-After")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+    let body = read_json_body(resp).await;
+    assert_eq!(body["error"]["code"], "RATE_LIMITED");
+
+    let addr: SocketAddr = ([127, 0, 0, 1], 33333).into();
+    let login_req = with_connect_info(
+        build_json_request(
+            "POST",
+            "/api/v1/auth/login",
+            json!({"username": "test_change_password_throttle", "password": old_password}),
+        ),
+        addr,
+    );
+    assert_eq!(
+        app.clone().oneshot(login_req).await.unwrap().status(),
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn test_server_kek_unlocks_credentials_without_login_session() {
+    let kek_hex = "ab".repeat(32);
+    let (app, _db, session_store) =
+        setup_test_app_with_server_kek(Some(ServerKek::from_hex(&kek_hex).unwrap())).await;
+
+    let register_req = build_json_request(
+        "POST",
+        "/api/v1/auth/register",
+        json!({"username": "test_user_server_kek", "password": "TestPass123!"}),
+    );
+    let register_resp = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(register_resp.status(), StatusCode::OK);
+    let register_json = read_json_body(register_resp).await;
+    let token = register_json["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string();
+    let user_id = register_json["data"]["user"]["id"]
+        .as_str()
+        .expect("缺少 user.id")
+        .to_string();
+
+    let save_req = with_bearer(
+        build_json_request(
+            "POST",
+            "/api/v1/auth/config",
+            json!({
+                "dify": { "base_url": "https://api.dify.ai", "api_key": "sk-dify-test" },
+                "generic_llm": { "provider": "siliconflow", "base_url": "https://api.siliconflow.cn", "api_key": "sk-llm-test" },
+                "teacher_settings": { "temperature": 0.7, "top_p": 0.9, "max_tokens": 2048 }
+            }),
+        ),
+        &token,
+    );
+    assert_eq!(
+        app.clone().oneshot(save_req).await.unwrap().status(),
+        StatusCode::OK
+    );
+
+    // 无解锁上下文的会话（如后台任务）经服务端 KEK 解锁数据密钥
+    let unattended_token = session_store
+        .create_session(user_id, None)
+        .await
+        .expect("创建会话失败");
+    let get_config = |token: &str| {
+        with_bearer(
+            Request::builder()
+                .method("GET")
+                .uri("/api/v1/auth/config")
+                .body(Body::empty())
+                .unwrap(),
+            token,
+        )
+    };
+    let resp = app
+        .clone()
+        .oneshot(get_config(&unattended_token))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let json = read_json_body(resp).await;
+    assert!(json["data"]["masked_dify_key"].as_str().is_some());
+    assert!(json["data"]["masked_generic_llm_key"].as_str().is_some());
+
+    // 未配置 KEK 时，同样的会话无法访问凭证
+    let (app_without_kek, db_without_kek, sessions_without_kek) =
+        setup_test_app_with_server_kek(None).await;
+    sqlx::query(
+        "INSERT INTO users (id, username, password_hash, created_at, updated_at) VALUES ('u-no-kek', 'u-no-kek', 'h', 1, 1)",
+    )
+    .execute(&db_without_kek)
+    .await
+    .expect("插入用户失败");
+    let token = sessions_without_kek
+        .create_session("u-no-kek".to_string(), None)
+        .await
+        .expect("创建会话失败");
+    let resp = app_without_kek.oneshot(get_config(&token)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,15 @@
+//! 集成测试共享夹具
+
+use prompt_faster::shared::config::AppConfig;
+
+/// 集成测试基础配置：内存数据库、随机端口，其余沿用 `AppConfig::default()`
+///
+/// 需要覆盖的字段请使用结构体更新语法：`AppConfig { is_dev: false, ..test_app_config() }`
+pub fn test_app_config() -> AppConfig {
+    AppConfig {
+        database_url: "sqlite::memory:".to_string(),
+        server_port: 0,
+        cors_origins: vec![],
+        ..AppConfig::default()
+    }
+}
@@ -1,10 +1,11 @@
 //! 连接测试集成测试
 //! 使用 wiremock 模拟外部 API 响应
 
+use prompt_faster::domain::models::{LlmApiFormat, LlmAuthStyle, LlmProviderSpec};
 use prompt_faster::infra::external::dify_client;
 use prompt_faster::infra::external::http_client::create_http_client;
 use prompt_faster::infra::external::llm_client;
-use wiremock::matchers::{header, method, path};
+use wiremock::matchers::{body_partial_json, header, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 /// 测试 Dify 连接成功（200 响应）
@@ -221,6 +222,12 @@ async fn test_llm_connection_invalid_provider() {
 fn test_validate_provider() {
     assert!(llm_client::validate_provider("siliconflow").is_ok());
     assert!(llm_client::validate_provider("modelscope").is_ok());
+    for provider in llm_client::ALLOWED_PROVIDERS {
+        assert!(
+            llm_client::validate_provider(provider).is_ok(),
+            "{provider}"
+        );
+    }
     assert!(llm_client::validate_provider("invalid").is_err());
     assert!(llm_client::validate_provider("").is_err());
 }
@@ -303,3 +310,145 @@ async fn test_upstream_error_message_does_not_include_body() {
         _ => panic!("预期 UpstreamError"),
     }
 }
+
+/// 测试 Azure 风格 Provider：api-key 头 + 带 api-version 的模型列表端点
+#[tokio::test]
+async fn test_llm_connection_azure_provider_uses_api_key_header() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/openai/models"))
+        .and(query_param("api-version", "2024-10-21"))
+        .and(header("api-key", "azure-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{ "id": "gpt-4o" }]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_http_client().expect("创建 HTTP 客户端失败");
+    let result = llm_client::test_connection(
+        &client,
+        &mock_server.uri(),
+        "azure-key",
+        "azure_openai",
+        "test-correlation-id",
+    )
+    .await
+    .expect("Azure 连接测试应成功");
+    assert_eq!(result.models, Some(vec!["gpt-4o".to_string()]));
+}
+
+/// 测试 Anthropic Provider：x-api-key + anthropic-version 头，Messages 协议
+#[tokio::test]
+async fn test_llm_anthropic_provider_lists_models_and_chats() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/v1/models"))
+        .and(header("x-api-key", "ant-key"))
+        .and(header("anthropic-version", "2023-06-01"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{ "id": "claude-sonnet" }]
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(header("x-api-key", "ant-key"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "claude-sonnet",
+            "system": "SYS",
+            "messages": [{ "role": "user", "content": "hi" }]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "content": [
+                { "type": "text", "text": "hello" },
+                { "type": "text", "text": " there" }
+            ]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = create_http_client().expect("创建 HTTP 客户端失败");
+    let models = llm_client::list_models(
+        &client,
+        &mock_server.uri(),
+        "ant-key",
+        "anthropic",
+        "test-correlation-id",
+    )
+    .await
+    .expect("Anthropic 模型列表应成功");
+    assert_eq!(models, vec!["claude-sonnet".to_string()]);
+
+    let spec = llm_client::resolve_builtin_provider("anthropic").unwrap();
+    let req = llm_client::ChatCompletionsRequest {
+        model: "claude-sonnet".to_string(),
+        messages: vec![
+            llm_client::ChatMessage {
+                role: "system".to_string(),
+                content: "SYS".to_string(),
+            },
+            llm_client::ChatMessage {
+                role: "user".to_string(),
+                content: "hi".to_string(),
+            },
+        ],
+    };
+    let output = llm_client::chat_completions_with_provider(
+        &client,
+        &mock_server.uri(),
+        "ant-key",
+        &spec,
+        "test-correlation-id",
+        &req,
+    )
+    .await
+    .expect("Anthropic messages 调用应成功");
+    assert_eq!(output, "hello there");
+}
+
+/// 测试用户自定义 Provider（自建 OpenAI 兼容服务，自定义路径 + 无鉴权头）
+#[tokio::test]
+async fn test_llm_connection_custom_provider_spec() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{ "id": "qwen2.5:7b" }]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let spec = LlmProviderSpec {
+        id: "my-ollama".to_string(),
+        display_name: "My Ollama".to_string(),
+        default_base_url: None,
+        auth_style: LlmAuthStyle::None,
+        models_path: "/api/v1/models".to_string(),
+        chat_path: "/api/v1/chat/completions".to_string(),
+        api_format: LlmApiFormat::OpenaiChat,
+        builtin: false,
+    };
+    let client = create_http_client().expect("创建 HTTP 客户端失败");
+    let result = llm_client::test_connection_with_provider(
+        &client,
+        &mock_server.uri(),
+        "unused",
+        &spec,
+        "test-correlation-id",
+    )
+    .await
+    .expect("自定义 Provider 连接测试应成功");
+    assert_eq!(result.models, Some(vec!["qwen2.5:7b".to_string()]));
+
+    let requests = mock_server.received_requests().await.unwrap();
+    assert!(
+        requests
+            .iter()
+            .all(|r| !r.headers.contains_key("authorization")),
+        "auth_style=none 不应发送 Authorization 头"
+    );
+}
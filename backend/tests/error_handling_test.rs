@@ -7,6 +7,8 @@
 //! 4. 验证 details 仅在开发环境出现
 //! 5. 验证 HTTP 状态码映射正确
 
+mod common;
+
 use axum::Router;
 use axum::body::Body;
 use axum::extract::ConnectInfo;
@@ -19,13 +21,14 @@ use std::sync::Arc;
 use tower::ServiceExt;
 
 use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
-use prompt_faster::api::middleware::{LoginAttemptStore, SessionStore, auth_middleware};
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
 use prompt_faster::api::routes::{auth, health, user_auth, workspaces};
 use prompt_faster::api::state::AppState;
 use prompt_faster::infra::db::pool::create_pool;
 use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
 use prompt_faster::infra::external::http_client::create_http_client;
-use prompt_faster::shared::config::AppConfig;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -42,25 +45,10 @@ async fn setup_test_app() -> Router {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-
-        checkpoint_cache_limit: 10,
-
-        checkpoint_memory_alert_threshold: 10,
-    });
+    let config = Arc::new(common::test_app_config());
     let api_key_manager = Arc::new(ApiKeyManager::new(Some(TEST_MASTER_PASSWORD.to_string())));
 
-    let session_store = SessionStore::new(24);
+    let session_store = SessionStore::new(db.clone(), 24);
     let login_attempt_store = LoginAttemptStore::default();
 
     let state = AppState {
@@ -70,6 +58,7 @@ async fn setup_test_app() -> Router {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: RateLimiter::default(),
     };
 
     let session_store_for_middleware = state.session_store.clone();
@@ -0,0 +1,33 @@
+//! 出站网络配置无效时执行目标必须创建失败（不回退为无 SSRF 防护的默认客户端）
+//!
+//! 环境变量为进程级全局状态：本文件仅包含一个测试，独立进程运行。
+
+use prompt_faster::core::execution_target::{
+    DifyExecutionTarget, DirectApiExecutionTarget, HttpExecutionTarget, create_execution_target,
+};
+use prompt_faster::domain::models::ExecutionTargetType;
+
+#[test]
+fn execution_targets_fail_closed_on_invalid_outbound_config() {
+    // Safety: 本测试二进制内没有其他测试并发读写环境变量
+    unsafe {
+        std::env::set_var("OUTBOUND_CA_BUNDLE", "/nonexistent/prompt-faster-ca.pem");
+    }
+
+    assert!(DifyExecutionTarget::new().is_err());
+    assert!(DirectApiExecutionTarget::new().is_err());
+    assert!(HttpExecutionTarget::new().is_err());
+    for target_type in [
+        ExecutionTargetType::Dify,
+        ExecutionTargetType::Generic,
+        ExecutionTargetType::Http,
+    ] {
+        assert!(
+            create_execution_target(target_type).is_err(),
+            "{target_type:?} 不应回退为默认客户端"
+        );
+    }
+
+    // 不依赖出站 HTTP 的执行目标不受影响
+    assert!(create_execution_target(ExecutionTargetType::Example).is_ok());
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use prompt_faster::core::iteration_engine::shutdown::{
+    StartupRecoveryPolicy, global_shutdown_coordinator, resume_interrupted_tasks,
+};
+use prompt_faster::core::optimization_engine::checkpoint_pause_if_requested;
+use prompt_faster::domain::models::{
+    ExecutionTargetType, IterationState, OptimizationTaskMode, OptimizationTaskStatus, RuleSystem,
+    TaskReference, TestCase,
+};
+use prompt_faster::domain::types::{
+    ExecutionTargetConfig, OptimizationConfig, OptimizationContext, RunControlState,
+};
+use prompt_faster::infra::db::pool::{create_pool, init_global_db_pool};
+use prompt_faster::infra::db::repositories::{
+    CheckpointRepo, CreateOptimizationTaskInput, OptimizationTaskRepo, TestSetRepo, WorkspaceRepo,
+};
+use prompt_faster::shared::time::now_millis;
+use sqlx::SqlitePool;
+
+/// 创建运行中的任务（含测试集），返回任务 ID
+async fn seed_running_task(db: &SqlitePool, user_id: &str) -> String {
+    let now = now_millis();
+    sqlx::query(
+        r#"
+        INSERT INTO users (id, username, password_hash, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        "#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .bind("hashed")
+    .bind(now)
+    .bind(now)
+    .execute(db)
+    .await
+    .expect("insert user");
+
+    let workspace = WorkspaceRepo::create(db, user_id, "ws-test", None)
+        .await
+        .expect("创建工作区失败");
+    let case = TestCase {
+        id: "case-1".to_string(),
+        input: HashMap::new(),
+        reference: TaskReference::Exact {
+            expected: "ok".to_string(),
+        },
+        split: None,
+        metadata: None,
+    };
+    let test_set = TestSetRepo::create(db, &workspace.id, "ts", None, &[case], None, None)
+        .await
+        .expect("创建测试集失败");
+    let created = OptimizationTaskRepo::create_scoped(
+        db,
+        CreateOptimizationTaskInput {
+            user_id,
+            workspace_id: &workspace.id,
+            name: "ws-task",
+            description: None,
+            goal: "goal",
+            execution_target_type: ExecutionTargetType::Example,
+            task_mode: OptimizationTaskMode::Fixed,
+            test_set_ids: std::slice::from_ref(&test_set.id),
+            teacher_prompt_version_id: None,
+        },
+    )
+    .await
+    .expect("创建任务失败");
+    OptimizationTaskRepo::update_status(db, &created.task.id, OptimizationTaskStatus::Running)
+        .await
+        .expect("更新状态失败");
+    created.task.id
+}
+
+fn build_context(task_id: &str, user_id: &str) -> OptimizationContext {
+    let mut extensions = HashMap::new();
+    extensions.insert("user_id".to_string(), serde_json::json!(user_id));
+    OptimizationContext {
+        task_id: task_id.to_string(),
+        execution_target_config: ExecutionTargetConfig::default(),
+        current_prompt: "prompt".to_string(),
+        rule_system: RuleSystem {
+            rules: vec![],
+            conflict_resolution_log: vec![],
+            merge_log: vec![],
+            coverage_map: HashMap::new(),
+            version: 1,
+        },
+        iteration: 2,
+        state: IterationState::RunningTests,
+        run_control_state: RunControlState::Running,
+        test_cases: vec![],
+        config: OptimizationConfig::default(),
+        checkpoints: vec![],
+        extensions,
+    }
+}
+
+#[tokio::test]
+async fn shutdown_parks_running_task_at_safe_point_and_startup_resumes_it() {
+    let pool = create_pool("sqlite::memory:")
+        .await
+        .expect("创建测试数据库失败");
+    sqlx::migrate!()
+        .run(&pool)
+        .await
+        .expect("运行 migrations 失败");
+    init_global_db_pool(pool.clone());
+
+    let user_id = "user-graceful-shutdown";
+    let task_id = seed_running_task(&pool, user_id).await;
+    let task_id = task_id.as_str();
+
+    // 模拟运行中的引擎：每层结束后检查暂停请求
+    let coordinator = global_shutdown_coordinator();
+    let running = coordinator.register(task_id);
+    let mut ctx = build_context(task_id, user_id);
+    tokio::spawn(async move {
+        let _running = running;
+        loop {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            checkpoint_pause_if_requested(&mut ctx)
+                .await
+                .expect("safe point");
+        }
+    });
+
+    let report = coordinator.shutdown(Duration::from_secs(5)).await;
+    assert_eq!(report.signalled, 1);
+    assert!(report.timed_out.is_empty(), "{report:?}");
+
+    let checkpoints = CheckpointRepo::list_checkpoints_by_task(&pool, task_id, 10)
+        .await
+        .expect("查询 checkpoint 失败");
+    assert_eq!(checkpoints.len(), 1);
+    assert_eq!(checkpoints[0].run_control_state, RunControlState::Paused);
+
+    let interrupted = OptimizationTaskRepo::list_interrupted_by_shutdown(&pool)
+        .await
+        .expect("查询停机暂停任务失败");
+    assert_eq!(
+        interrupted,
+        vec![(task_id.to_string(), user_id.to_string())]
+    );
+
+    // 仅列出：保留标记，任务出现在未完成任务列表中
+    let resumed = resume_interrupted_tasks(&pool, StartupRecoveryPolicy::ListOnly)
+        .await
+        .expect("startup list");
+    assert_eq!(resumed, 0);
+    let unfinished = OptimizationTaskRepo::find_unfinished_with_checkpoints(&pool, user_id)
+        .await
+        .expect("查询未完成任务失败");
+    assert_eq!(unfinished.len(), 1);
+    assert_eq!(unfinished[0].task_id, task_id);
+
+    // 自动恢复：通过 recover_task 恢复并清除标记
+    let resumed = resume_interrupted_tasks(&pool, StartupRecoveryPolicy::AutoResume)
+        .await
+        .expect("startup resume");
+    assert_eq!(resumed, 1);
+    assert!(
+        OptimizationTaskRepo::list_interrupted_by_shutdown(&pool)
+            .await
+            .expect("查询停机暂停任务失败")
+            .is_empty()
+    );
+}
@@ -1,3 +1,5 @@
+mod common;
+
 use axum::Router;
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
@@ -8,7 +10,9 @@ use std::sync::Arc;
 use tower::ServiceExt;
 
 use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
-use prompt_faster::api::middleware::{LoginAttemptStore, SessionStore, auth_middleware};
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
 use prompt_faster::api::routes::{auth, history, user_auth, workspaces};
 use prompt_faster::api::state::AppState;
 use prompt_faster::core::iteration_engine::checkpoint::compute_checksum;
@@ -23,7 +27,6 @@ use prompt_faster::infra::db::pool::create_pool;
 use prompt_faster::infra::db::repositories::{CheckpointRepo, HistoryEventRepo};
 use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
 use prompt_faster::infra::external::http_client::create_http_client;
-use prompt_faster::shared::config::AppConfig;
 use prompt_faster::shared::time::now_millis;
 
 const TEST_MASTER_PASSWORD: &str = "test_master_password_for_integration";
@@ -39,23 +42,10 @@ async fn setup_test_app_with_db() -> (Router, sqlx::SqlitePool) {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-        checkpoint_cache_limit: 10,
-        checkpoint_memory_alert_threshold: 10,
-    });
+    let config = Arc::new(common::test_app_config());
     let api_key_manager = Arc::new(ApiKeyManager::new(Some(TEST_MASTER_PASSWORD.to_string())));
 
-    let session_store = SessionStore::new(24);
+    let session_store = SessionStore::new(db.clone(), 24);
     let login_attempt_store = LoginAttemptStore::default();
 
     let state = AppState {
@@ -65,6 +55,7 @@ async fn setup_test_app_with_db() -> (Router, sqlx::SqlitePool) {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: RateLimiter::default(),
     };
 
     let session_store_for_middleware = state.session_store.clone();
@@ -560,9 +551,9 @@ async fn test_history_export_contains_sections() {
     let body = read_json_body(resp).await;
     let data = &body["data"];
     assert_eq!(data["task"]["id"].as_str(), Some(task_id.as_str()));
-    assert!(data["iterations"].as_array().unwrap().len() >= 1);
-    assert!(data["checkpoints"].as_array().unwrap().len() >= 1);
-    assert!(data["events"].as_array().unwrap().len() >= 1);
+    assert!(!data["iterations"].as_array().unwrap().is_empty());
+    assert!(!data["checkpoints"].as_array().unwrap().is_empty());
+    assert!(!data["events"].as_array().unwrap().is_empty());
     assert!(data["branches"].as_array().is_some());
     assert!(data["exportedAt"].is_string());
 }
@@ -1,3 +1,5 @@
+mod common;
+
 use axum::Router;
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
@@ -8,13 +10,15 @@ use std::sync::Arc;
 use tower::ServiceExt;
 
 use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
-use prompt_faster::api::middleware::{LoginAttemptStore, SessionStore, auth_middleware};
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
 use prompt_faster::api::routes::{auth, health, iterations, user_auth, workspaces};
 use prompt_faster::api::state::AppState;
 use prompt_faster::infra::db::pool::create_pool;
+use prompt_faster::infra::db::repositories::IterationRepo;
 use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
 use prompt_faster::infra::external::http_client::create_http_client;
-use prompt_faster::shared::config::AppConfig;
 
 const TEST_MASTER_PASSWORD: &str = "test_master_password_for_integration";
 
@@ -29,25 +33,10 @@ async fn setup_test_app_with_db() -> (Router, sqlx::SqlitePool) {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-
-        checkpoint_cache_limit: 10,
-
-        checkpoint_memory_alert_threshold: 10,
-    });
+    let config = Arc::new(common::test_app_config());
     let api_key_manager = Arc::new(ApiKeyManager::new(Some(TEST_MASTER_PASSWORD.to_string())));
 
-    let session_store = SessionStore::new(24);
+    let session_store = SessionStore::new(db.clone(), 24);
     let login_attempt_store = LoginAttemptStore::default();
 
     let state = AppState {
@@ -57,6 +46,7 @@ async fn setup_test_app_with_db() -> (Router, sqlx::SqlitePool) {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: RateLimiter::default(),
     };
 
     let session_store_for_middleware = state.session_store.clone();
@@ -370,3 +360,39 @@ async fn test_list_returns_round_desc() {
     assert_eq!(data[0]["round"], 2);
     assert_eq!(data[1]["round"], 1);
 }
+
+#[tokio::test]
+async fn test_list_includes_effective_concurrency() {
+    let (app, db) = setup_test_app_with_db().await;
+    let token = register_user(&app, "iter_list_concurrency", "TestPass123!").await;
+    let workspace_id = create_workspace(&app, &token).await;
+    let test_set_id =
+        create_test_set_with_cases(&app, &workspace_id, &token, "ts", sample_exact_cases_json())
+            .await;
+    let task_id = create_optimization_task(&app, &workspace_id, &token, test_set_id).await;
+
+    insert_iteration(&db, &task_id, "iter-1", 1).await;
+    insert_iteration(&db, &task_id, "iter-2", 2).await;
+    IterationRepo::update_effective_concurrency_for_round(&db, &task_id, 2, 3)
+        .await
+        .expect("写入有效并发失败");
+    assert!(
+        IterationRepo::update_effective_concurrency_for_round(&db, &task_id, 9, 3)
+            .await
+            .is_err(),
+        "不存在的轮次应返回 NotFound"
+    );
+
+    let req = with_bearer(
+        build_empty_request("GET", &format!("/api/v1/tasks/{}/iterations", task_id)),
+        &token,
+    );
+
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json_body(resp).await;
+    let data = body["data"].as_array().expect("data 不是数组");
+    assert_eq!(data[0]["effectiveConcurrency"], 3);
+    // 串行执行 / 未记录的轮次不返回该字段
+    assert!(data[1].get("effectiveConcurrency").is_none());
+}
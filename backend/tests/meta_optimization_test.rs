@@ -1,3 +1,8 @@
+// env_lock 有意跨 await 持有：用于串行化进程级环境变量修改。
+#![allow(clippy::await_holding_lock)]
+
+mod common;
+
 use axum::Router;
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
@@ -8,7 +13,9 @@ use std::sync::Arc;
 use tower::ServiceExt;
 
 use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
-use prompt_faster::api::middleware::{LoginAttemptStore, SessionStore, auth_middleware};
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
 use prompt_faster::api::routes::{auth, health, meta_optimization, user_auth};
 use prompt_faster::api::state::AppState;
 use prompt_faster::domain::models::{
@@ -21,7 +28,6 @@ use prompt_faster::infra::db::repositories::{
 };
 use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
 use prompt_faster::infra::external::http_client::create_http_client;
-use prompt_faster::shared::config::AppConfig;
 use std::sync::{Mutex, OnceLock};
 
 const TEST_MASTER_PASSWORD: &str = "test_master_password_for_integration";
@@ -49,23 +55,10 @@ async fn setup_test_app_with_db() -> (Router, sqlx::SqlitePool) {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-        checkpoint_cache_limit: 10,
-        checkpoint_memory_alert_threshold: 10,
-    });
+    let config = Arc::new(common::test_app_config());
     let api_key_manager = Arc::new(ApiKeyManager::new(Some(TEST_MASTER_PASSWORD.to_string())));
 
-    let session_store = SessionStore::new(24);
+    let session_store = SessionStore::new(db.clone(), 24);
     let login_attempt_store = LoginAttemptStore::default();
 
     let state = AppState {
@@ -75,6 +68,7 @@ async fn setup_test_app_with_db() -> (Router, sqlx::SqlitePool) {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: RateLimiter::default(),
     };
 
     let session_store_for_middleware = state.session_store.clone();
@@ -405,8 +399,8 @@ async fn test_validate_prompt_returns_errors() {
     let empty_resp = app.clone().oneshot(empty_req).await.unwrap();
     assert_eq!(empty_resp.status(), StatusCode::OK);
     let empty_json = read_json_body(empty_resp).await;
-    assert_eq!(empty_json["data"]["isValid"].as_bool().unwrap(), false);
-    assert!(empty_json["data"]["errors"].as_array().unwrap().len() >= 1);
+    assert!(!empty_json["data"]["isValid"].as_bool().unwrap());
+    assert!(!empty_json["data"]["errors"].as_array().unwrap().is_empty());
 
     let long_content = "a".repeat(100 * 1024 + 1);
     let long_req = with_bearer(
@@ -420,7 +414,7 @@ async fn test_validate_prompt_returns_errors() {
     let long_resp = app.clone().oneshot(long_req).await.unwrap();
     assert_eq!(long_resp.status(), StatusCode::OK);
     let long_json = read_json_body(long_resp).await;
-    assert_eq!(long_json["data"]["isValid"].as_bool().unwrap(), false);
+    assert!(!long_json["data"]["isValid"].as_bool().unwrap());
 }
 
 #[tokio::test]
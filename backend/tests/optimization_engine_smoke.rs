@@ -76,7 +76,7 @@ async fn optimization_engine_can_run_and_is_switchable_by_feature() {
         ..Default::default()
     };
 
-    let engine = create_optimization_engine(ExecutionTargetType::Example, task_cfg);
+    let engine = create_optimization_engine(ExecutionTargetType::Example, task_cfg).unwrap();
     let expected_name = if cfg!(feature = "alt-optimization-engine") {
         "alternate_optimization_engine"
     } else {
@@ -91,7 +91,8 @@ async fn optimization_engine_can_run_and_is_switchable_by_feature() {
     let out = engine.run(&mut ctx).await.unwrap();
     assert!(out.should_terminate);
     assert_eq!(out.primary.content, prompt);
-    assert!(out.primary.score >= 0.0 && out.primary.score <= 1.0);
+    let score = out.primary.score.expect("primary 应已评估");
+    assert!((0.0..=1.0).contains(&score));
 
     // 脱敏硬约束：不得把 TestCase input 全文泄露到结果/扩展中（即便 input 含明显敏感值）。
     let out_json = serde_json::to_string(&out).unwrap();
@@ -135,7 +136,7 @@ async fn optimization_engine_default_evaluator_path_does_not_require_manual_exte
         ..Default::default()
     };
 
-    let engine = create_optimization_engine(ExecutionTargetType::Example, task_cfg);
+    let engine = create_optimization_engine(ExecutionTargetType::Example, task_cfg).unwrap();
     let mut ctx = make_ctx(prompt, vec![tc]);
     ctx.config.iteration.max_iterations = 1;
     ctx.config.iteration.pass_threshold = 1.0;
@@ -170,7 +171,7 @@ async fn optimization_engine_can_resume_from_checkpoint() {
         ..Default::default()
     };
 
-    let engine = create_optimization_engine(ExecutionTargetType::Example, task_cfg);
+    let engine = create_optimization_engine(ExecutionTargetType::Example, task_cfg).unwrap();
     let mut ctx = make_ctx("different_prompt_before_resume", vec![tc]);
     ctx.config.iteration.max_iterations = 1;
     ctx.config.iteration.pass_threshold = 1.0;
@@ -1,3 +1,5 @@
+mod common;
+
 use axum::Router;
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
@@ -8,13 +10,14 @@ use std::sync::Arc;
 use tower::ServiceExt;
 
 use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
-use prompt_faster::api::middleware::{LoginAttemptStore, SessionStore, auth_middleware};
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
 use prompt_faster::api::routes::{auth, health, user_auth, workspaces};
 use prompt_faster::api::state::AppState;
 use prompt_faster::infra::db::pool::create_pool;
 use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
 use prompt_faster::infra::external::http_client::create_http_client;
-use prompt_faster::shared::config::AppConfig;
 
 const TEST_MASTER_PASSWORD: &str = "test_master_password_for_integration";
 
@@ -29,25 +32,10 @@ async fn setup_test_app_with_db() -> (Router, sqlx::SqlitePool) {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-
-        checkpoint_cache_limit: 10,
-
-        checkpoint_memory_alert_threshold: 10,
-    });
+    let config = Arc::new(common::test_app_config());
     let api_key_manager = Arc::new(ApiKeyManager::new(Some(TEST_MASTER_PASSWORD.to_string())));
 
-    let session_store = SessionStore::new(24);
+    let session_store = SessionStore::new(db.clone(), 24);
     let login_attempt_store = LoginAttemptStore::default();
 
     let state = AppState {
@@ -57,6 +45,7 @@ async fn setup_test_app_with_db() -> (Router, sqlx::SqlitePool) {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: RateLimiter::default(),
     };
 
     let session_store_for_middleware = state.session_store.clone();
@@ -1113,6 +1102,32 @@ async fn test_update_task_config_rejects_out_of_range_values() {
                 "sampling_strategy": "random"
             }
         }),
+        json!({
+            "initial_prompt": null,
+            "max_iterations": 10,
+            "pass_threshold_percent": 95,
+            "candidate_prompt_count": 5,
+            "diversity_injection_threshold": 3,
+            "train_percent": 80,
+            "validation_percent": 20,
+            "output_config": default_output_config_json(),
+            "evaluator_config": default_evaluator_config_json(),
+            "advanced_data_split": default_advanced_data_split_json(),
+            "http_target": { "method": "DELETE", "url": "https://example.com/hook" }
+        }),
+        json!({
+            "initial_prompt": null,
+            "max_iterations": 10,
+            "pass_threshold_percent": 95,
+            "candidate_prompt_count": 5,
+            "diversity_injection_threshold": 3,
+            "train_percent": 80,
+            "validation_percent": 20,
+            "output_config": default_output_config_json(),
+            "evaluator_config": default_evaluator_config_json(),
+            "advanced_data_split": default_advanced_data_split_json(),
+            "http_target": { "method": "POST", "url": "ftp://example.com/hook" }
+        }),
     ];
 
     for payload in invalid_payloads {
@@ -1515,3 +1530,92 @@ async fn test_update_task_config_rejects_oversize_config_json() {
         "期望错误消息包含“任务配置过大”，实际: {msg}"
     );
 }
+
+#[tokio::test]
+async fn test_update_task_config_validates_execution_credential_id() {
+    let app = setup_test_app().await;
+    let token = register_user(&app, "opt_task_execution_credential", "TestPass123!").await;
+    let workspace_id = create_workspace(&app, &token).await;
+    let ts1 =
+        create_test_set_with_cases(&app, &workspace_id, &token, "ts", sample_exact_cases_json())
+            .await;
+    let task_id = create_optimization_task(&app, &workspace_id, &token, "fixed", vec![ts1]).await;
+
+    let create_credential = |body: Value| {
+        with_bearer(
+            build_json_request("POST", "/api/v1/auth/credentials", body),
+            &token,
+        )
+    };
+    let resp = app
+        .clone()
+        .oneshot(create_credential(json!({
+            "credential_type": "dify",
+            "name": "app-b",
+            "base_url": "https://dify.example.com",
+            "api_key": "sk-dify-app-b"
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let dify_id = read_json_body(resp).await["data"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let resp = app
+        .clone()
+        .oneshot(create_credential(json!({
+            "credential_type": "generic_llm",
+            "name": "billing-b",
+            "provider": "openai",
+            "base_url": "https://api.openai.com",
+            "api_key": "sk-openai-b"
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let llm_id = read_json_body(resp).await["data"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let update = |credential_id: &str| {
+        with_bearer(
+            build_json_request(
+                "PUT",
+                &format!(
+                    "/api/v1/workspaces/{}/optimization-tasks/{}/config",
+                    workspace_id, task_id
+                ),
+                json!({
+                    "initial_prompt": null,
+                    "max_iterations": 10,
+                    "pass_threshold_percent": 95,
+                    "candidate_prompt_count": 5,
+                    "diversity_injection_threshold": 3,
+                    "train_percent": 80,
+                    "validation_percent": 20,
+                    "output_config": default_output_config_json(),
+                    "evaluator_config": default_evaluator_config_json(),
+                    "advanced_data_split": default_advanced_data_split_json(),
+                    "execution_credential_id": credential_id
+                }),
+            ),
+            &token,
+        )
+    };
+
+    // dify 任务不能引用 generic_llm 凭证
+    let resp = app.clone().oneshot(update(&llm_id)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = app.clone().oneshot(update("missing-id")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = app.clone().oneshot(update(&dify_id)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json_body(resp).await;
+    assert_eq!(
+        body["data"]["config"]["execution_credential_id"],
+        json!(dify_id)
+    );
+}
@@ -0,0 +1,215 @@
+mod common;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::header::RETRY_AFTER;
+use axum::http::{Request, StatusCode};
+use axum::middleware;
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
+use prompt_faster::api::routes::{meta_optimization, rate_limits, user_auth};
+use prompt_faster::api::state::AppState;
+use prompt_faster::infra::db::pool::create_pool;
+use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
+use prompt_faster::infra::external::http_client::create_http_client;
+use prompt_faster::shared::config::AppConfig;
+
+const PASSWORD: &str = "TestPass123!";
+
+async fn setup_test_app(config: AppConfig) -> Router {
+    let db = create_pool("sqlite::memory:")
+        .await
+        .expect("创建测试数据库失败");
+
+    sqlx::migrate!()
+        .run(&db)
+        .await
+        .expect("运行 migrations 失败");
+
+    let http_client = create_http_client().expect("创建 HTTP 客户端失败");
+    let rate_limiter = RateLimiter::new(config.rate_limit_config());
+
+    let state = AppState {
+        db: db.clone(),
+        http_client,
+        config: Arc::new(config),
+        api_key_manager: Arc::new(ApiKeyManager::new(None)),
+        session_store: SessionStore::new(db.clone(), 24),
+        login_attempt_store: LoginAttemptStore::default(),
+        rate_limiter,
+    };
+
+    let session_store_for_middleware = state.session_store.clone();
+    Router::<AppState>::new()
+        .nest("/api/v1/auth", user_auth::public_router())
+        .nest(
+            "/api/v1/auth/rate-limits",
+            rate_limits::router().layer(middleware::from_fn_with_state(
+                session_store_for_middleware.clone(),
+                auth_middleware,
+            )),
+        )
+        .nest(
+            "/api/v1/meta-optimization",
+            meta_optimization::router().layer(middleware::from_fn_with_state(
+                session_store_for_middleware,
+                auth_middleware,
+            )),
+        )
+        .with_state(state)
+        .layer(middleware::from_fn(correlation_id_middleware))
+}
+
+async fn register_user(app: &Router, username: &str) -> String {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/register")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            serde_json::to_vec(&json!({"username": username, "password": PASSWORD})).unwrap(),
+        ))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json(resp.into_body()).await;
+    body["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string()
+}
+
+async fn read_json(body: Body) -> Value {
+    let bytes = body.collect().await.expect("读取响应 body 失败").to_bytes();
+    serde_json::from_slice(&bytes).expect("解析 JSON 失败")
+}
+
+fn preview_request(token: &str) -> Request<Body> {
+    // 请求体无效：限流发生在请求体解析之前，仍会消耗令牌
+    Request::builder()
+        .method("POST")
+        .uri("/api/v1/meta-optimization/prompts/preview")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from("{}"))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn prompt_execution_is_rate_limited_per_user_with_retry_after() {
+    let app = setup_test_app(AppConfig {
+        rate_limit_prompt_execution_per_minute: 1,
+        rate_limit_prompt_execution_burst: 2,
+        ..common::test_app_config()
+    })
+    .await;
+    let admin_token = register_user(&app, "admin").await;
+    let member_token = register_user(&app, "member").await;
+
+    for _ in 0..2 {
+        let resp = app
+            .clone()
+            .oneshot(preview_request(&member_token))
+            .await
+            .unwrap();
+        assert_ne!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    let resp = app
+        .clone()
+        .oneshot(preview_request(&member_token))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = resp
+        .headers()
+        .get(RETRY_AFTER)
+        .expect("缺少 Retry-After")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+    let body = read_json(resp.into_body()).await;
+    assert_eq!(body["error"]["code"], "RATE_LIMITED");
+
+    // 其他用户的令牌桶不受影响
+    let resp = app
+        .clone()
+        .oneshot(preview_request(&admin_token))
+        .await
+        .unwrap();
+    assert_ne!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let stats_request = |token: &str| {
+        Request::builder()
+            .method("GET")
+            .uri("/api/v1/auth/rate-limits")
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let resp = app
+        .clone()
+        .oneshot(stats_request(&admin_token))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json(resp.into_body()).await;
+    let classes = body["data"]["classes"].as_array().unwrap();
+    let execution = classes
+        .iter()
+        .find(|c| c["class"] == "prompt_execution")
+        .expect("缺少 prompt_execution 统计");
+    assert_eq!(execution["per_minute"], 1);
+    assert_eq!(execution["burst"], 2);
+    assert_eq!(execution["allowed_total"], 3);
+    assert_eq!(execution["rejected_total"], 1);
+    assert_eq!(execution["active_buckets"], 2);
+
+    let resp = app
+        .clone()
+        .oneshot(stats_request(&member_token))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn rate_limits_follow_each_app_state_config() {
+    // 同一进程内的另一个 AppState 使用默认配置，不受其他测试的小配额影响
+    let app = setup_test_app(common::test_app_config()).await;
+    let token = register_user(&app, "admin").await;
+
+    for _ in 0..3 {
+        let resp = app.clone().oneshot(preview_request(&token)).await.unwrap();
+        assert_ne!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/api/v1/auth/rate-limits")
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json(resp.into_body()).await;
+    let execution = body["data"]["classes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["class"] == "prompt_execution")
+        .cloned()
+        .expect("缺少 prompt_execution 统计");
+    assert_eq!(execution["per_minute"], 10);
+    assert_eq!(execution["allowed_total"], 3);
+    assert_eq!(execution["rejected_total"], 0);
+}
@@ -1,3 +1,5 @@
+mod common;
+
 use axum::Router;
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
@@ -10,13 +12,14 @@ use wiremock::matchers::{header, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
-use prompt_faster::api::middleware::{LoginAttemptStore, SessionStore, auth_middleware};
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
 use prompt_faster::api::routes::{auth, health, user_auth, workspaces};
 use prompt_faster::api::state::AppState;
 use prompt_faster::infra::db::pool::create_pool;
 use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
 use prompt_faster::infra::external::http_client::create_http_client;
-use prompt_faster::shared::config::AppConfig;
 
 const TEST_MASTER_PASSWORD: &str = "test_master_password_for_integration";
 
@@ -31,25 +34,10 @@ async fn setup_test_app() -> Router {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-
-        checkpoint_cache_limit: 10,
-
-        checkpoint_memory_alert_threshold: 10,
-    });
+    let config = Arc::new(common::test_app_config());
     let api_key_manager = Arc::new(ApiKeyManager::new(Some(TEST_MASTER_PASSWORD.to_string())));
 
-    let session_store = SessionStore::new(24);
+    let session_store = SessionStore::new(db.clone(), 24);
     let login_attempt_store = LoginAttemptStore::default();
 
     let state = AppState {
@@ -59,6 +47,7 @@ async fn setup_test_app() -> Router {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: RateLimiter::default(),
     };
 
     let session_store_for_middleware = state.session_store.clone();
@@ -370,7 +359,9 @@ async fn test_save_dify_config_persists_and_can_be_read_back() {
                 "bindings": {
                     "k": { "source": "fixed", "value": 3 },
                     "flag": { "source": "testCaseInput", "inputKey": "flag" }
-                }
+                },
+                "appType": "chat",
+                "responseMode": "streaming"
             }),
         ),
         &token,
@@ -402,6 +393,93 @@ async fn test_save_dify_config_persists_and_can_be_read_back() {
         get_body["data"]["dify_config"]["targetPromptVariable"],
         "system_prompt"
     );
+    assert_eq!(get_body["data"]["dify_config"]["appType"], "chat");
+    assert_eq!(get_body["data"]["dify_config"]["responseMode"], "streaming");
+}
+
+#[tokio::test]
+async fn test_refresh_stores_declared_outputs_and_output_selector_is_validated() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/parameters"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "user_input_form": [
+                { "text-input": { "label": "System", "variable": "system_prompt", "required": true } }
+            ],
+            "outputs": [
+                { "variable": "summary", "value_type": "string" },
+                { "variable": "meta", "value_type": "object" }
+            ]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let app = setup_test_app().await;
+    let token = register_user(&app, "dify_outputs_user", "TestPass123!").await;
+    let workspace_id = create_workspace(&app, &token).await;
+    let test_set_id = create_test_set(&app, &token, &workspace_id).await;
+    save_auth_config(&app, &token, &mock_server.uri(), "sk-dify-test").await;
+
+    let config_uri = format!(
+        "/api/v1/workspaces/{}/test-sets/{}/dify/config",
+        workspace_id, test_set_id
+    );
+    let save = |selector: Value| {
+        with_bearer(
+            build_json_request(
+                "PUT",
+                &config_uri,
+                json!({
+                    "targetPromptVariable": "system_prompt",
+                    "bindings": {},
+                    "outputSelector": selector
+                }),
+            ),
+            &token,
+        )
+    };
+
+    let resp = app.clone().oneshot(save(Value::Null)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let refresh_req = with_bearer(
+        Request::builder()
+            .method("POST")
+            .uri(format!(
+                "/api/v1/workspaces/{}/test-sets/{}/dify/variables/refresh",
+                workspace_id, test_set_id
+            ))
+            .body(Body::empty())
+            .unwrap(),
+        &token,
+    );
+    let resp = app.clone().oneshot(refresh_req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json_body(resp).await;
+    assert_eq!(body["data"]["outputs"][0]["name"], "summary");
+    assert_eq!(body["data"]["outputs"][1]["type"], "object");
+
+    // 未声明的输出变量名被拒绝；JSON Pointer 不受声明约束
+    let resp = app.clone().oneshot(save(json!("unknown"))).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = app
+        .clone()
+        .oneshot(save(json!("/data/outputs/meta/score")))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = app.clone().oneshot(save(json!(" summary "))).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json_body(resp).await;
+    assert_eq!(body["data"]["difyConfig"]["outputSelector"], "summary");
+    assert_eq!(
+        body["data"]["difyConfig"]["outputsSnapshot"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
 }
 
 #[tokio::test]
@@ -1,3 +1,5 @@
+mod common;
+
 use axum::Router;
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
@@ -8,13 +10,14 @@ use std::sync::Arc;
 use tower::ServiceExt;
 
 use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
-use prompt_faster::api::middleware::{LoginAttemptStore, SessionStore, auth_middleware};
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
 use prompt_faster::api::routes::{auth, health, user_auth, workspaces};
 use prompt_faster::api::state::AppState;
 use prompt_faster::infra::db::pool::create_pool;
 use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
 use prompt_faster::infra::external::http_client::create_http_client;
-use prompt_faster::shared::config::AppConfig;
 
 const TEST_MASTER_PASSWORD: &str = "test_master_password_for_integration";
 
@@ -29,25 +32,10 @@ async fn setup_test_app() -> Router {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-
-        checkpoint_cache_limit: 10,
-
-        checkpoint_memory_alert_threshold: 10,
-    });
+    let config = Arc::new(common::test_app_config());
     let api_key_manager = Arc::new(ApiKeyManager::new(Some(TEST_MASTER_PASSWORD.to_string())));
 
-    let session_store = SessionStore::new(24);
+    let session_store = SessionStore::new(db.clone(), 24);
     let login_attempt_store = LoginAttemptStore::default();
 
     let state = AppState {
@@ -57,6 +45,7 @@ async fn setup_test_app() -> Router {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: RateLimiter::default(),
     };
 
     let session_store_for_middleware = state.session_store.clone();
@@ -658,3 +647,63 @@ async fn test_cases_validation_returns_400_for_missing_reference() {
     let body = read_json_body(resp).await;
     assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
 }
+
+#[tokio::test]
+async fn test_conversation_cases_are_imported_and_validated() {
+    let app = setup_test_app().await;
+    let token = register_user(&app, "ts_conversation_user", "TestPass123!").await;
+    let workspace_id = create_workspace(&app, &token).await;
+
+    let conversation_case = |turns: serde_json::Value| {
+        json!([{
+            "id": "dialog-1",
+            "input": { "conversation": turns },
+            "reference": { "Exact": { "expected": "fine" } }
+        }])
+    };
+
+    let req = with_bearer(
+        build_json_request(
+            "POST",
+            &format!("/api/v1/workspaces/{}/test-sets", workspace_id),
+            json!({
+                "name": "dialogs",
+                "description": null,
+                "cases": conversation_case(json!([
+                    { "role": "user", "content": "hi" },
+                    { "role": "assistant", "content": "hello" },
+                    { "role": "user", "content": "how are you?" }
+                ]))
+            }),
+        ),
+        &token,
+    );
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json_body(resp).await;
+    assert_eq!(
+        body["data"]["cases"][0]["input"]["conversation"][1]["role"],
+        "assistant"
+    );
+
+    // 最后一轮必须是 user；角色必须合法
+    for turns in [
+        json!([{ "role": "user", "content": "hi" }, { "role": "assistant", "content": "hello" }]),
+        json!([{ "role": "system", "content": "x" }, { "role": "user", "content": "hi" }]),
+        json!([]),
+    ] {
+        let req = with_bearer(
+            build_json_request(
+                "POST",
+                &format!("/api/v1/workspaces/{}/test-sets", workspace_id),
+                json!({ "name": "bad", "description": null, "cases": conversation_case(turns) }),
+            ),
+            &token,
+        );
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body = read_json_body(resp).await;
+        assert_eq!(body["error"]["code"], "VALIDATION_ERROR");
+        assert_eq!(body["error"]["details"]["id"], "dialog-1");
+    }
+}
@@ -0,0 +1,411 @@
+mod common;
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::{Request, StatusCode};
+use axum::middleware;
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use sqlx::SqlitePool;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
+use prompt_faster::api::routes::{totp, user_auth};
+use prompt_faster::api::state::AppState;
+use prompt_faster::infra::db::pool::create_pool;
+use prompt_faster::infra::external::api_key_manager::{ApiKeyManager, ServerKek};
+use prompt_faster::infra::external::http_client::create_http_client;
+use prompt_faster::shared::time::now_millis;
+use prompt_faster::shared::totp::{code_at, decode_base32, step_at};
+
+const PASSWORD: &str = "TestPass123!";
+
+async fn setup_test_app() -> Router {
+    setup_test_app_with_server_kek(None).await.0
+}
+
+async fn setup_test_app_with_server_kek(server_kek: Option<ServerKek>) -> (Router, SqlitePool) {
+    let db = create_pool("sqlite::memory:")
+        .await
+        .expect("创建测试数据库失败");
+
+    sqlx::migrate!()
+        .run(&db)
+        .await
+        .expect("运行 migrations 失败");
+
+    let http_client = create_http_client().expect("创建 HTTP 客户端失败");
+    let config = Arc::new(common::test_app_config());
+
+    let state = AppState {
+        db: db.clone(),
+        http_client,
+        config,
+        api_key_manager: Arc::new(ApiKeyManager::new(None).with_server_kek(server_kek)),
+        session_store: SessionStore::new(db.clone(), 24),
+        login_attempt_store: LoginAttemptStore::default(),
+        rate_limiter: RateLimiter::default(),
+    };
+
+    let session_store_for_middleware = state.session_store.clone();
+    let protected = |router: Router<AppState>| {
+        router.layer(middleware::from_fn_with_state(
+            session_store_for_middleware.clone(),
+            auth_middleware,
+        ))
+    };
+
+    let router = Router::<AppState>::new()
+        .nest("/api/v1/auth", user_auth::public_router())
+        .nest("/api/v1/auth", protected(user_auth::protected_router()))
+        .nest("/api/v1/auth/totp", protected(totp::router()))
+        .with_state(state)
+        .layer(middleware::from_fn(correlation_id_middleware));
+    (router, db)
+}
+
+async fn read_json_body(response: axum::response::Response) -> Value {
+    let bytes = response
+        .into_body()
+        .collect()
+        .await
+        .expect("读取响应 body 失败")
+        .to_bytes();
+    serde_json::from_slice(&bytes).expect("解析 JSON 失败")
+}
+
+fn json_request(method: &str, uri: &str, token: Option<&str>, body: Value) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        builder = builder.header("Authorization", format!("Bearer {}", token));
+    }
+    let mut req = builder
+        .body(Body::from(
+            serde_json::to_vec(&body).expect("序列化 JSON 失败"),
+        ))
+        .expect("构建请求失败");
+    req.extensions_mut()
+        .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 40000))));
+    req
+}
+
+async fn send(app: &Router, req: Request<Body>) -> (StatusCode, Value) {
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    (status, read_json_body(resp).await)
+}
+
+async fn register_user(app: &Router, username: &str) -> String {
+    let (status, body) = send(
+        app,
+        json_request(
+            "POST",
+            "/api/v1/auth/register",
+            None,
+            json!({"username": username, "password": PASSWORD}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    body["data"]["session_token"]
+        .as_str()
+        .expect("缺少 session_token")
+        .to_string()
+}
+
+async fn login(app: &Router, username: &str, totp_code: Option<&str>) -> (StatusCode, Value) {
+    send(
+        app,
+        json_request(
+            "POST",
+            "/api/v1/auth/login",
+            None,
+            json!({"username": username, "password": PASSWORD, "totp_code": totp_code}),
+        ),
+    )
+    .await
+}
+
+/// 生成并确认启用两步验证，返回（密钥，启用时使用的时间步，恢复码）
+async fn enable_totp(app: &Router, token: &str) -> (Vec<u8>, i64, Vec<String>) {
+    let (status, body) = send(
+        app,
+        json_request(
+            "POST",
+            "/api/v1/auth/totp/setup",
+            Some(token),
+            json!({"password": PASSWORD}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let secret_b32 = body["data"]["secret"].as_str().expect("缺少 secret");
+    let uri = body["data"]["provisioning_uri"]
+        .as_str()
+        .expect("缺少 provisioning_uri");
+    assert!(uri.starts_with("otpauth://totp/Prompt%20Faster:"));
+    assert!(uri.contains(&format!("secret={secret_b32}")));
+    let secret = decode_base32(secret_b32).expect("secret 应为 Base32");
+
+    let step = step_at(now_millis());
+    let (status, body) = send(
+        app,
+        json_request(
+            "POST",
+            "/api/v1/auth/totp/enable",
+            Some(token),
+            json!({"code": format!("{:06}", code_at(&secret, step))}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let recovery_codes: Vec<String> = body["data"]["recovery_codes"]
+        .as_array()
+        .expect("缺少 recovery_codes")
+        .iter()
+        .map(|c| c.as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(recovery_codes.len(), 10);
+
+    (secret, step, recovery_codes)
+}
+
+#[tokio::test]
+async fn test_totp_enrolment_and_login_second_step() {
+    let app = setup_test_app().await;
+    let token = register_user(&app, "alice").await;
+
+    let (status, body) = send(
+        &app,
+        json_request(
+            "POST",
+            "/api/v1/auth/totp/setup",
+            Some(&token),
+            json!({"password": "wrong-password"}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "AUTH_FAILED");
+
+    let (secret, step, recovery_codes) = enable_totp(&app, &token).await;
+
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/auth/totp")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = read_json_body(resp).await;
+    assert_eq!(body["data"]["enabled"], true);
+    assert_eq!(body["data"]["recovery_codes_remaining"], 10);
+
+    let (status, body) = login(&app, "alice", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"]["code"], "AUTH_TOTP_REQUIRED");
+
+    // 启用时使用过的验证码不可重放
+    let (status, body) = login(
+        &app,
+        "alice",
+        Some(&format!("{:06}", code_at(&secret, step))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"]["code"], "AUTH_TOTP_INVALID");
+
+    let next_code = format!("{:06}", code_at(&secret, step + 1));
+    let (status, body) = login(&app, "alice", Some(&next_code)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(body["data"]["session_token"].is_string());
+
+    // 恢复码一次性
+    let (status, _) = login(&app, "alice", Some(&recovery_codes[0].to_uppercase())).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = login(&app, "alice", Some(&recovery_codes[0])).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"]["code"], "AUTH_TOTP_INVALID");
+
+    // 已启用时不能重新生成密钥
+    let (status, body) = send(
+        &app,
+        json_request(
+            "POST",
+            "/api/v1/auth/totp/setup",
+            Some(&token),
+            json!({"password": PASSWORD}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"]["code"], "AUTH_TOTP_STATE_CONFLICT");
+
+    // 停用需要密码 + 第二因素
+    let (status, body) = send(
+        &app,
+        json_request(
+            "POST",
+            "/api/v1/auth/totp/disable",
+            Some(&token),
+            json!({"password": PASSWORD}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"]["code"], "AUTH_TOTP_REQUIRED");
+
+    let (status, body) = send(
+        &app,
+        json_request(
+            "POST",
+            "/api/v1/auth/totp/disable",
+            Some(&token),
+            json!({"password": PASSWORD, "code": recovery_codes[1]}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let (status, _) = login(&app, "alice", None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_regenerate_recovery_codes_invalidates_old_codes() {
+    let app = setup_test_app().await;
+    let token = register_user(&app, "alice").await;
+    let (_, _, old_codes) = enable_totp(&app, &token).await;
+
+    let (status, body) = send(
+        &app,
+        json_request(
+            "POST",
+            "/api/v1/auth/totp/recovery-codes",
+            Some(&token),
+            json!({"password": PASSWORD, "code": old_codes[0]}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let new_code = body["data"]["recovery_codes"][0]
+        .as_str()
+        .expect("缺少恢复码")
+        .to_string();
+
+    let (status, _) = login(&app, "alice", Some(&old_codes[1])).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = login(&app, "alice", Some(&new_code)).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_login_unlocks_data_key_only_after_second_factor() {
+    let (app, db) =
+        setup_test_app_with_server_kek(Some(ServerKek::from_hex(&"22".repeat(32)).unwrap())).await;
+    let token = register_user(&app, "alice").await;
+    let (secret, step, _) = enable_totp(&app, &token).await;
+
+    // 模拟 KEK 轮换：数据密钥需在下次完整登录时重新包装
+    sqlx::query("UPDATE user_data_keys SET server_wrapped_key = NULL, server_nonce = NULL, server_kek_id = NULL")
+        .execute(&db)
+        .await
+        .unwrap();
+    let server_kek_id = || async {
+        sqlx::query_scalar::<_, Option<String>>("SELECT server_kek_id FROM user_data_keys")
+            .fetch_one(&db)
+            .await
+            .unwrap()
+    };
+
+    // 密码正确但第二因素错误：不得写入数据密钥
+    let wrong_code = format!("{:06}", (code_at(&secret, step + 1) + 1) % 1_000_000);
+    let (status, body) = login(&app, "alice", Some(&wrong_code)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"]["code"], "AUTH_TOTP_INVALID");
+    assert_eq!(server_kek_id().await, None);
+
+    let (status, body) = login(&app, "alice", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"]["code"], "AUTH_TOTP_REQUIRED");
+    assert_eq!(server_kek_id().await, None);
+
+    // 全部因子通过后才解锁并重新包装
+    let code = format!("{:06}", code_at(&secret, step + 1));
+    let (status, body) = login(&app, "alice", Some(&code)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(server_kek_id().await.is_some());
+}
+
+#[tokio::test]
+async fn test_admin_reset_requires_first_user_and_reauthentication() {
+    let app = setup_test_app().await;
+    let admin_token = register_user(&app, "admin").await;
+    let bob_token = register_user(&app, "bob").await;
+    enable_totp(&app, &bob_token).await;
+
+    let (status, body) = send(
+        &app,
+        json_request(
+            "POST",
+            "/api/v1/auth/totp/admin-reset",
+            Some(&bob_token),
+            json!({"username": "admin", "password": PASSWORD}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"]["code"], "FORBIDDEN");
+
+    let (status, _) = send(
+        &app,
+        json_request(
+            "POST",
+            "/api/v1/auth/totp/admin-reset",
+            Some(&admin_token),
+            json!({"username": "bob", "password": "wrong-password"}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(
+        &app,
+        json_request(
+            "POST",
+            "/api/v1/auth/totp/admin-reset",
+            Some(&admin_token),
+            json!({"username": "bob", "password": PASSWORD}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let (status, _) = login(&app, "bob", None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(
+        &app,
+        json_request(
+            "POST",
+            "/api/v1/auth/totp/admin-reset",
+            Some(&admin_token),
+            json!({"username": "bob", "password": PASSWORD}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
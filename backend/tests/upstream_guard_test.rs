@@ -0,0 +1,168 @@
+//! 上游限流 / 熔断集成测试
+//! 熔断打开会写入全局连通性状态，因此放在独立测试进程中
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use prompt_faster::core::execution_target::{DifyExecutionTarget, ExecutionError};
+use prompt_faster::core::traits::ExecutionTarget;
+use prompt_faster::domain::models::ConnectivityStatus;
+use prompt_faster::domain::types::{DifyAppType, DifyResponseMode, ExecutionTargetConfig};
+use prompt_faster::infra::external::connectivity::check_connectivity_status;
+use prompt_faster::infra::external::http_client::create_http_client;
+use prompt_faster::infra::external::llm_client::{self, ChatCompletionsRequest, ChatMessage};
+use prompt_faster::infra::external::upstream_guard::{
+    CIRCUIT_FAILURE_THRESHOLD_DEFAULT, CircuitState, UpstreamGuard, UpstreamGuardConfig,
+    UpstreamGuardError, UpstreamOutcome,
+};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn dify_config(server: &MockServer) -> ExecutionTargetConfig {
+    ExecutionTargetConfig::Dify {
+        api_url: server.uri(),
+        workflow_id: "wf-1".to_string(),
+        prompt_variable: "prompt".to_string(),
+        api_key: Some("dify_guard_key".to_string()),
+        output_selector: None,
+        app_type: DifyAppType::Workflow,
+        response_mode: DifyResponseMode::Blocking,
+    }
+}
+
+#[tokio::test]
+async fn dify_execution_opens_circuit_after_consecutive_upstream_failures() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/workflows/wf-1/run"))
+        .respond_with(ResponseTemplate::new(502))
+        .mount(&server)
+        .await;
+
+    let target = DifyExecutionTarget::new().unwrap();
+    let config = dify_config(&server);
+    let input = HashMap::new();
+    for i in 0..CIRCUIT_FAILURE_THRESHOLD_DEFAULT {
+        let err = target
+            .execute(&config, "PROMPT", &input, &format!("tc-{i}"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecutionError::UpstreamError { .. }));
+    }
+
+    // 熔断打开：快速失败，不再请求上游
+    let err = target
+        .execute(&config, "PROMPT", &input, "tc-open")
+        .await
+        .unwrap_err();
+    match err {
+        ExecutionError::UpstreamError { message, .. } => {
+            assert!(message.contains("熔断"), "unexpected message: {message}");
+        }
+        other => panic!("预期 UpstreamError，实际: {other:?}"),
+    }
+    let received = server.received_requests().await.unwrap_or_default();
+    assert_eq!(received.len(), CIRCUIT_FAILURE_THRESHOLD_DEFAULT as usize);
+    assert!(matches!(
+        check_connectivity_status().await,
+        ConnectivityStatus::Limited
+    ));
+}
+
+#[tokio::test]
+async fn llm_chat_waits_for_retry_after_before_next_request() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "choices": [{ "message": { "content": "ok" } }]
+        })))
+        .mount(&server)
+        .await;
+
+    let client = create_http_client().expect("创建 HTTP 客户端失败");
+    let req = ChatCompletionsRequest {
+        model: "m1".to_string(),
+        messages: vec![ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        }],
+    };
+
+    let err = llm_client::chat_completions(&client, &server.uri(), "sk-guard", "cid-1", &req)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        llm_client::LlmConnectionError::UpstreamError(_)
+    ));
+
+    let start = std::time::Instant::now();
+    let output = llm_client::chat_completions(&client, &server.uri(), "sk-guard", "cid-2", &req)
+        .await
+        .expect("冷却结束后应成功");
+    assert_eq!(output, "ok");
+    assert!(start.elapsed() >= Duration::from_millis(900));
+}
+
+#[tokio::test]
+async fn half_open_allows_single_probe_and_closes_on_success() {
+    let guard = UpstreamGuard::new(
+        "half-open",
+        UpstreamGuardConfig {
+            failure_threshold: 2,
+            open_duration: Duration::from_millis(100),
+            ..UpstreamGuardConfig::default()
+        },
+    );
+    for _ in 0..2 {
+        guard.acquire(0).await.unwrap();
+        guard
+            .record(UpstreamOutcome::Failure { retry_after: None })
+            .await;
+    }
+    assert_eq!(guard.circuit_state().await, CircuitState::Open);
+    assert!(matches!(
+        guard.acquire(0).await,
+        Err(UpstreamGuardError::CircuitOpen { .. })
+    ));
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    guard.acquire(0).await.expect("冷却后放行探测请求");
+    assert_eq!(guard.circuit_state().await, CircuitState::HalfOpen);
+    assert!(
+        guard.acquire(0).await.is_err(),
+        "探测进行中时其余请求应快速失败"
+    );
+
+    guard.record(UpstreamOutcome::Success).await;
+    assert_eq!(guard.circuit_state().await, CircuitState::Closed);
+    guard.acquire(0).await.unwrap();
+}
+
+#[tokio::test]
+async fn half_open_probe_failure_reopens_circuit() {
+    let guard = UpstreamGuard::new(
+        "half-open-fail",
+        UpstreamGuardConfig {
+            failure_threshold: 1,
+            open_duration: Duration::from_millis(50),
+            ..UpstreamGuardConfig::default()
+        },
+    );
+    guard
+        .record(UpstreamOutcome::Failure { retry_after: None })
+        .await;
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    guard.acquire(0).await.unwrap();
+    guard
+        .record(UpstreamOutcome::Failure { retry_after: None })
+        .await;
+    assert_eq!(guard.circuit_state().await, CircuitState::Open);
+}
@@ -1,3 +1,5 @@
+mod common;
+
 use axum::Router;
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
@@ -9,13 +11,14 @@ use std::sync::Arc;
 use tower::ServiceExt;
 
 use prompt_faster::api::middleware::correlation_id::correlation_id_middleware;
-use prompt_faster::api::middleware::{LoginAttemptStore, SessionStore, auth_middleware};
-use prompt_faster::api::routes::{auth, health, user_auth, workspaces};
+use prompt_faster::api::middleware::{
+    LoginAttemptStore, RateLimiter, SessionStore, auth_middleware,
+};
+use prompt_faster::api::routes::{auth, diversity, health, user_auth, workspaces};
 use prompt_faster::api::state::AppState;
 use prompt_faster::infra::db::pool::create_pool;
 use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
 use prompt_faster::infra::external::http_client::create_http_client;
-use prompt_faster::shared::config::AppConfig;
 
 const TEST_MASTER_PASSWORD: &str = "test_master_password_for_integration";
 
@@ -30,25 +33,10 @@ async fn setup_test_app() -> Router {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-
-        checkpoint_cache_limit: 10,
-
-        checkpoint_memory_alert_threshold: 10,
-    });
+    let config = Arc::new(common::test_app_config());
     let api_key_manager = Arc::new(ApiKeyManager::new(Some(TEST_MASTER_PASSWORD.to_string())));
 
-    let session_store = SessionStore::new(24);
+    let session_store = SessionStore::new(db.clone(), 24);
     let login_attempt_store = LoginAttemptStore::default();
 
     let state = AppState {
@@ -58,6 +46,7 @@ async fn setup_test_app() -> Router {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: RateLimiter::default(),
     };
 
     let session_store_for_middleware = state.session_store.clone();
@@ -72,6 +61,11 @@ async fn setup_test_app() -> Router {
     );
 
     let protected_workspaces_routes = workspaces::router().layer(middleware::from_fn_with_state(
+        session_store_for_middleware.clone(),
+        auth_middleware,
+    ));
+
+    let protected_diversity_routes = diversity::router().layer(middleware::from_fn_with_state(
         session_store_for_middleware,
         auth_middleware,
     ));
@@ -83,6 +77,10 @@ async fn setup_test_app() -> Router {
         .nest("/api/v1/auth", user_auth::public_router())
         .nest("/api/v1/auth", protected_user_auth_routes)
         .nest("/api/v1/workspaces", protected_workspaces_routes)
+        .nest(
+            "/api/v1/tasks/{task_id}/diversity",
+            protected_diversity_routes,
+        )
         .with_state(state)
         .layer(middleware::from_fn(correlation_id_middleware))
 }
@@ -98,23 +96,10 @@ async fn setup_test_app_with_db() -> (Router, SqlitePool) {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-        checkpoint_cache_limit: 10,
-        checkpoint_memory_alert_threshold: 10,
-    });
+    let config = Arc::new(common::test_app_config());
     let api_key_manager = Arc::new(ApiKeyManager::new(Some(TEST_MASTER_PASSWORD.to_string())));
 
-    let session_store = SessionStore::new(24);
+    let session_store = SessionStore::new(db.clone(), 24);
     let login_attempt_store = LoginAttemptStore::default();
 
     let state = AppState {
@@ -124,6 +109,7 @@ async fn setup_test_app_with_db() -> (Router, SqlitePool) {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: RateLimiter::default(),
     };
 
     let session_store_for_middleware = state.session_store.clone();
@@ -533,3 +519,240 @@ async fn test_delete_workspace_cascades_to_optimization_task_test_sets() {
         .expect("查询 optimization_task_test_sets 失败");
     assert_eq!(otts_count, 0);
 }
+
+async fn add_member(
+    app: &Router,
+    token: &str,
+    workspace_id: &str,
+    username: &str,
+    role: &str,
+) -> StatusCode {
+    let req = with_bearer(
+        build_json_request(
+            "POST",
+            &format!("/api/v1/workspaces/{}/members", workspace_id),
+            json!({"username": username, "role": role}),
+        ),
+        token,
+    );
+    app.clone().oneshot(req).await.unwrap().status()
+}
+
+async fn member_user_id(app: &Router, token: &str, workspace_id: &str, username: &str) -> String {
+    let req = with_bearer(
+        build_empty_request(
+            "GET",
+            &format!("/api/v1/workspaces/{}/members", workspace_id),
+        ),
+        token,
+    );
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_json_body(resp).await;
+    body["data"]["members"]
+        .as_array()
+        .expect("缺少 members")
+        .iter()
+        .find(|m| m["username"] == username)
+        .and_then(|m| m["user_id"].as_str())
+        .expect("成员不存在")
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_shared_workspace_roles_control_read_and_write() {
+    let app = setup_test_app().await;
+
+    let token_a = register_user(&app, "test_share_owner", "TestPass123!").await;
+    let token_b = register_user(&app, "test_share_member", "TestPass123!").await;
+
+    let workspace_id = create_workspace(&app, &token_a, "Shared").await;
+    let test_set_id = create_test_set(&app, &token_a, &workspace_id, "ts").await;
+
+    assert_eq!(
+        add_member(&app, &token_a, &workspace_id, "test_share_member", "viewer").await,
+        StatusCode::OK
+    );
+
+    // viewer：可在列表中看到工作区并读取测试集
+    let list_req = with_bearer(build_empty_request("GET", "/api/v1/workspaces"), &token_b);
+    let list_json = read_json_body(app.clone().oneshot(list_req).await.unwrap()).await;
+    let list = list_json["data"].as_array().expect("缺少列表");
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0]["id"], workspace_id.as_str());
+    assert_eq!(list[0]["role"], "viewer");
+
+    let get_req = with_bearer(
+        build_empty_request(
+            "GET",
+            &format!(
+                "/api/v1/workspaces/{}/test-sets/{}",
+                workspace_id, test_set_id
+            ),
+        ),
+        &token_b,
+    );
+    assert_eq!(
+        app.clone().oneshot(get_req).await.unwrap().status(),
+        StatusCode::OK
+    );
+
+    // viewer：不可修改、不可删除、不可管理成员
+    let create_req = with_bearer(
+        build_json_request(
+            "POST",
+            &format!("/api/v1/workspaces/{}/test-sets", workspace_id),
+            json!({"name": "b-ts", "description": null, "cases": []}),
+        ),
+        &token_b,
+    );
+    assert_eq!(
+        app.clone().oneshot(create_req).await.unwrap().status(),
+        StatusCode::FORBIDDEN
+    );
+    let delete_req = with_bearer(
+        build_empty_request("DELETE", &format!("/api/v1/workspaces/{}", workspace_id)),
+        &token_b,
+    );
+    assert_eq!(
+        app.clone().oneshot(delete_req).await.unwrap().status(),
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        add_member(&app, &token_b, &workspace_id, "test_share_owner", "editor").await,
+        StatusCode::FORBIDDEN
+    );
+
+    // 升级为 editor 后可创建测试集与任务
+    let member_id = member_user_id(&app, &token_a, &workspace_id, "test_share_member").await;
+    let update_req = with_bearer(
+        build_json_request(
+            "PATCH",
+            &format!("/api/v1/workspaces/{}/members/{}", workspace_id, member_id),
+            json!({"role": "editor"}),
+        ),
+        &token_a,
+    );
+    assert_eq!(
+        app.clone().oneshot(update_req).await.unwrap().status(),
+        StatusCode::OK
+    );
+    let editor_test_set_id = create_test_set(&app, &token_b, &workspace_id, "b-ts").await;
+    create_optimization_task(&app, &token_b, &workspace_id, "b-task", &editor_test_set_id).await;
+
+    // 成员自行退出后不再可见
+    let leave_req = with_bearer(
+        build_empty_request(
+            "DELETE",
+            &format!("/api/v1/workspaces/{}/members/{}", workspace_id, member_id),
+        ),
+        &token_b,
+    );
+    assert_eq!(
+        app.clone().oneshot(leave_req).await.unwrap().status(),
+        StatusCode::OK
+    );
+    let get_ws_req = with_bearer(
+        build_empty_request("GET", &format!("/api/v1/workspaces/{}", workspace_id)),
+        &token_b,
+    );
+    assert_eq!(
+        app.clone().oneshot(get_ws_req).await.unwrap().status(),
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_viewer_cannot_trigger_mutating_post_endpoints() {
+    let app = setup_test_app().await;
+
+    let token_a = register_user(&app, "test_post_owner", "TestPass123!").await;
+    let token_b = register_user(&app, "test_post_viewer", "TestPass123!").await;
+
+    let workspace_id = create_workspace(&app, &token_a, "Shared").await;
+    let test_set_id = create_test_set(&app, &token_a, &workspace_id, "ts").await;
+    let task_id =
+        create_optimization_task(&app, &token_a, &workspace_id, "task", &test_set_id).await;
+    assert_eq!(
+        add_member(&app, &token_a, &workspace_id, "test_post_viewer", "viewer").await,
+        StatusCode::OK
+    );
+
+    let baseline_uri = format!("/api/v1/tasks/{}/diversity/baseline", task_id);
+    let refresh_uri = format!(
+        "/api/v1/workspaces/{}/test-sets/{}/dify/variables/refresh",
+        workspace_id, test_set_id
+    );
+
+    for uri in [&baseline_uri, &refresh_uri] {
+        let req = with_bearer(build_empty_request("POST", uri), &token_b);
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN, "{uri}");
+        let body = read_json_body(resp).await;
+        assert_eq!(body["error"]["code"], "FORBIDDEN", "{uri}");
+    }
+
+    // owner 不受只读限制（固定任务不能记录基线 / 未配置 Dify 凭证）
+    for uri in [&baseline_uri, &refresh_uri] {
+        let req = with_bearer(build_empty_request("POST", uri), &token_a);
+        assert_eq!(
+            app.clone().oneshot(req).await.unwrap().status(),
+            StatusCode::BAD_REQUEST,
+            "{uri}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_add_workspace_member_validation() {
+    let app = setup_test_app().await;
+
+    let token_a = register_user(&app, "test_invite_owner", "TestPass123!").await;
+    register_user(&app, "test_invite_member", "TestPass123!").await;
+    let workspace_id = create_workspace(&app, &token_a, "Invites").await;
+
+    assert_eq!(
+        add_member(&app, &token_a, &workspace_id, "test_invite_member", "owner").await,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        add_member(&app, &token_a, &workspace_id, "no_such_user", "viewer").await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        add_member(
+            &app,
+            &token_a,
+            &workspace_id,
+            "test_invite_member",
+            "editor"
+        )
+        .await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        add_member(
+            &app,
+            &token_a,
+            &workspace_id,
+            "test_invite_member",
+            "viewer"
+        )
+        .await,
+        StatusCode::CONFLICT
+    );
+
+    // owner 不能被移除
+    let owner_id = member_user_id(&app, &token_a, &workspace_id, "test_invite_owner").await;
+    let remove_req = with_bearer(
+        build_empty_request(
+            "DELETE",
+            &format!("/api/v1/workspaces/{}/members/{}", workspace_id, owner_id),
+        ),
+        &token_a,
+    );
+    assert_eq!(
+        app.clone().oneshot(remove_req).await.unwrap().status(),
+        StatusCode::BAD_REQUEST
+    );
+}
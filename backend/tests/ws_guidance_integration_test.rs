@@ -1,3 +1,5 @@
+mod common;
+
 use axum::Router;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::Value;
@@ -23,7 +25,6 @@ use prompt_faster::domain::types::{
 use prompt_faster::infra::db::pool::create_pool;
 use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
 use prompt_faster::infra::external::http_client::create_http_client;
-use prompt_faster::shared::config::AppConfig;
 use prompt_faster::shared::time::now_millis;
 
 async fn setup_test_app_with_db() -> (Router, AppState) {
@@ -36,25 +37,10 @@ async fn setup_test_app_with_db() -> (Router, AppState) {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-
-        checkpoint_cache_limit: 10,
-
-        checkpoint_memory_alert_threshold: 10,
-    });
+    let config = Arc::new(common::test_app_config());
     let api_key_manager = Arc::new(ApiKeyManager::new(None));
 
-    let session_store = prompt_faster::api::middleware::SessionStore::new(24);
+    let session_store = prompt_faster::api::middleware::SessionStore::new(db.clone(), 24);
     let login_attempt_store = prompt_faster::api::middleware::LoginAttemptStore::default();
 
     let state = AppState {
@@ -64,6 +50,7 @@ async fn setup_test_app_with_db() -> (Router, AppState) {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: prompt_faster::api::middleware::RateLimiter::default(),
     };
 
     let router = Router::<AppState>::new()
@@ -243,7 +230,8 @@ async fn ws_guidance_send_rejected_when_not_paused() {
     let token = state
         .session_store
         .create_session(user_id.to_string(), None)
-        .await;
+        .await
+        .expect("创建会话失败");
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -294,7 +282,8 @@ async fn ws_guidance_send_rejected_when_not_owner() {
     let token = state
         .session_store
         .create_session(other_id.to_string(), None)
-        .await;
+        .await
+        .expect("创建会话失败");
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -339,7 +328,8 @@ async fn ws_guidance_send_validates_input() {
     let token = state
         .session_store
         .create_session(user_id.to_string(), None)
-        .await;
+        .await
+        .expect("创建会话失败");
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -422,7 +412,8 @@ async fn ws_guidance_last_one_wins() {
     let token = state
         .session_store
         .create_session(user_id.to_string(), None)
-        .await;
+        .await
+        .expect("创建会话失败");
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -491,7 +482,8 @@ async fn ws_pause_send_guidance_resume_applies_and_clears() {
     let token = state
         .session_store
         .create_session(user_id.to_string(), None)
-        .await;
+        .await
+        .expect("创建会话失败");
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -1,3 +1,5 @@
+mod common;
+
 use axum::Router;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::Value;
@@ -22,7 +24,6 @@ use prompt_faster::domain::types::{
 use prompt_faster::infra::db::pool::create_pool;
 use prompt_faster::infra::external::api_key_manager::ApiKeyManager;
 use prompt_faster::infra::external::http_client::create_http_client;
-use prompt_faster::shared::config::AppConfig;
 use prompt_faster::shared::time::now_millis;
 
 async fn setup_test_app_with_db() -> (Router, AppState) {
@@ -35,25 +36,10 @@ async fn setup_test_app_with_db() -> (Router, AppState) {
         .expect("运行 migrations 失败");
 
     let http_client = create_http_client().expect("创建 HTTP 客户端失败");
-    let config = Arc::new(AppConfig {
-        database_url: "sqlite::memory:".to_string(),
-        server_host: "127.0.0.1".to_string(),
-        server_port: 0,
-        log_level: "info".to_string(),
-        is_dev: true,
-        cors_origins: vec![],
-        is_docker: false,
-        allow_http_base_url: true,
-        allow_localhost_base_url: true,
-        allow_private_network_base_url: true,
-
-        checkpoint_cache_limit: 10,
-
-        checkpoint_memory_alert_threshold: 10,
-    });
+    let config = Arc::new(common::test_app_config());
     let api_key_manager = Arc::new(ApiKeyManager::new(None));
 
-    let session_store = prompt_faster::api::middleware::SessionStore::new(24);
+    let session_store = prompt_faster::api::middleware::SessionStore::new(db.clone(), 24);
     let login_attempt_store = prompt_faster::api::middleware::LoginAttemptStore::default();
 
     let state = AppState {
@@ -63,6 +49,7 @@ async fn setup_test_app_with_db() -> (Router, AppState) {
         api_key_manager,
         session_store,
         login_attempt_store,
+        rate_limiter: prompt_faster::api::middleware::RateLimiter::default(),
     };
 
     let router = Router::<AppState>::new()
@@ -271,7 +258,8 @@ async fn ws_pause_resume_ack_and_events() {
     let token = state
         .session_store
         .create_session(user_id.to_string(), None)
-        .await;
+        .await
+        .expect("创建会话失败");
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -361,7 +349,8 @@ async fn ws_artifact_get_and_update_ack() {
     let token = state
         .session_store
         .create_session(user_id.to_string(), None)
-        .await;
+        .await
+        .expect("创建会话失败");
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -442,7 +431,8 @@ async fn ws_artifact_update_rejected_when_not_paused_or_owner() {
     let other_token = state
         .session_store
         .create_session(other_id.to_string(), None)
-        .await;
+        .await
+        .expect("创建会话失败");
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -475,6 +465,82 @@ async fn ws_artifact_update_rejected_when_not_paused_or_owner() {
     );
 }
 
+#[tokio::test]
+async fn ws_artifact_update_rejected_for_viewer_member() {
+    let (app, state) = setup_test_app_with_db().await;
+
+    let owner_id = "user-ws-share-owner";
+    let workspace_id = "ws-shared";
+    let task_id = "task-shared";
+    seed_user_workspace_task(&state.db, owner_id, workspace_id, task_id).await;
+
+    let viewer_id = "user-ws-viewer";
+    seed_user_workspace_task(&state.db, viewer_id, "ws-viewer", "task-viewer").await;
+    sqlx::query(
+        r#"
+        INSERT INTO workspace_members (workspace_id, user_id, role, invited_by, created_at, updated_at)
+        VALUES (?1, ?2, 'viewer', ?3, ?4, ?4)
+        "#,
+    )
+    .bind(workspace_id)
+    .bind(viewer_id)
+    .bind(owner_id)
+    .bind(now_millis())
+    .execute(&state.db)
+    .await
+    .expect("insert viewer member");
+
+    let viewer_token = state
+        .session_store
+        .create_session(viewer_id.to_string(), None)
+        .await
+        .expect("创建会话失败");
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service())
+            .await
+            .expect("serve");
+    });
+
+    let ws_url = format!("ws://{}/api/v1/ws?token={}", addr, viewer_token);
+    let (mut socket, _) = connect_async(ws_url).await.expect("connect ws");
+
+    let update_cmd = serde_json::json!({
+        "type": "artifact:update",
+        "payload": { "taskId": task_id, "artifacts": sample_artifacts_payload() },
+        "correlationId": "cid-viewer"
+    });
+    socket
+        .send(tokio_tungstenite::tungstenite::Message::Text(
+            update_cmd.to_string(),
+        ))
+        .await
+        .expect("send artifact:update");
+
+    let update_ack = read_message_of_type(&mut socket, "artifact:update:ack").await;
+    assert_eq!(update_ack["payload"]["ok"], false);
+    assert_eq!(
+        update_ack["payload"]["reason"].as_str(),
+        Some("task_not_found_or_forbidden")
+    );
+
+    // 拒绝写入安全审计日志
+    let denied: (String, String, String, String) = sqlx::query_as(
+        "SELECT actor_user_id, target_id, correlation_id, details FROM audit_events WHERE event_type = 'task_access_denied'",
+    )
+    .fetch_one(&state.db)
+    .await
+    .expect("缺少任务访问拒绝审计事件");
+    assert_eq!(denied.0, viewer_id);
+    assert_eq!(denied.1, task_id);
+    assert_eq!(denied.2, "cid-viewer");
+    let details: serde_json::Value = serde_json::from_str(&denied.3).unwrap();
+    assert_eq!(details["command"], "artifact:update");
+    assert_eq!(details["reason"], "read_only_member");
+}
+
 #[tokio::test]
 async fn ws_artifact_update_rejected_when_not_paused() {
     let (app, state) = setup_test_app_with_db().await;
@@ -487,7 +553,8 @@ async fn ws_artifact_update_rejected_when_not_paused() {
     let token = state
         .session_store
         .create_session(user_id.to_string(), None)
-        .await;
+        .await
+        .expect("创建会话失败");
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -532,7 +599,8 @@ async fn ws_pause_edit_resume_flow_applies_artifacts() {
     let token = state
         .session_store
         .create_session(user_id.to_string(), None)
-        .await;
+        .await
+        .expect("创建会话失败");
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -46,6 +46,25 @@ const defaultTaskConfig: OptimizationTaskResponse['config'] = {
   },
   teacher_llm: { model_id: null },
   advanced_data_split: { strategy: 'percent', k_fold_folds: 5, sampling_strategy: 'random' },
+  execution_cache: { enabled: false, ttl_seconds: 604800 },
+  execution_retry: { max_retries: 1, max_failure_percent: 20 },
+  command_target: {
+    program: '',
+    args: [],
+    timeout_ms: 30000,
+    env_allowlist: [],
+    max_concurrency: null,
+  },
+  http_target: {
+    method: 'POST',
+    url: '',
+    headers: {},
+    body_template: '{"prompt": {{prompt}}, "input": {{input}}}',
+    output_path: '$.output',
+    usage_path: null,
+    timeout_ms: 60000,
+  },
+  execution_credential_id: null,
 }
 
 const server = setupServer(
@@ -101,6 +120,7 @@ const server = setupServer(
       id: `ws-${workspaces.length + 1}`,
       name: body.name ?? '新工作区',
       description: body.description ?? null,
+      role: 'owner',
       created_at: 1,
       updated_at: 1,
     }
@@ -247,8 +267,8 @@ describe('App routes', () => {
     useWorkspaceStore.getState().reset()
 
     workspaces = [
-      { id: 'ws-1', name: '工作区 1', description: null, created_at: 1, updated_at: 1 },
-      { id: 'ws-2', name: '工作区 2', description: null, created_at: 1, updated_at: 1 },
+      { id: 'ws-1', name: '工作区 1', description: null, role: 'owner', created_at: 1, updated_at: 1 },
+      { id: 'ws-2', name: '工作区 2', description: null, role: 'owner', created_at: 1, updated_at: 1 },
     ]
     tasksByWorkspace = {
       'ws-1': [
@@ -706,7 +726,7 @@ describe('App routes', () => {
     })
     useWorkspaceStore.getState().setLastWorkspaceId('u1', 'ws-1')
 
-    workspaces = [{ id: 'ws-1', name: '工作区 1', description: null, created_at: 1, updated_at: 1 }]
+    workspaces = [{ id: 'ws-1', name: '工作区 1', description: null, role: 'owner', created_at: 1, updated_at: 1 }]
     tasksByWorkspace = { 'ws-1': [] }
     testSetsByWorkspace = { 'ws-1': [] }
 
@@ -4,6 +4,7 @@ import { Button } from '@/components/ui/button';
 import { DifyCredentialForm } from './DifyCredentialForm';
 import { GenericLlmCredentialForm } from './GenericLlmCredentialForm';
 import { TeacherModelParamsForm } from './TeacherModelParamsForm';
+import { CredentialProfilesPanel } from './CredentialProfilesPanel';
 import { FeedbackAlert } from './FeedbackAlert';
 import { useFeedback } from './hooks/useFeedback';
 import { useLoadApiConfig, useSaveApiConfig, buildSaveConfigRequest } from './hooks/useApiConfig';
@@ -136,6 +137,9 @@ export function ApiConfigPanel() {
           </div>
         </CardContent>
       </Card>
+
+      {/* 命名凭证（多组凭证 / 默认凭证切换） */}
+      <CredentialProfilesPanel />
     </div>
   );
 }
@@ -0,0 +1,177 @@
+import { useState, type FormEvent } from 'react';
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
+import { Input } from '@/components/ui/input';
+import { Button } from '@/components/ui/button';
+import { Label } from '@/components/ui/label';
+import { Badge } from '@/components/ui/badge';
+import { useCredentialProfileMutations, useCredentialProfiles } from './hooks/useCredentialProfiles';
+import { useLlmProviders } from './hooks/useLlmProviders';
+
+const CREDENTIAL_TYPE_LABELS: Record<string, string> = {
+  dify: 'Dify',
+  generic_llm: '通用大模型',
+};
+
+/**
+ * 命名凭证管理
+ *
+ * @description 每种凭证类型可保存多条命名凭证（如不同 Dify 应用、不同计费账号）；
+ * 测试集 / 优化任务可引用指定凭证，未引用时使用默认凭证。
+ */
+export function CredentialProfilesPanel() {
+  const { data: profiles = [], isLoading } = useCredentialProfiles();
+  const { create, setDefault, remove } = useCredentialProfileMutations();
+  const providers = useLlmProviders();
+
+  const [credentialType, setCredentialType] = useState('dify');
+  const [name, setName] = useState('');
+  const [provider, setProvider] = useState(providers[0]?.id ?? '');
+  const [baseUrl, setBaseUrl] = useState('');
+  const [apiKey, setApiKey] = useState('');
+
+  const error = create.error ?? setDefault.error ?? remove.error;
+  const errorMessage = error instanceof Error ? error.message : null;
+
+  const handleCreate = (e: FormEvent) => {
+    e.preventDefault();
+    create.mutate(
+      {
+        credential_type: credentialType,
+        name: name.trim(),
+        provider: credentialType === 'generic_llm' ? provider : undefined,
+        base_url: baseUrl.trim(),
+        api_key: apiKey,
+      },
+      {
+        onSuccess: () => {
+          setName('');
+          setApiKey('');
+        },
+      }
+    );
+  };
+
+  return (
+    <Card data-testid="credential-profiles-panel">
+      <CardHeader>
+        <CardTitle>命名凭证</CardTitle>
+        <CardDescription>
+          为不同应用或计费账号保存多组凭证；测试集与优化任务可单独选择，未选择时使用默认凭证
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-6">
+        {isLoading ? (
+          <div className="text-sm text-muted-foreground">加载中...</div>
+        ) : profiles.length === 0 ? (
+          <div className="text-sm text-muted-foreground">暂无凭证</div>
+        ) : (
+          <ul className="space-y-2">
+            {profiles.map((profile) => (
+              <li
+                key={profile.id}
+                className="flex items-center justify-between gap-2 rounded-md border px-3 py-2 text-sm"
+                data-testid={`credential-profile-${profile.id}`}
+              >
+                <div className="min-w-0">
+                  <div className="flex items-center gap-2">
+                    <span className="font-medium">{profile.name}</span>
+                    <Badge variant="outline">
+                      {CREDENTIAL_TYPE_LABELS[profile.credential_type] ?? profile.credential_type}
+                    </Badge>
+                    {profile.is_default && <Badge>默认</Badge>}
+                  </div>
+                  <div className="truncate text-xs text-muted-foreground">
+                    {profile.provider ? `${profile.provider} · ` : ''}
+                    {profile.base_url} · {profile.masked_api_key ?? '****'}
+                  </div>
+                </div>
+                <div className="flex shrink-0 gap-2">
+                  {!profile.is_default && (
+                    <Button
+                      type="button"
+                      size="sm"
+                      variant="outline"
+                      disabled={setDefault.isPending}
+                      onClick={() => setDefault.mutate(profile.id)}
+                    >
+                      设为默认
+                    </Button>
+                  )}
+                  <Button
+                    type="button"
+                    size="sm"
+                    variant="ghost"
+                    disabled={remove.isPending}
+                    onClick={() => remove.mutate(profile.id)}
+                  >
+                    删除
+                  </Button>
+                </div>
+              </li>
+            ))}
+          </ul>
+        )}
+
+        <form className="grid gap-2 border-t pt-4" onSubmit={handleCreate}>
+          <Label htmlFor="credential-profile-type">凭证类型</Label>
+          <select
+            id="credential-profile-type"
+            className="h-9 rounded-md border bg-transparent px-3 text-sm"
+            value={credentialType}
+            onChange={(e) => setCredentialType(e.target.value)}
+          >
+            <option value="dify">Dify</option>
+            <option value="generic_llm">通用大模型</option>
+          </select>
+          {credentialType === 'generic_llm' && (
+            <>
+              <Label htmlFor="credential-profile-provider">Provider</Label>
+              <select
+                id="credential-profile-provider"
+                className="h-9 rounded-md border bg-transparent px-3 text-sm"
+                value={provider}
+                onChange={(e) => setProvider(e.target.value)}
+              >
+                {providers.map((p) => (
+                  <option key={p.id} value={p.id}>
+                    {p.display_name}
+                  </option>
+                ))}
+              </select>
+            </>
+          )}
+          <Label htmlFor="credential-profile-name">名称</Label>
+          <Input
+            id="credential-profile-name"
+            value={name}
+            maxLength={64}
+            onChange={(e) => setName(e.target.value)}
+          />
+          <Label htmlFor="credential-profile-base-url">Base URL</Label>
+          <Input
+            id="credential-profile-base-url"
+            value={baseUrl}
+            onChange={(e) => setBaseUrl(e.target.value)}
+          />
+          <Label htmlFor="credential-profile-api-key">API Key</Label>
+          <Input
+            id="credential-profile-api-key"
+            type="password"
+            autoComplete="off"
+            value={apiKey}
+            onChange={(e) => setApiKey(e.target.value)}
+          />
+          {errorMessage && <div className="text-sm text-destructive">{errorMessage}</div>}
+          <div>
+            <Button
+              type="submit"
+              disabled={create.isPending || !name.trim() || !baseUrl.trim() || !apiKey}
+            >
+              {create.isPending ? '保存中...' : '添加凭证'}
+            </Button>
+          </div>
+        </form>
+      </CardContent>
+    </Card>
+  );
+}
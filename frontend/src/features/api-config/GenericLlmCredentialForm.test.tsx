@@ -56,6 +56,19 @@ describe('GenericLlmCredentialForm 组件', () => {
   });
 
   describe('Provider 选择交互', () => {
+    it('未登录时应展示内置 Provider 注册表并按 Provider 提示默认地址', async () => {
+      const user = userEvent.setup();
+      renderWithProviders(<GenericLlmCredentialForm />);
+
+      for (const name of ['OpenAI', 'DeepSeek', 'Azure OpenAI', 'Anthropic', 'OpenAI 兼容（自建）']) {
+        expect(screen.getByRole('button', { name })).toBeInTheDocument();
+      }
+
+      await user.click(screen.getByRole('button', { name: 'Anthropic' }));
+      expect(screen.getByLabelText('API 地址')).toHaveAttribute('placeholder', 'https://api.anthropic.com');
+      expect(useCredentialStore.getState().genericLlm.provider).toBe('anthropic');
+    });
+
     it('点击硅基流动后应显示表单字段', async () => {
       const user = userEvent.setup();
       renderWithProviders(<GenericLlmCredentialForm />);
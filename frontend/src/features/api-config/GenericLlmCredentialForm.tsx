@@ -9,30 +9,19 @@ import { useFeedback } from './hooks/useFeedback';
 import { useTestGenericLlmConnection } from './hooks/useTestConnection';
 import { FeedbackAlert } from './FeedbackAlert';
 import { API_KEY_MAX_LENGTH, BASE_URL_MAX_LENGTH } from './constants';
-import { statusBadgeMap, type GenericLlmProvider } from '@/types/credentials';
+import { useLlmProviders } from './hooks/useLlmProviders';
+import { statusBadgeMap } from '@/types/credentials';
 import { useConnectivity } from '@/features/checkpoint-recovery/hooks/useConnectivity';
 
 
-/**
- * Provider 配置信息
- */
-const PROVIDER_CONFIG: Record<GenericLlmProvider, { label: string; urlPlaceholder: string; keyPlaceholder: string }> = {
-  siliconflow: {
-    label: '硅基流动',
-    urlPlaceholder: 'https://api.siliconflow.cn',
-    keyPlaceholder: 'sk-xxxxxxxxxxxxxxxx',
-  },
-  modelscope: {
-    label: '魔搭社区',
-    urlPlaceholder: 'https://dashscope.aliyuncs.com/compatible-mode',
-    keyPlaceholder: 'sk-xxxxxxxxxxxxxxxx',
-  },
-};
+/** 未提供默认地址的 Provider（Azure / 自建服务）使用的占位提示 */
+const CUSTOM_URL_PLACEHOLDER = 'https://your-endpoint.example.com';
+const KEY_PLACEHOLDER = 'sk-xxxxxxxxxxxxxxxx';
 
 /**
  * 通用大模型凭证表单组件
  * 
- * @description 提供硅基流动和魔搭社区的 API 凭证配置界面
+ * @description 提供通用大模型的 API 凭证配置界面（Provider 列表来自后端注册表：内置 + 用户自定义）
  * @example
  * ```tsx
  * <GenericLlmCredentialForm />
@@ -56,6 +45,9 @@ export function GenericLlmCredentialForm() {
   // 使用共享的 useFeedback Hook
   const { feedback, showFeedback } = useFeedback();
 
+  const providers = useLlmProviders();
+  const selectedProvider = providers.find((p) => p.id === provider) ?? null;
+
   // 连接测试 mutation
   const testConnection = useTestGenericLlmConnection();
   const { isOffline } = useConnectivity();
@@ -68,7 +60,13 @@ export function GenericLlmCredentialForm() {
     if (!isFormComplete || !provider) return;
     
     try {
-      const result = await testConnection.mutateAsync({ baseUrl, apiKey, provider });
+      const result = await testConnection.mutateAsync({
+        baseUrl,
+        apiKey,
+        provider,
+        // 自定义 Provider 需携带描述，后端据此确定端点与鉴权方式
+        providerSpec: selectedProvider && !selectedProvider.builtin ? selectedProvider : undefined,
+      });
       // 展示后端返回的 message（包含模型数量）
       // 如果有模型列表，显示前 5 个作为预览
       let feedbackMessage = result?.message || '连接成功';
@@ -142,23 +140,18 @@ export function GenericLlmCredentialForm() {
       {/* Provider 选择器 */}
       <div className="space-y-2">
         <Label>Provider 类型</Label>
-        <div className="flex gap-2">
-          <Button
-            type="button"
-            variant={provider === 'siliconflow' ? 'default' : 'outline'}
-            onClick={() => handleProviderChange('siliconflow')}
-            data-testid="provider-siliconflow"
-          >
-            {PROVIDER_CONFIG.siliconflow.label}
-          </Button>
-          <Button
-            type="button"
-            variant={provider === 'modelscope' ? 'default' : 'outline'}
-            onClick={() => handleProviderChange('modelscope')}
-            data-testid="provider-modelscope"
-          >
-            {PROVIDER_CONFIG.modelscope.label}
-          </Button>
+        <div className="flex flex-wrap gap-2">
+          {providers.map((p) => (
+            <Button
+              key={p.id}
+              type="button"
+              variant={provider === p.id ? 'default' : 'outline'}
+              onClick={() => handleProviderChange(p.id)}
+              data-testid={`provider-${p.id}`}
+            >
+              {p.display_name}
+            </Button>
+          ))}
         </div>
       </div>
 
@@ -170,7 +163,7 @@ export function GenericLlmCredentialForm() {
             <Input
               id="generic-llm-base-url"
               type="text"
-              placeholder={PROVIDER_CONFIG[provider].urlPlaceholder}
+              placeholder={selectedProvider?.default_base_url ?? CUSTOM_URL_PLACEHOLDER}
               value={baseUrl}
               onChange={(e) => handleBaseUrlChange(e.target.value)}
               onBlur={() => handleBlur('baseUrl')}
@@ -190,7 +183,7 @@ export function GenericLlmCredentialForm() {
             <Input
               id="generic-llm-api-key"
               type="password"
-              placeholder={PROVIDER_CONFIG[provider].keyPlaceholder}
+              placeholder={KEY_PLACEHOLDER}
               value={apiKey}
               onChange={(e) => handleApiKeyChange(e.target.value)}
               onBlur={() => handleBlur('apiKey')}
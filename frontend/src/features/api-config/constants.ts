@@ -3,8 +3,48 @@
  * 由 Dify 和 Generic LLM 表单共用
  */
 
+import type { LlmProviderSpec } from '@/types/generated/models/LlmProviderSpec';
+
 /** API Key 最大长度限制（防止超长输入） */
 export const API_KEY_MAX_LENGTH = 256;
 
 /** Base URL 最大长度限制 */
 export const BASE_URL_MAX_LENGTH = 512;
+
+/**
+ * 内置 Provider 列表（与后端 LlmProviderRegistry::builtin 保持一致）
+ *
+ * 未登录或 Provider 列表加载失败时作为兜底选项。
+ */
+export const BUILTIN_LLM_PROVIDERS: LlmProviderSpec[] = [
+  builtinProvider('siliconflow', '硅基流动', 'https://api.siliconflow.cn'),
+  builtinProvider('modelscope', '魔搭社区', 'https://dashscope.aliyuncs.com/compatible-mode'),
+  builtinProvider('openai', 'OpenAI', 'https://api.openai.com'),
+  builtinProvider('deepseek', 'DeepSeek', 'https://api.deepseek.com'),
+  {
+    ...builtinProvider('azure_openai', 'Azure OpenAI', null),
+    auth_style: 'api_key_header',
+    models_path: '/openai/models?api-version=2024-10-21',
+    chat_path: '/openai/deployments/{model}/chat/completions?api-version=2024-10-21',
+  },
+  {
+    ...builtinProvider('anthropic', 'Anthropic', 'https://api.anthropic.com'),
+    auth_style: 'x_api_key',
+    chat_path: '/v1/messages',
+    api_format: 'anthropic_messages',
+  },
+  builtinProvider('openai_compatible', 'OpenAI 兼容（自建）', null),
+];
+
+function builtinProvider(id: string, displayName: string, defaultBaseUrl: string | null): LlmProviderSpec {
+  return {
+    id,
+    display_name: displayName,
+    default_base_url: defaultBaseUrl,
+    auth_style: 'bearer',
+    models_path: '/v1/models',
+    chat_path: '/v1/chat/completions',
+    api_format: 'openai_chat',
+    builtin: true,
+  };
+}
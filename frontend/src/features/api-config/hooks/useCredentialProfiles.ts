@@ -0,0 +1,71 @@
+/**
+ * 命名凭证 Hooks
+ * 每种凭证类型可保存多条命名凭证，其中一条为默认凭证
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import {
+  createCredentialProfile,
+  deleteCredentialProfile,
+  listCredentialProfiles,
+  setDefaultCredentialProfile,
+} from '../services/configService';
+import { useAuthStore } from '@/stores/useAuthStore';
+import type { CreateCredentialRequest } from '@/types/generated/api/CreateCredentialRequest';
+
+/** 命名凭证查询 key 前缀（任务配置 / 测试集页面的凭证选择器共用） */
+export const CREDENTIAL_PROFILES_QUERY_KEY = ['credential-profiles'] as const;
+
+function useSessionToken() {
+  const sessionToken = useAuthStore((state) => state.sessionToken);
+  const authStatus = useAuthStore((state) => state.authStatus);
+  return authStatus === 'authenticated' && sessionToken ? sessionToken : null;
+}
+
+/**
+ * 查询命名凭证列表（可按类型过滤）
+ */
+export function useCredentialProfiles(credentialType?: string) {
+  const sessionToken = useSessionToken();
+
+  return useQuery({
+    queryKey: [...CREDENTIAL_PROFILES_QUERY_KEY, credentialType ?? 'all'],
+    queryFn: async () => (await listCredentialProfiles(sessionToken!, credentialType)).credentials,
+    enabled: !!sessionToken,
+    staleTime: 60_000,
+  });
+}
+
+/**
+ * 命名凭证增删 / 切换默认
+ */
+export function useCredentialProfileMutations() {
+  const sessionToken = useSessionToken();
+  const queryClient = useQueryClient();
+  const invalidate = () =>
+    queryClient.invalidateQueries({ queryKey: CREDENTIAL_PROFILES_QUERY_KEY });
+
+  const requireToken = () => {
+    if (!sessionToken) {
+      throw new Error('未登录');
+    }
+    return sessionToken;
+  };
+
+  const create = useMutation({
+    mutationFn: (request: CreateCredentialRequest) =>
+      createCredentialProfile(request, requireToken()),
+    onSuccess: invalidate,
+  });
+  const setDefault = useMutation({
+    mutationFn: (credentialId: string) =>
+      setDefaultCredentialProfile(credentialId, requireToken()),
+    onSuccess: invalidate,
+  });
+  const remove = useMutation({
+    mutationFn: (credentialId: string) => deleteCredentialProfile(credentialId, requireToken()),
+    onSuccess: invalidate,
+  });
+
+  return { create, setDefault, remove };
+}
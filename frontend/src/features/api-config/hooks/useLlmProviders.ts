@@ -0,0 +1,28 @@
+/**
+ * 大模型 Provider 列表 Hook
+ *
+ * 已登录时从后端加载（内置 + 用户自定义），否则使用内置 Provider 兜底。
+ */
+
+import { useQuery } from '@tanstack/react-query';
+import { useAuthStore } from '@/stores/useAuthStore';
+import { listLlmProviders } from '../services/configService';
+import { BUILTIN_LLM_PROVIDERS } from '../constants';
+import type { LlmProviderSpec } from '@/types/generated/models/LlmProviderSpec';
+
+export const LLM_PROVIDERS_QUERY_KEY = ['llm-providers'] as const;
+
+export function useLlmProviders(): LlmProviderSpec[] {
+  const sessionToken = useAuthStore((state) => state.sessionToken);
+  const authStatus = useAuthStore((state) => state.authStatus);
+  const isAuthenticated = authStatus === 'authenticated' && !!sessionToken;
+
+  const { data } = useQuery({
+    queryKey: LLM_PROVIDERS_QUERY_KEY,
+    queryFn: () => listLlmProviders(sessionToken!),
+    enabled: isAuthenticated,
+    staleTime: 60_000,
+  });
+
+  return data?.providers.length ? data.providers : BUILTIN_LLM_PROVIDERS;
+}
@@ -12,6 +12,7 @@ import {
   testGenericLlmConnection,
 } from '../services/credentialService';
 import type { TestConnectionResult } from '@/types/generated/api/TestConnectionResult';
+import type { LlmProviderSpec } from '@/types/generated/models/LlmProviderSpec';
 
 /**
  * Dify 连接测试 Mutation Hook
@@ -69,15 +70,19 @@ export function useTestGenericLlmConnection() {
       baseUrl,
       apiKey,
       provider,
+      providerSpec,
     }: {
       baseUrl: string;
       apiKey: string;
       provider: string;
+      /** 用户自定义 Provider 描述（内置 Provider 无需提供） */
+      providerSpec?: LlmProviderSpec;
     }) => {
       const response = await testGenericLlmConnection({
         base_url: baseUrl,
         api_key: apiKey,
         provider,
+        ...(providerSpec ? { provider_spec: providerSpec } : {}),
       });
       if (isApiError(response)) {
         throw new Error(response.error.message);
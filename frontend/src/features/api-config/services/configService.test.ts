@@ -6,7 +6,13 @@
 import { describe, it, expect, beforeAll, afterAll, afterEach } from 'vitest';
 import { setupServer } from 'msw/node';
 import { http, HttpResponse } from 'msw';
-import { getConfig, saveConfig } from './configService';
+import {
+  createCredentialProfile,
+  getConfig,
+  listCredentialProfiles,
+  saveConfig,
+  setDefaultCredentialProfile,
+} from './configService';
 import type { ApiConfigResponse } from '@/types/credentials';
 import type { SaveConfigRequest } from '@/types/generated/api/SaveConfigRequest';
 
@@ -190,4 +196,83 @@ describe('configService', () => {
       );
     });
   });
+
+  describe('credential profiles', () => {
+    const profile = {
+      id: 'cred-2',
+      credential_type: 'dify',
+      name: 'app-b',
+      provider: null,
+      base_url: 'https://dify.example.com',
+      is_default: false,
+      masked_api_key: 'sk-****b123',
+      created_at: 1,
+      updated_at: 1,
+    };
+
+    it('应该按类型查询命名凭证', async () => {
+      let requestedType: string | null = null;
+      server.use(
+        http.get('http://localhost:3000/api/v1/auth/credentials', ({ request }) => {
+          requestedType = new URL(request.url).searchParams.get('credential_type');
+          return HttpResponse.json({ data: { credentials: [profile] } });
+        })
+      );
+
+      const result = await listCredentialProfiles('test-token', 'dify');
+
+      expect(requestedType).toBe('dify');
+      expect(result.credentials).toEqual([profile]);
+    });
+
+    it('应该创建命名凭证并透传名称冲突错误', async () => {
+      server.use(
+        http.post('http://localhost:3000/api/v1/auth/credentials', async ({ request }) => {
+          const body = (await request.json()) as { name: string };
+          if (body.name === 'app-b') {
+            return HttpResponse.json({ data: profile });
+          }
+          return HttpResponse.json(
+            { error: { code: 'VALIDATION_ERROR', message: '同类型凭证名称已存在: dup' } },
+            { status: 409 }
+          );
+        })
+      );
+
+      const created = await createCredentialProfile(
+        {
+          credential_type: 'dify',
+          name: 'app-b',
+          base_url: 'https://dify.example.com',
+          api_key: 'sk-dify-b',
+        },
+        'test-token'
+      );
+      expect(created.id).toBe('cred-2');
+
+      await expect(
+        createCredentialProfile(
+          {
+            credential_type: 'dify',
+            name: 'dup',
+            base_url: 'https://dify.example.com',
+            api_key: 'sk-dify-b',
+          },
+          'test-token'
+        )
+      ).rejects.toThrow('同类型凭证名称已存在');
+    });
+
+    it('应该设置默认凭证', async () => {
+      server.use(
+        http.post('http://localhost:3000/api/v1/auth/credentials/cred-2/default', () =>
+          HttpResponse.json({ data: { ...profile, is_default: true } })
+        )
+      );
+
+      const result = await setDefaultCredentialProfile('cred-2', 'test-token');
+
+      expect(result.is_default).toBe(true);
+    });
+  });
 });
@@ -5,6 +5,11 @@
 
 import { UnauthorizedError, apiRequestWithAuth, isApiError } from '@/lib/api';
 import type { ConfigResponse } from '@/types/generated/api/ConfigResponse';
+import type { CreateCredentialRequest } from '@/types/generated/api/CreateCredentialRequest';
+import type { CredentialListResponse } from '@/types/generated/api/CredentialListResponse';
+import type { CredentialProfileResponse } from '@/types/generated/api/CredentialProfileResponse';
+import type { DeleteCredentialResponse } from '@/types/generated/api/DeleteCredentialResponse';
+import type { LlmProvidersResponse } from '@/types/generated/api/LlmProvidersResponse';
 import type { SaveConfigRequest } from '@/types/generated/api/SaveConfigRequest';
 import type { SaveConfigResponse } from '@/types/generated/api/SaveConfigResponse';
 
@@ -55,3 +60,127 @@ export async function saveConfig(
 
   return response.data;
 }
+
+/**
+ * 获取大模型 Provider 列表（内置 + 当前用户自定义）
+ */
+export async function listLlmProviders(token: string): Promise<LlmProvidersResponse> {
+  const response = await apiRequestWithAuth<LlmProvidersResponse>(
+    '/auth/llm-providers',
+    {
+      method: 'GET',
+    },
+    token
+  );
+
+  if (isApiError(response)) {
+    if (response.error.code === 'UNAUTHORIZED') {
+      throw new UnauthorizedError(response.error.message);
+    }
+    throw new Error(response.error.message);
+  }
+
+  return response.data;
+}
+
+/**
+ * 获取命名凭证列表（API Key 仅返回脱敏值）
+ */
+export async function listCredentialProfiles(
+  token: string,
+  credentialType?: string
+): Promise<CredentialListResponse> {
+  const query = credentialType ? `?credential_type=${encodeURIComponent(credentialType)}` : '';
+  const response = await apiRequestWithAuth<CredentialListResponse>(
+    `/auth/credentials${query}`,
+    {
+      method: 'GET',
+    },
+    token
+  );
+
+  if (isApiError(response)) {
+    if (response.error.code === 'UNAUTHORIZED') {
+      throw new UnauthorizedError(response.error.message);
+    }
+    throw new Error(response.error.message);
+  }
+
+  return response.data;
+}
+
+/**
+ * 新建命名凭证
+ */
+export async function createCredentialProfile(
+  request: CreateCredentialRequest,
+  token: string
+): Promise<CredentialProfileResponse> {
+  const response = await apiRequestWithAuth<CredentialProfileResponse>(
+    '/auth/credentials',
+    {
+      method: 'POST',
+      body: JSON.stringify(request),
+    },
+    token
+  );
+
+  if (isApiError(response)) {
+    if (response.error.code === 'UNAUTHORIZED') {
+      throw new UnauthorizedError(response.error.message);
+    }
+    throw new Error(response.error.message);
+  }
+
+  return response.data;
+}
+
+/**
+ * 将命名凭证设为其类型的默认凭证
+ */
+export async function setDefaultCredentialProfile(
+  credentialId: string,
+  token: string
+): Promise<CredentialProfileResponse> {
+  const response = await apiRequestWithAuth<CredentialProfileResponse>(
+    `/auth/credentials/${encodeURIComponent(credentialId)}/default`,
+    {
+      method: 'POST',
+    },
+    token
+  );
+
+  if (isApiError(response)) {
+    if (response.error.code === 'UNAUTHORIZED') {
+      throw new UnauthorizedError(response.error.message);
+    }
+    throw new Error(response.error.message);
+  }
+
+  return response.data;
+}
+
+/**
+ * 删除命名凭证
+ */
+export async function deleteCredentialProfile(
+  credentialId: string,
+  token: string
+): Promise<DeleteCredentialResponse> {
+  const response = await apiRequestWithAuth<DeleteCredentialResponse>(
+    `/auth/credentials/${encodeURIComponent(credentialId)}`,
+    {
+      method: 'DELETE',
+    },
+    token
+  );
+
+  if (isApiError(response)) {
+    if (response.error.code === 'UNAUTHORIZED') {
+      throw new UnauthorizedError(response.error.message);
+    }
+    throw new Error(response.error.message);
+  }
+
+  return response.data;
+}
@@ -97,6 +97,35 @@ describe('credentialService', () => {
       });
     });
 
+    it('自定义 Provider 应携带 provider_spec', async () => {
+      const mockResponse = { data: { message: '连接成功', models: ['qwen2.5'] } };
+      vi.mocked(api.post).mockResolvedValue(mockResponse);
+      const providerSpec = {
+        id: 'my-vllm',
+        display_name: 'My vLLM',
+        default_base_url: null,
+        auth_style: 'bearer' as const,
+        models_path: '/v1/models',
+        chat_path: '/v1/chat/completions',
+        api_format: 'openai_chat' as const,
+        builtin: false,
+      };
+
+      await testGenericLlmConnection({
+        base_url: 'http://localhost:8000',
+        api_key: 'sk-test-key',
+        provider: 'my-vllm',
+        provider_spec: providerSpec,
+      });
+
+      expect(api.post).toHaveBeenCalledWith('/auth/test-connection/generic-llm', {
+        base_url: 'http://localhost:8000',
+        api_key: 'sk-test-key',
+        provider: 'my-vllm',
+        provider_spec: providerSpec,
+      });
+    });
+
     it('成功时应返回包含模型列表的响应', async () => {
       const mockResponse = {
         data: {
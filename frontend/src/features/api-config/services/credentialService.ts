@@ -30,7 +30,8 @@ export function testDifyConnection(
  * 
  * @param baseUrl - API 基础 URL
  * @param apiKey - API Key
- * @param provider - Provider 标识 ("siliconflow" | "modelscope")
+ * @param provider - Provider 标识（内置或用户自定义 Provider ID）
+ * @param provider_spec - 用户自定义 Provider 描述（内置 Provider 无需提供）
  * @returns API 响应（成功时包含 TestConnectionResult 和模型列表）
  */
 export function testGenericLlmConnection(
@@ -40,5 +41,6 @@ export function testGenericLlmConnection(
     base_url: payload.base_url,
     api_key: payload.api_key,
     provider: payload.provider,
+    ...(payload.provider_spec ? { provider_spec: payload.provider_spec } : {}),
   });
 }
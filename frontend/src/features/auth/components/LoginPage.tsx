@@ -6,7 +6,7 @@
 import { useState, useEffect } from 'react'
 import { useLocation, useNavigate } from 'react-router'
 import { useAuthStore } from '@/stores/useAuthStore'
-import { getSystemStatus } from '../services/authService'
+import { TOTP_REQUIRED_ERROR_CODE, getSystemStatus } from '../services/authService'
 import { isApiError } from '@/lib/api'
 
 export function LoginPage() {
@@ -24,6 +24,8 @@ export function LoginPage() {
   const [username, setUsername] = useState('')
   const [password, setPassword] = useState('')
   const [confirmPassword, setConfirmPassword] = useState('')
+  const [totpRequired, setTotpRequired] = useState(false)
+  const [totpCode, setTotpCode] = useState('')
   const [error, setError] = useState<string | null>(null)
   const [loading, setLoading] = useState(false)
   const [checkingStatus, setCheckingStatus] = useState(true)
@@ -79,16 +81,30 @@ export function LoginPage() {
       setError('密码长度至少 6 个字符')
       return
     }
+    if (totpRequired && !totpCode.trim()) {
+      setError('请输入两步验证码或恢复码')
+      return
+    }
 
     setLoading(true)
 
     try {
       const response = isRegisterMode
         ? await register({ username: username.trim(), password })
-        : await login({ username: username.trim(), password })
+        : await login({
+            username: username.trim(),
+            password,
+            ...(totpRequired ? { totp_code: totpCode.trim() } : {}),
+          })
 
       if (isApiError(response)) {
-        setError(response.error.message)
+        if (response.error.code === TOTP_REQUIRED_ERROR_CODE) {
+          // 密码已通过校验，进入两步验证
+          setTotpRequired(true)
+          setError(null)
+        } else {
+          setError(response.error.message)
+        }
       } else {
         navigate(redirectTo, { replace: true })
       }
@@ -140,7 +156,11 @@ export function LoginPage() {
                 className="appearance-none rounded-none relative block w-full px-3 py-2 border border-gray-300 placeholder-gray-500 text-gray-900 rounded-t-md focus:outline-none focus:ring-blue-500 focus:border-blue-500 focus:z-10 sm:text-sm"
                 placeholder="用户名"
                 value={username}
-                onChange={(e) => setUsername(e.target.value)}
+                onChange={(e) => {
+                  setUsername(e.target.value)
+                  setTotpRequired(false)
+                  setTotpCode('')
+                }}
                 disabled={loading}
               />
             </div>
@@ -163,6 +183,27 @@ export function LoginPage() {
                 disabled={loading}
               />
             </div>
+            {!isRegisterMode && totpRequired && (
+              <div className="pt-4">
+                <label htmlFor="totpCode" className="sr-only">
+                  两步验证码
+                </label>
+                <input
+                  id="totpCode"
+                  name="totpCode"
+                  type="text"
+                  inputMode="numeric"
+                  autoComplete="one-time-code"
+                  autoFocus
+                  data-testid="totp-code-input"
+                  className="appearance-none relative block w-full px-3 py-2 border border-gray-300 placeholder-gray-500 text-gray-900 rounded-md focus:outline-none focus:ring-blue-500 focus:border-blue-500 focus:z-10 sm:text-sm"
+                  placeholder="认证器中的 6 位验证码或恢复码"
+                  value={totpCode}
+                  onChange={(e) => setTotpCode(e.target.value)}
+                  disabled={loading}
+                />
+              </div>
+            )}
             {isRegisterMode && (
               <div>
                 <label htmlFor="confirmPassword" className="sr-only">
@@ -201,6 +242,8 @@ export function LoginPage() {
                 type="button"
                 onClick={() => {
                   setIsRegisterMode(!isRegisterMode)
+                  setTotpRequired(false)
+                  setTotpCode('')
                   setError(null)
                 }}
                 data-testid="toggle-auth-mode"
@@ -7,6 +7,21 @@ import {
   register,
   logout,
   getMe,
+  changePassword,
+  listSessions,
+  revokeSession,
+  revokeAllSessions,
+  listAccessTokens,
+  createAccessToken,
+  revokeAccessToken,
+  getTotpStatus,
+  setupTotp,
+  enableTotp,
+  disableTotp,
+  TOTP_REQUIRED_ERROR_CODE,
+  type AccessTokenInfo,
+  type ChangePasswordResponse,
+  type SessionListResponse,
   type SystemStatusResponse,
   type AuthResponse,
   type UserInfo,
@@ -14,6 +29,18 @@ import {
 
 const API_BASE = 'http://localhost:3000/api/v1'
 
+const ciToken: AccessTokenInfo = {
+  id: 'pat-1',
+  name: 'ci',
+  token_prefix: 'pf_pat_01234567',
+  scope: 'run',
+  workspace_id: null,
+  credential_access: true,
+  created_at: 1,
+  expires_at: null,
+  last_used_at: null,
+}
+
 const server = setupServer(
   http.get(`${API_BASE}/auth/status`, () => {
     const data: SystemStatusResponse = {
@@ -33,7 +60,17 @@ const server = setupServer(
   }),
 
   http.post(`${API_BASE}/auth/login`, async ({ request }) => {
-    const body = (await request.json()) as { username: string; password: string }
+    const body = (await request.json()) as {
+      username: string
+      password: string
+      totp_code?: string
+    }
+    if (body.username === 'totp-user' && !body.totp_code) {
+      return HttpResponse.json(
+        { error: { code: 'AUTH_TOTP_REQUIRED', message: '请输入两步验证码' } },
+        { status: 401 }
+      )
+    }
     const data: AuthResponse = {
       session_token: 'login-token',
       user: { id: 'u-login', username: body.username },
@@ -77,6 +114,142 @@ const server = setupServer(
       username: 'me-user',
     }
     return HttpResponse.json({ data })
+  }),
+
+  http.post(`${API_BASE}/auth/password`, async ({ request }) => {
+    const auth = request.headers.get('authorization')
+    const body = (await request.json()) as { old_password: string; new_password: string }
+    if (auth !== 'Bearer test-token' || body.old_password !== 'old-pass') {
+      return HttpResponse.json(
+        {
+          error: {
+            code: 'AUTH_FAILED',
+            message: '原密码错误',
+          },
+        },
+        { status: 400 }
+      )
+    }
+
+    const data: ChangePasswordResponse = {
+      reencrypted_credentials: 2,
+      migrated_legacy_credentials: 1,
+      revoked_sessions: 1,
+    }
+    return HttpResponse.json({ data })
+  }),
+
+  http.get(`${API_BASE}/auth/sessions`, ({ request }) => {
+    if (request.headers.get('authorization') !== 'Bearer test-token') {
+      return HttpResponse.json(
+        { error: { code: 'UNAUTHORIZED', message: '请先登录' } },
+        { status: 401 }
+      )
+    }
+
+    const data: SessionListResponse = {
+      sessions: [
+        {
+          id: 's-current',
+          user_agent: 'Laptop/1.0',
+          ip_address: '10.0.0.7',
+          created_at: 1,
+          last_seen_at: 2,
+          expires_at: 3,
+          current: true,
+        },
+      ],
+    }
+    return HttpResponse.json({ data })
+  }),
+
+  http.delete(`${API_BASE}/auth/sessions/:sessionId`, ({ params }) => {
+    if (params.sessionId !== 's-other') {
+      return HttpResponse.json(
+        { error: { code: 'NOT_FOUND', message: '会话不存在' } },
+        { status: 404 }
+      )
+    }
+    return HttpResponse.json({ data: { revoked_sessions: 1 } })
+  }),
+
+  http.delete(`${API_BASE}/auth/sessions`, ({ request }) => {
+    const includeCurrent = new URL(request.url).searchParams.get('include_current') === 'true'
+    return HttpResponse.json({ data: { revoked_sessions: includeCurrent ? 3 : 2 } })
+  }),
+
+  http.get(`${API_BASE}/auth/tokens`, () => {
+    return HttpResponse.json({ data: { tokens: [ciToken] } })
+  }),
+
+  http.post(`${API_BASE}/auth/tokens`, async ({ request }) => {
+    const body = (await request.json()) as { name: string; scope: string }
+    if (body.scope !== 'read' && body.scope !== 'run') {
+      return HttpResponse.json(
+        { error: { code: 'VALIDATION_ERROR', message: '无效的令牌权限范围' } },
+        { status: 400 }
+      )
+    }
+    return HttpResponse.json({
+      data: {
+        token: 'pf_pat_0123456789abcdef',
+        access_token: { ...ciToken, name: body.name, scope: body.scope },
+      },
+    })
+  }),
+
+  http.delete(`${API_BASE}/auth/tokens/:tokenId`, ({ params }) => {
+    if (params.tokenId !== ciToken.id) {
+      return HttpResponse.json(
+        { error: { code: 'NOT_FOUND', message: '令牌不存在' } },
+        { status: 404 }
+      )
+    }
+    return HttpResponse.json({ data: { message: '令牌已吊销' } })
+  }),
+
+  http.get(`${API_BASE}/auth/totp`, () => {
+    return HttpResponse.json({
+      data: { enabled: false, pending: false, recovery_codes_remaining: 0 },
+    })
+  }),
+
+  http.post(`${API_BASE}/auth/totp/setup`, async ({ request }) => {
+    const body = (await request.json()) as { password: string }
+    if (body.password !== 'pass123') {
+      return HttpResponse.json(
+        { error: { code: 'AUTH_FAILED', message: '密码错误' } },
+        { status: 400 }
+      )
+    }
+    return HttpResponse.json({
+      data: {
+        secret: 'JBSWY3DPEHPK3PXP',
+        provisioning_uri: 'otpauth://totp/Prompt%20Faster:me?secret=JBSWY3DPEHPK3PXP',
+      },
+    })
+  }),
+
+  http.post(`${API_BASE}/auth/totp/enable`, async ({ request }) => {
+    const body = (await request.json()) as { code: string }
+    if (body.code !== '123456') {
+      return HttpResponse.json(
+        { error: { code: 'AUTH_TOTP_INVALID', message: '验证码无效' } },
+        { status: 400 }
+      )
+    }
+    return HttpResponse.json({ data: { recovery_codes: ['abcde-fghij'] } })
+  }),
+
+  http.post(`${API_BASE}/auth/totp/disable`, async ({ request }) => {
+    const body = (await request.json()) as { password: string; code?: string }
+    if (!body.code) {
+      return HttpResponse.json(
+        { error: { code: 'AUTH_TOTP_REQUIRED', message: '请输入两步验证码' } },
+        { status: 400 }
+      )
+    }
+    return HttpResponse.json({ data: { message: '两步验证已停用' } })
   })
 )
 
@@ -132,4 +305,101 @@ describe('authService', () => {
       expect(res.data.username).toBe('me-user')
     }
   })
+
+  it('changePassword 应携带原密码与新密码并返回重新加密统计', async () => {
+    const res = await changePassword(
+      { old_password: 'old-pass', new_password: 'new-pass' },
+      'test-token'
+    )
+    expect('data' in res).toBe(true)
+
+    if ('data' in res) {
+      expect(res.data.reencrypted_credentials).toBe(2)
+      expect(res.data.revoked_sessions).toBe(1)
+    }
+  })
+
+  it('listSessions 应返回会话列表并标记当前会话', async () => {
+    const res = await listSessions('test-token')
+    expect('data' in res).toBe(true)
+
+    if ('data' in res) {
+      expect(res.data.sessions).toHaveLength(1)
+      expect(res.data.sessions[0].current).toBe(true)
+      expect(res.data.sessions[0].user_agent).toBe('Laptop/1.0')
+    }
+  })
+
+  it('revokeSession 对不存在的会话应返回错误', async () => {
+    const ok = await revokeSession('s-other', 'test-token')
+    expect('data' in ok && ok.data.revoked_sessions).toBe(1)
+
+    const missing = await revokeSession('s-missing', 'test-token')
+    expect('error' in missing).toBe(true)
+  })
+
+  it('revokeAllSessions 默认保留当前会话，includeCurrent 时一并吊销', async () => {
+    const others = await revokeAllSessions('test-token')
+    expect('data' in others && others.data.revoked_sessions).toBe(2)
+
+    const all = await revokeAllSessions('test-token', true)
+    expect('data' in all && all.data.revoked_sessions).toBe(3)
+  })
+
+  it('createAccessToken 应返回一次性令牌明文，非法 scope 返回错误', async () => {
+    const res = await createAccessToken(
+      { name: 'ci', scope: 'read', workspace_id: null, expires_in_days: 30 },
+      'test-token'
+    )
+    expect('data' in res).toBe(true)
+    if ('data' in res) {
+      expect(res.data.token.startsWith('pf_pat_')).toBe(true)
+      expect(res.data.access_token.scope).toBe('read')
+    }
+
+    const invalid = await createAccessToken(
+      { name: 'ci', scope: 'admin', workspace_id: null, expires_in_days: null },
+      'test-token'
+    )
+    expect('error' in invalid).toBe(true)
+  })
+
+  it('listAccessTokens / revokeAccessToken 应列出并吊销令牌', async () => {
+    const list = await listAccessTokens('test-token')
+    expect('data' in list && list.data.tokens[0].token_prefix).toBe('pf_pat_01234567')
+
+    const ok = await revokeAccessToken('pat-1', 'test-token')
+    expect('data' in ok).toBe(true)
+
+    const missing = await revokeAccessToken('pat-missing', 'test-token')
+    expect('error' in missing).toBe(true)
+  })
+
+  it('login 在启用两步验证时应返回 AUTH_TOTP_REQUIRED，携带验证码后成功', async () => {
+    const first = await login({ username: 'totp-user', password: 'pass123' })
+    expect('error' in first && first.error.code).toBe(TOTP_REQUIRED_ERROR_CODE)
+
+    const second = await login({ username: 'totp-user', password: 'pass123', totp_code: '123456' })
+    expect('data' in second && second.data.session_token).toBe('login-token')
+  })
+
+  it('setupTotp / enableTotp / disableTotp 应完成两步验证的启停流程', async () => {
+    const status = await getTotpStatus('test-token')
+    expect('data' in status && status.data.enabled).toBe(false)
+
+    const setup = await setupTotp({ password: 'pass123' }, 'test-token')
+    expect('data' in setup && setup.data.secret).toBe('JBSWY3DPEHPK3PXP')
+
+    const invalid = await enableTotp({ code: '000000' }, 'test-token')
+    expect('error' in invalid && invalid.error.code).toBe('AUTH_TOTP_INVALID')
+
+    const enabled = await enableTotp({ code: '123456' }, 'test-token')
+    expect('data' in enabled && enabled.data.recovery_codes).toEqual(['abcde-fghij'])
+
+    const missingCode = await disableTotp({ password: 'pass123' }, 'test-token')
+    expect('error' in missingCode && missingCode.error.code).toBe(TOTP_REQUIRED_ERROR_CODE)
+
+    const disabled = await disableTotp({ password: 'pass123', code: 'abcde-fghij' }, 'test-token')
+    expect('data' in disabled).toBe(true)
+  })
 })
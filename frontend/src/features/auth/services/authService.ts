@@ -1,6 +1,6 @@
 /**
  * 认证 API 服务
- * 提供注册、登录、登出、获取当前用户等纯函数
+ * 提供注册、登录、登出、获取当前用户、会话与访问令牌管理等纯函数
  * 
  * Code Review Fix (Story 1.6):
  * - 使用 apiRequestWithAuth 统一鉴权注入点
@@ -8,17 +8,61 @@
  */
 
 import { apiRequest, apiRequestWithAuth, type ApiResponse } from '@/lib/api'
+import type { AccessTokenInfo } from '@/types/generated/api/AccessTokenInfo'
+import type { AccessTokenListResponse } from '@/types/generated/api/AccessTokenListResponse'
+import type { AdminResetTotpRequest } from '@/types/generated/api/AdminResetTotpRequest'
 import type { AuthResponse } from '@/types/generated/api/AuthResponse'
+import type { ChangePasswordRequest } from '@/types/generated/api/ChangePasswordRequest'
+import type { ChangePasswordResponse } from '@/types/generated/api/ChangePasswordResponse'
+import type { CreateAccessTokenRequest } from '@/types/generated/api/CreateAccessTokenRequest'
+import type { CreateAccessTokenResponse } from '@/types/generated/api/CreateAccessTokenResponse'
+import type { DisableTotpResponse } from '@/types/generated/api/DisableTotpResponse'
 import type { LoginRequest } from '@/types/generated/api/LoginRequest'
 import type { LogoutResponse } from '@/types/generated/api/LogoutResponse'
 import type { RegisterRequest } from '@/types/generated/api/RegisterRequest'
+import type { RevokeAccessTokenResponse } from '@/types/generated/api/RevokeAccessTokenResponse'
+import type { RevokeSessionsResponse } from '@/types/generated/api/RevokeSessionsResponse'
+import type { SessionInfo } from '@/types/generated/api/SessionInfo'
+import type { SessionListResponse } from '@/types/generated/api/SessionListResponse'
 import type { SystemStatusResponse } from '@/types/generated/api/SystemStatusResponse'
+import type { TotpEnableRequest } from '@/types/generated/api/TotpEnableRequest'
+import type { TotpReauthRequest } from '@/types/generated/api/TotpReauthRequest'
+import type { TotpRecoveryCodesResponse } from '@/types/generated/api/TotpRecoveryCodesResponse'
+import type { TotpSetupRequest } from '@/types/generated/api/TotpSetupRequest'
+import type { TotpSetupResponse } from '@/types/generated/api/TotpSetupResponse'
+import type { TotpStatusResponse } from '@/types/generated/api/TotpStatusResponse'
 import type { UserInfo } from '@/types/generated/api/UserInfo'
 
 /** 注册/登录参数类型（ts-rs 生成） */
 export type RegisterParams = RegisterRequest
 export type LoginParams = LoginRequest
-export type { AuthResponse, LogoutResponse, SystemStatusResponse, UserInfo }
+export type ChangePasswordParams = ChangePasswordRequest
+export type CreateAccessTokenParams = CreateAccessTokenRequest
+export type TotpSetupParams = TotpSetupRequest
+export type TotpEnableParams = TotpEnableRequest
+export type TotpReauthParams = TotpReauthRequest
+export type AdminResetTotpParams = AdminResetTotpRequest
+export type {
+  AccessTokenInfo,
+  AccessTokenListResponse,
+  AuthResponse,
+  ChangePasswordResponse,
+  CreateAccessTokenResponse,
+  DisableTotpResponse,
+  LogoutResponse,
+  RevokeAccessTokenResponse,
+  RevokeSessionsResponse,
+  SessionInfo,
+  SessionListResponse,
+  SystemStatusResponse,
+  TotpRecoveryCodesResponse,
+  TotpSetupResponse,
+  TotpStatusResponse,
+  UserInfo,
+}
+
+/** 登录需要两步验证码时的错误码（密码已通过校验） */
+export const TOTP_REQUIRED_ERROR_CODE = 'AUTH_TOTP_REQUIRED'
 
 /**
  * 获取系统状态
@@ -40,6 +84,7 @@ export async function register(params: RegisterParams): Promise<ApiResponse<Auth
 
 /**
  * 用户登录
+ * 已启用两步验证时需附带 totp_code（验证码或恢复码），缺失时返回 AUTH_TOTP_REQUIRED
  */
 export async function login(params: LoginParams): Promise<ApiResponse<AuthResponse>> {
   return apiRequest<AuthResponse>('/auth/login', {
@@ -63,3 +108,193 @@ export async function logout(token: string): Promise<ApiResponse<LogoutResponse>
 export async function getMe(token: string): Promise<ApiResponse<UserInfo>> {
   return apiRequestWithAuth<UserInfo>('/auth/me', { method: 'GET' }, token)
 }
+
+/**
+ * 修改密码
+ * 成功后数据密钥改由新密码保护（旧格式凭证一并迁移），其他会话将被注销
+ */
+export async function changePassword(
+  params: ChangePasswordParams,
+  token: string
+): Promise<ApiResponse<ChangePasswordResponse>> {
+  return apiRequestWithAuth<ChangePasswordResponse>(
+    '/auth/password',
+    {
+      method: 'POST',
+      body: JSON.stringify(params),
+    },
+    token
+  )
+}
+
+/**
+ * 列出当前用户的登录会话（设备）
+ */
+export async function listSessions(token: string): Promise<ApiResponse<SessionListResponse>> {
+  return apiRequestWithAuth<SessionListResponse>('/auth/sessions', { method: 'GET' }, token)
+}
+
+/**
+ * 吊销指定会话
+ */
+export async function revokeSession(
+  sessionId: string,
+  token: string
+): Promise<ApiResponse<RevokeSessionsResponse>> {
+  return apiRequestWithAuth<RevokeSessionsResponse>(
+    `/auth/sessions/${encodeURIComponent(sessionId)}`,
+    { method: 'DELETE' },
+    token
+  )
+}
+
+/**
+ * 吊销全部会话
+ * 默认保留当前会话（登出其他设备）；includeCurrent 为 true 时当前会话一并吊销
+ */
+export async function revokeAllSessions(
+  token: string,
+  includeCurrent = false
+): Promise<ApiResponse<RevokeSessionsResponse>> {
+  const query = includeCurrent ? '?include_current=true' : ''
+  return apiRequestWithAuth<RevokeSessionsResponse>(
+    `/auth/sessions${query}`,
+    { method: 'DELETE' },
+    token
+  )
+}
+
+/**
+ * 列出当前用户的个人访问令牌（不含明文）
+ */
+export async function listAccessTokens(
+  token: string
+): Promise<ApiResponse<AccessTokenListResponse>> {
+  return apiRequestWithAuth<AccessTokenListResponse>('/auth/tokens', { method: 'GET' }, token)
+}
+
+/**
+ * 创建个人访问令牌
+ * 令牌明文仅在响应中返回一次
+ */
+export async function createAccessToken(
+  params: CreateAccessTokenParams,
+  token: string
+): Promise<ApiResponse<CreateAccessTokenResponse>> {
+  return apiRequestWithAuth<CreateAccessTokenResponse>(
+    '/auth/tokens',
+    {
+      method: 'POST',
+      body: JSON.stringify(params),
+    },
+    token
+  )
+}
+
+/**
+ * 吊销个人访问令牌
+ */
+export async function revokeAccessToken(
+  tokenId: string,
+  token: string
+): Promise<ApiResponse<RevokeAccessTokenResponse>> {
+  return apiRequestWithAuth<RevokeAccessTokenResponse>(
+    `/auth/tokens/${encodeURIComponent(tokenId)}`,
+    { method: 'DELETE' },
+    token
+  )
+}
+
+/**
+ * 查询两步验证状态
+ */
+export async function getTotpStatus(token: string): Promise<ApiResponse<TotpStatusResponse>> {
+  return apiRequestWithAuth<TotpStatusResponse>('/auth/totp', { method: 'GET' }, token)
+}
+
+/**
+ * 生成两步验证密钥（需登录密码）
+ * 返回的 provisioning_uri 用于渲染二维码，需再调用 enableTotp 确认
+ */
+export async function setupTotp(
+  params: TotpSetupParams,
+  token: string
+): Promise<ApiResponse<TotpSetupResponse>> {
+  return apiRequestWithAuth<TotpSetupResponse>(
+    '/auth/totp/setup',
+    {
+      method: 'POST',
+      body: JSON.stringify(params),
+    },
+    token
+  )
+}
+
+/**
+ * 使用认证器验证码确认启用两步验证
+ * 恢复码仅在响应中返回一次
+ */
+export async function enableTotp(
+  params: TotpEnableParams,
+  token: string
+): Promise<ApiResponse<TotpRecoveryCodesResponse>> {
+  return apiRequestWithAuth<TotpRecoveryCodesResponse>(
+    '/auth/totp/enable',
+    {
+      method: 'POST',
+      body: JSON.stringify(params),
+    },
+    token
+  )
+}
+
+/**
+ * 停用两步验证（需登录密码与验证码或恢复码）
+ */
+export async function disableTotp(
+  params: TotpReauthParams,
+  token: string
+): Promise<ApiResponse<DisableTotpResponse>> {
+  return apiRequestWithAuth<DisableTotpResponse>(
+    '/auth/totp/disable',
+    {
+      method: 'POST',
+      body: JSON.stringify(params),
+    },
+    token
+  )
+}
+
+/**
+ * 重新生成恢复码（旧恢复码全部失效）
+ */
+export async function regenerateRecoveryCodes(
+  params: TotpReauthParams,
+  token: string
+): Promise<ApiResponse<TotpRecoveryCodesResponse>> {
+  return apiRequestWithAuth<TotpRecoveryCodesResponse>(
+    '/auth/totp/recovery-codes',
+    {
+      method: 'POST',
+      body: JSON.stringify(params),
+    },
+    token
+  )
+}
+
+/**
+ * 实例管理员重置他人的两步验证（需管理员本人重新认证）
+ */
+export async function adminResetTotp(
+  params: AdminResetTotpParams,
+  token: string
+): Promise<ApiResponse<DisableTotpResponse>> {
+  return apiRequestWithAuth<DisableTotpResponse>(
+    '/auth/totp/admin-reset',
+    {
+      method: 'POST',
+      body: JSON.stringify(params),
+    },
+    token
+  )
+}
@@ -0,0 +1,20 @@
+import { useQuery } from '@tanstack/react-query'
+import { useAuthStore } from '@/stores/useAuthStore'
+import { listCredentialProfilesByType } from '../services/credentialProfileService'
+
+/** 与 API 配置页的命名凭证管理共用缓存前缀，增删凭证后自动刷新选择器 */
+export const CREDENTIAL_PROFILES_QUERY_KEY = ['credential-profiles'] as const
+
+/** 查询某类型的命名凭证（dify / generic_llm），用于测试集 / 任务的凭证选择器 */
+export function useCredentialProfiles(credentialType: string | null) {
+  const sessionToken = useAuthStore((state) => state.sessionToken)
+  const authStatus = useAuthStore((state) => state.authStatus)
+  const isAuthenticated = authStatus === 'authenticated' && !!sessionToken
+
+  return useQuery({
+    queryKey: [...CREDENTIAL_PROFILES_QUERY_KEY, credentialType ?? 'none'],
+    queryFn: () => listCredentialProfilesByType(sessionToken!, credentialType!),
+    enabled: isAuthenticated && !!credentialType,
+    staleTime: 60_000,
+  })
+}
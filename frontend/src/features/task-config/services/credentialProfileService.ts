@@ -0,0 +1,23 @@
+import { UnauthorizedError, apiRequestWithAuth, isApiError } from '@/lib/api'
+import type { CredentialListResponse } from '@/types/generated/api/CredentialListResponse'
+import type { CredentialProfileResponse } from '@/types/generated/api/CredentialProfileResponse'
+
+export async function listCredentialProfilesByType(
+  token: string,
+  credentialType: string
+): Promise<CredentialProfileResponse[]> {
+  const response = await apiRequestWithAuth<CredentialListResponse>(
+    `/auth/credentials?credential_type=${encodeURIComponent(credentialType)}`,
+    { method: 'GET' },
+    token
+  )
+
+  if (isApiError(response)) {
+    if (response.error.code === 'UNAUTHORIZED') {
+      throw new UnauthorizedError(response.error.message)
+    }
+    throw new Error(response.error.message)
+  }
+
+  return response.data.credentials
+}
@@ -34,6 +34,7 @@ const server = setupServer(
             raw: null,
           },
         ],
+        outputs: [{ name: 'text', type: 'string' }],
       }
       return HttpResponse.json({ data })
     }
@@ -56,6 +57,11 @@ const server = setupServer(
           targetPromptVariable: body.targetPromptVariable,
           bindings: body.bindings,
           parametersSnapshot: null,
+          outputSelector: body.outputSelector,
+          outputsSnapshot: null,
+          appType: body.appType,
+          responseMode: body.responseMode,
+          credentialId: body.credentialId,
         },
       }
       return HttpResponse.json({ data })
@@ -84,10 +90,18 @@ describe('difyService', () => {
     const res = await saveDifyConfig(
       'ws-1',
       'ts-1',
-      { targetPromptVariable: 'system_prompt', bindings: {} },
+      {
+        targetPromptVariable: 'system_prompt',
+        bindings: {},
+        outputSelector: null,
+        appType: 'workflow',
+        responseMode: 'blocking',
+        credentialId: 'cred-b',
+      },
       'test-token'
     )
     expect(res.difyConfig.targetPromptVariable).toBe('system_prompt')
+    expect(res.difyConfig.credentialId).toBe('cred-b')
   })
 
   it('refreshDifyVariables 在 502 时应抛出上游错误 message', async () => {
@@ -4,13 +4,17 @@ import { http, HttpResponse } from 'msw'
 
 import { UnauthorizedError } from '@/lib/api'
 import {
+  addWorkspaceMember,
   createWorkspace,
   deleteWorkspace,
   getWorkspace,
+  listWorkspaceMembers,
   listWorkspaces,
 } from './workspaceService'
 import type { CreateWorkspaceRequest } from '@/types/generated/api/CreateWorkspaceRequest'
 import type { WorkspaceResponse } from '@/types/generated/api/WorkspaceResponse'
+import type { AddWorkspaceMemberRequest } from '@/types/generated/api/AddWorkspaceMemberRequest'
+import type { WorkspaceMemberInfo } from '@/types/generated/api/WorkspaceMemberInfo'
 
 const API_BASE = 'http://localhost:3000/api/v1'
 
@@ -34,6 +38,7 @@ const server = setupServer(
         id: 'ws-1',
         name: '工作区 1',
         description: 'desc',
+        role: 'owner',
         created_at: 1,
         updated_at: 2,
       },
@@ -61,6 +66,7 @@ const server = setupServer(
       id: 'ws-created',
       name: body.name,
       description: body.description ?? null,
+      role: 'owner',
       created_at: 10,
       updated_at: 10,
     }
@@ -86,6 +92,7 @@ const server = setupServer(
       id: String(params.id),
       name: '工作区详情',
       description: null,
+      role: 'viewer',
       created_at: 10,
       updated_at: 11,
     }
@@ -108,6 +115,38 @@ const server = setupServer(
     }
 
     return HttpResponse.json({ data: { message: '删除成功' } })
+  }),
+
+  http.get(`${API_BASE}/workspaces/:id/members`, () => {
+    const members: WorkspaceMemberInfo[] = [
+      { user_id: 'u-1', username: 'alice', role: 'owner', invited_by: null, created_at: 1 },
+      { user_id: 'u-2', username: 'bob', role: 'viewer', invited_by: 'u-1', created_at: 2 },
+    ]
+    return HttpResponse.json({ data: { members } })
+  }),
+
+  http.post(`${API_BASE}/workspaces/:id/members`, async ({ request }) => {
+    const body = (await request.json()) as AddWorkspaceMemberRequest
+    if (body.username === 'nobody') {
+      return HttpResponse.json(
+        {
+          error: {
+            code: 'RESOURCE_NOT_FOUND',
+            message: '用户不存在',
+          },
+        },
+        { status: 404 }
+      )
+    }
+
+    const data: WorkspaceMemberInfo = {
+      user_id: 'u-3',
+      username: body.username,
+      role: body.role,
+      invited_by: 'u-1',
+      created_at: 3,
+    }
+    return HttpResponse.json({ data })
   })
 )
 
@@ -142,4 +181,19 @@ describe('workspaceService', () => {
     const res = await deleteWorkspace('ws-1', 'test-token')
     expect(res.message).toBe('删除成功')
   })
+
+  it('listWorkspaceMembers 应返回成员列表', async () => {
+    const res = await listWorkspaceMembers('ws-1', 'test-token')
+    expect(res.map((m) => m.role)).toEqual(['owner', 'viewer'])
+  })
+
+  it('addWorkspaceMember 应返回新成员，失败时抛出后端错误信息', async () => {
+    const res = await addWorkspaceMember('ws-1', { username: 'carol', role: 'editor' }, 'test-token')
+    expect(res.username).toBe('carol')
+    expect(res.role).toBe('editor')
+
+    await expect(
+      addWorkspaceMember('ws-1', { username: 'nobody', role: 'viewer' }, 'test-token')
+    ).rejects.toThrow('用户不存在')
+  })
 })
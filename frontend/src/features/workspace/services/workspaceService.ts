@@ -2,6 +2,11 @@ import { UnauthorizedError, apiRequestWithAuth, delWithAuth, isApiError } from '
 import type { CreateWorkspaceRequest } from '@/types/generated/api/CreateWorkspaceRequest'
 import type { DeleteWorkspaceResponse } from '@/types/generated/api/DeleteWorkspaceResponse'
 import type { WorkspaceResponse } from '@/types/generated/api/WorkspaceResponse'
+import type { AddWorkspaceMemberRequest } from '@/types/generated/api/AddWorkspaceMemberRequest'
+import type { UpdateWorkspaceMemberRequest } from '@/types/generated/api/UpdateWorkspaceMemberRequest'
+import type { WorkspaceMemberInfo } from '@/types/generated/api/WorkspaceMemberInfo'
+import type { WorkspaceMemberListResponse } from '@/types/generated/api/WorkspaceMemberListResponse'
+import type { RemoveWorkspaceMemberResponse } from '@/types/generated/api/RemoveWorkspaceMemberResponse'
 
 export async function listWorkspaces(token: string): Promise<WorkspaceResponse[]> {
   const response = await apiRequestWithAuth<WorkspaceResponse[]>(
@@ -72,3 +77,92 @@ export async function deleteWorkspace(id: string, token: string): Promise<Delete
 
   return response.data
 }
+
+export async function listWorkspaceMembers(
+  workspaceId: string,
+  token: string
+): Promise<WorkspaceMemberInfo[]> {
+  const response = await apiRequestWithAuth<WorkspaceMemberListResponse>(
+    `/workspaces/${workspaceId}/members`,
+    { method: 'GET' },
+    token
+  )
+
+  if (isApiError(response)) {
+    if (response.error.code === 'UNAUTHORIZED') {
+      throw new UnauthorizedError(response.error.message)
+    }
+    throw new Error(response.error.message)
+  }
+
+  return response.data.members
+}
+
+export async function addWorkspaceMember(
+  workspaceId: string,
+  params: AddWorkspaceMemberRequest,
+  token: string
+): Promise<WorkspaceMemberInfo> {
+  const response = await apiRequestWithAuth<WorkspaceMemberInfo>(
+    `/workspaces/${workspaceId}/members`,
+    {
+      method: 'POST',
+      body: JSON.stringify(params),
+    },
+    token
+  )
+
+  if (isApiError(response)) {
+    if (response.error.code === 'UNAUTHORIZED') {
+      throw new UnauthorizedError(response.error.message)
+    }
+    throw new Error(response.error.message)
+  }
+
+  return response.data
+}
+
+export async function updateWorkspaceMember(
+  workspaceId: string,
+  memberUserId: string,
+  params: UpdateWorkspaceMemberRequest,
+  token: string
+): Promise<WorkspaceMemberInfo[]> {
+  const response = await apiRequestWithAuth<WorkspaceMemberListResponse>(
+    `/workspaces/${workspaceId}/members/${memberUserId}`,
+    {
+      method: 'PATCH',
+      body: JSON.stringify(params),
+    },
+    token
+  )
+
+  if (isApiError(response)) {
+    if (response.error.code === 'UNAUTHORIZED') {
+      throw new UnauthorizedError(response.error.message)
+    }
+    throw new Error(response.error.message)
+  }
+
+  return response.data.members
+}
+
+export async function removeWorkspaceMember(
+  workspaceId: string,
+  memberUserId: string,
+  token: string
+): Promise<RemoveWorkspaceMemberResponse> {
+  const response = await delWithAuth<RemoveWorkspaceMemberResponse>(
+    `/workspaces/${workspaceId}/members/${memberUserId}`,
+    token
+  )
+
+  if (isApiError(response)) {
+    if (response.error.code === 'UNAUTHORIZED') {
+      throw new UnauthorizedError(response.error.message)
+    }
+    throw new Error(response.error.message)
+  }
+
+  return response.data
+}
@@ -30,6 +30,24 @@ const server = setupServer(
     return HttpResponse.json({ data: { models: ['gpt-4', 'gpt-3.5-turbo'] } })
   }),
 
+  http.get(`${API_BASE}/auth/credentials`, ({ request }) => {
+    const credentialType = new URL(request.url).searchParams.get('credential_type')
+    const credentials = [
+      { id: 'cred-a', credential_type: 'dify', name: 'app-a', is_default: true },
+      { id: 'cred-b', credential_type: 'dify', name: 'app-b', is_default: false },
+    ]
+      .filter((c) => c.credential_type === credentialType)
+      .map((c) => ({
+        ...c,
+        provider: null,
+        base_url: 'https://dify.example.com',
+        masked_api_key: 'sk-****',
+        created_at: 1,
+        updated_at: 1,
+      }))
+    return HttpResponse.json({ data: { credentials } })
+  }),
+
   http.get(`${API_BASE}/workspaces/:workspaceId/optimization-tasks/:taskId`, ({ request, params }) => {
     const auth = request.headers.get('authorization')
     if (auth !== 'Bearer test-token') {
@@ -95,6 +113,11 @@ const server = setupServer(
             evaluator_config: body.evaluator_config,
             teacher_llm: body.teacher_llm,
             advanced_data_split: body.advanced_data_split,
+            execution_cache: body.execution_cache,
+            execution_retry: body.execution_retry,
+            command_target: body.command_target,
+            http_target: body.http_target,
+            execution_credential_id: body.execution_credential_id,
           },
           updated_at: now,
         }
@@ -177,6 +200,25 @@ describe('OptimizationTaskConfigView', () => {
         },
         teacher_llm: { model_id: null },
         advanced_data_split: { strategy: 'percent', k_fold_folds: 5, sampling_strategy: 'random' },
+        execution_cache: { enabled: false, ttl_seconds: 604800 },
+        execution_retry: { max_retries: 1, max_failure_percent: 20 },
+        command_target: {
+          program: '',
+          args: [],
+          timeout_ms: 30000,
+          env_allowlist: [],
+          max_concurrency: null,
+        },
+        http_target: {
+          method: 'POST',
+          url: '',
+          headers: {},
+          body_template: '{"prompt": {{prompt}}, "input": {{input}}}',
+          output_path: '$.output',
+          usage_path: null,
+          timeout_ms: 60000,
+        },
+        execution_credential_id: null,
       },
       final_prompt: null,
       terminated_at: null,
@@ -348,6 +390,28 @@ describe('OptimizationTaskConfigView', () => {
     expect(screen.getByLabelText('老师模型')).toHaveValue('gpt-4')
   })
 
+  it('可为 Dify 任务选择执行凭证并在保存 payload 中包含 execution_credential_id', async () => {
+    renderPage('/workspaces/ws-1/tasks/task-1')
+
+    await screen.findByText('任务配置：任务 1')
+
+    expect(screen.getByLabelText('执行凭证')).toHaveValue('')
+    await waitFor(() => {
+      expect(screen.getByRole('option', { name: 'app-b' })).toBeInTheDocument()
+    })
+    expect(screen.getByRole('option', { name: 'app-a（默认）' })).toBeInTheDocument()
+
+    fireEvent.change(screen.getByLabelText('执行凭证'), { target: { value: 'cred-b' } })
+    fireEvent.click(screen.getByRole('button', { name: '保存配置' }))
+
+    await waitFor(() => {
+      expect(screen.getByText('保存成功')).toBeInTheDocument()
+    })
+
+    expect(lastPutBody?.execution_credential_id).toBe('cred-b')
+    expect(screen.getByLabelText('执行凭证')).toHaveValue('cred-b')
+  })
+
   it('当模型列表为空时应显示引导到 /settings/api', async () => {
     server.use(
       http.get(`${API_BASE}/auth/generic-llm/models`, () => {
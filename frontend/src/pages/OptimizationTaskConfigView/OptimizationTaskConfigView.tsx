@@ -9,9 +9,12 @@ import {
   useUpdateOptimizationTaskConfig,
 } from '@/features/task-config/hooks/useOptimizationTasks'
 import { useTeacherModels } from '@/features/task-config/hooks/useTeacherModels'
+import { useCredentialProfiles } from '@/features/task-config/hooks/useCredentialProfiles'
 import type { UpdateOptimizationTaskConfigRequest } from '@/types/generated/api/UpdateOptimizationTaskConfigRequest'
 import type { OptimizationTaskResponse } from '@/types/generated/api/OptimizationTaskResponse'
 import type { AdvancedDataSplitStrategy } from '@/types/generated/models/AdvancedDataSplitStrategy'
+import type { CommandTargetConfig } from '@/types/generated/models/CommandTargetConfig'
+import type { HttpTargetConfig } from '@/types/generated/models/HttpTargetConfig'
 import type { ExecutionMode } from '@/types/generated/models/ExecutionMode'
 import type { EvaluatorType } from '@/types/generated/models/EvaluatorType'
 import type { OutputStrategy } from '@/types/generated/models/OutputStrategy'
@@ -44,6 +47,49 @@ const LLM_JUDGE_SAMPLES_MAX = 5
 const MAX_CONCURRENCY_MIN = 1
 const MAX_CONCURRENCY_MAX = 64
 const MAX_CONCURRENCY_DEFAULT = 4
+const EXECUTION_CACHE_TTL_SECONDS_DEFAULT = 7 * 24 * 3600
+const EXECUTION_MAX_RETRIES_MAX = 5
+const EXECUTION_MAX_RETRIES_DEFAULT = 1
+const EXECUTION_MAX_FAILURE_PERCENT_DEFAULT = 20
+const COMMAND_TARGET_DEFAULT: CommandTargetConfig = {
+  program: '',
+  args: [],
+  timeout_ms: 30000,
+  env_allowlist: [],
+  max_concurrency: null,
+}
+const HTTP_TARGET_DEFAULT: HttpTargetConfig = {
+  method: 'POST',
+  url: '',
+  headers: {},
+  body_template: '{"prompt": {{prompt}}, "input": {{input}}}',
+  output_path: '$.output',
+  usage_path: null,
+  timeout_ms: 60000,
+}
+const HTTP_TARGET_METHODS = ['GET', 'POST', 'PUT', 'PATCH']
+
+/** 支持按任务选择执行凭证的执行目标 → 凭证类型 */
+const EXECUTION_CREDENTIAL_TYPES: Record<string, string> = {
+  dify: 'dify',
+  generic: 'generic_llm',
+}
+
+function formatHttpHeaders(headers: HttpTargetConfig['headers']) {
+  return Object.entries(headers)
+    .map(([name, value]) => `${name}: ${value ?? ''}`)
+    .join('\n')
+}
+
+function parseHttpHeaders(text: string): HttpTargetConfig['headers'] {
+  const headers: Record<string, string> = {}
+  for (const line of text.split('\n')) {
+    const idx = line.indexOf(':')
+    if (idx <= 0) continue
+    headers[line.slice(0, idx).trim()] = line.slice(idx + 1).trim()
+  }
+  return headers
+}
 
 function validateIntegerInRange(value: number, min: number, max: number, label: string) {
   if (!Number.isFinite(value)) {
@@ -155,6 +201,30 @@ function OptimizationTaskConfigForm(props: {
     task.config.execution_mode ?? 'serial'
   )
   const [maxConcurrency, setMaxConcurrency] = useState(task.config.max_concurrency ?? MAX_CONCURRENCY_DEFAULT)
+  const [executionCacheEnabled, setExecutionCacheEnabled] = useState(
+    task.config.execution_cache?.enabled ?? false
+  )
+  const [executionCacheTtlSeconds, setExecutionCacheTtlSeconds] = useState(
+    task.config.execution_cache?.ttl_seconds ?? EXECUTION_CACHE_TTL_SECONDS_DEFAULT
+  )
+  const [executionMaxRetries, setExecutionMaxRetries] = useState(
+    task.config.execution_retry?.max_retries ?? EXECUTION_MAX_RETRIES_DEFAULT
+  )
+  const [executionMaxFailurePercent, setExecutionMaxFailurePercent] = useState(
+    task.config.execution_retry?.max_failure_percent ?? EXECUTION_MAX_FAILURE_PERCENT_DEFAULT
+  )
+  const [commandTarget, setCommandTarget] = useState<CommandTargetConfig>(
+    task.config.command_target ?? COMMAND_TARGET_DEFAULT
+  )
+  const [httpTarget, setHttpTarget] = useState<HttpTargetConfig>(
+    task.config.http_target ?? HTTP_TARGET_DEFAULT
+  )
+  const [httpHeadersText, setHttpHeadersText] = useState(
+    formatHttpHeaders((task.config.http_target ?? HTTP_TARGET_DEFAULT).headers)
+  )
+  const [executionCredentialId, setExecutionCredentialId] = useState(
+    task.config.execution_credential_id ?? ''
+  )
   const [trainPercent, setTrainPercent] = useState(task.config.data_split.train_percent)
   const [validationPercent, setValidationPercent] = useState(task.config.data_split.validation_percent)
 
@@ -191,6 +261,9 @@ function OptimizationTaskConfigForm(props: {
   const teacherModelsErrorMessage =
     teacherModelsError instanceof Error ? teacherModelsError.message : teacherModelsError ? '加载失败' : null
 
+  const executionCredentialType = EXECUTION_CREDENTIAL_TYPES[task.execution_target_type] ?? null
+  const { data: executionCredentials } = useCredentialProfiles(executionCredentialType)
+
   const [localError, setLocalError] = useState<string | null>(null)
   const [successMessage, setSuccessMessage] = useState<string | null>(null)
 
@@ -215,6 +288,18 @@ function OptimizationTaskConfigForm(props: {
     setDiversityComputeSemantic(config.diversity_config?.computeSemantic ?? false)
     setExecutionMode(config.execution_mode)
     setMaxConcurrency(config.max_concurrency)
+    setExecutionCacheEnabled(config.execution_cache?.enabled ?? false)
+    setExecutionCacheTtlSeconds(
+      config.execution_cache?.ttl_seconds ?? EXECUTION_CACHE_TTL_SECONDS_DEFAULT
+    )
+    setExecutionMaxRetries(config.execution_retry?.max_retries ?? EXECUTION_MAX_RETRIES_DEFAULT)
+    setExecutionMaxFailurePercent(
+      config.execution_retry?.max_failure_percent ?? EXECUTION_MAX_FAILURE_PERCENT_DEFAULT
+    )
+    setCommandTarget(config.command_target ?? COMMAND_TARGET_DEFAULT)
+    setHttpTarget(config.http_target ?? HTTP_TARGET_DEFAULT)
+    setHttpHeadersText(formatHttpHeaders((config.http_target ?? HTTP_TARGET_DEFAULT).headers))
+    setExecutionCredentialId(config.execution_credential_id ?? '')
     setTrainPercent(config.data_split.train_percent)
     setValidationPercent(config.data_split.validation_percent)
     setOutputStrategy(config.output_config.strategy)
@@ -407,6 +492,17 @@ function OptimizationTaskConfigForm(props: {
       },
       execution_mode: executionMode,
       max_concurrency: maxConcurrencyValue,
+      execution_cache: {
+        enabled: executionCacheEnabled,
+        ttl_seconds: executionCacheTtlSeconds,
+      },
+      execution_retry: {
+        max_retries: executionMaxRetries,
+        max_failure_percent: executionMaxFailurePercent,
+      },
+      command_target: commandTarget,
+      http_target: { ...httpTarget, headers: parseHttpHeaders(httpHeadersText) },
+      execution_credential_id: executionCredentialId === '' ? null : executionCredentialId,
       train_percent: trainPercentValue,
       validation_percent: validationPercentValue,
       output_config: {
@@ -479,6 +575,17 @@ function OptimizationTaskConfigForm(props: {
       },
       execution_mode: executionMode,
       max_concurrency: Number(maxConcurrency),
+      execution_cache: {
+        enabled: false,
+        ttl_seconds: EXECUTION_CACHE_TTL_SECONDS_DEFAULT,
+      },
+      execution_retry: {
+        max_retries: EXECUTION_MAX_RETRIES_DEFAULT,
+        max_failure_percent: EXECUTION_MAX_FAILURE_PERCENT_DEFAULT,
+      },
+      command_target: COMMAND_TARGET_DEFAULT,
+      http_target: HTTP_TARGET_DEFAULT,
+      execution_credential_id: null,
       train_percent: Number(trainPercent),
       validation_percent: Number(validationPercent),
       output_config: {
@@ -650,6 +757,191 @@ function OptimizationTaskConfigForm(props: {
         </div>
       )}
 
+      <div className="grid gap-2">
+        <div className="flex items-center gap-2">
+          <input
+            id="execution-cache-enabled"
+            type="checkbox"
+            checked={executionCacheEnabled}
+            onChange={(e) => setExecutionCacheEnabled(e.target.checked)}
+          />
+          <Label htmlFor="execution-cache-enabled">启用执行结果缓存</Label>
+        </div>
+        <div className="text-xs text-muted-foreground">
+          相同 Prompt、输入与执行目标配置的用例直接复用历史结果（不再调用执行目标），适合重跑/恢复任务；执行目标输出不确定时请关闭。
+        </div>
+      </div>
+
+      <div className="grid gap-2">
+        <Label htmlFor="execution-max-retries">单用例重试次数</Label>
+        <Input
+          id="execution-max-retries"
+          type="number"
+          min={0}
+          max={EXECUTION_MAX_RETRIES_MAX}
+          value={executionMaxRetries}
+          onChange={(e) => setExecutionMaxRetries(Number(e.target.value))}
+        />
+        <Label htmlFor="execution-max-failure-percent">允许失败用例占比（%）</Label>
+        <Input
+          id="execution-max-failure-percent"
+          type="number"
+          min={0}
+          max={100}
+          value={executionMaxFailurePercent}
+          onChange={(e) => setExecutionMaxFailurePercent(Number(e.target.value))}
+        />
+        <div className="text-xs text-muted-foreground">
+          网络/超时/上游错误按次数重试；仍失败的用例计为未通过，仅当失败占比超过阈值时中止本轮迭代（0 表示任一失败即中止）。
+        </div>
+      </div>
+
+      {executionCredentialType && (
+        <div className="grid gap-2">
+          <Label htmlFor="execution-credential">执行凭证</Label>
+          <select
+            id="execution-credential"
+            className="h-9 rounded-md border bg-transparent px-3 text-sm"
+            value={executionCredentialId}
+            onChange={(e) => setExecutionCredentialId(e.target.value)}
+          >
+            <option value="">使用默认凭证</option>
+            {(executionCredentials ?? []).map((cred) => (
+              <option key={cred.id} value={cred.id}>
+                {cred.is_default ? `${cred.name}（默认）` : cred.name}
+              </option>
+            ))}
+          </select>
+          <div className="text-xs text-muted-foreground">
+            不同应用或计费账号可在“API 配置”中保存多组凭证；Dify 任务未选择时依次使用测试集指定的凭证与默认凭证。
+          </div>
+        </div>
+      )}
+
+      {task.execution_target_type === 'command' && (
+        <div className="grid gap-2">
+          <Label htmlFor="command-program">本地命令（program）</Label>
+          <Input
+            id="command-program"
+            value={commandTarget.program}
+            onChange={(e) => setCommandTarget({ ...commandTarget, program: e.target.value })}
+          />
+          <Label htmlFor="command-args">参数（每行一个）</Label>
+          <textarea
+            id="command-args"
+            className="min-h-16 w-full rounded-md border bg-transparent px-3 py-2 text-sm"
+            value={commandTarget.args.join('\n')}
+            onChange={(e) =>
+              setCommandTarget({
+                ...commandTarget,
+                args: e.target.value.split('\n').filter((arg) => arg.length > 0),
+              })
+            }
+          />
+          <Label htmlFor="command-timeout-ms">超时（毫秒）</Label>
+          <Input
+            id="command-timeout-ms"
+            type="number"
+            min={100}
+            value={commandTarget.timeout_ms}
+            onChange={(e) => setCommandTarget({ ...commandTarget, timeout_ms: Number(e.target.value) })}
+          />
+          <Label htmlFor="command-env-allowlist">透传环境变量（逗号分隔）</Label>
+          <Input
+            id="command-env-allowlist"
+            value={commandTarget.env_allowlist.join(',')}
+            onChange={(e) =>
+              setCommandTarget({
+                ...commandTarget,
+                env_allowlist: e.target.value
+                  .split(',')
+                  .map((name) => name.trim())
+                  .filter((name) => name.length > 0),
+              })
+            }
+          />
+          <Label htmlFor="command-max-concurrency">命令并发上限（可空）</Label>
+          <Input
+            id="command-max-concurrency"
+            type="number"
+            min={1}
+            value={commandTarget.max_concurrency ?? ''}
+            onChange={(e) =>
+              setCommandTarget({
+                ...commandTarget,
+                max_concurrency: e.target.value === '' ? null : Number(e.target.value),
+              })
+            }
+          />
+          <div className="text-xs text-muted-foreground">
+            每条用例启动一次子进程：stdin 写入 {'{prompt, input, test_case_id}'} JSON，stdout 需返回 {'{output, usage?}'} JSON。program 还需出现在服务端白名单 COMMAND_TARGET_ALLOWED_PROGRAMS 中；透传的环境变量只能从 COMMAND_TARGET_ALLOWED_ENV 中选择。
+          </div>
+        </div>
+      )}
+
+      {task.execution_target_type === 'http' && (
+        <div className="grid gap-2">
+          <Label htmlFor="http-method">HTTP 方法</Label>
+          <select
+            id="http-method"
+            className="h-9 rounded-md border bg-transparent px-3 text-sm"
+            value={httpTarget.method}
+            onChange={(e) => setHttpTarget({ ...httpTarget, method: e.target.value })}
+          >
+            {HTTP_TARGET_METHODS.map((m) => (
+              <option key={m} value={m}>
+                {m}
+              </option>
+            ))}
+          </select>
+          <Label htmlFor="http-url">URL</Label>
+          <Input
+            id="http-url"
+            value={httpTarget.url}
+            onChange={(e) => setHttpTarget({ ...httpTarget, url: e.target.value })}
+          />
+          <Label htmlFor="http-headers">请求头（每行一个 Name: Value）</Label>
+          <textarea
+            id="http-headers"
+            className="min-h-16 w-full rounded-md border bg-transparent px-3 py-2 text-sm"
+            value={httpHeadersText}
+            onChange={(e) => setHttpHeadersText(e.target.value)}
+          />
+          <Label htmlFor="http-body-template">请求体模板（JSON）</Label>
+          <textarea
+            id="http-body-template"
+            className="min-h-24 w-full rounded-md border bg-transparent px-3 py-2 font-mono text-sm"
+            value={httpTarget.body_template}
+            onChange={(e) => setHttpTarget({ ...httpTarget, body_template: e.target.value })}
+          />
+          <Label htmlFor="http-output-path">输出提取路径</Label>
+          <Input
+            id="http-output-path"
+            value={httpTarget.output_path}
+            onChange={(e) => setHttpTarget({ ...httpTarget, output_path: e.target.value })}
+          />
+          <Label htmlFor="http-usage-path">Token 用量提取路径（可空）</Label>
+          <Input
+            id="http-usage-path"
+            value={httpTarget.usage_path ?? ''}
+            onChange={(e) =>
+              setHttpTarget({ ...httpTarget, usage_path: e.target.value === '' ? null : e.target.value })
+            }
+          />
+          <Label htmlFor="http-timeout-ms">超时（毫秒）</Label>
+          <Input
+            id="http-timeout-ms"
+            type="number"
+            min={100}
+            value={httpTarget.timeout_ms}
+            onChange={(e) => setHttpTarget({ ...httpTarget, timeout_ms: Number(e.target.value) })}
+          />
+          <div className="text-xs text-muted-foreground">
+            模板占位符 {'{{prompt}}'} / {'{{input}}'} / {'{{input.<key>}}'} / {'{{test_case_id}}'} 以 JSON 字面量替换；请求头可用 {'{{credential.generic_llm}}'} / {'{{credential.dify}}'} 引用已保存的凭证。提取路径支持 $.a.b[0]（JSONPath）或 /a/b/0（JSON Pointer）。
+          </div>
+        </div>
+      )}
+
       <div className="grid gap-2">
         <Label>数据划分策略（百分比）</Label>
         <div className="grid grid-cols-2 gap-3">
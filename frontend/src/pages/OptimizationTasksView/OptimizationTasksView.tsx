@@ -80,6 +80,12 @@ export function OptimizationTasksView() {
     if (executionTargetType === 'example') {
       return '已选择 Example（确定性示例，不出网）：用于扩展点开发/回归验证，不依赖真实 LLM。'
     }
+    if (executionTargetType === 'command') {
+      return '已选择 本地命令：每条用例启动一次子进程（stdin/stdout JSON 协议），程序与参数在任务配置页填写，且需在服务端白名单中。'
+    }
+    if (executionTargetType === 'http') {
+      return '已选择 HTTP Webhook：按请求体模板调用任意 HTTP 服务，并按 JSONPath/JSON Pointer 提取输出；URL、请求头与模板在任务配置页填写。'
+    }
     return executionTargetHelp
   }, [executionTargetType, executionTargetHelp])
 
@@ -207,6 +213,8 @@ export function OptimizationTasksView() {
                 <option value="dify">Dify 工作流</option>
                 <option value="generic">通用 API（直连模型）</option>
                 <option value="example">Example（确定性示例）</option>
+                <option value="command">本地命令（子进程）</option>
+                <option value="http">HTTP Webhook</option>
               </select>
               <div className="text-xs text-muted-foreground">{executionTargetHelpNormalized}</div>
             </div>
@@ -138,6 +138,7 @@ const server = setupServer(
             raw: null,
           },
         ],
+        outputs: [{ name: 'text', type: 'string' }],
       }
       return HttpResponse.json({ data })
     }
@@ -161,6 +162,11 @@ const server = setupServer(
             targetPromptVariable: lastSaveDifyConfigBody.targetPromptVariable,
             bindings: lastSaveDifyConfigBody.bindings,
             parametersSnapshot: null,
+            outputSelector: lastSaveDifyConfigBody.outputSelector,
+            outputsSnapshot: null,
+            appType: lastSaveDifyConfigBody.appType,
+            responseMode: lastSaveDifyConfigBody.responseMode,
+            credentialId: lastSaveDifyConfigBody.credentialId,
           },
         },
       })
@@ -394,6 +400,9 @@ describe('TestSetsView templates', () => {
     const jsonEditor = await screen.findByPlaceholderText('例如："hello" / 123 / true / {"a":1} / [1,2]')
     fireEvent.change(jsonEditor, { target: { value: '5' } })
 
+    const outputSelectorInput = screen.getByLabelText('输出选择器（可选）')
+    fireEvent.change(outputSelectorInput, { target: { value: ' text ' } })
+
     const saveButton = screen.getByRole('button', { name: '保存 Dify 配置' })
     fireEvent.click(saveButton)
 
@@ -403,6 +412,7 @@ describe('TestSetsView templates', () => {
 
     expect(lastSaveDifyConfigBody?.targetPromptVariable).toBe('system_prompt')
     expect(lastSaveDifyConfigBody?.bindings?.k?.source).toBe('fixed')
+    expect(lastSaveDifyConfigBody?.outputSelector).toBe('text')
   })
 
   it('Dify 变量配置：解析失败应展示友好错误并可重试', async () => {
@@ -572,6 +582,40 @@ describe('TestSetsView templates', () => {
     expect(casesTextarea.value).toContain('"expected": "new-expected"')
   })
 
+  it('多轮对话用例：最后一轮不是 user 时应阻止创建并提示错误', async () => {
+    renderPage('/workspaces/ws-1/test-sets')
+
+    const nameInput = (await screen.findByLabelText('名称')) as HTMLInputElement
+    fireEvent.change(nameInput, { target: { value: '对话测试集' } })
+
+    const casesTextarea = screen.getByLabelText('cases (JSON)') as HTMLTextAreaElement
+    fireEvent.change(casesTextarea, {
+      target: {
+        value: JSON.stringify([
+          {
+            id: 'dialog-1',
+            input: {
+              conversation: [
+                { role: 'user', content: 'hi' },
+                { role: 'assistant', content: 'hello' },
+              ],
+            },
+            reference: { Exact: { expected: 'x' } },
+          },
+        ]),
+      },
+    })
+
+    fireEvent.click(screen.getByRole('button', { name: '创建测试集' }))
+
+    await waitFor(() => {
+      expect(
+        screen.getByText('校验失败：cases[0].input.conversation 的最后一轮必须是 user')
+      ).toBeInTheDocument()
+    })
+    expect(lastCreateTestSetBody).toBeNull()
+  })
+
   it('创意任务配置：应写回 casesJson 并在创建请求体中包含 core_request/constraints.params', async () => {
     renderPage('/workspaces/ws-1/test-sets')
 
@@ -30,11 +30,15 @@ import { getTestSet } from '@/features/test-set-manager/services/testSetService'
 import { getTestSetTemplate } from '@/features/test-set-manager/services/testSetTemplateService'
 import { refreshDifyVariables, saveDifyConfig } from '@/features/test-set-manager/services/difyService'
 import { deleteGenericConfig, saveGenericConfig } from '@/features/test-set-manager/services/genericConfigService'
+import { useCredentialProfiles } from '@/features/task-config/hooks/useCredentialProfiles'
 import { useAuthStore } from '@/stores/useAuthStore'
 import type { TestSetListItemResponse } from '@/types/generated/api/TestSetListItemResponse'
 import type { DifyBindingSource } from '@/types/generated/api/DifyBindingSource'
 import type { DifyConfig } from '@/types/generated/api/DifyConfig'
 import type { DifyInputVariable } from '@/types/generated/api/DifyInputVariable'
+import type { DifyOutputVariable } from '@/types/generated/api/DifyOutputVariable'
+import type { DifyAppType } from '@/types/generated/models/DifyAppType'
+import type { DifyResponseMode } from '@/types/generated/models/DifyResponseMode'
 import type { SaveDifyConfigRequest } from '@/types/generated/api/SaveDifyConfigRequest'
 import type { GenericConfig } from '@/types/generated/api/GenericConfig'
 import type { GenericInputVariable } from '@/types/generated/api/GenericInputVariable'
@@ -48,9 +52,34 @@ const JSONL_FORMAT_HELP = {
   description: '仅支持 txt（UTF-8），一行一个 TestCase JSON；空行会被跳过。',
   example: `{"id":"case-1","input":{"question":"你好，帮我写一段自我介绍"},"reference":{"Exact":{"expected":"（此处填写期望输出）"}}}
 {"id":"case-2","input":{"question":"用 JSON 输出一个用户对象"},"reference":{"Constrained":{"core_request":"必须是 JSON 且字段合理","constraints":[{"name":"format","description":"必须是 JSON","params":{"format":"json"},"weight":null}],"quality_dimensions":[]}}}
-{"id":"case-3","input":{"prompt":"写一段欢迎文案"},"reference":{"Constrained":{"core_request":"友好、简洁、鼓励探索","constraints":[{"name":"length","description":"长度限制","params":{"minChars":30,"maxChars":120},"weight":null}],"quality_dimensions":[]}}}`,
+{"id":"case-3","input":{"prompt":"写一段欢迎文案"},"reference":{"Constrained":{"core_request":"友好、简洁、鼓励探索","constraints":[{"name":"length","description":"长度限制","params":{"minChars":30,"maxChars":120},"weight":null}],"quality_dimensions":[]}}}
+{"id":"case-4","input":{"conversation":[{"role":"user","content":"你好"},{"role":"assistant","content":"你好！有什么可以帮你？"},{"role":"user","content":"推荐一本书"}]},"reference":{"Constrained":{"core_request":"只评估对最后一条 user 消息的回复","constraints":[],"quality_dimensions":[]}}}`,
 } as const
 
+function validateConversationInput(index: number, conversation: unknown): string | null {
+  const prefix = `cases[${index}].input.conversation`
+  if (!Array.isArray(conversation) || conversation.length === 0) {
+    return `${prefix} 必须是非空数组`
+  }
+  for (const [turnIndex, turn] of conversation.entries()) {
+    if (typeof turn !== 'object' || turn === null || Array.isArray(turn)) {
+      return `${prefix}[${turnIndex}] 必须是对象`
+    }
+    const turnRecord = turn as Record<string, unknown>
+    if (turnRecord.role !== 'user' && turnRecord.role !== 'assistant') {
+      return `${prefix}[${turnIndex}].role 必须是 user 或 assistant`
+    }
+    if (typeof turnRecord.content !== 'string' || turnRecord.content.trim() === '') {
+      return `${prefix}[${turnIndex}].content 必须是非空字符串`
+    }
+  }
+  const last = conversation[conversation.length - 1] as Record<string, unknown>
+  if (last.role !== 'user') {
+    return `${prefix} 的最后一轮必须是 user`
+  }
+  return null
+}
+
 function validateCasesJson(parsed: unknown): string | null {
   if (!Array.isArray(parsed)) return 'cases 必须是数组'
 
@@ -65,6 +94,11 @@ function validateCasesJson(parsed: unknown): string | null {
     if (typeof record.input !== 'object' || record.input === null || Array.isArray(record.input)) {
       return `cases[${index}].input 必须是对象`
     }
+    const inputRecord = record.input as Record<string, unknown>
+    if ('conversation' in inputRecord) {
+      const conversationError = validateConversationInput(index, inputRecord.conversation)
+      if (conversationError) return conversationError
+    }
     const reference = record.reference
     if (typeof reference !== 'object' || reference === null || Array.isArray(reference)) {
       return `cases[${index}].reference 必须是对象`
@@ -176,6 +210,12 @@ export function TestSetsView() {
   const [isRefreshingDifyVariables, setIsRefreshingDifyVariables] = useState(false)
 
   const [difyTargetPromptVariable, setDifyTargetPromptVariable] = useState('')
+  const [difyOutputSelector, setDifyOutputSelector] = useState('')
+  const [difyOutputs, setDifyOutputs] = useState<DifyOutputVariable[]>([])
+  const [difyAppType, setDifyAppType] = useState<DifyAppType>('workflow')
+  const [difyResponseMode, setDifyResponseMode] = useState<DifyResponseMode>('blocking')
+  const [difyCredentialId, setDifyCredentialId] = useState('')
+  const { data: difyCredentials } = useCredentialProfiles('dify')
   const [difyBindingDrafts, setDifyBindingDrafts] = useState<Record<string, DifyBindingDraft>>({})
   const [difySaveError, setDifySaveError] = useState<string | null>(null)
   const [difySaveSuccess, setDifySaveSuccess] = useState<string | null>(null)
@@ -226,6 +266,11 @@ export function TestSetsView() {
 
   const applyDifyConfigToDrafts = (config: DifyConfig | null) => {
     setDifyTargetPromptVariable(config?.targetPromptVariable ?? '')
+    setDifyOutputSelector(config?.outputSelector ?? '')
+    setDifyOutputs(config?.outputsSnapshot ?? [])
+    setDifyAppType(config?.appType ?? 'workflow')
+    setDifyResponseMode(config?.responseMode ?? 'blocking')
+    setDifyCredentialId(config?.credentialId ?? '')
 
     const nextDrafts: Record<string, DifyBindingDraft> = {}
     const bindings = config?.bindings ?? {}
@@ -316,6 +361,11 @@ export function TestSetsView() {
     setDifyVariablesError(null)
     setIsRefreshingDifyVariables(false)
     setDifyTargetPromptVariable('')
+    setDifyOutputSelector('')
+    setDifyOutputs([])
+    setDifyAppType('workflow')
+    setDifyResponseMode('blocking')
+    setDifyCredentialId('')
     setDifyBindingDrafts({})
     setDifySaveError(null)
     setDifySaveSuccess(null)
@@ -406,6 +456,11 @@ export function TestSetsView() {
         setPendingTemplateDifyConfig({
           targetPromptVariable: tpl.dify_config.targetPromptVariable,
           bindings: tpl.dify_config.bindings,
+          outputSelector: tpl.dify_config.outputSelector ?? null,
+          appType: tpl.dify_config.appType ?? 'workflow',
+          responseMode: tpl.dify_config.responseMode ?? 'blocking',
+          // 凭证 ID 属于具体用户的凭证，不随模板复制
+          credentialId: null,
         })
       } else {
         setPendingTemplateDifyConfig(null)
@@ -837,6 +892,7 @@ export function TestSetsView() {
     try {
       const res = await refreshDifyVariables(workspaceId, editingId, sessionToken)
       setDifyVariables(res.variables)
+      setDifyOutputs(res.outputs ?? [])
     } catch (e) {
       setDifyVariablesError(e instanceof Error ? e.message : '解析变量失败')
     } finally {
@@ -886,7 +942,14 @@ export function TestSetsView() {
       const res = await saveDifyConfig(
         workspaceId,
         editingId,
-        { targetPromptVariable: target, bindings },
+        {
+          targetPromptVariable: target,
+          bindings,
+          outputSelector: difyOutputSelector.trim() || null,
+          appType: difyAppType,
+          responseMode: difyResponseMode,
+          credentialId: difyCredentialId || null,
+        },
         sessionToken
       )
       applyDifyConfigToDrafts(res.difyConfig)
@@ -1849,6 +1912,98 @@ export function TestSetsView() {
                   </select>
                 </div>
 
+                <div className="grid gap-2">
+                  <Label htmlFor="dify-credential">Dify 凭证</Label>
+                  <select
+                    id="dify-credential"
+                    className="h-10 w-full rounded-md border border-input bg-background px-3 text-sm"
+                    value={difyCredentialId}
+                    onChange={(e) => {
+                      setDifyCredentialId(e.target.value)
+                      setDifySaveError(null)
+                      setDifySaveSuccess(null)
+                    }}
+                  >
+                    <option value="">使用默认凭证</option>
+                    {(difyCredentials ?? []).map((cred) => (
+                      <option key={cred.id} value={cred.id}>
+                        {cred.is_default ? `${cred.name}（默认）` : cred.name}
+                      </option>
+                    ))}
+                  </select>
+                  <div className="text-xs text-muted-foreground">
+                    不同 Dify 应用使用不同 API Key 时，在“API 配置”中添加命名凭证后于此选择；保存后刷新变量与执行均使用该凭证。
+                  </div>
+                </div>
+
+                <div className="grid gap-4 md:grid-cols-2">
+                  <div className="grid gap-2">
+                    <Label htmlFor="dify-app-type">应用类型</Label>
+                    <select
+                      id="dify-app-type"
+                      className="h-10 w-full rounded-md border border-input bg-background px-3 text-sm"
+                      value={difyAppType}
+                      onChange={(e) => {
+                        setDifyAppType(e.target.value as DifyAppType)
+                        setDifySaveError(null)
+                        setDifySaveSuccess(null)
+                      }}
+                    >
+                      <option value="workflow">工作流（Workflow）</option>
+                      <option value="chat">对话（Chatflow / Agent / 聊天助手）</option>
+                      <option value="completion">文本生成（Completion）</option>
+                    </select>
+                    {difyAppType === 'chat' && (
+                      <div className="text-xs text-muted-foreground">
+                        对话应用从测试用例输入的 query 字段读取用户消息；conversation_id 字段（可选）用于续接会话。
+                      </div>
+                    )}
+                  </div>
+                  <div className="grid gap-2">
+                    <Label htmlFor="dify-response-mode">响应模式</Label>
+                    <select
+                      id="dify-response-mode"
+                      className="h-10 w-full rounded-md border border-input bg-background px-3 text-sm"
+                      value={difyResponseMode}
+                      onChange={(e) => {
+                        setDifyResponseMode(e.target.value as DifyResponseMode)
+                        setDifySaveError(null)
+                        setDifySaveSuccess(null)
+                      }}
+                    >
+                      <option value="blocking">阻塞（blocking）</option>
+                      <option value="streaming">流式（streaming，Agent 应用必选）</option>
+                    </select>
+                  </div>
+                </div>
+
+                <div className="grid gap-2">
+                  <Label htmlFor="dify-output-selector">输出选择器（可选）</Label>
+                  <Input
+                    id="dify-output-selector"
+                    list="dify-output-options"
+                    value={difyOutputSelector}
+                    placeholder="输出变量名，或以 / 开头的 JSON Pointer；留空则自动识别"
+                    onChange={(e) => {
+                      setDifyOutputSelector(e.target.value)
+                      setDifySaveError(null)
+                      setDifySaveSuccess(null)
+                    }}
+                  />
+                  <datalist id="dify-output-options">
+                    {difyOutputs.map((o) => (
+                      <option key={o.name} value={o.name}>
+                        {o.name} ({o.type})
+                      </option>
+                    ))}
+                  </datalist>
+                  <div className="text-xs text-muted-foreground">
+                    {difyOutputs.length > 0
+                      ? '可从工作流声明的输出中选择；对象/数组输出按规范 JSON 参与评估。'
+                      : '未获取到工作流声明的输出，可手动填写。对象/数组输出按规范 JSON 参与评估。'}
+                  </div>
+                </div>
+
                 <div className="overflow-x-auto rounded-md border">
                   <table className="w-full text-left text-sm">
                     <thead className="bg-muted/50">
@@ -34,9 +34,12 @@ export interface DifyCredential {
 }
 
 /**
- * 通用大模型 Provider 类型
+ * 通用大模型 Provider 标识
+ *
+ * 内置 Provider ID（如 'siliconflow' / 'openai' / 'anthropic'）或用户自定义 Provider ID，
+ * 可选项以后端 GET /auth/llm-providers 返回为准。
  */
-export type GenericLlmProvider = 'siliconflow' | 'modelscope';
+export type GenericLlmProvider = string;
 
 /**
  * 通用大模型 API 凭证接口
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 个人访问令牌信息（不含令牌明文）
+ */
+export type AccessTokenInfo = { id: string, name: string, 
+/**
+ * 令牌前缀（用于识别）
+ */
+token_prefix: string, scope: string, workspace_id: string | null, 
+/**
+ * 是否可在无登录会话时解密凭证
+ */
+credential_access: boolean, created_at: number, expires_at: number | null, last_used_at: number | null, };
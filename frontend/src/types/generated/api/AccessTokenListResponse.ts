@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AccessTokenInfo } from "./AccessTokenInfo";
+
+/**
+ * 个人访问令牌列表响应
+ */
+export type AccessTokenListResponse = { tokens: Array<AccessTokenInfo>, };
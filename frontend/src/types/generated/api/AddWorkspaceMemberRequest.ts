@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 邀请成员请求
+ */
+export type AddWorkspaceMemberRequest = { username: string, 
+/**
+ * 角色：`editor` 或 `viewer`
+ */
+role: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 管理员重置他人两步验证请求
+ */
+export type AdminResetTotpRequest = { 
+/**
+ * 需要重置的用户名
+ */
+username: string, 
+/**
+ * 管理员本人的登录密码
+ */
+password: string, 
+/**
+ * 管理员本人的验证码或恢复码（管理员已启用两步验证时必填）
+ */
+code?: string, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "../serde_json/JsonValue";
+
+/**
+ * 审计事件
+ */
+export type AuditEventInfo = { id: string, event_type: string, 
+/**
+ * success / failure / denied
+ */
+outcome: string, actor_user_id: string | null, actor_username: string | null, ip_address: string | null, user_agent: string | null, correlation_id: string | null, 
+/**
+ * 目标类型（credential / session / task）
+ */
+target_type: string | null, target_id: string | null, 
+/**
+ * 附加信息（已脱敏）
+ */
+details: JsonValue | null, created_at: number, };
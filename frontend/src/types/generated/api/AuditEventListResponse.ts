@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AuditEventInfo } from "./AuditEventInfo";
+
+/**
+ * 审计事件列表响应
+ */
+export type AuditEventListResponse = { events: Array<AuditEventInfo>, total: number, has_more: boolean, };
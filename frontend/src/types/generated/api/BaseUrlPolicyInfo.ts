@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * base_url 校验策略（SSRF 防护）
+ */
+export type BaseUrlPolicyInfo = { allow_http: boolean, allow_localhost: boolean, allow_private_network: boolean, };
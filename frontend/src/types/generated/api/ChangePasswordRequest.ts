@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 修改密码请求
+ */
+export type ChangePasswordRequest = { old_password: string, new_password: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 修改密码响应
+ */
+export type ChangePasswordResponse = { 
+/**
+ * 本次重新加密（从旧格式迁移到数据密钥）的凭证数
+ */
+reencrypted_credentials: number, 
+/**
+ * 其中由 legacy `MASTER_PASSWORD` 迁移的凭证数
+ */
+migrated_legacy_credentials: number, 
+/**
+ * 已注销的其他会话数
+ */
+revoked_sessions: number, };
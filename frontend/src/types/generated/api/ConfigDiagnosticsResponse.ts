@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BaseUrlPolicyInfo } from "./BaseUrlPolicyInfo";
+import type { OutboundHttpInfo } from "./OutboundHttpInfo";
+import type { UpstreamGuardInfo } from "./UpstreamGuardInfo";
+
+/**
+ * 配置诊断响应
+ */
+export type ConfigDiagnosticsResponse = { 
+/**
+ * development / production
+ */
+app_env: string, is_docker: boolean, server_addr: string, base_url_policy: BaseUrlPolicyInfo, upstream_guard: UpstreamGuardInfo, outbound_http: OutboundHttpInfo, shutdown_grace_period_secs: number, 
+/**
+ * list_only / auto_resume
+ */
+startup_recovery_policy: string, audit_retention_days: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 创建个人访问令牌请求
+ */
+export type CreateAccessTokenRequest = { name: string, 
+/**
+ * 权限范围：`read`（只读）或 `run`（可启动任务、导出结果）
+ */
+scope: string, 
+/**
+ * 限定工作区（为空表示不限）
+ */
+workspace_id: string | null, 
+/**
+ * 有效天数（1-365，为空表示永不过期）
+ */
+expires_in_days: number | null, };
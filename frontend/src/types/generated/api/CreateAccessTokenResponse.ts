@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AccessTokenInfo } from "./AccessTokenInfo";
+
+/**
+ * 创建个人访问令牌响应
+ */
+export type CreateAccessTokenResponse = { 
+/**
+ * 令牌明文（仅此一次返回）
+ */
+token: string, access_token: AccessTokenInfo, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 新建命名凭证请求
+ */
+export type CreateCredentialRequest = { 
+/**
+ * 凭证类型：dify / generic_llm
+ */
+credential_type: string, name: string, 
+/**
+ * Provider（仅 generic_llm 需要）
+ */
+provider?: string, base_url: string, api_key: string, 
+/**
+ * 是否设为默认凭证（该类型尚无凭证时总是设为默认）
+ */
+is_default?: boolean, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CredentialProfileResponse } from "./CredentialProfileResponse";
+
+/**
+ * 凭证列表响应
+ */
+export type CredentialListResponse = { credentials: Array<CredentialProfileResponse>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 命名凭证概要（API Key 仅返回脱敏值）
+ */
+export type CredentialProfileResponse = { id: string, credential_type: string, name: string, provider: string | null, base_url: string, is_default: boolean, masked_api_key: string | null, created_at: number, updated_at: number, };
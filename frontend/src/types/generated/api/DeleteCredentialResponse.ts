@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 删除凭证响应
+ */
+export type DeleteCredentialResponse = { message: string, };
@@ -1,5 +1,24 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DifyAppType } from "../models/DifyAppType";
 import type { DifyBinding } from "./DifyBinding";
 import type { DifyInputVariable } from "./DifyInputVariable";
+import type { DifyOutputVariable } from "./DifyOutputVariable";
+import type { DifyResponseMode } from "../models/DifyResponseMode";
 
-export type DifyConfig = { targetPromptVariable: string, bindings: { [key in string]?: DifyBinding }, parametersSnapshot: Array<DifyInputVariable> | null, };
+export type DifyConfig = { targetPromptVariable: string, bindings: { [key in string]?: DifyBinding }, parametersSnapshot: Array<DifyInputVariable> | null, 
+/**
+ * 输出选择器：输出变量名或 JSON Pointer（`/` 开头）；None 表示按常见字段猜测
+ */
+outputSelector: string | null, 
+/**
+ * 工作流声明的输出变量快照（刷新变量时更新）
+ */
+outputsSnapshot: Array<DifyOutputVariable> | null, 
+/**
+ * 应用类型（工作流 / 对话 / 文本生成）；旧配置缺省为工作流
+ */
+appType: DifyAppType, responseMode: DifyResponseMode, 
+/**
+ * 使用的 Dify 凭证 ID；None 表示使用默认 Dify 凭证
+ */
+credentialId: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DifyValueType } from "./DifyValueType";
+
+/**
+ * Dify 工作流声明的输出变量（用于前端选择输出选择器）
+ */
+export type DifyOutputVariable = { name: string, type: DifyValueType, };
@@ -1,4 +1,9 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { DifyInputVariable } from "./DifyInputVariable";
+import type { DifyOutputVariable } from "./DifyOutputVariable";
 
-export type DifyVariablesResponse = { variables: Array<DifyInputVariable>, };
+export type DifyVariablesResponse = { variables: Array<DifyInputVariable>, 
+/**
+ * 工作流声明的输出变量；上游未声明时为空（前端退化为手动填写输出选择器）
+ */
+outputs: Array<DifyOutputVariable>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LlmProviderSpec } from "../models/LlmProviderSpec";
+
+/**
+ * Provider 列表响应（内置 + 当前用户自定义）
+ */
+export type LlmProvidersResponse = { providers: Array<LlmProviderSpec>, };
@@ -3,4 +3,8 @@
 /**
  * 登录请求
  */
-export type LoginRequest = { username: string, password: string, };
+export type LoginRequest = { username: string, password: string, 
+/**
+ * 两步验证码或恢复码（已启用两步验证时必填；缺失时返回 `AUTH_TOTP_REQUIRED`）
+ */
+totp_code?: string, };
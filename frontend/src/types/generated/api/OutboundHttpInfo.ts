@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 出站 HTTP 网络配置（已脱敏）
+ */
+export type OutboundHttpInfo = { 
+/**
+ * 代理地址（用户名/密码已替换为 ****）
+ */
+proxy_url: string | null, no_proxy: string | null, ca_bundle_path: string | null, client_cert_path: string | null, 
+/**
+ * 是否单独配置了客户端私钥文件
+ */
+client_key_configured: boolean, 
+/**
+ * 是否允许由代理解析目标主机（此时连接阶段不复核目标 IP）
+ */
+proxy_remote_dns: boolean, };
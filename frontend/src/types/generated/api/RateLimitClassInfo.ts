@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 单个路由类别的限流统计
+ */
+export type RateLimitClassInfo = { 
+/**
+ * prompt_execution / upstream_query
+ */
+class: string, 
+/**
+ * 每分钟配额（0 = 不限制）
+ */
+per_minute: number, 
+/**
+ * 突发上限
+ */
+burst: number, allowed_total: number, rejected_total: number, active_buckets: number, };
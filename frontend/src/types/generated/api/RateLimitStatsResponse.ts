@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RateLimitClassInfo } from "./RateLimitClassInfo";
+
+/**
+ * 限流统计响应
+ */
+export type RateLimitStatsResponse = { classes: Array<RateLimitClassInfo>, };
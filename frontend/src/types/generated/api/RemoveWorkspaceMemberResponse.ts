@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 移除成员响应
+ */
+export type RemoveWorkspaceMemberResponse = { message: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 吊销个人访问令牌响应
+ */
+export type RevokeAccessTokenResponse = { message: string, };
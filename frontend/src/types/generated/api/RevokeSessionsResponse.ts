@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 吊销会话响应
+ */
+export type RevokeSessionsResponse = { 
+/**
+ * 已吊销的会话数
+ */
+revoked_sessions: number, };
@@ -1,4 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DifyAppType } from "../models/DifyAppType";
 import type { DifyBinding } from "./DifyBinding";
+import type { DifyResponseMode } from "../models/DifyResponseMode";
 
-export type SaveDifyConfigRequest = { targetPromptVariable: string, bindings: { [key in string]?: DifyBinding }, };
+export type SaveDifyConfigRequest = { targetPromptVariable: string, bindings: { [key in string]?: DifyBinding }, outputSelector: string | null, appType: DifyAppType, responseMode: DifyResponseMode, credentialId: string | null, };
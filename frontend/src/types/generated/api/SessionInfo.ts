@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 会话信息（设备列表）
+ */
+export type SessionInfo = { 
+/**
+ * 会话 ID（用于吊销）
+ */
+id: string, user_agent: string | null, ip_address: string | null, created_at: number, last_seen_at: number, expires_at: number, 
+/**
+ * 是否为当前请求使用的会话
+ */
+current: boolean, };
@@ -1,6 +1,15 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LlmProviderSpec } from "../models/LlmProviderSpec";
 
 /**
  * 通用大模型连接测试请求
  */
-export type TestGenericLlmConnectionRequest = { base_url: string, api_key: string, provider: string, };
+export type TestGenericLlmConnectionRequest = { base_url: string, api_key: string, 
+/**
+ * Provider 标识：内置 Provider ID，或用户自定义 Provider ID（需同时提供 provider_spec）
+ */
+provider: string, 
+/**
+ * 用户自定义 Provider 描述（仅当 provider 不是内置 ID 时使用）
+ */
+provider_spec?: LlmProviderSpec, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 确认启用两步验证请求
+ */
+export type TotpEnableRequest = { 
+/**
+ * 认证器当前显示的 6 位验证码
+ */
+code: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 重新认证请求（停用 / 重新生成恢复码）
+ */
+export type TotpReauthRequest = { 
+/**
+ * 当前登录密码
+ */
+password: string, 
+/**
+ * 6 位验证码或恢复码（已启用两步验证时必填）
+ */
+code?: string, };
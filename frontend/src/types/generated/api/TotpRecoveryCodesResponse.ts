@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 恢复码响应（明文仅返回一次）
+ */
+export type TotpRecoveryCodesResponse = { recovery_codes: Array<string>, };
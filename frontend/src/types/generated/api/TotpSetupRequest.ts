@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 生成两步验证密钥请求
+ */
+export type TotpSetupRequest = { 
+/**
+ * 当前登录密码（重新认证）
+ */
+password: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 生成两步验证密钥响应
+ */
+export type TotpSetupResponse = { 
+/**
+ * Base32 密钥（供手动输入）
+ */
+secret: string, 
+/**
+ * `otpauth://` 配置 URI（渲染为二维码供认证器扫描）
+ */
+provisioning_uri: string, };
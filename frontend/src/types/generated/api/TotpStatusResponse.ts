@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 两步验证状态
+ */
+export type TotpStatusResponse = { enabled: boolean, 
+/**
+ * 已生成密钥但尚未确认
+ */
+pending: boolean, 
+/**
+ * 剩余未使用的恢复码数量
+ */
+recovery_codes_remaining: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 更新命名凭证请求（api_key 缺省时保留原值）
+ */
+export type UpdateCredentialRequest = { name: string, provider?: string, base_url: string, api_key?: string, };
@@ -1,9 +1,17 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { AdvancedDataSplitConfig } from "../models/AdvancedDataSplitConfig";
+import type { CommandTargetConfig } from "../models/CommandTargetConfig";
 import type { DiversityConfig } from "../models/DiversityConfig";
 import type { EvaluatorConfig } from "../models/EvaluatorConfig";
+import type { ExecutionCacheConfig } from "../models/ExecutionCacheConfig";
 import type { ExecutionMode } from "../models/ExecutionMode";
+import type { ExecutionRetryConfig } from "../models/ExecutionRetryConfig";
+import type { HttpTargetConfig } from "../models/HttpTargetConfig";
 import type { OutputConfig } from "../models/OutputConfig";
 import type { TeacherLlmConfig } from "../models/TeacherLlmConfig";
 
-export type UpdateOptimizationTaskConfigRequest = { initial_prompt: string | null, max_iterations: number, pass_threshold_percent: number, candidate_prompt_count: number, diversity_injection_threshold: number, execution_mode: ExecutionMode, max_concurrency: number, train_percent: number, validation_percent: number, output_config: OutputConfig, evaluator_config: EvaluatorConfig, diversity_config: DiversityConfig, teacher_llm: TeacherLlmConfig, advanced_data_split: AdvancedDataSplitConfig, };
+export type UpdateOptimizationTaskConfigRequest = { initial_prompt: string | null, max_iterations: number, pass_threshold_percent: number, candidate_prompt_count: number, diversity_injection_threshold: number, execution_mode: ExecutionMode, max_concurrency: number, train_percent: number, validation_percent: number, output_config: OutputConfig, evaluator_config: EvaluatorConfig, diversity_config: DiversityConfig, teacher_llm: TeacherLlmConfig, advanced_data_split: AdvancedDataSplitConfig, execution_cache: ExecutionCacheConfig, execution_retry: ExecutionRetryConfig, command_target: CommandTargetConfig, http_target: HttpTargetConfig, 
+/**
+ * 执行凭证 ID（仅 dify / generic 执行目标可用；None 表示使用默认凭证）
+ */
+execution_credential_id: string | null, };
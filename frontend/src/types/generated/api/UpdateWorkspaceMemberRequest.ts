@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 修改成员角色请求
+ */
+export type UpdateWorkspaceMemberRequest = { 
+/**
+ * 角色：`editor` 或 `viewer`
+ */
+role: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 上游限流 / 熔断配置
+ */
+export type UpstreamGuardInfo = { rpm_limit: number | null, tpm_limit: number | null, circuit_failure_threshold: number, circuit_open_secs: number, };
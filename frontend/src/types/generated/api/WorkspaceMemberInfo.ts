@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 工作区成员信息
+ */
+export type WorkspaceMemberInfo = { user_id: string, username: string, 
+/**
+ * 角色：owner / editor / viewer
+ */
+role: string, invited_by: string | null, created_at: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WorkspaceMemberInfo } from "./WorkspaceMemberInfo";
+
+/**
+ * 工作区成员列表响应
+ */
+export type WorkspaceMemberListResponse = { members: Array<WorkspaceMemberInfo>, };
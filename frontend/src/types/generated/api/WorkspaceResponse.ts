@@ -1,3 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type WorkspaceResponse = { id: string, name: string, description: string | null, created_at: number, updated_at: number, };
+export type WorkspaceResponse = { id: string, name: string, description: string | null, 
+/**
+ * 当前用户在该工作区中的角色：owner / editor / viewer
+ */
+role: string, created_at: number, updated_at: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 本地命令执行目标配置（仅 execution_target_type = command 时使用）
+ *
+ * 安全边界：program 还必须出现在服务端白名单 `COMMAND_TARGET_ALLOWED_PROGRAMS` 中才会被执行。
+ */
+export type CommandTargetConfig = { program: string, args: Array<string>, timeout_ms: number, env_allowlist: Array<string>, 
+/**
+ * 并发上限；None 表示沿用任务级 max_concurrency
+ */
+max_concurrency: number | null, };
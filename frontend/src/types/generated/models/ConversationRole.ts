@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 对话角色（system 由待优化 Prompt 提供，不出现在用例中）
+ */
+export type ConversationRole = "user" | "assistant";
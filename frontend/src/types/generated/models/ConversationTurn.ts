@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConversationRole } from "./ConversationRole";
+
+/**
+ * 对话中的一轮
+ */
+export type ConversationTurn = { role: ConversationRole, content: string, };
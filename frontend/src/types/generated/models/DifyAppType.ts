@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Dify 应用类型（决定调用的 API 端点）
+ */
+export type DifyAppType = "workflow" | "chat" | "completion";
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 执行结果缓存配置
+ *
+ * 命中条件：规范化 Prompt + 规范化输入 + 执行目标配置（不含密钥）完全一致，且未过期。
+ */
+export type ExecutionCacheConfig = { 
+/**
+ * 是否启用（默认关闭：执行目标可能非确定性，需用户显式开启）
+ */
+enabled: boolean, 
+/**
+ * 缓存有效期（秒）
+ */
+ttl_seconds: number, };
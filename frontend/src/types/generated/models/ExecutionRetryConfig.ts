@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 用例级执行容错配置
+ *
+ * 单条用例执行失败不再中止整批：可重试错误（网络 / 超时 / 上游）先按 `max_retries` 重试，
+ * 仍失败的用例记为未通过（失败维度 `execution`）；仅当失败用例占比超过阈值时中止本轮。
+ */
+export type ExecutionRetryConfig = { 
+/**
+ * 单条用例的最大重试次数（0 表示不重试）
+ */
+max_retries: number, 
+/**
+ * 允许的失败用例占比（百分比）；超过时中止本轮迭代，0 表示任一失败即中止
+ */
+max_failure_percent: number, };
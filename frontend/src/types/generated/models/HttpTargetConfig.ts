@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 通用 HTTP Webhook 执行目标配置（仅 execution_target_type = http 时使用）
+ *
+ * 请求头中的 `{{credential.dify}}` / `{{credential.generic_llm}}` 在运行时替换为 CredentialRepo 中解密的凭证，
+ * 配置本身不保存任何密钥。
+ */
+export type HttpTargetConfig = { method: string, url: string, headers: { [key in string]?: string }, 
+/**
+ * 渲染后必须为合法 JSON；`{{prompt}}` / `{{input}}` / `{{input.<key>}}` / `{{test_case_id}}` 以 JSON 字面量替换
+ */
+body_template: string, 
+/**
+ * `$.a.b[0]`（JSONPath 子集）或 `/a/b/0`（JSON Pointer）
+ */
+output_path: string, usage_path: string | null, timeout_ms: number, };
@@ -36,4 +36,8 @@ passedCases: number,
 /**
  * 迭代状态
  */
-status: IterationStatus, };
+status: IterationStatus, 
+/**
+ * 本轮结束时的自适应有效并发（仅并行执行的轮次有值）
+ */
+effectiveConcurrency?: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 对话接口协议格式
+ */
+export type LlmApiFormat = "openai_chat" | "anthropic_messages";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 鉴权头风格
+ */
+export type LlmAuthStyle = "bearer" | "api_key_header" | "x_api_key" | "none";
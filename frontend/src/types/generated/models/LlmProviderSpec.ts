@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LlmApiFormat } from "./LlmApiFormat";
+import type { LlmAuthStyle } from "./LlmAuthStyle";
+
+/**
+ * Provider 描述
+ */
+export type LlmProviderSpec = { 
+/**
+ * Provider 标识（小写字母、数字、`_`、`-`）
+ */
+id: string, display_name: string, 
+/**
+ * 默认 Base URL（None 表示必须由用户填写）
+ */
+default_base_url: string | null, auth_style: LlmAuthStyle, 
+/**
+ * 模型列表端点路径（相对 Base URL，可包含查询串）
+ */
+models_path: string, 
+/**
+ * 对话端点路径（相对 Base URL，可包含查询串与 `{model}` 占位符）
+ */
+chat_path: string, api_format: LlmApiFormat, 
+/**
+ * 是否为内置 Provider（用户自定义 Provider 恒为 false）
+ */
+builtin: boolean, };
@@ -1,10 +1,18 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { AdvancedDataSplitConfig } from "./AdvancedDataSplitConfig";
+import type { CommandTargetConfig } from "./CommandTargetConfig";
 import type { DataSplitPercentConfig } from "./DataSplitPercentConfig";
 import type { DiversityConfig } from "./DiversityConfig";
 import type { EvaluatorConfig } from "./EvaluatorConfig";
+import type { ExecutionCacheConfig } from "./ExecutionCacheConfig";
 import type { ExecutionMode } from "./ExecutionMode";
+import type { ExecutionRetryConfig } from "./ExecutionRetryConfig";
+import type { HttpTargetConfig } from "./HttpTargetConfig";
 import type { OutputConfig } from "./OutputConfig";
 import type { TeacherLlmConfig } from "./TeacherLlmConfig";
 
-export type OptimizationTaskConfig = { schema_version: number, initial_prompt: string | null, max_iterations: number, pass_threshold_percent: number, candidate_prompt_count: number, diversity_injection_threshold: number, execution_mode: ExecutionMode, max_concurrency: number, data_split: DataSplitPercentConfig, output_config: OutputConfig, evaluator_config: EvaluatorConfig, diversity_config: DiversityConfig, teacher_llm: TeacherLlmConfig, advanced_data_split: AdvancedDataSplitConfig, };
+export type OptimizationTaskConfig = { schema_version: number, initial_prompt: string | null, max_iterations: number, pass_threshold_percent: number, candidate_prompt_count: number, diversity_injection_threshold: number, execution_mode: ExecutionMode, max_concurrency: number, data_split: DataSplitPercentConfig, output_config: OutputConfig, evaluator_config: EvaluatorConfig, diversity_config: DiversityConfig, teacher_llm: TeacherLlmConfig, advanced_data_split: AdvancedDataSplitConfig, execution_cache: ExecutionCacheConfig, execution_retry: ExecutionRetryConfig, command_target: CommandTargetConfig, http_target: HttpTargetConfig, 
+/**
+ * 执行时使用的命名凭证 ID（None 表示使用该类型的默认凭证）
+ */
+execution_credential_id: string | null, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 请求用户引导事件负载
+ */
+export type GuidanceRequestedPayload = { 
+/**
+ * 任务 ID
+ */
+taskId: string, 
+/**
+ * 触发时的迭代轮次
+ */
+iteration: number, 
+/**
+ * 请求原因（结构化标识，如 `no_improvement_consecutive`）
+ */
+reason: string, 
+/**
+ * 连续无提升轮数
+ */
+consecutiveNoImprovement: number, 
+/**
+ * 请求时间（ISO 8601）
+ */
+requestedAt: string, };
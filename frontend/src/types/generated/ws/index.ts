@@ -4,6 +4,7 @@ export type { ArtifactUpdateAckPayload } from './ArtifactUpdateAckPayload'
 export type { ArtifactUpdatePayload } from './ArtifactUpdatePayload'
 export type { ArtifactUpdatedPayload } from './ArtifactUpdatedPayload'
 export type { GuidanceAppliedPayload } from './GuidanceAppliedPayload'
+export type { GuidanceRequestedPayload } from './GuidanceRequestedPayload'
 export type { GuidanceSendAckPayload } from './GuidanceSendAckPayload'
 export type { GuidanceSendPayload } from './GuidanceSendPayload'
 export type { GuidanceSentPayload } from './GuidanceSentPayload'